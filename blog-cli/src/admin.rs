@@ -0,0 +1,169 @@
+use anyhow::{Result, bail};
+use blog_client::BlogClient;
+use blog_client::error::BlogClientError;
+use blog_client::models::{ImportSummary, UserAccount};
+use clap::ValueEnum;
+
+use crate::i18n::Msg;
+use crate::jwt;
+use crate::output;
+
+/// Page size used when walking the user list.
+const PAGE_SIZE: u32 = 100;
+
+/// Role that can be assigned to a user.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum RoleArg {
+    User,
+    Admin,
+}
+
+impl RoleArg {
+    /// Returns the role name understood by the server.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            RoleArg::User => "user",
+            RoleArg::Admin => "admin",
+        }
+    }
+}
+
+/// Blog export format accepted by `admin import`.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum ImportFormatArg {
+    /// WordPress eXtended RSS (WXR) export.
+    Wordpress,
+    /// Medium export zip, or a single post's HTML file.
+    Medium,
+}
+
+impl ImportFormatArg {
+    /// Returns the format name understood by the server.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ImportFormatArg::Wordpress => "wordpress",
+            ImportFormatArg::Medium => "medium",
+        }
+    }
+}
+
+/// Fails early unless the stored token belongs to an administrator.
+pub fn require_admin(client: &BlogClient) -> Result<()> {
+    let Some(token) = client.get_token() else {
+        bail!("{}", Msg::AdminLoginRequired);
+    };
+
+    if !jwt::is_admin(token) {
+        bail!("{}", Msg::AdminRoleRequired);
+    }
+
+    Ok(())
+}
+
+/// Fetches every user account, page by page.
+pub async fn fetch_all_users(
+    client: &BlogClient,
+    mut on_page: impl FnMut(usize),
+) -> Result<Vec<UserAccount>, BlogClientError> {
+    let mut users = Vec::new();
+
+    loop {
+        let page = client.list_users(PAGE_SIZE, users.len() as u32).await?;
+        let done = page.len() < PAGE_SIZE as usize;
+        users.extend(page);
+        on_page(users.len());
+
+        if done {
+            return Ok(users);
+        }
+    }
+}
+
+/// Prints users as a table, or as a JSON array when `json` is set.
+pub fn print_users(users: &[UserAccount], json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(users)?);
+        return Ok(());
+    }
+
+    if users.is_empty() {
+        output::info(Msg::NoUsers);
+        return Ok(());
+    }
+
+    for user in users {
+        println!(
+            "{}  {:<6} {:<8} {:<20} {}",
+            user.id,
+            user.role,
+            status(user),
+            user.username,
+            user.email
+        );
+    }
+
+    Ok(())
+}
+
+/// Prints a single updated user, as JSON when `json` is set.
+pub fn print_user(user: &UserAccount, json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(user)?);
+        return Ok(());
+    }
+
+    output::field("id", user.id);
+    output::field("username", &user.username);
+    output::field("email", &user.email);
+    output::field("role", &user.role);
+    output::field("status", status(user));
+    Ok(())
+}
+
+/// Returns a short account status label.
+fn status(user: &UserAccount) -> &'static str {
+    if user.disabled_at.is_some() {
+        "disabled"
+    } else {
+        "active"
+    }
+}
+
+/// Prints what an import did, or the summary as JSON when `json` is set.
+pub fn print_import_summary(summary: &ImportSummary, json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(summary)?);
+        return Ok(());
+    }
+
+    for author in &summary.authors {
+        output::info(Msg::ImportAuthor {
+            source: &author.source,
+            user_id: author.user_id,
+            matched: author.matched,
+        });
+    }
+    for post in &summary.imported {
+        output::info(Msg::ImportCreated { file: &post.source_id, id: &post.post_id.to_string() });
+    }
+    for item in &summary.skipped {
+        output::warn(Msg::ImportSkipped { source: &item.source_id, reason: &item.reason });
+    }
+    for failure in &summary.image_failures {
+        output::warn(Msg::ImportImageFailed { url: &failure.url, reason: &failure.reason });
+    }
+    for warning in &summary.warnings {
+        output::warn(warning);
+    }
+
+    println!(
+        "{}",
+        Msg::BlogImportSummary {
+            imported: summary.imported.len(),
+            skipped: summary.skipped.len(),
+            images: summary.images_imported,
+            image_failures: summary.image_failures.len(),
+        }
+    );
+    Ok(())
+}
@@ -0,0 +1,279 @@
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow};
+use blog_client::BlogClient;
+use blog_client::error::BlogClientError;
+use blog_client::models::Post;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::markdown::{FrontMatter, MarkdownPost};
+
+/// Manifest file written next to exported posts.
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Page size used when walking the post list.
+const PAGE_SIZE: u32 = 100;
+
+/// Longest slug used as a file name stem.
+const MAX_SLUG_LEN: usize = 60;
+
+/// Index of an export directory.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Manifest {
+    /// Export timestamp.
+    pub exported_at: DateTime<Utc>,
+
+    /// Exported posts in server order.
+    pub posts: Vec<ManifestEntry>,
+}
+
+/// Manifest entry describing one exported post.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Post identifier.
+    pub id: Uuid,
+
+    /// File name relative to the export directory.
+    pub file: String,
+
+    /// Post title.
+    pub title: String,
+
+    /// Post creation timestamp.
+    pub created_at: DateTime<Utc>,
+}
+
+/// Fetches every post of the authenticated user, page by page.
+///
+/// `on_page` is called with the running total after each page.
+pub async fn fetch_all_posts(
+    client: &BlogClient,
+    tag: Option<&str>,
+    mut on_page: impl FnMut(usize),
+) -> Result<Vec<Post>, BlogClientError> {
+    let mut posts = Vec::new();
+
+    loop {
+        let page = client.list_posts(PAGE_SIZE, posts.len() as u32, tag).await?;
+        let done = page.next_offset().is_none();
+        posts.extend(page.items);
+        on_page(posts.len());
+
+        if done {
+            return Ok(posts);
+        }
+    }
+}
+
+/// Writes posts as Markdown files plus a manifest into `dir`.
+pub fn export_posts(posts: &[Post], dir: &Path) -> Result<Manifest> {
+    fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+
+    let mut used = HashSet::new();
+    let mut entries = Vec::with_capacity(posts.len());
+
+    for post in posts {
+        let mut stem = slugify(&post.title);
+        if !used.insert(stem.clone()) {
+            stem = format!("{stem}-{}", &post.id.simple().to_string()[..8]);
+            used.insert(stem.clone());
+        }
+        let file = format!("{stem}.md");
+
+        let doc = MarkdownPost {
+            front_matter: FrontMatter {
+                id: Some(post.id),
+                title: Some(post.title.clone()),
+                tags: post.tags.clone(),
+                created_at: Some(post.created_at),
+                ..FrontMatter::default()
+            },
+            body: post.content.clone(),
+        };
+
+        let path = dir.join(&file);
+        fs::write(&path, doc.render()?)
+            .with_context(|| format!("failed to write {}", path.display()))?;
+
+        entries.push(ManifestEntry {
+            id: post.id,
+            file,
+            title: post.title.clone(),
+            created_at: post.created_at,
+        });
+    }
+
+    let manifest = Manifest {
+        exported_at: Utc::now(),
+        posts: entries,
+    };
+
+    let path = dir.join(MANIFEST_FILE);
+    fs::write(&path, serde_json::to_string_pretty(&manifest)?)
+        .with_context(|| format!("failed to write {}", path.display()))?;
+
+    Ok(manifest)
+}
+
+/// Result of importing a single file.
+#[derive(Debug)]
+pub enum ImportStatus {
+    /// A new post was (or would be) created.
+    Created,
+
+    /// An existing post was (or would be) updated.
+    Updated,
+
+    /// The matching post already has the same title, content and tags.
+    Unchanged,
+
+    /// The file could not be imported.
+    Failed(String),
+}
+
+/// Per-file import report.
+#[derive(Debug)]
+pub struct ImportOutcome {
+    /// File name relative to the import directory.
+    pub file: String,
+
+    /// What happened to the file.
+    pub status: ImportStatus,
+
+    /// Identifier of the matched or created post, if known.
+    pub id: Option<Uuid>,
+}
+
+/// Planned change for one file.
+enum Plan {
+    Create,
+    Update(Uuid),
+    Unchanged(Uuid),
+}
+
+/// Imports Markdown posts from `dir`, matching existing posts by ID, then by slug.
+///
+/// With `dry_run` set nothing is sent to the server; the report shows what would happen.
+pub async fn import_posts(
+    client: &BlogClient,
+    dir: &Path,
+    dry_run: bool,
+) -> Result<Vec<ImportOutcome>> {
+    let mut files: Vec<String> = fs::read_dir(dir)
+        .with_context(|| format!("failed to read {}", dir.display()))?
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.ends_with(".md"))
+        .collect();
+    files.sort();
+
+    let existing = fetch_all_posts(client, None, |_| {}).await?;
+    let by_id: HashMap<Uuid, &Post> = existing.iter().map(|p| (p.id, p)).collect();
+    let mut by_slug: HashMap<String, Vec<&Post>> = HashMap::new();
+    for post in &existing {
+        by_slug.entry(slugify(&post.title)).or_default().push(post);
+    }
+
+    let mut outcomes = Vec::with_capacity(files.len());
+
+    for file in files {
+        let outcome = match import_file(client, &dir.join(&file), &by_id, &by_slug, dry_run).await {
+            Ok((status, id)) => ImportOutcome { file, status, id },
+            Err(err) => ImportOutcome {
+                file,
+                status: ImportStatus::Failed(format!("{err:#}")),
+                id: None,
+            },
+        };
+        outcomes.push(outcome);
+    }
+
+    Ok(outcomes)
+}
+
+/// Imports a single file.
+async fn import_file(
+    client: &BlogClient,
+    path: &Path,
+    by_id: &HashMap<Uuid, &Post>,
+    by_slug: &HashMap<String, Vec<&Post>>,
+    dry_run: bool,
+) -> Result<(ImportStatus, Option<Uuid>)> {
+    let doc = MarkdownPost::read(path)?;
+    let title = doc
+        .front_matter
+        .title
+        .ok_or_else(|| anyhow!("front matter has no `title`"))?;
+
+    let matched = doc
+        .front_matter
+        .id
+        .and_then(|id| by_id.get(&id))
+        .or_else(|| match by_slug.get(&slugify(&title)).map(Vec::as_slice) {
+            Some([post]) => Some(post),
+            _ => None,
+        });
+
+    let tags = doc.front_matter.tags;
+    let plan = match matched {
+        Some(post) if post.title == title && post.content == doc.body && same_tags(&post.tags, &tags) => {
+            Plan::Unchanged(post.id)
+        }
+        Some(post) => Plan::Update(post.id),
+        None => Plan::Create,
+    };
+
+    match plan {
+        Plan::Unchanged(id) => Ok((ImportStatus::Unchanged, Some(id))),
+        Plan::Update(id) if dry_run => Ok((ImportStatus::Updated, Some(id))),
+        Plan::Create if dry_run => Ok((ImportStatus::Created, None)),
+        Plan::Update(id) => {
+            client.update_post(id, title, doc.body, Some(tags)).await?;
+            Ok((ImportStatus::Updated, Some(id)))
+        }
+        Plan::Create => {
+            let post = client.create_post(title, doc.body, tags).await?;
+            Ok((ImportStatus::Created, Some(post.id)))
+        }
+    }
+}
+
+/// Compares server tags with file tags the way the server normalizes them.
+fn same_tags(server: &[String], file: &[String]) -> bool {
+    let mut normalized: Vec<String> = Vec::with_capacity(file.len());
+    for tag in file.iter().map(|tag| tag.trim().to_lowercase()) {
+        if !tag.is_empty() && !normalized.contains(&tag) {
+            normalized.push(tag);
+        }
+    }
+
+    server == normalized.as_slice()
+}
+
+/// Builds a file-name-safe slug from a post title.
+pub fn slugify(title: &str) -> String {
+    let mut slug = String::with_capacity(title.len());
+
+    for c in title.chars().flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+
+        if slug.chars().count() >= MAX_SLUG_LEN {
+            break;
+        }
+    }
+
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() {
+        "post".to_string()
+    } else {
+        slug.to_string()
+    }
+}
@@ -0,0 +1,65 @@
+use std::collections::{HashMap, HashSet};
+
+use blog_client::BlogClient;
+use blog_client::error::BlogClientError;
+use blog_client::models::Comment;
+use uuid::Uuid;
+
+/// Page size used when walking the comment list.
+const PAGE_SIZE: u32 = 100;
+
+/// Fetches every comment on a post, page by page.
+pub async fn fetch_all_comments(
+    client: &BlogClient,
+    post_id: Uuid,
+) -> Result<Vec<Comment>, BlogClientError> {
+    let mut comments = Vec::new();
+
+    loop {
+        let page = client
+            .list_comments(post_id, PAGE_SIZE, comments.len() as u32)
+            .await?;
+        let done = page.next_offset().is_none();
+        comments.extend(page.items);
+
+        if done {
+            return Ok(comments);
+        }
+    }
+}
+
+/// Prints comments as an indented thread, oldest first at every level.
+///
+/// Replies whose parent is missing (e.g. deleted) are shown at the top level.
+pub fn print_thread(comments: &[Comment]) {
+    let ids: HashSet<Uuid> = comments.iter().map(|c| c.id).collect();
+    let mut children: HashMap<Option<Uuid>, Vec<&Comment>> = HashMap::new();
+
+    for comment in comments {
+        let parent = comment.parent_id.filter(|id| ids.contains(id));
+        children.entry(parent).or_default().push(comment);
+    }
+
+    print_level(&children, None, 0);
+}
+
+/// Prints the replies to `parent` and, recursively, their replies.
+fn print_level(children: &HashMap<Option<Uuid>, Vec<&Comment>>, parent: Option<Uuid>, depth: usize) {
+    let Some(replies) = children.get(&parent) else {
+        return;
+    };
+
+    let indent = "  ".repeat(depth);
+    for comment in replies {
+        println!(
+            "{indent}- [{}] {} ({})",
+            comment.id,
+            comment.author_id,
+            comment.created_at.format("%Y-%m-%d %H:%M")
+        );
+        for line in comment.content.lines() {
+            println!("{indent}  {line}");
+        }
+        print_level(children, Some(comment.id), depth + 1);
+    }
+}
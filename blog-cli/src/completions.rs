@@ -0,0 +1,72 @@
+use std::ffi::OsStr;
+use std::io;
+
+use anyhow::{Result, anyhow};
+use clap::ValueEnum;
+use clap_complete::engine::CompletionCandidate;
+use clap_complete::env::Shells;
+
+use crate::config::Config;
+
+/// Binary name used in completion scripts.
+const BIN_NAME: &str = "blog-cli";
+
+/// Environment variable that switches the binary into completion mode.
+pub const COMPLETE_VAR: &str = "COMPLETE";
+
+/// Shells supported by `blog-cli completions`.
+#[derive(Debug, Clone, Copy, ValueEnum)]
+pub enum Shell {
+    Bash,
+    Zsh,
+    Fish,
+    Powershell,
+}
+
+impl Shell {
+    /// Returns the shell name understood by `clap_complete`.
+    fn name(self) -> &'static str {
+        match self {
+            Shell::Bash => "bash",
+            Shell::Zsh => "zsh",
+            Shell::Fish => "fish",
+            Shell::Powershell => "powershell",
+        }
+    }
+}
+
+/// Writes the completion registration script for the given shell to stdout.
+///
+/// The script calls back into the binary on every completion request,
+/// so dynamic values such as profile names stay up to date.
+pub fn write_registration(shell: Shell) -> Result<()> {
+    let shells = Shells::builtins();
+    let completer = shells
+        .completer(shell.name())
+        .ok_or_else(|| anyhow!("unsupported shell: {}", shell.name()))?;
+
+    let exe = std::env::current_exe()
+        .ok()
+        .and_then(|path| path.to_str().map(str::to_owned))
+        .unwrap_or_else(|| BIN_NAME.to_string());
+
+    completer.write_registration(COMPLETE_VAR, BIN_NAME, BIN_NAME, &exe, &mut io::stdout())?;
+    Ok(())
+}
+
+/// Completes profile names defined in the default config file.
+pub fn complete_profiles(current: &OsStr) -> Vec<CompletionCandidate> {
+    let Some(current) = current.to_str() else {
+        return Vec::new();
+    };
+    let Ok(config) = Config::load(None) else {
+        return Vec::new();
+    };
+
+    config
+        .profiles
+        .keys()
+        .filter(|name| name.starts_with(current))
+        .map(CompletionCandidate::new)
+        .collect()
+}
@@ -0,0 +1,188 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, anyhow};
+use serde::Deserialize;
+
+/// Profile used when neither `--profile` nor `default_profile` is set.
+pub const DEFAULT_PROFILE: &str = "default";
+
+/// Default HTTP server address.
+const DEFAULT_HTTP_SERVER: &str = "http://127.0.0.1:8080";
+
+/// Default gRPC server address.
+const DEFAULT_GRPC_SERVER: &str = "http://127.0.0.1:50051";
+
+/// Transport used to reach the server.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TransportKind {
+    /// HTTP/JSON API.
+    #[default]
+    Http,
+
+    /// gRPC API.
+    Grpc,
+}
+
+/// Where access tokens are kept.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenStorage {
+    /// OS keychain, falling back to `token_path` when unavailable.
+    #[default]
+    Keyring,
+
+    /// Plain file at `token_path`.
+    File,
+}
+
+impl TransportKind {
+    /// Returns the default server address for this transport.
+    pub fn default_server(self) -> &'static str {
+        match self {
+            TransportKind::Http => DEFAULT_HTTP_SERVER,
+            TransportKind::Grpc => DEFAULT_GRPC_SERVER,
+        }
+    }
+}
+
+/// CLI configuration file.
+///
+/// Loaded from `~/.config/blog-cli/config.toml`:
+///
+/// ```toml
+/// default_profile = "local"
+///
+/// [profiles.local]
+/// server = "http://127.0.0.1:8080"
+/// transport = "http"
+/// api_prefix = "/blog/api/v1"
+///
+/// [profiles.prod-grpc]
+/// server = "https://blog.example.com:50051"
+/// transport = "grpc"
+/// token_path = "~/.local/share/blog-cli/prod.token"
+/// token_storage = "file"
+/// ```
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    /// Profile used when `--profile` is not passed.
+    pub default_profile: Option<String>,
+
+    /// Named connection profiles.
+    #[serde(default)]
+    pub profiles: BTreeMap<String, ProfileConfig>,
+}
+
+/// Profile entry as written in the configuration file.
+#[derive(Debug, Default, Clone, Deserialize)]
+pub struct ProfileConfig {
+    /// Server address.
+    pub server: Option<String>,
+
+    /// Transport used to reach the server.
+    pub transport: Option<TransportKind>,
+
+    /// File where the access token is stored.
+    pub token_path: Option<PathBuf>,
+
+    /// Token storage backend.
+    pub token_storage: Option<TokenStorage>,
+
+    /// Path the HTTP API is mounted under.
+    pub api_prefix: Option<String>,
+}
+
+/// Profile with all defaults applied.
+#[derive(Debug, Clone)]
+pub struct Profile {
+    /// Profile name.
+    pub name: String,
+
+    /// Server address configured for the profile, if any.
+    pub server: Option<String>,
+
+    /// Transport used to reach the server.
+    pub transport: TransportKind,
+
+    /// File where the access token is stored.
+    pub token_path: PathBuf,
+
+    /// Token storage backend.
+    pub token_storage: TokenStorage,
+
+    /// Path the HTTP API is mounted under, if not the client default.
+    pub api_prefix: Option<String>,
+}
+
+impl Config {
+    /// Returns the default configuration file location.
+    pub fn default_path() -> Option<PathBuf> {
+        dirs::config_dir().map(|dir| dir.join("blog-cli").join("config.toml"))
+    }
+
+    /// Loads the configuration file.
+    ///
+    /// A missing file yields an empty configuration.
+    pub fn load(path: Option<&Path>) -> Result<Self> {
+        let path = match path {
+            Some(path) => path.to_path_buf(),
+            None => match Self::default_path() {
+                Some(path) => path,
+                None => return Ok(Self::default()),
+            },
+        };
+
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let raw = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read config {}", path.display()))?;
+
+        toml::from_str(&raw).with_context(|| format!("invalid config {}", path.display()))
+    }
+
+    /// Resolves a profile by name, falling back to the default profile.
+    pub fn profile(&self, name: Option<&str>) -> Result<Profile> {
+        let name = name
+            .or(self.default_profile.as_deref())
+            .unwrap_or(DEFAULT_PROFILE);
+
+        let entry = match self.profiles.get(name) {
+            Some(entry) => entry.clone(),
+            None if name == DEFAULT_PROFILE => ProfileConfig::default(),
+            None => return Err(anyhow!("unknown profile: {name}")),
+        };
+
+        let token_path = match entry.token_path {
+            Some(path) => expand_home(&path),
+            None => default_token_path(name)?,
+        };
+
+        Ok(Profile {
+            name: name.to_string(),
+            server: entry.server,
+            transport: entry.transport.unwrap_or_default(),
+            token_path,
+            token_storage: entry.token_storage.unwrap_or_default(),
+            api_prefix: entry.api_prefix,
+        })
+    }
+}
+
+/// Returns the default token location for a profile.
+fn default_token_path(profile: &str) -> Result<PathBuf> {
+    let dir = dirs::data_dir().ok_or_else(|| anyhow!("cannot determine data directory"))?;
+    Ok(dir.join("blog-cli").join(format!("{profile}.token")))
+}
+
+/// Expands a leading `~` to the user's home directory.
+fn expand_home(path: &Path) -> PathBuf {
+    match (path.strip_prefix("~"), dirs::home_dir()) {
+        (Ok(rest), Some(home)) => home.join(rest),
+        _ => path.to_path_buf(),
+    }
+}
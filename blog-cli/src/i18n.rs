@@ -0,0 +1,394 @@
+use std::fmt::{self, Display};
+use std::sync::OnceLock;
+
+use uuid::Uuid;
+
+/// Environment variable selecting the output language, e.g. `ru` or `en_US.UTF-8`.
+pub const LANG_ENV: &str = "BLOG_CLI_LANG";
+
+/// Language of user-facing messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Lang {
+    En,
+    Ru,
+}
+
+static LANG: OnceLock<Lang> = OnceLock::new();
+
+impl Lang {
+    /// Parses a language tag or POSIX locale; only the language part is used.
+    fn from_locale(locale: &str) -> Option<Self> {
+        let language = locale
+            .split(['_', '-', '.'])
+            .next()
+            .unwrap_or_default()
+            .to_lowercase();
+
+        match language.as_str() {
+            "en" => Some(Lang::En),
+            "ru" => Some(Lang::Ru),
+            _ => None,
+        }
+    }
+}
+
+/// Selects the message language from `BLOG_CLI_LANG`, defaulting to English.
+///
+/// Returns the variable's value when it names an unsupported language.
+pub fn init() -> Option<String> {
+    let requested = std::env::var(LANG_ENV).ok().filter(|value| !value.is_empty());
+    let lang = requested.as_deref().and_then(Lang::from_locale);
+    LANG.get_or_init(|| lang.unwrap_or(Lang::En));
+
+    requested.filter(|_| lang.is_none())
+}
+
+/// Returns the selected message language.
+pub fn lang() -> Lang {
+    LANG.get().copied().unwrap_or(Lang::En)
+}
+
+/// Returns whether a confirmation answer means "yes" in any supported language.
+pub fn is_yes(answer: &str) -> bool {
+    matches!(answer.trim().to_lowercase().as_str(), "y" | "yes" | "д" | "да")
+}
+
+/// User-facing CLI message.
+///
+/// Every message the CLI prints goes through this catalog so that each
+/// language stays complete and consistent.
+pub enum Msg<'a> {
+    LoggedOut,
+    NotLoggedIn,
+    Registered,
+    RegisteredWithoutToken,
+    LoggedIn,
+    LoggedInWithoutToken,
+    UserLine { username: &'a str, email: &'a str },
+    PostCreated,
+    PostUpdated,
+    PostDeleted,
+    ConfirmDeletePost { title: &'a str, id: Uuid },
+    NoPosts,
+    PageSummary { first: u64, last: u64, total: u64 },
+    NoTags,
+    NoSearchResults,
+    FetchingPosts,
+    FetchingUsers,
+    Exported { count: usize, dir: &'a dyn Display },
+    ImportCreated { file: &'a str, id: &'a str },
+    ImportUpdated { file: &'a str, id: &'a str },
+    ImportUnchanged { file: &'a str, id: &'a str },
+    ImportFailed { file: &'a str, reason: &'a str },
+    ImportSummary { dry_run: bool, created: usize, updated: usize, unchanged: usize, failed: usize },
+    StatusFrontMatterIgnored,
+    NoComments,
+    CommentAdded { id: Uuid },
+    CommentDeleted,
+    ConfirmDeleteComment { id: Uuid },
+    Watching,
+    StreamClosed,
+    NoRevisions { id: Uuid },
+    RevisionNotFound { rev: &'a dyn Display },
+    NoDifferences,
+    RollbackToCurrent,
+    ConfirmRollback { title: &'a str, rev: &'a dyn Display },
+    PostRolledBack,
+    NoStats,
+    StatsSummary { posts: usize, views: i64, days: u32 },
+    NoFilesMatch { pattern: &'a str },
+    Uploading { file: &'a str },
+    MediaUploaded { file: &'a str },
+    UploadFailed { file: &'a dyn Display, err: &'a dyn Display },
+    UploadsFailed { failed: usize, total: usize },
+    MediaLinked { count: usize, title: &'a str },
+    NoMedia,
+    ConfirmDeleteMedia { id: Uuid },
+    MediaDeleted,
+    NoUsers,
+    UserDisabled,
+    ConfirmDisableUser { id: Uuid },
+    RoleSet { role: &'a str },
+    ImportAuthor { source: &'a str, user_id: Uuid, matched: bool },
+    ImportSkipped { source: &'a str, reason: &'a str },
+    ImportImageFailed { url: &'a str, reason: &'a str },
+    BlogImportSummary { imported: usize, skipped: usize, images: usize, image_failures: usize },
+    AdminLoginRequired,
+    AdminRoleRequired,
+    Aborted,
+    ServerUnhealthy,
+    SessionRefreshed,
+    RefreshFailed { err: &'a dyn Display },
+    LoginRequired,
+    SessionExpired,
+    KeychainUnavailable { err: &'a dyn Display, path: &'a dyn Display },
+    ConfirmSuffix,
+    ConfirmationNeedsTerminal,
+    PasswordPrompt,
+    RepeatPasswordPrompt,
+    NoPasswordOnStdin,
+    NoPasswordGiven { env: &'a str },
+    PasswordsDiffer,
+    UnsupportedLang { value: &'a str },
+    ErrorLabel,
+    CausedBy,
+}
+
+impl Display for Msg<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match lang() {
+            Lang::En => self.en(f),
+            Lang::Ru => self.ru(f),
+        }
+    }
+}
+
+impl Msg<'_> {
+    /// Writes the English text.
+    fn en(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Msg::LoggedOut => f.write_str("👋 Logged out, token removed."),
+            Msg::NotLoggedIn => f.write_str("Not logged in."),
+            Msg::Registered => f.write_str("✅ User registered, token saved."),
+            Msg::RegisteredWithoutToken => {
+                f.write_str("Registration succeeded, but no token was returned.")
+            }
+            Msg::LoggedIn => f.write_str("✅ Login successful, token saved."),
+            Msg::LoggedInWithoutToken => f.write_str("Login succeeded, but no token was returned."),
+            Msg::UserLine { username, email } => write!(f, "user: {username} <{email}>"),
+            Msg::PostCreated => f.write_str("✅ Post created:"),
+            Msg::PostUpdated => f.write_str("✅ Post updated:"),
+            Msg::PostDeleted => f.write_str("🗑 Post deleted."),
+            Msg::ConfirmDeletePost { title, id } => write!(f, "Delete post \"{title}\" ({id})?"),
+            Msg::NoPosts => f.write_str("(there are no posts yet)"),
+            Msg::PageSummary { first, last, total } => write!(f, "posts {first}–{last} of {total}"),
+            Msg::NoTags => f.write_str("(there are no tags yet)"),
+            Msg::NoSearchResults => f.write_str("(no posts match)"),
+            Msg::FetchingPosts => f.write_str("Fetching posts"),
+            Msg::FetchingUsers => f.write_str("Fetching users"),
+            Msg::Exported { count, dir } => write!(f, "📦 Exported {count} posts to {dir}"),
+            Msg::ImportCreated { file, id } => write!(f, "created    {file} {id}"),
+            Msg::ImportUpdated { file, id } => write!(f, "updated    {file} {id}"),
+            Msg::ImportUnchanged { file, id } => write!(f, "unchanged  {file} {id}"),
+            Msg::ImportFailed { file, reason } => write!(f, "failed     {file}: {reason}"),
+            Msg::ImportSummary { dry_run, created, updated, unchanged, failed } => write!(
+                f,
+                "{}{created} created, {updated} updated, {unchanged} unchanged, {failed} failed",
+                if *dry_run { "(dry run) " } else { "" }
+            ),
+            Msg::StatusFrontMatterIgnored => f.write_str(
+                "`status` front matter is not supported by the server yet and was ignored.",
+            ),
+            Msg::NoComments => f.write_str("(there are no comments yet)"),
+            Msg::CommentAdded { id } => write!(f, "💬 Comment added: {id}"),
+            Msg::CommentDeleted => f.write_str("🗑 Comment deleted."),
+            Msg::ConfirmDeleteComment { id } => write!(f, "Delete comment {id}?"),
+            Msg::Watching => f.write_str("👀 Watching for events, press Ctrl+C to stop."),
+            Msg::StreamClosed => f.write_str("The server closed the event stream."),
+            Msg::NoRevisions { id } => write!(f, "post {id} has no earlier versions"),
+            Msg::RevisionNotFound { rev } => write!(f, "this post has no revision {rev}"),
+            Msg::NoDifferences => f.write_str("(no differences)"),
+            Msg::RollbackToCurrent => {
+                f.write_str("--to must name an earlier version, not the current one")
+            }
+            Msg::ConfirmRollback { title, rev } => {
+                write!(f, "Roll \"{title}\" back to revision {rev}?")
+            }
+            Msg::PostRolledBack => f.write_str("⏪ Post rolled back."),
+            Msg::NoStats => f.write_str("(you have no posts)"),
+            Msg::StatsSummary { posts, views, days } => {
+                write!(f, "{posts} posts, {views} views in the last {days} days")
+            }
+            Msg::NoFilesMatch { pattern } => write!(f, "no files match `{pattern}`"),
+            Msg::Uploading { file } => write!(f, "Uploading {file}"),
+            Msg::MediaUploaded { file } => write!(f, "🖼 Uploaded {file}."),
+            Msg::UploadFailed { file, err } => write!(f, "Could not upload {file}: {err}"),
+            Msg::UploadsFailed { failed, total } => write!(f, "{failed} of {total} uploads failed"),
+            Msg::MediaLinked { count, title } => {
+                write!(f, "🔗 Added {count} images to \"{title}\".")
+            }
+            Msg::NoMedia => f.write_str("(you have no uploads)"),
+            Msg::ConfirmDeleteMedia { id } => {
+                write!(f, "Delete upload {id}? Posts embedding it will show a broken image.")
+            }
+            Msg::MediaDeleted => f.write_str("🗑 Upload deleted."),
+            Msg::NoUsers => f.write_str("(there are no users)"),
+            Msg::UserDisabled => f.write_str("🚫 User disabled."),
+            Msg::ConfirmDisableUser { id } => write!(f, "Disable user {id}?"),
+            Msg::RoleSet { role } => write!(f, "✅ Role set to {role}."),
+            Msg::ImportAuthor { source, user_id, matched } => write!(
+                f,
+                "author     {source} → {user_id}{}",
+                if *matched { "" } else { " (no matching account, default author)" }
+            ),
+            Msg::ImportSkipped { source, reason } => write!(f, "skipped    {source}: {reason}"),
+            Msg::ImportImageFailed { url, reason } => write!(f, "image      {url}: {reason}"),
+            Msg::BlogImportSummary { imported, skipped, images, image_failures } => write!(
+                f,
+                "{imported} imported, {skipped} skipped, {images} images copied, \
+                 {image_failures} images failed"
+            ),
+            Msg::AdminLoginRequired => {
+                f.write_str("not logged in; run `blog-cli login` with an admin account first")
+            }
+            Msg::AdminRoleRequired => f.write_str(
+                "admin commands require an account with the admin role \
+                 (log in again if your role was changed recently)",
+            ),
+            Msg::Aborted => f.write_str("Aborted."),
+            Msg::ServerUnhealthy => f.write_str("server is not healthy"),
+            Msg::SessionRefreshed => f.write_str("🔄 Session expired and was refreshed."),
+            Msg::RefreshFailed { err } => write!(f, "Could not refresh the expired session: {err}"),
+            Msg::LoginRequired => f.write_str("not logged in; run `blog-cli login` first"),
+            Msg::SessionExpired => f.write_str(
+                "your session has expired or is invalid; please log in again with `blog-cli login`",
+            ),
+            Msg::KeychainUnavailable { err, path } => {
+                write!(f, "OS keychain unavailable ({err}); storing token in {path}")
+            }
+            Msg::ConfirmSuffix => f.write_str("[y/N]"),
+            Msg::ConfirmationNeedsTerminal => f.write_str(
+                "confirmation required but stdin is not a terminal; pass --yes to proceed",
+            ),
+            Msg::PasswordPrompt => f.write_str("Password: "),
+            Msg::RepeatPasswordPrompt => f.write_str("Repeat password: "),
+            Msg::NoPasswordOnStdin => f.write_str("no password on stdin"),
+            Msg::NoPasswordGiven { env } => {
+                write!(f, "no password given: pass --password-stdin or set {env}")
+            }
+            Msg::PasswordsDiffer => f.write_str("passwords do not match"),
+            Msg::UnsupportedLang { value } => {
+                write!(f, "{LANG_ENV}={value} is not supported; using English (en, ru)")
+            }
+            Msg::ErrorLabel => f.write_str("Error:"),
+            Msg::CausedBy => f.write_str("caused by:"),
+        }
+    }
+
+    /// Writes the Russian text.
+    fn ru(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Msg::LoggedOut => f.write_str("👋 Вы вышли, токен удалён."),
+            Msg::NotLoggedIn => f.write_str("Вход не выполнен."),
+            Msg::Registered => f.write_str("✅ Пользователь зарегистрирован, токен сохранён."),
+            Msg::RegisteredWithoutToken => {
+                f.write_str("Регистрация прошла успешно, но сервер не вернул токен.")
+            }
+            Msg::LoggedIn => f.write_str("✅ Вход выполнен, токен сохранён."),
+            Msg::LoggedInWithoutToken => f.write_str("Вход выполнен, но сервер не вернул токен."),
+            Msg::UserLine { username, email } => write!(f, "пользователь: {username} <{email}>"),
+            Msg::PostCreated => f.write_str("✅ Пост создан:"),
+            Msg::PostUpdated => f.write_str("✅ Пост обновлён:"),
+            Msg::PostDeleted => f.write_str("🗑 Пост удалён."),
+            Msg::ConfirmDeletePost { title, id } => write!(f, "Удалить пост «{title}» ({id})?"),
+            Msg::NoPosts => f.write_str("(постов пока нет)"),
+            Msg::PageSummary { first, last, total } => write!(f, "посты {first}–{last} из {total}"),
+            Msg::NoTags => f.write_str("(тегов пока нет)"),
+            Msg::NoSearchResults => f.write_str("(подходящих постов нет)"),
+            Msg::FetchingPosts => f.write_str("Загрузка постов"),
+            Msg::FetchingUsers => f.write_str("Загрузка пользователей"),
+            Msg::Exported { count, dir } => {
+                write!(f, "📦 Экспортировано постов: {count}, каталог {dir}")
+            }
+            Msg::ImportCreated { file, id } => write!(f, "создан        {file} {id}"),
+            Msg::ImportUpdated { file, id } => write!(f, "обновлён      {file} {id}"),
+            Msg::ImportUnchanged { file, id } => write!(f, "без изменений {file} {id}"),
+            Msg::ImportFailed { file, reason } => write!(f, "ошибка        {file}: {reason}"),
+            Msg::ImportSummary { dry_run, created, updated, unchanged, failed } => write!(
+                f,
+                "{}создано: {created}, обновлено: {updated}, без изменений: {unchanged}, ошибок: {failed}",
+                if *dry_run { "(пробный запуск) " } else { "" }
+            ),
+            Msg::StatusFrontMatterIgnored => f.write_str(
+                "Поле `status` во front matter пока не поддерживается сервером и пропущено.",
+            ),
+            Msg::NoComments => f.write_str("(комментариев пока нет)"),
+            Msg::CommentAdded { id } => write!(f, "💬 Комментарий добавлен: {id}"),
+            Msg::CommentDeleted => f.write_str("🗑 Комментарий удалён."),
+            Msg::ConfirmDeleteComment { id } => write!(f, "Удалить комментарий {id}?"),
+            Msg::Watching => f.write_str("👀 Ожидание событий, Ctrl+C для выхода."),
+            Msg::StreamClosed => f.write_str("Сервер закрыл поток событий."),
+            Msg::NoRevisions { id } => write!(f, "у поста {id} нет предыдущих версий"),
+            Msg::RevisionNotFound { rev } => write!(f, "у этого поста нет версии {rev}"),
+            Msg::NoDifferences => f.write_str("(различий нет)"),
+            Msg::RollbackToCurrent => {
+                f.write_str("--to должен указывать на предыдущую версию, а не на текущую")
+            }
+            Msg::ConfirmRollback { title, rev } => {
+                write!(f, "Откатить «{title}» к версии {rev}?")
+            }
+            Msg::PostRolledBack => f.write_str("⏪ Пост откачен к прежней версии."),
+            Msg::NoStats => f.write_str("(у вас нет постов)"),
+            Msg::StatsSummary { posts, views, days } => {
+                write!(f, "постов: {posts}, просмотров за последние {days} дн.: {views}")
+            }
+            Msg::NoFilesMatch { pattern } => write!(f, "нет файлов, подходящих под `{pattern}`"),
+            Msg::Uploading { file } => write!(f, "Загрузка {file}"),
+            Msg::MediaUploaded { file } => write!(f, "🖼 Файл {file} загружен."),
+            Msg::UploadFailed { file, err } => write!(f, "Не удалось загрузить {file}: {err}"),
+            Msg::UploadsFailed { failed, total } => {
+                write!(f, "не удалось загрузить файлов: {failed} из {total}")
+            }
+            Msg::MediaLinked { count, title } => {
+                write!(f, "🔗 В пост «{title}» добавлено изображений: {count}.")
+            }
+            Msg::NoMedia => f.write_str("(у вас нет загруженных файлов)"),
+            Msg::ConfirmDeleteMedia { id } => write!(
+                f,
+                "Удалить файл {id}? В постах, где он используется, изображение пропадёт."
+            ),
+            Msg::MediaDeleted => f.write_str("🗑 Файл удалён."),
+            Msg::NoUsers => f.write_str("(пользователей нет)"),
+            Msg::UserDisabled => f.write_str("🚫 Пользователь заблокирован."),
+            Msg::ConfirmDisableUser { id } => write!(f, "Заблокировать пользователя {id}?"),
+            Msg::RoleSet { role } => write!(f, "✅ Роль изменена на {role}."),
+            Msg::ImportAuthor { source, user_id, matched } => write!(
+                f,
+                "автор         {source} → {user_id}{}",
+                if *matched { "" } else { " (учётная запись не найдена, автор по умолчанию)" }
+            ),
+            Msg::ImportSkipped { source, reason } => write!(f, "пропущен      {source}: {reason}"),
+            Msg::ImportImageFailed { url, reason } => {
+                write!(f, "изображение   {url}: {reason}")
+            }
+            Msg::BlogImportSummary { imported, skipped, images, image_failures } => write!(
+                f,
+                "импортировано: {imported}, пропущено: {skipped}, изображений скопировано: \
+                 {images}, не удалось: {image_failures}"
+            ),
+            Msg::AdminLoginRequired => f.write_str(
+                "вход не выполнен; выполните `blog-cli login` под учётной записью администратора",
+            ),
+            Msg::AdminRoleRequired => f.write_str(
+                "команды администрирования доступны только администраторам \
+                 (если роль недавно изменилась, войдите заново)",
+            ),
+            Msg::Aborted => f.write_str("Отменено."),
+            Msg::ServerUnhealthy => f.write_str("сервер неисправен"),
+            Msg::SessionRefreshed => f.write_str("🔄 Сессия истекла и была обновлена."),
+            Msg::RefreshFailed { err } => write!(f, "Не удалось обновить истёкшую сессию: {err}"),
+            Msg::LoginRequired => f.write_str("вход не выполнен; выполните `blog-cli login`"),
+            Msg::SessionExpired => f.write_str(
+                "сессия истекла или недействительна; войдите заново с помощью `blog-cli login`",
+            ),
+            Msg::KeychainUnavailable { err, path } => {
+                write!(f, "Хранилище ключей ОС недоступно ({err}); токен сохранён в {path}")
+            }
+            Msg::ConfirmSuffix => f.write_str("[д/Н]"),
+            Msg::ConfirmationNeedsTerminal => f.write_str(
+                "требуется подтверждение, но stdin не является терминалом; укажите --yes",
+            ),
+            Msg::PasswordPrompt => f.write_str("Пароль: "),
+            Msg::RepeatPasswordPrompt => f.write_str("Повторите пароль: "),
+            Msg::NoPasswordOnStdin => f.write_str("пароль не передан в stdin"),
+            Msg::NoPasswordGiven { env } => {
+                write!(f, "пароль не указан: используйте --password-stdin или задайте {env}")
+            }
+            Msg::PasswordsDiffer => f.write_str("пароли не совпадают"),
+            // Only shown after falling back to English.
+            Msg::UnsupportedLang { .. } => self.en(f),
+            Msg::ErrorLabel => f.write_str("Ошибка:"),
+            Msg::CausedBy => f.write_str("причина:"),
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use chrono::Utc;
+use serde::Deserialize;
+
+/// Tokens this close to expiry are treated as expired, to absorb clock skew
+/// and the time the request takes.
+const EXPIRY_LEEWAY_SECS: i64 = 30;
+
+/// Claims the CLI reads from a stored access token.
+///
+/// The signature is not verified: the server remains the authority, these
+/// are only used to decide what to attempt locally.
+#[derive(Debug, Deserialize)]
+pub struct TokenClaims {
+    /// Expiration time, in seconds since the Unix epoch.
+    #[serde(default)]
+    pub exp: Option<i64>,
+
+    /// User role at the time the token was issued.
+    #[serde(default)]
+    pub role: Option<String>,
+}
+
+/// Decodes the payload of a JWT without verifying it.
+pub fn decode_claims(token: &str) -> Option<TokenClaims> {
+    let payload = token.split('.').nth(1)?;
+    let bytes = URL_SAFE_NO_PAD.decode(payload).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+/// Returns whether the token was issued to an administrator.
+pub fn is_admin(token: &str) -> bool {
+    decode_claims(token).is_some_and(|claims| claims.role.as_deref() == Some("admin"))
+}
+
+/// Returns whether the token has expired or is about to.
+///
+/// Tokens whose expiry cannot be read are left for the server to judge.
+pub fn is_expired(token: &str) -> bool {
+    decode_claims(token)
+        .and_then(|claims| claims.exp)
+        .is_some_and(|exp| exp - EXPIRY_LEEWAY_SECS <= Utc::now().timestamp())
+}
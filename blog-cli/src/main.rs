@@ -1,14 +1,45 @@
-use std::fs;
-use std::io::{self, Write};
-use std::path::Path;
-
-use anyhow::Result;
-use clap::{Parser, Subcommand};
+mod admin;
+mod archive;
+mod comments;
+mod completions;
+mod config;
+mod i18n;
+mod jwt;
+mod markdown;
+mod media;
+mod output;
+mod progress;
+mod prompt;
+mod revisions;
+mod session;
+mod stats;
+mod status;
+mod token_store;
+mod watch;
+
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use anyhow::{Context, Result};
+use clap::{CommandFactory, Parser, Subcommand};
+use clap_complete::engine::ArgValueCompleter;
+use clap_complete::env::CompleteEnv;
+use futures_util::StreamExt;
 use blog_client::{BlogClient, Transport};
 use blog_client::error::BlogClientError;
 use uuid::Uuid;
 
-const TOKEN_FILE: &str = ".blog_token";
+use crate::admin::{ImportFormatArg, RoleArg};
+use crate::archive::ImportStatus;
+use crate::completions::{COMPLETE_VAR, Shell, complete_profiles};
+use crate::config::{Config, Profile, TransportKind};
+use crate::i18n::Msg;
+use crate::markdown::MarkdownPost;
+use crate::progress::Progress;
+use crate::prompt::PasswordArgs;
+use crate::revisions::RevisionRef;
+use crate::token_store::TokenStore;
+use crate::watch::EventFilter;
 
 #[derive(Parser, Debug)]
 #[command(name = "blog-cli")]
@@ -20,31 +51,67 @@ struct Cli {
     #[arg(long)]
     server: Option<String>,
 
+    /// Path the HTTP API is mounted under (defaults to /api/v1).
+    #[arg(long, global = true)]
+    api_prefix: Option<String>,
+
+    /// Profile from the config file to use.
+    #[arg(long, global = true, add = ArgValueCompleter::new(complete_profiles))]
+    profile: Option<String>,
+
+    /// Path to the config file (defaults to ~/.config/blog-cli/config.toml).
+    #[arg(long, global = true)]
+    config: Option<PathBuf>,
+
+    /// Skip confirmation prompts for destructive commands.
+    #[arg(short, long, global = true)]
+    yes: bool,
+
+    /// Show HTTP request details; repeat (-vv) for headers.
+    #[arg(short, long, global = true, action = clap::ArgAction::Count, conflicts_with = "quiet")]
+    verbose: u8,
+
+    /// Print only essential output, e.g. just the ID of a created post.
+    #[arg(short, long, global = true)]
+    quiet: bool,
+
+    /// Disable colored output (also honors the NO_COLOR env var).
+    #[arg(long, global = true)]
+    no_color: bool,
+
     #[command(subcommand)]
     command: Commands,
 }
 
 #[derive(Subcommand, Debug)]
 enum Commands {
+    /// Create an account and save the returned token.
     Register {
         #[arg(long)]
         username: String,
         #[arg(long)]
         email: String,
-        #[arg(long)]
-        password: String,
+        #[command(flatten)]
+        password: PasswordArgs,
     },
+    /// Log in and save the returned token.
     Login {
         #[arg(long)]
         email: String,
-        #[arg(long)]
-        password: String,
+        #[command(flatten)]
+        password: PasswordArgs,
     },
     Create {
+        #[arg(long, required_unless_present = "file")]
+        title: Option<String>,
+        #[arg(long, required_unless_present = "file", conflicts_with = "file")]
+        content: Option<String>,
+        /// Markdown file with YAML front matter; the body becomes the content.
         #[arg(long)]
-        title: String,
-        #[arg(long)]
-        content: String,
+        file: Option<PathBuf>,
+        /// Comma-separated tags; overrides `tags` from the front matter.
+        #[arg(long, value_delimiter = ',')]
+        tags: Option<Vec<String>>,
     },
     Get {
         #[arg(long)]
@@ -55,94 +122,392 @@ enum Commands {
         id: String,
         #[arg(long)]
         title: Option<String>,
-        #[arg(long)]
+        #[arg(long, conflicts_with = "file")]
         content: Option<String>,
+        /// Markdown file with YAML front matter; the body becomes the content.
+        #[arg(long)]
+        file: Option<PathBuf>,
+        /// Comma-separated tags replacing the current ones; `--tags ""` clears them.
+        #[arg(long, value_delimiter = ',')]
+        tags: Option<Vec<String>>,
     },
     Delete {
         #[arg(long)]
         id: String,
     },
     List {
-        #[arg(long, default_value_t = 20)]
+        /// Posts per page (the server caps this at 100).
+        #[arg(long, visible_alias = "per-page", default_value_t = 20)]
         limit: u32,
-        #[arg(long, default_value_t = 0)]
+        #[arg(long, default_value_t = 0, conflicts_with = "page")]
         offset: u32,
+        /// 1-based page number; sets the offset from the page size.
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        page: Option<u32>,
+        /// Follow pagination until every post has been listed.
+        #[arg(long, conflicts_with_all = ["offset", "page", "limit"])]
+        all: bool,
+        /// Only list posts with this tag.
+        #[arg(long)]
+        tag: Option<String>,
+    },
+    /// Search posts of all authors, best matches first.
+    Search {
+        /// Search terms: `"exact phrase"`, `or` and `-excluded` are understood.
+        query: String,
+        /// Results per page (the server caps this at 100).
+        #[arg(long, visible_alias = "per-page", default_value_t = 20)]
+        limit: u32,
+        /// 1-based page number.
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        page: Option<u32>,
+        /// Only search posts with this tag.
+        #[arg(long)]
+        tag: Option<String>,
+    },
+    /// Inspect tags used on your posts.
+    Tags {
+        #[command(subcommand)]
+        action: TagsCommand,
+    },
+    /// Forget the token stored for the current profile.
+    Logout,
+    /// Export all posts as Markdown files plus a manifest.
+    Export {
+        #[arg(long)]
+        dir: PathBuf,
+    },
+    /// Import posts from an export directory, creating or updating by ID or slug.
+    Import {
+        #[arg(long)]
+        dir: PathBuf,
+        /// Show what would change without touching the server.
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Manage comments on posts.
+    Comments {
+        #[command(subcommand)]
+        action: CommentsCommand,
+    },
+    /// Print blog events (new posts, comments, ...) as they happen.
+    Watch {
+        /// Only show these event types, e.g. `--filter comment,post_deleted`.
+        #[arg(long, value_enum, value_delimiter = ',')]
+        filter: Vec<EventFilter>,
+    },
+    /// Show what changed in a post between two versions, as a unified diff.
+    Diff {
+        post_id: String,
+        /// Older version: `current`, N versions back (1 is the latest earlier one) or a
+        /// revision ID.
+        #[arg(long, default_value = "1")]
+        from: RevisionRef,
+        /// Newer version, named like `--from`.
+        #[arg(long, default_value = "current")]
+        to: RevisionRef,
+    },
+    /// Restore an earlier version of a post after showing what would change.
+    Rollback {
+        post_id: String,
+        /// Version to restore: N versions back (1 is the latest earlier one) or a revision ID.
+        #[arg(long)]
+        to: RevisionRef,
+    },
+    /// Show views, reactions and comments of your posts (HTTP only).
+    Stats {
+        /// Only show this post, with its daily views.
+        #[arg(long)]
+        post: Option<String>,
+        /// Period to report on: `30d`, `4w` or a number of days, up to 365.
+        #[arg(long, default_value = "30d", value_parser = stats::parse_period)]
+        since: u32,
+        /// Print the stats as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Upload, list and delete your images (HTTP only).
+    Media {
+        #[command(subcommand)]
+        action: MediaCommand,
+    },
+    /// Administer the server (requires the admin role).
+    Admin {
+        #[command(subcommand)]
+        action: AdminCommand,
+    },
+    /// Check server health over both HTTP and gRPC; exits non-zero if unhealthy.
+    Status {
+        /// HTTP server to probe instead of the profile or default one.
+        #[arg(long)]
+        http_server: Option<String>,
+        /// gRPC server to probe instead of the profile or default one.
+        #[arg(long)]
+        grpc_server: Option<String>,
+    },
+    /// Print a shell completion script, e.g. `source <(blog-cli completions bash)`.
+    Completions {
+        #[arg(value_enum)]
+        shell: Shell,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum TagsCommand {
+    /// List tags with the number of posts using each.
+    List,
+}
+
+#[derive(Subcommand, Debug)]
+enum MediaCommand {
+    /// Upload images and print their IDs and URLs.
+    Upload {
+        /// Files to upload; glob patterns such as `"shots/*.png"` are expanded.
+        #[arg(required = true)]
+        files: Vec<String>,
+        /// Append the uploaded images to the end of this post as Markdown.
+        #[arg(long)]
+        link_to_post: Option<String>,
+        /// Print the uploaded media as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// List your uploads, oldest first.
+    List {
+        /// Print uploads as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Delete an upload.
+    Delete {
+        id: String,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum AdminCommand {
+    /// Manage user accounts.
+    Users {
+        #[command(subcommand)]
+        action: AdminUsersCommand,
+    },
+    /// Import posts from a WordPress (WXR) or Medium export, copying their images.
+    Import {
+        /// Export file: WordPress WXR XML, a Medium export zip or a Medium post's HTML.
+        file: PathBuf,
+        #[arg(long, value_enum)]
+        format: ImportFormatArg,
+        /// User ID receiving posts whose author has no account here (default: you).
+        #[arg(long)]
+        author: Option<String>,
+        /// Print the import summary as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum AdminUsersCommand {
+    /// List user accounts, oldest first.
+    List {
+        /// Users per page (the server caps this at 100).
+        #[arg(long, visible_alias = "per-page", default_value_t = 20)]
+        limit: u32,
+        /// 1-based page number.
+        #[arg(long, value_parser = clap::value_parser!(u32).range(1..))]
+        page: Option<u32>,
+        /// Follow pagination until every user has been listed.
+        #[arg(long, conflicts_with_all = ["page", "limit"])]
+        all: bool,
+        /// Print users as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Disable an account so it can no longer log in or use its tokens.
+    Disable {
+        id: String,
+        /// Print the updated user as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+    /// Change the role of an account.
+    SetRole {
+        id: String,
+        #[arg(value_enum)]
+        role: RoleArg,
+        /// Print the updated user as JSON.
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+#[derive(Subcommand, Debug)]
+enum CommentsCommand {
+    /// Show the comment thread of a post.
+    List {
+        post_id: String,
+    },
+    /// Comment on a post.
+    Add {
+        post_id: String,
+        #[arg(long)]
+        text: String,
+        /// Reply to an existing comment.
+        #[arg(long)]
+        reply_to: Option<String>,
+    },
+    /// Delete a comment.
+    Delete {
+        id: String,
     },
 }
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
+    CompleteEnv::with_factory(Cli::command)
+        .var(COMPLETE_VAR)
+        .complete();
+
     dotenvy::dotenv().ok();
 
     let cli = Cli::parse();
+    output::init(cli.verbose, cli.quiet, cli.no_color);
+    if let Some(value) = i18n::init() {
+        output::warn(Msg::UnsupportedLang { value: &value });
+    }
 
-    let server_addr = cli.server.unwrap_or_else(|| {
-        if cli.grpc {
-            "http://127.0.0.1:50051".to_string()
+    match run(cli).await {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            output::error(&err);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+/// Runs the parsed command.
+async fn run(cli: Cli) -> Result<()> {
+    if let Commands::Completions { shell } = cli.command {
+        return completions::write_registration(shell);
+    }
+
+    let config = Config::load(cli.config.as_deref())?;
+    let profile = config.profile(cli.profile.as_deref())?;
+
+    let tokens = TokenStore::new(&profile);
+
+    // Logging out is local only: the server has no token revocation endpoint yet.
+    if let Commands::Logout = cli.command {
+        if tokens.clear()? {
+            output::success(Msg::LoggedOut);
         } else {
-            "http://127.0.0.1:8080".to_string()
+            output::info(Msg::NotLoggedIn);
         }
-    });
+        return Ok(());
+    }
 
-    let transport = if cli.grpc {
-        Transport::Grpc(server_addr)
+    let kind = if cli.grpc {
+        TransportKind::Grpc
     } else {
-        Transport::Http(server_addr)
+        profile.transport
+    };
+
+    if let Commands::Status { http_server, grpc_server } = cli.command {
+        let server = cli.server.as_deref();
+        let http = http_server
+            .unwrap_or_else(|| server_address(TransportKind::Http, kind, server, &profile));
+        let grpc = grpc_server
+            .unwrap_or_else(|| server_address(TransportKind::Grpc, kind, server, &profile));
+
+        let probes = [status::probe_http(http).await, status::probe_grpc(grpc).await];
+        status::print(&probes);
+
+        if probes.iter().all(status::Probe::is_ok) {
+            return Ok(());
+        }
+        anyhow::bail!("{}", Msg::ServerUnhealthy);
+    }
+
+    let server_addr = server_address(kind, kind, cli.server.as_deref(), &profile);
+
+    let transport = match kind {
+        TransportKind::Grpc => Transport::Grpc(server_addr.clone()),
+        TransportKind::Http => Transport::Http(server_addr.clone()),
     };
 
     let mut client = BlogClient::new(transport)
         .await
         .map_err(map_client_err)?;
+    if let Some(prefix) = cli.api_prefix.as_deref().or(profile.api_prefix.as_deref()) {
+        client = client.with_api_prefix(prefix);
+    }
 
-    if let Some(token) = load_token() {
-        client.set_token(token);
+    // Logging in replaces the session, so there is nothing worth refreshing.
+    if !matches!(cli.command, Commands::Register { .. } | Commands::Login { .. }) {
+        session::restore(&mut client, &tokens).await?;
     }
 
     match cli.command {
         Commands::Register { username, email, password } => {
-            let resp = client
-                .register(username.clone(), email, password)
-                .await
-                .map_err(map_client_err)?;
+            let password = password.resolve(true)?;
+            let resp = client.register(username.clone(), email, password).await?;
 
             if let Some(token) = resp.access_token.as_ref() {
-                save_token(token)?;
-                println!("✅ User registered, token saved.");
+                tokens.save(token, resp.refresh_token.as_deref())?;
+                output::success(Msg::Registered);
             } else {
-                println!("⚠ Registration succeeded, but no token was returned.");
+                output::warn(Msg::RegisteredWithoutToken);
             }
 
             if let Some(user) = resp.user {
-                println!("user: {} <{}>", user.username, user.email);
+                output::info(Msg::UserLine { username: &user.username, email: &user.email });
             }
         }
 
         Commands::Login { email, password } => {
-            let resp = client
-                .login(email.clone(), password)
-                .await
-                .map_err(map_client_err)?;
+            let password = password.resolve(false)?;
+            // Rejected credentials are not an expired session, so skip `map_client_err`.
+            let resp = client.login(email.clone(), password).await?;
 
             if let Some(token) = resp.access_token.as_ref() {
-                save_token(token)?;
-                println!("✅ Login successful, token saved.");
+                tokens.save(token, resp.refresh_token.as_deref())?;
+                output::success(Msg::LoggedIn);
             } else {
-                println!("⚠ Login succeeded, but no token was returned.");
+                output::warn(Msg::LoggedInWithoutToken);
             }
 
             if let Some(user) = resp.user {
-                println!("user: {} <{}>", user.username, user.email);
+                output::info(Msg::UserLine { username: &user.username, email: &user.email });
             }
         }
 
-        Commands::Create { title, content } => {
+        Commands::Create { title, content, file, tags } => {
+            let (title, content, tags) = match file {
+                Some(path) => {
+                    let doc = read_post_file(&path)?;
+                    let title = title.or(doc.front_matter.title).ok_or_else(|| {
+                        anyhow::anyhow!("post title is missing: pass --title or set `title` in front matter")
+                    })?;
+                    (title, doc.body, tags.unwrap_or(doc.front_matter.tags))
+                }
+                None => (
+                    title.unwrap_or_default(),
+                    content.unwrap_or_default(),
+                    tags.unwrap_or_default(),
+                ),
+            };
+
             let post = client
-                .create_post(title, content)
+                .create_post(title, content, tags)
                 .await
                 .map_err(map_client_err)?;
 
-            println!("✅ Post created:");
-            print_post(&post);
+            if output::is_quiet() {
+                println!("{}", post.id);
+            } else {
+                output::success(Msg::PostCreated);
+                print_post(&post);
+            }
         }
 
         Commands::Get { id } => {
@@ -155,8 +520,18 @@ async fn main() -> Result<()> {
             print_post(&post);
         }
 
-        Commands::Update { id, title, content } => {
+        Commands::Update { id, title, content, file, tags } => {
             let id = parse_uuid(&id)?;
+            let (title, content, tags) = match file {
+                Some(path) => {
+                    let doc = read_post_file(&path)?;
+                    // A file without `tags` keeps the current tags.
+                    let file_tags = Some(doc.front_matter.tags).filter(|tags| !tags.is_empty());
+                    (title.or(doc.front_matter.title), Some(doc.body), tags.or(file_tags))
+                }
+                None => (title, content, tags),
+            };
+
             let mut post = client
                 .get_post(id)
                 .await
@@ -170,84 +545,467 @@ async fn main() -> Result<()> {
             }
 
             let updated = client
-                .update_post(post.id, post.title.clone(), post.content.clone())
+                .update_post(post.id, post.title.clone(), post.content.clone(), tags)
                 .await
                 .map_err(map_client_err)?;
 
-            println!("✅ Post updated:");
-            print_post(&updated);
+            if output::is_quiet() {
+                println!("{}", updated.id);
+            } else {
+                output::success(Msg::PostUpdated);
+                print_post(&updated);
+            }
         }
 
         Commands::Delete { id } => {
             let id = parse_uuid(&id)?;
+            let post = client
+                .get_post(id)
+                .await
+                .map_err(map_client_err)?;
+
+            if !prompt::confirm(Msg::ConfirmDeletePost { title: &post.title, id }, cli.yes)? {
+                output::info(Msg::Aborted);
+                return Ok(());
+            }
+
             client
                 .delete_post(id)
                 .await
                 .map_err(map_client_err)?;
 
-            println!("🗑 Post deleted.");
+            output::success(Msg::PostDeleted);
         }
 
-        Commands::List { limit, offset } => {
-            let posts = client
-                .list_posts(limit, offset)
-                .await
-                .map_err(map_client_err)?;
+        Commands::List { limit, offset, page, all, tag } => {
+            let (posts, total) = if all {
+                let progress = Progress::new(Msg::FetchingPosts);
+                let posts = archive::fetch_all_posts(&client, tag.as_deref(), |n| progress.update(n)).await;
+                progress.finish();
+                (posts.map_err(map_client_err)?, None)
+            } else {
+                let offset = page.map_or(offset, |page| (page - 1).saturating_mul(limit));
+                let page = client
+                    .list_posts(limit, offset, tag.as_deref())
+                    .await
+                    .map_err(map_client_err)?;
+                (page.items, Some((page.offset, page.total)))
+            };
 
             if posts.is_empty() {
-                println!("(there are no posts yet)");
+                output::info(Msg::NoPosts);
             } else {
+                let count = posts.len() as u64;
                 for p in posts {
                     println!("------------------------------");
                     print_post(&p);
                 }
+                if let Some((offset, total)) = total {
+                    let first = u64::from(offset) + 1;
+                    output::info(Msg::PageSummary { first, last: first + count - 1, total });
+                }
+            }
+        }
+
+        Commands::Search { query, limit, page, tag } => {
+            let offset = page.map_or(0, |page| (page - 1).saturating_mul(limit));
+            let page = client
+                .search_posts(&query, limit, offset, tag.as_deref())
+                .await
+                .map_err(map_client_err)?;
+
+            if page.items.is_empty() {
+                output::info(Msg::NoSearchResults);
+            } else {
+                let count = page.items.len() as u64;
+                for result in &page.items {
+                    println!("------------------------------");
+                    output::field("id", result.post.id);
+                    output::field("title", &result.post.title);
+                    output::field("rank", format!("{:.3}", result.rank));
+                    output::field("snippet", output::snippet(&result.snippet));
+                }
+                let first = u64::from(page.offset) + 1;
+                output::info(Msg::PageSummary { first, last: first + count - 1, total: page.total });
+            }
+        }
+
+        Commands::Tags { action: TagsCommand::List } => {
+            let tags = client
+                .list_tags()
+                .await
+                .map_err(map_client_err)?;
+
+            if tags.is_empty() {
+                output::info(Msg::NoTags);
+            } else {
+                for tag in tags {
+                    println!("{:<24} {}", tag.name, tag.post_count);
+                }
+            }
+        }
+
+        Commands::Export { dir } => {
+            let progress = Progress::new(Msg::FetchingPosts);
+            let posts = archive::fetch_all_posts(&client, None, |n| progress.update(n)).await;
+            progress.finish();
+            let posts = posts.map_err(map_client_err)?;
+            let manifest = archive::export_posts(&posts, &dir)?;
+
+            output::success(Msg::Exported {
+                count: manifest.posts.len(),
+                dir: &dir.display(),
+            });
+        }
+
+        Commands::Import { dir, dry_run } => {
+            let outcomes = archive::import_posts(&client, &dir, dry_run).await?;
+            let (mut created, mut updated, mut unchanged, mut failed) = (0, 0, 0, 0);
+
+            for outcome in &outcomes {
+                let id = outcome.id.map(|id| id.to_string()).unwrap_or_default();
+                match &outcome.status {
+                    ImportStatus::Created => {
+                        created += 1;
+                        output::info(Msg::ImportCreated { file: &outcome.file, id: &id });
+                    }
+                    ImportStatus::Updated => {
+                        updated += 1;
+                        output::info(Msg::ImportUpdated { file: &outcome.file, id: &id });
+                    }
+                    ImportStatus::Unchanged => {
+                        unchanged += 1;
+                        output::info(Msg::ImportUnchanged { file: &outcome.file, id: &id });
+                    }
+                    ImportStatus::Failed(reason) => {
+                        failed += 1;
+                        output::warn(Msg::ImportFailed { file: &outcome.file, reason });
+                    }
+                }
+            }
+
+            println!(
+                "{}",
+                Msg::ImportSummary { dry_run, created, updated, unchanged, failed }
+            );
+        }
+
+        Commands::Comments { action } => match action {
+            CommentsCommand::List { post_id } => {
+                let post_id = parse_uuid(&post_id)?;
+                let comments = comments::fetch_all_comments(&client, post_id)
+                    .await
+                    .map_err(map_client_err)?;
+
+                if comments.is_empty() {
+                    output::info(Msg::NoComments);
+                } else {
+                    comments::print_thread(&comments);
+                }
+            }
+
+            CommentsCommand::Add { post_id, text, reply_to } => {
+                let post_id = parse_uuid(&post_id)?;
+                let parent_id = reply_to.as_deref().map(parse_uuid).transpose()?;
+                let comment = client
+                    .add_comment(post_id, text, parent_id)
+                    .await
+                    .map_err(map_client_err)?;
+
+                if output::is_quiet() {
+                    println!("{}", comment.id);
+                } else {
+                    output::success(Msg::CommentAdded { id: comment.id });
+                }
+            }
+
+            CommentsCommand::Delete { id } => {
+                let id = parse_uuid(&id)?;
+                if !prompt::confirm(Msg::ConfirmDeleteComment { id }, cli.yes)? {
+                    output::info(Msg::Aborted);
+                    return Ok(());
+                }
+
+                client
+                    .delete_comment(id)
+                    .await
+                    .map_err(map_client_err)?;
+
+                output::success(Msg::CommentDeleted);
+            }
+        },
+
+        Commands::Watch { filter } => {
+            let mut events = client.events().await.map_err(map_client_err)?;
+            output::status(Msg::Watching);
+
+            while let Some(event) = events.next().await {
+                let event = event.map_err(map_client_err)?;
+                if watch::is_selected(&filter, &event) {
+                    watch::print_event(&event);
+                }
+            }
+
+            output::warn(Msg::StreamClosed);
+        }
+
+        Commands::Diff { post_id, from, to } => {
+            let id = parse_uuid(&post_id)?;
+            let post = client.get_post(id).await.map_err(map_client_err)?;
+            let revisions = client.list_revisions(id).await.map_err(map_client_err)?;
+
+            let from = revisions::resolve(from, &post, &revisions)?;
+            let to = revisions::resolve(to, &post, &revisions)?;
+            if !revisions::print_diff(&from, &to) {
+                output::info(Msg::NoDifferences);
             }
         }
+
+        Commands::Rollback { post_id, to } => {
+            let id = parse_uuid(&post_id)?;
+            let post = client.get_post(id).await.map_err(map_client_err)?;
+            let revisions = client.list_revisions(id).await.map_err(map_client_err)?;
+
+            let target = revisions::resolve(to, &post, &revisions)?;
+            let Some(revision_id) = target.revision_id else {
+                anyhow::bail!("{}", Msg::RollbackToCurrent);
+            };
+            let current = revisions::resolve(RevisionRef::Current, &post, &revisions)?;
+            if !revisions::print_diff(&current, &target) {
+                output::info(Msg::NoDifferences);
+                return Ok(());
+            }
+
+            if !prompt::confirm(Msg::ConfirmRollback { title: &post.title, rev: &to }, cli.yes)? {
+                output::info(Msg::Aborted);
+                return Ok(());
+            }
+
+            let restored = client
+                .restore_revision(id, revision_id)
+                .await
+                .map_err(map_client_err)?;
+
+            if output::is_quiet() {
+                println!("{}", restored.id);
+            } else {
+                output::success(Msg::PostRolledBack);
+                print_post(&restored);
+            }
+        }
+
+        Commands::Stats { post, since, json } => match post {
+            Some(id) => {
+                let id = parse_uuid(&id)?;
+                let stats = client
+                    .get_post_stats(id, since)
+                    .await
+                    .map_err(map_client_err)?;
+
+                stats::print_one(&stats, json)?;
+            }
+            None => {
+                let stats = client
+                    .list_post_stats(since)
+                    .await
+                    .map_err(map_client_err)?;
+
+                stats::print_all(&stats, since, json)?;
+            }
+        },
+
+        Commands::Media { action } => match action {
+            MediaCommand::Upload { files, link_to_post, json } => {
+                let files = media::expand_files(&files)?;
+                // Checked before uploading, so a wrong ID wastes no uploads.
+                let post = match link_to_post {
+                    Some(id) => {
+                        let id = parse_uuid(&id)?;
+                        Some(client.get_post(id).await.map_err(map_client_err)?)
+                    }
+                    None => None,
+                };
+
+                let mut uploads = Vec::with_capacity(files.len());
+                let mut failed = 0;
+                for path in &files {
+                    match media::upload_file(&client, path).await {
+                        Ok(uploaded) => {
+                            if !json {
+                                media::print_uploaded(&uploaded, &server_addr);
+                            }
+                            uploads.push(uploaded);
+                        }
+                        // Every other file would fail the same way.
+                        Err(err) if is_unauthorized(&err) => return Err(err),
+                        Err(err) => {
+                            failed += 1;
+                            output::warn(Msg::UploadFailed { file: &path.display(), err: &err });
+                        }
+                    }
+                }
+                if json {
+                    println!("{}", serde_json::to_string_pretty(&uploads)?);
+                }
+
+                if let Some(post) = post.filter(|_| !uploads.is_empty()) {
+                    let content = media::append_images(&post.content, &uploads, &server_addr);
+                    client
+                        .update_post(post.id, post.title.clone(), content, None)
+                        .await
+                        .map_err(map_client_err)?;
+                    output::success(Msg::MediaLinked { count: uploads.len(), title: &post.title });
+                }
+
+                if failed > 0 {
+                    anyhow::bail!("{}", Msg::UploadsFailed { failed, total: files.len() });
+                }
+            }
+            MediaCommand::List { json } => {
+                let uploads = client.list_media().await.map_err(map_client_err)?;
+                media::print_list(&uploads, &server_addr, json)?;
+            }
+            MediaCommand::Delete { id } => {
+                let id = parse_uuid(&id)?;
+                if !prompt::confirm(Msg::ConfirmDeleteMedia { id }, cli.yes)? {
+                    output::info(Msg::Aborted);
+                    return Ok(());
+                }
+
+                client.delete_media(id).await.map_err(map_client_err)?;
+                output::success(Msg::MediaDeleted);
+            }
+        },
+
+        Commands::Admin { action: AdminCommand::Users { action } } => {
+            admin::require_admin(&client)?;
+
+            match action {
+                AdminUsersCommand::List { limit, page, all, json } => {
+                    let users = if all {
+                        let progress = Progress::new(Msg::FetchingUsers);
+                        let users = admin::fetch_all_users(&client, |n| progress.update(n)).await;
+                        progress.finish();
+                        users
+                    } else {
+                        let offset = page.map_or(0, |page| (page - 1).saturating_mul(limit));
+                        client.list_users(limit, offset).await
+                    }
+                    .map_err(map_client_err)?;
+
+                    admin::print_users(&users, json)?;
+                }
+                AdminUsersCommand::Disable { id, json } => {
+                    let id = parse_uuid(&id)?;
+                    if !prompt::confirm(Msg::ConfirmDisableUser { id }, cli.yes)? {
+                        output::info(Msg::Aborted);
+                        return Ok(());
+                    }
+
+                    let user = client.disable_user(id).await.map_err(map_client_err)?;
+                    if !json {
+                        output::success(Msg::UserDisabled);
+                    }
+                    admin::print_user(&user, json)?;
+                }
+                AdminUsersCommand::SetRole { id, role, json } => {
+                    let id = parse_uuid(&id)?;
+                    let user = client
+                        .set_user_role(id, role.as_str())
+                        .await
+                        .map_err(map_client_err)?;
+                    if !json {
+                        output::success(Msg::RoleSet { role: &user.role });
+                    }
+                    admin::print_user(&user, json)?;
+                }
+            }
+        }
+
+        Commands::Admin { action: AdminCommand::Import { file, format, author, json } } => {
+            admin::require_admin(&client)?;
+
+            let author_id = author.as_deref().map(parse_uuid).transpose()?;
+            let export = std::fs::read(&file)
+                .with_context(|| format!("failed to read {}", file.display()))?;
+            let summary = client
+                .import_posts(format.as_str(), export, author_id)
+                .await
+                .map_err(map_client_err)?;
+
+            admin::print_import_summary(&summary, json)?;
+        }
+
+        Commands::Logout | Commands::Completions { .. } | Commands::Status { .. } => {
+            unreachable!("handled before connecting")
+        }
     }
 
     Ok(())
 }
 
-fn parse_uuid(input: &str) -> Result<Uuid> {
-    Ok(Uuid::parse_str(input)?)
+/// Resolves the server address for `kind` when `selected` is the active transport.
+///
+/// `--server` only applies to the selected transport and the profile server only
+/// to the profile's transport; anything else falls back to the local default.
+fn server_address(
+    kind: TransportKind,
+    selected: TransportKind,
+    server: Option<&str>,
+    profile: &Profile,
+) -> String {
+    server
+        .filter(|_| kind == selected)
+        .or_else(|| profile.server.as_deref().filter(|_| kind == profile.transport))
+        .unwrap_or(kind.default_server())
+        .to_string()
 }
 
-fn load_token() -> Option<String> {
-    if !Path::new(TOKEN_FILE).exists() {
-        return None;
-    }
+/// Reads a Markdown post file, warning about front matter the server cannot store.
+fn read_post_file(path: &Path) -> Result<MarkdownPost> {
+    let doc = MarkdownPost::read(path)?;
 
-    match fs::read_to_string(TOKEN_FILE) {
-        Ok(s) => {
-            let t = s.trim().to_string();
-            if t.is_empty() {
-                None
-            } else {
-                Some(t)
-            }
-        }
-        Err(_) => None,
+    if doc.front_matter.status.is_some() {
+        output::warn(Msg::StatusFrontMatterIgnored);
     }
+
+    Ok(doc)
 }
 
-fn save_token(token: &str) -> io::Result<()> {
-    let mut file = fs::File::create(TOKEN_FILE)?;
-    file.write_all(token.as_bytes())?;
-    Ok(())
+fn parse_uuid(input: &str) -> Result<Uuid> {
+    Ok(Uuid::parse_str(input)?)
 }
 
 fn print_post(post: &blog_client::models::Post) {
-    println!("id:         {}", post.id);
-    println!("title:      {}", post.title);
-    println!("content:    {}", post.content);
-    println!("author_id:  {}", post.author_id);
-    println!("created_at: {}", post.created_at);
-
-    if let Some(updated_at) = post.updated_at {
-        println!("updated_at: {}", updated_at);
+    output::field("id", post.id);
+    output::field("title", &post.title);
+    output::field("content", &post.content);
+    if !post.tags.is_empty() {
+        output::field("tags", post.tags.join(", "));
+    }
+    output::field("author_id", post.author_id);
+    if let Some(author) = &post.author {
+        output::field("author", &author.username);
+    }
+    output::field("created_at", post.created_at);
+
+    if post.updated_at != post.created_at {
+        output::field("updated_at", post.updated_at);
     }
 }
 
+/// Returns whether a command failed for lack of a valid session.
+fn is_unauthorized(err: &anyhow::Error) -> bool {
+    err.downcast_ref::<BlogClientError>()
+        .is_some_and(BlogClientError::is_unauthorized)
+}
+
 fn map_client_err(err: BlogClientError) -> anyhow::Error {
-    anyhow::anyhow!(err)
+    match err {
+        BlogClientError::Unauthorized(_) => {
+            anyhow::anyhow!(err).context(Msg::LoginRequired.to_string())
+        }
+        err if err.is_unauthorized() => anyhow::anyhow!(err).context(Msg::SessionExpired.to_string()),
+        err => anyhow::anyhow!(err),
+    }
 }
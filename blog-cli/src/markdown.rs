@@ -0,0 +1,102 @@
+use std::fs;
+use std::path::Path;
+
+use anyhow::{Context, Result, anyhow};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Front matter delimiter line.
+const DELIMITER: &str = "---";
+
+/// YAML front matter of a Markdown post.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct FrontMatter {
+    /// Server-side post identifier, set on exported posts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Uuid>,
+
+    /// Post title.
+    pub title: Option<String>,
+
+    /// Post tags.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// Publication status, e.g. `draft` or `published`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<String>,
+
+    /// Post creation timestamp, set on exported posts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created_at: Option<DateTime<Utc>>,
+}
+
+/// Markdown post split into front matter and body.
+#[derive(Debug)]
+pub struct MarkdownPost {
+    /// Parsed front matter.
+    pub front_matter: FrontMatter,
+
+    /// Post body used as the content.
+    pub body: String,
+}
+
+impl MarkdownPost {
+    /// Reads and parses a Markdown file.
+    pub fn read(path: &Path) -> Result<Self> {
+        let source = fs::read_to_string(path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+
+        Self::parse(&source).with_context(|| format!("invalid post file {}", path.display()))
+    }
+
+    /// Parses Markdown source with optional `---` delimited YAML front matter.
+    pub fn parse(source: &str) -> Result<Self> {
+        let source = source.strip_prefix('\u{feff}').unwrap_or(source);
+
+        let Some(rest) = strip_delimiter(source) else {
+            return Ok(Self {
+                front_matter: FrontMatter::default(),
+                body: source.trim().to_string(),
+            });
+        };
+
+        let mut bounds = None;
+        let mut offset = 0;
+        for line in rest.split_inclusive('\n') {
+            if line.trim_end() == DELIMITER {
+                bounds = Some((offset, offset + line.len()));
+                break;
+            }
+            offset += line.len();
+        }
+
+        let (yaml_end, body_start) =
+            bounds.ok_or_else(|| anyhow!("front matter is not terminated by `---`"))?;
+
+        let yaml = &rest[..yaml_end];
+        let front_matter = if yaml.trim().is_empty() {
+            FrontMatter::default()
+        } else {
+            serde_yaml::from_str(yaml).context("invalid front matter")?
+        };
+
+        Ok(Self {
+            front_matter,
+            body: rest[body_start..].trim().to_string(),
+        })
+    }
+
+    /// Renders the post as Markdown with YAML front matter.
+    pub fn render(&self) -> Result<String> {
+        let yaml = serde_yaml::to_string(&self.front_matter).context("invalid front matter")?;
+        Ok(format!("{DELIMITER}\n{yaml}{DELIMITER}\n\n{}\n", self.body))
+    }
+}
+
+/// Strips the opening delimiter line, if the source starts with one.
+fn strip_delimiter(source: &str) -> Option<&str> {
+    let (first, rest) = source.split_once('\n')?;
+    (first.trim_end() == DELIMITER).then_some(rest)
+}
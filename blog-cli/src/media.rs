@@ -0,0 +1,142 @@
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result, bail};
+use blog_client::BlogClient;
+use blog_client::error::BlogClientError;
+use blog_client::models::Media;
+use blog_client::upload::ProgressReporter;
+
+use crate::i18n::Msg;
+use crate::output;
+use crate::progress::Progress;
+
+/// Expands `media upload` arguments into the files to upload, in order.
+///
+/// Arguments containing glob characters are matched here, so patterns work
+/// even where the shell leaves them alone (quoted, or on Windows). A pattern
+/// matching no file is an error rather than silently uploading nothing.
+pub fn expand_files(args: &[String]) -> Result<Vec<PathBuf>> {
+    let mut files = Vec::new();
+
+    for arg in args {
+        if !arg.contains(['*', '?', '[']) {
+            files.push(PathBuf::from(arg));
+            continue;
+        }
+
+        let matched = files.len();
+        for entry in glob::glob(arg).with_context(|| format!("invalid pattern `{arg}`"))? {
+            let path = entry?;
+            if path.is_file() {
+                files.push(path);
+            }
+        }
+        if files.len() == matched {
+            bail!("{}", Msg::NoFilesMatch { pattern: arg });
+        }
+    }
+
+    Ok(files)
+}
+
+/// Uploads a file in resumable chunks, drawing its progress on stderr.
+pub async fn upload_file(client: &BlogClient, path: &Path) -> Result<Media> {
+    let bytes =
+        std::fs::read(path).with_context(|| format!("failed to read {}", path.display()))?;
+    let name = path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+
+    let progress = Progress::new(Msg::Uploading { file: &name });
+    let (reporter, mut updates) = ProgressReporter::new();
+    let upload = client.upload_media_resumable(&name, &bytes, Some(&reporter));
+    tokio::pin!(upload);
+
+    let result: Result<Media, BlogClientError> = loop {
+        tokio::select! {
+            result = &mut upload => break result,
+            Ok(()) = updates.changed() => {
+                let sent = *updates.borrow_and_update();
+                progress.update_share(sent.sent, sent.total);
+            }
+        }
+    };
+    progress.finish();
+
+    result.map_err(crate::map_client_err)
+}
+
+/// Turns a server-relative media path into a URL on `server`.
+pub fn absolute_url(server: &str, path: &str) -> String {
+    format!("{}{}", server.trim_end_matches('/'), path)
+}
+
+/// Appends Markdown images of `uploads` to the end of post `content`, with
+/// the file names as alt text.
+pub fn append_images(content: &str, uploads: &[Media], server: &str) -> String {
+    let images: Vec<String> = uploads
+        .iter()
+        .map(|media| {
+            let alt = media.filename.replace(['[', ']'], "");
+            format!("![{alt}]({})", absolute_url(server, &media.url))
+        })
+        .collect();
+
+    format!("{}\n\n{}\n", content.trim_end(), images.join("\n"))
+}
+
+/// Prints an uploaded file, or just its ID in quiet mode.
+pub fn print_uploaded(media: &Media, server: &str) {
+    if output::is_quiet() {
+        println!("{}", media.id);
+        return;
+    }
+
+    output::success(Msg::MediaUploaded { file: &media.filename });
+    output::field("id", media.id);
+    output::field("url", absolute_url(server, &media.url));
+}
+
+/// Prints uploads as a table, or as a JSON array when `json` is set.
+pub fn print_list(uploads: &[Media], server: &str, json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(uploads)?);
+        return Ok(());
+    }
+
+    if uploads.is_empty() {
+        output::info(Msg::NoMedia);
+        return Ok(());
+    }
+
+    for media in uploads {
+        println!(
+            "{}  {:>9}  {}  {:<24} {}",
+            media.id,
+            format_size(media.size),
+            media.created_at.format("%Y-%m-%d %H:%M"),
+            media.filename,
+            absolute_url(server, &media.url)
+        );
+    }
+
+    Ok(())
+}
+
+/// Formats a size in bytes with a binary unit, e.g. `1.4 MiB`.
+fn format_size(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KiB", "MiB", "GiB"];
+
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    match unit {
+        0 => format!("{bytes} B"),
+        _ => format!("{size:.1} {}", UNITS[unit]),
+    }
+}
@@ -0,0 +1,110 @@
+use std::fmt::Display;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use anstream::{ColorChoice, eprintln, println};
+use anstyle::{AnsiColor, Style};
+use tracing_subscriber::EnvFilter;
+
+use crate::i18n::Msg;
+
+/// Whether non-essential output is suppressed.
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+const SUCCESS: Style = AnsiColor::Green.on_default();
+const WARNING: Style = AnsiColor::Yellow.on_default();
+const ERROR: Style = AnsiColor::Red.on_default().bold();
+const LABEL: Style = Style::new().dimmed();
+const MATCH: Style = Style::new().bold();
+
+/// Configures output for the rest of the process.
+///
+/// Colors are disabled by `no_color`, the `NO_COLOR` env var, or when the
+/// stream is not a terminal. `verbosity` enables request logging:
+/// `-v` shows each HTTP exchange, `-vv` adds headers. `RUST_LOG` overrides it.
+pub fn init(verbosity: u8, quiet: bool, no_color: bool) {
+    QUIET.store(quiet, Ordering::Relaxed);
+
+    if no_color {
+        ColorChoice::Never.write_global();
+    }
+
+    let directives = match verbosity {
+        0 => "warn",
+        1 => "warn,blog_client=debug",
+        _ => "info,blog_client=trace",
+    };
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(directives));
+
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr)
+        .with_ansi(!no_color && anstream::AutoStream::choice(&std::io::stderr()) != ColorChoice::Never)
+        .without_time()
+        .with_target(false)
+        .init();
+}
+
+/// Returns whether quiet mode is on.
+pub fn is_quiet() -> bool {
+    QUIET.load(Ordering::Relaxed)
+}
+
+/// Prints a success message; suppressed in quiet mode.
+pub fn success(message: impl Display) {
+    if !is_quiet() {
+        println!("{SUCCESS}{message}{SUCCESS:#}");
+    }
+}
+
+/// Prints an informational status line; suppressed in quiet mode.
+pub fn info(message: impl Display) {
+    if !is_quiet() {
+        println!("{message}");
+    }
+}
+
+/// Prints a warning to stderr.
+pub fn warn(message: impl Display) {
+    eprintln!("{WARNING}⚠ {message}{WARNING:#}");
+}
+
+/// Prints a progress note to stderr; suppressed in quiet mode.
+pub fn status(message: impl Display) {
+    if !is_quiet() {
+        eprintln!("{message}");
+    }
+}
+
+/// Prints an error and its causes to stderr.
+///
+/// Causes already included in the previous message are skipped.
+pub fn error(err: &anyhow::Error) {
+    eprintln!("{ERROR}{}{ERROR:#} {err}", Msg::ErrorLabel);
+
+    let mut shown = err.to_string();
+    for cause in err.chain().skip(1) {
+        let cause = cause.to_string();
+        if !shown.contains(&cause) {
+            eprintln!("  {LABEL}{}{LABEL:#} {cause}", Msg::CausedBy);
+        }
+        shown = cause;
+    }
+}
+
+/// Prints a labelled field of a record.
+pub fn field(label: &str, value: impl Display) {
+    let label = format!("{label}:");
+    println!("{LABEL}{label:<11}{LABEL:#} {value}");
+}
+
+/// Turns a search snippet, HTML with matches in `<mark>`, into terminal text
+/// with the matches in bold.
+pub fn snippet(html: &str) -> String {
+    html.replace("<mark>", &format!("{MATCH}"))
+        .replace("</mark>", &format!("{MATCH:#}"))
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&")
+}
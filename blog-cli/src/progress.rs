@@ -0,0 +1,48 @@
+use std::fmt::Display;
+use std::io::{self, IsTerminal, Write};
+
+use crate::output;
+
+/// Single-line progress counter drawn on stderr.
+///
+/// Draws nothing in quiet mode or when stderr is not a terminal, so piped
+/// output stays clean.
+pub struct Progress {
+    label: String,
+    enabled: bool,
+}
+
+impl Progress {
+    /// Creates a progress counter with the given label.
+    pub fn new(label: impl Display) -> Self {
+        Self {
+            label: label.to_string(),
+            enabled: io::stderr().is_terminal() && !output::is_quiet(),
+        }
+    }
+
+    /// Redraws the counter with the current item count.
+    pub fn update(&self, count: usize) {
+        if self.enabled {
+            eprint!("\r{} {count}…", self.label);
+            let _ = io::stderr().flush();
+        }
+    }
+
+    /// Redraws the counter with the share of `total` done, as a percentage.
+    pub fn update_share(&self, done: u64, total: u64) {
+        if self.enabled {
+            let percent = (done * 100).checked_div(total).unwrap_or(100);
+            eprint!("\r{} {percent}%…", self.label);
+            let _ = io::stderr().flush();
+        }
+    }
+
+    /// Clears the counter line.
+    pub fn finish(&self) {
+        if self.enabled {
+            eprint!("\r\x1b[2K");
+            let _ = io::stderr().flush();
+        }
+    }
+}
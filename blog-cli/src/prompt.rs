@@ -0,0 +1,75 @@
+use std::io::{self, BufRead, IsTerminal, Write};
+
+use anyhow::{Result, bail};
+
+use crate::i18n::{self, Msg};
+
+/// Asks the user to confirm a destructive action.
+///
+/// Returns `true` immediately when `assume_yes` is set. Without a terminal to
+/// ask on, refuses instead of guessing, so scripts must pass `--yes`.
+pub fn confirm(question: Msg<'_>, assume_yes: bool) -> Result<bool> {
+    if assume_yes {
+        return Ok(true);
+    }
+
+    let stdin = io::stdin();
+    if !stdin.is_terminal() {
+        bail!("{}", Msg::ConfirmationNeedsTerminal);
+    }
+
+    eprint!("{question} {} ", Msg::ConfirmSuffix);
+    io::stderr().flush()?;
+
+    let mut answer = String::new();
+    stdin.lock().read_line(&mut answer)?;
+
+    Ok(i18n::is_yes(&answer))
+}
+
+/// Environment variable read when `--password` is not given.
+pub const PASSWORD_ENV: &str = "BLOG_CLI_PASSWORD";
+
+/// Where to take the account password from.
+#[derive(clap::Args, Debug)]
+pub struct PasswordArgs {
+    /// Password (visible in shell history; prefer the prompt, --password-stdin or the env var).
+    #[arg(long, env = PASSWORD_ENV, hide_env_values = true)]
+    password: Option<String>,
+
+    /// Read the password from the first line of stdin.
+    #[arg(long, conflicts_with = "password")]
+    password_stdin: bool,
+}
+
+impl PasswordArgs {
+    /// Resolves the password from the flag, env var, stdin, or a hidden prompt.
+    ///
+    /// With `confirm` set the prompt asks twice, for choosing a new password.
+    pub fn resolve(self, confirm: bool) -> Result<String> {
+        if let Some(password) = self.password {
+            return Ok(password);
+        }
+
+        if self.password_stdin {
+            let mut line = String::new();
+            io::stdin().lock().read_line(&mut line)?;
+            let password = line.trim_end_matches(['\r', '\n']);
+            if password.is_empty() {
+                bail!("{}", Msg::NoPasswordOnStdin);
+            }
+            return Ok(password.to_string());
+        }
+
+        if !io::stdin().is_terminal() {
+            bail!("{}", Msg::NoPasswordGiven { env: PASSWORD_ENV });
+        }
+
+        let password = rpassword::prompt_password(Msg::PasswordPrompt.to_string())?;
+        if confirm && rpassword::prompt_password(Msg::RepeatPasswordPrompt.to_string())? != password {
+            bail!("{}", Msg::PasswordsDiffer);
+        }
+
+        Ok(password)
+    }
+}
@@ -0,0 +1,152 @@
+use std::fmt::{self, Display};
+use std::str::FromStr;
+
+use anstream::println;
+use anstyle::{AnsiColor, Style};
+use anyhow::{Result, bail};
+use blog_client::models::{Post, PostRevision};
+use similar::{ChangeTag, TextDiff};
+use uuid::Uuid;
+
+use crate::i18n::Msg;
+
+const FILE_HEADER: Style = Style::new().bold();
+const HUNK_HEADER: Style = AnsiColor::Cyan.on_default();
+const REMOVED: Style = AnsiColor::Red.on_default();
+const ADDED: Style = AnsiColor::Green.on_default();
+
+/// Unchanged lines shown around each change.
+const CONTEXT_LINES: usize = 3;
+
+/// Version of a post named on the command line.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RevisionRef {
+    /// The post as it is now.
+    Current,
+    /// The `n`th earlier version, 1 being the most recent.
+    Back(usize),
+    /// A revision by its ID.
+    Id(Uuid),
+}
+
+impl FromStr for RevisionRef {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("current") {
+            return Ok(RevisionRef::Current);
+        }
+        if let Ok(n) = s.parse::<usize>() {
+            return match n {
+                0 => Ok(RevisionRef::Current),
+                n => Ok(RevisionRef::Back(n)),
+            };
+        }
+
+        Uuid::parse_str(s).map(RevisionRef::Id).map_err(|_| {
+            format!("expected `current`, a number of versions back or a revision ID, got `{s}`")
+        })
+    }
+}
+
+impl Display for RevisionRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RevisionRef::Current => f.write_str("current"),
+            RevisionRef::Back(n) => write!(f, "{n}"),
+            RevisionRef::Id(id) => write!(f, "{id}"),
+        }
+    }
+}
+
+/// Title and content of one version of a post, with a label naming it.
+#[derive(Debug, Clone)]
+pub struct Version {
+    /// Shown in the diff headers, e.g. `current` or `revision 1a2b3c4d, 2026-05-01 12:00`.
+    pub label: String,
+    /// Revision ID; `None` for the current version.
+    pub revision_id: Option<Uuid>,
+    /// Post title.
+    pub title: String,
+    /// Post content.
+    pub content: String,
+}
+
+impl Version {
+    /// Returns the post as it is now.
+    fn current(post: &Post) -> Self {
+        Self {
+            label: "current".into(),
+            revision_id: None,
+            title: post.title.clone(),
+            content: post.content.clone(),
+        }
+    }
+
+    /// Returns an earlier version of the post.
+    fn of(revision: &PostRevision) -> Self {
+        let id = revision.id.to_string();
+        Self {
+            label: format!(
+                "revision {}, {}",
+                &id[..8],
+                revision.edited_at.format("%Y-%m-%d %H:%M")
+            ),
+            revision_id: Some(revision.id),
+            title: revision.title.clone(),
+            content: revision.content.clone(),
+        }
+    }
+}
+
+/// Finds the version `rev` names among the post and its revisions, newest first.
+pub fn resolve(rev: RevisionRef, post: &Post, revisions: &[PostRevision]) -> Result<Version> {
+    let revision = match rev {
+        RevisionRef::Current => return Ok(Version::current(post)),
+        RevisionRef::Back(n) => revisions.get(n - 1),
+        RevisionRef::Id(id) => revisions.iter().find(|revision| revision.id == id),
+    };
+
+    match revision {
+        Some(revision) => Ok(Version::of(revision)),
+        None if revisions.is_empty() => bail!("{}", Msg::NoRevisions { id: post.id }),
+        None => bail!("{}", Msg::RevisionNotFound { rev: &rev }),
+    }
+}
+
+/// Prints a colored unified diff of the title and content of two versions.
+///
+/// Returns whether they differ; nothing is printed when they do not.
+pub fn print_diff(from: &Version, to: &Version) -> bool {
+    let title = print_field_diff("title", from, to, &from.title, &to.title);
+    let content = print_field_diff("content", from, to, &from.content, &to.content);
+    title || content
+}
+
+/// Prints the unified diff of one field, if it changed.
+fn print_field_diff(field: &str, from: &Version, to: &Version, old: &str, new: &str) -> bool {
+    if old == new {
+        return false;
+    }
+
+    println!("{FILE_HEADER}--- {field} ({}){FILE_HEADER:#}", from.label);
+    println!("{FILE_HEADER}+++ {field} ({}){FILE_HEADER:#}", to.label);
+
+    let diff = TextDiff::from_lines(old, new);
+    for hunk in diff.unified_diff().context_radius(CONTEXT_LINES).iter_hunks() {
+        println!("{HUNK_HEADER}{}{HUNK_HEADER:#}", hunk.header());
+        for change in hunk.iter_changes() {
+            let (sign, style) = match change.tag() {
+                ChangeTag::Delete => ('-', REMOVED),
+                ChangeTag::Insert => ('+', ADDED),
+                ChangeTag::Equal => (' ', Style::new()),
+            };
+            let line = change.value();
+            let line = line.strip_suffix('\n').unwrap_or(line);
+            println!("{style}{sign}{line}{style:#}");
+        }
+    }
+
+    true
+}
@@ -0,0 +1,41 @@
+use anyhow::Result;
+use blog_client::BlogClient;
+
+use crate::i18n::Msg;
+use crate::jwt;
+use crate::output;
+use crate::token_store::TokenStore;
+
+/// Loads the stored session into the client.
+///
+/// An expired access token is exchanged for a new one when a refresh token is
+/// stored. When that is impossible the expired token is still used, so that
+/// commands needing authentication fail with a "log in again" hint.
+pub async fn restore(client: &mut BlogClient, tokens: &TokenStore) -> Result<()> {
+    let Some(token) = tokens.load() else {
+        return Ok(());
+    };
+
+    if !jwt::is_expired(&token) {
+        client.set_token(token);
+        return Ok(());
+    }
+
+    if let Some(refresh_token) = tokens.load_refresh() {
+        match client.refresh(&refresh_token).await {
+            Ok(resp) => {
+                if let Some(access_token) = resp.access_token {
+                    // Servers that do not rotate refresh tokens keep the old one valid.
+                    let refresh_token = resp.refresh_token.unwrap_or(refresh_token);
+                    tokens.save(&access_token, Some(&refresh_token))?;
+                    output::status(Msg::SessionRefreshed);
+                    return Ok(());
+                }
+            }
+            Err(err) => output::warn(Msg::RefreshFailed { err: &err }),
+        }
+    }
+
+    client.set_token(token);
+    Ok(())
+}
@@ -0,0 +1,141 @@
+use anyhow::Result;
+use blog_client::models::{DailyViews, PostStats};
+
+use crate::i18n::Msg;
+use crate::output;
+
+/// Largest number of days the server reports on.
+const MAX_DAYS: u32 = 365;
+
+/// Widest sparkline printed; longer periods are summed into this many bars.
+const SPARKLINE_WIDTH: usize = 30;
+
+/// Referring domains and client kinds listed for one post; JSON has them all.
+const TOP_SOURCES: usize = 5;
+
+/// Bars of increasing height used to draw sparklines.
+const BARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Parses a `--since` period such as `30d`, `4w` or plain `30` into days.
+pub fn parse_period(value: &str) -> Result<u32, String> {
+    let value = value.trim();
+    let (number, unit_days) = match value.strip_suffix('w') {
+        Some(weeks) => (weeks, 7),
+        None => (value.strip_suffix('d').unwrap_or(value), 1),
+    };
+
+    let days = number
+        .parse::<u32>()
+        .ok()
+        .and_then(|n| n.checked_mul(unit_days))
+        .filter(|days| (1..=MAX_DAYS).contains(days))
+        .ok_or_else(|| format!("expected a period of 1 to {MAX_DAYS} days, such as 30d or 4w"))?;
+
+    Ok(days)
+}
+
+/// Prints the stats of several posts as a table, or as a JSON array when
+/// `json` is set.
+pub fn print_all(stats: &[PostStats], days: u32, json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(stats)?);
+        return Ok(());
+    }
+
+    if stats.is_empty() {
+        output::info(Msg::NoStats);
+        return Ok(());
+    }
+
+    println!(
+        "{:>7} {:>6} {:>9} {:>8}  {:<width$}  title",
+        "views",
+        "likes",
+        "bookmarks",
+        "comments",
+        "trend",
+        width = SPARKLINE_WIDTH
+    );
+    for post in stats {
+        println!(
+            "{:>7} {:>6} {:>9} {:>8}  {:<width$}  {}",
+            post.views,
+            post.likes,
+            post.bookmarks,
+            post.comments,
+            sparkline(&post.daily_views),
+            post.title,
+            width = SPARKLINE_WIDTH
+        );
+    }
+
+    output::info(Msg::StatsSummary {
+        posts: stats.len(),
+        views: stats.iter().map(|post| post.views).sum(),
+        days,
+    });
+    Ok(())
+}
+
+/// Prints the stats of one post, as JSON when `json` is set.
+pub fn print_one(stats: &PostStats, json: bool) -> Result<()> {
+    if json {
+        println!("{}", serde_json::to_string_pretty(stats)?);
+        return Ok(());
+    }
+
+    output::field("id", stats.post_id);
+    output::field("title", &stats.title);
+    output::field("views", stats.views);
+    output::field("likes", stats.likes);
+    output::field("bookmarks", stats.bookmarks);
+    output::field("comments", stats.comments);
+    output::field("trend", sparkline(&stats.daily_views));
+    let peak = stats.daily_views.iter().max_by_key(|day| day.views);
+    if let Some(peak) = peak.filter(|day| day.views > 0) {
+        output::field("peak", format!("{} ({})", peak.day, peak.views));
+    }
+    if let Some(referrers) = stats.referrers.as_deref().filter(|r| !r.is_empty()) {
+        let referrers = referrers.iter().map(|r| {
+            let domain = r.referrer.as_deref().unwrap_or("direct");
+            (domain, r.views)
+        });
+        output::field("referrers", top_sources(referrers));
+    }
+    if let Some(agents) = stats.user_agents.as_deref().filter(|a| !a.is_empty()) {
+        let agents = agents.iter().map(|a| (a.agent.as_str(), a.views));
+        output::field("clients", top_sources(agents));
+    }
+    Ok(())
+}
+
+/// Joins the first sources with their views, e.g. `example.com 12, direct 3`.
+fn top_sources<'a>(sources: impl Iterator<Item = (&'a str, i64)>) -> String {
+    sources
+        .take(TOP_SOURCES)
+        .map(|(source, views)| format!("{source} {views}"))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Draws daily views as a row of bars scaled to the busiest day, summing
+/// neighbouring days when there are more than fit.
+fn sparkline(days: &[DailyViews]) -> String {
+    if days.is_empty() {
+        return String::new();
+    }
+
+    let per_bar = days.len().div_ceil(SPARKLINE_WIDTH);
+    let bars: Vec<i64> = days
+        .chunks(per_bar)
+        .map(|chunk| chunk.iter().map(|day| day.views).sum())
+        .collect();
+    let max = bars.iter().copied().max().unwrap_or(0).max(1);
+
+    bars.iter()
+        .map(|&views| {
+            let level = (views * (BARS.len() as i64 - 1) + max - 1) / max;
+            BARS[level.clamp(0, BARS.len() as i64 - 1) as usize]
+        })
+        .collect()
+}
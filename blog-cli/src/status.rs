@@ -0,0 +1,119 @@
+use std::time::{Duration, Instant};
+
+use anstream::println;
+use blog_client::error::BlogClientError;
+use blog_client::grpc_client::GrpcClient;
+use blog_client::http_client::HttpClient;
+use blog_client::models::{BuildInfo, Health};
+
+use crate::output;
+
+/// Outcome of probing one server endpoint.
+pub struct Probe {
+    /// Transport name shown in the report.
+    pub transport: &'static str,
+
+    /// Probed server address.
+    pub address: String,
+
+    /// Reported health and round-trip time, or why the probe failed.
+    pub result: Result<(Health, Duration), BlogClientError>,
+
+    /// Build details, if the server reports them.
+    pub build: Option<BuildInfo>,
+}
+
+impl Probe {
+    /// Returns whether the endpoint answered and reported itself healthy.
+    pub fn is_ok(&self) -> bool {
+        matches!(&self.result, Ok((health, _)) if health.is_ok())
+    }
+}
+
+/// Probes the HTTP liveness and readiness endpoints and reads the build details.
+///
+/// The latency is that of the liveness call.
+pub async fn probe_http(address: String) -> Probe {
+    let mut build = None;
+    let result = async {
+        let client = HttpClient::new(address.clone())?;
+
+        let started = Instant::now();
+        let live = client.health().await?;
+        let latency = started.elapsed();
+
+        let mut ready = client.readiness().await?;
+        ready.version = ready.version.or(live.version);
+
+        // Servers predating the version endpoint answer 404; that is not a failure.
+        build = client.version().await.ok();
+        Ok::<_, BlogClientError>((ready, latency))
+    }
+    .await;
+
+    Probe {
+        transport: "HTTP",
+        address,
+        result,
+        build,
+    }
+}
+
+/// Probes the gRPC health service.
+pub async fn probe_grpc(address: String) -> Probe {
+    let result = async {
+        let client = GrpcClient::connect(address.clone()).await?;
+
+        let started = Instant::now();
+        let health = client.health().await?;
+        Ok::<_, BlogClientError>((health, started.elapsed()))
+    }
+    .await;
+
+    Probe {
+        transport: "gRPC",
+        address,
+        result,
+        build: None,
+    }
+}
+
+/// Prints the probe report; suppressed in quiet mode.
+pub fn print(probes: &[Probe]) {
+    if output::is_quiet() {
+        return;
+    }
+
+    for (i, probe) in probes.iter().enumerate() {
+        if i > 0 {
+            println!();
+        }
+        println!("{} {}", probe.transport, probe.address);
+
+        match &probe.result {
+            Ok((health, latency)) => {
+                output::field("status", format!("{} ({} ms)", health.status, latency.as_millis()));
+                if let Some(version) = &health.version {
+                    output::field("version", version);
+                }
+                if let Some(build) = &probe.build {
+                    output::field("commit", &build.git_sha);
+                    if let Some(built) = build.build_timestamp {
+                        output::field("built", built.to_rfc3339());
+                    }
+                    if !build.features.is_empty() {
+                        output::field("features", build.features.join(", "));
+                    }
+                }
+                for (name, check) in &health.checks {
+                    let mut line = format!("{} ({} ms)", check.status, check.latency_ms);
+                    if let Some(error) = &check.error {
+                        line.push_str(&format!(": {error}"));
+                    }
+                    output::field(name, line);
+                }
+            }
+            Err(err) => output::field("status", format!("unreachable: {err}")),
+        }
+    }
+}
@@ -0,0 +1,165 @@
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use anyhow::{Context, Result};
+use keyring::Entry;
+
+use crate::config::{Profile, TokenStorage};
+use crate::i18n::Msg;
+use crate::output;
+
+/// Keychain service name under which tokens are stored.
+const KEYRING_SERVICE: &str = "blog-cli";
+
+/// Per-profile access and refresh token storage.
+///
+/// Uses the OS keychain (macOS Keychain, Windows Credential Manager,
+/// Secret Service) when available and falls back to a file otherwise.
+/// The refresh token, when the server issues one, sits next to the access
+/// token: a `<profile>:refresh` keychain entry or a `.refresh` file.
+pub struct TokenStore {
+    profile: String,
+    path: PathBuf,
+    refresh_path: PathBuf,
+    storage: TokenStorage,
+}
+
+impl TokenStore {
+    /// Creates a token store for the given profile.
+    pub fn new(profile: &Profile) -> Self {
+        Self {
+            profile: profile.name.clone(),
+            path: profile.token_path.clone(),
+            refresh_path: profile.token_path.with_extension("refresh"),
+            storage: profile.token_storage,
+        }
+    }
+
+    /// Loads the stored access token, if any.
+    ///
+    /// Falls back to the token file when the keychain has no entry, which
+    /// covers tokens saved while the keychain was unavailable.
+    pub fn load(&self) -> Option<String> {
+        load_slot(self.entry(&self.profile), &self.path)
+    }
+
+    /// Loads the stored refresh token, if any.
+    pub fn load_refresh(&self) -> Option<String> {
+        load_slot(self.entry(&self.refresh_account()), &self.refresh_path)
+    }
+
+    /// Stores a session, replacing the previous one.
+    ///
+    /// Without a refresh token any previously stored one is removed, so it
+    /// cannot outlive the session it belonged to.
+    pub fn save(&self, token: &str, refresh_token: Option<&str>) -> Result<()> {
+        self.save_slot(&self.profile, &self.path, token, true)?;
+
+        match refresh_token {
+            Some(refresh_token) => {
+                self.save_slot(&self.refresh_account(), &self.refresh_path, refresh_token, false)
+            }
+            None => clear_slot(self.entry(&self.refresh_account()), &self.refresh_path).map(drop),
+        }
+    }
+
+    /// Removes the stored tokens from the keychain and the token files.
+    ///
+    /// Returns whether an access token was present.
+    pub fn clear(&self) -> Result<bool> {
+        clear_slot(self.entry(&self.refresh_account()), &self.refresh_path)?;
+        clear_slot(self.entry(&self.profile), &self.path)
+    }
+
+    /// Stores a token in the keychain, falling back to `path` when it is unavailable.
+    ///
+    /// `warn` reports the fallback to the user.
+    fn save_slot(&self, account: &str, path: &Path, token: &str, warn: bool) -> Result<()> {
+        if let Some(entry) = self.entry(account) {
+            match entry.set_password(token) {
+                Ok(()) => {
+                    // Do not leave a stale plaintext copy behind.
+                    remove_token_file(path)?;
+                    return Ok(());
+                }
+                Err(err) if warn => output::warn(Msg::KeychainUnavailable {
+                    err: &err,
+                    path: &path.display(),
+                }),
+                Err(_) => {}
+            }
+        }
+
+        write_token_file(path, token)
+            .with_context(|| format!("failed to write token to {}", path.display()))
+    }
+
+    /// Returns the keychain account holding the refresh token.
+    fn refresh_account(&self) -> String {
+        format!("{}:refresh", self.profile)
+    }
+
+    /// Returns the keychain entry when keychain storage is enabled and usable.
+    fn entry(&self, account: &str) -> Option<Entry> {
+        match self.storage {
+            TokenStorage::Keyring => Entry::new(KEYRING_SERVICE, account).ok(),
+            TokenStorage::File => None,
+        }
+    }
+}
+
+/// Loads a token from the keychain entry, falling back to the file.
+fn load_slot(entry: Option<Entry>, path: &Path) -> Option<String> {
+    entry
+        .and_then(|entry| entry.get_password().ok())
+        .or_else(|| read_token_file(path))
+}
+
+/// Removes a token from the keychain entry and the file; returns whether one was present.
+fn clear_slot(entry: Option<Entry>, path: &Path) -> Result<bool> {
+    let mut removed = false;
+
+    // Without a usable keychain the token can only be in the file.
+    if let Some(entry) = entry {
+        removed = entry.delete_credential().is_ok();
+    }
+
+    if path.exists() {
+        remove_token_file(path).with_context(|| format!("failed to remove {}", path.display()))?;
+        removed = true;
+    }
+
+    Ok(removed)
+}
+
+/// Reads a token file, ignoring missing or empty files.
+fn read_token_file(path: &Path) -> Option<String> {
+    let token = fs::read_to_string(path).ok()?;
+    let token = token.trim();
+    (!token.is_empty()).then(|| token.to_string())
+}
+
+/// Writes a token file readable only by the current user.
+fn write_token_file(path: &Path, token: &str) -> io::Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let mut file = fs::File::create(path)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+
+    file.write_all(token.as_bytes())
+}
+
+/// Removes a token file if it exists.
+fn remove_token_file(path: &Path) -> io::Result<()> {
+    match fs::remove_file(path) {
+        Err(err) if err.kind() != io::ErrorKind::NotFound => Err(err),
+        _ => Ok(()),
+    }
+}
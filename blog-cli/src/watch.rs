@@ -0,0 +1,69 @@
+use blog_client::models::{BlogEvent, EventKind};
+use clap::ValueEnum;
+
+/// Event types or groups selectable with `watch --filter`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+#[value(rename_all = "snake_case")]
+pub enum EventFilter {
+    /// Any post event.
+    Post,
+    /// Any comment event.
+    Comment,
+    PostCreated,
+    PostUpdated,
+    PostDeleted,
+    CommentAdded,
+}
+
+impl EventFilter {
+    /// Returns whether the event kind is selected by this filter.
+    fn matches(self, kind: &EventKind) -> bool {
+        match self {
+            EventFilter::Post => matches!(
+                kind,
+                EventKind::PostCreated { .. }
+                    | EventKind::PostUpdated { .. }
+                    | EventKind::PostDeleted { .. }
+            ),
+            EventFilter::Comment => matches!(kind, EventKind::CommentAdded { .. }),
+            EventFilter::PostCreated => matches!(kind, EventKind::PostCreated { .. }),
+            EventFilter::PostUpdated => matches!(kind, EventKind::PostUpdated { .. }),
+            EventFilter::PostDeleted => matches!(kind, EventKind::PostDeleted { .. }),
+            EventFilter::CommentAdded => matches!(kind, EventKind::CommentAdded { .. }),
+        }
+    }
+}
+
+/// Returns whether an event passes the filters; no filters selects everything.
+pub fn is_selected(filters: &[EventFilter], event: &BlogEvent) -> bool {
+    filters.is_empty() || filters.iter().any(|filter| filter.matches(&event.kind))
+}
+
+/// Prints a single event as one line.
+pub fn print_event(event: &BlogEvent) {
+    let time = event.at.format("%H:%M:%S");
+    let name = event.kind.name();
+
+    match &event.kind {
+        EventKind::PostCreated { post_id, title, .. }
+        | EventKind::PostUpdated { post_id, title, .. } => {
+            println!("{time} {name:<14} {post_id} {title:?}");
+        }
+        EventKind::PostDeleted { post_id, .. } => {
+            println!("{time} {name:<14} {post_id}");
+        }
+        EventKind::CommentAdded {
+            comment_id,
+            post_id,
+            author_id,
+            parent_id,
+            ..
+        } => {
+            let reply = parent_id
+                .map(|id| format!(" reply to {id}"))
+                .unwrap_or_default();
+            println!("{time} {name:<14} {comment_id} on {post_id} by {author_id}{reply}");
+        }
+        EventKind::Unknown => println!("{time} {name:<14} (event #{})", event.id),
+    }
+}
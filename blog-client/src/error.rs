@@ -1,5 +1,10 @@
+use prost::Message;
 use thiserror::Error;
 
+use crate::blog::ErrorDetails;
+
+pub use blog_core::error::ErrorCode;
+
 /// Blog client errors.
 #[derive(Debug, Error)]
 pub enum BlogClientError {
@@ -7,8 +12,21 @@ pub enum BlogClientError {
     #[error("http error: {0}")]
     Http(#[from] reqwest::Error),
 
+    /// Error response from the HTTP API.
+    #[error("api error {status} ({code}): {message}")]
+    Api {
+        /// HTTP status code.
+        status: u16,
+        /// Stable error code.
+        code: ErrorCode,
+        /// Human-readable message.
+        message: String,
+        /// Server request ID, for matching server logs.
+        request_id: Option<String>,
+    },
+
     /// gRPC status error returned by the server.
-    #[error("grpc status: {0}")]
+    #[error("grpc status {}: {}", .0.code(), .0.message())]
     GrpcStatus(#[from] tonic::Status),
 
     /// gRPC transport-level error.
@@ -19,6 +37,10 @@ pub enum BlogClientError {
     #[error("serialization error: {0}")]
     Serde(#[from] serde_json::Error),
 
+    /// Error decoding a MessagePack or CBOR body.
+    #[error("decode error: {0}")]
+    Decode(String),
+
     /// Unauthorized request or missing authentication.
     #[error("unauthorized: {0}")]
     Unauthorized(String),
@@ -35,7 +57,64 @@ pub enum BlogClientError {
     #[error("invalid state: {0}")]
     InvalidState(String),
 
+    /// Local offline store error.
+    #[error("storage error: {0}")]
+    Storage(String),
+
     /// Other client error.
     #[error("other error: {0}")]
     Other(#[from] anyhow::Error),
 }
+
+impl BlogClientError {
+    /// Returns whether the request was rejected for missing or invalid credentials.
+    pub fn is_unauthorized(&self) -> bool {
+        match self {
+            BlogClientError::Unauthorized(_) => true,
+            BlogClientError::Api { status, .. } => *status == 401,
+            BlogClientError::Http(err) => err.status() == Some(reqwest::StatusCode::UNAUTHORIZED),
+            BlogClientError::GrpcStatus(status) => status.code() == tonic::Code::Unauthenticated,
+            _ => false,
+        }
+    }
+
+    /// Returns whether retrying later may succeed: the server could not be
+    /// reached or failed, rather than rejecting the request.
+    pub fn is_transient(&self) -> bool {
+        match self {
+            BlogClientError::Http(err) => err.status().is_none_or(|status| status.is_server_error()),
+            BlogClientError::Api { status, .. } => *status >= 500,
+            BlogClientError::GrpcStatus(status) => matches!(
+                status.code(),
+                tonic::Code::Unavailable
+                    | tonic::Code::Unknown
+                    | tonic::Code::Internal
+                    | tonic::Code::Aborted
+                    | tonic::Code::Cancelled
+                    | tonic::Code::DeadlineExceeded
+            ),
+            BlogClientError::GrpcTransport(_) => true,
+            _ => false,
+        }
+    }
+
+    /// Returns the server's error code, if the server reported one.
+    pub fn code(&self) -> Option<ErrorCode> {
+        match self {
+            BlogClientError::Api { code, .. } => Some(*code),
+            BlogClientError::GrpcStatus(status) => ErrorDetails::decode(status.details())
+                .ok()
+                .filter(|details| !details.code.is_empty())
+                .map(|details| ErrorCode::parse(&details.code)),
+            _ => None,
+        }
+    }
+
+    /// Returns the server request ID of a failed HTTP request, if known.
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            BlogClientError::Api { request_id, .. } => request_id.as_deref(),
+            _ => None,
+        }
+    }
+}
@@ -1,28 +1,72 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use crate::blog::{
     blog_service_client::BlogServiceClient,
+    AddCommentRequest, Comment as ProtoComment, ListTagsRequest, TagList, DeleteCommentRequest, ListCommentsRequest,
     CreatePostRequest, GetPostRequest, UpdatePostRequest, ListPostRequest,
-    ListPostsResponse as ProtoListPostsResponse, Post as ProtoPost,
-    RegisterRequest, LoginRequest, LoginResponse, RegisterResponse,
+    ListCommentsResponse as ProtoListCommentsResponse, ListPostsResponse as ProtoListPostsResponse,
+    Post as ProtoPost, Profile as ProtoProfile, PublicUser as ProtoPublicUser,
+    GetUserRequest, GetProfileRequest, UpdateProfileRequest,
+    SearchPostsRequest, SearchPostsResponse as ProtoSearchPostsResponse, StreamEventsRequest,
+    ListRevisionsRequest, RevisionRequest, Revision as ProtoRevision,
+    RegisterRequest, LoginRequest, LoginResponse, RefreshTokenRequest, RegisterResponse,
 };
 use crate::error::BlogClientError;
-use crate::models::{AuthResponse, Post, User};
+use crate::models::{
+    AuthResponse, Author, BlogEvent, Comment, Health, Page, Post, PostRevision, Profile,
+    PublicUser, SearchResult, Tag, User,
+};
+use futures_util::{Stream, StreamExt};
 use tonic::metadata::MetadataValue;
 use tonic::transport::Channel;
 use tonic::Request;
+use tonic_health::pb::health_check_response::ServingStatus;
+use tonic_health::pb::health_client::HealthClient;
+use tonic_health::pb::HealthCheckRequest;
 use uuid::Uuid;
 
 /// gRPC client implementation for the Blog service.
 #[derive(Clone)]
 pub struct GrpcClient {
     inner: BlogServiceClient<Channel>,
+    health: HealthClient<Channel>,
 }
 
+/// Name the server registers the blog service under in the gRPC health service.
+const BLOG_SERVICE_NAME: &str = "blog.BlogService";
+
 impl GrpcClient {
     /// Connects to a gRPC server and creates a new client.
     pub async fn connect(addr: String) -> Result<Self, BlogClientError> {
-        let inner = BlogServiceClient::connect(addr).await?;
-        Ok(Self { inner })
+        let channel = Channel::from_shared(addr)
+            .map_err(|e| BlogClientError::InvalidRequest(format!("invalid server address: {e}")))?
+            .connect()
+            .await?;
+
+        Ok(Self {
+            inner: BlogServiceClient::new(channel.clone()),
+            health: HealthClient::new(channel),
+        })
+    }
+
+    /// Asks the standard gRPC health service whether the blog service is serving.
+    pub async fn health(&self) -> Result<Health, BlogClientError> {
+        let req = HealthCheckRequest {
+            service: BLOG_SERVICE_NAME.to_string(),
+        };
+        let resp = self.health.clone().check(Request::new(req)).await?;
+
+        let status = match ServingStatus::try_from(resp.into_inner().status) {
+            Ok(ServingStatus::Serving) => "ok".to_string(),
+            Ok(ServingStatus::NotServing) => "unavailable".to_string(),
+            Ok(other) => other.as_str_name().to_lowercase(),
+            Err(_) => "unknown".to_string(),
+        };
+
+        Ok(Health {
+            status,
+            version: None,
+            checks: Default::default(),
+        })
     }
 
     /// Registers a new user.
@@ -53,6 +97,18 @@ impl GrpcClient {
         Ok(resp.into_inner().into())
     }
 
+    /// Exchanges a refresh token for a new access token.
+    ///
+    /// The given refresh token is revoked; use the one in the response next time.
+    pub async fn refresh(&self, refresh_token: &str) -> Result<AuthResponse, BlogClientError> {
+        let req = RefreshTokenRequest {
+            refresh_token: refresh_token.to_string(),
+        };
+
+        let resp = self.inner.clone().refresh_token(Request::new(req)).await?;
+        Ok(resp.into_inner().into())
+    }
+
     /// Creates a new post.
     ///
     /// Requires a valid JWT token.
@@ -61,16 +117,18 @@ impl GrpcClient {
         token: &str,
         title: &str,
         content: &str,
+        tags: &[String],
     ) -> Result<Post, BlogClientError> {
         let payload = CreatePostRequest {
             title: title.to_string(),
             content: content.to_string(),
+            tags: tags.to_vec(),
         };
 
         let req = with_auth(Request::new(payload), token)?;
         let resp = self.inner.clone().create_post(req).await?;
 
-        Ok(Self::map_post(resp.into_inner().post.unwrap()))
+        Self::map_post(resp.into_inner().post.unwrap())
     }
 
     /// Returns a post by its ID.
@@ -83,10 +141,10 @@ impl GrpcClient {
         let resp = self.inner.clone().get_post(req).await?;
         let post = resp.into_inner().post.unwrap();
 
-        Ok(Self::map_post(post))
+        Self::map_post(post)
     }
 
-    /// Updates an existing post.
+    /// Updates an existing post; `None` tags keep the current ones.
     ///
     /// Requires a valid JWT token.
     pub async fn update_post(
@@ -95,17 +153,23 @@ impl GrpcClient {
         id: Uuid,
         title: &str,
         content: &str,
+        tags: Option<&[String]>,
+        expected_updated_at: Option<DateTime<Utc>>,
     ) -> Result<Post, BlogClientError> {
         let payload = UpdatePostRequest {
             id: id.to_string(),
             title: title.to_string(),
             content: content.to_string(),
+            tags: tags.map(|tags| TagList { names: tags.to_vec() }),
+            expected_updated_at: expected_updated_at
+                .map(|at| at.to_rfc3339())
+                .unwrap_or_default(),
         };
 
         let req = with_auth(Request::new(payload), token)?;
         let resp = self.inner.clone().update_post(req).await?;
 
-        Ok(Self::map_post(resp.into_inner().post.unwrap()))
+        Self::map_post(resp.into_inner().post.unwrap())
     }
 
     /// Deletes a post by its ID.
@@ -120,32 +184,391 @@ impl GrpcClient {
         Ok(())
     }
 
-    /// Lists posts of the authenticated user.
+    /// Lists earlier versions of a post, newest first.
+    ///
+    /// Requires a valid JWT token.
+    pub async fn list_revisions(
+        &self,
+        token: &str,
+        post_id: Uuid,
+    ) -> Result<Vec<PostRevision>, BlogClientError> {
+        let payload = ListRevisionsRequest { post_id: post_id.to_string() };
+
+        let req = with_auth(Request::new(payload), token)?;
+        let resp = self.inner.clone().list_revisions(req).await?;
+
+        resp.into_inner()
+            .revisions
+            .into_iter()
+            .map(Self::map_revision)
+            .collect()
+    }
+
+    /// Returns one earlier version of a post.
+    ///
+    /// Requires a valid JWT token.
+    pub async fn get_revision(
+        &self,
+        token: &str,
+        post_id: Uuid,
+        revision_id: Uuid,
+    ) -> Result<PostRevision, BlogClientError> {
+        let payload = RevisionRequest {
+            post_id: post_id.to_string(),
+            revision_id: revision_id.to_string(),
+        };
+
+        let req = with_auth(Request::new(payload), token)?;
+        let resp = self.inner.clone().get_revision(req).await?;
+        let revision = resp
+            .into_inner()
+            .revision
+            .ok_or_else(|| BlogClientError::InvalidState("server returned no revision".into()))?;
+
+        Self::map_revision(revision)
+    }
+
+    /// Makes an earlier version of a post current again.
+    ///
+    /// Requires a valid JWT token of the post's author.
+    pub async fn restore_revision(
+        &self,
+        token: &str,
+        post_id: Uuid,
+        revision_id: Uuid,
+    ) -> Result<Post, BlogClientError> {
+        let payload = RevisionRequest {
+            post_id: post_id.to_string(),
+            revision_id: revision_id.to_string(),
+        };
+
+        let req = with_auth(Request::new(payload), token)?;
+        let resp = self.inner.clone().restore_revision(req).await?;
+        let post = resp
+            .into_inner()
+            .post
+            .ok_or_else(|| BlogClientError::InvalidState("server returned no post".into()))?;
+
+        Self::map_post(post)
+    }
+
+    /// Lists posts of the authenticated user, optionally filtered by tag.
     ///
     /// Requires a valid JWT token.
-    pub async fn list_posts(&self, token: &str) -> Result<Vec<Post>, BlogClientError> {
-        let payload = ListPostRequest {};
+    pub async fn list_posts(
+        &self,
+        token: &str,
+        limit: u32,
+        offset: u32,
+        tag: Option<&str>,
+    ) -> Result<Page<Post>, BlogClientError> {
+        let payload = ListPostRequest {
+            limit,
+            offset,
+            tag: tag.unwrap_or_default().to_string(),
+        };
 
         let req = with_auth(Request::new(payload), token)?;
         let resp = self.inner.clone().list_posts(req).await?;
 
-        let ProtoListPostsResponse { posts } = resp.into_inner();
-        Ok(posts.into_iter().map(Self::map_post).collect())
+        let ProtoListPostsResponse { posts, total } = resp.into_inner();
+        Ok(Page {
+            items: posts.into_iter().map(Self::map_post).collect::<Result<_, _>>()?,
+            total,
+            offset,
+        })
+    }
+
+    /// Searches posts of all authors, best matches first, optionally only
+    /// those tagged `tag`.
+    pub async fn search_posts(
+        &self,
+        query: &str,
+        limit: u32,
+        offset: u32,
+        tag: Option<&str>,
+    ) -> Result<Page<SearchResult>, BlogClientError> {
+        let payload = SearchPostsRequest {
+            query: query.to_string(),
+            limit,
+            offset,
+            tag: tag.unwrap_or_default().to_string(),
+        };
+
+        let resp = self.inner.clone().search_posts(Request::new(payload)).await?;
+
+        let ProtoSearchPostsResponse { results, total } = resp.into_inner();
+        let items = results
+            .into_iter()
+            .map(|result| {
+                let post = result
+                    .post
+                    .ok_or_else(|| BlogClientError::InvalidState("server returned no post".into()))?;
+                Ok(SearchResult {
+                    post: Self::map_post(post)?,
+                    rank: result.rank,
+                    snippet: result.snippet,
+                })
+            })
+            .collect::<Result<_, BlogClientError>>()?;
+
+        Ok(Page { items, total, offset })
+    }
+
+    /// Lists the tags used on the authenticated user's posts.
+    ///
+    /// Requires a valid JWT token.
+    pub async fn list_tags(&self, token: &str) -> Result<Vec<Tag>, BlogClientError> {
+        let req = with_auth(Request::new(ListTagsRequest {}), token)?;
+        let resp = self.inner.clone().list_tags(req).await?;
+
+        Ok(resp
+            .into_inner()
+            .tags
+            .into_iter()
+            .map(|tag| Tag {
+                name: tag.name,
+                post_count: tag.post_count,
+            })
+            .collect())
+    }
+
+    /// Lists comments on a post, oldest first.
+    ///
+    /// Requires a valid JWT token.
+    pub async fn list_comments(
+        &self,
+        token: &str,
+        post_id: Uuid,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Page<Comment>, BlogClientError> {
+        let payload = ListCommentsRequest {
+            post_id: post_id.to_string(),
+            limit,
+            offset,
+        };
+
+        let req = with_auth(Request::new(payload), token)?;
+        let resp = self.inner.clone().list_comments(req).await?;
+
+        let ProtoListCommentsResponse { comments, total } = resp.into_inner();
+        Ok(Page {
+            items: comments.into_iter().map(Self::map_comment).collect::<Result<_, _>>()?,
+            total,
+            offset,
+        })
+    }
+
+    /// Adds a comment to a post, optionally as a reply.
+    ///
+    /// Requires a valid JWT token.
+    pub async fn add_comment(
+        &self,
+        token: &str,
+        post_id: Uuid,
+        content: &str,
+        parent_id: Option<Uuid>,
+    ) -> Result<Comment, BlogClientError> {
+        let payload = AddCommentRequest {
+            post_id: post_id.to_string(),
+            content: content.to_string(),
+            parent_id: parent_id.map(|id| id.to_string()).unwrap_or_default(),
+        };
+
+        let req = with_auth(Request::new(payload), token)?;
+        let resp = self.inner.clone().add_comment(req).await?;
+        let comment = resp
+            .into_inner()
+            .comment
+            .ok_or_else(|| BlogClientError::InvalidState("server returned no comment".into()))?;
+
+        Self::map_comment(comment)
+    }
+
+    /// Deletes a comment by its ID.
+    ///
+    /// Requires a valid JWT token.
+    pub async fn delete_comment(&self, token: &str, id: Uuid) -> Result<(), BlogClientError> {
+        let payload = DeleteCommentRequest { id: id.to_string() };
+
+        let req = with_auth(Request::new(payload), token)?;
+        let _ = self.inner.clone().delete_comment(req).await?;
+
+        Ok(())
+    }
+
+    /// Returns another user by their ID.
+    ///
+    /// Requires a valid JWT token.
+    pub async fn get_user(&self, token: &str, id: Uuid) -> Result<PublicUser, BlogClientError> {
+        let payload = GetUserRequest { id: id.to_string() };
+
+        let req = with_auth(Request::new(payload), token)?;
+        let resp = self.inner.clone().get_user(req).await?;
+        let user = resp
+            .into_inner()
+            .user
+            .ok_or_else(|| BlogClientError::InvalidState("server returned no user".into()))?;
+
+        Self::map_public_user(user)
+    }
+
+    /// Returns the profile of the authenticated user.
+    ///
+    /// Requires a valid JWT token.
+    pub async fn get_profile(&self, token: &str) -> Result<Profile, BlogClientError> {
+        let req = with_auth(Request::new(GetProfileRequest {}), token)?;
+        let resp = self.inner.clone().get_profile(req).await?;
+        let profile = resp
+            .into_inner()
+            .profile
+            .ok_or_else(|| BlogClientError::InvalidState("server returned no profile".into()))?;
+
+        Self::map_profile(profile)
+    }
+
+    /// Updates the profile of the authenticated user; `None` fields keep their
+    /// current value and an empty `bio` clears it.
+    ///
+    /// Requires a valid JWT token.
+    pub async fn update_profile(
+        &self,
+        token: &str,
+        username: Option<&str>,
+        email: Option<&str>,
+        bio: Option<&str>,
+    ) -> Result<Profile, BlogClientError> {
+        let payload = UpdateProfileRequest {
+            username: username.map(str::to_string),
+            email: email.map(str::to_string),
+            bio: bio.map(str::to_string),
+        };
+
+        let req = with_auth(Request::new(payload), token)?;
+        let resp = self.inner.clone().update_profile(req).await?;
+        let profile = resp
+            .into_inner()
+            .profile
+            .ok_or_else(|| BlogClientError::InvalidState("server returned no profile".into()))?;
+
+        Self::map_profile(profile)
+    }
+
+    /// Opens the server's event stream, first replaying the events after
+    /// `last_event_id` the server still holds.
+    ///
+    /// Requires a valid JWT token.
+    pub async fn events(
+        &self,
+        token: &str,
+        last_event_id: Option<u64>,
+    ) -> Result<impl Stream<Item = Result<BlogEvent, BlogClientError>> + use<>, BlogClientError> {
+        let payload = StreamEventsRequest {
+            last_event_id: last_event_id.unwrap_or_default(),
+        };
+
+        let req = with_auth(Request::new(payload), token)?;
+        let resp = self.inner.clone().stream_events(req).await?;
+
+        Ok(resp.into_inner().map(|item| Ok(serde_json::from_str(&item?.data)?)))
+    }
+
+    /// Maps a protobuf public user into a client model.
+    fn map_public_user(proto: ProtoPublicUser) -> Result<PublicUser, BlogClientError> {
+        Ok(PublicUser {
+            id: parse_uuid(&proto.id)?,
+            username: proto.username,
+            bio: (!proto.bio.is_empty()).then_some(proto.bio),
+            created_at: parse_timestamp(&proto.created_at)?,
+        })
+    }
+
+    /// Maps a protobuf profile into a client model.
+    fn map_profile(proto: ProtoProfile) -> Result<Profile, BlogClientError> {
+        Ok(Profile {
+            id: parse_uuid(&proto.id)?,
+            username: proto.username,
+            email: proto.email,
+            bio: (!proto.bio.is_empty()).then_some(proto.bio),
+            role: proto.role.parse().map_err(BlogClientError::InvalidState)?,
+            created_at: parse_timestamp(&proto.created_at)?,
+        })
+    }
+
+    /// Maps a protobuf revision into a client revision model.
+    fn map_revision(proto: ProtoRevision) -> Result<PostRevision, BlogClientError> {
+        Ok(PostRevision {
+            id: parse_uuid(&proto.id)?,
+            post_id: parse_uuid(&proto.post_id)?,
+            title: proto.title,
+            content: proto.content,
+            tags: proto.tags,
+            edited_at: parse_timestamp(&proto.edited_at)?,
+            created_at: parse_timestamp(&proto.created_at)?,
+        })
+    }
+
+    /// Maps a protobuf comment into a client comment model.
+    fn map_comment(proto: ProtoComment) -> Result<Comment, BlogClientError> {
+        let parse_id = |value: &str| {
+            Uuid::parse_str(value)
+                .map_err(|e| BlogClientError::InvalidState(format!("invalid id {value:?}: {e}")))
+        };
+
+        Ok(Comment {
+            id: parse_id(&proto.id)?,
+            post_id: parse_id(&proto.post_id)?,
+            author_id: parse_id(&proto.author_id)?,
+            parent_id: match proto.parent_id.as_str() {
+                "" => None,
+                id => Some(parse_id(id)?),
+            },
+            content: proto.content,
+            status: proto.status.parse().map_err(BlogClientError::InvalidState)?,
+            created_at: DateTime::parse_from_rfc3339(&proto.created_at)
+                .map_err(|e| BlogClientError::InvalidState(format!("invalid timestamp: {e}")))?
+                .with_timezone(&Utc),
+        })
     }
 
     /// Maps a protobuf post into a client post model.
-    fn map_post(proto: ProtoPost) -> Post {
-        Post {
-            id: Uuid::parse_str(&proto.id).expect("invalid post id"),
+    fn map_post(proto: ProtoPost) -> Result<Post, BlogClientError> {
+        Ok(Post {
+            id: parse_uuid(&proto.id)?,
+            author_id: parse_uuid(&proto.author_id)?,
+            author: proto.author.and_then(|author| {
+                Some(Author {
+                    id: Uuid::parse_str(&author.id).ok()?,
+                    username: author.username,
+                })
+            }),
             title: proto.title,
+            slug: proto.slug,
             content: proto.content,
-            author_id: Uuid::parse_str(&proto.author_id).unwrap(),
-            created_at: Utc::now(), // FIXME: add created_at to proto
-            updated_at: None,
-        }
+            tags: proto.tags,
+            visibility: proto.visibility.parse().map_err(BlogClientError::InvalidState)?,
+            created_at: parse_timestamp(&proto.created_at)?,
+            updated_at: parse_timestamp(&proto.updated_at)?,
+            deleted_at: None,
+            locale: None,
+        })
     }
 }
 
+/// Parses an ID sent by the server.
+fn parse_uuid(value: &str) -> Result<Uuid, BlogClientError> {
+    Uuid::parse_str(value)
+        .map_err(|e| BlogClientError::InvalidState(format!("invalid id {value:?}: {e}")))
+}
+
+/// Parses an RFC 3339 timestamp sent by the server.
+fn parse_timestamp(value: &str) -> Result<DateTime<Utc>, BlogClientError> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|ts| ts.with_timezone(&Utc))
+        .map_err(|e| BlogClientError::InvalidState(format!("invalid timestamp: {e}")))
+}
+
 /// Attaches the `authorization` metadata header to a gRPC request.
 fn with_auth<T>(mut req: Request<T>, token: &str) -> Result<Request<T>, tonic::Status> {
     let value = format!("Bearer {}", token);
@@ -166,6 +589,11 @@ impl From<LoginResponse> for AuthResponse {
             } else {
                 Some(proto.token)
             },
+            refresh_token: if proto.refresh_token.is_empty() {
+                None
+            } else {
+                Some(proto.refresh_token)
+            },
             user: None,
         }
     }
@@ -178,6 +606,7 @@ impl From<RegisterResponse> for AuthResponse {
 
         Self {
             access_token: None,
+            refresh_token: None,
             user: Some(User {
                 id: Uuid::parse_str(user.id.as_str()).expect("invalid user id"),
                 username: user.username,
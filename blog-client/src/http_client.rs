@@ -1,12 +1,74 @@
-use crate::error::BlogClientError;
-use crate::models::{AuthResponse, Post};
-use reqwest::Client;
+use crate::error::{BlogClientError, ErrorCode};
+use blog_core::auth::{LoginRequest, RegisterRequest};
+use blog_core::comment::CommentRequest;
+use blog_core::error::ErrorBody;
+use blog_core::post::PostRequest;
+use chrono::{DateTime, Utc};
+use crate::models::{
+    AuthResponse, BlogEvent, BuildInfo, Comment, Health, ImportSummary, Media, MediaUpload, Page,
+    Post, PostRevision, PostStats, SearchResult, Tag, UserAccount,
+};
+use crate::upload::{self, ProgressReporter};
+use eventsource_stream::{EventStreamError, Eventsource};
+use futures_util::{Stream, StreamExt};
+use reqwest::multipart::{Form, Part};
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use std::time::{Duration, Instant};
+use tracing::{debug, trace, warn};
 use uuid::Uuid;
 
+/// Path the server mounts the current API version under by default.
+pub const DEFAULT_API_PREFIX: &str = "/api/v1";
+
+/// Multipart field the server reads uploaded media from.
+const MEDIA_FILE_FIELD: &str = "file";
+
+/// Largest chunk sent in one request of a chunked upload, so a dropped
+/// connection loses little; the server may ask for smaller ones.
+const UPLOAD_CHUNK_BYTES: u64 = 1024 * 1024;
+
+/// Times a chunk is retried after consecutive failures before giving up.
+const MAX_CHUNK_RETRIES: u32 = 5;
+
+/// Wait before the first retry of a failed chunk.
+const CHUNK_RETRY_DELAY: Duration = Duration::from_millis(500);
+
+/// Longest wait between retries of a failed chunk.
+const MAX_CHUNK_RETRY_DELAY: Duration = Duration::from_secs(15);
+
+/// Body encoding requested from the server on post endpoints.
+///
+/// Binary formats cut payload size; the server answers in JSON when it
+/// cannot honour the request.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum WireFormat {
+    /// JSON, understood by every server version.
+    #[default]
+    Json,
+    /// MessagePack.
+    MsgPack,
+    /// CBOR.
+    Cbor,
+}
+
+impl WireFormat {
+    /// Returns the media type of bodies in this format.
+    pub fn mime(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::MsgPack => "application/msgpack",
+            Self::Cbor => "application/cbor",
+        }
+    }
+}
+
 /// HTTP transport implementation for the blog client.
 #[derive(Clone)]
 pub struct HttpClient {
     base_url: String,
+    api_prefix: String,
+    format: WireFormat,
     client: Client,
 }
 
@@ -14,16 +76,70 @@ impl HttpClient {
     /// Creates a new HTTP client with the given base URL.
     pub fn new(base_url: String) -> Result<Self, BlogClientError> {
         Ok(Self {
-            base_url,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            api_prefix: DEFAULT_API_PREFIX.to_string(),
+            format: WireFormat::default(),
             client: Client::new(),
         })
     }
 
-    /// Builds a full URL from a relative path.
+    /// Sets the path the API is mounted under, e.g. `/blog/api/v1`.
+    pub fn with_api_prefix(mut self, api_prefix: &str) -> Self {
+        self.api_prefix = format!("/{}", api_prefix.trim_matches('/'));
+        self
+    }
+
+    /// Sets the body encoding requested on post endpoints, JSON by default.
+    pub fn with_format(mut self, format: WireFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Asks for post bodies in the configured [`WireFormat`].
+    fn accept(&self, request: RequestBuilder) -> RequestBuilder {
+        match self.format {
+            WireFormat::Json => request,
+            format => request.header(
+                reqwest::header::ACCEPT,
+                format!("{}, application/json;q=0.5", format.mime()),
+            ),
+        }
+    }
+
+    /// Builds a full URL from a path relative to the server root.
     fn url(&self, path: &str) -> String {
         format!("{}{}", self.base_url, path)
     }
 
+    /// Builds a full URL from a path relative to the API prefix.
+    fn api_url(&self, path: &str) -> String {
+        format!("{}{}{}", self.base_url, self.api_prefix, path)
+    }
+
+    /// Calls the liveness probe.
+    pub async fn health(&self) -> Result<Health, BlogClientError> {
+        let resp = self.client.get(self.url("/healthz")).send_logged().await?;
+        Ok(resp.json().await?)
+    }
+
+    /// Calls the readiness probe.
+    ///
+    /// An unavailable server still reports its dependency health rather than an error.
+    pub async fn readiness(&self) -> Result<Health, BlogClientError> {
+        let resp = self.client.get(self.url("/readyz")).send_traced().await?;
+        if resp.status() == StatusCode::SERVICE_UNAVAILABLE {
+            return Ok(resp.json().await?);
+        }
+
+        Ok(resp.error_for_status()?.json().await?)
+    }
+
+    /// Fetches the server's version, commit, build time and features.
+    pub async fn version(&self) -> Result<BuildInfo, BlogClientError> {
+        let resp = self.client.get(self.api_url("/public/version")).send_logged().await?;
+        read_body(resp).await
+    }
+
     /// Registers a new user.
     pub async fn register(
         &self,
@@ -31,37 +147,49 @@ impl HttpClient {
         email: &str,
         password: &str,
     ) -> Result<AuthResponse, BlogClientError> {
-        let body = serde_json::json!({
-            "username": username,
-            "email": email,
-            "password": password,
-        });
+        let body = RegisterRequest {
+            username: username.to_string(),
+            email: email.to_string(),
+            password: password.to_string(),
+        };
 
         let resp = self
             .client
-            .post(self.url("/api/public/auth/register"))
+            .post(self.api_url("/public/auth/register"))
             .json(&body)
-            .send()
-            .await?
-            .error_for_status()?;
+            .send_logged()
+            .await?;
 
         Ok(resp.json().await?)
     }
 
     /// Authenticates a user and returns an auth response.
     pub async fn login(&self, email: &str, password: &str) -> Result<AuthResponse, BlogClientError> {
-        let body = serde_json::json!({
-            "email": email,
-            "password": password,
-        });
+        let body = LoginRequest {
+            email: email.to_string(),
+            password: password.to_string(),
+        };
+
+        let resp = self
+            .client
+            .post(self.api_url("/public/auth/login"))
+            .json(&body)
+            .send_logged()
+            .await?;
+
+        Ok(resp.json().await?)
+    }
+
+    /// Exchanges a refresh token for a new access token.
+    pub async fn refresh(&self, refresh_token: &str) -> Result<AuthResponse, BlogClientError> {
+        let body = serde_json::json!({ "refresh_token": refresh_token });
 
         let resp = self
             .client
-            .post(self.url("/api/public/auth/login"))
+            .post(self.api_url("/public/auth/refresh"))
             .json(&body)
-            .send()
-            .await?
-            .error_for_status()?;
+            .send_logged()
+            .await?;
 
         Ok(resp.json().await?)
     }
@@ -74,22 +202,23 @@ impl HttpClient {
         token: &str,
         title: &str,
         content: &str,
+        tags: &[String],
     ) -> Result<Post, BlogClientError> {
-        let body = serde_json::json!({
-            "title": title,
-            "content": content,
-        });
+        let body = PostRequest {
+            title: title.to_string(),
+            content: content.to_string(),
+            tags: Some(tags.to_vec()),
+            ..Default::default()
+        };
 
         let resp = self
-            .client
-            .post(self.url("/api/protected/posts"))
+            .accept(self.client.post(self.api_url("/protected/posts")))
             .bearer_auth(token)
             .json(&body)
-            .send()
-            .await?
-            .error_for_status()?;
+            .send_logged()
+            .await?;
 
-        Ok(resp.json().await?)
+        read_body(resp).await
     }
 
     /// Returns a post by its ID.
@@ -97,17 +226,15 @@ impl HttpClient {
     /// Requires a valid JWT token.
     pub async fn get_post(&self, token: &str, id: Uuid) -> Result<Post, BlogClientError> {
         let resp = self
-            .client
-            .get(self.url(&format!("/api/protected/posts/{id}")))
+            .accept(self.client.get(self.api_url(&format!("/protected/posts/{id}"))))
             .bearer_auth(token)
-            .send()
-            .await?
-            .error_for_status()?;
+            .send_logged()
+            .await?;
 
-        Ok(resp.json().await?)
+        read_body(resp).await
     }
 
-    /// Updates an existing post.
+    /// Updates an existing post; `None` tags keep the current ones.
     ///
     /// Requires a valid JWT token.
     pub async fn update_post(
@@ -116,22 +243,25 @@ impl HttpClient {
         id: Uuid,
         title: &str,
         content: &str,
+        tags: Option<&[String]>,
+        expected_updated_at: Option<DateTime<Utc>>,
     ) -> Result<Post, BlogClientError> {
-        let body = serde_json::json!({
-            "title": title,
-            "content": content,
-        });
+        let body = PostRequest {
+            title: title.to_string(),
+            content: content.to_string(),
+            tags: tags.map(<[String]>::to_vec),
+            expected_updated_at,
+            ..Default::default()
+        };
 
         let resp = self
-            .client
-            .put(self.url(&format!("/api/protected/posts/{id}")))
+            .accept(self.client.put(self.api_url(&format!("/protected/posts/{id}"))))
             .bearer_auth(token)
             .json(&body)
-            .send()
-            .await?
-            .error_for_status()?;
+            .send_logged()
+            .await?;
 
-        Ok(resp.json().await?)
+        read_body(resp).await
     }
 
     /// Deletes a post by its ID.
@@ -139,16 +269,72 @@ impl HttpClient {
     /// Requires a valid JWT token.
     pub async fn delete_post(&self, token: &str, id: Uuid) -> Result<(), BlogClientError> {
         self.client
-            .delete(self.url(&format!("/api/protected/posts/{id}")))
+            .delete(self.api_url(&format!("/protected/posts/{id}")))
             .bearer_auth(token)
-            .send()
-            .await?
-            .error_for_status()?;
+            .send_logged()
+            .await?;
 
         Ok(())
     }
 
-    /// Lists posts of the authenticated user.
+    /// Lists earlier versions of a post, newest first.
+    ///
+    /// Requires a valid JWT token.
+    pub async fn list_revisions(
+        &self,
+        token: &str,
+        post_id: Uuid,
+    ) -> Result<Vec<PostRevision>, BlogClientError> {
+        let resp = self
+            .client
+            .get(self.api_url(&format!("/protected/posts/{post_id}/revisions")))
+            .bearer_auth(token)
+            .send_logged()
+            .await?;
+
+        Ok(resp.json().await?)
+    }
+
+    /// Returns one earlier version of a post.
+    ///
+    /// Requires a valid JWT token.
+    pub async fn get_revision(
+        &self,
+        token: &str,
+        post_id: Uuid,
+        revision_id: Uuid,
+    ) -> Result<PostRevision, BlogClientError> {
+        let url = format!("/protected/posts/{post_id}/revisions/{revision_id}");
+        let resp = self
+            .client
+            .get(self.api_url(&url))
+            .bearer_auth(token)
+            .send_logged()
+            .await?;
+
+        Ok(resp.json().await?)
+    }
+
+    /// Makes an earlier version of a post current again.
+    ///
+    /// Requires a valid JWT token of the post's author.
+    pub async fn restore_revision(
+        &self,
+        token: &str,
+        post_id: Uuid,
+        revision_id: Uuid,
+    ) -> Result<Post, BlogClientError> {
+        let url = format!("/protected/posts/{post_id}/revisions/{revision_id}/restore");
+        let resp = self
+            .accept(self.client.post(self.api_url(&url)))
+            .bearer_auth(token)
+            .send_logged()
+            .await?;
+
+        read_body(resp).await
+    }
+
+    /// Lists posts of the authenticated user, optionally filtered by tag.
     ///
     /// Requires a valid JWT token.
     pub async fn list_posts(
@@ -156,16 +342,626 @@ impl HttpClient {
         token: &str,
         limit: u32,
         offset: u32,
-    ) -> Result<Vec<Post>, BlogClientError> {
+        tag: Option<&str>,
+    ) -> Result<Page<Post>, BlogClientError> {
+        let mut request = self
+            .accept(self.client.get(self.api_url("/protected/posts")))
+            .bearer_auth(token)
+            .query(&[("limit", limit), ("offset", offset)]);
+
+        if let Some(tag) = tag {
+            request = request.query(&[("tag", tag)]);
+        }
+
+        let resp = request.send_logged().await?;
+
+        read_page(resp, offset).await
+    }
+
+    /// Searches posts of all authors, best matches first, optionally only
+    /// those tagged `tag`.
+    pub async fn search_posts(
+        &self,
+        query: &str,
+        limit: u32,
+        offset: u32,
+        tag: Option<&str>,
+    ) -> Result<Page<SearchResult>, BlogClientError> {
+        let mut request = self
+            .accept(self.client.get(self.api_url("/public/posts/search")))
+            .query(&[("q", query)])
+            .query(&[("limit", limit), ("offset", offset)]);
+
+        if let Some(tag) = tag {
+            request = request.query(&[("tag", tag)]);
+        }
+
+        let resp = request.send_logged().await?;
+
+        read_page(resp, offset).await
+    }
+
+    /// Lists the tags used on the authenticated user's posts.
+    ///
+    /// Requires a valid JWT token.
+    pub async fn list_tags(&self, token: &str) -> Result<Vec<Tag>, BlogClientError> {
+        let resp = self
+            .client
+            .get(self.api_url("/protected/tags"))
+            .bearer_auth(token)
+            .send_logged()
+            .await?;
+
+        Ok(resp.json().await?)
+    }
+
+    /// Lists comments on a post, oldest first.
+    ///
+    /// Requires a valid JWT token.
+    pub async fn list_comments(
+        &self,
+        token: &str,
+        post_id: Uuid,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Page<Comment>, BlogClientError> {
+        let resp = self
+            .client
+            .get(self.api_url(&format!("/protected/posts/{post_id}/comments")))
+            .bearer_auth(token)
+            .query(&[("limit", limit), ("offset", offset)])
+            .send_logged()
+            .await?;
+
+        read_page(resp, offset).await
+    }
+
+    /// Adds a comment to a post, optionally as a reply.
+    ///
+    /// Requires a valid JWT token.
+    pub async fn add_comment(
+        &self,
+        token: &str,
+        post_id: Uuid,
+        content: &str,
+        parent_id: Option<Uuid>,
+    ) -> Result<Comment, BlogClientError> {
+        let body = CommentRequest {
+            content: content.to_string(),
+            parent_id,
+            ..Default::default()
+        };
+
+        let resp = self
+            .client
+            .post(self.api_url(&format!("/protected/posts/{post_id}/comments")))
+            .bearer_auth(token)
+            .json(&body)
+            .send_logged()
+            .await?;
+
+        Ok(resp.json().await?)
+    }
+
+    /// Deletes a comment by its ID.
+    ///
+    /// Requires a valid JWT token.
+    pub async fn delete_comment(&self, token: &str, id: Uuid) -> Result<(), BlogClientError> {
+        self.client
+            .delete(self.api_url(&format!("/protected/comments/{id}")))
+            .bearer_auth(token)
+            .send_logged()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Lists the stats of the authenticated user's posts over the last `days`
+    /// days, most viewed first.
+    pub async fn list_post_stats(
+        &self,
+        token: &str,
+        days: u32,
+    ) -> Result<Vec<PostStats>, BlogClientError> {
+        let resp = self
+            .client
+            .get(self.api_url("/protected/analytics/posts"))
+            .bearer_auth(token)
+            .query(&[("days", days)])
+            .send_logged()
+            .await?;
+
+        Ok(resp.json().await?)
+    }
+
+    /// Returns the stats of one of the authenticated user's posts over the
+    /// last `days` days.
+    pub async fn get_post_stats(
+        &self,
+        token: &str,
+        id: Uuid,
+        days: u32,
+    ) -> Result<PostStats, BlogClientError> {
+        let resp = self
+            .client
+            .get(self.api_url(&format!("/protected/analytics/posts/{id}")))
+            .bearer_auth(token)
+            .query(&[("days", days)])
+            .send_logged()
+            .await?;
+
+        Ok(resp.json().await?)
+    }
+
+    /// Lists user accounts, oldest first.
+    ///
+    /// Requires a JWT token of an administrator.
+    pub async fn list_users(
+        &self,
+        token: &str,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<UserAccount>, BlogClientError> {
         let resp = self
             .client
-            .get(self.url("/api/protected/posts"))
+            .get(self.api_url("/protected/admin/users"))
             .bearer_auth(token)
             .query(&[("limit", limit), ("offset", offset)])
-            .send()
-            .await?
-            .error_for_status()?;
+            .send_logged()
+            .await?;
+
+        Ok(resp.json().await?)
+    }
+
+    /// Disables a user account.
+    ///
+    /// Requires a JWT token of an administrator.
+    pub async fn disable_user(&self, token: &str, id: Uuid) -> Result<UserAccount, BlogClientError> {
+        let resp = self
+            .client
+            .post(self.api_url(&format!("/protected/admin/users/{id}/disable")))
+            .bearer_auth(token)
+            .send_logged()
+            .await?;
 
         Ok(resp.json().await?)
     }
+
+    /// Changes the role of a user account.
+    ///
+    /// Requires a JWT token of an administrator.
+    pub async fn set_user_role(
+        &self,
+        token: &str,
+        id: Uuid,
+        role: &str,
+    ) -> Result<UserAccount, BlogClientError> {
+        let resp = self
+            .client
+            .put(self.api_url(&format!("/protected/admin/users/{id}/role")))
+            .bearer_auth(token)
+            .json(&serde_json::json!({ "role": role }))
+            .send_logged()
+            .await?;
+
+        Ok(resp.json().await?)
+    }
+
+    /// Imports posts from a `wordpress` or `medium` export.
+    ///
+    /// Posts whose author has no account go to `author_id`, or to the caller
+    /// when `None`. Requires a JWT token of an administrator.
+    pub async fn import_posts(
+        &self,
+        token: &str,
+        format: &str,
+        export: Vec<u8>,
+        author_id: Option<Uuid>,
+    ) -> Result<ImportSummary, BlogClientError> {
+        let mut query = vec![("format", format.to_string())];
+        if let Some(author_id) = author_id {
+            query.push(("author_id", author_id.to_string()));
+        }
+
+        let resp = self
+            .client
+            .post(self.api_url("/protected/admin/import"))
+            .bearer_auth(token)
+            .query(&query)
+            .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+            .body(export)
+            .send_logged()
+            .await?;
+
+        Ok(resp.json().await?)
+    }
+
+    /// Lists the authenticated user's uploads, oldest first.
+    ///
+    /// Requires a valid JWT token.
+    pub async fn list_media(&self, token: &str) -> Result<Vec<Media>, BlogClientError> {
+        let resp = self
+            .client
+            .get(self.api_url("/protected/media"))
+            .bearer_auth(token)
+            .send_logged()
+            .await?;
+
+        Ok(resp.json().await?)
+    }
+
+    /// Deletes one of the authenticated user's uploads.
+    ///
+    /// Requires a valid JWT token.
+    pub async fn delete_media(&self, token: &str, id: Uuid) -> Result<(), BlogClientError> {
+        self.client
+            .delete(self.api_url(&format!("/protected/media/{id}")))
+            .bearer_auth(token)
+            .send_logged()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Uploads an image in one multipart request.
+    ///
+    /// Requires a valid JWT token. Large files on unreliable connections are
+    /// better sent with [`Self::upload_media_resumable`].
+    pub async fn upload_media(
+        &self,
+        token: &str,
+        filename: &str,
+        bytes: Vec<u8>,
+        progress: Option<&ProgressReporter>,
+    ) -> Result<Media, BlogClientError> {
+        let size = bytes.len() as u64;
+        let body = upload::progress_body(&bytes, 0, size, progress);
+        let part = Part::stream_with_length(body, size).file_name(filename.to_string());
+        let form = Form::new().part(MEDIA_FILE_FIELD, part);
+
+        let resp = self
+            .client
+            .post(self.api_url("/protected/media"))
+            .bearer_auth(token)
+            .multipart(form)
+            .send_logged()
+            .await?;
+
+        Ok(resp.json().await?)
+    }
+
+    /// Starts a chunked upload of a `size`-byte file.
+    ///
+    /// Requires a valid JWT token.
+    pub async fn start_media_upload(
+        &self,
+        token: &str,
+        filename: &str,
+        size: u64,
+    ) -> Result<MediaUpload, BlogClientError> {
+        let resp = self
+            .client
+            .post(self.api_url("/protected/media/uploads"))
+            .bearer_auth(token)
+            .json(&serde_json::json!({ "filename": filename, "size": size }))
+            .send_logged()
+            .await?;
+
+        Ok(resp.json().await?)
+    }
+
+    /// Returns how far a chunked upload got.
+    ///
+    /// Requires a valid JWT token.
+    pub async fn get_media_upload(
+        &self,
+        token: &str,
+        id: Uuid,
+    ) -> Result<MediaUpload, BlogClientError> {
+        let resp = self
+            .client
+            .get(self.api_url(&format!("/protected/media/uploads/{id}")))
+            .bearer_auth(token)
+            .send_logged()
+            .await?;
+
+        Ok(resp.json().await?)
+    }
+
+    /// Sends the chunk of a file starting at byte `offset`, which must be
+    /// where the server's copy ends.
+    ///
+    /// Requires a valid JWT token. Progress is reported through the whole
+    /// file rather than the chunk.
+    pub async fn upload_media_chunk(
+        &self,
+        token: &str,
+        upload: &MediaUpload,
+        chunk: &[u8],
+        progress: Option<&ProgressReporter>,
+    ) -> Result<MediaUpload, BlogClientError> {
+        let id = upload.id;
+        let body = upload::progress_body(chunk, upload.offset, upload.size, progress);
+
+        let resp = self
+            .client
+            .put(self.api_url(&format!("/protected/media/uploads/{id}")))
+            .bearer_auth(token)
+            .query(&[("offset", upload.offset)])
+            .header(reqwest::header::CONTENT_TYPE, "application/octet-stream")
+            .header(reqwest::header::CONTENT_LENGTH, chunk.len())
+            .body(body)
+            .send_logged()
+            .await?;
+
+        Ok(resp.json().await?)
+    }
+
+    /// Turns a fully received chunked upload into media.
+    ///
+    /// Requires a valid JWT token.
+    pub async fn complete_media_upload(&self, token: &str, id: Uuid) -> Result<Media, BlogClientError> {
+        let resp = self
+            .client
+            .post(self.api_url(&format!("/protected/media/uploads/{id}/complete")))
+            .bearer_auth(token)
+            .send_logged()
+            .await?;
+
+        Ok(resp.json().await?)
+    }
+
+    /// Abandons a chunked upload, discarding what the server received.
+    ///
+    /// Requires a valid JWT token.
+    pub async fn cancel_media_upload(&self, token: &str, id: Uuid) -> Result<(), BlogClientError> {
+        self.client
+            .delete(self.api_url(&format!("/protected/media/uploads/{id}")))
+            .bearer_auth(token)
+            .send_logged()
+            .await?;
+
+        Ok(())
+    }
+
+    /// Uploads an image in chunks, retrying chunks that fail to arrive.
+    ///
+    /// Requires a valid JWT token. If the upload still fails, the error is
+    /// returned and the upload can be picked up again with
+    /// [`Self::resume_media_upload`] until the server expires it.
+    pub async fn upload_media_resumable(
+        &self,
+        token: &str,
+        filename: &str,
+        bytes: &[u8],
+        progress: Option<&ProgressReporter>,
+    ) -> Result<Media, BlogClientError> {
+        let upload = self
+            .start_media_upload(token, filename, bytes.len() as u64)
+            .await?;
+        debug!(upload_id = %upload.id, size = upload.size, "started chunked upload");
+
+        self.send_chunks(token, upload, bytes, progress).await
+    }
+
+    /// Sends the rest of the chunked upload `id` of `bytes`, the same file it
+    /// was started with, from wherever the server's copy ends.
+    ///
+    /// Requires a valid JWT token.
+    pub async fn resume_media_upload(
+        &self,
+        token: &str,
+        id: Uuid,
+        bytes: &[u8],
+        progress: Option<&ProgressReporter>,
+    ) -> Result<Media, BlogClientError> {
+        let upload = self.get_media_upload(token, id).await?;
+        if upload.size != bytes.len() as u64 {
+            return Err(BlogClientError::InvalidRequest(format!(
+                "upload {id} expects {} bytes, file has {}",
+                upload.size,
+                bytes.len()
+            )));
+        }
+        debug!(upload_id = %id, offset = upload.offset, "resuming chunked upload");
+
+        self.send_chunks(token, upload, bytes, progress).await
+    }
+
+    /// Sends the chunks of `bytes` from the upload's offset on, then
+    /// completes it.
+    ///
+    /// A chunk that fails transiently, or that the server refuses because its
+    /// copy ends elsewhere, is retried from the offset the server reports,
+    /// waiting longer after each consecutive failure.
+    async fn send_chunks(
+        &self,
+        token: &str,
+        mut upload: MediaUpload,
+        bytes: &[u8],
+        progress: Option<&ProgressReporter>,
+    ) -> Result<Media, BlogClientError> {
+        let chunk_bytes = upload.max_chunk_bytes.clamp(1, UPLOAD_CHUNK_BYTES);
+        let mut failures = 0;
+        let mut delay = CHUNK_RETRY_DELAY;
+        let mut resync = false;
+
+        while resync || upload.offset < upload.size {
+            let result = if resync {
+                self.get_media_upload(token, upload.id).await
+            } else {
+                let start = upload.offset as usize;
+                let end = (upload.offset + chunk_bytes).min(upload.size) as usize;
+                self.upload_media_chunk(token, &upload, &bytes[start..end], progress)
+                    .await
+            };
+
+            match result {
+                Ok(next) => {
+                    upload = next;
+                    resync = false;
+                    failures = 0;
+                    delay = CHUNK_RETRY_DELAY;
+                    if let Some(progress) = progress {
+                        progress.report(upload.offset, upload.size);
+                    }
+                }
+                Err(err)
+                    if failures < MAX_CHUNK_RETRIES
+                        && (err.is_transient() || err.code() == Some(ErrorCode::Conflict)) =>
+                {
+                    failures += 1;
+                    warn!(
+                        upload_id = %upload.id,
+                        offset = upload.offset,
+                        attempt = failures,
+                        error = %err,
+                        "chunk upload failed, retrying in {delay:?}"
+                    );
+                    tokio::time::sleep(delay).await;
+                    delay = (delay * 2).min(MAX_CHUNK_RETRY_DELAY);
+                    resync = true;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+
+        self.complete_media_upload(token, upload.id).await
+    }
+
+    /// Opens the server's event stream, first replaying the events after
+    /// `last_event_id` the server still holds.
+    ///
+    /// Requires a valid JWT token.
+    pub async fn events(
+        &self,
+        token: &str,
+        last_event_id: Option<u64>,
+    ) -> Result<impl Stream<Item = Result<BlogEvent, BlogClientError>> + use<>, BlogClientError> {
+        let mut request = self
+            .client
+            .get(self.api_url("/protected/events"))
+            .bearer_auth(token)
+            .header(reqwest::header::ACCEPT, "text/event-stream");
+
+        if let Some(id) = last_event_id {
+            request = request.header("Last-Event-ID", id.to_string());
+        }
+
+        let resp = request.send_logged().await?;
+
+        Ok(resp.bytes_stream().eventsource().map(|item| match item {
+            Ok(event) => Ok(serde_json::from_str(&event.data)?),
+            Err(EventStreamError::Transport(err)) => Err(BlogClientError::Http(err)),
+            Err(err) => Err(BlogClientError::InvalidState(format!("malformed event stream: {err}"))),
+        }))
+    }
+}
+
+/// Header carrying the server-assigned request identifier.
+const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// Header carrying the total number of items a paged list spans.
+const TOTAL_COUNT_HEADER: &str = "x-total-count";
+
+/// Decodes a response body according to its `Content-Type`.
+///
+/// Bodies that are neither MessagePack nor CBOR are read as JSON.
+async fn read_body<T: DeserializeOwned>(resp: Response) -> Result<T, BlogClientError> {
+    let content_type = resp
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .unwrap_or_default()
+        .to_ascii_lowercase();
+
+    if content_type.starts_with(WireFormat::MsgPack.mime()) {
+        let body = resp.bytes().await?;
+        return rmp_serde::from_slice(&body).map_err(|e| BlogClientError::Decode(e.to_string()));
+    }
+    if content_type.starts_with(WireFormat::Cbor.mime()) {
+        let body = resp.bytes().await?;
+        return ciborium::from_reader(body.as_ref())
+            .map_err(|e| BlogClientError::Decode(e.to_string()));
+    }
+
+    Ok(resp.json().await?)
+}
+
+/// Reads a page of a list fetched at `offset`, taking the total from `X-Total-Count`.
+///
+/// Without the header the total only covers the items seen so far.
+async fn read_page<T: DeserializeOwned>(resp: Response, offset: u32) -> Result<Page<T>, BlogClientError> {
+    let total = resp
+        .headers()
+        .get(TOTAL_COUNT_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok());
+    let items: Vec<T> = read_body(resp).await?;
+
+    Ok(Page {
+        total: total.unwrap_or(u64::from(offset) + items.len() as u64),
+        items,
+        offset,
+    })
+}
+
+/// Sends a request, logging the exchange and failing on error statuses.
+trait SendLogged: Sized {
+    /// Sends the request; logs method, URL, status, and request ID at debug level.
+    async fn send_traced(self) -> Result<Response, BlogClientError>;
+
+    /// Like [`send_traced`](Self::send_traced), but turns error statuses into errors.
+    ///
+    /// Error bodies in the server's JSON format become [`BlogClientError::Api`].
+    async fn send_logged(self) -> Result<Response, BlogClientError> {
+        let resp = self.send_traced().await?;
+        let Err(err) = resp.error_for_status_ref() else {
+            return Ok(resp);
+        };
+
+        let status = resp.status().as_u16();
+        let body: Option<ErrorBody> = resp.json().await.ok();
+        Err(match body {
+            Some(body) => BlogClientError::Api {
+                status,
+                code: body.code,
+                message: body.error,
+                request_id: body.request_id,
+            },
+            None => BlogClientError::Http(err),
+        })
+    }
+}
+
+impl SendLogged for RequestBuilder {
+    async fn send_traced(self) -> Result<Response, BlogClientError> {
+        let (client, request) = self.build_split();
+        let request = request?;
+        let method = request.method().clone();
+        let url = request.url().clone();
+
+        debug!(%method, %url, "sending request");
+        trace!(headers = ?request.headers(), "request headers");
+
+        let started = Instant::now();
+        let resp = client.execute(request).await?;
+
+        let request_id = resp
+            .headers()
+            .get(REQUEST_ID_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("-");
+        debug!(
+            %method,
+            %url,
+            status = resp.status().as_u16(),
+            request_id,
+            elapsed_ms = started.elapsed().as_millis() as u64,
+            "received response"
+        );
+        trace!(headers = ?resp.headers(), "response headers");
+
+        Ok(resp)
+    }
 }
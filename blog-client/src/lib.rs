@@ -1,3 +1,6 @@
+// `tonic::Status` is large; boxing it in every error path is not worth the noise.
+#![allow(clippy::result_large_err)]
+
 /// Client error types.
 pub mod error;
 
@@ -10,16 +13,37 @@ pub mod grpc_client;
 /// Client-side domain models.
 pub mod models;
 
+/// Upload progress reporting.
+pub mod upload;
+
+/// Mock server and fixtures for testing code built on the client.
+#[cfg(feature = "testing")]
+pub mod testing;
+
+/// Local post store with offline edits synced to the server.
+#[cfg(feature = "offline")]
+pub mod offline;
+
 /// Generated gRPC protobuf definitions.
 pub mod blog {
     tonic::include_proto!("blog");
 }
 
 use std::sync::Arc;
+use std::time::Duration;
 
 use error::BlogClientError;
+use futures_util::StreamExt;
+use futures_util::stream::BoxStream;
 use grpc_client::GrpcClient;
-use http_client::HttpClient;
+use http_client::{HttpClient, WireFormat};
+use tracing::{debug, warn};
+
+/// Wait before the first attempt to reopen a dropped event stream.
+const RECONNECT_DELAY: Duration = Duration::from_secs(1);
+
+/// Longest wait between attempts to reopen a dropped event stream.
+const MAX_RECONNECT_DELAY: Duration = Duration::from_secs(30);
 
 /// Transport configuration for the blog client.
 #[derive(Clone, Debug)]
@@ -64,6 +88,26 @@ impl BlogClient {
         })
     }
 
+    /// Sets the path the HTTP API is mounted under, `/api/v1` by default.
+    ///
+    /// Has no effect on the gRPC transport.
+    pub fn with_api_prefix(mut self, api_prefix: &str) -> Self {
+        self.http_client = self
+            .http_client
+            .map(|http| Arc::new(http.as_ref().clone().with_api_prefix(api_prefix)));
+        self
+    }
+
+    /// Sets the body encoding the HTTP transport requests on post endpoints.
+    ///
+    /// Has no effect on the gRPC transport.
+    pub fn with_wire_format(mut self, format: WireFormat) -> Self {
+        self.http_client = self
+            .http_client
+            .map(|http| Arc::new(http.as_ref().clone().with_format(format)));
+        self
+    }
+
     /// Sets the JWT token used for authenticated requests.
     pub fn set_token(&mut self, token: String) {
         self.token = Some(token);
@@ -129,21 +173,22 @@ impl BlogClient {
         }
     }
 
-    /// Creates a new post.
+    /// Creates a new post with the given tags.
     ///
     /// Requires a JWT token to be set.
     pub async fn create_post(
         &self,
         title: String,
         content: String,
+        tags: Vec<String>,
     ) -> Result<models::Post, BlogClientError> {
         let token = self
             .get_token()
             .ok_or(BlogClientError::Unauthorized("token is missing".into()))?;
 
         match (&self.transport, &self.http_client, &self.grpc_client) {
-            (Transport::Http(_), Some(http), _) => http.create_post(token, &title, &content).await,
-            (Transport::Grpc(_), _, Some(grpc)) => grpc.create_post(token, &title, &content).await,
+            (Transport::Http(_), Some(http), _) => http.create_post(token, &title, &content, &tags).await,
+            (Transport::Grpc(_), _, Some(grpc)) => grpc.create_post(token, &title, &content, &tags).await,
             _ => Err(BlogClientError::InvalidState(
                 "transport not properly initialized".into(),
             )),
@@ -169,20 +214,49 @@ impl BlogClient {
 
     /// Updates an existing post.
     ///
+    /// Tags are replaced when `tags` is set and kept otherwise.
     /// Requires a JWT token to be set.
     pub async fn update_post(
         &self,
         id: uuid::Uuid,
         title: String,
         content: String,
+        tags: Option<Vec<String>>,
     ) -> Result<models::Post, BlogClientError> {
         let token = self
             .get_token()
             .ok_or(BlogClientError::Unauthorized("token is missing".into()))?;
 
         match (&self.transport, &self.http_client, &self.grpc_client) {
-            (Transport::Http(_), Some(http), _) => http.update_post(token, id, &title, &content).await,
-            (Transport::Grpc(_), _, Some(grpc)) => grpc.update_post(token, id, &title, &content).await,
+            (Transport::Http(_), Some(http), _) => http.update_post(token, id, &title, &content, tags.as_deref(), None).await,
+            (Transport::Grpc(_), _, Some(grpc)) => grpc.update_post(token, id, &title, &content, tags.as_deref(), None).await,
+            _ => Err(BlogClientError::InvalidState(
+                "transport not properly initialized".into(),
+            )),
+        }
+    }
+
+    /// Updates a post only if nobody changed it since `expected_updated_at`.
+    ///
+    /// Fails with [`ErrorCode::Conflict`](error::ErrorCode::Conflict) when the
+    /// post was modified in the meantime; fetch it again and retry with the new
+    /// `updated_at`. Requires a JWT token to be set.
+    pub async fn update_post_if_unchanged(
+        &self,
+        id: uuid::Uuid,
+        title: String,
+        content: String,
+        tags: Option<Vec<String>>,
+        expected_updated_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<models::Post, BlogClientError> {
+        let token = self
+            .get_token()
+            .ok_or(BlogClientError::Unauthorized("token is missing".into()))?;
+        let expected = Some(expected_updated_at);
+
+        match (&self.transport, &self.http_client, &self.grpc_client) {
+            (Transport::Http(_), Some(http), _) => http.update_post(token, id, &title, &content, tags.as_deref(), expected).await,
+            (Transport::Grpc(_), _, Some(grpc)) => grpc.update_post(token, id, &title, &content, tags.as_deref(), expected).await,
             _ => Err(BlogClientError::InvalidState(
                 "transport not properly initialized".into(),
             )),
@@ -206,26 +280,501 @@ impl BlogClient {
         }
     }
 
-    /// Lists posts of the authenticated user.
+    /// Lists earlier versions of a post, newest first.
+    ///
+    /// Requires a JWT token to be set.
+    pub async fn list_revisions(
+        &self,
+        post_id: uuid::Uuid,
+    ) -> Result<Vec<models::PostRevision>, BlogClientError> {
+        let token = self
+            .get_token()
+            .ok_or(BlogClientError::Unauthorized("token is missing".into()))?;
+
+        match (&self.transport, &self.http_client, &self.grpc_client) {
+            (Transport::Http(_), Some(http), _) => http.list_revisions(token, post_id).await,
+            (Transport::Grpc(_), _, Some(grpc)) => grpc.list_revisions(token, post_id).await,
+            _ => Err(BlogClientError::InvalidState(
+                "transport not properly initialized".into(),
+            )),
+        }
+    }
+
+    /// Returns one earlier version of a post.
+    ///
+    /// Requires a JWT token to be set.
+    pub async fn get_revision(
+        &self,
+        post_id: uuid::Uuid,
+        revision_id: uuid::Uuid,
+    ) -> Result<models::PostRevision, BlogClientError> {
+        let token = self
+            .get_token()
+            .ok_or(BlogClientError::Unauthorized("token is missing".into()))?;
+
+        match (&self.transport, &self.http_client, &self.grpc_client) {
+            (Transport::Http(_), Some(http), _) => {
+                http.get_revision(token, post_id, revision_id).await
+            }
+            (Transport::Grpc(_), _, Some(grpc)) => {
+                grpc.get_revision(token, post_id, revision_id).await
+            }
+            _ => Err(BlogClientError::InvalidState(
+                "transport not properly initialized".into(),
+            )),
+        }
+    }
+
+    /// Makes an earlier version of a post current again; the replaced
+    /// version becomes a revision in turn.
+    ///
+    /// Requires a JWT token of the post's author.
+    pub async fn restore_revision(
+        &self,
+        post_id: uuid::Uuid,
+        revision_id: uuid::Uuid,
+    ) -> Result<models::Post, BlogClientError> {
+        let token = self
+            .get_token()
+            .ok_or(BlogClientError::Unauthorized("token is missing".into()))?;
+
+        match (&self.transport, &self.http_client, &self.grpc_client) {
+            (Transport::Http(_), Some(http), _) => {
+                http.restore_revision(token, post_id, revision_id).await
+            }
+            (Transport::Grpc(_), _, Some(grpc)) => {
+                grpc.restore_revision(token, post_id, revision_id).await
+            }
+            _ => Err(BlogClientError::InvalidState(
+                "transport not properly initialized".into(),
+            )),
+        }
+    }
+
+    /// Lists posts of the authenticated user, optionally only those tagged `tag`.
+    ///
+    /// The page carries the total number of matching posts for page controls.
     ///
     /// Requires a JWT token to be set.
     pub async fn list_posts(
         &self,
         limit: u32,
         offset: u32,
-    ) -> Result<Vec<models::Post>, BlogClientError> {
+        tag: Option<&str>,
+    ) -> Result<models::Page<models::Post>, BlogClientError> {
         let token = self
             .get_token()
             .ok_or(BlogClientError::Unauthorized("token is missing".into()))?;
 
         match (&self.transport, &self.http_client, &self.grpc_client) {
-            (Transport::Http(_), Some(http), _) => http.list_posts(token, limit, offset).await,
-            (Transport::Grpc(_), _, Some(grpc)) => {
-                grpc.list_posts(token).await // TODO: add limit/offset to gRPC
+            (Transport::Http(_), Some(http), _) => http.list_posts(token, limit, offset, tag).await,
+            (Transport::Grpc(_), _, Some(grpc)) => grpc.list_posts(token, limit, offset, tag).await,
+            _ => Err(BlogClientError::InvalidState(
+                "transport not properly initialized".into(),
+            )),
+        }
+    }
+
+    /// Searches posts of all authors, best matches first, optionally only
+    /// those tagged `tag`.
+    ///
+    /// `query` accepts web-search syntax (`"exact phrase"`, `or`, `-excluded`).
+    /// No token is needed.
+    pub async fn search_posts(
+        &self,
+        query: &str,
+        limit: u32,
+        offset: u32,
+        tag: Option<&str>,
+    ) -> Result<models::Page<models::SearchResult>, BlogClientError> {
+        match (&self.transport, &self.http_client, &self.grpc_client) {
+            (Transport::Http(_), Some(http), _) => http.search_posts(query, limit, offset, tag).await,
+            (Transport::Grpc(_), _, Some(grpc)) => grpc.search_posts(query, limit, offset, tag).await,
+            _ => Err(BlogClientError::InvalidState(
+                "transport not properly initialized".into(),
+            )),
+        }
+    }
+
+    /// Lists the tags used on the authenticated user's posts.
+    ///
+    /// Requires a JWT token to be set.
+    pub async fn list_tags(&self) -> Result<Vec<models::Tag>, BlogClientError> {
+        let token = self
+            .get_token()
+            .ok_or(BlogClientError::Unauthorized("token is missing".into()))?;
+
+        match (&self.transport, &self.http_client, &self.grpc_client) {
+            (Transport::Http(_), Some(http), _) => http.list_tags(token).await,
+            (Transport::Grpc(_), _, Some(grpc)) => grpc.list_tags(token).await,
+            _ => Err(BlogClientError::InvalidState(
+                "transport not properly initialized".into(),
+            )),
+        }
+    }
+
+    /// Lists comments on a post, oldest first.
+    ///
+    /// Requires a JWT token to be set.
+    pub async fn list_comments(
+        &self,
+        post_id: uuid::Uuid,
+        limit: u32,
+        offset: u32,
+    ) -> Result<models::Page<models::Comment>, BlogClientError> {
+        let token = self
+            .get_token()
+            .ok_or(BlogClientError::Unauthorized("token is missing".into()))?;
+
+        match (&self.transport, &self.http_client, &self.grpc_client) {
+            (Transport::Http(_), Some(http), _) => http.list_comments(token, post_id, limit, offset).await,
+            (Transport::Grpc(_), _, Some(grpc)) => grpc.list_comments(token, post_id, limit, offset).await,
+            _ => Err(BlogClientError::InvalidState(
+                "transport not properly initialized".into(),
+            )),
+        }
+    }
+
+    /// Adds a comment to a post, optionally as a reply to `parent_id`.
+    ///
+    /// Requires a JWT token to be set.
+    pub async fn add_comment(
+        &self,
+        post_id: uuid::Uuid,
+        content: String,
+        parent_id: Option<uuid::Uuid>,
+    ) -> Result<models::Comment, BlogClientError> {
+        let token = self
+            .get_token()
+            .ok_or(BlogClientError::Unauthorized("token is missing".into()))?;
+
+        match (&self.transport, &self.http_client, &self.grpc_client) {
+            (Transport::Http(_), Some(http), _) => http.add_comment(token, post_id, &content, parent_id).await,
+            (Transport::Grpc(_), _, Some(grpc)) => grpc.add_comment(token, post_id, &content, parent_id).await,
+            _ => Err(BlogClientError::InvalidState(
+                "transport not properly initialized".into(),
+            )),
+        }
+    }
+
+    /// Deletes a comment by its ID.
+    ///
+    /// Requires a JWT token to be set.
+    pub async fn delete_comment(&self, id: uuid::Uuid) -> Result<(), BlogClientError> {
+        let token = self
+            .get_token()
+            .ok_or(BlogClientError::Unauthorized("token is missing".into()))?;
+
+        match (&self.transport, &self.http_client, &self.grpc_client) {
+            (Transport::Http(_), Some(http), _) => http.delete_comment(token, id).await,
+            (Transport::Grpc(_), _, Some(grpc)) => grpc.delete_comment(token, id).await,
+            _ => Err(BlogClientError::InvalidState(
+                "transport not properly initialized".into(),
+            )),
+        }
+    }
+
+    /// Subscribes to blog events for the authenticated user.
+    ///
+    /// Events arrive over Server-Sent Events with the HTTP transport and over
+    /// the `StreamEvents` RPC with gRPC. A dropped stream is reopened after a
+    /// growing delay, resuming after the last event received, so the stream
+    /// only ends after an error retrying cannot fix, such as an expired token.
+    ///
+    /// Requires a JWT token to be set.
+    pub async fn events(
+        &self,
+    ) -> Result<BoxStream<'static, Result<models::BlogEvent, BlogClientError>>, BlogClientError> {
+        self.events_since(None).await
+    }
+
+    /// Like [`Self::events`], but first replays the events after
+    /// `last_event_id` the server still holds.
+    pub async fn events_since(
+        &self,
+        last_event_id: Option<u64>,
+    ) -> Result<BoxStream<'static, Result<models::BlogEvent, BlogClientError>>, BlogClientError> {
+        let token = self
+            .get_token()
+            .ok_or(BlogClientError::Unauthorized("token is missing".into()))?
+            .to_string();
+
+        // The first connection fails fast; only later ones are retried.
+        let stream = self.open_events(&token, last_event_id).await?;
+        let state = EventStreamState {
+            client: self.clone(),
+            token,
+            last_event_id,
+            stream: Some(stream),
+            delay: RECONNECT_DELAY,
+            done: false,
+        };
+
+        Ok(futures_util::stream::unfold(state, EventStreamState::next).boxed())
+    }
+
+    /// Opens one event stream over the configured transport.
+    async fn open_events(
+        &self,
+        token: &str,
+        last_event_id: Option<u64>,
+    ) -> Result<BoxStream<'static, Result<models::BlogEvent, BlogClientError>>, BlogClientError> {
+        match (&self.transport, &self.http_client, &self.grpc_client) {
+            (Transport::Http(_), Some(http), _) => Ok(http.events(token, last_event_id).await?.boxed()),
+            (Transport::Grpc(_), _, Some(grpc)) => Ok(grpc.events(token, last_event_id).await?.boxed()),
+            _ => Err(BlogClientError::InvalidState(
+                "transport not properly initialized".into(),
+            )),
+        }
+    }
+
+    /// Exchanges a refresh token for a new access token and starts using it.
+    ///
+    /// The refresh token is single-use: keep the one in the response for the
+    /// next refresh.
+    pub async fn refresh(
+        &mut self,
+        refresh_token: &str,
+    ) -> Result<models::AuthResponse, BlogClientError> {
+        let resp = match (&self.transport, &self.http_client, &self.grpc_client) {
+            (Transport::Http(_), Some(http), _) => http.refresh(refresh_token).await?,
+            (Transport::Grpc(_), _, Some(grpc)) => grpc.refresh(refresh_token).await?,
+            _ => {
+                return Err(BlogClientError::InvalidState(
+                    "transport not properly initialized".into(),
+                ));
             }
+        };
+
+        if let Some(token) = &resp.access_token {
+            self.set_token(token.clone());
+        }
+        Ok(resp)
+    }
+
+    /// Lists the stats of the authenticated user's posts over the last `days`
+    /// days (zero for the server default), most viewed first.
+    ///
+    /// Only the HTTP transport exposes analytics.
+    pub async fn list_post_stats(
+        &self,
+        days: u32,
+    ) -> Result<Vec<models::PostStats>, BlogClientError> {
+        let (http, token) = self.analytics_http()?;
+        http.list_post_stats(token, days).await
+    }
+
+    /// Returns the stats of one of the authenticated user's posts over the
+    /// last `days` days (zero for the server default).
+    ///
+    /// Only the HTTP transport exposes analytics.
+    pub async fn get_post_stats(
+        &self,
+        id: uuid::Uuid,
+        days: u32,
+    ) -> Result<models::PostStats, BlogClientError> {
+        let (http, token) = self.analytics_http()?;
+        http.get_post_stats(token, id, days).await
+    }
+
+    /// Lists user accounts, oldest first.
+    ///
+    /// Only the HTTP transport exposes the admin API.
+    /// Requires a JWT token of an administrator.
+    pub async fn list_users(
+        &self,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<models::UserAccount>, BlogClientError> {
+        let (http, token) = self.admin_http()?;
+        http.list_users(token, limit, offset).await
+    }
+
+    /// Disables a user account.
+    ///
+    /// Requires a JWT token of an administrator.
+    pub async fn disable_user(&self, id: uuid::Uuid) -> Result<models::UserAccount, BlogClientError> {
+        let (http, token) = self.admin_http()?;
+        http.disable_user(token, id).await
+    }
+
+    /// Changes the role (`user` or `admin`) of a user account.
+    ///
+    /// Requires a JWT token of an administrator.
+    pub async fn set_user_role(
+        &self,
+        id: uuid::Uuid,
+        role: &str,
+    ) -> Result<models::UserAccount, BlogClientError> {
+        let (http, token) = self.admin_http()?;
+        http.set_user_role(token, id, role).await
+    }
+
+    /// Imports posts from a `wordpress` or `medium` export.
+    ///
+    /// Posts whose author has no account go to `author_id`, or to the caller
+    /// when `None`. Requires a JWT token of an administrator.
+    pub async fn import_posts(
+        &self,
+        format: &str,
+        export: Vec<u8>,
+        author_id: Option<uuid::Uuid>,
+    ) -> Result<models::ImportSummary, BlogClientError> {
+        let (http, token) = self.admin_http()?;
+        http.import_posts(token, format, export, author_id).await
+    }
+
+    /// Lists your uploads, oldest first.
+    ///
+    /// Only supported over HTTP.
+    pub async fn list_media(&self) -> Result<Vec<models::Media>, BlogClientError> {
+        let (http, token) = self.media_http()?;
+        http.list_media(token).await
+    }
+
+    /// Deletes one of your uploads.
+    ///
+    /// Only supported over HTTP.
+    pub async fn delete_media(&self, id: uuid::Uuid) -> Result<(), BlogClientError> {
+        let (http, token) = self.media_http()?;
+        http.delete_media(token, id).await
+    }
+
+    /// Uploads an image in one request, reporting progress to `progress`.
+    ///
+    /// Only supported over HTTP.
+    pub async fn upload_media(
+        &self,
+        filename: &str,
+        bytes: Vec<u8>,
+        progress: Option<&upload::ProgressReporter>,
+    ) -> Result<models::Media, BlogClientError> {
+        let (http, token) = self.media_http()?;
+        http.upload_media(token, filename, bytes, progress).await
+    }
+
+    /// Uploads an image in chunks, retrying chunks lost to connection
+    /// problems, and reports progress to `progress`.
+    ///
+    /// Only supported over HTTP.
+    pub async fn upload_media_resumable(
+        &self,
+        filename: &str,
+        bytes: &[u8],
+        progress: Option<&upload::ProgressReporter>,
+    ) -> Result<models::Media, BlogClientError> {
+        let (http, token) = self.media_http()?;
+        http.upload_media_resumable(token, filename, bytes, progress)
+            .await
+    }
+
+    /// Finishes the chunked upload `id` of `bytes` after an earlier attempt
+    /// gave up.
+    ///
+    /// Only supported over HTTP.
+    pub async fn resume_media_upload(
+        &self,
+        id: uuid::Uuid,
+        bytes: &[u8],
+        progress: Option<&upload::ProgressReporter>,
+    ) -> Result<models::Media, BlogClientError> {
+        let (http, token) = self.media_http()?;
+        http.resume_media_upload(token, id, bytes, progress).await
+    }
+
+    /// Abandons the chunked upload `id`.
+    ///
+    /// Only supported over HTTP.
+    pub async fn cancel_media_upload(&self, id: uuid::Uuid) -> Result<(), BlogClientError> {
+        let (http, token) = self.media_http()?;
+        http.cancel_media_upload(token, id).await
+    }
+
+    /// Returns the HTTP transport and token for admin API calls.
+    fn admin_http(&self) -> Result<(&HttpClient, &str), BlogClientError> {
+        self.http_only("user administration")
+    }
+
+    /// Returns the HTTP transport and token for analytics API calls.
+    fn analytics_http(&self) -> Result<(&HttpClient, &str), BlogClientError> {
+        self.http_only("analytics")
+    }
+
+    /// Returns the HTTP transport and token for media API calls.
+    fn media_http(&self) -> Result<(&HttpClient, &str), BlogClientError> {
+        self.http_only("media management")
+    }
+
+    /// Returns the HTTP transport and token for calls to `feature`, which the
+    /// gRPC API does not offer.
+    fn http_only(&self, feature: &str) -> Result<(&HttpClient, &str), BlogClientError> {
+        let token = self
+            .get_token()
+            .ok_or(BlogClientError::Unauthorized("token is missing".into()))?;
+
+        match (&self.transport, &self.http_client, &self.grpc_client) {
+            (Transport::Http(_), Some(http), _) => Ok((http, token)),
+            (Transport::Grpc(_), _, Some(_)) => Err(BlogClientError::InvalidRequest(format!(
+                "{feature} is not supported over gRPC yet"
+            ))),
             _ => Err(BlogClientError::InvalidState(
                 "transport not properly initialized".into(),
             )),
         }
     }
 }
+
+/// Progress of a reconnecting event stream.
+struct EventStreamState {
+    client: BlogClient,
+    token: String,
+    last_event_id: Option<u64>,
+    stream: Option<BoxStream<'static, Result<models::BlogEvent, BlogClientError>>>,
+    delay: Duration,
+    done: bool,
+}
+
+impl EventStreamState {
+    /// Returns the next event, reopening the stream as long as it drops for
+    /// reasons retrying may fix.
+    async fn next(mut self) -> Option<(Result<models::BlogEvent, BlogClientError>, Self)> {
+        loop {
+            if self.done {
+                return None;
+            }
+
+            if let Some(stream) = self.stream.as_mut() {
+                match stream.next().await {
+                    Some(Ok(event)) => {
+                        self.last_event_id = Some(event.id);
+                        self.delay = RECONNECT_DELAY;
+                        return Some((Ok(event), self));
+                    }
+                    Some(Err(err)) if !err.is_transient() => return Some(self.fail(err)),
+                    Some(Err(err)) => warn!(error = %err, "event stream dropped"),
+                    None => debug!("event stream closed by the server"),
+                }
+                self.stream = None;
+            }
+
+            tokio::time::sleep(self.delay).await;
+            self.delay = (self.delay * 2).min(MAX_RECONNECT_DELAY);
+
+            match self.client.open_events(&self.token, self.last_event_id).await {
+                Ok(stream) => {
+                    debug!(last_event_id = ?self.last_event_id, "event stream reopened");
+                    self.stream = Some(stream);
+                }
+                Err(err) if !err.is_transient() => return Some(self.fail(err)),
+                Err(err) => warn!(error = %err, "failed to reopen the event stream"),
+            }
+        }
+    }
+
+    /// Ends the stream after handing out `err`.
+    fn fail(mut self, err: BlogClientError) -> (Result<models::BlogEvent, BlogClientError>, Self) {
+        self.stream = None;
+        self.done = true;
+        (Err(err), self)
+    }
+}
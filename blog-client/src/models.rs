@@ -1,13 +1,23 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
+pub use blog_core::auth::{Profile, Role};
+pub use blog_core::comment::{Comment, CommentStatus};
+pub use blog_core::media::{Media, MediaUpload, MediaVariants};
+pub use blog_core::post::{Author, Post, SearchResult, Tag, Visibility};
+
 /// Authentication response returned by the API.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthResponse {
     /// JWT access token, if authentication was successful.
     pub access_token: Option<String>,
 
+    /// Refresh token for obtaining new access tokens, if the server issued one.
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+
     /// Authenticated user information, if available.
     pub user: Option<User>,
 }
@@ -25,24 +35,340 @@ pub struct User {
     pub email: String,
 }
 
-/// Blog post model returned by the client API.
+/// User account as returned by the admin API.
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Post {
-    /// Unique post identifier.
+pub struct UserAccount {
+    /// Unique user identifier.
     pub id: Uuid,
 
+    /// User display name.
+    pub username: String,
+
+    /// User email address.
+    pub email: String,
+
+    /// User role, `user` or `admin`.
+    pub role: String,
+
+    /// Registration timestamp.
+    pub created_at: DateTime<Utc>,
+
+    /// Time the account was disabled, if disabled.
+    pub disabled_at: Option<DateTime<Utc>>,
+}
+
+/// Another user, as any authenticated user may see them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PublicUser {
+    /// Unique user identifier.
+    pub id: Uuid,
+
+    /// User display name.
+    pub username: String,
+
+    /// Short self-description, if set.
+    pub bio: Option<String>,
+
+    /// Registration timestamp.
+    pub created_at: DateTime<Utc>,
+}
+
+/// Outcome of a blog import, as returned by the admin API.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportSummary {
+    /// How the export's authors were mapped to local accounts.
+    pub authors: Vec<ImportAuthor>,
+
+    /// Posts that were created.
+    pub imported: Vec<ImportedPost>,
+
+    /// Entries that were not imported, with the reason.
+    pub skipped: Vec<ImportSkipped>,
+
+    /// Referenced images downloaded into the media store.
+    pub images_imported: usize,
+
+    /// Referenced images that could not be downloaded.
+    pub image_failures: Vec<ImportImageFailure>,
+
+    /// Anything else worth a look, such as dropped tags.
+    pub warnings: Vec<String>,
+}
+
+/// Local account an export author's posts were assigned to.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportAuthor {
+    /// Author name in the export.
+    pub source: String,
+
+    /// Account the posts were assigned to.
+    pub user_id: Uuid,
+
+    /// Whether an account with the author's email was found.
+    pub matched: bool,
+}
+
+/// Post created by an import.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportedPost {
+    /// Identifier of the post in the export.
+    pub source_id: String,
+
+    /// Identifier of the created post.
+    pub post_id: Uuid,
+
     /// Post title.
     pub title: String,
 
-    /// Post content.
+    /// Images of the post that were downloaded into the media store.
+    pub images: usize,
+}
+
+/// Export entry that was not imported.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportSkipped {
+    /// Identifier of the entry in the export.
+    pub source_id: String,
+
+    /// Entry title, if it has one.
+    pub title: String,
+
+    /// Why it was skipped.
+    pub reason: String,
+}
+
+/// Referenced image that could not be imported.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportImageFailure {
+    /// Image URL as referenced by the post.
+    pub url: String,
+
+    /// Why it failed.
+    pub reason: String,
+}
+
+/// Earlier version of a post, recorded whenever the post is edited.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostRevision {
+    /// Revision identifier.
+    pub id: Uuid,
+
+    /// Identifier of the revised post.
+    pub post_id: Uuid,
+
+    /// Title the post had.
+    pub title: String,
+
+    /// Content the post had.
     pub content: String,
 
-    /// Post author identifier.
-    pub author_id: Uuid,
+    /// Tags the post had.
+    #[serde(default)]
+    pub tags: Vec<String>,
 
-    /// Post creation timestamp.
+    /// When this version was written.
+    pub edited_at: DateTime<Utc>,
+
+    /// When this version was replaced.
     pub created_at: DateTime<Utc>,
+}
+
+/// Views, reactions and comments a post received over a period.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostStats {
+    /// Post identifier.
+    pub post_id: Uuid,
+
+    /// Post title.
+    pub title: String,
+
+    /// Reads over the period.
+    pub views: i64,
+
+    /// Likes given over the period.
+    pub likes: i64,
+
+    /// Bookmarks added over the period.
+    pub bookmarks: i64,
+
+    /// Published comments written over the period.
+    pub comments: i64,
+
+    /// Reads per day, oldest first, including days without any.
+    pub daily_views: Vec<DailyViews>,
+
+    /// Reads per referring domain, most first; only set for a single post.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub referrers: Option<Vec<ReferrerViews>>,
+
+    /// Reads per kind of client, most first; only set for a single post.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user_agents: Option<Vec<AgentViews>>,
+}
+
+/// Reads of a post on one day.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DailyViews {
+    /// Day, in UTC.
+    pub day: NaiveDate,
+
+    /// Number of reads.
+    pub views: i64,
+}
+
+/// Reads of a post from one referring domain.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReferrerViews {
+    /// Referring domain; `None` for direct visits.
+    pub referrer: Option<String>,
+
+    /// Number of reads.
+    pub views: i64,
+}
+
+/// Reads of a post from one kind of client.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AgentViews {
+    /// Kind of client: `desktop`, `mobile`, `bot` or `other`.
+    pub agent: String,
+
+    /// Number of reads.
+    pub views: i64,
+}
+
+/// Server health reported by the liveness and readiness probes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Health {
+    /// Overall status, `ok` when the server is healthy.
+    pub status: String,
+
+    /// Server version, if reported.
+    #[serde(default)]
+    pub version: Option<String>,
+
+    /// Health of each server dependency, keyed by name.
+    #[serde(default)]
+    pub checks: BTreeMap<String, DependencyHealth>,
+}
+
+impl Health {
+    /// Returns whether the server and all its dependencies are healthy.
+    pub fn is_ok(&self) -> bool {
+        self.status == "ok" && self.checks.values().all(|check| check.status == "ok")
+    }
+}
+
+/// Version and build details reported by the server.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BuildInfo {
+    /// Server version.
+    pub version: String,
+
+    /// Abbreviated git commit the server was built from, or `unknown`.
+    pub git_sha: String,
+
+    /// When the server was built.
+    #[serde(default)]
+    pub build_timestamp: Option<DateTime<Utc>>,
+
+    /// Cargo features the server was built with.
+    #[serde(default)]
+    pub features: Vec<String>,
+}
+
+/// Health of a single server dependency.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DependencyHealth {
+    /// `ok` or `error`.
+    pub status: String,
+
+    /// Time the server spent checking the dependency, in milliseconds.
+    pub latency_ms: u64,
+
+    /// Failure reason, if the check failed.
+    #[serde(default)]
+    pub error: Option<String>,
+}
+
+/// One page of a paged listing, with the size of the whole listing.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Page<T> {
+    /// Items on this page.
+    pub items: Vec<T>,
+
+    /// Number of items across all pages.
+    pub total: u64,
+
+    /// Number of items skipped before this page.
+    pub offset: u32,
+}
+
+impl<T> Page<T> {
+    /// Returns the offset of the next page, if there is one.
+    pub fn next_offset(&self) -> Option<u32> {
+        let next = self.offset + self.items.len() as u32;
+        (!self.items.is_empty() && u64::from(next) < self.total).then_some(next)
+    }
+}
+
+/// Blog activity event received from the server's event stream.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlogEvent {
+    /// Server-assigned event sequence number.
+    pub id: u64,
+
+    /// Event timestamp.
+    pub at: DateTime<Utc>,
+
+    /// What happened.
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+/// Kind of blog activity, tagged by `type`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventKind {
+    /// A post was created.
+    PostCreated {
+        post_id: Uuid,
+        author_id: Uuid,
+        title: String,
+    },
+
+    /// A post was updated.
+    PostUpdated {
+        post_id: Uuid,
+        author_id: Uuid,
+        title: String,
+    },
+
+    /// A post was deleted.
+    PostDeleted { post_id: Uuid, author_id: Uuid },
+
+    /// A comment was added to a post.
+    CommentAdded {
+        comment_id: Uuid,
+        post_id: Uuid,
+        post_author_id: Uuid,
+        author_id: Uuid,
+        parent_id: Option<Uuid>,
+    },
+
+    /// An event type this client version does not know.
+    #[serde(other)]
+    Unknown,
+}
 
-    /// Post update timestamp, if updated.
-    pub updated_at: Option<DateTime<Utc>>,
+impl EventKind {
+    /// Returns the event type name used on the wire.
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::PostCreated { .. } => "post_created",
+            EventKind::PostUpdated { .. } => "post_updated",
+            EventKind::PostDeleted { .. } => "post_deleted",
+            EventKind::CommentAdded { .. } => "comment_added",
+            EventKind::Unknown => "unknown",
+        }
+    }
 }
@@ -0,0 +1,673 @@
+//! Offline-first editing of the authenticated user's posts.
+//!
+//! [`OfflineClient`] keeps a copy of the user's posts in a local SQLite file.
+//! Creates, updates and deletes apply to that copy immediately and are queued;
+//! [`OfflineClient::sync`] sends the queue to the server when it is reachable
+//! and then refreshes the local copy. Edits that race with changes made
+//! elsewhere are detected through the post's `updated_at` and handed to a
+//! [`ConflictResolver`].
+
+use std::path::Path;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePool, SqlitePoolOptions};
+use tracing::{debug, warn};
+use uuid::Uuid;
+
+use crate::BlogClient;
+use crate::error::{BlogClientError, ErrorCode};
+use crate::models::{Post, Visibility};
+
+/// Posts fetched per request when refreshing the local copy.
+const PULL_PAGE_SIZE: u32 = 100;
+
+const SCHEMA: &str = "
+CREATE TABLE IF NOT EXISTS posts (
+    id   TEXT PRIMARY KEY,
+    body TEXT NOT NULL
+);
+CREATE TABLE IF NOT EXISTS pending_changes (
+    seq     INTEGER PRIMARY KEY AUTOINCREMENT,
+    post_id TEXT NOT NULL UNIQUE,
+    body    TEXT NOT NULL
+);
+";
+
+/// Kind of a queued post mutation.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    /// Post written offline that the server has not seen yet.
+    Create,
+
+    /// Edit of a post that exists on the server.
+    Update,
+
+    /// Removal of a post that exists on the server.
+    Delete,
+}
+
+/// Post fields written by an edit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PostEdit {
+    /// Post title.
+    pub title: String,
+
+    /// Post content.
+    pub content: String,
+
+    /// Post tags.
+    pub tags: Vec<String>,
+}
+
+/// Post mutation waiting to be sent to the server.
+///
+/// The queue holds at most one change per post: further edits are folded into
+/// the queued one.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PendingChange {
+    /// What the change does.
+    pub kind: ChangeKind,
+
+    /// Post the change applies to; a local ID for queued creates.
+    pub post_id: Uuid,
+
+    /// Post fields after the change; unused by deletes.
+    pub edit: PostEdit,
+
+    /// Server `updated_at` the change was based on; `None` for creates.
+    pub base_updated_at: Option<DateTime<Utc>>,
+
+    /// When the change was first queued.
+    pub queued_at: DateTime<Utc>,
+}
+
+/// Post edited both locally and on the server since the last sync.
+#[derive(Debug, Clone, Copy)]
+pub struct Conflict<'a> {
+    /// Queued local change.
+    pub local: &'a PendingChange,
+
+    /// Current server version of the post.
+    pub server: &'a Post,
+}
+
+/// How to settle a [`Conflict`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Resolution {
+    /// Drop the local change and keep the server version.
+    KeepServer,
+
+    /// Overwrite the server version with the local change.
+    KeepLocal,
+
+    /// Save the given fields, typically combined from both versions.
+    Merge(PostEdit),
+
+    /// Leave the change queued and report it; sync retries it next time.
+    Defer,
+}
+
+/// Strategy deciding how conflicting edits are settled during sync.
+///
+/// Implemented for closures taking a [`Conflict`].
+pub trait ConflictResolver: Send + Sync {
+    /// Decides how to settle `conflict`.
+    fn resolve(&self, conflict: Conflict<'_>) -> Resolution;
+}
+
+impl<F> ConflictResolver for F
+where
+    F: Fn(Conflict<'_>) -> Resolution + Send + Sync,
+{
+    fn resolve(&self, conflict: Conflict<'_>) -> Resolution {
+        self(conflict)
+    }
+}
+
+/// Resolver that discards local edits in favor of the server version.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ServerWins;
+
+impl ConflictResolver for ServerWins {
+    fn resolve(&self, _: Conflict<'_>) -> Resolution {
+        Resolution::KeepServer
+    }
+}
+
+/// Resolver that overwrites the server version with local edits.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LocalWins;
+
+impl ConflictResolver for LocalWins {
+    fn resolve(&self, _: Conflict<'_>) -> Resolution {
+        Resolution::KeepLocal
+    }
+}
+
+/// Resolver that keeps conflicting edits queued for the user to settle.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Manual;
+
+impl ConflictResolver for Manual {
+    fn resolve(&self, _: Conflict<'_>) -> Resolution {
+        Resolution::Defer
+    }
+}
+
+/// Queued change the server rejected; it was dropped from the queue.
+#[derive(Debug, Clone)]
+pub struct SyncFailure {
+    /// Rejected change.
+    pub change: PendingChange,
+
+    /// Server's reason.
+    pub error: String,
+}
+
+/// Outcome of [`OfflineClient::sync`].
+#[derive(Debug, Clone, Default)]
+pub struct SyncReport {
+    /// Posts as saved on the server by pushed changes and resolved conflicts.
+    pub pushed: Vec<Post>,
+
+    /// Conflicts left queued by [`Resolution::Defer`].
+    pub conflicts: Vec<PendingChange>,
+
+    /// Changes the server rejected.
+    pub failed: Vec<SyncFailure>,
+
+    /// Number of posts in the refreshed local copy; 0 when the refresh did not run.
+    pub pulled: usize,
+
+    /// Changes still queued after the sync.
+    pub pending: usize,
+
+    /// Whether the server could not be reached; the remaining changes stay queued.
+    pub offline: bool,
+}
+
+/// Blog client working on a local copy of the user's posts.
+pub struct OfflineClient {
+    client: BlogClient,
+    store: LocalStore,
+    resolver: Box<dyn ConflictResolver>,
+}
+
+impl OfflineClient {
+    /// Opens or creates the local store at `path`, syncing through `client`.
+    ///
+    /// Conflicts are left queued until a resolver is set with
+    /// [`with_resolver`](Self::with_resolver).
+    pub async fn open(path: impl AsRef<Path>, client: BlogClient) -> Result<Self, BlogClientError> {
+        Ok(Self {
+            client,
+            store: LocalStore::open(path.as_ref()).await?,
+            resolver: Box::new(Manual),
+        })
+    }
+
+    /// Sets the strategy for settling conflicting edits.
+    pub fn with_resolver(mut self, resolver: impl ConflictResolver + 'static) -> Self {
+        self.resolver = Box::new(resolver);
+        self
+    }
+
+    /// Returns the underlying client.
+    pub fn client(&self) -> &BlogClient {
+        &self.client
+    }
+
+    /// Returns the underlying client mutably, e.g. to set a refreshed token.
+    pub fn client_mut(&mut self) -> &mut BlogClient {
+        &mut self.client
+    }
+
+    /// Lists the local posts, newest first, including unsynced changes.
+    pub async fn posts(&self) -> Result<Vec<Post>, BlogClientError> {
+        self.store.posts().await
+    }
+
+    /// Returns a local post by its ID.
+    pub async fn post(&self, id: Uuid) -> Result<Option<Post>, BlogClientError> {
+        self.store.post(id).await
+    }
+
+    /// Lists the changes waiting to be synced, oldest first.
+    pub async fn pending(&self) -> Result<Vec<PendingChange>, BlogClientError> {
+        self.store.pending().await
+    }
+
+    /// Creates a post locally and queues it for the server.
+    ///
+    /// The post gets a local ID that is replaced by the server's on sync.
+    pub async fn create_post(
+        &self,
+        title: String,
+        content: String,
+        tags: Vec<String>,
+    ) -> Result<Post, BlogClientError> {
+        let now = Utc::now();
+        let post = Post {
+            id: Uuid::new_v4(),
+            author_id: Uuid::nil(),
+            author: None,
+            title,
+            slug: String::new(),
+            content,
+            tags,
+            visibility: Visibility::default(),
+            created_at: now,
+            updated_at: now,
+            deleted_at: None,
+            locale: None,
+        };
+        let change = PendingChange {
+            kind: ChangeKind::Create,
+            post_id: post.id,
+            edit: edit_of(&post),
+            base_updated_at: None,
+            queued_at: now,
+        };
+
+        self.store.queue(&change, Some(&post)).await?;
+        Ok(post)
+    }
+
+    /// Updates a local post and queues the edit for the server.
+    ///
+    /// Tags are replaced when `tags` is set and kept otherwise.
+    pub async fn update_post(
+        &self,
+        id: Uuid,
+        title: String,
+        content: String,
+        tags: Option<Vec<String>>,
+    ) -> Result<Post, BlogClientError> {
+        let mut post = self
+            .store
+            .post(id)
+            .await?
+            .ok_or_else(|| BlogClientError::NotFound(format!("post {id} is not stored locally")))?;
+        let now = Utc::now();
+        let base_updated_at = post.updated_at;
+
+        post.title = title;
+        post.content = content;
+        if let Some(tags) = tags {
+            post.tags = tags;
+        }
+        post.updated_at = now;
+
+        let change = match self.store.pending_for(id).await? {
+            Some(queued) => PendingChange {
+                edit: edit_of(&post),
+                ..queued
+            },
+            None => PendingChange {
+                kind: ChangeKind::Update,
+                post_id: id,
+                edit: edit_of(&post),
+                base_updated_at: Some(base_updated_at),
+                queued_at: now,
+            },
+        };
+
+        self.store.queue(&change, Some(&post)).await?;
+        Ok(post)
+    }
+
+    /// Deletes a local post and queues the deletion for the server.
+    ///
+    /// Posts never synced are simply dropped.
+    pub async fn delete_post(&self, id: Uuid) -> Result<(), BlogClientError> {
+        let post = self
+            .store
+            .post(id)
+            .await?
+            .ok_or_else(|| BlogClientError::NotFound(format!("post {id} is not stored locally")))?;
+
+        match self.store.pending_for(id).await? {
+            Some(queued) if queued.kind == ChangeKind::Create => self.store.forget(&queued).await,
+            queued => {
+                let change = PendingChange {
+                    kind: ChangeKind::Delete,
+                    post_id: id,
+                    edit: edit_of(&post),
+                    base_updated_at: queued
+                        .as_ref()
+                        .map_or(Some(post.updated_at), |queued| queued.base_updated_at),
+                    queued_at: queued.map_or_else(Utc::now, |queued| queued.queued_at),
+                };
+                self.store.queue(&change, None).await
+            }
+        }
+    }
+
+    /// Sends queued changes in order, then refreshes the local copy.
+    ///
+    /// Stops at the first change the server cannot be reached for, leaving it
+    /// and the rest queued; the refresh is skipped in that case.
+    pub async fn sync(&self) -> Result<SyncReport, BlogClientError> {
+        let mut report = SyncReport::default();
+
+        for change in self.store.pending().await? {
+            let outcome = match self.push(&change, change.base_updated_at, &change.edit).await {
+                Err(err) if err.code() == Some(ErrorCode::Conflict) => self.resolve(&change).await,
+                result => result.map(Outcome::Saved),
+            };
+
+            match outcome {
+                Ok(Outcome::Saved(post)) => {
+                    self.store.complete(&change, post.as_ref()).await?;
+                    report.pushed.extend(post);
+                }
+                Ok(Outcome::Deferred) => report.conflicts.push(change),
+                Err(err) if err.is_transient() => {
+                    debug!("server unreachable, keeping queued changes: {err}");
+                    report.offline = true;
+                    break;
+                }
+                Err(err) => {
+                    warn!("server rejected queued change to post {}: {err}", change.post_id);
+                    self.store.forget(&change).await?;
+                    report.failed.push(SyncFailure {
+                        change,
+                        error: err.to_string(),
+                    });
+                }
+            }
+        }
+
+        if !report.offline {
+            match self.pull().await {
+                Ok(pulled) => report.pulled = pulled,
+                Err(err) if err.is_transient() => report.offline = true,
+                Err(err) => return Err(err),
+            }
+        }
+
+        report.pending = self.store.pending().await?.len();
+        Ok(report)
+    }
+
+    /// Sends one change; returns the saved post, or `None` for deletes.
+    async fn push(
+        &self,
+        change: &PendingChange,
+        expected_updated_at: Option<DateTime<Utc>>,
+        edit: &PostEdit,
+    ) -> Result<Option<Post>, BlogClientError> {
+        let PostEdit { title, content, tags } = edit.clone();
+
+        match (change.kind, expected_updated_at) {
+            (ChangeKind::Create, _) => self.client.create_post(title, content, tags).await.map(Some),
+            (ChangeKind::Update, Some(expected)) => self
+                .client
+                .update_post_if_unchanged(change.post_id, title, content, Some(tags), expected)
+                .await
+                .map(Some),
+            (ChangeKind::Update, None) => self
+                .client
+                .update_post(change.post_id, title, content, Some(tags))
+                .await
+                .map(Some),
+            (ChangeKind::Delete, _) => match self.client.delete_post(change.post_id).await {
+                Err(err) if err.code() == Some(ErrorCode::PostNotFound) => Ok(None),
+                result => result.map(|()| None),
+            },
+        }
+    }
+
+    /// Settles a conflicting update with the configured resolver.
+    async fn resolve(&self, change: &PendingChange) -> Result<Outcome, BlogClientError> {
+        let server = self.client.get_post(change.post_id).await?;
+        let resolution = self.resolver.resolve(Conflict {
+            local: change,
+            server: &server,
+        });
+        debug!("conflict on post {} resolved as {resolution:?}", change.post_id);
+
+        let edit = match resolution {
+            Resolution::KeepServer => return Ok(Outcome::Saved(Some(server))),
+            Resolution::KeepLocal => change.edit.clone(),
+            Resolution::Merge(edit) => edit,
+            Resolution::Defer => return Ok(Outcome::Deferred),
+        };
+
+        match self.push(change, Some(server.updated_at), &edit).await {
+            // Edited again on the server while resolving; try on the next sync.
+            Err(err) if err.code() == Some(ErrorCode::Conflict) => Ok(Outcome::Deferred),
+            result => result.map(Outcome::Saved),
+        }
+    }
+
+    /// Replaces the local copy with the server's posts, keeping unsynced ones.
+    async fn pull(&self) -> Result<usize, BlogClientError> {
+        let mut posts = Vec::new();
+        let mut offset = Some(0);
+
+        while let Some(current) = offset {
+            let page = self.client.list_posts(PULL_PAGE_SIZE, current, None).await?;
+            offset = page.next_offset();
+            posts.extend(page.items);
+        }
+
+        self.store.replace_synced(&posts).await?;
+        self.store.count().await
+    }
+}
+
+/// Result of sending a queued change.
+// Short-lived and never stored, so boxing the post is not worth it.
+#[allow(clippy::large_enum_variant)]
+enum Outcome {
+    /// The server applied the change; holds the saved post, `None` for deletes.
+    Saved(Option<Post>),
+
+    /// The change conflicts and stays queued.
+    Deferred,
+}
+
+/// Returns the editable fields of `post`.
+fn edit_of(post: &Post) -> PostEdit {
+    PostEdit {
+        title: post.title.clone(),
+        content: post.content.clone(),
+        tags: post.tags.clone(),
+    }
+}
+
+/// SQLite file holding the local posts and the change queue.
+struct LocalStore {
+    pool: SqlitePool,
+}
+
+impl LocalStore {
+    async fn open(path: &Path) -> Result<Self, BlogClientError> {
+        let options = SqliteConnectOptions::new()
+            .filename(path)
+            .create_if_missing(true);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .map_err(storage)?;
+
+        sqlx::raw_sql(SCHEMA).execute(&pool).await.map_err(storage)?;
+        Ok(Self { pool })
+    }
+
+    async fn posts(&self) -> Result<Vec<Post>, BlogClientError> {
+        let rows: Vec<String> = sqlx::query_scalar(
+            "SELECT body FROM posts ORDER BY json_extract(body, '$.created_at') DESC",
+        )
+        .fetch_all(&self.pool)
+        .await
+        .map_err(storage)?;
+
+        rows.iter()
+            .map(|body| serde_json::from_str(body).map_err(BlogClientError::from))
+            .collect()
+    }
+
+    async fn post(&self, id: Uuid) -> Result<Option<Post>, BlogClientError> {
+        let row: Option<String> = sqlx::query_scalar("SELECT body FROM posts WHERE id = ?")
+            .bind(id.to_string())
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(storage)?;
+
+        Ok(row.map(|body| serde_json::from_str(&body)).transpose()?)
+    }
+
+    async fn count(&self) -> Result<usize, BlogClientError> {
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM posts")
+            .fetch_one(&self.pool)
+            .await
+            .map_err(storage)?;
+
+        Ok(count as usize)
+    }
+
+    async fn pending(&self) -> Result<Vec<PendingChange>, BlogClientError> {
+        let rows: Vec<String> = sqlx::query_scalar("SELECT body FROM pending_changes ORDER BY seq")
+            .fetch_all(&self.pool)
+            .await
+            .map_err(storage)?;
+
+        rows.iter()
+            .map(|body| serde_json::from_str(body).map_err(BlogClientError::from))
+            .collect()
+    }
+
+    async fn pending_for(&self, post_id: Uuid) -> Result<Option<PendingChange>, BlogClientError> {
+        let row: Option<String> =
+            sqlx::query_scalar("SELECT body FROM pending_changes WHERE post_id = ?")
+                .bind(post_id.to_string())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(storage)?;
+
+        Ok(row.map(|body| serde_json::from_str(&body)).transpose()?)
+    }
+
+    /// Stores `change`, keeping the queue position of an earlier change to the
+    /// same post, and writes or removes the local post.
+    async fn queue(&self, change: &PendingChange, post: Option<&Post>) -> Result<(), BlogClientError> {
+        let id = change.post_id.to_string();
+        let mut tx = self.pool.begin().await.map_err(storage)?;
+
+        sqlx::query(
+            "INSERT INTO pending_changes (post_id, body) VALUES (?, ?)
+             ON CONFLICT (post_id) DO UPDATE SET body = excluded.body",
+        )
+        .bind(&id)
+        .bind(serde_json::to_string(change)?)
+        .execute(&mut *tx)
+        .await
+        .map_err(storage)?;
+
+        match post {
+            Some(post) => {
+                sqlx::query("INSERT OR REPLACE INTO posts (id, body) VALUES (?, ?)")
+                    .bind(&id)
+                    .bind(serde_json::to_string(post)?)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(storage)?;
+            }
+            None => {
+                sqlx::query("DELETE FROM posts WHERE id = ?")
+                    .bind(&id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(storage)?;
+            }
+        }
+
+        tx.commit().await.map_err(storage)
+    }
+
+    /// Removes a synced change, storing the server's version of the post.
+    async fn complete(&self, change: &PendingChange, post: Option<&Post>) -> Result<(), BlogClientError> {
+        let mut tx = self.pool.begin().await.map_err(storage)?;
+
+        sqlx::query("DELETE FROM pending_changes WHERE post_id = ?")
+            .bind(change.post_id.to_string())
+            .execute(&mut *tx)
+            .await
+            .map_err(storage)?;
+
+        // Creates are stored under a local ID the server replaced.
+        sqlx::query("DELETE FROM posts WHERE id = ?")
+            .bind(change.post_id.to_string())
+            .execute(&mut *tx)
+            .await
+            .map_err(storage)?;
+
+        if let Some(post) = post {
+            sqlx::query("INSERT OR REPLACE INTO posts (id, body) VALUES (?, ?)")
+                .bind(post.id.to_string())
+                .bind(serde_json::to_string(post)?)
+                .execute(&mut *tx)
+                .await
+                .map_err(storage)?;
+        }
+
+        tx.commit().await.map_err(storage)
+    }
+
+    /// Drops a change; a post only created locally is dropped with it, others
+    /// are restored by the next pull.
+    async fn forget(&self, change: &PendingChange) -> Result<(), BlogClientError> {
+        let mut tx = self.pool.begin().await.map_err(storage)?;
+
+        sqlx::query("DELETE FROM pending_changes WHERE post_id = ?")
+            .bind(change.post_id.to_string())
+            .execute(&mut *tx)
+            .await
+            .map_err(storage)?;
+
+        if change.kind == ChangeKind::Create {
+            sqlx::query("DELETE FROM posts WHERE id = ?")
+                .bind(change.post_id.to_string())
+                .execute(&mut *tx)
+                .await
+                .map_err(storage)?;
+        }
+
+        tx.commit().await.map_err(storage)
+    }
+
+    /// Replaces every post without a queued change with the server's `posts`.
+    async fn replace_synced(&self, posts: &[Post]) -> Result<(), BlogClientError> {
+        let mut tx = self.pool.begin().await.map_err(storage)?;
+
+        sqlx::query("DELETE FROM posts WHERE id NOT IN (SELECT post_id FROM pending_changes)")
+            .execute(&mut *tx)
+            .await
+            .map_err(storage)?;
+
+        for post in posts {
+            let id = post.id.to_string();
+            sqlx::query(
+                "INSERT OR IGNORE INTO posts (id, body)
+                 SELECT ?, ? WHERE NOT EXISTS (SELECT 1 FROM pending_changes WHERE post_id = ?)",
+            )
+            .bind(&id)
+            .bind(serde_json::to_string(post)?)
+            .bind(&id)
+            .execute(&mut *tx)
+            .await
+            .map_err(storage)?;
+        }
+
+        tx.commit().await.map_err(storage)
+    }
+}
+
+fn storage(err: sqlx::Error) -> BlogClientError {
+    BlogClientError::Storage(err.to_string())
+}
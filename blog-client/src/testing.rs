@@ -0,0 +1,757 @@
+use std::collections::BTreeMap;
+
+use blog_core::error::ErrorBody;
+use chrono::{DateTime, NaiveDate, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+use wiremock::matchers::{method, path_regex};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use crate::error::{BlogClientError, ErrorCode};
+use crate::http_client::{DEFAULT_API_PREFIX, HttpClient};
+use crate::models::{
+    AuthResponse, Author, BlogEvent, BuildInfo, Comment, CommentStatus, DailyViews,
+    DependencyHealth, EventKind, Health, ImportSummary, Media, MediaUpload, MediaVariants, Post,
+    PostRevision, PostStats, Profile, PublicUser, Role, SearchResult, Tag, User, UserAccount, Visibility,
+};
+use crate::{BlogClient, Transport};
+
+/// Access token returned by the canned login and refresh mappings.
+pub const TEST_ACCESS_TOKEN: &str = "test-access-token";
+
+/// Refresh token returned by the canned login and refresh mappings.
+pub const TEST_REFRESH_TOKEN: &str = "test-refresh-token";
+
+/// Request ID carried by canned error bodies.
+pub const TEST_REQUEST_ID: &str = "test-request-id";
+
+/// Priority of the mappings mounted by [`MockBlogServer::mount_defaults`].
+///
+/// Lower than wiremock's default, so mocks mounted afterwards win over them.
+const DEFAULT_PRIORITY: u8 = 10;
+
+/// Largest chunk the canned upload mappings accept, in bytes.
+const TEST_MAX_CHUNK_BYTES: u64 = 1024 * 1024;
+
+/// Header carrying the total number of items a paged list spans.
+const TOTAL_COUNT_HEADER: &str = "x-total-count";
+
+/// Timestamp every fixture uses unless told otherwise: 2024-01-01T00:00:00Z.
+pub fn fixture_time() -> DateTime<Utc> {
+    DateTime::from_timestamp(1_704_067_200, 0).unwrap_or_default()
+}
+
+/// HTTP endpoint of the blog server, as called by [`HttpClient`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Endpoint {
+    /// `GET /healthz`
+    Health,
+    /// `GET /readyz`
+    Readiness,
+    /// `GET /public/version`
+    Version,
+    /// `POST /public/auth/register`
+    Register,
+    /// `POST /public/auth/login`
+    Login,
+    /// `POST /public/auth/refresh`
+    Refresh,
+    /// `POST /protected/posts`
+    CreatePost,
+    /// `GET /protected/posts`
+    ListPosts,
+    /// `GET /protected/posts/{id}`
+    GetPost,
+    /// `PUT /protected/posts/{id}`
+    UpdatePost,
+    /// `DELETE /protected/posts/{id}`
+    DeletePost,
+    /// `GET /protected/posts/{id}/revisions`
+    ListRevisions,
+    /// `GET /protected/posts/{id}/revisions/{revision_id}`
+    GetRevision,
+    /// `POST /protected/posts/{id}/revisions/{revision_id}/restore`
+    RestoreRevision,
+    /// `GET /public/posts/search`
+    SearchPosts,
+    /// `GET /protected/tags`
+    ListTags,
+    /// `GET /protected/posts/{id}/comments`
+    ListComments,
+    /// `POST /protected/posts/{id}/comments`
+    AddComment,
+    /// `DELETE /protected/comments/{id}`
+    DeleteComment,
+    /// `GET /protected/analytics/posts`
+    ListPostStats,
+    /// `GET /protected/analytics/posts/{id}`
+    GetPostStats,
+    /// `GET /protected/admin/users`
+    ListUsers,
+    /// `POST /protected/admin/users/{id}/disable`
+    DisableUser,
+    /// `PUT /protected/admin/users/{id}/role`
+    SetUserRole,
+    /// `POST /protected/admin/import`
+    ImportPosts,
+    /// `GET /protected/media`
+    ListMedia,
+    /// `POST /protected/media`
+    UploadMedia,
+    /// `DELETE /protected/media/{id}`
+    DeleteMedia,
+    /// `POST /protected/media/uploads`
+    StartMediaUpload,
+    /// `GET /protected/media/uploads/{id}`
+    GetMediaUpload,
+    /// `PUT /protected/media/uploads/{id}`
+    UploadMediaChunk,
+    /// `POST /protected/media/uploads/{id}/complete`
+    CompleteMediaUpload,
+    /// `DELETE /protected/media/uploads/{id}`
+    CancelMediaUpload,
+    /// `GET /protected/events`
+    Events,
+}
+
+impl Endpoint {
+    /// Every endpoint, in the order [`HttpClient`] declares them.
+    pub const ALL: [Endpoint; 34] = [
+        Endpoint::Health,
+        Endpoint::Readiness,
+        Endpoint::Version,
+        Endpoint::Register,
+        Endpoint::Login,
+        Endpoint::Refresh,
+        Endpoint::CreatePost,
+        Endpoint::ListPosts,
+        Endpoint::GetPost,
+        Endpoint::UpdatePost,
+        Endpoint::DeletePost,
+        Endpoint::ListRevisions,
+        Endpoint::GetRevision,
+        Endpoint::RestoreRevision,
+        Endpoint::SearchPosts,
+        Endpoint::ListTags,
+        Endpoint::ListComments,
+        Endpoint::AddComment,
+        Endpoint::DeleteComment,
+        Endpoint::ListPostStats,
+        Endpoint::GetPostStats,
+        Endpoint::ListUsers,
+        Endpoint::DisableUser,
+        Endpoint::SetUserRole,
+        Endpoint::ImportPosts,
+        Endpoint::ListMedia,
+        Endpoint::UploadMedia,
+        Endpoint::DeleteMedia,
+        Endpoint::StartMediaUpload,
+        Endpoint::GetMediaUpload,
+        Endpoint::UploadMediaChunk,
+        Endpoint::CompleteMediaUpload,
+        Endpoint::CancelMediaUpload,
+        Endpoint::Events,
+    ];
+
+    /// Returns the HTTP method of the endpoint.
+    pub fn method(self) -> &'static str {
+        match self {
+            Endpoint::Health
+            | Endpoint::Readiness
+            | Endpoint::Version
+            | Endpoint::ListPosts
+            | Endpoint::GetPost
+            | Endpoint::ListRevisions
+            | Endpoint::GetRevision
+            | Endpoint::SearchPosts
+            | Endpoint::ListTags
+            | Endpoint::ListComments
+            | Endpoint::ListPostStats
+            | Endpoint::GetPostStats
+            | Endpoint::ListUsers
+            | Endpoint::ListMedia
+            | Endpoint::GetMediaUpload
+            | Endpoint::Events => "GET",
+            Endpoint::Register
+            | Endpoint::Login
+            | Endpoint::Refresh
+            | Endpoint::CreatePost
+            | Endpoint::RestoreRevision
+            | Endpoint::AddComment
+            | Endpoint::DisableUser
+            | Endpoint::ImportPosts
+            | Endpoint::UploadMedia
+            | Endpoint::StartMediaUpload
+            | Endpoint::CompleteMediaUpload => "POST",
+            Endpoint::UpdatePost | Endpoint::SetUserRole | Endpoint::UploadMediaChunk => "PUT",
+            Endpoint::DeletePost
+            | Endpoint::DeleteComment
+            | Endpoint::DeleteMedia
+            | Endpoint::CancelMediaUpload => "DELETE",
+        }
+    }
+
+    /// Returns a regex matching the endpoint's path under the default API prefix.
+    pub fn path_pattern(self) -> String {
+        let path = match self {
+            Endpoint::Health => return "^/healthz$".to_string(),
+            Endpoint::Readiness => return "^/readyz$".to_string(),
+            Endpoint::Version => "/public/version",
+            Endpoint::Register => "/public/auth/register",
+            Endpoint::Login => "/public/auth/login",
+            Endpoint::Refresh => "/public/auth/refresh",
+            Endpoint::CreatePost | Endpoint::ListPosts => "/protected/posts",
+            Endpoint::GetPost | Endpoint::UpdatePost | Endpoint::DeletePost => {
+                "/protected/posts/[^/]+"
+            }
+            Endpoint::ListRevisions => "/protected/posts/[^/]+/revisions",
+            Endpoint::GetRevision => "/protected/posts/[^/]+/revisions/[^/]+",
+            Endpoint::RestoreRevision => "/protected/posts/[^/]+/revisions/[^/]+/restore",
+            Endpoint::SearchPosts => "/public/posts/search",
+            Endpoint::ListTags => "/protected/tags",
+            Endpoint::ListComments | Endpoint::AddComment => "/protected/posts/[^/]+/comments",
+            Endpoint::DeleteComment => "/protected/comments/[^/]+",
+            Endpoint::ListPostStats => "/protected/analytics/posts",
+            Endpoint::GetPostStats => "/protected/analytics/posts/[^/]+",
+            Endpoint::ListUsers => "/protected/admin/users",
+            Endpoint::DisableUser => "/protected/admin/users/[^/]+/disable",
+            Endpoint::SetUserRole => "/protected/admin/users/[^/]+/role",
+            Endpoint::ImportPosts => "/protected/admin/import",
+            Endpoint::ListMedia | Endpoint::UploadMedia => "/protected/media",
+            Endpoint::DeleteMedia => "/protected/media/[^/]+",
+            Endpoint::StartMediaUpload => "/protected/media/uploads",
+            Endpoint::GetMediaUpload | Endpoint::UploadMediaChunk | Endpoint::CancelMediaUpload => {
+                "/protected/media/uploads/[^/]+"
+            }
+            Endpoint::CompleteMediaUpload => "/protected/media/uploads/[^/]+/complete",
+            Endpoint::Events => "/protected/events",
+        };
+
+        format!("^{}{}$", DEFAULT_API_PREFIX, path)
+    }
+
+    /// Returns a mock of the endpoint answering with `response`.
+    pub fn respond_with(self, response: ResponseTemplate) -> Mock {
+        Mock::given(method(self.method()))
+            .and(path_regex(self.path_pattern()))
+            .respond_with(response)
+    }
+
+    /// Returns a mock of the endpoint answering like the server does on success,
+    /// with fixture bodies.
+    pub fn success(self) -> Mock {
+        self.respond_with(self.success_response())
+    }
+
+    /// Returns a mock of the endpoint failing with `code`, using the server's
+    /// status and error body for it.
+    pub fn failure(self, code: ErrorCode) -> Mock {
+        self.respond_with(error_response(code))
+    }
+
+    /// Builds the canned success response of the endpoint.
+    fn success_response(self) -> ResponseTemplate {
+        let post = PostBuilder::default().build();
+        let user = UserBuilder::default();
+
+        match self {
+            Endpoint::Health | Endpoint::Readiness => json(200, &health()),
+            Endpoint::Version => json(200, &build_info()),
+            Endpoint::Register => {
+                let user = user.build();
+                json(
+                    201,
+                    &serde_json::json!({
+                        "user_id": user.id,
+                        "username": user.username,
+                        "email": user.email,
+                    }),
+                )
+            }
+            Endpoint::Login | Endpoint::Refresh => json(
+                200,
+                &AuthResponse {
+                    access_token: Some(TEST_ACCESS_TOKEN.to_string()),
+                    refresh_token: Some(TEST_REFRESH_TOKEN.to_string()),
+                    user: None,
+                },
+            ),
+            Endpoint::CreatePost
+            | Endpoint::GetPost
+            | Endpoint::UpdatePost
+            | Endpoint::RestoreRevision => json(200, &post),
+            Endpoint::DeletePost | Endpoint::DeleteComment => json(200, &"{}"),
+            Endpoint::ListPosts => page(&[post]),
+            Endpoint::ListRevisions => json(200, &[revision(post.id)]),
+            Endpoint::GetRevision => json(200, &revision(post.id)),
+            Endpoint::SearchPosts => page(&[SearchResult {
+                snippet: post.content.clone(),
+                post,
+                rank: 1.0,
+            }]),
+            Endpoint::ListTags => json(
+                200,
+                &[Tag {
+                    name: "rust".to_string(),
+                    post_count: 1,
+                }],
+            ),
+            Endpoint::ListComments => page(&[comment(post.id)]),
+            Endpoint::AddComment => json(200, &comment(post.id)),
+            Endpoint::ListPostStats => json(200, &[stats(&post)]),
+            Endpoint::GetPostStats => json(200, &stats(&post)),
+            Endpoint::ListUsers => json(200, &[user.account()]),
+            Endpoint::SetUserRole => json(200, &user.account()),
+            Endpoint::DisableUser => {
+                let mut account = user.account();
+                account.disabled_at = Some(fixture_time());
+                json(200, &account)
+            }
+            Endpoint::ImportPosts => json(
+                200,
+                &ImportSummary {
+                    authors: Vec::new(),
+                    imported: Vec::new(),
+                    skipped: Vec::new(),
+                    images_imported: 0,
+                    image_failures: Vec::new(),
+                    warnings: Vec::new(),
+                },
+            ),
+            Endpoint::ListMedia => json(200, &[media()]),
+            Endpoint::UploadMedia | Endpoint::CompleteMediaUpload => json(201, &media()),
+            Endpoint::StartMediaUpload => json(201, &upload()),
+            Endpoint::GetMediaUpload | Endpoint::UploadMediaChunk => json(200, &upload()),
+            Endpoint::DeleteMedia | Endpoint::CancelMediaUpload => ResponseTemplate::new(204),
+            Endpoint::Events => {
+                let event = BlogEvent {
+                    id: 1,
+                    at: fixture_time(),
+                    kind: EventKind::PostCreated {
+                        post_id: post.id,
+                        author_id: post.author_id,
+                        title: post.title,
+                    },
+                };
+                let data = serde_json::to_string(&event).unwrap_or_default();
+
+                ResponseTemplate::new(200)
+                    .set_body_raw(format!("id: 1\ndata: {data}\n\n"), "text/event-stream")
+            }
+        }
+    }
+}
+
+/// Returns the HTTP status the server answers `code` with.
+pub fn error_status(code: ErrorCode) -> u16 {
+    match code {
+        ErrorCode::ValidationFailed | ErrorCode::EmailTaken | ErrorCode::UsernameTaken => 400,
+        ErrorCode::InvalidCredentials => 401,
+        ErrorCode::Forbidden | ErrorCode::QuotaExceeded => 403,
+        ErrorCode::UserNotFound
+        | ErrorCode::PostNotFound
+        | ErrorCode::CommentNotFound
+        | ErrorCode::MediaNotFound
+        | ErrorCode::ExportNotFound
+        | ErrorCode::MentionNotFound => 404,
+        ErrorCode::Conflict => 409,
+        ErrorCode::PayloadTooLarge => 413,
+        ErrorCode::RateLimited => 429,
+        ErrorCode::Internal | ErrorCode::Unknown => 500,
+    }
+}
+
+/// Builds the server's error response for `code`.
+pub fn error_response(code: ErrorCode) -> ResponseTemplate {
+    let message = format!("canned {code} response");
+    let details = (code != ErrorCode::Internal).then(|| serde_json::json!({ "message": message }));
+
+    json(
+        error_status(code),
+        &ErrorBody {
+            error: message,
+            code,
+            request_id: Some(TEST_REQUEST_ID.to_string()),
+            details,
+        },
+    )
+    .insert_header("x-request-id", TEST_REQUEST_ID)
+}
+
+/// In-process stand-in for the blog server's HTTP API.
+///
+/// Starts empty: unmatched requests get `404` with no body. Mount canned
+/// mappings with [`mount_defaults`](Self::mount_defaults), and per-test ones
+/// with [`mount`](Self::mount).
+pub struct MockBlogServer {
+    server: MockServer,
+}
+
+impl MockBlogServer {
+    /// Starts a mock server on a random local port.
+    pub async fn start() -> Self {
+        Self {
+            server: MockServer::start().await,
+        }
+    }
+
+    /// Mounts the canned success mapping of every endpoint.
+    ///
+    /// They have a low priority, so mocks mounted afterwards override them.
+    pub async fn mount_defaults(&self) {
+        for endpoint in Endpoint::ALL {
+            self.server
+                .register(endpoint.success().with_priority(DEFAULT_PRIORITY))
+                .await;
+        }
+    }
+
+    /// Mounts a mock, e.g. from [`Endpoint::success`] or [`Endpoint::failure`].
+    pub async fn mount(&self, mock: Mock) {
+        self.server.register(mock).await;
+    }
+
+    /// Returns the base URL of the server.
+    pub fn uri(&self) -> String {
+        self.server.uri()
+    }
+
+    /// Returns an HTTP client pointed at the server.
+    pub fn http_client(&self) -> Result<HttpClient, BlogClientError> {
+        HttpClient::new(self.uri())
+    }
+
+    /// Returns a blog client using the HTTP transport against the server.
+    pub async fn client(&self) -> Result<BlogClient, BlogClientError> {
+        BlogClient::new(Transport::Http(self.uri())).await
+    }
+
+    /// Returns the underlying wiremock server, e.g. to inspect received requests.
+    pub fn inner(&self) -> &MockServer {
+        &self.server
+    }
+}
+
+/// Builder of [`Post`] fixtures.
+#[derive(Debug, Clone)]
+pub struct PostBuilder {
+    post: Post,
+}
+
+impl Default for PostBuilder {
+    fn default() -> Self {
+        Self {
+            post: Post {
+                id: Uuid::new_v4(),
+                author_id: Uuid::new_v4(),
+                author: None,
+                title: "Test post".to_string(),
+                slug: "test-post".to_string(),
+                content: "Test content.".to_string(),
+                tags: Vec::new(),
+                visibility: Visibility::Public,
+                created_at: fixture_time(),
+                updated_at: fixture_time(),
+                deleted_at: None,
+                locale: None,
+            },
+        }
+    }
+}
+
+impl PostBuilder {
+    /// Starts a public post with a random ID and author.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the post ID.
+    pub fn id(mut self, id: Uuid) -> Self {
+        self.post.id = id;
+        self
+    }
+
+    /// Sets the author, embedding their username in the post.
+    pub fn author(mut self, id: Uuid, username: &str) -> Self {
+        self.post.author_id = id;
+        self.post.author = Some(Author {
+            id,
+            username: username.to_string(),
+        });
+        self
+    }
+
+    /// Sets the title.
+    pub fn title(mut self, title: &str) -> Self {
+        self.post.title = title.to_string();
+        self
+    }
+
+    /// Sets the permalink slug.
+    pub fn slug(mut self, slug: &str) -> Self {
+        self.post.slug = slug.to_string();
+        self
+    }
+
+    /// Sets the content.
+    pub fn content(mut self, content: &str) -> Self {
+        self.post.content = content.to_string();
+        self
+    }
+
+    /// Sets the tags.
+    pub fn tags<I, S>(mut self, tags: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.post.tags = tags.into_iter().map(Into::into).collect();
+        self
+    }
+
+    /// Sets who may read the post.
+    pub fn visibility(mut self, visibility: Visibility) -> Self {
+        self.post.visibility = visibility;
+        self
+    }
+
+    /// Sets the creation timestamp; the update timestamp follows unless set later.
+    pub fn created_at(mut self, at: DateTime<Utc>) -> Self {
+        self.post.created_at = at;
+        self.post.updated_at = at;
+        self
+    }
+
+    /// Sets the last modification timestamp.
+    pub fn updated_at(mut self, at: DateTime<Utc>) -> Self {
+        self.post.updated_at = at;
+        self
+    }
+
+    /// Returns the post.
+    pub fn build(self) -> Post {
+        self.post
+    }
+}
+
+/// Builder of user fixtures, as [`User`], [`Profile`], [`PublicUser`] or [`UserAccount`].
+#[derive(Debug, Clone)]
+pub struct UserBuilder {
+    id: Uuid,
+    username: String,
+    email: String,
+    bio: Option<String>,
+    role: Role,
+    created_at: DateTime<Utc>,
+}
+
+impl Default for UserBuilder {
+    fn default() -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            username: "test-user".to_string(),
+            email: "test-user@example.com".to_string(),
+            bio: None,
+            role: Role::User,
+            created_at: fixture_time(),
+        }
+    }
+}
+
+impl UserBuilder {
+    /// Starts a regular user with a random ID.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the user ID.
+    pub fn id(mut self, id: Uuid) -> Self {
+        self.id = id;
+        self
+    }
+
+    /// Sets the username.
+    pub fn username(mut self, username: &str) -> Self {
+        self.username = username.to_string();
+        self
+    }
+
+    /// Sets the email address.
+    pub fn email(mut self, email: &str) -> Self {
+        self.email = email.to_string();
+        self
+    }
+
+    /// Sets the bio.
+    pub fn bio(mut self, bio: &str) -> Self {
+        self.bio = Some(bio.to_string());
+        self
+    }
+
+    /// Sets the role.
+    pub fn role(mut self, role: Role) -> Self {
+        self.role = role;
+        self
+    }
+
+    /// Sets the registration timestamp.
+    pub fn created_at(mut self, at: DateTime<Utc>) -> Self {
+        self.created_at = at;
+        self
+    }
+
+    /// Returns the user as embedded in auth responses.
+    pub fn build(self) -> User {
+        User {
+            id: self.id,
+            username: self.username,
+            email: self.email,
+        }
+    }
+
+    /// Returns the user as their own profile.
+    pub fn profile(self) -> Profile {
+        Profile {
+            id: self.id,
+            username: self.username,
+            email: self.email,
+            bio: self.bio,
+            role: self.role,
+            created_at: self.created_at,
+        }
+    }
+
+    /// Returns the user as other users see them.
+    pub fn public(self) -> PublicUser {
+        PublicUser {
+            id: self.id,
+            username: self.username,
+            bio: self.bio,
+            created_at: self.created_at,
+        }
+    }
+
+    /// Returns the user as the admin API lists them.
+    pub fn account(self) -> UserAccount {
+        UserAccount {
+            id: self.id,
+            username: self.username,
+            email: self.email,
+            role: self.role.to_string(),
+            created_at: self.created_at,
+            disabled_at: None,
+        }
+    }
+}
+
+/// Builds a JSON response.
+fn json<T: Serialize + ?Sized>(status: u16, body: &T) -> ResponseTemplate {
+    ResponseTemplate::new(status).set_body_json(body)
+}
+
+/// Builds a JSON list response carrying its total count.
+fn page<T: Serialize>(items: &[T]) -> ResponseTemplate {
+    json(200, items).insert_header(TOTAL_COUNT_HEADER, items.len().to_string().as_str())
+}
+
+/// Returns a healthy server status.
+fn health() -> Health {
+    Health {
+        status: "ok".to_string(),
+        version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        checks: BTreeMap::from([(
+            "database".to_string(),
+            DependencyHealth {
+                status: "ok".to_string(),
+                latency_ms: 1,
+                error: None,
+            },
+        )]),
+    }
+}
+
+/// Returns the build details of a test server.
+fn build_info() -> BuildInfo {
+    BuildInfo {
+        version: env!("CARGO_PKG_VERSION").to_string(),
+        git_sha: "0123456789ab".to_string(),
+        build_timestamp: Some(fixture_time()),
+        features: Vec::new(),
+    }
+}
+
+/// Returns an earlier version of a post.
+fn revision(post_id: Uuid) -> PostRevision {
+    PostRevision {
+        id: Uuid::new_v4(),
+        post_id,
+        title: "Earlier title".to_string(),
+        content: "Earlier content.".to_string(),
+        tags: Vec::new(),
+        edited_at: fixture_time(),
+        created_at: fixture_time(),
+    }
+}
+
+/// Returns a published top-level comment on a post.
+fn comment(post_id: Uuid) -> Comment {
+    Comment {
+        id: Uuid::new_v4(),
+        post_id,
+        author_id: Uuid::new_v4(),
+        parent_id: None,
+        content: "Test comment.".to_string(),
+        status: CommentStatus::Published,
+        created_at: fixture_time(),
+    }
+}
+
+/// Returns the stats of a post for a single day.
+fn stats(post: &Post) -> PostStats {
+    PostStats {
+        post_id: post.id,
+        title: post.title.clone(),
+        views: 1,
+        likes: 0,
+        bookmarks: 0,
+        comments: 0,
+        daily_views: vec![DailyViews {
+            day: NaiveDate::from_ymd_opt(2024, 1, 1).unwrap_or_default(),
+            views: 1,
+        }],
+        referrers: None,
+        user_agents: None,
+    }
+}
+
+/// Returns an uploaded image.
+fn media() -> Media {
+    let id = Uuid::new_v4();
+    let url = format!("{}/public/media/{}", DEFAULT_API_PREFIX, id);
+
+    Media {
+        id,
+        variants: MediaVariants {
+            thumbnail: format!("{url}/thumbnail"),
+            medium: format!("{url}/medium"),
+            original: format!("{url}/original"),
+        },
+        url,
+        filename: "image.png".to_string(),
+        content_type: "image/png".to_string(),
+        size: 1024,
+        created_at: fixture_time(),
+    }
+}
+
+/// Returns a chunked upload that has not received any bytes yet.
+fn upload() -> MediaUpload {
+    MediaUpload {
+        id: Uuid::new_v4(),
+        filename: "image.png".to_string(),
+        size: 1024,
+        offset: 0,
+        max_chunk_bytes: TEST_MAX_CHUNK_BYTES,
+        created_at: fixture_time(),
+    }
+}
@@ -0,0 +1,76 @@
+use std::sync::Arc;
+
+use futures_util::stream;
+use reqwest::Body;
+use tokio::sync::watch;
+
+/// Size of the pieces request bodies are streamed in, and so how often
+/// progress is reported.
+const STREAM_PIECE_BYTES: usize = 64 * 1024;
+
+/// How much of an upload has been sent.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct UploadProgress {
+    /// Bytes sent so far.
+    pub sent: u64,
+    /// Size of the whole file in bytes.
+    pub total: u64,
+}
+
+impl UploadProgress {
+    /// Returns whether every byte has been sent.
+    pub fn is_done(&self) -> bool {
+        self.sent >= self.total
+    }
+}
+
+/// Reports the progress of an upload to whoever holds the paired receiver.
+///
+/// Awaiting [`watch::Receiver::changed`] yields the latest progress only, so
+/// slow consumers such as progress bars never hold up the upload.
+#[derive(Debug, Clone)]
+pub struct ProgressReporter {
+    tx: Arc<watch::Sender<UploadProgress>>,
+}
+
+impl ProgressReporter {
+    /// Creates a reporter and the receiver its progress is sent to.
+    pub fn new() -> (Self, watch::Receiver<UploadProgress>) {
+        let (tx, rx) = watch::channel(UploadProgress::default());
+        (Self { tx: Arc::new(tx) }, rx)
+    }
+
+    /// Publishes that `sent` of `total` bytes have been sent.
+    pub(crate) fn report(&self, sent: u64, total: u64) {
+        self.tx.send_replace(UploadProgress { sent, total });
+    }
+}
+
+/// Builds a request body streaming `bytes`, reporting progress as pieces
+/// are handed to the connection.
+///
+/// `base` is the number of bytes of the file sent before this body, so a
+/// chunk of a larger file reports progress through the whole file.
+pub(crate) fn progress_body(
+    bytes: &[u8],
+    base: u64,
+    total: u64,
+    progress: Option<&ProgressReporter>,
+) -> Body {
+    let Some(progress) = progress.cloned() else {
+        return Body::from(bytes.to_vec());
+    };
+
+    let pieces: Vec<Vec<u8>> = bytes
+        .chunks(STREAM_PIECE_BYTES)
+        .map(<[u8]>::to_vec)
+        .collect();
+    let mut sent = base;
+    let stream = stream::iter(pieces.into_iter().map(move |piece| {
+        sent += piece.len() as u64;
+        progress.report(sent, total);
+        Ok::<_, std::io::Error>(piece)
+    }));
+
+    Body::wrap_stream(stream)
+}
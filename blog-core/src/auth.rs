@@ -0,0 +1,150 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// User registration request payload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RegisterRequest {
+    /// Desired username.
+    pub username: String,
+
+    /// User email address.
+    pub email: String,
+
+    /// User plaintext password.
+    pub password: String,
+}
+
+/// User login request payload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginRequest {
+    /// User email address.
+    pub email: String,
+
+    /// User plaintext password.
+    pub password: String,
+}
+
+/// Password change request payload.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PasswordChange {
+    /// Password the user currently logs in with.
+    pub current_password: String,
+
+    /// Replacement password.
+    pub new_password: String,
+}
+
+/// Tokens issued by a login or token refresh.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+pub struct AuthTokens {
+    /// Access token.
+    pub access_token: String,
+
+    /// Single-use token for `POST /auth/refresh`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub refresh_token: Option<String>,
+}
+
+/// How a login or refresh in cookie mode hands the session to the client.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+pub struct CookieSession {
+    /// Token to echo in the `X-CSRF-Token` header of unsafe requests; also
+    /// readable from the `blog_csrf` cookie.
+    pub csrf_token: String,
+
+    /// Seconds until the access cookie expires.
+    pub expires_in: i64,
+}
+
+/// User role controlling access to administrative operations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// Regular blog author.
+    #[default]
+    User,
+
+    /// Administrator allowed to manage other users.
+    Admin,
+}
+
+impl Role {
+    /// Returns the role name as sent on the wire.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::User => "user",
+            Role::Admin => "admin",
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Role {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "user" => Ok(Role::User),
+            "admin" => Ok(Role::Admin),
+            other => Err(format!("unknown role `{other}`")),
+        }
+    }
+}
+
+/// Account of the authenticated user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+pub struct Profile {
+    /// Unique user identifier.
+    #[cfg_attr(feature = "wasm", tsify(type = "string"))]
+    pub id: Uuid,
+
+    /// User display name.
+    pub username: String,
+
+    /// User email address.
+    pub email: String,
+
+    /// Short self-description, if set.
+    pub bio: Option<String>,
+
+    /// User role.
+    pub role: Role,
+
+    /// Registration timestamp.
+    #[cfg_attr(feature = "wasm", tsify(type = "string"))]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Profile update request payload; omitted fields are left unchanged.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+pub struct ProfileUpdate {
+    /// New username.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub username: Option<String>,
+
+    /// New email address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub email: Option<String>,
+
+    /// New bio; an empty string clears it.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub bio: Option<String>,
+}
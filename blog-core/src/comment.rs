@@ -0,0 +1,93 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Whether a comment is shown on its post.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+#[serde(rename_all = "lowercase")]
+pub enum CommentStatus {
+    /// Shown on the post.
+    #[default]
+    Published,
+
+    /// Held in the moderation queue as suspected spam.
+    Flagged,
+}
+
+impl CommentStatus {
+    /// Returns the status name as sent on the wire.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            CommentStatus::Published => "published",
+            CommentStatus::Flagged => "flagged",
+        }
+    }
+}
+
+impl fmt::Display for CommentStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for CommentStatus {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "published" => Ok(CommentStatus::Published),
+            "flagged" => Ok(CommentStatus::Flagged),
+            other => Err(format!("unknown comment status `{other}`")),
+        }
+    }
+}
+
+/// Comment on a post.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+pub struct Comment {
+    /// Comment identifier.
+    #[cfg_attr(feature = "wasm", tsify(type = "string"))]
+    pub id: Uuid,
+
+    /// Commented post identifier.
+    #[cfg_attr(feature = "wasm", tsify(type = "string"))]
+    pub post_id: Uuid,
+
+    /// Comment author identifier.
+    #[cfg_attr(feature = "wasm", tsify(type = "string"))]
+    pub author_id: Uuid,
+
+    /// Parent comment identifier for replies.
+    #[cfg_attr(feature = "wasm", tsify(type = "string | null"))]
+    pub parent_id: Option<Uuid>,
+
+    /// Comment text.
+    pub content: String,
+
+    /// Whether the comment is shown or awaits moderation.
+    #[serde(default)]
+    pub status: CommentStatus,
+
+    /// Comment creation timestamp.
+    #[cfg_attr(feature = "wasm", tsify(type = "string"))]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Comment creation request payload.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CommentRequest {
+    /// Comment text.
+    pub content: String,
+
+    /// Comment being replied to, if any.
+    pub parent_id: Option<Uuid>,
+
+    /// Honeypot field hidden from people; bots that fill it in are rejected.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub website: Option<String>,
+}
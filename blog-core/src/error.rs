@@ -0,0 +1,118 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+/// Stable error code reported by the server.
+///
+/// Lets callers branch on the kind of failure without matching messages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// The server failed.
+    Internal,
+    /// The input was rejected.
+    ValidationFailed,
+    /// The email address is already registered.
+    EmailTaken,
+    /// The username is taken or too similar to an existing one.
+    UsernameTaken,
+    /// The user does not exist.
+    UserNotFound,
+    /// The post does not exist.
+    PostNotFound,
+    /// The comment does not exist.
+    CommentNotFound,
+    /// The media file does not exist.
+    MediaNotFound,
+    /// The data export does not exist or has expired.
+    ExportNotFound,
+    /// The webmention does not exist.
+    MentionNotFound,
+    /// The request body is too large.
+    PayloadTooLarge,
+    /// Too many requests of a kind were sent in a short time.
+    RateLimited,
+    /// The user would go over one of their content limits.
+    QuotaExceeded,
+    /// The resource changed since it was read.
+    Conflict,
+    /// The credentials are wrong.
+    InvalidCredentials,
+    /// The user is not allowed to do this.
+    Forbidden,
+    /// A code this version does not know.
+    #[serde(other)]
+    Unknown,
+}
+
+impl ErrorCode {
+    /// Parses a code as sent by the server; unknown codes map to [`ErrorCode::Unknown`].
+    pub fn parse(code: &str) -> Self {
+        match code {
+            "INTERNAL" => Self::Internal,
+            "VALIDATION_FAILED" => Self::ValidationFailed,
+            "EMAIL_TAKEN" => Self::EmailTaken,
+            "USERNAME_TAKEN" => Self::UsernameTaken,
+            "USER_NOT_FOUND" => Self::UserNotFound,
+            "POST_NOT_FOUND" => Self::PostNotFound,
+            "COMMENT_NOT_FOUND" => Self::CommentNotFound,
+            "MEDIA_NOT_FOUND" => Self::MediaNotFound,
+            "EXPORT_NOT_FOUND" => Self::ExportNotFound,
+            "MENTION_NOT_FOUND" => Self::MentionNotFound,
+            "PAYLOAD_TOO_LARGE" => Self::PayloadTooLarge,
+            "RATE_LIMITED" => Self::RateLimited,
+            "QUOTA_EXCEEDED" => Self::QuotaExceeded,
+            "CONFLICT" => Self::Conflict,
+            "INVALID_CREDENTIALS" => Self::InvalidCredentials,
+            "FORBIDDEN" => Self::Forbidden,
+            _ => Self::Unknown,
+        }
+    }
+
+    /// Returns the code as sent by the server.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Internal => "INTERNAL",
+            Self::ValidationFailed => "VALIDATION_FAILED",
+            Self::EmailTaken => "EMAIL_TAKEN",
+            Self::UsernameTaken => "USERNAME_TAKEN",
+            Self::UserNotFound => "USER_NOT_FOUND",
+            Self::PostNotFound => "POST_NOT_FOUND",
+            Self::CommentNotFound => "COMMENT_NOT_FOUND",
+            Self::MediaNotFound => "MEDIA_NOT_FOUND",
+            Self::ExportNotFound => "EXPORT_NOT_FOUND",
+            Self::MentionNotFound => "MENTION_NOT_FOUND",
+            Self::PayloadTooLarge => "PAYLOAD_TOO_LARGE",
+            Self::RateLimited => "RATE_LIMITED",
+            Self::QuotaExceeded => "QUOTA_EXCEEDED",
+            Self::Conflict => "CONFLICT",
+            Self::InvalidCredentials => "INVALID_CREDENTIALS",
+            Self::Forbidden => "FORBIDDEN",
+            Self::Unknown => "UNKNOWN",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// HTTP error response body.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ErrorBody {
+    /// Human-readable error message.
+    pub error: String,
+
+    /// Stable error code.
+    pub code: ErrorCode,
+
+    /// ID of the failed request, for matching server logs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+
+    /// Optional structured error details.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<serde_json::Value>,
+}
@@ -0,0 +1,20 @@
+//! Wire models shared by the blog server and its clients.
+//!
+//! The HTTP API sends and accepts these types as JSON; blog-server, blog-client
+//! and blog-wasm all use them, so a field changed here changes everywhere.
+//! With the `wasm` feature the models also derive TypeScript declarations.
+
+/// Registration, login and account payloads.
+pub mod auth;
+
+/// Comment payloads.
+pub mod comment;
+
+/// Error codes and the error response body.
+pub mod error;
+
+/// Uploaded media payloads.
+pub mod media;
+
+/// Post, search and tag payloads.
+pub mod post;
@@ -0,0 +1,69 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Uploaded media file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+pub struct Media {
+    /// Media identifier.
+    #[cfg_attr(feature = "wasm", tsify(type = "string"))]
+    pub id: Uuid,
+
+    /// Path the file is served from.
+    pub url: String,
+
+    /// Sanitized original file name.
+    pub filename: String,
+
+    /// MIME type detected from the file contents.
+    pub content_type: String,
+
+    /// File size in bytes.
+    pub size: u64,
+
+    /// Paths of the size variants.
+    pub variants: MediaVariants,
+
+    /// Upload timestamp.
+    #[cfg_attr(feature = "wasm", tsify(type = "string"))]
+    pub created_at: DateTime<Utc>,
+}
+
+/// Paths of the size variants of an uploaded image.
+///
+/// Variants are generated in the background; until then they serve the original.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+pub struct MediaVariants {
+    /// Small preview for lists.
+    pub thumbnail: String,
+
+    /// Inline size for post bodies.
+    pub medium: String,
+
+    /// The file as uploaded.
+    pub original: String,
+}
+
+/// Chunked upload in progress.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MediaUpload {
+    /// Upload identifier, used to resume the upload.
+    pub id: Uuid,
+
+    /// Sanitized original file name.
+    pub filename: String,
+
+    /// Size of the whole file in bytes.
+    pub size: u64,
+
+    /// Bytes received so far; the next chunk starts here.
+    pub offset: u64,
+
+    /// Largest chunk accepted in one request, in bytes.
+    pub max_chunk_bytes: u64,
+
+    /// When the upload started.
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,170 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Who may read a post.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+#[serde(rename_all = "lowercase")]
+pub enum Visibility {
+    /// Listed in the feed and search, readable by anyone.
+    #[default]
+    Public,
+
+    /// Left out of the feed and search; readable through a share link.
+    Unlisted,
+
+    /// Readable by its author only.
+    Private,
+}
+
+impl Visibility {
+    /// Returns the visibility name as sent on the wire.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Visibility::Public => "public",
+            Visibility::Unlisted => "unlisted",
+            Visibility::Private => "private",
+        }
+    }
+}
+
+impl fmt::Display for Visibility {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Visibility {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "public" => Ok(Visibility::Public),
+            "unlisted" => Ok(Visibility::Unlisted),
+            "private" => Ok(Visibility::Private),
+            other => Err(format!("unknown visibility `{other}`")),
+        }
+    }
+}
+
+/// Blog post.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+pub struct Post {
+    /// Post identifier.
+    #[cfg_attr(feature = "wasm", tsify(type = "string"))]
+    pub id: Uuid,
+
+    /// Post author identifier.
+    #[cfg_attr(feature = "wasm", tsify(type = "string"))]
+    pub author_id: Uuid,
+
+    /// Post author, when loaded together with the post.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub author: Option<Author>,
+
+    /// Post title.
+    pub title: String,
+
+    /// Post permalink slug.
+    pub slug: String,
+
+    /// Post content.
+    pub content: String,
+
+    /// Post tags.
+    #[serde(default)]
+    pub tags: Vec<String>,
+
+    /// Who may read the post.
+    #[serde(default)]
+    pub visibility: Visibility,
+
+    /// Post creation timestamp.
+    #[cfg_attr(feature = "wasm", tsify(type = "string"))]
+    pub created_at: DateTime<Utc>,
+
+    /// Last modification timestamp; send it back as `expected_updated_at` to
+    /// detect conflicting edits.
+    #[cfg_attr(feature = "wasm", tsify(type = "string"))]
+    pub updated_at: DateTime<Utc>,
+
+    /// When the post was moved to the trash; only set on trashed posts.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "wasm", tsify(optional, type = "string"))]
+    pub deleted_at: Option<DateTime<Utc>>,
+
+    /// Language of the title and content, on responses picked by `Accept-Language`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[cfg_attr(feature = "wasm", tsify(optional))]
+    pub locale: Option<String>,
+}
+
+/// Post author summary embedded in posts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+pub struct Author {
+    /// Author identifier.
+    #[cfg_attr(feature = "wasm", tsify(type = "string"))]
+    pub id: Uuid,
+
+    /// Author username.
+    pub username: String,
+}
+
+/// Post creation and update request payload.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PostRequest {
+    /// Post title.
+    pub title: String,
+
+    /// Post content.
+    pub content: String,
+
+    /// Post tags; on update, omitting them keeps the current tags.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tags: Option<Vec<String>>,
+
+    /// Who may read the post; defaults to public on create and is kept on update.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub visibility: Option<Visibility>,
+
+    /// On update, the `updated_at` the client last saw; a mismatch fails with `409 Conflict`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expected_updated_at: Option<DateTime<Utc>>,
+
+    /// Stores the content without HTML sanitization; administrators only.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub raw: bool,
+}
+
+/// Post matching a search, with its relevance and an excerpt.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "wasm", derive(tsify::Tsify))]
+pub struct SearchResult {
+    /// Matching post.
+    #[serde(flatten)]
+    pub post: Post,
+
+    /// Relevance score; higher is better.
+    pub rank: f32,
+
+    /// HTML-escaped content excerpt with matches wrapped in `<mark>`, safe
+    /// to insert as HTML.
+    pub snippet: String,
+}
+
+/// Tag usage.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Tag {
+    /// Tag name.
+    pub name: String,
+
+    /// Number of posts carrying the tag.
+    pub post_count: u64,
+}
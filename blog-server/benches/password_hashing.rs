@@ -0,0 +1,99 @@
+//! Compares Argon2 hashing inline on the executor with hashing on the
+//! blocking thread pool, as `AuthService` does.
+//!
+//! Runs a burst of registrations on a single-threaded runtime, like one
+//! actix worker, next to a task that ticks every millisecond. Reports the
+//! burst's wall time and the worst tick delay, i.e. how long every other
+//! request on the worker would have been stalled.
+//!
+//! Run with `cargo bench -p blog-server --bench password_hashing`.
+
+use std::future::Future;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+use argon2::password_hash::{PasswordHasher, SaltString, rand_core::OsRng};
+use argon2::Argon2;
+
+/// Registrations per burst.
+const BURST: usize = 16;
+
+/// Hashes a password with the server's Argon2 parameters.
+fn hash(password: &str) -> String {
+    let salt = SaltString::generate(&mut OsRng);
+    Argon2::default()
+        .hash_password(password.as_bytes(), &salt)
+        .expect("hashing failed")
+        .to_string()
+}
+
+/// Hashes on the executor thread.
+async fn inline(password: String) {
+    hash(&password);
+}
+
+/// Hashes on the blocking thread pool.
+async fn offloaded(password: String) {
+    tokio::task::spawn_blocking(move || hash(&password))
+        .await
+        .expect("hashing task panicked");
+}
+
+/// Runs a burst of `BURST` hashes and returns its wall time and worst tick delay.
+fn run<F, Fut>(register: F) -> (Duration, Duration)
+where
+    F: Fn(String) -> Fut,
+    Fut: Future<Output = ()> + 'static,
+{
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_time()
+        .build()
+        .expect("failed to build runtime");
+    let local = tokio::task::LocalSet::new();
+
+    local.block_on(&runtime, async {
+        let done = Arc::new(AtomicBool::new(false));
+        let worst_us = Arc::new(AtomicU64::new(0));
+
+        let ticker = {
+            let done = done.clone();
+            let worst_us = worst_us.clone();
+            tokio::task::spawn_local(async move {
+                while !done.load(Ordering::Relaxed) {
+                    let started = Instant::now();
+                    tokio::time::sleep(Duration::from_millis(1)).await;
+                    let late = started.elapsed().saturating_sub(Duration::from_millis(1));
+                    worst_us.fetch_max(late.as_micros() as u64, Ordering::Relaxed);
+                }
+            })
+        };
+        // Let the ticker start before the burst arrives.
+        tokio::task::yield_now().await;
+
+        let started = Instant::now();
+        let tasks: Vec<_> = (0..BURST)
+            .map(|i| tokio::task::spawn_local(register(format!("password-{i}"))))
+            .collect();
+        for task in tasks {
+            task.await.expect("registration task panicked");
+        }
+        let elapsed = started.elapsed();
+
+        done.store(true, Ordering::Relaxed);
+        ticker.await.expect("ticker panicked");
+        (elapsed, Duration::from_micros(worst_us.load(Ordering::Relaxed)))
+    })
+}
+
+fn main() {
+    println!("{BURST} concurrent registrations on one worker thread:");
+    for (name, (elapsed, stall)) in [("inline", run(inline)), ("spawn_blocking", run(offloaded))] {
+        println!(
+            "  {name:<15} wall {:>7.1} ms  ({:>5.1} hashes/s)  worst executor stall {:>7.1} ms",
+            elapsed.as_secs_f64() * 1000.0,
+            BURST as f64 / elapsed.as_secs_f64(),
+            stall.as_secs_f64() * 1000.0,
+        );
+    }
+}
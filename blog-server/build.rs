@@ -1,3 +1,6 @@
+use std::process::Command;
+use std::time::{SystemTime, UNIX_EPOCH};
+
 // should be run before cargo build
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     println!("cargo:rerun-if-changed=proto/blog.proto");
@@ -6,5 +9,61 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         .build_server(true)
         .build_client(false)
         .compile(&["proto/blog.proto"], &["proto"])?;
+
+    embed_build_info();
     Ok(())
 }
+
+/// Passes the git commit, build time and enabled features to the crate as
+/// `BLOG_GIT_SHA`, `BLOG_BUILD_UNIX_SECS` and `BLOG_BUILD_FEATURES`.
+fn embed_build_info() {
+    println!("cargo:rerun-if-env-changed=BLOG_GIT_SHA");
+    println!("cargo:rerun-if-env-changed=SOURCE_DATE_EPOCH");
+
+    // Builds from a source archive have no repository; let them pass the SHA in.
+    let git_sha = std::env::var("BLOG_GIT_SHA")
+        .ok()
+        .filter(|sha| !sha.is_empty())
+        .or_else(|| git(&["rev-parse", "--short=12", "HEAD"]))
+        .unwrap_or_else(|| "unknown".into());
+    // A commit moves the branch HEAD points to, not HEAD itself.
+    let head_ref = git(&["symbolic-ref", "-q", "HEAD"]);
+    for name in ["HEAD"].into_iter().chain(head_ref.as_deref()) {
+        if let Some(path) = git(&["rev-parse", "--git-path", name]) {
+            println!("cargo:rerun-if-changed={path}");
+        }
+    }
+
+    // SOURCE_DATE_EPOCH keeps reproducible builds reproducible.
+    let build_secs = std::env::var("SOURCE_DATE_EPOCH")
+        .ok()
+        .and_then(|secs| secs.parse::<u64>().ok())
+        .unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs())
+        });
+
+    let mut features: Vec<String> = std::env::vars()
+        .filter_map(|(name, _)| {
+            name.strip_prefix("CARGO_FEATURE_")
+                .map(|feature| feature.to_lowercase().replace('_', "-"))
+        })
+        .collect();
+    features.sort();
+
+    println!("cargo:rustc-env=BLOG_GIT_SHA={git_sha}");
+    println!("cargo:rustc-env=BLOG_BUILD_UNIX_SECS={build_secs}");
+    println!("cargo:rustc-env=BLOG_BUILD_FEATURES={}", features.join(","));
+}
+
+/// Runs git with `args`, returning its trimmed output if it succeeded.
+fn git(args: &[&str]) -> Option<String> {
+    let output = Command::new("git").args(args).output().ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    String::from_utf8(output.stdout)
+        .ok()
+        .map(|out| out.trim().to_string())
+}
@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS public.comments
+(
+    id         UUID                     NOT NULL
+        CONSTRAINT comments_pk
+            PRIMARY KEY,
+    post_id    UUID                     NOT NULL,
+    author_id  UUID                     NOT NULL,
+    parent_id  UUID,
+    content    TEXT                     NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    deleted_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS comments_post_id_created_at_index
+    ON public.comments (post_id, created_at);
@@ -0,0 +1,5 @@
+ALTER TABLE public.posts
+    ADD COLUMN IF NOT EXISTS tags TEXT[] NOT NULL DEFAULT '{}';
+
+CREATE INDEX IF NOT EXISTS posts_tags_index
+    ON public.posts USING GIN (tags);
@@ -0,0 +1,5 @@
+ALTER TABLE public.users
+    ADD COLUMN IF NOT EXISTS role VARCHAR NOT NULL DEFAULT 'user';
+
+ALTER TABLE public.users
+    ADD COLUMN IF NOT EXISTS disabled_at TIMESTAMP WITH TIME ZONE;
@@ -0,0 +1,14 @@
+ALTER TABLE posts ADD COLUMN IF NOT EXISTS slug VARCHAR;
+
+-- Mirrors `slugify` in src/domain/post.rs for posts created before slugs existed.
+UPDATE posts
+SET slug = concat_ws(
+    '-',
+    NULLIF(trim(BOTH '-' FROM regexp_replace(lower(left(title, 60)), '[^[:alnum:]]+', '-', 'g')), ''),
+    left(replace(id::TEXT, '-', ''), 8)
+)
+WHERE slug IS NULL;
+
+ALTER TABLE posts ALTER COLUMN slug SET NOT NULL;
+
+CREATE UNIQUE INDEX IF NOT EXISTS posts_slug_idx ON posts (slug);
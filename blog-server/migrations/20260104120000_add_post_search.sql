@@ -0,0 +1,8 @@
+ALTER TABLE public.posts
+    ADD COLUMN IF NOT EXISTS search TSVECTOR GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', title), 'A') ||
+        setweight(to_tsvector('english', content), 'B')
+    ) STORED;
+
+CREATE INDEX IF NOT EXISTS posts_search_index
+    ON public.posts USING GIN (search);
@@ -0,0 +1,14 @@
+CREATE TABLE IF NOT EXISTS public.media
+(
+    id           UUID                     NOT NULL
+        CONSTRAINT media_pk
+            PRIMARY KEY,
+    owner_id     UUID                     NOT NULL,
+    filename     TEXT                     NOT NULL,
+    content_type TEXT                     NOT NULL,
+    size_bytes   BIGINT                   NOT NULL,
+    created_at   TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS media_owner_id_created_at_index
+    ON public.media (owner_id, created_at);
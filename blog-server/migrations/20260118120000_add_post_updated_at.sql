@@ -0,0 +1,5 @@
+ALTER TABLE posts ADD COLUMN IF NOT EXISTS updated_at TIMESTAMP WITH TIME ZONE;
+
+UPDATE posts SET updated_at = created_at WHERE updated_at IS NULL;
+
+ALTER TABLE posts ALTER COLUMN updated_at SET NOT NULL;
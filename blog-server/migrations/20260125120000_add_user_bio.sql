@@ -0,0 +1,2 @@
+ALTER TABLE public.users
+    ADD COLUMN IF NOT EXISTS bio TEXT;
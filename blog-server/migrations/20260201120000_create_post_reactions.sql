@@ -0,0 +1,20 @@
+CREATE TABLE IF NOT EXISTS public.post_likes
+(
+    post_id    UUID                     NOT NULL,
+    user_id    UUID                     NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    CONSTRAINT post_likes_pk
+        PRIMARY KEY (post_id, user_id)
+);
+
+CREATE TABLE IF NOT EXISTS public.bookmarks
+(
+    post_id    UUID                     NOT NULL,
+    user_id    UUID                     NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    CONSTRAINT bookmarks_pk
+        PRIMARY KEY (post_id, user_id)
+);
+
+CREATE INDEX IF NOT EXISTS bookmarks_user_id_created_at_index
+    ON public.bookmarks (user_id, created_at);
@@ -0,0 +1,16 @@
+ALTER TABLE public.users
+    ADD COLUMN IF NOT EXISTS username_skeleton VARCHAR;
+
+-- Emails are compared in lowercase; skip rows that would collide with an existing account.
+UPDATE public.users u
+SET email = lower(btrim(u.email))
+WHERE u.email <> lower(btrim(u.email))
+  AND NOT EXISTS (SELECT 1 FROM public.users o WHERE o.email = lower(btrim(u.email)));
+
+-- Skeletons of the characters usernames may contain; the server computes them for new rows.
+UPDATE public.users
+SET username_skeleton = replace(replace(replace(lower(username), 'm', 'rn'), '0', 'o'), '1', 'l')
+WHERE username_skeleton IS NULL;
+
+CREATE INDEX IF NOT EXISTS users_username_skeleton_index
+    ON public.users (username_skeleton);
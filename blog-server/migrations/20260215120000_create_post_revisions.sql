@@ -0,0 +1,15 @@
+CREATE TABLE IF NOT EXISTS public.post_revisions
+(
+    id         UUID                     NOT NULL
+        CONSTRAINT post_revisions_pk
+            PRIMARY KEY,
+    post_id    UUID                     NOT NULL,
+    title      VARCHAR                  NOT NULL,
+    content    TEXT                     NOT NULL,
+    tags       TEXT[]                   NOT NULL DEFAULT '{}',
+    edited_at  TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS post_revisions_post_id_created_at_index
+    ON public.post_revisions (post_id, created_at);
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS public.refresh_tokens
+(
+    id         UUID                     NOT NULL
+        CONSTRAINT refresh_tokens_pk
+            PRIMARY KEY,
+    user_id    UUID                     NOT NULL,
+    token_hash TEXT                     NOT NULL
+        CONSTRAINT refresh_tokens_token_hash_key
+            UNIQUE,
+    expires_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    revoked_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS refresh_tokens_user_id_index
+    ON public.refresh_tokens (user_id);
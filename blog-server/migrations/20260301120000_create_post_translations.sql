@@ -0,0 +1,11 @@
+CREATE TABLE IF NOT EXISTS public.post_translations
+(
+    post_id    UUID                     NOT NULL,
+    locale     VARCHAR                  NOT NULL,
+    title      VARCHAR                  NOT NULL,
+    content    TEXT                     NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    CONSTRAINT post_translations_pk
+        PRIMARY KEY (post_id, locale)
+);
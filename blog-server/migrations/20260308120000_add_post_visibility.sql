@@ -0,0 +1,2 @@
+ALTER TABLE public.posts
+    ADD COLUMN IF NOT EXISTS visibility VARCHAR NOT NULL DEFAULT 'public';
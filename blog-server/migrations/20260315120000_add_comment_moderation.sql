@@ -0,0 +1,6 @@
+ALTER TABLE public.comments
+    ADD COLUMN IF NOT EXISTS status VARCHAR NOT NULL DEFAULT 'published',
+    ADD COLUMN IF NOT EXISTS flag_reason TEXT;
+
+CREATE INDEX IF NOT EXISTS comments_flagged_idx
+    ON public.comments (created_at) WHERE status = 'flagged';
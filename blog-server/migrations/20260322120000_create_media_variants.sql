@@ -0,0 +1,12 @@
+CREATE TABLE IF NOT EXISTS public.media_variants
+(
+    media_id     UUID                     NOT NULL,
+    name         VARCHAR                  NOT NULL,
+    content_type TEXT                     NOT NULL,
+    width        INTEGER                  NOT NULL,
+    height       INTEGER                  NOT NULL,
+    size_bytes   BIGINT                   NOT NULL,
+    created_at   TIMESTAMP WITH TIME ZONE NOT NULL,
+    CONSTRAINT media_variants_pk
+        PRIMARY KEY (media_id, name)
+);
@@ -0,0 +1,16 @@
+CREATE TABLE IF NOT EXISTS public.data_exports
+(
+    id           UUID                     NOT NULL
+        CONSTRAINT data_exports_pk
+            PRIMARY KEY,
+    user_id      UUID                     NOT NULL,
+    status       VARCHAR                  NOT NULL DEFAULT 'pending',
+    size_bytes   BIGINT,
+    error        TEXT,
+    created_at   TIMESTAMP WITH TIME ZONE NOT NULL,
+    completed_at TIMESTAMP WITH TIME ZONE,
+    expires_at   TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS data_exports_user_id_index
+    ON public.data_exports (user_id);
@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS public.activities
+(
+    id             UUID                     NOT NULL
+        CONSTRAINT activities_pk
+            PRIMARY KEY,
+    actor_id       UUID                     NOT NULL,
+    kind           VARCHAR                  NOT NULL,
+    post_id        UUID                     NOT NULL,
+    comment_id     UUID,
+    post_author_id UUID                     NOT NULL,
+    created_at     TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS activities_actor_id_created_at_index
+    ON public.activities (actor_id, created_at DESC);
+
+CREATE INDEX IF NOT EXISTS activities_post_author_id_created_at_index
+    ON public.activities (post_author_id, created_at DESC);
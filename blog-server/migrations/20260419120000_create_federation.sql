@@ -0,0 +1,19 @@
+CREATE TABLE IF NOT EXISTS public.federation_keys
+(
+    user_id         UUID                     NOT NULL
+        CONSTRAINT federation_keys_pk
+            PRIMARY KEY,
+    public_key_pem  TEXT                     NOT NULL,
+    private_key_pem TEXT                     NOT NULL,
+    created_at      TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE TABLE IF NOT EXISTS public.federation_followers
+(
+    user_id    UUID                     NOT NULL,
+    actor_url  VARCHAR                  NOT NULL,
+    inbox_url  VARCHAR                  NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL,
+    CONSTRAINT federation_followers_pk
+        PRIMARY KEY (user_id, actor_url)
+);
@@ -0,0 +1,24 @@
+CREATE TABLE IF NOT EXISTS public.mentions
+(
+    id          UUID                     NOT NULL
+        CONSTRAINT mentions_pk
+            PRIMARY KEY,
+    post_id     UUID                     NOT NULL,
+    source      VARCHAR                  NOT NULL,
+    target      VARCHAR                  NOT NULL,
+    status      VARCHAR                  NOT NULL DEFAULT 'pending',
+    author_name VARCHAR,
+    author_url  VARCHAR,
+    title       VARCHAR,
+    excerpt     TEXT,
+    created_at  TIMESTAMP WITH TIME ZONE NOT NULL,
+    verified_at TIMESTAMP WITH TIME ZONE,
+    CONSTRAINT mentions_source_target_key
+        UNIQUE (source, target)
+);
+
+CREATE INDEX IF NOT EXISTS mentions_post_id_created_at_index
+    ON public.mentions (post_id, created_at) WHERE status = 'published';
+
+CREATE INDEX IF NOT EXISTS mentions_held_idx
+    ON public.mentions (created_at) WHERE status = 'held';
@@ -0,0 +1,18 @@
+CREATE TABLE IF NOT EXISTS public.outbox
+(
+    id           UUID                     NOT NULL
+        CONSTRAINT outbox_pk
+            PRIMARY KEY,
+    event_type   VARCHAR                  NOT NULL,
+    payload      TEXT                     NOT NULL,
+    created_at   TIMESTAMP WITH TIME ZONE NOT NULL,
+    attempts     INTEGER                  NOT NULL DEFAULT 0,
+    last_error   TEXT,
+    delivered_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS outbox_pending_idx
+    ON public.outbox (created_at, id) WHERE delivered_at IS NULL;
+
+CREATE INDEX IF NOT EXISTS outbox_delivered_at_idx
+    ON public.outbox (delivered_at) WHERE delivered_at IS NOT NULL;
@@ -0,0 +1,43 @@
+-- Read model for post listings, feeds and search: live posts with their
+-- author's username, kept in step with posts and users by the application.
+CREATE TABLE IF NOT EXISTS public.post_listings
+(
+    id              UUID                     NOT NULL
+        CONSTRAINT post_listings_pk
+            PRIMARY KEY,
+    author_id       UUID                     NOT NULL,
+    author_username VARCHAR,
+    title           VARCHAR                  NOT NULL,
+    slug            VARCHAR                  NOT NULL,
+    content         TEXT                     NOT NULL,
+    tags            TEXT[]                   NOT NULL DEFAULT '{}',
+    visibility      VARCHAR                  NOT NULL,
+    created_at      TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at      TIMESTAMP WITH TIME ZONE NOT NULL,
+    search          TSVECTOR GENERATED ALWAYS AS (
+        setweight(to_tsvector('english', title), 'A') ||
+        setweight(to_tsvector('english', content), 'B')
+    ) STORED
+);
+
+CREATE INDEX IF NOT EXISTS post_listings_author_id_created_at_index
+    ON public.post_listings (author_id, created_at DESC, id);
+
+CREATE INDEX IF NOT EXISTS post_listings_public_created_at_index
+    ON public.post_listings (created_at DESC, id)
+    WHERE visibility = 'public';
+
+CREATE INDEX IF NOT EXISTS post_listings_tags_index
+    ON public.post_listings USING GIN (tags);
+
+CREATE INDEX IF NOT EXISTS post_listings_search_index
+    ON public.post_listings USING GIN (search);
+
+INSERT INTO public.post_listings (id, author_id, author_username, title, slug, content, tags,
+                                  visibility, created_at, updated_at)
+SELECT p.id, p.author_id, u.username, p.title, p.slug, p.content, p.tags, p.visibility,
+       p.created_at, p.updated_at
+FROM public.posts p
+LEFT JOIN public.users u ON u.id = p.author_id
+WHERE p.deleted_at IS NULL
+ON CONFLICT (id) DO NOTHING;
@@ -0,0 +1,15 @@
+-- Review queue of posts a content moderator flagged; one entry per post.
+CREATE TABLE IF NOT EXISTS public.post_flags
+(
+    post_id    UUID                     NOT NULL
+        CONSTRAINT post_flags_pk
+            PRIMARY KEY
+        CONSTRAINT post_flags_post_id_fk
+            REFERENCES public.posts (id)
+            ON DELETE CASCADE,
+    reason     TEXT                     NOT NULL,
+    flagged_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS post_flags_flagged_at_index
+    ON public.post_flags (flagged_at, post_id);
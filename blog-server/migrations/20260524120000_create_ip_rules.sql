@@ -0,0 +1,11 @@
+-- Client address allow and deny rules added through the admin API.
+CREATE TABLE IF NOT EXISTS public.ip_rules
+(
+    id         UUID                     NOT NULL
+        CONSTRAINT ip_rules_pk
+            PRIMARY KEY,
+    network    VARCHAR                  NOT NULL,
+    action     VARCHAR                  NOT NULL,
+    reason     TEXT,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
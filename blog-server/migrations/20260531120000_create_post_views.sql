@@ -0,0 +1,9 @@
+-- Number of times each post was read, per day.
+CREATE TABLE IF NOT EXISTS public.post_views
+(
+    post_id UUID   NOT NULL,
+    day     DATE   NOT NULL,
+    views   BIGINT NOT NULL,
+    CONSTRAINT post_views_pk
+        PRIMARY KEY (post_id, day)
+);
@@ -0,0 +1,12 @@
+-- Number of times each post was read, per day, referring domain and kind of client.
+-- An empty referrer stands for direct visits.
+CREATE TABLE IF NOT EXISTS public.post_traffic
+(
+    post_id  UUID         NOT NULL,
+    day      DATE         NOT NULL,
+    referrer VARCHAR(255) NOT NULL,
+    agent    VARCHAR(16)  NOT NULL,
+    views    BIGINT       NOT NULL,
+    CONSTRAINT post_traffic_pk
+        PRIMARY KEY (post_id, day, referrer, agent)
+);
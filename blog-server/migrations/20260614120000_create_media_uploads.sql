@@ -0,0 +1,16 @@
+-- Chunked media uploads in progress; the received bytes live in media storage.
+CREATE TABLE IF NOT EXISTS public.media_uploads
+(
+    id             UUID                     NOT NULL
+        CONSTRAINT media_uploads_pk
+            PRIMARY KEY,
+    owner_id       UUID                     NOT NULL,
+    filename       TEXT                     NOT NULL,
+    size_bytes     BIGINT                   NOT NULL,
+    received_bytes BIGINT                   NOT NULL DEFAULT 0,
+    created_at     TIMESTAMP WITH TIME ZONE NOT NULL,
+    updated_at     TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS media_uploads_updated_at_index
+    ON public.media_uploads (updated_at);
@@ -0,0 +1,18 @@
+-- Media file contents, stored once per distinct SHA-256 however many uploads share them.
+CREATE TABLE IF NOT EXISTS public.media_blobs
+(
+    sha256     CHAR(64)                 NOT NULL
+        CONSTRAINT media_blobs_pk
+            PRIMARY KEY,
+    size_bytes BIGINT                   NOT NULL,
+    ref_count  BIGINT                   NOT NULL DEFAULT 0,
+    created_at TIMESTAMP WITH TIME ZONE NOT NULL
+);
+
+CREATE INDEX IF NOT EXISTS media_blobs_unreferenced_index
+    ON public.media_blobs (sha256)
+    WHERE ref_count <= 0;
+
+-- Files uploaded before deduplication keep a NULL hash and stay stored under their media ID.
+ALTER TABLE public.media
+    ADD COLUMN IF NOT EXISTS sha256 CHAR(64);
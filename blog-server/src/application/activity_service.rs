@@ -0,0 +1,125 @@
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::application::post_service::{DEFAULT_PAGE_LIMIT, MAX_PAGE_LIMIT};
+use crate::data::activity_repository::ActivityRepository;
+use crate::data::user_repository::UserRepository;
+use crate::domain::activity::Activity;
+use crate::domain::error::DomainError;
+use crate::domain::page::Page;
+use crate::infrastructure::events::EventBus;
+
+/// Activity history service.
+///
+/// Records what users do from the events they cause and lists it per user
+/// and as a personal feed.
+#[derive(Clone)]
+pub struct ActivityService<A, U>
+where
+    A: ActivityRepository + 'static,
+    U: UserRepository + 'static,
+{
+    activities: Arc<A>,
+    users: Arc<U>,
+}
+
+impl<A, U> ActivityService<A, U>
+where
+    A: ActivityRepository + 'static,
+    U: UserRepository + 'static,
+{
+    /// Creates a new activity service.
+    pub fn new(activities: Arc<A>, users: Arc<U>) -> Self {
+        Self { activities, users }
+    }
+
+    /// Returns a page of what a user did on public posts, newest first.
+    ///
+    /// Uses the same page size rules as post listings.
+    pub async fn list_user_activity(
+        &self,
+        user_id: Uuid,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Page<Activity>, DomainError> {
+        self.users
+            .find_by_id(user_id)
+            .await?
+            .filter(|user| user.deleted_at.is_none())
+            .ok_or_else(|| DomainError::UserNotFound(format!("user id: {}", user_id)))?;
+
+        let limit = page_limit(limit);
+        let (items, total) = self
+            .activities
+            .list_by_actor(user_id, i64::from(limit), i64::from(offset))
+            .await?;
+
+        Ok(Page {
+            items,
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    /// Returns a page of a user's feed, newest first: what the user did and
+    /// what others did on the user's posts.
+    pub async fn feed(
+        &self,
+        user_id: Uuid,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Page<Activity>, DomainError> {
+        let limit = page_limit(limit);
+        let (items, total) = self
+            .activities
+            .feed(user_id, i64::from(limit), i64::from(offset))
+            .await?;
+
+        Ok(Page {
+            items,
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    /// Records activities from `events` in a background task until the bus
+    /// closes.
+    ///
+    /// Events missed while the task lags behind are not recorded.
+    pub fn spawn_recorder(&self, events: &EventBus) {
+        let activities = Arc::clone(&self.activities);
+        let mut events = events.subscribe();
+
+        tokio::spawn(async move {
+            loop {
+                let event = match events.recv().await {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(skipped, "activity recorder lagged behind");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => return,
+                };
+
+                let Some(activity) = Activity::from_event(&event) else {
+                    continue;
+                };
+                if let Err(e) = activities.record(&activity).await {
+                    warn!(event_id = event.id, error = %e, "failed to record activity");
+                }
+            }
+        });
+    }
+}
+
+/// Applies the post listing page size rules.
+fn page_limit(limit: u32) -> u32 {
+    match limit {
+        0 => DEFAULT_PAGE_LIMIT,
+        limit => limit.min(MAX_PAGE_LIMIT),
+    }
+}
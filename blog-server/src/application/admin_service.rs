@@ -0,0 +1,98 @@
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::auth_service::user_cache_key;
+use crate::application::post_service::{DEFAULT_PAGE_LIMIT, MAX_PAGE_LIMIT};
+use crate::data::user_repository::UserRepository;
+use crate::domain::error::DomainError;
+use crate::domain::user::{Role, User};
+use crate::infrastructure::cache::Cache;
+
+/// User administration service.
+///
+/// Lists users and changes their role or status; every operation requires
+/// the acting user to be an administrator.
+#[derive(Clone)]
+pub struct AdminService<R: UserRepository + 'static> {
+    users: Arc<R>,
+    cache: Arc<dyn Cache>,
+}
+
+impl<R> AdminService<R>
+where
+    R: UserRepository + 'static,
+{
+    /// Creates a new admin service; disabled users are removed from the
+    /// token user `cache`.
+    pub fn new(users: Arc<R>, cache: Arc<dyn Cache>) -> Self {
+        Self { users, cache }
+    }
+
+    /// Returns a page of users, oldest first.
+    ///
+    /// Uses the same page size rules as post listings.
+    pub async fn list_users(
+        &self,
+        actor_id: Uuid,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Vec<User>, DomainError> {
+        self.require_admin(actor_id).await?;
+
+        let limit = match limit {
+            0 => DEFAULT_PAGE_LIMIT,
+            limit => limit.min(MAX_PAGE_LIMIT),
+        };
+
+        self.users.list(i64::from(limit), i64::from(offset)).await
+    }
+
+    /// Disables a user account; disabled users can no longer log in or use their tokens.
+    pub async fn disable_user(&self, actor_id: Uuid, id: Uuid) -> Result<User, DomainError> {
+        self.require_admin(actor_id).await?;
+
+        if id == actor_id {
+            return Err(DomainError::Validation(
+                "administrators cannot disable their own account".into(),
+            ));
+        }
+
+        let user = self.users.disable(id).await?;
+        self.cache.forget(&user_cache_key(id)).await;
+
+        Ok(user)
+    }
+
+    /// Re-enables a disabled user account, lifting a ban.
+    pub async fn enable_user(&self, actor_id: Uuid, id: Uuid) -> Result<User, DomainError> {
+        self.require_admin(actor_id).await?;
+
+        let user = self.users.enable(id).await?;
+        self.cache.forget(&user_cache_key(id)).await;
+
+        Ok(user)
+    }
+
+    /// Changes a user's role.
+    pub async fn set_role(&self, actor_id: Uuid, id: Uuid, role: Role) -> Result<User, DomainError> {
+        self.require_admin(actor_id).await?;
+
+        if id == actor_id {
+            return Err(DomainError::Validation(
+                "administrators cannot change their own role".into(),
+            ));
+        }
+
+        self.users.set_role(id, role).await
+    }
+
+    /// Fails unless the given user is an administrator.
+    pub async fn require_admin(&self, actor_id: Uuid) -> Result<(), DomainError> {
+        let actor = self.users.find_by_id(actor_id).await?;
+
+        match actor {
+            Some(user) if user.role == Role::Admin => Ok(()),
+            _ => Err(DomainError::Forbidden("administrator role required".into())),
+        }
+    }
+}
@@ -0,0 +1,217 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{Days, NaiveDate, Utc};
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::data::analytics_repository::AnalyticsRepository;
+use crate::domain::analytics::{
+    AgentClass, DailyViews, PostStats, ViewCount, ViewSource, MAX_REFERRER_LEN,
+};
+use crate::domain::error::DomainError;
+
+/// Number of days stats cover when none is asked for.
+pub const DEFAULT_STATS_DAYS: u32 = 30;
+
+/// Largest number of days stats may cover.
+pub const MAX_STATS_DAYS: u32 = 365;
+
+/// Number of referring domains a post's stats list, most reads first.
+const MAX_REFERRERS: i64 = 20;
+
+/// Post view counting and per-post statistics service.
+///
+/// Views are counted in memory per day, referring domain and kind of client
+/// and written in batches by [`Self::spawn_flush`], so reading a post never
+/// waits on the database for it; views counted since the last flush are lost
+/// if the server stops.
+#[derive(Clone)]
+pub struct AnalyticsService<R>
+where
+    R: AnalyticsRepository + 'static,
+{
+    repo: Arc<R>,
+    pending: Arc<Mutex<HashMap<(Uuid, NaiveDate, ViewSource), i64>>>,
+}
+
+impl<R> AnalyticsService<R>
+where
+    R: AnalyticsRepository + 'static,
+{
+    /// Creates a new analytics service.
+    pub fn new(repo: Arc<R>) -> Self {
+        Self {
+            repo,
+            pending: Arc::new(Mutex::new(HashMap::new())),
+        }
+    }
+
+    /// Counts one read of a post today, from the page `referer` names and
+    /// with the client `user_agent` describes.
+    pub fn record_view(&self, post_id: Uuid, referer: Option<&str>, user_agent: Option<&str>) {
+        let today = Utc::now().date_naive();
+        let source = ViewSource {
+            referrer: referer.and_then(referrer_domain),
+            agent: AgentClass::classify(user_agent),
+        };
+
+        *self
+            .pending
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .entry((post_id, today, source))
+            .or_default() += 1;
+    }
+
+    /// Writes the views counted since the last flush.
+    ///
+    /// Counts that fail to be written are kept for the next flush.
+    pub async fn flush(&self) -> Result<(), DomainError> {
+        let pending = std::mem::take(&mut *self.pending.lock().unwrap_or_else(|e| e.into_inner()));
+        if pending.is_empty() {
+            return Ok(());
+        }
+
+        let views: Vec<_> = pending
+            .iter()
+            .map(|((post_id, day, source), views)| ViewCount {
+                post_id: *post_id,
+                day: *day,
+                source: source.clone(),
+                views: *views,
+            })
+            .collect();
+        if let Err(e) = self.repo.record_views(&views).await {
+            let mut current = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+            for (key, views) in pending {
+                *current.entry(key).or_default() += views;
+            }
+            return Err(e);
+        }
+
+        Ok(())
+    }
+
+    /// Flushes counted views every `period` in a background task.
+    pub fn spawn_flush(&self, period: Duration) {
+        let analytics = self.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = interval(period);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+                if let Err(e) = analytics.flush().await {
+                    warn!(error = %e, "failed to record post views");
+                }
+            }
+        });
+    }
+
+    /// Returns the stats of an author's posts over the last `days` days,
+    /// today included, most viewed first.
+    ///
+    /// `days` of zero means [`DEFAULT_STATS_DAYS`]; larger values than
+    /// [`MAX_STATS_DAYS`] are capped.
+    pub async fn list_post_stats(
+        &self,
+        author_id: Uuid,
+        days: u32,
+    ) -> Result<Vec<PostStats>, DomainError> {
+        self.stats(author_id, None, days).await
+    }
+
+    /// Returns the stats of one of an author's posts over the last `days`
+    /// days, as for [`Self::list_post_stats`], along with where its readers
+    /// came from.
+    pub async fn get_post_stats(
+        &self,
+        author_id: Uuid,
+        post_id: Uuid,
+        days: u32,
+    ) -> Result<PostStats, DomainError> {
+        let mut stats = self
+            .stats(author_id, Some(post_id), days)
+            .await?
+            .pop()
+            .ok_or_else(|| DomainError::PostNotFound(format!("post id: {}", post_id)))?;
+
+        let traffic = self
+            .repo
+            .post_traffic(post_id, period_start(days), MAX_REFERRERS)
+            .await?;
+        stats.traffic = Some(traffic);
+
+        Ok(stats)
+    }
+
+    /// Loads the stats and fills in daily views, one entry per day.
+    async fn stats(
+        &self,
+        author_id: Uuid,
+        post_id: Option<Uuid>,
+        days: u32,
+    ) -> Result<Vec<PostStats>, DomainError> {
+        let today = Utc::now().date_naive();
+        let since = period_start(days);
+
+        let mut stats = self.repo.post_stats(author_id, post_id, since).await?;
+        let post_ids: Vec<Uuid> = stats.iter().map(|stats| stats.post_id).collect();
+        let mut stored: HashMap<(Uuid, NaiveDate), i64> = self
+            .repo
+            .daily_views(&post_ids, since)
+            .await?
+            .into_iter()
+            .map(|(post_id, daily)| ((post_id, daily.day), daily.views))
+            .collect();
+
+        for stats in &mut stats {
+            stats.daily_views = since
+                .iter_days()
+                .take_while(|day| *day <= today)
+                .map(|day| DailyViews {
+                    day,
+                    views: stored.remove(&(stats.post_id, day)).unwrap_or(0),
+                })
+                .collect();
+        }
+
+        Ok(stats)
+    }
+}
+
+/// Returns the first day of a period of `days` days ending today.
+///
+/// `days` of zero means [`DEFAULT_STATS_DAYS`]; larger values than
+/// [`MAX_STATS_DAYS`] are capped.
+fn period_start(days: u32) -> NaiveDate {
+    let days = match days {
+        0 => DEFAULT_STATS_DAYS,
+        days => days.min(MAX_STATS_DAYS),
+    };
+    let today = Utc::now().date_naive();
+    today
+        .checked_sub_days(Days::new(u64::from(days - 1)))
+        .unwrap_or(today)
+}
+
+/// Returns the domain of a `Referer` URL, lowercase and without `www.`, if
+/// it is a web page.
+fn referrer_domain(referer: &str) -> Option<String> {
+    let url = reqwest::Url::parse(referer.trim()).ok()?;
+    if !matches!(url.scheme(), "http" | "https") {
+        return None;
+    }
+
+    let host = url.host_str()?.to_ascii_lowercase();
+    let host = host.strip_prefix("www.").unwrap_or(&host);
+    if host.is_empty() || host.len() > MAX_REFERRER_LEN {
+        return None;
+    }
+
+    Some(host.to_string())
+}
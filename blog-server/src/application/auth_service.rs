@@ -1,12 +1,35 @@
 use std::sync::Arc;
+use std::time::Duration;
 
+use serde::{Deserialize, Serialize};
 use tracing::instrument;
+use uuid::Uuid;
 
+use crate::application::outbox_service::OutboxService;
 use crate::data::user_repository::UserRepository;
 use crate::domain::error::DomainError;
-use crate::domain::user::User;
+use crate::domain::outbox::OutboxEvent;
+use crate::domain::user::{
+    normalize_email, normalize_username, validate_email, validate_password, validate_username,
+    User,
+};
+use crate::domain::validation::Violations;
+use crate::infrastructure::cache::Cache;
 use crate::infrastructure::security::{JwtKeys, hash_password, verify_password};
 
+/// What authenticating a token needs to know about its user.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenUser {
+    /// User identifier.
+    pub id: Uuid,
+
+    /// User email address.
+    pub email: String,
+
+    /// Whether the account has been disabled.
+    pub disabled: bool,
+}
+
 /// Authentication service.
 ///
 /// Handles user registration, login, and JWT token management.
@@ -14,15 +37,33 @@ use crate::infrastructure::security::{JwtKeys, hash_password, verify_password};
 pub struct AuthService<R: UserRepository + 'static> {
     repo: Arc<R>,
     keys: JwtKeys,
+    outbox: OutboxService,
+    cache: Arc<dyn Cache>,
+    cache_ttl: Duration,
 }
 
 impl<R> AuthService<R>
 where
     R: UserRepository + 'static,
 {
-    /// Creates a new authentication service.
-    pub fn new(repo: Arc<R>, keys: JwtKeys) -> Self {
-        Self { repo, keys }
+    /// Creates a new authentication service recording registrations in
+    /// `outbox` and keeping token users in `cache` for `cache_ttl`.
+    ///
+    /// A zero `cache_ttl` disables the token user cache.
+    pub fn new(
+        repo: Arc<R>,
+        keys: JwtKeys,
+        outbox: OutboxService,
+        cache: Arc<dyn Cache>,
+        cache_ttl: Duration,
+    ) -> Self {
+        Self {
+            repo,
+            keys,
+            outbox,
+            cache,
+            cache_ttl,
+        }
     }
 
     /// Returns JWT signing and verification keys.
@@ -35,13 +76,40 @@ where
         self.repo
             .find_by_id(id)
             .await
-            .map_err(DomainError::from)?
+            ?
             .ok_or_else(|| DomainError::UserNotFound(format!("user {}", id)))
     }
 
+    /// Returns the user a token was issued to, as checked on every
+    /// authenticated request.
+    ///
+    /// Served from the cache when possible; profile changes and disabling an
+    /// account remove the cached entry.
+    pub async fn token_user(&self, id: Uuid) -> Result<TokenUser, DomainError> {
+        let key = user_cache_key(id);
+        if !self.cache_ttl.is_zero()
+            && let Some(user) = self.cache.get_json(&key).await
+        {
+            return Ok(user);
+        }
+
+        let user = self.get_user(id).await?;
+        let user = TokenUser {
+            id: user.id,
+            email: user.email.clone(),
+            disabled: user.is_disabled(),
+        };
+        if !self.cache_ttl.is_zero() {
+            self.cache.set_json(&key, &user, self.cache_ttl).await;
+        }
+
+        Ok(user)
+    }
+
     /// Registers a new user.
     ///
-    /// The password is hashed before storing.
+    /// The username, email and password are checked against their rules and
+    /// every violation is reported at once. The password is hashed before storing.
     #[instrument(skip(self))]
     pub async fn register(
         &self,
@@ -49,34 +117,151 @@ where
         email: String,
         password: String,
     ) -> Result<User, DomainError> {
-        let hash =
-            hash_password(&password).map_err(|err| DomainError::Internal(err.to_string()))?;
-        let user = User::new(username.to_lowercase(), email.to_lowercase(), hash);
-        self.repo.create(user).await.map_err(DomainError::from)
+        let username = normalize_username(&username);
+        let email = normalize_email(&email);
+
+        let mut violations = Violations::default();
+        validate_username(&username, &mut violations);
+        validate_email(&email, &mut violations);
+        validate_password(&password, &mut violations);
+        violations.into_result()?;
+
+        let hash = hash_blocking(password).await?;
+        let mut uow = self.repo.begin().await?;
+        let user = self.repo.create(&mut uow, User::new(username, email, hash)).await?;
+        self.outbox
+            .record(
+                &mut uow,
+                OutboxEvent::UserRegistered {
+                    user_id: user.id,
+                    username: user.username.clone(),
+                },
+            )
+            .await?;
+        uow.commit().await?;
+
+        Ok(user)
+    }
+
+    /// Updates the profile of a user; `None` fields keep their current value.
+    ///
+    /// An empty `bio` clears it.
+    pub async fn update_profile(
+        &self,
+        id: uuid::Uuid,
+        username: Option<String>,
+        email: Option<String>,
+        bio: Option<String>,
+    ) -> Result<User, DomainError> {
+        let mut user = self.get_user(id).await?;
+        let mut violations = Violations::default();
+
+        if let Some(username) = username {
+            let username = normalize_username(&username);
+            validate_username(&username, &mut violations);
+            user.username = username;
+        }
+        if let Some(email) = email {
+            let email = normalize_email(&email);
+            validate_email(&email, &mut violations);
+            user.email = email;
+        }
+        violations.into_result()?;
+
+        if let Some(bio) = bio {
+            let bio = bio.trim();
+            user.bio = (!bio.is_empty()).then(|| bio.to_string());
+        }
+
+        let updated = self.repo.update_profile(&user).await?;
+        self.cache.forget(&user_cache_key(id)).await;
+
+        Ok(updated)
+    }
+
+    /// Changes the password of a user after checking the current one.
+    #[instrument(skip(self, current_password, new_password))]
+    pub async fn change_password(
+        &self,
+        id: uuid::Uuid,
+        current_password: &str,
+        new_password: &str,
+    ) -> Result<(), DomainError> {
+        let user = self.get_user(id).await?;
+
+        let valid = verify_blocking(current_password.to_string(), user.password_hash).await?;
+        if !valid {
+            // Not `InvalidCredentials`: the session itself is fine.
+            return Err(DomainError::Validation("current password is incorrect".into()));
+        }
+        let mut violations = Violations::default();
+        validate_password(new_password, &mut violations);
+        violations.into_result()?;
+
+        let hash = hash_blocking(new_password.to_string()).await?;
+        self.repo.set_password(id, &hash).await
     }
 
     /// Authenticates a user and returns a JWT token.
     #[instrument(skip(self))]
     pub async fn login(&self, email: &str, password: &str) -> Result<String, DomainError> {
+        let user = self.authenticate(email, password).await?;
+        self.issue_token(&user)
+    }
+
+    /// Checks the credentials of an enabled user and returns the user.
+    pub async fn authenticate(&self, email: &str, password: &str) -> Result<User, DomainError> {
         let user = self
             .repo
-            .find_by_email(&email.to_lowercase())
+            .find_by_email(email)
             .await
-            .map_err(DomainError::from)?
+            ?
             .ok_or_else(|| {
                 DomainError::InvalidCredentials("email / pass are incorrect".to_string())
             })?;
 
-        let valid = verify_password(password, &user.password_hash)
-            .map_err(|e| DomainError::Internal(e.to_string()))?;
+        let valid = verify_blocking(password.to_string(), user.password_hash.clone()).await?;
         if !valid {
             return Err(DomainError::InvalidCredentials(
                 "email / pass are incorrect".to_string(),
             ));
         }
 
+        if user.is_disabled() {
+            return Err(DomainError::Forbidden("account is disabled".to_string()));
+        }
+
+        Ok(user)
+    }
+
+    /// Signs an access token for a user.
+    pub fn issue_token(&self, user: &User) -> Result<String, DomainError> {
         self.keys
-            .generate_token(user.id)
+            .generate_token(user.id, user.role)
             .map_err(|err| DomainError::Internal(err.to_string()))
     }
 }
+
+/// Hashes a password on the blocking thread pool.
+///
+/// Argon2 is deliberately slow; running it on an async worker would stall
+/// every other request scheduled on that worker.
+async fn hash_blocking(password: String) -> Result<String, DomainError> {
+    tokio::task::spawn_blocking(move || hash_password(&password))
+        .await
+        .map_err(|e| DomainError::Internal(e.to_string()))?
+        .map_err(|e| DomainError::Internal(e.to_string()))
+}
+
+/// Verifies a password against a hash on the blocking thread pool.
+async fn verify_blocking(password: String, hash: String) -> Result<bool, DomainError> {
+    tokio::task::spawn_blocking(move || verify_password(&password, &hash))
+        .await
+        .map_err(|e| DomainError::Internal(e.to_string()))?
+        .map_err(|e| DomainError::Internal(e.to_string()))
+}
+
+/// Returns the cache key of a token user.
+pub(crate) fn user_cache_key(id: Uuid) -> String {
+    format!("token-user:{}", id)
+}
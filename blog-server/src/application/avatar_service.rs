@@ -0,0 +1,119 @@
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::media_service::MediaService;
+use crate::data::media_repository::MediaRepository;
+use crate::data::user_repository::UserRepository;
+use crate::domain::error::DomainError;
+use crate::domain::media::VariantName;
+use crate::infrastructure::gravatar::GravatarProxy;
+
+/// Avatar size served when none is asked for, in pixels.
+pub const DEFAULT_AVATAR_SIZE: u32 = 80;
+
+/// Largest avatar size served, in pixels.
+pub const MAX_AVATAR_SIZE: u32 = 2048;
+
+/// Avatar image ready to serve.
+pub struct Avatar {
+    /// MIME type of the image.
+    pub content_type: String,
+
+    /// Image contents.
+    pub bytes: Vec<u8>,
+}
+
+/// User avatar service.
+///
+/// Lets users pick one of their uploaded images as avatar and serves it,
+/// falling back to their Gravatar when they have none.
+#[derive(Clone)]
+pub struct AvatarService<U, M>
+where
+    U: UserRepository + 'static,
+    M: MediaRepository + 'static,
+{
+    users: Arc<U>,
+    media: MediaService<M>,
+    gravatar: GravatarProxy,
+}
+
+impl<U, M> AvatarService<U, M>
+where
+    U: UserRepository + 'static,
+    M: MediaRepository + 'static,
+{
+    /// Creates a new avatar service.
+    pub fn new(users: Arc<U>, media: MediaService<M>, gravatar: GravatarProxy) -> Self {
+        Self {
+            users,
+            media,
+            gravatar,
+        }
+    }
+
+    /// Makes one of the user's uploaded images their avatar.
+    pub async fn set_avatar(&self, user_id: Uuid, media_id: Uuid) -> Result<(), DomainError> {
+        let media = self.media.get_media(media_id).await?;
+        if media.owner_id != user_id {
+            return Err(DomainError::Forbidden(
+                "only images you uploaded can be your avatar".into(),
+            ));
+        }
+
+        self.users.set_avatar(user_id, Some(media_id)).await
+    }
+
+    /// Removes the user's avatar, so their Gravatar is served again.
+    pub async fn clear_avatar(&self, user_id: Uuid) -> Result<(), DomainError> {
+        self.users.set_avatar(user_id, None).await
+    }
+
+    /// Returns a user's avatar for display at `size` pixels.
+    ///
+    /// Uploaded avatars are served in the smallest variant at least `size`
+    /// pixels large, keeping their aspect ratio; Gravatars come square.
+    pub async fn avatar(&self, user_id: Uuid, size: Option<u32>) -> Result<Avatar, DomainError> {
+        let size = size.unwrap_or(DEFAULT_AVATAR_SIZE);
+        if !(1..=MAX_AVATAR_SIZE).contains(&size) {
+            return Err(DomainError::Validation(format!(
+                "size must be between 1 and {MAX_AVATAR_SIZE}"
+            )));
+        }
+
+        let user = self
+            .users
+            .find_by_id(user_id)
+            .await?
+            .filter(|user| user.deleted_at.is_none())
+            .ok_or_else(|| DomainError::UserNotFound(format!("user {}", user_id)))?;
+
+        if let Some(media_id) = self.users.find_avatar(user_id).await? {
+            match self.media.read_variant(media_id, variant_for(size)).await {
+                Ok(file) => {
+                    return Ok(Avatar {
+                        content_type: file.content_type,
+                        bytes: file.bytes,
+                    });
+                }
+                // The image is gone; fall back to the Gravatar.
+                Err(DomainError::MediaNotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let image = self.gravatar.fetch(&user.email, size).await?;
+        Ok(Avatar {
+            content_type: image.content_type,
+            bytes: image.bytes,
+        })
+    }
+}
+
+/// Picks the smallest media variant covering `size` pixels.
+fn variant_for(size: u32) -> VariantName {
+    VariantName::ALL
+        .into_iter()
+        .find(|variant| variant.max_dimension().is_none_or(|max| max >= size))
+        .unwrap_or(VariantName::Original)
+}
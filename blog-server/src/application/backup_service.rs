@@ -0,0 +1,378 @@
+use chrono::Utc;
+use serde::de::DeserializeOwned;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+use std::sync::Arc;
+use tokio::sync::mpsc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::data::comment_repository::CommentRepository;
+use crate::data::media_repository::MediaRepository;
+use crate::data::post_repository::PostRepository;
+use crate::data::user_repository::UserRepository;
+use crate::domain::backup::{
+    BackupManifest, BackupUser, BACKUP_FORMAT, BLOBS_DIR, COMMENTS_PATH, MANIFEST_PATH, MEDIA_PATH,
+    POSTS_PATH, USERS_PATH,
+};
+use crate::domain::comment::Comment;
+use crate::domain::error::DomainError;
+use crate::domain::media::Media;
+use crate::domain::post::Post;
+use crate::infrastructure::archive::{read_tar_zst, write_tar_zst, ArchiveEntry};
+use crate::infrastructure::media_storage::{content_hash, MediaStorage};
+
+/// Users and posts loaded per query while taking a backup.
+const BACKUP_PAGE_SIZE: i64 = 100;
+
+/// Whole-instance backup and restore.
+///
+/// Backups go through the repositories rather than the database, so they
+/// can be restored into another instance, even one on a newer schema. They
+/// hold every active user with their posts, comments and uploads; deleted
+/// accounts, trashed posts and deleted comments are left out.
+#[derive(Clone)]
+pub struct BackupService<U, P, C, M>
+where
+    U: UserRepository + 'static,
+    P: PostRepository + 'static,
+    C: CommentRepository + 'static,
+    M: MediaRepository + 'static,
+{
+    users: Arc<U>,
+    posts: Arc<P>,
+    comments: Arc<C>,
+    media: Arc<M>,
+    storage: MediaStorage,
+}
+
+impl<U, P, C, M> BackupService<U, P, C, M>
+where
+    U: UserRepository + 'static,
+    P: PostRepository + 'static,
+    C: CommentRepository + 'static,
+    M: MediaRepository + 'static,
+{
+    /// Creates a new backup service reading and writing media contents in `storage`.
+    pub fn new(
+        users: Arc<U>,
+        posts: Arc<P>,
+        comments: Arc<C>,
+        media: Arc<M>,
+        storage: MediaStorage,
+    ) -> Self {
+        Self {
+            users,
+            posts,
+            comments,
+            media,
+            storage,
+        }
+    }
+
+    /// Writes a backup of the instance to a tar.zst archive at `out` and
+    /// returns its manifest.
+    pub async fn backup(&self, out: &Path) -> Result<BackupManifest, DomainError> {
+        let mut users = Vec::new();
+        let mut posts = Vec::new();
+        let mut comments = Vec::new();
+        let mut uploads = Vec::new();
+
+        let mut offset = 0;
+        loop {
+            let page = self.users.list(BACKUP_PAGE_SIZE, offset).await?;
+            let done = (page.len() as i64) < BACKUP_PAGE_SIZE;
+
+            for user in page {
+                posts.extend(self.posts_of(user.id).await?);
+                comments.extend(self.comments.list_by_author(user.id).await?);
+                uploads.extend(self.media.list_by_owner(user.id).await?);
+                let avatar_id = self.users.find_avatar(user.id).await?;
+                users.push(BackupUser { user, avatar_id });
+            }
+
+            if done {
+                break;
+            }
+            offset += BACKUP_PAGE_SIZE;
+        }
+
+        // Files uploaded before deduplication are hashed here, so the archive
+        // stores all contents as blobs.
+        for upload in uploads.iter_mut().filter(|upload| upload.sha256.is_none()) {
+            match self.storage.read(upload).await {
+                Ok(bytes) => upload.sha256 = Some(content_hash(&bytes)),
+                Err(DomainError::MediaNotFound(_)) => {}
+                Err(e) => return Err(e),
+            }
+        }
+
+        let manifest = BackupManifest {
+            format: BACKUP_FORMAT,
+            server_version: env!("CARGO_PKG_VERSION").to_string(),
+            created_at: Utc::now(),
+            users: users.len(),
+            posts: posts.len(),
+            comments: comments.len(),
+            media: uploads.len(),
+        };
+
+        let (tx, writer) = write_tar_zst(out.to_path_buf());
+        let sent = async {
+            let files = [
+                ArchiveEntry::json(MANIFEST_PATH, &manifest)?,
+                ArchiveEntry::json(USERS_PATH, &users)?,
+                ArchiveEntry::json(POSTS_PATH, &posts)?,
+                ArchiveEntry::json(COMMENTS_PATH, &comments)?,
+                ArchiveEntry::json(MEDIA_PATH, &uploads)?,
+            ];
+            for file in files {
+                send(&tx, file).await?;
+            }
+            self.send_blobs(&tx, &uploads).await
+        }
+        .await;
+        drop(tx);
+
+        // A failed write closes the channel, so its error explains a failed send.
+        let size_bytes = writer
+            .await
+            .map_err(|e| DomainError::Internal(format!("archive task error: {}", e)))??;
+        sent?;
+
+        info!(
+            path = %out.display(),
+            size_bytes,
+            users = manifest.users,
+            posts = manifest.posts,
+            comments = manifest.comments,
+            media = manifest.media,
+            "backup written"
+        );
+        Ok(manifest)
+    }
+
+    /// Restores the backup archive at `input` into this instance and returns
+    /// its manifest.
+    ///
+    /// The instance must have no users yet. Records keep their IDs, so links
+    /// to posts and media keep working. Media whose contents are missing from
+    /// the archive are left out, as are comments on posts that are.
+    pub async fn restore(&self, input: &Path) -> Result<BackupManifest, DomainError> {
+        if !self.users.list(1, 0).await?.is_empty() {
+            return Err(DomainError::Conflict(
+                "backups can only be restored into an instance without users".into(),
+            ));
+        }
+
+        let mut entries = read_tar_zst(input.to_path_buf());
+        let manifest: BackupManifest = next_json(&mut entries, MANIFEST_PATH).await?;
+        if manifest.format != BACKUP_FORMAT {
+            return Err(DomainError::Validation(format!(
+                "unsupported backup format {}, expected {}",
+                manifest.format, BACKUP_FORMAT
+            )));
+        }
+        let users: Vec<BackupUser> = next_json(&mut entries, USERS_PATH).await?;
+        let posts: Vec<Post> = next_json(&mut entries, POSTS_PATH).await?;
+        let comments: Vec<Comment> = next_json(&mut entries, COMMENTS_PATH).await?;
+        let uploads: Vec<Media> = next_json(&mut entries, MEDIA_PATH).await?;
+
+        let users = self.restore_records(users, posts, comments).await?;
+        let restored_media = self.restore_media(&mut entries, uploads).await?;
+
+        // Profiles are finished last, once the avatars they point at exist.
+        for BackupUser { user, avatar_id } in &users {
+            if user.bio.is_some() {
+                self.users.update_profile(user).await?;
+            }
+            if user.disabled_at.is_some() {
+                self.users.disable(user.id).await?;
+            }
+            if let Some(avatar_id) = avatar_id.filter(|id| restored_media.contains(id)) {
+                self.users.set_avatar(user.id, Some(avatar_id)).await?;
+            }
+        }
+
+        info!(
+            path = %input.display(),
+            users = users.len(),
+            media = restored_media.len(),
+            "backup restored"
+        );
+        Ok(manifest)
+    }
+
+    /// Returns every post of the given author, whatever its visibility.
+    async fn posts_of(&self, author_id: Uuid) -> Result<Vec<Post>, DomainError> {
+        let mut posts = Vec::new();
+        let mut offset = 0;
+        loop {
+            let (page, _) = self
+                .posts
+                .list(Some(author_id), None, BACKUP_PAGE_SIZE, offset)
+                .await?;
+            let done = (page.len() as i64) < BACKUP_PAGE_SIZE;
+            posts.extend(page);
+
+            if done {
+                return Ok(posts);
+            }
+            offset += BACKUP_PAGE_SIZE;
+        }
+    }
+
+    /// Sends the contents of `uploads` to the archive, each blob once.
+    async fn send_blobs(
+        &self,
+        tx: &mpsc::Sender<ArchiveEntry>,
+        uploads: &[Media],
+    ) -> Result<(), DomainError> {
+        let mut sent = HashSet::new();
+        for upload in uploads {
+            let Some(hash) = upload.sha256.as_deref() else {
+                warn!(media_id = %upload.id, "media file missing from backup");
+                continue;
+            };
+            if !sent.insert(hash) {
+                continue;
+            }
+
+            match self.storage.read(upload).await {
+                Ok(bytes) => {
+                    let file = ArchiveEntry {
+                        path: format!("{}{}", BLOBS_DIR, hash),
+                        bytes,
+                        compressed: true,
+                    };
+                    send(tx, file).await?;
+                }
+                // The file is gone; restoring leaves its metadata out.
+                Err(DomainError::MediaNotFound(_)) => {
+                    warn!(media_id = %upload.id, "media file missing from backup")
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Creates the users, posts and comments of a backup in one transaction;
+    /// returns the users as created.
+    async fn restore_records(
+        &self,
+        users: Vec<BackupUser>,
+        posts: Vec<Post>,
+        mut comments: Vec<Comment>,
+    ) -> Result<Vec<BackupUser>, DomainError> {
+        let mut uow = self.users.begin().await?;
+
+        let mut created = Vec::with_capacity(users.len());
+        for BackupUser { user, avatar_id } in users {
+            let user = self.users.create(&mut uow, user).await?;
+            created.push(BackupUser { user, avatar_id });
+        }
+
+        let post_ids: HashSet<Uuid> = posts.iter().map(|post| post.id).collect();
+        for post in posts {
+            self.posts.create(&mut uow, post).await?;
+        }
+
+        // Oldest first, so replies come after the comments they answer.
+        comments.sort_by_key(|comment| comment.created_at);
+        let mut comment_ids = HashSet::new();
+        for mut comment in comments {
+            if !post_ids.contains(&comment.post_id) {
+                warn!(comment_id = %comment.id, "comment on a post missing from backup skipped");
+                continue;
+            }
+            // Replies to deleted comments become top-level comments.
+            if comment.parent_id.is_some_and(|parent| !comment_ids.contains(&parent)) {
+                comment.parent_id = None;
+            }
+
+            comment_ids.insert(comment.id);
+            self.comments.create(&mut uow, comment).await?;
+        }
+
+        uow.commit().await?;
+        Ok(created)
+    }
+
+    /// Stores the media blobs left in the archive and creates the metadata
+    /// of the uploads using them; returns the IDs of the restored uploads.
+    async fn restore_media(
+        &self,
+        entries: &mut mpsc::Receiver<Result<ArchiveEntry, DomainError>>,
+        uploads: Vec<Media>,
+    ) -> Result<HashSet<Uuid>, DomainError> {
+        let mut by_hash: HashMap<String, Vec<Media>> = HashMap::new();
+        for upload in uploads {
+            match upload.sha256.clone() {
+                Some(hash) => by_hash.entry(hash).or_default().push(upload),
+                None => warn!(media_id = %upload.id, "media without contents skipped"),
+            }
+        }
+
+        let mut restored = HashSet::new();
+        while let Some(entry) = entries.recv().await {
+            let entry = entry?;
+            let Some(hash) = entry.path.strip_prefix(BLOBS_DIR) else {
+                warn!(path = %entry.path, "unexpected file in backup skipped");
+                continue;
+            };
+            if content_hash(&entry.bytes) != hash {
+                return Err(invalid_backup(format!("{} does not match its hash", entry.path)));
+            }
+            let Some(uploads) = by_hash.remove(hash) else {
+                continue;
+            };
+
+            // Retained before writing, as on upload, so the blob is never
+            // collected in between.
+            for upload in &uploads {
+                self.media.retain_blob(hash, upload.size_bytes).await?;
+            }
+            self.storage.write_blob(hash, &entry.bytes).await?;
+            for upload in uploads {
+                restored.insert(upload.id);
+                self.media.create(upload).await?;
+            }
+        }
+
+        for upload in by_hash.values().flatten() {
+            warn!(media_id = %upload.id, "media file missing from backup skipped");
+        }
+
+        Ok(restored)
+    }
+}
+
+/// Hands a file to the archive writer.
+async fn send(tx: &mpsc::Sender<ArchiveEntry>, file: ArchiveEntry) -> Result<(), DomainError> {
+    tx.send(file)
+        .await
+        .map_err(|_| DomainError::Internal("archive writer stopped".into()))
+}
+
+/// Reads the next file of a backup, which must be the JSON file at `path`.
+async fn next_json<T: DeserializeOwned>(
+    entries: &mut mpsc::Receiver<Result<ArchiveEntry, DomainError>>,
+    path: &str,
+) -> Result<T, DomainError> {
+    let entry = entries
+        .recv()
+        .await
+        .ok_or_else(|| invalid_backup(format!("{} is missing", path)))??;
+    if entry.path != path {
+        return Err(invalid_backup(format!("expected {}, found {}", path, entry.path)));
+    }
+
+    serde_json::from_slice(&entry.bytes).map_err(|e| invalid_backup(format!("{}: {}", path, e)))
+}
+
+/// Reports a backup archive that cannot be restored.
+fn invalid_backup(reason: String) -> DomainError {
+    DomainError::Validation(format!("invalid backup: {}", reason))
+}
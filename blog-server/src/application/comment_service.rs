@@ -0,0 +1,336 @@
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::application::outbox_service::OutboxService;
+use crate::application::post_service::{DEFAULT_PAGE_LIMIT, MAX_PAGE_LIMIT};
+use crate::data::comment_repository::CommentRepository;
+use crate::data::post_repository::PostRepository;
+use crate::data::unit_of_work::UnitOfWork;
+use crate::domain::comment::{Comment, CommentOrigin, CommentStatus};
+use crate::domain::error::DomainError;
+use crate::domain::event::EventKind;
+use crate::domain::moderation::{ContentKind, Verdict};
+use crate::domain::outbox::OutboxEvent;
+use crate::domain::page::Page;
+use crate::infrastructure::events::EventBus;
+use crate::infrastructure::moderation::{ContentModerator, ModeratedContent};
+use crate::infrastructure::rate_limit::RateLimiter;
+use crate::infrastructure::sanitizer::HtmlSanitizer;
+use crate::infrastructure::spam::SpamClassifier;
+
+/// Comment management service.
+///
+/// Adds, lists, and deletes comments on blog posts, rate limits new comments
+/// and holds suspected spam in a moderation queue.
+#[derive(Clone)]
+pub struct CommentService<C, P>
+where
+    C: CommentRepository + 'static,
+    P: PostRepository + 'static,
+{
+    comments: Arc<C>,
+    posts: Arc<P>,
+    events: EventBus,
+    sanitizer: HtmlSanitizer,
+    classifier: Option<Arc<dyn SpamClassifier>>,
+    user_limiter: RateLimiter,
+    ip_limiter: RateLimiter,
+    outbox: OutboxService,
+    moderator: Option<Arc<dyn ContentModerator>>,
+}
+
+impl<C, P> CommentService<C, P>
+where
+    C: CommentRepository + 'static,
+    P: PostRepository + 'static,
+{
+    /// Creates a new comment service publishing new comments to `events` and
+    /// cleaning their content with `sanitizer`.
+    ///
+    /// New comments are limited per author by `user_limiter` and per client IP
+    /// by `ip_limiter`, and checked by `moderator` and `classifier` when they
+    /// are configured. Published comments are recorded in `outbox`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        comments: Arc<C>,
+        posts: Arc<P>,
+        events: EventBus,
+        sanitizer: HtmlSanitizer,
+        classifier: Option<Arc<dyn SpamClassifier>>,
+        user_limiter: RateLimiter,
+        ip_limiter: RateLimiter,
+        outbox: OutboxService,
+        moderator: Option<Arc<dyn ContentModerator>>,
+    ) -> Self {
+        Self {
+            comments,
+            posts,
+            events,
+            sanitizer,
+            classifier,
+            user_limiter,
+            ip_limiter,
+            outbox,
+            moderator,
+        }
+    }
+
+    /// Adds a comment to a post, optionally as a reply to another comment.
+    ///
+    /// HTML in the content is always sanitized. Comments the content
+    /// moderator rejects fail with a validation error. Comments it flags, or
+    /// the spam classifier suspects, are stored flagged and only show up once
+    /// a moderator approves them; if either check fails, the comment is
+    /// published.
+    pub async fn add_comment(
+        &self,
+        post_id: Uuid,
+        author_id: Uuid,
+        content: String,
+        parent_id: Option<Uuid>,
+        origin: &CommentOrigin,
+    ) -> Result<Comment, DomainError> {
+        let content = self.sanitizer.clean(&content);
+        if content.trim().is_empty() {
+            return Err(DomainError::Validation("comment must not be empty".into()));
+        }
+
+        let ip_allowed = match origin.ip.as_deref() {
+            Some(ip) => self.ip_limiter.check(ip).await,
+            None => true,
+        };
+        if !ip_allowed || !self.user_limiter.check(&author_id.to_string()).await {
+            return Err(DomainError::RateLimited(
+                "too many comments; try again later".into(),
+            ));
+        }
+
+        let post = self
+            .posts
+            .get(post_id)
+            .await?
+            .ok_or_else(|| DomainError::PostNotFound(format!("post id: {}", post_id)))?;
+
+        if let Some(parent_id) = parent_id {
+            let parent = self.get_comment(parent_id).await?;
+            if parent.post_id != post_id {
+                return Err(DomainError::Validation(
+                    "parent comment belongs to another post".into(),
+                ));
+            }
+        }
+
+        let mut comment = Comment::new(post_id, author_id, parent_id, content);
+        let flag = match self.moderate(&comment).await? {
+            Some(reason) => Some(reason),
+            None => self.classify(&comment, origin).await,
+        };
+        if let Some(reason) = flag {
+            comment.flag(reason);
+        }
+        let mut uow = self.comments.begin().await?;
+        let comment = self.comments.create(&mut uow, comment).await?;
+        if comment.status == CommentStatus::Published {
+            self.record(&mut uow, &comment, post.author_id).await?;
+        }
+        uow.commit().await?;
+
+        if comment.status == CommentStatus::Published {
+            self.announce(&comment, post.author_id);
+        } else {
+            info!(comment_id = %comment.id, post_id = %post_id, "comment held for moderation");
+        }
+
+        Ok(comment)
+    }
+
+    /// Asks the content moderator about a new comment; returns the flag
+    /// reason if it should be reviewed, and fails if it is rejected.
+    async fn moderate(&self, comment: &Comment) -> Result<Option<String>, DomainError> {
+        let Some(moderator) = &self.moderator else {
+            return Ok(None);
+        };
+        let submitted = ModeratedContent {
+            kind: ContentKind::Comment,
+            author_id: comment.author_id,
+            title: None,
+            content: &comment.content,
+        };
+
+        match moderator.moderate(&submitted).await {
+            Ok(Verdict::Accept) => Ok(None),
+            Ok(Verdict::Flag(reason)) => Ok(Some(reason)),
+            Ok(Verdict::Reject(reason)) => {
+                Err(DomainError::Validation(format!("comment rejected: {}", reason)))
+            }
+            Err(e) => {
+                warn!(post_id = %comment.post_id, error = %e, "content moderator failed");
+                Ok(None)
+            }
+        }
+    }
+
+    /// Asks the spam classifier about a new comment; returns the flag reason
+    /// if it looks like spam.
+    async fn classify(&self, comment: &Comment, origin: &CommentOrigin) -> Option<String> {
+        let classifier = self.classifier.as_ref()?;
+
+        match classifier.classify(comment, origin).await {
+            Ok(reason) => reason,
+            Err(e) => {
+                warn!(post_id = %comment.post_id, error = %e, "spam classifier failed");
+                None
+            }
+        }
+    }
+
+    /// Records the domain event of a published comment in the outbox.
+    async fn record(
+        &self,
+        uow: &mut UnitOfWork,
+        comment: &Comment,
+        post_author_id: Uuid,
+    ) -> Result<(), DomainError> {
+        self.outbox
+            .record(
+                uow,
+                OutboxEvent::CommentCreated {
+                    comment_id: comment.id,
+                    post_id: comment.post_id,
+                    author_id: comment.author_id,
+                    parent_id: comment.parent_id,
+                    post_author_id,
+                },
+            )
+            .await
+    }
+
+    /// Publishes the event announcing a comment on its post.
+    fn announce(&self, comment: &Comment, post_author_id: Uuid) {
+        self.events.publish(EventKind::CommentAdded {
+            comment_id: comment.id,
+            post_id: comment.post_id,
+            post_author_id,
+            author_id: comment.author_id,
+            parent_id: comment.parent_id,
+        });
+    }
+
+    /// Returns a comment by its ID.
+    pub async fn get_comment(&self, id: Uuid) -> Result<Comment, DomainError> {
+        self.comments
+            .get(id)
+            .await?
+            .ok_or_else(|| DomainError::CommentNotFound(format!("comment id: {}", id)))
+    }
+
+    /// Returns a page of comments on a post, oldest first.
+    ///
+    /// Uses the same page size rules as post listings.
+    pub async fn list_comments(
+        &self,
+        post_id: Uuid,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Page<Comment>, DomainError> {
+        let limit = match limit {
+            0 => DEFAULT_PAGE_LIMIT,
+            limit => limit.min(MAX_PAGE_LIMIT),
+        };
+
+        let (comments, mut total) = self
+            .comments
+            .list_by_post(post_id, i64::from(limit), i64::from(offset))
+            .await?;
+
+        if comments.is_empty() && offset > 0 {
+            total = self.comments.count_by_post(post_id).await?;
+        }
+
+        Ok(Page {
+            items: comments,
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    /// Deletes a comment.
+    ///
+    /// Only the comment author or the author of the commented post may delete it.
+    pub async fn delete_comment(&self, id: Uuid, user_id: Uuid) -> Result<(), DomainError> {
+        let comment = self.get_comment(id).await?;
+
+        if comment.author_id != user_id {
+            let post_author = self
+                .posts
+                .get(comment.post_id)
+                .await?
+                .map(|post| post.author_id);
+
+            if post_author != Some(user_id) {
+                return Err(DomainError::Forbidden(
+                    "only the comment or post author can delete a comment".into(),
+                ));
+            }
+        }
+
+        self.comments.delete(id).await
+    }
+
+    /// Returns a page of the moderation queue, oldest first.
+    ///
+    /// Uses the same page size rules as post listings.
+    pub async fn list_flagged(&self, limit: u32, offset: u32) -> Result<Page<Comment>, DomainError> {
+        let limit = match limit {
+            0 => DEFAULT_PAGE_LIMIT,
+            limit => limit.min(MAX_PAGE_LIMIT),
+        };
+
+        let (comments, mut total) = self
+            .comments
+            .list_flagged(i64::from(limit), i64::from(offset))
+            .await?;
+
+        if comments.is_empty() && offset > 0 {
+            total = self.comments.count_flagged().await?;
+        }
+
+        Ok(Page {
+            items: comments,
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    /// Publishes a flagged comment on its post.
+    pub async fn approve_comment(&self, id: Uuid) -> Result<Comment, DomainError> {
+        let mut uow = self.comments.begin().await?;
+        let comment = self.comments.approve(&mut uow, id).await?;
+        let post = self.posts.get(comment.post_id).await?;
+        if let Some(post) = &post {
+            self.record(&mut uow, &comment, post.author_id).await?;
+        }
+        uow.commit().await?;
+
+        if let Some(post) = post {
+            self.announce(&comment, post.author_id);
+        }
+
+        Ok(comment)
+    }
+
+    /// Deletes a comment regardless of who wrote it; for moderators.
+    pub async fn reject_comment(&self, id: Uuid) -> Result<(), DomainError> {
+        self.comments.delete(id).await
+    }
+
+    /// Permanently removes comments deleted more than `retention` ago;
+    /// returns how many were removed.
+    pub async fn purge_deleted(&self, retention: Duration) -> Result<u64, DomainError> {
+        self.comments.purge_deleted(Utc::now() - retention).await
+    }
+}
@@ -0,0 +1,281 @@
+use chrono::{Duration, Utc};
+use serde_json::json;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::data::comment_repository::CommentRepository;
+use crate::data::export_repository::ExportRepository;
+use crate::data::media_repository::MediaRepository;
+use crate::data::post_repository::PostRepository;
+use crate::data::user_repository::UserRepository;
+use crate::domain::error::DomainError;
+use crate::domain::event::EventKind;
+use crate::domain::export::{post_markdown, DataExport};
+use crate::infrastructure::archive::{write_zip, ArchiveEntry};
+use crate::infrastructure::events::EventBus;
+use crate::infrastructure::media_storage::MediaStorage;
+use crate::infrastructure::security::JwtKeys;
+
+/// Posts loaded per query while gathering an export.
+const EXPORT_PAGE_SIZE: i64 = 100;
+
+/// Personal data export service.
+///
+/// Builds a zip of everything a user stored on the blog in the background,
+/// keeps it in media storage for a while and hands out signed, expiring
+/// download links for it.
+#[derive(Clone)]
+pub struct ExportService<X, U, P, C, M>
+where
+    X: ExportRepository + 'static,
+    U: UserRepository + 'static,
+    P: PostRepository + 'static,
+    C: CommentRepository + 'static,
+    M: MediaRepository + 'static,
+{
+    exports: Arc<X>,
+    users: Arc<U>,
+    posts: Arc<P>,
+    comments: Arc<C>,
+    media: Arc<M>,
+    storage: MediaStorage,
+    keys: JwtKeys,
+    events: EventBus,
+    ttl: Duration,
+}
+
+impl<X, U, P, C, M> ExportService<X, U, P, C, M>
+where
+    X: ExportRepository + 'static,
+    U: UserRepository + 'static,
+    P: PostRepository + 'static,
+    C: CommentRepository + 'static,
+    M: MediaRepository + 'static,
+{
+    /// Creates a new export service keeping archives for `ttl` and announcing
+    /// finished exports on `events`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        exports: Arc<X>,
+        users: Arc<U>,
+        posts: Arc<P>,
+        comments: Arc<C>,
+        media: Arc<M>,
+        storage: MediaStorage,
+        keys: JwtKeys,
+        events: EventBus,
+        ttl: Duration,
+    ) -> Self {
+        Self {
+            exports,
+            users,
+            posts,
+            comments,
+            media,
+            storage,
+            keys,
+            events,
+            ttl,
+        }
+    }
+
+    /// Starts exporting a user's data and returns the pending export.
+    ///
+    /// While an export is being built, asking again returns that one instead
+    /// of starting another.
+    pub async fn request_export(&self, user_id: Uuid) -> Result<DataExport, DomainError> {
+        if let Some(pending) = self.exports.find_pending(user_id).await? {
+            return Ok(pending);
+        }
+
+        let export = DataExport::new(user_id);
+        self.exports.create(&export).await?;
+        self.spawn_build(export.clone());
+
+        Ok(export)
+    }
+
+    /// Returns one of the user's exports; expired ones are reported as missing.
+    pub async fn get_export(&self, id: Uuid, user_id: Uuid) -> Result<DataExport, DomainError> {
+        self.exports
+            .get(id)
+            .await?
+            .filter(|export| export.user_id == user_id && export.expires_at > Utc::now())
+            .ok_or_else(|| DomainError::ExportNotFound(format!("export id: {}", id)))
+    }
+
+    /// Returns a token downloading the export's archive until it expires, or
+    /// `None` while there is nothing to download.
+    pub fn download_token(&self, export: &DataExport) -> Result<Option<String>, DomainError> {
+        if !export.is_downloadable(Utc::now()) {
+            return Ok(None);
+        }
+
+        self.keys
+            .generate_export_token(export.id, export.expires_at)
+            .map(Some)
+            .map_err(|e| DomainError::Internal(format!("export token error: {}", e)))
+    }
+
+    /// Returns the export a download token was issued for, with its archive.
+    ///
+    /// Invalid and expired tokens are reported as a missing export.
+    pub async fn download(&self, token: &str) -> Result<(DataExport, Vec<u8>), DomainError> {
+        let not_found = || DomainError::ExportNotFound("export download".into());
+        let id = self.keys.verify_export_token(token).map_err(|_| not_found())?;
+        let export = self
+            .exports
+            .get(id)
+            .await?
+            .filter(|export| export.is_downloadable(Utc::now()))
+            .ok_or_else(not_found)?;
+
+        let bytes = self.storage.read_export(id).await.map_err(|e| match e {
+            DomainError::MediaNotFound(_) => not_found(),
+            e => e,
+        })?;
+
+        Ok((export, bytes))
+    }
+
+    /// Deletes expired exports and their archives; returns how many.
+    pub async fn purge_expired(&self) -> Result<u64, DomainError> {
+        let ids = self.exports.purge_expired(Utc::now()).await?;
+        for id in &ids {
+            self.storage.remove_export(*id).await?;
+        }
+
+        Ok(ids.len() as u64)
+    }
+
+    /// Builds and stores the archive of an export in a background task, then
+    /// records the outcome and announces it to the user.
+    fn spawn_build(&self, mut export: DataExport) {
+        let exports = Arc::clone(&self.exports);
+        let users = Arc::clone(&self.users);
+        let posts = Arc::clone(&self.posts);
+        let comments = Arc::clone(&self.comments);
+        let media = Arc::clone(&self.media);
+        let storage = self.storage.clone();
+        let events = self.events.clone();
+        let ttl = self.ttl;
+
+        tokio::spawn(async move {
+            let built = build_archive(
+                users.as_ref(),
+                posts.as_ref(),
+                comments.as_ref(),
+                media.as_ref(),
+                &storage,
+                &export,
+            )
+            .await;
+
+            match built {
+                Ok(size_bytes) => {
+                    info!(export_id = %export.id, size_bytes, "data export built");
+                    export.complete(size_bytes, ttl);
+                }
+                Err(e) => {
+                    warn!(export_id = %export.id, error = %e, "data export failed");
+                    export.fail(e.to_string(), ttl);
+                }
+            }
+
+            if let Err(e) = exports.update(&export).await {
+                warn!(export_id = %export.id, error = %e, "failed to record data export outcome");
+                return;
+            }
+
+            events.publish(EventKind::ExportCompleted {
+                export_id: export.id,
+                user_id: export.user_id,
+                status: export.status,
+            });
+        });
+    }
+}
+
+/// Gathers the user's profile, posts as Markdown, comments and uploads into
+/// a zip and stores it under the export ID; returns the archive size.
+async fn build_archive<U, P, C, M>(
+    users: &U,
+    posts: &P,
+    comments: &C,
+    media: &M,
+    storage: &MediaStorage,
+    export: &DataExport,
+) -> Result<i64, DomainError>
+where
+    U: UserRepository,
+    P: PostRepository,
+    C: CommentRepository,
+    M: MediaRepository,
+{
+    let user_id = export.user_id;
+    let user = users
+        .find_by_id(user_id)
+        .await?
+        .ok_or_else(|| DomainError::UserNotFound(format!("user {}", user_id)))?;
+    let avatar_id = users.find_avatar(user_id).await?;
+
+    let mut entries = vec![ArchiveEntry::json(
+        "profile.json",
+        &json!({
+            "id": user.id,
+            "username": user.username,
+            "email": user.email,
+            "bio": user.bio,
+            "role": user.role,
+            "avatar_id": avatar_id,
+            "created_at": user.created_at,
+        }),
+    )?];
+
+    let mut offset = 0;
+    loop {
+        let (page, _) = posts
+            .list(Some(user_id), None, EXPORT_PAGE_SIZE, offset)
+            .await?;
+        let done = (page.len() as i64) < EXPORT_PAGE_SIZE;
+
+        entries.extend(page.iter().map(|post| ArchiveEntry {
+            path: format!("posts/{}.md", post.slug),
+            bytes: post_markdown(post).into_bytes(),
+            compressed: false,
+        }));
+
+        if done {
+            break;
+        }
+        offset += EXPORT_PAGE_SIZE;
+    }
+
+    let comments = comments.list_by_author(user_id).await?;
+    entries.push(ArchiveEntry::json("comments.json", &comments)?);
+
+    let uploads = media.list_by_owner(user_id).await?;
+    for upload in &uploads {
+        match storage.read(upload).await {
+            Ok(bytes) => entries.push(ArchiveEntry {
+                path: format!("media/{}-{}", upload.id, upload.filename),
+                bytes,
+                compressed: true,
+            }),
+            // The file is gone; its metadata is still exported.
+            Err(DomainError::MediaNotFound(_)) => {
+                warn!(export_id = %export.id, media_id = %upload.id, "media file missing from export")
+            }
+            Err(e) => return Err(e),
+        }
+    }
+    entries.push(ArchiveEntry::json("media.json", &uploads)?);
+
+    let archive = tokio::task::spawn_blocking(move || write_zip(entries))
+        .await
+        .map_err(|e| DomainError::Internal(format!("archive task error: {}", e)))??;
+
+    storage.write_export(export.id, &archive).await?;
+    Ok(archive.len() as i64)
+}
@@ -0,0 +1,543 @@
+use chrono::{DateTime, Duration, Utc};
+use serde_json::{json, Value};
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::data::federation_repository::FederationRepository;
+use crate::data::post_repository::PostRepository;
+use crate::data::user_repository::UserRepository;
+use crate::domain::error::DomainError;
+use crate::domain::event::EventKind;
+use crate::domain::federation::{ActorKeys, Follower};
+use crate::domain::post::{Post, Visibility};
+use crate::domain::user::User;
+use crate::infrastructure::events::EventBus;
+use crate::infrastructure::http_signature::{
+    generate_key_pair, parse_signature, sign_request, verify_signature, IncomingRequest,
+};
+
+/// Media type of ActivityPub documents.
+pub const ACTIVITY_JSON: &str = "application/activity+json";
+
+/// JSON-LD context of ActivityPub documents.
+const ACTIVITY_STREAMS: &str = "https://www.w3.org/ns/activitystreams";
+
+/// Audience of public activities.
+const PUBLIC_AUDIENCE: &str = "https://www.w3.org/ns/activitystreams#Public";
+
+/// Posts listed in an author's outbox.
+const OUTBOX_SIZE: i64 = 20;
+
+/// Posts created longer than this before they are announced (imported ones,
+/// say) are not delivered to followers.
+const MAX_DELIVERY_DELAY_MINUTES: i64 = 60;
+
+/// ActivityPub federation service.
+///
+/// Exposes every author as an ActivityPub actor that remote servers, such as
+/// Mastodon, can follow, and delivers the author's new public posts to them.
+#[derive(Clone)]
+pub struct FederationService<F, U, P>
+where
+    F: FederationRepository + 'static,
+    U: UserRepository + 'static,
+    P: PostRepository + 'static,
+{
+    federation: Arc<F>,
+    users: Arc<U>,
+    posts: Arc<P>,
+    client: reqwest::Client,
+    base_url: String,
+    domain: String,
+}
+
+impl<F, U, P> FederationService<F, U, P>
+where
+    F: FederationRepository + 'static,
+    U: UserRepository + 'static,
+    P: PostRepository + 'static,
+{
+    /// Creates a new federation service publishing actors under `base_url`
+    /// and giving up on remote servers after `timeout`.
+    pub fn new(
+        federation: Arc<F>,
+        users: Arc<U>,
+        posts: Arc<P>,
+        base_url: &str,
+        timeout: std::time::Duration,
+    ) -> Result<Self, DomainError> {
+        let base_url = base_url.trim_end_matches('/').to_string();
+        let domain = reqwest::Url::parse(&base_url)
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_string))
+            .ok_or_else(|| {
+                DomainError::Internal(format!("invalid federation base URL: {}", base_url))
+            })?;
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| DomainError::Internal(format!("federation client error: {}", e)))?;
+
+        Ok(Self {
+            federation,
+            users,
+            posts,
+            client,
+            base_url,
+            domain,
+        })
+    }
+
+    /// Resolves a WebFinger `acct:username@domain` resource to its actor.
+    pub async fn webfinger(&self, resource: &str) -> Result<Value, DomainError> {
+        let not_found = || DomainError::UserNotFound(format!("resource: {}", resource));
+
+        let (username, domain) = resource
+            .strip_prefix("acct:")
+            .unwrap_or(resource)
+            .trim_start_matches('@')
+            .rsplit_once('@')
+            .ok_or_else(not_found)?;
+        if !domain.eq_ignore_ascii_case(&self.domain) {
+            return Err(not_found());
+        }
+
+        let user = self
+            .users
+            .find_by_username(username)
+            .await?
+            .filter(is_active)
+            .ok_or_else(not_found)?;
+
+        Ok(json!({
+            "subject": format!("acct:{}@{}", user.username, self.domain),
+            "aliases": [self.actor_url(user.id)],
+            "links": [{
+                "rel": "self",
+                "type": ACTIVITY_JSON,
+                "href": self.actor_url(user.id),
+            }],
+        }))
+    }
+
+    /// Returns an author's actor document, generating its keys on first use.
+    pub async fn actor(&self, user_id: Uuid) -> Result<Value, DomainError> {
+        let user = self.active_user(user_id).await?;
+        let keys = self.keys(user_id).await?;
+        let actor_url = self.actor_url(user_id);
+
+        Ok(json!({
+            "@context": [ACTIVITY_STREAMS, "https://w3id.org/security/v1"],
+            "id": actor_url,
+            "type": "Person",
+            "preferredUsername": user.username,
+            "name": user.username,
+            "summary": user.bio.unwrap_or_default(),
+            "inbox": format!("{}/inbox", actor_url),
+            "outbox": format!("{}/outbox", actor_url),
+            "followers": format!("{}/followers", actor_url),
+            "published": user.created_at.to_rfc3339(),
+            "publicKey": {
+                "id": key_id(&actor_url),
+                "owner": actor_url,
+                "publicKeyPem": keys.public_key_pem,
+            },
+        }))
+    }
+
+    /// Returns an author's latest public posts as `Create` activities.
+    pub async fn outbox(&self, user_id: Uuid) -> Result<Value, DomainError> {
+        self.active_user(user_id).await?;
+
+        let (posts, _) = self.posts.list(Some(user_id), None, OUTBOX_SIZE, 0).await?;
+        let items: Vec<Value> = posts
+            .iter()
+            .filter(|post| post.visibility == Visibility::Public)
+            .map(|post| self.create_activity(post))
+            .collect();
+
+        Ok(json!({
+            "@context": ACTIVITY_STREAMS,
+            "id": format!("{}/outbox", self.actor_url(user_id)),
+            "type": "OrderedCollection",
+            "totalItems": items.len(),
+            "orderedItems": items,
+        }))
+    }
+
+    /// Returns how many remote actors follow an author; the followers
+    /// themselves are not disclosed.
+    pub async fn followers(&self, user_id: Uuid) -> Result<Value, DomainError> {
+        self.active_user(user_id).await?;
+        let total = self.federation.count_followers(user_id).await?;
+
+        Ok(json!({
+            "@context": ACTIVITY_STREAMS,
+            "id": format!("{}/followers", self.actor_url(user_id)),
+            "type": "OrderedCollection",
+            "totalItems": total,
+        }))
+    }
+
+    /// Returns a public post as an `Article`.
+    pub async fn article(&self, post_id: Uuid) -> Result<Value, DomainError> {
+        let post = self
+            .posts
+            .get(post_id)
+            .await?
+            .filter(|post| post.visibility == Visibility::Public && post.deleted_at.is_none())
+            .ok_or_else(|| DomainError::PostNotFound(format!("post id: {}", post_id)))?;
+
+        let mut article = self.article_object(&post);
+        article["@context"] = json!(ACTIVITY_STREAMS);
+        Ok(article)
+    }
+
+    /// Handles an activity posted to an author's inbox.
+    ///
+    /// The request must be signed by the activity's actor. `Follow` adds the
+    /// actor as a follower and answers with an `Accept`; `Undo` of a follow
+    /// removes it; anything else is ignored.
+    pub async fn handle_inbox(
+        &self,
+        user_id: Uuid,
+        request: IncomingRequest<'_>,
+    ) -> Result<(), DomainError> {
+        self.active_user(user_id).await?;
+
+        let params = parse_signature(&request)?;
+        let (signer, public_key_pem) = self.signer(&params.key_id).await?;
+        verify_signature(&request, &params, &public_key_pem)?;
+
+        let activity: Value = serde_json::from_slice(request.body)
+            .map_err(|e| DomainError::Validation(format!("invalid activity: {}", e)))?;
+        let actor_url = object_id(&activity["actor"])
+            .ok_or_else(|| DomainError::Validation("activity has no actor".into()))?;
+        if signer["id"].as_str() != Some(actor_url.as_str()) {
+            return Err(DomainError::Forbidden(
+                "activity not signed by its actor".into(),
+            ));
+        }
+
+        let our_actor = self.actor_url(user_id);
+        match activity["type"].as_str() {
+            Some("Follow")
+                if object_id(&activity["object"]).as_deref() == Some(our_actor.as_str()) =>
+            {
+                let inbox_url = signer["endpoints"]["sharedInbox"]
+                    .as_str()
+                    .or_else(|| signer["inbox"].as_str())
+                    .ok_or_else(|| DomainError::Validation("actor has no inbox".into()))?
+                    .to_string();
+
+                self.federation
+                    .add_follower(&Follower {
+                        user_id,
+                        actor_url: actor_url.clone(),
+                        inbox_url,
+                        created_at: Utc::now(),
+                    })
+                    .await?;
+
+                let accept = json!({
+                    "@context": ACTIVITY_STREAMS,
+                    "id": format!("{}#accepts/{}", our_actor, Uuid::new_v4()),
+                    "type": "Accept",
+                    "actor": our_actor,
+                    "object": activity,
+                });
+                let actor_inbox = signer["inbox"].as_str().unwrap_or_default();
+                self.deliver(user_id, actor_inbox, &accept).await?;
+            }
+            Some("Undo") if activity["object"]["type"].as_str() == Some("Follow") => {
+                self.federation.remove_follower(user_id, &actor_url).await?;
+                info!(user_id = %user_id, actor = %actor_url, "remote follower removed");
+            }
+            other => {
+                info!(user_id = %user_id, activity_type = ?other, "inbox activity ignored");
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Delivers new public posts from `events` to their authors' followers in
+    /// a background task until the bus closes.
+    ///
+    /// Deliveries are attempted once; failures are logged.
+    pub fn spawn_publisher(&self, events: &EventBus) {
+        let service = self.clone();
+        let mut events = events.subscribe();
+
+        tokio::spawn(async move {
+            loop {
+                let event = match events.recv().await {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(skipped, "federation publisher lagged behind");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => return,
+                };
+
+                let EventKind::PostCreated { post_id, .. } = event.kind else {
+                    continue;
+                };
+                if let Err(e) = service.publish(post_id, event.at).await {
+                    warn!(post_id = %post_id, error = %e, "failed to publish post to followers");
+                }
+            }
+        });
+    }
+
+    /// Sends a `Create` activity for a new post to every follower inbox of
+    /// its author.
+    async fn publish(&self, post_id: Uuid, announced_at: DateTime<Utc>) -> Result<(), DomainError> {
+        let Some(post) = self.posts.get(post_id).await? else {
+            return Ok(());
+        };
+        if post.visibility != Visibility::Public
+            || post.created_at < announced_at - Duration::minutes(MAX_DELIVERY_DELAY_MINUTES)
+        {
+            return Ok(());
+        }
+
+        let mut inboxes: Vec<String> = self
+            .federation
+            .list_followers(post.author_id)
+            .await?
+            .into_iter()
+            .map(|follower| follower.inbox_url)
+            .collect();
+        inboxes.sort();
+        inboxes.dedup();
+
+        let activity = self.create_activity(&post);
+        for inbox in &inboxes {
+            if let Err(e) = self.deliver(post.author_id, inbox, &activity).await {
+                warn!(post_id = %post_id, inbox = %inbox, error = %e, "post delivery failed");
+            }
+        }
+
+        info!(post_id = %post_id, inboxes = inboxes.len(), "post published to followers");
+        Ok(())
+    }
+
+    /// POSTs an activity to a remote inbox, signed with the author's key.
+    async fn deliver(
+        &self,
+        user_id: Uuid,
+        inbox: &str,
+        activity: &Value,
+    ) -> Result<(), DomainError> {
+        let deliver_err =
+            |e: reqwest::Error| DomainError::Internal(format!("delivery failed: {}", e));
+
+        let url = reqwest::Url::parse(inbox)
+            .map_err(|e| DomainError::Validation(format!("invalid inbox URL {}: {}", inbox, e)))?;
+        let body = serde_json::to_vec(activity)
+            .map_err(|e| DomainError::Internal(format!("serialization error: {}", e)))?;
+        let keys = self.keys(user_id).await?;
+        let signed = sign_request(
+            &keys.private_key_pem,
+            &key_id(&self.actor_url(user_id)),
+            "POST",
+            &url,
+            Some(&body),
+        )?;
+
+        let mut request = self
+            .client
+            .post(url)
+            .header("Content-Type", ACTIVITY_JSON)
+            .header("Date", signed.date)
+            .header("Signature", signed.signature);
+        if let Some(digest) = signed.digest {
+            request = request.header("Digest", digest);
+        }
+
+        request
+            .body(body)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(deliver_err)?;
+
+        Ok(())
+    }
+
+    /// Fetches the actor owning `key_id`, with its PEM public key.
+    ///
+    /// `key_id` may point to the actor itself or to a standalone key
+    /// document naming its owner; either way the actor must publish the key.
+    async fn signer(&self, key_id: &str) -> Result<(Value, String), DomainError> {
+        let unavailable = |reason: String| {
+            DomainError::InvalidCredentials(format!("signing key unavailable: {}", reason))
+        };
+
+        let document = self
+            .fetch_json(key_id)
+            .await
+            .map_err(|e| unavailable(e.to_string()))?;
+        let actor = match document["owner"].as_str() {
+            Some(owner) if !document["publicKey"].is_object() => self
+                .fetch_json(owner)
+                .await
+                .map_err(|e| unavailable(e.to_string()))?,
+            _ => document,
+        };
+
+        let key = &actor["publicKey"];
+        if key["id"].as_str() != Some(key_id) {
+            return Err(unavailable(format!(
+                "{} is not published by its owner",
+                key_id
+            )));
+        }
+        let pem = key["publicKeyPem"]
+            .as_str()
+            .ok_or_else(|| unavailable(format!("{} has no PEM", key_id)))?
+            .to_string();
+
+        Ok((actor, pem))
+    }
+
+    /// Fetches a remote ActivityPub document.
+    async fn fetch_json(&self, url: &str) -> Result<Value, DomainError> {
+        let fetch_err = |e: reqwest::Error| DomainError::Internal(format!("fetch failed: {}", e));
+
+        if !url.starts_with("https://") && !url.starts_with("http://") {
+            return Err(DomainError::Validation(format!(
+                "not an http(s) URL: {}",
+                url
+            )));
+        }
+
+        self.client
+            .get(url)
+            .header("Accept", ACTIVITY_JSON)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(fetch_err)?
+            .json()
+            .await
+            .map_err(fetch_err)
+    }
+
+    /// Returns an author's keys, generating and storing them on first use.
+    async fn keys(&self, user_id: Uuid) -> Result<ActorKeys, DomainError> {
+        if let Some(keys) = self.federation.get_keys(user_id).await? {
+            return Ok(keys);
+        }
+
+        let pair = tokio::task::spawn_blocking(generate_key_pair)
+            .await
+            .map_err(|e| DomainError::Internal(format!("key generation task failed: {}", e)))??;
+        info!(user_id = %user_id, "federation keys generated");
+
+        self.federation
+            .save_keys(&ActorKeys {
+                user_id,
+                public_key_pem: pair.public_key_pem,
+                private_key_pem: pair.private_key_pem,
+            })
+            .await
+    }
+
+    /// Returns a user who may be federated: existing, not deleted and not
+    /// disabled.
+    async fn active_user(&self, user_id: Uuid) -> Result<User, DomainError> {
+        self.users
+            .find_by_id(user_id)
+            .await?
+            .filter(is_active)
+            .ok_or_else(|| DomainError::UserNotFound(format!("user id: {}", user_id)))
+    }
+
+    /// Returns a `Create` activity for a post.
+    fn create_activity(&self, post: &Post) -> Value {
+        let actor_url = self.actor_url(post.author_id);
+
+        json!({
+            "@context": ACTIVITY_STREAMS,
+            "id": format!("{}/activity", self.article_url(post.id)),
+            "type": "Create",
+            "actor": actor_url,
+            "published": post.created_at.to_rfc3339(),
+            "to": [PUBLIC_AUDIENCE],
+            "cc": [format!("{}/followers", actor_url)],
+            "object": self.article_object(post),
+        })
+    }
+
+    /// Returns a post as an `Article`: the title and a link as HTML content,
+    /// with the Markdown body as its source.
+    fn article_object(&self, post: &Post) -> Value {
+        let actor_url = self.actor_url(post.author_id);
+        let url = self.article_url(post.id);
+        let tags: Vec<Value> = post
+            .tags
+            .iter()
+            .map(|tag| json!({ "type": "Hashtag", "name": format!("#{}", tag) }))
+            .collect();
+
+        json!({
+            "id": url,
+            "type": "Article",
+            "attributedTo": actor_url,
+            "name": post.title,
+            "content": format!(
+                r#"<p>{}</p><p><a href="{}">{}</a></p>"#,
+                escape_html(&post.title),
+                url,
+                url
+            ),
+            "source": { "content": post.content, "mediaType": "text/markdown" },
+            "url": url,
+            "published": post.created_at.to_rfc3339(),
+            "updated": post.updated_at.to_rfc3339(),
+            "tag": tags,
+            "to": [PUBLIC_AUDIENCE],
+            "cc": [format!("{}/followers", actor_url)],
+        })
+    }
+
+    /// Returns the ID (URL) of an author's actor.
+    fn actor_url(&self, user_id: Uuid) -> String {
+        format!("{}/ap/users/{}", self.base_url, user_id)
+    }
+
+    /// Returns the ID (URL) of a post's `Article`.
+    fn article_url(&self, post_id: Uuid) -> String {
+        format!("{}/ap/posts/{}", self.base_url, post_id)
+    }
+}
+
+/// Returns whether a user is neither deleted nor disabled.
+fn is_active(user: &User) -> bool {
+    user.deleted_at.is_none() && !user.is_disabled()
+}
+
+/// Returns the ID of the key an actor signs with.
+fn key_id(actor_url: &str) -> String {
+    format!("{}#main-key", actor_url)
+}
+
+/// Returns the ID of an object given inline or by reference.
+fn object_id(value: &Value) -> Option<String> {
+    value
+        .as_str()
+        .or_else(|| value["id"].as_str())
+        .map(str::to_string)
+}
+
+/// Escapes text for HTML content.
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
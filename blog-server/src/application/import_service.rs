@@ -0,0 +1,246 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::application::media_service::MediaService;
+use crate::application::post_service::PostService;
+use crate::data::media_repository::MediaRepository;
+use crate::data::post_repository::PostRepository;
+use crate::data::revision_repository::RevisionRepository;
+use crate::data::user_repository::UserRepository;
+use crate::domain::error::DomainError;
+use crate::domain::import::{
+    AuthorMapping, ImageFailure, ImportFormat, ImportSummary, ImportedPost, SkippedItem,
+    SourceDocument, SourcePost,
+};
+use crate::domain::tag::normalize_tags_lenient;
+use crate::infrastructure::import_formats::{image_sources, parse_export};
+use crate::infrastructure::remote_media::RemoteMediaFetcher;
+
+/// Blog import service.
+///
+/// Brings posts over from WordPress and Medium exports: maps their authors to
+/// local accounts, copies referenced images into the media store and reports
+/// what happened to every entry.
+#[derive(Clone)]
+pub struct ImportService<P, V, U, M>
+where
+    P: PostRepository + 'static,
+    V: RevisionRepository + 'static,
+    U: UserRepository + 'static,
+    M: MediaRepository + 'static,
+{
+    posts: PostService<P, V>,
+    users: Arc<U>,
+    media: MediaService<M>,
+    fetcher: RemoteMediaFetcher,
+    max_bytes: usize,
+}
+
+impl<P, V, U, M> ImportService<P, V, U, M>
+where
+    P: PostRepository + 'static,
+    V: RevisionRepository + 'static,
+    U: UserRepository + 'static,
+    M: MediaRepository + 'static,
+{
+    /// Creates a new import service accepting exports of up to `max_bytes`
+    /// and downloading images with `fetcher`.
+    pub fn new(
+        posts: PostService<P, V>,
+        users: Arc<U>,
+        media: MediaService<M>,
+        fetcher: RemoteMediaFetcher,
+        max_bytes: usize,
+    ) -> Self {
+        Self {
+            posts,
+            users,
+            media,
+            fetcher,
+            max_bytes,
+        }
+    }
+
+    /// Largest accepted export, in bytes.
+    pub fn max_bytes(&self) -> usize {
+        self.max_bytes
+    }
+
+    /// Imports an export, creating its posts.
+    ///
+    /// Posts go to the local account with their author's email, or to
+    /// `default_author`. Images are re-hosted and their URLs rewritten to the
+    /// public media path under `api_path`; images that cannot be fetched keep
+    /// their original URL. A post that cannot be imported is skipped and
+    /// reported without stopping the import.
+    pub async fn import(
+        &self,
+        format: ImportFormat,
+        bytes: Vec<u8>,
+        default_author: Uuid,
+        api_path: &str,
+    ) -> Result<ImportSummary, DomainError> {
+        self.users
+            .find_by_id(default_author)
+            .await?
+            .ok_or_else(|| DomainError::UserNotFound(format!("user id: {}", default_author)))?;
+
+        let document = tokio::task::spawn_blocking(move || parse_export(format, &bytes))
+            .await
+            .map_err(|e| DomainError::Internal(format!("import parse task error: {}", e)))??;
+        let SourceDocument {
+            authors,
+            posts,
+            skipped,
+        } = document;
+
+        let mut summary = ImportSummary {
+            skipped,
+            ..ImportSummary::default()
+        };
+
+        let mut author_ids: HashMap<String, Uuid> = HashMap::new();
+        for author in authors {
+            let user = match &author.email {
+                Some(email) => self.users.find_by_email(email).await?,
+                None => None,
+            }
+            .filter(|user| user.disabled_at.is_none() && user.deleted_at.is_none());
+
+            let user_id = user.as_ref().map_or(default_author, |user| user.id);
+            author_ids.insert(author.login.clone(), user_id);
+            summary.authors.push(AuthorMapping {
+                source: author.display_name.unwrap_or(author.login),
+                user_id,
+                matched: user.is_some(),
+            });
+        }
+
+        // Images shared by several posts are downloaded once.
+        let mut rehosted: HashMap<String, Option<Uuid>> = HashMap::new();
+
+        for post in posts {
+            let author_id = post
+                .author
+                .as_ref()
+                .and_then(|login| author_ids.get(login))
+                .copied()
+                .unwrap_or(default_author);
+
+            match self
+                .import_post(post, author_id, api_path, &mut rehosted, &mut summary)
+                .await
+            {
+                Ok(imported) => summary.imported.push(imported),
+                Err(skipped) => summary.skipped.push(skipped),
+            }
+        }
+
+        info!(
+            format = %format,
+            imported = summary.imported.len(),
+            skipped = summary.skipped.len(),
+            images = summary.images_imported,
+            image_failures = summary.image_failures.len(),
+            "blog export imported"
+        );
+
+        Ok(summary)
+    }
+
+    /// Re-hosts a post's images and creates it, or reports why it was skipped.
+    async fn import_post(
+        &self,
+        post: SourcePost,
+        author_id: Uuid,
+        api_path: &str,
+        rehosted: &mut HashMap<String, Option<Uuid>>,
+        summary: &mut ImportSummary,
+    ) -> Result<ImportedPost, SkippedItem> {
+        let (tags, dropped) = normalize_tags_lenient(post.tags);
+        summary.warnings.extend(
+            dropped
+                .into_iter()
+                .map(|reason| format!("{}: {}", post.source_id, reason)),
+        );
+
+        let mut content = post.content;
+        let mut images = 0;
+        for url in image_sources(&content) {
+            let media_id = match rehosted.get(&url) {
+                Some(media_id) => *media_id,
+                None => {
+                    let media_id = match self.rehost(&url, author_id).await {
+                        Ok(media_id) => {
+                            summary.images_imported += 1;
+                            Some(media_id)
+                        }
+                        Err(e) => {
+                            warn!(url = %url, error = %e, "failed to import image");
+                            summary.image_failures.push(ImageFailure {
+                                url: url.clone(),
+                                reason: e.to_string(),
+                            });
+                            None
+                        }
+                    };
+                    rehosted.insert(url.clone(), media_id);
+                    media_id
+                }
+            };
+
+            if let Some(media_id) = media_id {
+                let local = format!("{}/public/media/{}", api_path, media_id);
+                content = content
+                    .replace(&url.replace('&', "&amp;"), &local)
+                    .replace(&url, &local);
+                images += 1;
+            }
+        }
+
+        let created = self
+            .posts
+            .import_post(
+                post.title.clone(),
+                content,
+                tags,
+                post.visibility,
+                author_id,
+                post.published_at,
+            )
+            .await;
+
+        match created {
+            Ok(created) => Ok(ImportedPost {
+                source_id: post.source_id,
+                post_id: created.id,
+                title: created.title,
+                images,
+            }),
+            Err(e) => Err(SkippedItem {
+                source_id: post.source_id,
+                title: post.title,
+                reason: e.to_string(),
+            }),
+        }
+    }
+
+    /// Downloads an image into the media store on behalf of `owner_id`.
+    async fn rehost(&self, url: &str, owner_id: Uuid) -> Result<Uuid, DomainError> {
+        let bytes = self.fetcher.fetch(url, self.media.max_bytes()).await?;
+        let media = self.media.upload(owner_id, &image_filename(url), bytes).await?;
+        Ok(media.id)
+    }
+}
+
+/// Returns the file name an image URL ends with.
+fn image_filename(url: &str) -> String {
+    url.split(['?', '#'])
+        .next()
+        .and_then(|path| path.rsplit('/').next())
+        .filter(|name| !name.is_empty())
+        .unwrap_or("image")
+        .to_string()
+}
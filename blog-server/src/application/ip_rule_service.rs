@@ -0,0 +1,114 @@
+use std::net::IpAddr;
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::data::ip_rule_repository::IpRuleRepository;
+use crate::domain::error::DomainError;
+use crate::domain::ip_rule::{IpFilter, IpNet, IpRule, IpRuleAction};
+
+/// Client address allow and deny lists.
+///
+/// Combines the rules given in the configuration, which cannot be changed
+/// at runtime, with those administrators add through the API. Requests are
+/// checked against an in-memory copy of the rules, rebuilt whenever this
+/// instance changes them and periodically to pick up changes made by others.
+#[derive(Clone)]
+pub struct IpRuleService {
+    repo: Arc<dyn IpRuleRepository>,
+    configured: Vec<(IpNet, IpRuleAction)>,
+    filter: Arc<RwLock<IpFilter>>,
+}
+
+impl IpRuleService {
+    /// Creates a service applying the configured `allow` and `deny` blocks
+    /// together with the rules stored in `repo`.
+    ///
+    /// Only the configured blocks apply until [`Self::reload`] first runs.
+    pub fn new(repo: Arc<dyn IpRuleRepository>, allow: &[IpNet], deny: &[IpNet]) -> Self {
+        let configured: Vec<_> = allow
+            .iter()
+            .map(|network| (*network, IpRuleAction::Allow))
+            .chain(deny.iter().map(|network| (*network, IpRuleAction::Deny)))
+            .collect();
+        let filter = Arc::new(RwLock::new(IpFilter::new(configured.clone())));
+
+        Self {
+            repo,
+            configured,
+            filter,
+        }
+    }
+
+    /// Returns whether requests from `ip` are let through.
+    pub fn allows(&self, ip: IpAddr) -> bool {
+        self.filter
+            .read()
+            .unwrap_or_else(|e| e.into_inner())
+            .allows(ip)
+    }
+
+    /// Returns the rules added through the API, oldest first.
+    pub async fn list_rules(&self) -> Result<Vec<IpRule>, DomainError> {
+        self.repo.list().await
+    }
+
+    /// Adds a rule, in effect on this instance immediately.
+    pub async fn add_rule(
+        &self,
+        network: &str,
+        action: IpRuleAction,
+        reason: Option<String>,
+    ) -> Result<IpRule, DomainError> {
+        let network = network.parse().map_err(DomainError::Validation)?;
+        let reason = reason.filter(|reason| !reason.trim().is_empty());
+
+        let rule = IpRule::new(network, action, reason);
+        self.repo.create(&rule).await?;
+        self.reload().await?;
+
+        Ok(rule)
+    }
+
+    /// Removes a rule, in effect on this instance immediately.
+    pub async fn remove_rule(&self, id: Uuid) -> Result<(), DomainError> {
+        self.repo.delete(id).await?;
+        self.reload().await
+    }
+
+    /// Rebuilds the in-memory rules from the configuration and the stored rules.
+    pub async fn reload(&self) -> Result<(), DomainError> {
+        let stored = self.repo.list().await?;
+        let rules = self
+            .configured
+            .iter()
+            .copied()
+            .chain(stored.iter().map(|rule| (rule.network, rule.action)));
+
+        let filter = IpFilter::new(rules);
+        *self.filter.write().unwrap_or_else(|e| e.into_inner()) = filter;
+
+        Ok(())
+    }
+
+    /// Reloads the stored rules every `period` in a background task, so rules
+    /// changed through another server instance apply here too.
+    pub fn spawn_refresh(&self, period: Duration) {
+        let rules = self.clone();
+
+        tokio::spawn(async move {
+            let mut ticker = interval(period);
+            ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+            loop {
+                ticker.tick().await;
+                if let Err(e) = rules.reload().await {
+                    warn!(error = %e, "failed to reload IP rules");
+                }
+            }
+        });
+    }
+}
@@ -0,0 +1,415 @@
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::data::media_repository::MediaRepository;
+use crate::domain::error::DomainError;
+use crate::domain::media::{
+    sanitize_filename, sniff_content_type, Media, MediaFile, MediaUpload, MediaVariant,
+    VariantName,
+};
+use crate::domain::quota::Quota;
+use crate::infrastructure::image_processing::render_variants;
+use crate::infrastructure::media_storage::{content_hash, MediaStorage};
+
+/// Largest chunk of a chunked upload accepted in one request, in bytes.
+pub const MAX_UPLOAD_CHUNK_BYTES: usize = 4 * 1024 * 1024;
+
+/// Unreferenced blobs removed per transaction during garbage collection.
+const BLOB_GC_BATCH: i64 = 100;
+
+/// Media upload service.
+///
+/// Validates uploaded images, stores them, renders their resized variants in
+/// the background, and serves them back. Large files may also arrive in
+/// chunks over several requests, so an interrupted upload can resume.
+/// Identical files are stored once, however many users upload them.
+#[derive(Clone)]
+pub struct MediaService<R: MediaRepository + 'static> {
+    repo: Arc<R>,
+    storage: MediaStorage,
+    max_bytes: usize,
+    quota: Quota,
+}
+
+impl<R> MediaService<R>
+where
+    R: MediaRepository + 'static,
+{
+    /// Creates a new media service accepting files of up to `max_bytes`
+    /// from users within the media storage limit of `quota`.
+    pub fn new(repo: Arc<R>, storage: MediaStorage, max_bytes: usize, quota: Quota) -> Self {
+        Self {
+            repo,
+            storage,
+            max_bytes,
+            quota,
+        }
+    }
+
+    /// Largest accepted upload, in bytes.
+    pub fn max_bytes(&self) -> usize {
+        self.max_bytes
+    }
+
+    /// Stores an uploaded image.
+    ///
+    /// The content type is detected from the file itself; whatever the
+    /// client declared is ignored. Uploads that would take the owner over
+    /// their media storage limit are refused.
+    pub async fn upload(
+        &self,
+        owner_id: Uuid,
+        filename: &str,
+        bytes: Vec<u8>,
+    ) -> Result<Media, DomainError> {
+        if bytes.is_empty() {
+            return Err(DomainError::Validation("file must not be empty".into()));
+        }
+        if bytes.len() > self.max_bytes {
+            return Err(DomainError::PayloadTooLarge(format!(
+                "file exceeds {} bytes",
+                self.max_bytes
+            )));
+        }
+
+        let content_type = sniff_content_type(&bytes).ok_or_else(|| {
+            DomainError::Validation("only PNG, JPEG, GIF and WebP images are supported".into())
+        })?;
+        self.quota.check_media(self.storage_used(owner_id).await?, bytes.len())?;
+
+        let hash = content_hash(&bytes);
+        let media = Media::new(
+            owner_id,
+            sanitize_filename(filename),
+            content_type.to_string(),
+            bytes.len() as i64,
+            hash.clone(),
+        );
+
+        // Retained before writing, so garbage collection cannot remove the
+        // blob between the write and the metadata referencing it.
+        self.repo.retain_blob(&hash, media.size_bytes).await?;
+        let stored = match self.storage.write_blob(&hash, &bytes).await {
+            Ok(()) => self.repo.create(media).await,
+            Err(e) => Err(e),
+        };
+
+        match stored {
+            Ok(media) => {
+                self.spawn_variants(&media, bytes);
+                Ok(media)
+            }
+            Err(e) => {
+                // Nothing references the blob after all; let collection have it.
+                if let Err(e) = self.repo.release_blob(&hash).await {
+                    warn!(sha256 = %hash, error = %e, "failed to release blob");
+                }
+                Err(e)
+            }
+        }
+    }
+
+    /// Returns the user's uploads, oldest first.
+    pub async fn list(&self, owner_id: Uuid) -> Result<Vec<Media>, DomainError> {
+        self.repo.list_by_owner(owner_id).await
+    }
+
+    /// Deletes one of the user's uploads and its variants.
+    ///
+    /// Contents shared with other uploads stay; the blob is collected once
+    /// nothing references it.
+    pub async fn delete(&self, owner_id: Uuid, id: Uuid) -> Result<(), DomainError> {
+        let media = self.get_media(id).await?;
+        if media.owner_id != owner_id {
+            return Err(DomainError::Forbidden(
+                "media belongs to another user".into(),
+            ));
+        }
+
+        let Some(media) = self.repo.delete(id).await? else {
+            return Err(DomainError::MediaNotFound(format!("media id: {}", id)));
+        };
+        if let Err(e) = self.storage.remove(&media).await {
+            warn!(media_id = %id, error = %e, "failed to remove media files");
+        }
+
+        Ok(())
+    }
+
+    /// Removes the blobs no upload references any more; returns how many
+    /// were removed.
+    ///
+    /// Each batch of blobs stays locked until its files are gone, so an
+    /// upload of the same contents waits and then stores them afresh.
+    pub async fn collect_garbage(&self) -> Result<u64, DomainError> {
+        let mut removed = 0;
+
+        loop {
+            let mut uow = self.repo.begin().await?;
+            let hashes = self
+                .repo
+                .lock_unreferenced_blobs(&mut uow, BLOB_GC_BATCH)
+                .await?;
+            let batch_len = hashes.len();
+
+            let mut gone = Vec::with_capacity(batch_len);
+            for hash in hashes {
+                match self.storage.remove_blob(&hash).await {
+                    Ok(()) => gone.push(hash),
+                    Err(e) => warn!(sha256 = %hash, error = %e, "failed to remove blob"),
+                }
+            }
+            self.repo.delete_blobs(&mut uow, &gone).await?;
+            uow.commit().await?;
+
+            removed += gone.len() as u64;
+            if batch_len < BLOB_GC_BATCH as usize {
+                return Ok(removed);
+            }
+        }
+    }
+
+    /// Starts a chunked upload of a file of `size` bytes.
+    ///
+    /// The announced size is checked against the upload and storage limits
+    /// up front, so a client does not send most of a file only to have it
+    /// refused.
+    pub async fn start_upload(
+        &self,
+        owner_id: Uuid,
+        filename: &str,
+        size: i64,
+    ) -> Result<MediaUpload, DomainError> {
+        if size <= 0 {
+            return Err(DomainError::Validation("file must not be empty".into()));
+        }
+        if size as u64 > self.max_bytes as u64 {
+            return Err(DomainError::PayloadTooLarge(format!(
+                "file exceeds {} bytes",
+                self.max_bytes
+            )));
+        }
+        self.quota.check_media(self.storage_used(owner_id).await?, size as usize)?;
+
+        let upload = MediaUpload::new(owner_id, sanitize_filename(filename), size);
+        self.repo.create_upload(&upload).await?;
+
+        Ok(upload)
+    }
+
+    /// Returns one of the user's chunked uploads still in progress.
+    pub async fn get_upload(&self, owner_id: Uuid, id: Uuid) -> Result<MediaUpload, DomainError> {
+        self.repo
+            .get_upload(id)
+            .await?
+            .filter(|upload| upload.owner_id == owner_id)
+            .ok_or_else(|| DomainError::MediaNotFound(format!("upload id: {}", id)))
+    }
+
+    /// Stores the next chunk of a chunked upload, which must start right
+    /// after the bytes received so far.
+    ///
+    /// A chunk starting anywhere else is refused as a conflict; the client
+    /// then asks for the upload to learn where to resume.
+    pub async fn upload_chunk(
+        &self,
+        owner_id: Uuid,
+        id: Uuid,
+        offset: i64,
+        bytes: Vec<u8>,
+    ) -> Result<MediaUpload, DomainError> {
+        let upload = self.get_upload(owner_id, id).await?;
+        let len = bytes.len() as i64;
+
+        if offset != upload.received_bytes {
+            return Err(offset_conflict(&upload, offset));
+        }
+        if bytes.is_empty() {
+            return Err(DomainError::Validation("chunk must not be empty".into()));
+        }
+        if offset + len > upload.size_bytes {
+            return Err(DomainError::Validation(format!(
+                "chunk ends past the announced size of {} bytes",
+                upload.size_bytes
+            )));
+        }
+
+        self.storage.write_upload_chunk(id, offset as u64, &bytes).await?;
+        self.repo
+            .advance_upload(id, offset, len)
+            .await?
+            .ok_or_else(|| offset_conflict(&upload, offset))
+    }
+
+    /// Turns a fully received chunked upload into stored media.
+    ///
+    /// The file goes through the same checks as a single-request upload; if
+    /// it fails them the upload stays until cancelled or purged.
+    pub async fn complete_upload(&self, owner_id: Uuid, id: Uuid) -> Result<Media, DomainError> {
+        let upload = self.get_upload(owner_id, id).await?;
+        if !upload.is_complete() {
+            return Err(DomainError::Conflict(format!(
+                "upload {} has received {} of {} bytes",
+                id, upload.received_bytes, upload.size_bytes
+            )));
+        }
+
+        let bytes = self.storage.read_upload(id).await?;
+        let media = self.upload(owner_id, &upload.filename, bytes).await?;
+        self.discard_upload(id).await;
+
+        Ok(media)
+    }
+
+    /// Abandons one of the user's chunked uploads.
+    pub async fn cancel_upload(&self, owner_id: Uuid, id: Uuid) -> Result<(), DomainError> {
+        self.get_upload(owner_id, id).await?;
+        self.discard_upload(id).await;
+        Ok(())
+    }
+
+    /// Removes chunked uploads that received nothing for `ttl`; returns how
+    /// many were removed.
+    pub async fn purge_stale_uploads(&self, ttl: Duration) -> Result<u64, DomainError> {
+        let ids = self.repo.delete_stale_uploads(Utc::now() - ttl).await?;
+        for id in &ids {
+            if let Err(e) = self.storage.remove_upload(*id).await {
+                warn!(upload_id = %id, error = %e, "failed to remove stale upload");
+            }
+        }
+
+        Ok(ids.len() as u64)
+    }
+
+    /// Removes a chunked upload and its partial file, logging failures.
+    async fn discard_upload(&self, id: Uuid) {
+        if let Err(e) = self.repo.delete_upload(id).await {
+            warn!(upload_id = %id, error = %e, "failed to delete upload");
+        }
+        if let Err(e) = self.storage.remove_upload(id).await {
+            warn!(upload_id = %id, error = %e, "failed to remove upload file");
+        }
+    }
+
+    /// Renders and stores the resized variants of an upload in a background task.
+    ///
+    /// Failures are logged; until a variant exists, the original is served for it.
+    fn spawn_variants(&self, media: &Media, bytes: Vec<u8>) {
+        let repo = Arc::clone(&self.repo);
+        let storage = self.storage.clone();
+        let id = media.id;
+        let content_type = media.content_type.clone();
+
+        tokio::spawn(async move {
+            match store_variants(repo.as_ref(), &storage, id, content_type, bytes).await {
+                Ok(()) => info!(media_id = %id, "media variants generated"),
+                Err(e) => warn!(media_id = %id, error = %e, "media variant generation failed"),
+            }
+        });
+    }
+
+    /// Returns the bytes of media the given user stores.
+    pub async fn storage_used(&self, owner_id: Uuid) -> Result<i64, DomainError> {
+        self.repo.total_size(owner_id).await
+    }
+
+    /// Returns media metadata by its ID.
+    pub async fn get_media(&self, id: Uuid) -> Result<Media, DomainError> {
+        self.repo
+            .get(id)
+            .await?
+            .ok_or_else(|| DomainError::MediaNotFound(format!("media id: {}", id)))
+    }
+
+    /// Returns media metadata together with the file contents.
+    pub async fn read_media(&self, id: Uuid) -> Result<(Media, Vec<u8>), DomainError> {
+        let media = self.get_media(id).await?;
+        let bytes = self.storage.read(&media).await?;
+
+        Ok((media, bytes))
+    }
+
+    /// Returns the metadata of the variants generated so far, smallest first.
+    pub async fn list_variants(&self, id: Uuid) -> Result<Vec<MediaVariant>, DomainError> {
+        self.get_media(id).await?;
+        self.repo.list_variants(id).await
+    }
+
+    /// Returns one variant of a media file.
+    ///
+    /// Falls back to the original while the variant is being generated; the
+    /// returned file names the variant it actually is.
+    pub async fn read_variant(
+        &self,
+        id: Uuid,
+        name: VariantName,
+    ) -> Result<MediaFile, DomainError> {
+        if name != VariantName::Original {
+            if let Some(variant) = self.repo.get_variant(id, name).await? {
+                let bytes = self.storage.read_variant(id, name).await?;
+                return Ok(MediaFile {
+                    variant: name,
+                    content_type: variant.content_type,
+                    modified: variant.created_at,
+                    bytes,
+                });
+            }
+        }
+
+        let (media, bytes) = self.read_media(id).await?;
+        Ok(MediaFile {
+            variant: VariantName::Original,
+            content_type: media.content_type,
+            modified: media.created_at,
+            bytes,
+        })
+    }
+}
+
+/// Renders every variant of an uploaded image and stores the resized files
+/// and the metadata of all of them, the original included.
+async fn store_variants<R: MediaRepository>(
+    repo: &R,
+    storage: &MediaStorage,
+    id: Uuid,
+    content_type: String,
+    bytes: Vec<u8>,
+) -> Result<(), DomainError> {
+    let original_size = bytes.len() as i64;
+    let variants = tokio::task::spawn_blocking(move || render_variants(&bytes, &content_type))
+        .await
+        .map_err(|e| DomainError::Internal(format!("image task error: {}", e)))??;
+
+    for variant in variants {
+        let size_bytes = match &variant.bytes {
+            Some(bytes) => {
+                storage.write_variant(id, variant.name, bytes).await?;
+                bytes.len() as i64
+            }
+            None => original_size,
+        };
+
+        repo.upsert_variant(&MediaVariant {
+            media_id: id,
+            name: variant.name,
+            content_type: variant.content_type,
+            width: variant.width as i32,
+            height: variant.height as i32,
+            size_bytes,
+            created_at: Utc::now(),
+        })
+        .await?;
+    }
+
+    Ok(())
+}
+
+/// Builds the error for a chunk that does not start where the upload left off.
+fn offset_conflict(upload: &MediaUpload, offset: i64) -> DomainError {
+    DomainError::Conflict(format!(
+        "upload {} expects a chunk at byte {}, not {}",
+        upload.id, upload.received_bytes, offset
+    ))
+}
@@ -0,0 +1,386 @@
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::application::post_service::{DEFAULT_PAGE_LIMIT, MAX_PAGE_LIMIT};
+use crate::data::mention_repository::MentionRepository;
+use crate::data::post_repository::PostRepository;
+use crate::domain::error::DomainError;
+use crate::domain::event::EventKind;
+use crate::domain::mention::{Mention, MentionStatus};
+use crate::domain::page::Page;
+use crate::domain::post::{Post, Visibility};
+use crate::infrastructure::events::EventBus;
+use crate::infrastructure::rate_limit::RateLimiter;
+use crate::infrastructure::webmention::{extract_links, SourceCheck, WebmentionClient};
+
+/// Links in a new post that are notified, at most.
+const MAX_OUTBOUND_LINKS: usize = 20;
+
+/// Posts created longer than this before they are announced (imported ones,
+/// say) do not send webmentions.
+const MAX_SEND_DELAY_MINUTES: i64 = 60;
+
+/// Webmention service.
+///
+/// Receives webmentions for public posts, verifies them in the background and
+/// holds them for moderation, and notifies the sites new posts link to.
+#[derive(Clone)]
+pub struct MentionService<M, P>
+where
+    M: MentionRepository + 'static,
+    P: PostRepository + 'static,
+{
+    mentions: Arc<M>,
+    posts: Arc<P>,
+    client: WebmentionClient,
+    site_url: Option<reqwest::Url>,
+    api_path: String,
+    ip_limiter: RateLimiter,
+}
+
+impl<M, P> MentionService<M, P>
+where
+    M: MentionRepository + 'static,
+    P: PostRepository + 'static,
+{
+    /// Creates a new webmention service for the site at `site_url`, whose
+    /// API is mounted at `api_path`.
+    ///
+    /// Without a `site_url`, webmentions are neither received nor sent and
+    /// only the stored ones can be listed and moderated. Received webmentions
+    /// are limited per client IP by `ip_limiter`.
+    pub fn new(
+        mentions: Arc<M>,
+        posts: Arc<P>,
+        client: WebmentionClient,
+        site_url: Option<&str>,
+        api_path: String,
+        ip_limiter: RateLimiter,
+    ) -> Result<Self, DomainError> {
+        let site_url = site_url
+            .map(reqwest::Url::parse)
+            .transpose()
+            .map_err(|e| DomainError::Internal(format!("invalid webmention site URL: {}", e)))?;
+
+        Ok(Self {
+            mentions,
+            posts,
+            client,
+            site_url,
+            api_path,
+            ip_limiter,
+        })
+    }
+
+    /// Accepts a webmention telling that `source` links to `target`, one of
+    /// this site's public posts.
+    ///
+    /// The source is fetched and checked in the background; mentions whose
+    /// source links to the post are held until a moderator approves them,
+    /// and mentions whose source no longer does are removed. Sending the same
+    /// mention again re-checks it.
+    pub async fn receive(
+        &self,
+        source: String,
+        target: String,
+        ip: Option<&str>,
+    ) -> Result<Mention, DomainError> {
+        let ip_allowed = match ip {
+            Some(ip) => self.ip_limiter.check(ip).await,
+            None => true,
+        };
+        if !ip_allowed {
+            return Err(DomainError::RateLimited(
+                "too many webmentions; try again later".into(),
+            ));
+        }
+
+        let source_url = parse_http_url(&source, "source")?;
+        let target_url = parse_http_url(&target, "target")?;
+        if source_url == target_url {
+            return Err(DomainError::Validation(
+                "source and target must differ".into(),
+            ));
+        }
+
+        let post = self.target_post(&target_url).await?;
+        let mention = self
+            .mentions
+            .save(&Mention::new(post.id, source, target))
+            .await?;
+
+        info!(mention_id = %mention.id, post_id = %post.id, "webmention received");
+
+        let service = self.clone();
+        let received = mention.clone();
+        tokio::spawn(async move {
+            if let Err(e) = service.verify(received).await {
+                warn!(error = %e, "webmention verification failed");
+            }
+        });
+
+        Ok(mention)
+    }
+
+    /// Returns a page of a public post's published mentions, oldest first.
+    ///
+    /// Uses the same page size rules as post listings.
+    pub async fn list_mentions(
+        &self,
+        post_id: Uuid,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Page<Mention>, DomainError> {
+        self.posts
+            .get(post_id)
+            .await?
+            .filter(is_public)
+            .ok_or_else(|| DomainError::PostNotFound(format!("post id: {}", post_id)))?;
+
+        let limit = page_limit(limit);
+        let (items, total) = self
+            .mentions
+            .list_by_post(post_id, i64::from(limit), i64::from(offset))
+            .await?;
+
+        Ok(Page {
+            items,
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    /// Returns a page of the verified mentions awaiting a moderator, oldest
+    /// first.
+    pub async fn list_held(&self, limit: u32, offset: u32) -> Result<Page<Mention>, DomainError> {
+        let limit = page_limit(limit);
+        let (items, total) = self
+            .mentions
+            .list_held(i64::from(limit), i64::from(offset))
+            .await?;
+
+        Ok(Page {
+            items,
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    /// Publishes a held mention on its post.
+    pub async fn approve_mention(&self, id: Uuid) -> Result<Mention, DomainError> {
+        let mut mention = self.get_mention(id).await?;
+        if mention.status != MentionStatus::Held {
+            return Err(DomainError::Conflict(
+                "only verified mentions awaiting moderation can be approved".into(),
+            ));
+        }
+
+        mention.status = MentionStatus::Published;
+        self.mentions.update(&mention).await?;
+
+        Ok(mention)
+    }
+
+    /// Deletes a mention, whatever its status; for moderators.
+    pub async fn reject_mention(&self, id: Uuid) -> Result<(), DomainError> {
+        self.get_mention(id).await?;
+        self.mentions.delete(id).await
+    }
+
+    /// Sends webmentions for the links in new public posts from `events` in a
+    /// background task until the bus closes.
+    ///
+    /// Each link is tried once; failures are logged.
+    pub fn spawn_sender(&self, events: &EventBus) {
+        let service = self.clone();
+        let mut events = events.subscribe();
+
+        tokio::spawn(async move {
+            loop {
+                let event = match events.recv().await {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(skipped, "webmention sender lagged behind");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => return,
+                };
+
+                let EventKind::PostCreated { post_id, .. } = event.kind else {
+                    continue;
+                };
+                if let Err(e) = service.send_mentions(post_id, event.at).await {
+                    warn!(post_id = %post_id, error = %e, "failed to send webmentions");
+                }
+            }
+        });
+    }
+
+    /// Returns a mention by its ID.
+    async fn get_mention(&self, id: Uuid) -> Result<Mention, DomainError> {
+        self.mentions
+            .get(id)
+            .await?
+            .ok_or_else(|| DomainError::MentionNotFound(format!("mention id: {}", id)))
+    }
+
+    /// Checks that a mention's source links to its target; holds the mention
+    /// for moderation if it does and removes it otherwise.
+    ///
+    /// Published mentions stay published when their source still links.
+    async fn verify(&self, mut mention: Mention) -> Result<(), DomainError> {
+        let check = match self
+            .client
+            .check_source(&mention.source, &mention.target)
+            .await
+        {
+            Ok(check) => check,
+            Err(e) => {
+                warn!(mention_id = %mention.id, error = %e, "webmention source unavailable");
+                SourceCheck::NoLink
+            }
+        };
+
+        let SourceCheck::Links(page) = check else {
+            self.mentions.delete(mention.id).await?;
+            info!(mention_id = %mention.id, "webmention removed: source does not link");
+            return Ok(());
+        };
+
+        if mention.status != MentionStatus::Published {
+            mention.status = MentionStatus::Held;
+        }
+        mention.author_name = page.author_name;
+        mention.author_url = page.author_url;
+        mention.title = page.title;
+        mention.excerpt = page.excerpt;
+        mention.verified_at = Some(Utc::now());
+        self.mentions.update(&mention).await?;
+
+        info!(
+            mention_id = %mention.id,
+            status = ?mention.status,
+            "webmention verified"
+        );
+        Ok(())
+    }
+
+    /// Sends a webmention to every site a new public post links to that
+    /// advertises an endpoint.
+    async fn send_mentions(
+        &self,
+        post_id: Uuid,
+        announced_at: DateTime<Utc>,
+    ) -> Result<(), DomainError> {
+        let Some(site_url) = &self.site_url else {
+            return Ok(());
+        };
+        let Some(post) = self.posts.get(post_id).await? else {
+            return Ok(());
+        };
+        if !is_public(&post)
+            || post.created_at < announced_at - Duration::minutes(MAX_SEND_DELAY_MINUTES)
+        {
+            return Ok(());
+        }
+
+        let source = format!(
+            "{}{}/public/posts/slug/{}",
+            site_url.as_str().trim_end_matches('/'),
+            self.api_path,
+            post.slug
+        );
+        let links: Vec<String> = extract_links(&post.content)
+            .into_iter()
+            .filter(|link| {
+                reqwest::Url::parse(link).is_ok_and(|url| url.host_str() != site_url.host_str())
+            })
+            .take(MAX_OUTBOUND_LINKS)
+            .collect();
+
+        let mut sent = 0;
+        for link in &links {
+            let endpoint = match self.client.discover_endpoint(link).await {
+                Ok(Some(endpoint)) => endpoint,
+                Ok(None) => continue,
+                Err(e) => {
+                    warn!(post_id = %post_id, link = %link, error = %e, "webmention discovery failed");
+                    continue;
+                }
+            };
+
+            match self.client.send(endpoint, &source, link).await {
+                Ok(()) => sent += 1,
+                Err(e) => {
+                    warn!(post_id = %post_id, link = %link, error = %e, "webmention sending failed");
+                }
+            }
+        }
+
+        info!(
+            post_id = %post_id,
+            links = links.len(),
+            sent,
+            "webmentions sent"
+        );
+        Ok(())
+    }
+
+    /// Returns the public post a webmention target points at: a frontend
+    /// permalink (`/#/p/{slug}`) or a post URL of the API, by slug or ID.
+    async fn target_post(&self, target: &reqwest::Url) -> Result<Post, DomainError> {
+        let not_ours = || DomainError::Validation("target is not a post on this site".into());
+
+        let Some(site_url) = &self.site_url else {
+            return Err(DomainError::Validation(
+                "webmentions are not accepted".into(),
+            ));
+        };
+        if target.host_str() != site_url.host_str()
+            || target.port_or_known_default() != site_url.port_or_known_default()
+        {
+            return Err(not_ours());
+        }
+
+        let path = target.path().trim_end_matches('/');
+        let post = if let Some(slug) = target.fragment().and_then(|f| f.strip_prefix("/p/")) {
+            self.posts.get_by_slug(slug).await?
+        } else if let Some((_, slug)) = path.rsplit_once("/posts/slug/") {
+            self.posts.get_by_slug(slug).await?
+        } else if let Some((_, id)) = path.rsplit_once("/posts/")
+            && let Ok(id) = Uuid::parse_str(id)
+        {
+            self.posts.get(id).await?
+        } else {
+            return Err(not_ours());
+        };
+
+        post.filter(is_public).ok_or_else(not_ours)
+    }
+}
+
+/// Returns whether a post is public and not deleted.
+fn is_public(post: &Post) -> bool {
+    post.visibility == Visibility::Public && post.deleted_at.is_none()
+}
+
+/// Parses a webmention parameter that must be an `http(s)` URL.
+fn parse_http_url(value: &str, name: &str) -> Result<reqwest::Url, DomainError> {
+    reqwest::Url::parse(value)
+        .ok()
+        .filter(|url| matches!(url.scheme(), "http" | "https"))
+        .ok_or_else(|| DomainError::Validation(format!("{} must be an http(s) URL", name)))
+}
+
+/// Applies the post listing page size rules.
+fn page_limit(limit: u32) -> u32 {
+    match limit {
+        0 => DEFAULT_PAGE_LIMIT,
+        limit => limit.min(MAX_PAGE_LIMIT),
+    }
+}
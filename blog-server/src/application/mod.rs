@@ -1,5 +1,53 @@
+/// Activity history services.
+pub mod activity_service;
+
+/// Post view counting and statistics services.
+pub mod analytics_service;
+
+/// User administration services.
+pub mod admin_service;
+
+/// Instance backup and restore.
+pub mod backup_service;
+
+/// User avatar services.
+pub mod avatar_service;
+
 /// gRPC authentication services.
 pub mod auth_service;
 
+/// Comment services.
+pub mod comment_service;
+
+/// Personal data export services.
+pub mod export_service;
+
+/// ActivityPub federation services.
+pub mod federation_service;
+
+/// Blog import services.
+pub mod import_service;
+
+/// Client address allow and deny lists.
+pub mod ip_rule_service;
+
+/// Media upload services.
+pub mod media_service;
+
+/// Webmention services.
+pub mod mention_service;
+
+/// Transactional outbox of domain events.
+pub mod outbox_service;
+
 /// gRPC post services.
-pub mod post_service;
\ No newline at end of file
+pub mod post_service;
+
+/// Like and bookmark services.
+pub mod reaction_service;
+
+/// Login sessions with refresh token rotation.
+pub mod session_service;
+
+/// Post translation services.
+pub mod translation_service;
\ No newline at end of file
@@ -0,0 +1,100 @@
+use chrono::{Duration, Utc};
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::data::outbox_repository::OutboxRepository;
+use crate::data::unit_of_work::UnitOfWork;
+use crate::domain::error::DomainError;
+use crate::domain::outbox::{OutboxEvent, OutboxMessage};
+use crate::infrastructure::event_publisher::EventPublisher;
+
+/// Transactional outbox of domain events.
+///
+/// Services record events in the unit of work making the change they
+/// describe, so an event is stored exactly when its change is committed; a
+/// relay then publishes stored events to the message broker at least once,
+/// in order. Without a repository, as when no broker is configured, nothing
+/// is recorded.
+#[derive(Clone)]
+pub struct OutboxService {
+    repo: Option<Arc<dyn OutboxRepository>>,
+}
+
+impl OutboxService {
+    /// Creates an outbox storing events in `repo`.
+    pub fn new(repo: Arc<dyn OutboxRepository>) -> Self {
+        Self { repo: Some(repo) }
+    }
+
+    /// Creates an outbox that records nothing.
+    pub fn disabled() -> Self {
+        Self { repo: None }
+    }
+
+    /// Records an event as part of the unit of work making its change.
+    pub async fn record(&self, uow: &mut UnitOfWork, event: OutboxEvent) -> Result<(), DomainError> {
+        match &self.repo {
+            Some(repo) => repo.append(uow, &OutboxMessage::new(event)).await,
+            None => Ok(()),
+        }
+    }
+
+    /// Publishes up to `limit` stored events, oldest first, encoding each
+    /// with `encode`; returns how many were published.
+    ///
+    /// Stops at the first event the broker refuses, recording the error, so
+    /// later events are not published ahead of it.
+    pub async fn relay<F>(
+        &self,
+        publisher: &dyn EventPublisher,
+        encode: F,
+        limit: i64,
+    ) -> Result<usize, DomainError>
+    where
+        F: Fn(&OutboxMessage) -> Vec<u8> + Send + Sync,
+    {
+        let Some(repo) = &self.repo else {
+            return Ok(0);
+        };
+
+        let mut uow = repo.begin().await?;
+        let messages = repo.claim(&mut uow, limit).await?;
+
+        let mut published = 0;
+        for message in &messages {
+            let key = message.event.key().to_string();
+            match publisher
+                .publish(message.event.name(), &key, encode(message))
+                .await
+            {
+                Ok(()) => {
+                    repo.mark_delivered(&mut uow, message.id).await?;
+                    published += 1;
+                }
+                Err(e) => {
+                    warn!(
+                        message_id = %message.id,
+                        attempts = message.attempts + 1,
+                        error = %e,
+                        "failed to publish outbox message"
+                    );
+                    repo.mark_failed(&mut uow, message.id, &e.to_string())
+                        .await?;
+                    break;
+                }
+            }
+        }
+
+        uow.commit().await?;
+        Ok(published)
+    }
+
+    /// Permanently removes events published longer than `retention` ago;
+    /// returns how many were removed.
+    pub async fn purge_delivered(&self, retention: Duration) -> Result<u64, DomainError> {
+        match &self.repo {
+            Some(repo) => repo.purge_delivered(Utc::now() - retention).await,
+            None => Ok(0),
+        }
+    }
+}
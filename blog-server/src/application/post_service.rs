@@ -1,78 +1,752 @@
+use chrono::{DateTime, Duration, SubsecRound, Utc};
 use std::sync::Arc;
+use tokio::sync::broadcast::error::RecvError;
+use tracing::warn;
 use uuid::Uuid;
 
+use crate::application::outbox_service::OutboxService;
 use crate::data::post_repository::PostRepository;
+use crate::data::revision_repository::RevisionRepository;
+use crate::data::search_index::SearchIndex;
 use crate::domain::error::DomainError;
-use crate::domain::post::Post;
+use crate::domain::event::EventKind;
+use crate::domain::moderation::{ContentKind, FlaggedPost, Verdict};
+use crate::domain::outbox::OutboxEvent;
+use crate::domain::page::Page;
+use crate::domain::post::{timestamp, validate_post, Post, Visibility};
+use crate::domain::quota::Quota;
+use crate::domain::revision::PostRevision;
+use crate::domain::search::{highlight, SearchHit};
+use crate::domain::tag::{Tag, normalize_tag, normalize_tags};
+use crate::domain::validation::Violations;
+use crate::infrastructure::cache::Cache;
+use crate::infrastructure::events::EventBus;
+use crate::infrastructure::moderation::{ContentModerator, ModeratedContent};
+use crate::infrastructure::sanitizer::HtmlSanitizer;
+use crate::infrastructure::security::JwtKeys;
+
+/// Page size used when the caller does not specify one.
+pub const DEFAULT_PAGE_LIMIT: u32 = 20;
+
+/// Largest page size a caller may request.
+pub const MAX_PAGE_LIMIT: u32 = 100;
+
+/// Posts sent to the search index at once while rebuilding it.
+const REINDEX_BATCH: i64 = 100;
 
 /// Post management service.
 ///
-/// Provides CRUD operations for blog posts.
+/// Provides CRUD operations for blog posts and keeps their revision history.
 #[derive(Clone)]
-pub struct PostService<R: PostRepository + 'static> {
+pub struct PostService<R, V>
+where
+    R: PostRepository + 'static,
+    V: RevisionRepository + 'static,
+{
     repo: Arc<R>,
+    revisions: Arc<V>,
+    events: EventBus,
+    sanitizer: HtmlSanitizer,
+    keys: JwtKeys,
+    search: Arc<dyn SearchIndex>,
+    outbox: OutboxService,
+    cache: Arc<dyn Cache>,
+    cache_ttl: std::time::Duration,
+    quota: Quota,
+    moderator: Option<Arc<dyn ContentModerator>>,
 }
 
-impl<R> PostService<R>
+impl<R, V> PostService<R, V>
 where
     R: PostRepository + 'static,
+    V: RevisionRepository + 'static,
 {
-    /// Creates a new post service.
-    pub fn new(repo: Arc<R>) -> Self {
-        Self { repo }
+    /// Creates a new post service publishing changes to `events`, cleaning
+    /// post content with `sanitizer`, signing share links with `keys`,
+    /// answering searches from `search`, recording new public posts in
+    /// `outbox`, keeping posts read by ID in `cache` for `cache_ttl`,
+    /// holding authors to the post limits of `quota` and checking new and
+    /// updated posts with `moderator` when one is configured.
+    ///
+    /// A zero `cache_ttl` disables the post cache.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        repo: Arc<R>,
+        revisions: Arc<V>,
+        events: EventBus,
+        sanitizer: HtmlSanitizer,
+        keys: JwtKeys,
+        search: Arc<dyn SearchIndex>,
+        outbox: OutboxService,
+        cache: Arc<dyn Cache>,
+        cache_ttl: std::time::Duration,
+        quota: Quota,
+        moderator: Option<Arc<dyn ContentModerator>>,
+    ) -> Self {
+        Self {
+            repo,
+            revisions,
+            events,
+            sanitizer,
+            keys,
+            search,
+            outbox,
+            cache,
+            cache_ttl,
+            quota,
+            moderator,
+        }
+    }
+
+    /// Returns the content limits posts are held to.
+    pub fn quota(&self) -> Quota {
+        self.quota
     }
 
     /// Creates a new post authored by the given user.
+    ///
+    /// The title and content must be non-blank and within their length limits.
+    /// HTML in the content is sanitized unless `raw` is set; callers must only
+    /// set it for trusted administrators. Posts the content moderator rejects
+    /// fail with a validation error; flagged ones are stored and queued for
+    /// review. If the moderator fails, the post is accepted.
     pub async fn create_post(
         &self,
         title: String,
         content: String,
+        tags: Vec<String>,
+        visibility: Visibility,
         author_id: Uuid,
+        raw: bool,
     ) -> Result<Post, DomainError> {
-        let model = Post::new(title, content, author_id);
-        let post = self.repo.create(model).await.map_err(DomainError::from)?;
+        let content = self.clean(content, raw);
+        let mut violations = Violations::default();
+        validate_post(&title, &content, &mut violations);
+        violations.into_result()?;
 
-        Ok(post)
+        let mut model = Post::new(title, content, normalize_tags(tags)?, author_id);
+        model.visibility = visibility;
+        self.insert(model).await
+    }
+
+    /// Creates a post brought over from another blog.
+    ///
+    /// Content is always sanitized. The post is dated `published_at` when the
+    /// original was published, and now otherwise.
+    pub async fn import_post(
+        &self,
+        title: String,
+        content: String,
+        tags: Vec<String>,
+        visibility: Visibility,
+        author_id: Uuid,
+        published_at: Option<DateTime<Utc>>,
+    ) -> Result<Post, DomainError> {
+        let content = self.clean(content, false);
+        let mut violations = Violations::default();
+        validate_post(&title, &content, &mut violations);
+        violations.into_result()?;
+
+        let mut model = Post::new(title, content, normalize_tags(tags)?, author_id);
+        model.visibility = visibility;
+        if let Some(published_at) = published_at {
+            model.created_at = published_at.trunc_subsecs(6);
+            model.updated_at = model.created_at;
+        }
+        self.insert(model).await
     }
 
     /// Updates an existing post.
+    ///
+    /// Tags and visibility are replaced only when set. With `expected_updated_at`,
+    /// the update fails with a conflict if the post changed since then.
+    /// Content is sanitized and moderated as in [`Self::create_post`]. The
+    /// previous version is kept as a revision, written in the same
+    /// transaction as the update.
     pub async fn update_post(
         &self,
         id: Uuid,
         title: String,
         content: String,
+        tags: Option<Vec<String>>,
+        visibility: Option<Visibility>,
+        expected_updated_at: Option<DateTime<Utc>>,
+        raw: bool,
     ) -> Result<Post, DomainError> {
+        let content = self.clean(content, raw);
+        let mut violations = Violations::default();
+        validate_post(&title, &content, &mut violations);
+        violations.into_result()?;
+        self.quota.check_post_size(&content)?;
+        let author_id = self.get_post(id).await?.author_id;
+        let flag = self.moderate(author_id, &title, &content).await?;
+
+        let mut uow = self.repo.begin().await?;
         let mut post = self.repo
-            .get(id)
+            .get_for_update(&mut uow, id)
             .await
-            .map_err(DomainError::from)?
+            ?
             .ok_or_else(|| DomainError::PostNotFound(format!("post id: {}", id)))?;
 
+        if let Some(expected) = expected_updated_at
+            && expected != post.updated_at
+        {
+            return Err(DomainError::Conflict(format!(
+                "post was modified at {}",
+                post.updated_at.to_rfc3339()
+            )));
+        }
+
+        let revision = PostRevision::of(&post);
         post.title = title;
         post.content = content;
+        if let Some(tags) = tags {
+            post.tags = normalize_tags(tags)?;
+        }
+        if let Some(visibility) = visibility {
+            post.visibility = visibility;
+        }
+        post.updated_at = timestamp();
+
+        self.revisions.create(&mut uow, &revision).await?;
+        let updated = self.repo.update(&mut uow, post).await?;
+        if let Some(reason) = &flag {
+            self.repo.flag(&mut uow, id, reason).await?;
+        }
+        uow.commit().await?;
+        self.cache.forget(&post_cache_key(id)).await;
 
-        let updated = self.repo.update(post).await.map_err(DomainError::from)?;
+        self.events.publish(EventKind::PostUpdated {
+            post_id: updated.id,
+            author_id: updated.author_id,
+            title: updated.title.clone(),
+        });
 
         Ok(updated)
     }
 
     /// Returns a post by its ID.
+    ///
+    /// Posts are cached for the configured time; the author's username may
+    /// lag behind a profile change by as much.
     pub async fn get_post(&self, id: Uuid) -> Result<Post, DomainError> {
+        if self.cache_ttl.is_zero() {
+            return self.load_post(id).await;
+        }
+        let key = post_cache_key(id);
+        if let Some(post) = self.cache.get_json(&key).await {
+            return Ok(post);
+        }
+
+        let post = self.load_post(id).await?;
+        self.cache.set_json(&key, &post, self.cache_ttl).await;
+
+        Ok(post)
+    }
+
+    /// Returns a post by its ID from the repository.
+    async fn load_post(&self, id: Uuid) -> Result<Post, DomainError> {
         self.repo
             .get(id)
             .await
-            .map_err(DomainError::from)?
+            ?
             .ok_or_else(|| DomainError::PostNotFound(format!("post id: {}", id)))
     }
 
-    /// Deletes a post by its ID.
+    /// Returns a post by its ID if `viewer` may read it.
+    ///
+    /// Anonymous readers pass `None`. Posts the viewer may not read are
+    /// reported as missing, so their existence is not revealed.
+    pub async fn read_post(&self, id: Uuid, viewer: Option<Uuid>) -> Result<Post, DomainError> {
+        let post = self.get_post(id).await?;
+        readable(post, viewer, || format!("post id: {}", id))
+    }
+
+    /// Returns a post by its slug if `viewer` may read it, as for [`Self::read_post`].
+    pub async fn read_post_by_slug(
+        &self,
+        slug: &str,
+        viewer: Option<Uuid>,
+    ) -> Result<Post, DomainError> {
+        let post = self.get_post_by_slug(slug).await?;
+        readable(post, viewer, || format!("post slug: {}", slug))
+    }
+
+    /// Returns a share token for one of the author's unlisted posts.
+    ///
+    /// Anyone holding the token can read the post until it is made private.
+    pub async fn share_post(&self, id: Uuid, author_id: Uuid) -> Result<String, DomainError> {
+        let post = self.get_post(id).await?;
+        if post.author_id != author_id {
+            return Err(DomainError::Forbidden(
+                "only the post author can share a post".into(),
+            ));
+        }
+        if post.visibility != Visibility::Unlisted {
+            return Err(DomainError::Validation(
+                "only unlisted posts can be shared".into(),
+            ));
+        }
+
+        self.keys
+            .generate_share_token(post.id)
+            .map_err(|e| DomainError::Internal(format!("share token error: {}", e)))
+    }
+
+    /// Returns the post a share token grants access to.
+    ///
+    /// Invalid tokens and posts made private since are reported as missing.
+    pub async fn get_shared_post(&self, token: &str) -> Result<Post, DomainError> {
+        let not_found = || DomainError::PostNotFound("shared post".into());
+        let id = self.keys.verify_share_token(token).map_err(|_| not_found())?;
+        let post = self.get_post(id).await.map_err(|_| not_found())?;
+
+        if post.visibility == Visibility::Private {
+            return Err(not_found());
+        }
+        Ok(post)
+    }
+
+    /// Returns the earlier versions of a post, newest first.
+    pub async fn list_revisions(&self, id: Uuid) -> Result<Vec<PostRevision>, DomainError> {
+        self.get_post(id).await?;
+        self.revisions.list(id).await
+    }
+
+    /// Returns one earlier version of a post.
+    pub async fn get_revision(
+        &self,
+        id: Uuid,
+        revision_id: Uuid,
+    ) -> Result<PostRevision, DomainError> {
+        self.get_post(id).await?;
+        self.revisions
+            .get(id, revision_id)
+            .await?
+            .ok_or_else(|| DomainError::PostNotFound(format!("revision id: {}", revision_id)))
+    }
+
+    /// Makes an earlier version of one of the author's posts current again.
+    ///
+    /// The title, content and tags are restored through [`Self::update_post`],
+    /// so the content is sanitized and moderated again and the version being
+    /// replaced becomes a revision in turn. Visibility is left as is.
+    pub async fn restore_revision(
+        &self,
+        id: Uuid,
+        revision_id: Uuid,
+        author_id: Uuid,
+    ) -> Result<Post, DomainError> {
+        let post = self.get_post(id).await?;
+        if post.author_id != author_id {
+            return Err(DomainError::Forbidden(
+                "only the post author can restore a revision".into(),
+            ));
+        }
+        let revision = self.get_revision(id, revision_id).await?;
+
+        self.update_post(
+            id,
+            revision.title,
+            revision.content,
+            Some(revision.tags),
+            None,
+            None,
+            false,
+        )
+        .await
+    }
+
+    /// Returns a post by its slug.
+    pub async fn get_post_by_slug(&self, slug: &str) -> Result<Post, DomainError> {
+        self.repo
+            .get_by_slug(slug)
+            .await?
+            .ok_or_else(|| DomainError::PostNotFound(format!("post slug: {}", slug)))
+    }
+
+    /// Moves a post to the trash.
+    ///
+    /// Trashed posts disappear from every lookup and list until restored, and
+    /// are removed for good by [`Self::purge_trash`].
     pub async fn delete_post(&self, id: Uuid) -> Result<(), DomainError> {
-        self.repo.delete(id).await.map_err(DomainError::from)?;
+        let post = self.get_post(id).await?;
+        self.repo.delete(id).await?;
+        self.cache.forget(&post_cache_key(id)).await;
+
+        self.events.publish(EventKind::PostDeleted {
+            post_id: post.id,
+            author_id: post.author_id,
+        });
+
         Ok(())
     }
 
-    /// Returns a list of posts for the given author.
-    pub async fn list_posts(&self, author_id: Uuid) -> Result<Vec<Post>, DomainError> {
-        self.repo.list(author_id).await.map_err(DomainError::from)
+    /// Returns a page of the review queue of flagged posts, oldest flag first.
+    ///
+    /// Flagged posts stay readable while queued. Paging follows
+    /// [`Self::list_posts`].
+    pub async fn list_flagged(
+        &self,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Page<FlaggedPost>, DomainError> {
+        let limit = page_limit(limit);
+
+        let (posts, mut total) = self
+            .repo
+            .list_flagged(i64::from(limit), i64::from(offset))
+            .await?;
+
+        if posts.is_empty() && offset > 0 {
+            total = self.repo.count_flagged().await?;
+        }
+
+        Ok(Page {
+            items: posts,
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    /// Takes a flagged post out of the review queue, leaving it as it is.
+    pub async fn approve_post(&self, id: Uuid) -> Result<(), DomainError> {
+        self.repo.unflag(id).await
+    }
+
+    /// Returns a page of the given author's trashed posts, most recently deleted first.
+    ///
+    /// Paging follows [`Self::list_posts`].
+    pub async fn list_trash(
+        &self,
+        author_id: Uuid,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Page<Post>, DomainError> {
+        let limit = page_limit(limit);
+
+        let (posts, mut total) = self
+            .repo
+            .list_trash(author_id, i64::from(limit), i64::from(offset))
+            .await?;
+
+        // Past the last page the window count has no row to ride on.
+        if posts.is_empty() && offset > 0 {
+            total = self.repo.count_trash(author_id).await?;
+        }
+
+        Ok(Page {
+            items: posts,
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    /// Takes one of the author's posts out of the trash and returns it.
+    pub async fn restore_post(&self, id: Uuid, author_id: Uuid) -> Result<Post, DomainError> {
+        self.repo.restore(id, author_id).await?;
+        let post = self.get_post(id).await?;
+
+        self.events.publish(EventKind::PostUpdated {
+            post_id: post.id,
+            author_id: post.author_id,
+            title: post.title.clone(),
+        });
+
+        Ok(post)
+    }
+
+    /// Permanently removes posts that have been in the trash longer than
+    /// `retention`; returns how many were removed.
+    pub async fn purge_trash(&self, retention: Duration) -> Result<u64, DomainError> {
+        self.repo.purge_trash(Utc::now() - retention).await
+    }
+
+    /// Returns a page of posts for the given author, optionally filtered by tag.
+    ///
+    /// A `limit` of zero selects [`DEFAULT_PAGE_LIMIT`]; larger values are
+    /// capped at [`MAX_PAGE_LIMIT`].
+    pub async fn list_posts(
+        &self,
+        author_id: Uuid,
+        tag: Option<&str>,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Page<Post>, DomainError> {
+        self.list(Some(author_id), tag, limit, offset).await
+    }
+
+    /// Counts the given author's posts outside the trash.
+    pub async fn count_posts(&self, author_id: Uuid) -> Result<i64, DomainError> {
+        self.repo.count(Some(author_id), None).await
+    }
+
+    /// Returns a page of the public feed: posts of all authors, newest first.
+    ///
+    /// Paging and tag filtering follow [`Self::list_posts`].
+    pub async fn list_feed(
+        &self,
+        tag: Option<&str>,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Page<Post>, DomainError> {
+        self.list(None, tag, limit, offset).await
+    }
+
+    /// Searches posts of all authors by title and content, best matches first.
+    ///
+    /// `query` accepts web-search syntax (`"exact phrase"`, `or`, `-excluded`).
+    /// Paging and tag filtering follow [`Self::list_posts`].
+    pub async fn search_posts(
+        &self,
+        query: &str,
+        tag: Option<&str>,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Page<SearchHit>, DomainError> {
+        let query = search_query(query)?;
+        let limit = page_limit(limit);
+        let tag = tag.map(normalize_tag);
+
+        let (hits, mut total) = self
+            .search
+            .search(query, tag.as_deref(), i64::from(limit), i64::from(offset))
+            .await?;
+
+        // Past the last page the window count has no row to ride on.
+        if hits.is_empty() && offset > 0 {
+            total = self.search.count(query, tag.as_deref()).await?;
+        }
+
+        let page = Page {
+            items: hits,
+            total,
+            limit,
+            offset,
+        };
+
+        Ok(page.map(|(post, rank, snippet)| SearchHit {
+            post,
+            rank,
+            snippet: highlight(&snippet),
+        }))
+    }
+
+    /// Rebuilds the search index from every public post; returns how many
+    /// posts were indexed.
+    ///
+    /// Searches may miss posts until the rebuild finishes. Does nothing when
+    /// the index is kept by the database.
+    pub async fn reindex_search(&self) -> Result<u64, DomainError> {
+        if !self.search.is_external() {
+            return Ok(0);
+        }
+
+        self.search.clear().await?;
+
+        let mut indexed = 0;
+        loop {
+            let (posts, _) = self
+                .repo
+                .list(None, None, REINDEX_BATCH, indexed as i64)
+                .await?;
+            if posts.is_empty() {
+                break;
+            }
+
+            self.search.index(&posts).await?;
+            indexed += posts.len() as u64;
+        }
+
+        Ok(indexed)
+    }
+
+    /// Keeps an external search index up to date with the post changes
+    /// from `events` in a background task until the bus closes.
+    ///
+    /// Failed updates are logged; [`Self::reindex_search`] repairs the index.
+    pub fn spawn_search_indexer(&self, events: &EventBus) {
+        let repo = Arc::clone(&self.repo);
+        let search = Arc::clone(&self.search);
+        let mut events = events.subscribe();
+
+        tokio::spawn(async move {
+            loop {
+                let event = match events.recv().await {
+                    Ok(event) => event,
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(skipped, "search indexer lagged behind; reindex to catch up");
+                        continue;
+                    }
+                    Err(RecvError::Closed) => return,
+                };
+
+                let name = event.kind.name();
+                let result = match event.kind {
+                    EventKind::PostCreated { post_id, .. }
+                    | EventKind::PostUpdated { post_id, .. } => match repo.get(post_id).await {
+                        Ok(Some(post)) => search.index(&[post]).await,
+                        Ok(None) => search.remove(post_id).await,
+                        Err(e) => Err(e),
+                    },
+                    EventKind::PostDeleted { post_id, .. } => search.remove(post_id).await,
+                    _ => continue,
+                };
+
+                if let Err(e) = result {
+                    warn!(event = name, error = %e, "failed to update search index");
+                }
+            }
+        });
     }
+
+    /// Returns the tags used on the given author's posts.
+    pub async fn list_tags(&self, author_id: Uuid) -> Result<Vec<Tag>, DomainError> {
+        self.repo.tags(author_id).await
+    }
+
+    /// Lists posts after applying the page size rules and tag normalization.
+    async fn list(
+        &self,
+        author_id: Option<Uuid>,
+        tag: Option<&str>,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Page<Post>, DomainError> {
+        let limit = page_limit(limit);
+
+        let tag = tag.map(normalize_tag);
+
+        let (posts, mut total) = self
+            .repo
+            .list(author_id, tag.as_deref(), i64::from(limit), i64::from(offset))
+            .await?;
+
+        // Past the last page the window count has no row to ride on.
+        if posts.is_empty() && offset > 0 {
+            total = self.repo.count(author_id, tag.as_deref()).await?;
+        }
+
+        Ok(Page {
+            items: posts,
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    /// Stores a new post and announces it, once the author's quota and the
+    /// content moderator allow it.
+    async fn insert(&self, model: Post) -> Result<Post, DomainError> {
+        self.quota.check_post_size(&model.content)?;
+        self.quota.check_posts(self.count_posts(model.author_id).await?)?;
+        let flag = self.moderate(model.author_id, &model.title, &model.content).await?;
+
+        let mut uow = self.repo.begin().await?;
+        let post = self.repo.create(&mut uow, model).await?;
+        if let Some(reason) = &flag {
+            self.repo.flag(&mut uow, post.id, reason).await?;
+        }
+        if post.visibility == Visibility::Public {
+            self.outbox
+                .record(
+                    &mut uow,
+                    OutboxEvent::PostPublished {
+                        post_id: post.id,
+                        author_id: post.author_id,
+                        title: post.title.clone(),
+                        slug: post.slug.clone(),
+                        tags: post.tags.clone(),
+                    },
+                )
+                .await?;
+        }
+        uow.commit().await?;
+
+        self.events.publish(EventKind::PostCreated {
+            post_id: post.id,
+            author_id: post.author_id,
+            title: post.title.clone(),
+        });
+
+        Ok(post)
+    }
+
+    /// Asks the content moderator about a post; returns the flag reason if it
+    /// should be reviewed, and fails if it is rejected.
+    async fn moderate(
+        &self,
+        author_id: Uuid,
+        title: &str,
+        content: &str,
+    ) -> Result<Option<String>, DomainError> {
+        let Some(moderator) = &self.moderator else {
+            return Ok(None);
+        };
+        let submitted = ModeratedContent {
+            kind: ContentKind::Post,
+            author_id,
+            title: Some(title),
+            content,
+        };
+
+        match moderator.moderate(&submitted).await {
+            Ok(Verdict::Accept) => Ok(None),
+            Ok(Verdict::Flag(reason)) => Ok(Some(reason)),
+            Ok(Verdict::Reject(reason)) => {
+                Err(DomainError::Validation(format!("post rejected: {}", reason)))
+            }
+            Err(e) => {
+                warn!(%author_id, error = %e, "content moderator failed");
+                Ok(None)
+            }
+        }
+    }
+
+    /// Sanitizes post content unless the caller asked to keep it raw.
+    fn clean(&self, content: String, raw: bool) -> String {
+        if raw {
+            content
+        } else {
+            self.sanitizer.clean(&content)
+        }
+    }
+}
+
+/// Passes a post through if `viewer` may read it, otherwise reports it as
+/// missing with the given description.
+fn readable(
+    post: Post,
+    viewer: Option<Uuid>,
+    describe: impl FnOnce() -> String,
+) -> Result<Post, DomainError> {
+    if post.is_readable_by(viewer) {
+        Ok(post)
+    } else {
+        Err(DomainError::PostNotFound(describe()))
+    }
+}
+
+/// Applies the page size rules: zero selects [`DEFAULT_PAGE_LIMIT`], larger
+/// values are capped at [`MAX_PAGE_LIMIT`].
+fn page_limit(limit: u32) -> u32 {
+    match limit {
+        0 => DEFAULT_PAGE_LIMIT,
+        limit => limit.min(MAX_PAGE_LIMIT),
+    }
+}
+
+/// Trims a search query, rejecting blank ones.
+fn search_query(query: &str) -> Result<&str, DomainError> {
+    match query.trim() {
+        "" => Err(DomainError::Validation("search query must not be empty".into())),
+        query => Ok(query),
+    }
+}
+
+/// Returns the cache key of a post.
+fn post_cache_key(id: Uuid) -> String {
+    format!("post:{}", id)
 }
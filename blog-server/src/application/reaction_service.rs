@@ -0,0 +1,121 @@
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::application::post_service::{DEFAULT_PAGE_LIMIT, MAX_PAGE_LIMIT};
+use crate::data::post_repository::PostRepository;
+use crate::data::reaction_repository::ReactionRepository;
+use crate::domain::error::DomainError;
+use crate::domain::page::Page;
+use crate::domain::post::Post;
+use crate::domain::reaction::Reactions;
+
+/// Like and bookmark service.
+///
+/// Every change returns the post's updated counts so clients can refresh
+/// them without another request.
+#[derive(Clone)]
+pub struct ReactionService<R, P>
+where
+    R: ReactionRepository + 'static,
+    P: PostRepository + 'static,
+{
+    reactions: Arc<R>,
+    posts: Arc<P>,
+}
+
+impl<R, P> ReactionService<R, P>
+where
+    R: ReactionRepository + 'static,
+    P: PostRepository + 'static,
+{
+    /// Creates a new reaction service.
+    pub fn new(reactions: Arc<R>, posts: Arc<P>) -> Self {
+        Self { reactions, posts }
+    }
+
+    /// Likes a post on behalf of a user; liking twice is a no-op.
+    pub async fn like_post(&self, post_id: Uuid, user_id: Uuid) -> Result<Reactions, DomainError> {
+        self.ensure_post(post_id).await?;
+        self.reactions.add_like(post_id, user_id).await?;
+        self.reactions.reactions(post_id, user_id).await
+    }
+
+    /// Removes a user's like from a post.
+    pub async fn unlike_post(&self, post_id: Uuid, user_id: Uuid) -> Result<Reactions, DomainError> {
+        self.ensure_post(post_id).await?;
+        self.reactions.remove_like(post_id, user_id).await?;
+        self.reactions.reactions(post_id, user_id).await
+    }
+
+    /// Bookmarks a post for a user; bookmarking twice is a no-op.
+    pub async fn bookmark_post(
+        &self,
+        post_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Reactions, DomainError> {
+        self.ensure_post(post_id).await?;
+        self.reactions.add_bookmark(post_id, user_id).await?;
+        self.reactions.reactions(post_id, user_id).await
+    }
+
+    /// Removes a user's bookmark of a post.
+    pub async fn unbookmark_post(
+        &self,
+        post_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Reactions, DomainError> {
+        self.ensure_post(post_id).await?;
+        self.reactions.remove_bookmark(post_id, user_id).await?;
+        self.reactions.reactions(post_id, user_id).await
+    }
+
+    /// Returns the like and bookmark counts of a post as seen by a user.
+    pub async fn get_reactions(
+        &self,
+        post_id: Uuid,
+        user_id: Uuid,
+    ) -> Result<Reactions, DomainError> {
+        self.ensure_post(post_id).await?;
+        self.reactions.reactions(post_id, user_id).await
+    }
+
+    /// Returns a page of a user's bookmarked posts, most recently bookmarked first.
+    ///
+    /// Uses the same page size rules as post listings.
+    pub async fn list_bookmarks(
+        &self,
+        user_id: Uuid,
+        limit: u32,
+        offset: u32,
+    ) -> Result<Page<Post>, DomainError> {
+        let limit = match limit {
+            0 => DEFAULT_PAGE_LIMIT,
+            limit => limit.min(MAX_PAGE_LIMIT),
+        };
+
+        let (posts, mut total) = self
+            .reactions
+            .list_bookmarks(user_id, i64::from(limit), i64::from(offset))
+            .await?;
+
+        if posts.is_empty() && offset > 0 {
+            total = self.reactions.count_bookmarks(user_id).await?;
+        }
+
+        Ok(Page {
+            items: posts,
+            total,
+            limit,
+            offset,
+        })
+    }
+
+    /// Fails with `PostNotFound` unless the post exists.
+    async fn ensure_post(&self, post_id: Uuid) -> Result<(), DomainError> {
+        self.posts
+            .get(post_id)
+            .await?
+            .map(|_| ())
+            .ok_or_else(|| DomainError::PostNotFound(format!("post id: {}", post_id)))
+    }
+}
@@ -0,0 +1,120 @@
+use std::sync::Arc;
+
+use chrono::{Duration, Utc};
+use tracing::{info, instrument};
+
+use crate::application::auth_service::AuthService;
+use crate::data::refresh_token_repository::RefreshTokenRepository;
+use crate::data::user_repository::UserRepository;
+use crate::domain::error::DomainError;
+use crate::domain::session::RefreshToken;
+use crate::domain::user::User;
+use crate::infrastructure::security::{generate_secret, hash_refresh_token};
+
+/// Access and refresh token pair handed to a client.
+#[derive(Debug, Clone)]
+pub struct SessionTokens {
+    /// Short-lived JWT for authenticated requests.
+    pub access_token: String,
+
+    /// Single-use token for obtaining the next pair.
+    pub refresh_token: String,
+}
+
+/// Login sessions backed by rotating refresh tokens.
+///
+/// Every refresh revokes the token it was given and issues a new pair, so
+/// a stolen refresh token stops working once the client refreshes.
+#[derive(Clone)]
+pub struct SessionService<R, T>
+where
+    R: UserRepository + 'static,
+    T: RefreshTokenRepository + 'static,
+{
+    auth: AuthService<R>,
+    tokens: Arc<T>,
+    refresh_ttl: Duration,
+}
+
+impl<R, T> SessionService<R, T>
+where
+    R: UserRepository + 'static,
+    T: RefreshTokenRepository + 'static,
+{
+    /// Creates a session service issuing refresh tokens valid for `refresh_ttl`.
+    pub fn new(auth: AuthService<R>, tokens: Arc<T>, refresh_ttl: Duration) -> Self {
+        Self {
+            auth,
+            tokens,
+            refresh_ttl,
+        }
+    }
+
+    /// Returns how long refresh tokens stay valid.
+    pub fn refresh_ttl(&self) -> Duration {
+        self.refresh_ttl
+    }
+
+    /// Authenticates a user and starts a session.
+    #[instrument(skip(self, password))]
+    pub async fn login(&self, email: &str, password: &str) -> Result<SessionTokens, DomainError> {
+        let user = self.auth.authenticate(email, password).await?;
+        self.issue(&user).await
+    }
+
+    /// Exchanges a refresh token for a new token pair, revoking it.
+    #[instrument(skip_all)]
+    pub async fn refresh(&self, refresh_token: &str) -> Result<SessionTokens, DomainError> {
+        let invalid = || DomainError::InvalidCredentials("invalid or expired refresh token".into());
+
+        let user_id = self
+            .tokens
+            .consume(&hash_refresh_token(refresh_token))
+            .await?
+            .ok_or_else(invalid)?;
+        let user = self.auth.get_user(user_id).await.map_err(|e| match e {
+            DomainError::UserNotFound(_) => invalid(),
+            e => e,
+        })?;
+        if user.is_disabled() {
+            return Err(DomainError::Forbidden("account is disabled".to_string()));
+        }
+
+        info!(user_id = %user.id, "session refreshed");
+        self.issue(&user).await
+    }
+
+    /// Ends a session by revoking its refresh token.
+    #[instrument(skip_all)]
+    pub async fn logout(&self, refresh_token: &str) -> Result<(), DomainError> {
+        self.tokens.revoke(&hash_refresh_token(refresh_token)).await
+    }
+
+    /// Deletes refresh tokens past their expiry; returns how many.
+    pub async fn purge_expired_tokens(&self) -> Result<u64, DomainError> {
+        self.tokens.purge_expired().await
+    }
+
+    /// Deletes refresh tokens of sessions that ended more than `retention`
+    /// ago, by logout or rotation; returns how many.
+    pub async fn purge_ended_sessions(&self, retention: Duration) -> Result<u64, DomainError> {
+        self.tokens.purge_revoked(Utc::now() - retention).await
+    }
+
+    /// Issues a new token pair for a user.
+    async fn issue(&self, user: &User) -> Result<SessionTokens, DomainError> {
+        let refresh_token = generate_secret();
+        self.tokens
+            .create(&RefreshToken::new(
+                user.id,
+                hash_refresh_token(&refresh_token),
+                self.refresh_ttl,
+            ))
+            .await?;
+
+        Ok(SessionTokens {
+            access_token: self.auth.issue_token(user)?,
+            refresh_token,
+        })
+    }
+}
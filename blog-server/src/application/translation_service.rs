@@ -0,0 +1,186 @@
+use std::sync::Arc;
+use uuid::Uuid;
+
+use crate::data::post_repository::PostRepository;
+use crate::data::translation_repository::TranslationRepository;
+use crate::domain::error::DomainError;
+use crate::domain::page::Page;
+use crate::domain::post::{validate_post, Post};
+use crate::domain::translation::{
+    best_translation, normalize_locale, LocalizedPost, PostTranslation,
+};
+use crate::domain::validation::Violations;
+use crate::infrastructure::sanitizer::HtmlSanitizer;
+
+/// Post translation service.
+///
+/// Lets authors translate their posts and serves readers the translation
+/// matching their language, falling back to the post as written.
+#[derive(Clone)]
+pub struct TranslationService<T, P>
+where
+    T: TranslationRepository + 'static,
+    P: PostRepository + 'static,
+{
+    translations: Arc<T>,
+    posts: Arc<P>,
+    sanitizer: HtmlSanitizer,
+    default_locale: String,
+}
+
+impl<T, P> TranslationService<T, P>
+where
+    T: TranslationRepository + 'static,
+    P: PostRepository + 'static,
+{
+    /// Creates a new translation service for posts written in `default_locale`.
+    pub fn new(
+        translations: Arc<T>,
+        posts: Arc<P>,
+        sanitizer: HtmlSanitizer,
+        default_locale: String,
+    ) -> Self {
+        Self {
+            translations,
+            posts,
+            sanitizer,
+            default_locale,
+        }
+    }
+
+    /// Returns the translations of a post, by locale.
+    pub async fn list_translations(
+        &self,
+        post_id: Uuid,
+    ) -> Result<Vec<PostTranslation>, DomainError> {
+        self.get_post(post_id).await?;
+        self.translations.list(post_id).await
+    }
+
+    /// Adds or replaces the translation of a post into `locale`.
+    ///
+    /// Only the post's author may translate it. Title and content follow the
+    /// rules for posts, and the content is sanitized the same way.
+    pub async fn set_translation(
+        &self,
+        post_id: Uuid,
+        user_id: Uuid,
+        locale: &str,
+        title: String,
+        content: String,
+    ) -> Result<PostTranslation, DomainError> {
+        let locale = normalize_locale(locale)?;
+        if locale == self.default_locale {
+            return Err(DomainError::Validation(format!(
+                "`{locale}` is the language posts are written in; edit the post instead"
+            )));
+        }
+
+        let content = self.sanitizer.clean(&content);
+        let mut violations = Violations::default();
+        validate_post(&title, &content, &mut violations);
+        violations.into_result()?;
+
+        self.ensure_author(post_id, user_id).await?;
+        self.translations
+            .upsert(&PostTranslation::new(post_id, locale, title, content))
+            .await
+    }
+
+    /// Removes the translation of a post into `locale`; only its author may.
+    pub async fn delete_translation(
+        &self,
+        post_id: Uuid,
+        user_id: Uuid,
+        locale: &str,
+    ) -> Result<(), DomainError> {
+        let locale = normalize_locale(locale)?;
+        self.ensure_author(post_id, user_id).await?;
+        self.translations.delete(post_id, &locale).await
+    }
+
+    /// Serves a post in the first of the `preferred` locales it is available in.
+    pub async fn localize(
+        &self,
+        post: Post,
+        preferred: &[String],
+    ) -> Result<LocalizedPost, DomainError> {
+        let translations = if preferred.is_empty() {
+            Vec::new()
+        } else {
+            self.translations.list(post.id).await?
+        };
+
+        Ok(self.apply(post, &translations, preferred))
+    }
+
+    /// Serves every post of a page as [`Self::localize`] does, with one lookup.
+    pub async fn localize_page(
+        &self,
+        page: Page<Post>,
+        preferred: &[String],
+    ) -> Result<Page<LocalizedPost>, DomainError> {
+        let translations = if preferred.is_empty() || page.items.is_empty() {
+            Vec::new()
+        } else {
+            let ids: Vec<Uuid> = page.items.iter().map(|post| post.id).collect();
+            self.translations.list_for_posts(&ids).await?
+        };
+
+        Ok(page.map(|post| {
+            let own: Vec<PostTranslation> = translations
+                .iter()
+                .filter(|t| t.post_id == post.id)
+                .cloned()
+                .collect();
+            self.apply(post, &own, preferred)
+        }))
+    }
+
+    /// Replaces a post's title and content with its best matching translation.
+    fn apply(
+        &self,
+        mut post: Post,
+        translations: &[PostTranslation],
+        preferred: &[String],
+    ) -> LocalizedPost {
+        let preferred: Vec<String> = preferred
+            .iter()
+            .filter_map(|locale| normalize_locale(locale).ok())
+            .collect();
+
+        match best_translation(translations, &preferred, &self.default_locale) {
+            Some(translation) => {
+                post.title = translation.title.clone();
+                post.content = translation.content.clone();
+                LocalizedPost {
+                    post,
+                    locale: translation.locale.clone(),
+                }
+            }
+            None => LocalizedPost {
+                post,
+                locale: self.default_locale.clone(),
+            },
+        }
+    }
+
+    /// Returns a post by its ID.
+    async fn get_post(&self, post_id: Uuid) -> Result<Post, DomainError> {
+        self.posts
+            .get(post_id)
+            .await?
+            .ok_or_else(|| DomainError::PostNotFound(format!("post id: {}", post_id)))
+    }
+
+    /// Fails with `Forbidden` unless the user wrote the post.
+    async fn ensure_author(&self, post_id: Uuid, user_id: Uuid) -> Result<(), DomainError> {
+        if self.get_post(post_id).await?.author_id != user_id {
+            return Err(DomainError::Forbidden(
+                "only the post author can manage its translations".into(),
+            ));
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,185 @@
+use crate::domain::activity::Activity;
+use crate::domain::error::DomainError;
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use tracing::{error, instrument};
+use uuid::Uuid;
+
+/// Activities whose post still exists and whose comment, if any, is shown;
+/// `$1` is the user whose listing is being read.
+const VISIBLE_ACTIVITIES: &str = r#"
+    FROM activities a
+    JOIN posts p ON p.id = a.post_id AND p.deleted_at IS NULL
+    LEFT JOIN comments c ON c.id = a.comment_id
+    WHERE (a.comment_id IS NULL OR (c.deleted_at IS NULL AND c.status = 'published'))
+"#;
+
+/// Activity history persistence abstraction.
+#[async_trait]
+pub trait ActivityRepository: Send + Sync {
+    /// Records an activity.
+    async fn record(&self, activity: &Activity) -> Result<(), DomainError>;
+
+    /// Returns a page of a user's activity on public posts, newest first,
+    /// with the number of such activities.
+    async fn list_by_actor(
+        &self,
+        actor_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Activity>, i64), DomainError>;
+
+    /// Returns a page of a user's feed, newest first, with its length: what
+    /// the user did and what others did on the user's posts.
+    async fn feed(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Activity>, i64), DomainError>;
+}
+
+/// PostgreSQL-backed activity repository implementation.
+#[derive(Clone)]
+pub struct PostgresActivityRepository {
+    pool: PgPool,
+}
+
+impl PostgresActivityRepository {
+    /// Creates a new PostgreSQL activity repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Returns a page of the visible activities matching `filter`, newest
+    /// first, with their number.
+    async fn page(
+        &self,
+        filter: &str,
+        user_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Activity>, i64), DomainError> {
+        let db_err = |e: sqlx::Error| {
+            error!("failed to list activities of user {}: {}", user_id, e);
+            DomainError::Internal(format!("database error: {}", e))
+        };
+
+        let total: i64 = sqlx::query_scalar(&format!(
+            "SELECT COUNT(*) {} AND {}",
+            VISIBLE_ACTIVITIES, filter
+        ))
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(db_err)?;
+
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT a.id, a.actor_id, a.kind, a.post_id, p.title AS post_title, a.comment_id,
+                   a.post_author_id, a.created_at
+            {} AND {}
+            ORDER BY a.created_at DESC, a.id
+            LIMIT $2 OFFSET $3
+            "#,
+            VISIBLE_ACTIVITIES, filter
+        ))
+            .bind(user_id)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(db_err)?;
+
+        let activities = rows
+            .into_iter()
+            .map(map_row)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok((activities, total))
+    }
+}
+
+#[async_trait]
+impl ActivityRepository for PostgresActivityRepository {
+    /// Inserts an activity into the database.
+    #[instrument(name = "activities.record", skip(self, activity), fields(activity_id = %activity.id))]
+    async fn record(&self, activity: &Activity) -> Result<(), DomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO activities (id, actor_id, kind, post_id, comment_id, post_author_id, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+            .bind(activity.id)
+            .bind(activity.actor_id)
+            .bind(activity.kind)
+            .bind(activity.post_id)
+            .bind(activity.comment_id)
+            .bind(activity.post_author_id)
+            .bind(activity.created_at)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to record activity of user {}: {}", activity.actor_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    /// Lists a user's activities on public posts.
+    #[instrument(name = "activities.list_by_actor", skip(self))]
+    async fn list_by_actor(
+        &self,
+        actor_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Activity>, i64), DomainError> {
+        self.page(
+            "a.actor_id = $1 AND p.visibility = 'public'",
+            actor_id,
+            limit,
+            offset,
+        )
+            .await
+    }
+
+    /// Lists a user's own activities and those on the user's posts; private
+    /// posts only appear to their author.
+    #[instrument(name = "activities.feed", skip(self))]
+    async fn feed(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Activity>, i64), DomainError> {
+        self.page(
+            "(a.actor_id = $1 OR a.post_author_id = $1) \
+             AND (p.visibility = 'public' OR p.author_id = $1)",
+            user_id,
+            limit,
+            offset,
+        )
+            .await
+    }
+}
+
+/// Maps a database row to an activity.
+fn map_row(row: PgRow) -> Result<Activity, DomainError> {
+    let decode_err = |e: sqlx::Error| {
+        DomainError::Internal(format!("row decode error: {}", e))
+    };
+
+    Ok(Activity {
+        id: row.try_get("id").map_err(decode_err)?,
+        actor_id: row.try_get("actor_id").map_err(decode_err)?,
+        kind: row.try_get("kind").map_err(decode_err)?,
+        post_id: row.try_get("post_id").map_err(decode_err)?,
+        post_title: row.try_get("post_title").map_err(decode_err)?,
+        comment_id: row.try_get("comment_id").map_err(decode_err)?,
+        post_author_id: row.try_get("post_author_id").map_err(decode_err)?,
+        created_at: row.try_get("created_at").map_err(decode_err)?,
+    })
+}
@@ -0,0 +1,283 @@
+use crate::domain::analytics::{
+    AgentViews, DailyViews, PostStats, PostTraffic, ReferrerViews, ViewCount,
+};
+use crate::domain::error::DomainError;
+use async_trait::async_trait;
+use chrono::NaiveDate;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use tracing::{error, info, instrument};
+use uuid::Uuid;
+
+/// Post view and engagement statistics persistence abstraction.
+#[async_trait]
+pub trait AnalyticsRepository: Send + Sync {
+    /// Adds view counts to the stored daily views and traffic sources,
+    /// skipping posts that no longer exist.
+    async fn record_views(&self, views: &[ViewCount]) -> Result<(), DomainError>;
+
+    /// Returns the view, like, bookmark and comment counts since `since` of
+    /// an author's posts, or of the one post `post_id` among them, most
+    /// viewed first. Daily views are left empty.
+    async fn post_stats(
+        &self,
+        author_id: Uuid,
+        post_id: Option<Uuid>,
+        since: NaiveDate,
+    ) -> Result<Vec<PostStats>, DomainError>;
+
+    /// Returns the daily views since `since` of the given posts, for days
+    /// with any, oldest first.
+    async fn daily_views(
+        &self,
+        post_ids: &[Uuid],
+        since: NaiveDate,
+    ) -> Result<Vec<(Uuid, DailyViews)>, DomainError>;
+
+    /// Returns a post's views since `since` by referring domain, the top
+    /// `max_referrers` of them, and by kind of client, most first.
+    async fn post_traffic(
+        &self,
+        post_id: Uuid,
+        since: NaiveDate,
+        max_referrers: i64,
+    ) -> Result<PostTraffic, DomainError>;
+}
+
+/// PostgreSQL-backed analytics repository implementation.
+#[derive(Clone)]
+pub struct PostgresAnalyticsRepository {
+    pool: PgPool,
+}
+
+impl PostgresAnalyticsRepository {
+    /// Creates a new PostgreSQL analytics repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AnalyticsRepository for PostgresAnalyticsRepository {
+    /// Upserts all counts in one statement, summing the sources into the
+    /// daily views.
+    #[instrument(name = "analytics.record_views", skip(self, views), fields(count = views.len()))]
+    async fn record_views(&self, views: &[ViewCount]) -> Result<(), DomainError> {
+        let post_ids: Vec<Uuid> = views.iter().map(|count| count.post_id).collect();
+        let days: Vec<NaiveDate> = views.iter().map(|count| count.day).collect();
+        let referrers: Vec<&str> = views
+            .iter()
+            .map(|count| count.source.referrer.as_deref().unwrap_or_default())
+            .collect();
+        let agents: Vec<&str> = views.iter().map(|count| count.source.agent.as_str()).collect();
+        let counts: Vec<i64> = views.iter().map(|count| count.views).collect();
+
+        sqlx::query(
+            r#"
+            WITH v AS (
+                SELECT v.*
+                FROM UNNEST($1::uuid[], $2::date[], $3::varchar[], $4::varchar[], $5::bigint[])
+                    AS v (post_id, day, referrer, agent, views)
+                WHERE EXISTS (SELECT 1 FROM posts p WHERE p.id = v.post_id)
+            ), daily AS (
+                INSERT INTO post_views (post_id, day, views)
+                SELECT post_id, day, SUM(views)::bigint
+                FROM v
+                GROUP BY post_id, day
+                ON CONFLICT (post_id, day) DO UPDATE SET views = post_views.views + EXCLUDED.views
+            )
+            INSERT INTO post_traffic (post_id, day, referrer, agent, views)
+            SELECT post_id, day, referrer, agent, views
+            FROM v
+            ON CONFLICT (post_id, day, referrer, agent)
+                DO UPDATE SET views = post_traffic.views + EXCLUDED.views
+            "#,
+        )
+            .bind(&post_ids)
+            .bind(&days)
+            .bind(&referrers)
+            .bind(&agents)
+            .bind(&counts)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to record post views: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        info!(count = views.len(), "post views recorded");
+        Ok(())
+    }
+
+    /// Counts everything with correlated subqueries over the author's posts.
+    #[instrument(name = "analytics.post_stats", skip(self))]
+    async fn post_stats(
+        &self,
+        author_id: Uuid,
+        post_id: Option<Uuid>,
+        since: NaiveDate,
+    ) -> Result<Vec<PostStats>, DomainError> {
+        let since_at = since.and_time(chrono::NaiveTime::MIN).and_utc();
+
+        let rows = sqlx::query(
+            r#"
+            SELECT p.id, p.title,
+                   (SELECT COALESCE(SUM(v.views), 0)::bigint FROM post_views v
+                    WHERE v.post_id = p.id AND v.day >= $3) AS views,
+                   (SELECT COUNT(*) FROM post_likes l
+                    WHERE l.post_id = p.id AND l.created_at >= $4) AS likes,
+                   (SELECT COUNT(*) FROM bookmarks b
+                    WHERE b.post_id = p.id AND b.created_at >= $4) AS bookmarks,
+                   (SELECT COUNT(*) FROM comments c
+                    WHERE c.post_id = p.id AND c.created_at >= $4
+                      AND c.status = 'published' AND c.deleted_at IS NULL) AS comments
+            FROM posts p
+            WHERE p.author_id = $1 AND p.deleted_at IS NULL
+              AND ($2::uuid IS NULL OR p.id = $2)
+            ORDER BY views DESC, p.created_at DESC, p.id
+            "#,
+        )
+            .bind(author_id)
+            .bind(post_id)
+            .bind(since)
+            .bind(since_at)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to count post stats of user {}: {}", author_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        rows.into_iter().map(map_row).collect()
+    }
+
+    /// Lists stored daily views of the posts.
+    #[instrument(name = "analytics.daily_views", skip(self, post_ids))]
+    async fn daily_views(
+        &self,
+        post_ids: &[Uuid],
+        since: NaiveDate,
+    ) -> Result<Vec<(Uuid, DailyViews)>, DomainError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT post_id, day, views
+            FROM post_views
+            WHERE post_id = ANY($1) AND day >= $2
+            ORDER BY post_id, day
+            "#,
+        )
+            .bind(post_ids)
+            .bind(since)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to list daily post views: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        let decode_err = |e: sqlx::Error| {
+            DomainError::Internal(format!("row decode error: {}", e))
+        };
+
+        rows.into_iter()
+            .map(|row| {
+                let views = DailyViews {
+                    day: row.try_get("day").map_err(decode_err)?,
+                    views: row.try_get("views").map_err(decode_err)?,
+                };
+                Ok((row.try_get("post_id").map_err(decode_err)?, views))
+            })
+            .collect()
+    }
+
+    /// Sums the stored traffic of the post per referrer and per kind of client.
+    #[instrument(name = "analytics.post_traffic", skip(self))]
+    async fn post_traffic(
+        &self,
+        post_id: Uuid,
+        since: NaiveDate,
+        max_referrers: i64,
+    ) -> Result<PostTraffic, DomainError> {
+        let referrer_rows = sqlx::query(
+            r#"
+            SELECT referrer, SUM(views)::bigint AS views
+            FROM post_traffic
+            WHERE post_id = $1 AND day >= $2
+            GROUP BY referrer
+            ORDER BY views DESC, referrer
+            LIMIT $3
+            "#,
+        )
+            .bind(post_id)
+            .bind(since)
+            .bind(max_referrers)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to list referrers of post {}: {}", post_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        let agent_rows = sqlx::query(
+            r#"
+            SELECT agent, SUM(views)::bigint AS views
+            FROM post_traffic
+            WHERE post_id = $1 AND day >= $2
+            GROUP BY agent
+            ORDER BY views DESC, agent
+            "#,
+        )
+            .bind(post_id)
+            .bind(since)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to list client kinds of post {}: {}", post_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        let decode_err = |e: sqlx::Error| {
+            DomainError::Internal(format!("row decode error: {}", e))
+        };
+
+        let referrers = referrer_rows
+            .into_iter()
+            .map(|row| {
+                let referrer: String = row.try_get("referrer").map_err(decode_err)?;
+                Ok(ReferrerViews {
+                    referrer: Some(referrer).filter(|referrer| !referrer.is_empty()),
+                    views: row.try_get("views").map_err(decode_err)?,
+                })
+            })
+            .collect::<Result<_, DomainError>>()?;
+        let agents = agent_rows
+            .into_iter()
+            .map(|row| {
+                Ok(AgentViews {
+                    agent: row.try_get("agent").map_err(decode_err)?,
+                    views: row.try_get("views").map_err(decode_err)?,
+                })
+            })
+            .collect::<Result<_, DomainError>>()?;
+
+        Ok(PostTraffic { referrers, agents })
+    }
+}
+
+/// Maps a database row to post stats without daily views.
+fn map_row(row: PgRow) -> Result<PostStats, DomainError> {
+    let decode_err = |e: sqlx::Error| {
+        DomainError::Internal(format!("row decode error: {}", e))
+    };
+
+    Ok(PostStats {
+        post_id: row.try_get("id").map_err(decode_err)?,
+        title: row.try_get("title").map_err(decode_err)?,
+        views: row.try_get("views").map_err(decode_err)?,
+        likes: row.try_get("likes").map_err(decode_err)?,
+        bookmarks: row.try_get("bookmarks").map_err(decode_err)?,
+        comments: row.try_get("comments").map_err(decode_err)?,
+        daily_views: Vec::new(),
+        traffic: None,
+    })
+}
@@ -0,0 +1,349 @@
+use crate::data::unit_of_work::UnitOfWork;
+use crate::domain::comment::Comment;
+use crate::domain::error::DomainError;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use tracing::{error, info, instrument};
+use uuid::Uuid;
+
+/// Comment persistence abstraction.
+///
+/// Defines operations for storing and retrieving post comments.
+#[async_trait]
+pub trait CommentRepository: Send + Sync {
+    /// Starts a unit of work on the comment database.
+    async fn begin(&self) -> Result<UnitOfWork, DomainError>;
+
+    /// Persists a new comment as part of the given unit of work.
+    async fn create(&self, uow: &mut UnitOfWork, comment: Comment) -> Result<Comment, DomainError>;
+
+    /// Returns a comment by its ID, if it exists and is not deleted.
+    async fn get(&self, id: Uuid) -> Result<Option<Comment>, DomainError>;
+
+    /// Returns a page of published comments on the given post, oldest first, with
+    /// the number of them on the post; the count is zero when the page is empty.
+    async fn list_by_post(
+        &self,
+        post_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Comment>, i64), DomainError>;
+
+    /// Returns how many comments on the given post are published and not deleted.
+    async fn count_by_post(&self, post_id: Uuid) -> Result<i64, DomainError>;
+
+    /// Returns every comment the given user wrote and has not deleted, flagged
+    /// ones included, oldest first.
+    async fn list_by_author(&self, author_id: Uuid) -> Result<Vec<Comment>, DomainError>;
+
+    /// Returns a page of flagged comments, oldest first, with the number of
+    /// flagged comments; the count is zero when the page is empty.
+    async fn list_flagged(&self, limit: i64, offset: i64) -> Result<(Vec<Comment>, i64), DomainError>;
+
+    /// Returns how many comments are flagged and not deleted.
+    async fn count_flagged(&self) -> Result<i64, DomainError>;
+
+    /// Publishes a flagged comment and clears its flag reason as part of the
+    /// given unit of work.
+    async fn approve(&self, uow: &mut UnitOfWork, id: Uuid) -> Result<Comment, DomainError>;
+
+    /// Marks a comment as deleted.
+    async fn delete(&self, id: Uuid) -> Result<(), DomainError>;
+
+    /// Permanently removes comments deleted before `before`; returns how many.
+    async fn purge_deleted(&self, before: DateTime<Utc>) -> Result<u64, DomainError>;
+}
+
+/// PostgreSQL-backed comment repository implementation.
+#[derive(Clone)]
+pub struct PostgresCommentRepository {
+    pool: PgPool,
+}
+
+impl PostgresCommentRepository {
+    /// Creates a new PostgreSQL comment repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl CommentRepository for PostgresCommentRepository {
+    /// Begins a transaction on the pool.
+    #[instrument(name = "comments.begin", skip(self))]
+    async fn begin(&self) -> Result<UnitOfWork, DomainError> {
+        UnitOfWork::begin(&self.pool).await
+    }
+
+    /// Inserts a new comment into the database.
+    #[instrument(name = "comments.create", skip(self, uow, comment), fields(comment_id = %comment.id, post_id = %comment.post_id))]
+    async fn create(&self, uow: &mut UnitOfWork, comment: Comment) -> Result<Comment, DomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO comments (id, post_id, author_id, parent_id, content, status, flag_reason, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+            .bind(comment.id)
+            .bind(comment.post_id)
+            .bind(comment.author_id)
+            .bind(comment.parent_id)
+            .bind(&comment.content)
+            .bind(comment.status)
+            .bind(&comment.flag_reason)
+            .bind(comment.created_at)
+            .execute(uow.conn())
+            .await
+            .map_err(|e| {
+                error!("failed to create comment: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        info!(comment_id = %comment.id, post_id = %comment.post_id, "comment created");
+        Ok(comment)
+    }
+
+    /// Returns a comment by its ID, if present.
+    #[instrument(name = "comments.get", skip(self))]
+    async fn get(&self, id: Uuid) -> Result<Option<Comment>, DomainError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, post_id, author_id, parent_id, content, status, flag_reason,
+                   created_at, deleted_at
+            FROM comments
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+        )
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to find comment by id {}: {}", id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        row.map(map_row).transpose()
+    }
+
+    /// Returns a page of comments on the given post.
+    ///
+    /// The total is counted with a window function in the same query.
+    #[instrument(name = "comments.list_by_post", skip(self))]
+    async fn list_by_post(
+        &self,
+        post_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Comment>, i64), DomainError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, post_id, author_id, parent_id, content, status, flag_reason,
+                   created_at, deleted_at, COUNT(*) OVER () AS total
+            FROM comments
+            WHERE post_id = $1 AND status = 'published' AND deleted_at IS NULL
+            ORDER BY created_at, id
+            LIMIT $2 OFFSET $3
+            "#,
+        )
+            .bind(post_id)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to list comments for post {}: {}", post_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        let total = match rows.first() {
+            Some(row) => row
+                .try_get("total")
+                .map_err(|e| DomainError::Internal(format!("row decode error: {}", e)))?,
+            None => 0,
+        };
+        let comments = rows.into_iter().map(map_row).collect::<Result<_, _>>()?;
+
+        Ok((comments, total))
+    }
+
+    /// Counts the comments `list_by_post` pages through.
+    #[instrument(name = "comments.count_by_post", skip(self))]
+    async fn count_by_post(&self, post_id: Uuid) -> Result<i64, DomainError> {
+        sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM comments
+            WHERE post_id = $1 AND status = 'published' AND deleted_at IS NULL
+            "#,
+        )
+            .bind(post_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to count comments for post {}: {}", post_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })
+    }
+
+    /// Lists all comments of an author.
+    #[instrument(name = "comments.list_by_author", skip(self))]
+    async fn list_by_author(&self, author_id: Uuid) -> Result<Vec<Comment>, DomainError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, post_id, author_id, parent_id, content, status, flag_reason,
+                   created_at, deleted_at
+            FROM comments
+            WHERE author_id = $1 AND deleted_at IS NULL
+            ORDER BY created_at, id
+            "#,
+        )
+            .bind(author_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to list comments by author {}: {}", author_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        rows.into_iter().map(map_row).collect()
+    }
+
+    /// Returns a page of the moderation queue.
+    ///
+    /// The total is counted with a window function in the same query.
+    #[instrument(name = "comments.list_flagged", skip(self))]
+    async fn list_flagged(&self, limit: i64, offset: i64) -> Result<(Vec<Comment>, i64), DomainError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, post_id, author_id, parent_id, content, status, flag_reason,
+                   created_at, deleted_at, COUNT(*) OVER () AS total
+            FROM comments
+            WHERE status = 'flagged' AND deleted_at IS NULL
+            ORDER BY created_at, id
+            LIMIT $1 OFFSET $2
+            "#,
+        )
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to list flagged comments: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        let total = match rows.first() {
+            Some(row) => row
+                .try_get("total")
+                .map_err(|e| DomainError::Internal(format!("row decode error: {}", e)))?,
+            None => 0,
+        };
+        let comments = rows.into_iter().map(map_row).collect::<Result<_, _>>()?;
+
+        Ok((comments, total))
+    }
+
+    /// Counts the comments `list_flagged` pages through.
+    #[instrument(name = "comments.count_flagged", skip(self))]
+    async fn count_flagged(&self) -> Result<i64, DomainError> {
+        sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM comments
+            WHERE status = 'flagged' AND deleted_at IS NULL
+            "#,
+        )
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to count flagged comments: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })
+    }
+
+    /// Moves a comment out of the moderation queue onto its post.
+    #[instrument(name = "comments.approve", skip(self, uow))]
+    async fn approve(&self, uow: &mut UnitOfWork, id: Uuid) -> Result<Comment, DomainError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE comments SET status = 'published', flag_reason = NULL
+            WHERE id = $1 AND status = 'flagged' AND deleted_at IS NULL
+            RETURNING id, post_id, author_id, parent_id, content, status, flag_reason,
+                      created_at, deleted_at
+            "#,
+        )
+            .bind(id)
+            .fetch_optional(uow.conn())
+            .await
+            .map_err(|e| {
+                error!("failed to approve comment {}: {}", id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?
+            .ok_or_else(|| DomainError::CommentNotFound(format!("flagged comment id: {}", id)))?;
+
+        info!(comment_id = %id, "comment approved");
+        map_row(row)
+    }
+
+    /// Soft-deletes a comment by its ID.
+    #[instrument(name = "comments.delete", skip(self))]
+    async fn delete(&self, id: Uuid) -> Result<(), DomainError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE comments SET deleted_at = now()
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+        )
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::CommentNotFound(id.to_string()));
+        }
+
+        info!(comment_id = %id, "comment deleted");
+        Ok(())
+    }
+
+    /// Deletes the rows of comments soft-deleted before the cutoff.
+    #[instrument(name = "comments.purge_deleted", skip(self))]
+    async fn purge_deleted(&self, before: DateTime<Utc>) -> Result<u64, DomainError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM comments WHERE deleted_at < $1
+            "#,
+        )
+            .bind(before)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to purge deleted comments: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// Maps a database row to a comment domain model.
+fn map_row(row: PgRow) -> Result<Comment, DomainError> {
+    let decode_err = |e: sqlx::Error| {
+        DomainError::Internal(format!("row decode error: {}", e))
+    };
+
+    Ok(Comment {
+        id: row.try_get("id").map_err(decode_err)?,
+        post_id: row.try_get("post_id").map_err(decode_err)?,
+        author_id: row.try_get("author_id").map_err(decode_err)?,
+        parent_id: row.try_get("parent_id").map_err(decode_err)?,
+        content: row.try_get("content").map_err(decode_err)?,
+        status: row.try_get("status").map_err(decode_err)?,
+        flag_reason: row.try_get("flag_reason").map_err(decode_err)?,
+        created_at: row.try_get("created_at").map_err(decode_err)?,
+        deleted_at: row.try_get("deleted_at").map_err(decode_err)?,
+    })
+}
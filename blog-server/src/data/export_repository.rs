@@ -0,0 +1,182 @@
+use crate::domain::error::DomainError;
+use crate::domain::export::DataExport;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use tracing::{error, info, instrument};
+use uuid::Uuid;
+
+/// Data export persistence abstraction.
+///
+/// Tracks requested exports; the archives live in media storage.
+#[async_trait]
+pub trait ExportRepository: Send + Sync {
+    /// Persists a newly requested export.
+    async fn create(&self, export: &DataExport) -> Result<(), DomainError>;
+
+    /// Returns an export by its ID, if it exists.
+    async fn get(&self, id: Uuid) -> Result<Option<DataExport>, DomainError>;
+
+    /// Returns the user's export still being built, if any.
+    async fn find_pending(&self, user_id: Uuid) -> Result<Option<DataExport>, DomainError>;
+
+    /// Stores the outcome of building an export.
+    async fn update(&self, export: &DataExport) -> Result<(), DomainError>;
+
+    /// Deletes exports that expired before `before`; returns their IDs.
+    async fn purge_expired(&self, before: DateTime<Utc>) -> Result<Vec<Uuid>, DomainError>;
+}
+
+/// PostgreSQL-backed data export repository implementation.
+#[derive(Clone)]
+pub struct PostgresExportRepository {
+    pool: PgPool,
+}
+
+impl PostgresExportRepository {
+    /// Creates a new PostgreSQL data export repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl ExportRepository for PostgresExportRepository {
+    /// Inserts an export into the database.
+    #[instrument(name = "exports.create", skip(self, export), fields(export_id = %export.id))]
+    async fn create(&self, export: &DataExport) -> Result<(), DomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO data_exports (id, user_id, status, size_bytes, error, created_at, completed_at, expires_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+            "#,
+        )
+            .bind(export.id)
+            .bind(export.user_id)
+            .bind(export.status)
+            .bind(export.size_bytes)
+            .bind(&export.error)
+            .bind(export.created_at)
+            .bind(export.completed_at)
+            .bind(export.expires_at)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to create export for user {}: {}", export.user_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        info!(export_id = %export.id, user_id = %export.user_id, "data export created");
+        Ok(())
+    }
+
+    /// Returns an export by its ID, if present.
+    #[instrument(name = "exports.get", skip(self))]
+    async fn get(&self, id: Uuid) -> Result<Option<DataExport>, DomainError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, user_id, status, size_bytes, error, created_at, completed_at, expires_at
+            FROM data_exports
+            WHERE id = $1
+            "#,
+        )
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to find export by id {}: {}", id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        row.map(map_row).transpose()
+    }
+
+    /// Returns the newest pending, unexpired export of a user.
+    #[instrument(name = "exports.find_pending", skip(self))]
+    async fn find_pending(&self, user_id: Uuid) -> Result<Option<DataExport>, DomainError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, user_id, status, size_bytes, error, created_at, completed_at, expires_at
+            FROM data_exports
+            WHERE user_id = $1 AND status = 'pending' AND expires_at > NOW()
+            ORDER BY created_at DESC
+            LIMIT 1
+            "#,
+        )
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to find pending export of user {}: {}", user_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        row.map(map_row).transpose()
+    }
+
+    /// Updates the status, size, error and timestamps of an export.
+    #[instrument(name = "exports.update", skip(self, export), fields(export_id = %export.id))]
+    async fn update(&self, export: &DataExport) -> Result<(), DomainError> {
+        let result = sqlx::query(
+            r#"
+            UPDATE data_exports
+            SET status = $2, size_bytes = $3, error = $4, completed_at = $5, expires_at = $6
+            WHERE id = $1
+            "#,
+        )
+            .bind(export.id)
+            .bind(export.status)
+            .bind(export.size_bytes)
+            .bind(&export.error)
+            .bind(export.completed_at)
+            .bind(export.expires_at)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to update export {}: {}", export.id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::ExportNotFound(export.id.to_string()));
+        }
+
+        Ok(())
+    }
+
+    /// Deletes expired exports.
+    #[instrument(name = "exports.purge_expired", skip(self))]
+    async fn purge_expired(&self, before: DateTime<Utc>) -> Result<Vec<Uuid>, DomainError> {
+        sqlx::query_scalar(
+            r#"
+            DELETE FROM data_exports WHERE expires_at < $1 RETURNING id
+            "#,
+        )
+            .bind(before)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to purge expired exports: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })
+    }
+}
+
+/// Maps a database row to a data export.
+fn map_row(row: PgRow) -> Result<DataExport, DomainError> {
+    let decode_err = |e: sqlx::Error| {
+        DomainError::Internal(format!("row decode error: {}", e))
+    };
+
+    Ok(DataExport {
+        id: row.try_get("id").map_err(decode_err)?,
+        user_id: row.try_get("user_id").map_err(decode_err)?,
+        status: row.try_get("status").map_err(decode_err)?,
+        size_bytes: row.try_get("size_bytes").map_err(decode_err)?,
+        error: row.try_get("error").map_err(decode_err)?,
+        created_at: row.try_get("created_at").map_err(decode_err)?,
+        completed_at: row.try_get("completed_at").map_err(decode_err)?,
+        expires_at: row.try_get("expires_at").map_err(decode_err)?,
+    })
+}
@@ -0,0 +1,207 @@
+use crate::domain::error::DomainError;
+use crate::domain::federation::{ActorKeys, Follower};
+use async_trait::async_trait;
+use chrono::Utc;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use tracing::{error, info, instrument};
+use uuid::Uuid;
+
+/// ActivityPub federation persistence abstraction.
+///
+/// Keeps the signing keys of author actors and their remote followers.
+#[async_trait]
+pub trait FederationRepository: Send + Sync {
+    /// Returns an author's signing keys, if they were generated.
+    async fn get_keys(&self, user_id: Uuid) -> Result<Option<ActorKeys>, DomainError>;
+
+    /// Stores an author's signing keys unless some are stored already;
+    /// returns the stored ones.
+    async fn save_keys(&self, keys: &ActorKeys) -> Result<ActorKeys, DomainError>;
+
+    /// Adds or refreshes a remote follower of an author.
+    async fn add_follower(&self, follower: &Follower) -> Result<(), DomainError>;
+
+    /// Removes a remote follower of an author.
+    async fn remove_follower(&self, user_id: Uuid, actor_url: &str) -> Result<(), DomainError>;
+
+    /// Returns the remote followers of an author.
+    async fn list_followers(&self, user_id: Uuid) -> Result<Vec<Follower>, DomainError>;
+
+    /// Returns how many remote actors follow an author.
+    async fn count_followers(&self, user_id: Uuid) -> Result<i64, DomainError>;
+}
+
+/// PostgreSQL-backed federation repository implementation.
+#[derive(Clone)]
+pub struct PostgresFederationRepository {
+    pool: PgPool,
+}
+
+impl PostgresFederationRepository {
+    /// Creates a new PostgreSQL federation repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl FederationRepository for PostgresFederationRepository {
+    /// Returns the signing keys of an author, if present.
+    #[instrument(name = "federation.get_keys", skip(self))]
+    async fn get_keys(&self, user_id: Uuid) -> Result<Option<ActorKeys>, DomainError> {
+        let row = sqlx::query(
+            r#"
+            SELECT user_id, public_key_pem, private_key_pem
+            FROM federation_keys
+            WHERE user_id = $1
+            "#,
+        )
+            .bind(user_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to find federation keys of user {}: {}", user_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        row.map(map_keys_row).transpose()
+    }
+
+    /// Inserts signing keys, keeping existing ones if another request won the race.
+    #[instrument(name = "federation.save_keys", skip(self, keys), fields(user_id = %keys.user_id))]
+    async fn save_keys(&self, keys: &ActorKeys) -> Result<ActorKeys, DomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO federation_keys (user_id, public_key_pem, private_key_pem, created_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id) DO NOTHING
+            "#,
+        )
+            .bind(keys.user_id)
+            .bind(&keys.public_key_pem)
+            .bind(&keys.private_key_pem)
+            .bind(Utc::now())
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to save federation keys of user {}: {}", keys.user_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        self.get_keys(keys.user_id)
+            .await?
+            .ok_or_else(|| DomainError::Internal("federation keys vanished".into()))
+    }
+
+    /// Inserts a follower, updating the inbox of an existing one.
+    #[instrument(name = "federation.add_follower", skip(self, follower), fields(user_id = %follower.user_id))]
+    async fn add_follower(&self, follower: &Follower) -> Result<(), DomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO federation_followers (user_id, actor_url, inbox_url, created_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (user_id, actor_url) DO UPDATE SET inbox_url = EXCLUDED.inbox_url
+            "#,
+        )
+            .bind(follower.user_id)
+            .bind(&follower.actor_url)
+            .bind(&follower.inbox_url)
+            .bind(follower.created_at)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to add follower of user {}: {}", follower.user_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        info!(user_id = %follower.user_id, actor = %follower.actor_url, "remote follower added");
+        Ok(())
+    }
+
+    /// Deletes a follower.
+    #[instrument(name = "federation.remove_follower", skip(self))]
+    async fn remove_follower(&self, user_id: Uuid, actor_url: &str) -> Result<(), DomainError> {
+        sqlx::query(
+            r#"
+            DELETE FROM federation_followers WHERE user_id = $1 AND actor_url = $2
+            "#,
+        )
+            .bind(user_id)
+            .bind(actor_url)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to remove follower of user {}: {}", user_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    /// Lists the followers of an author, oldest first.
+    #[instrument(name = "federation.list_followers", skip(self))]
+    async fn list_followers(&self, user_id: Uuid) -> Result<Vec<Follower>, DomainError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT user_id, actor_url, inbox_url, created_at
+            FROM federation_followers
+            WHERE user_id = $1
+            ORDER BY created_at
+            "#,
+        )
+            .bind(user_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to list followers of user {}: {}", user_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        rows.into_iter().map(map_follower_row).collect()
+    }
+
+    /// Counts the followers of an author.
+    #[instrument(name = "federation.count_followers", skip(self))]
+    async fn count_followers(&self, user_id: Uuid) -> Result<i64, DomainError> {
+        sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*) FROM federation_followers WHERE user_id = $1
+            "#,
+        )
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to count followers of user {}: {}", user_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })
+    }
+}
+
+/// Maps a database row to actor keys.
+fn map_keys_row(row: PgRow) -> Result<ActorKeys, DomainError> {
+    let decode_err = |e: sqlx::Error| {
+        DomainError::Internal(format!("row decode error: {}", e))
+    };
+
+    Ok(ActorKeys {
+        user_id: row.try_get("user_id").map_err(decode_err)?,
+        public_key_pem: row.try_get("public_key_pem").map_err(decode_err)?,
+        private_key_pem: row.try_get("private_key_pem").map_err(decode_err)?,
+    })
+}
+
+/// Maps a database row to a follower.
+fn map_follower_row(row: PgRow) -> Result<Follower, DomainError> {
+    let decode_err = |e: sqlx::Error| {
+        DomainError::Internal(format!("row decode error: {}", e))
+    };
+
+    Ok(Follower {
+        user_id: row.try_get("user_id").map_err(decode_err)?,
+        actor_url: row.try_get("actor_url").map_err(decode_err)?,
+        inbox_url: row.try_get("inbox_url").map_err(decode_err)?,
+        created_at: row.try_get("created_at").map_err(decode_err)?,
+    })
+}
@@ -0,0 +1,119 @@
+use crate::domain::error::DomainError;
+use crate::domain::ip_rule::IpRule;
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use tracing::{error, info, instrument};
+use uuid::Uuid;
+
+/// IP rule persistence abstraction.
+#[async_trait]
+pub trait IpRuleRepository: Send + Sync {
+    /// Stores a new rule.
+    async fn create(&self, rule: &IpRule) -> Result<(), DomainError>;
+
+    /// Returns every rule, oldest first.
+    async fn list(&self) -> Result<Vec<IpRule>, DomainError>;
+
+    /// Deletes a rule.
+    async fn delete(&self, id: Uuid) -> Result<(), DomainError>;
+}
+
+/// PostgreSQL-backed IP rule repository implementation.
+#[derive(Clone)]
+pub struct PostgresIpRuleRepository {
+    pool: PgPool,
+}
+
+impl PostgresIpRuleRepository {
+    /// Creates a new PostgreSQL IP rule repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl IpRuleRepository for PostgresIpRuleRepository {
+    /// Inserts a new rule.
+    #[instrument(name = "ip_rules.create", skip(self, rule), fields(rule_id = %rule.id))]
+    async fn create(&self, rule: &IpRule) -> Result<(), DomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO ip_rules (id, network, action, reason, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+            .bind(rule.id)
+            .bind(rule.network.to_string())
+            .bind(rule.action)
+            .bind(&rule.reason)
+            .bind(rule.created_at)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to create IP rule for {}: {}", rule.network, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        info!(rule_id = %rule.id, network = %rule.network, "IP rule created");
+        Ok(())
+    }
+
+    /// Lists all rules.
+    #[instrument(name = "ip_rules.list", skip(self))]
+    async fn list(&self) -> Result<Vec<IpRule>, DomainError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, network, action, reason, created_at
+            FROM ip_rules
+            ORDER BY created_at, id
+            "#,
+        )
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to list IP rules: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        rows.into_iter().map(map_row).collect()
+    }
+
+    /// Deletes a rule by ID.
+    #[instrument(name = "ip_rules.delete", skip(self))]
+    async fn delete(&self, id: Uuid) -> Result<(), DomainError> {
+        let result = sqlx::query("DELETE FROM ip_rules WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to delete IP rule {}: {}", id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::Validation(format!("IP rule {} does not exist", id)));
+        }
+
+        info!(rule_id = %id, "IP rule deleted");
+        Ok(())
+    }
+}
+
+/// Maps a database row to an IP rule.
+fn map_row(row: PgRow) -> Result<IpRule, DomainError> {
+    let decode_err = |e: sqlx::Error| {
+        DomainError::Internal(format!("row decode error: {}", e))
+    };
+    let network: String = row.try_get("network").map_err(decode_err)?;
+
+    Ok(IpRule {
+        id: row.try_get("id").map_err(decode_err)?,
+        network: network
+            .parse()
+            .map_err(|e: String| DomainError::Internal(format!("row decode error: {}", e)))?,
+        action: row.try_get("action").map_err(decode_err)?,
+        reason: row.try_get("reason").map_err(decode_err)?,
+        created_at: row.try_get("created_at").map_err(decode_err)?,
+    })
+}
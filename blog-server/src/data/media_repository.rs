@@ -0,0 +1,571 @@
+use crate::data::unit_of_work::UnitOfWork;
+use crate::domain::error::DomainError;
+use crate::domain::media::{Media, MediaUpload, MediaVariant, VariantName};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use tracing::{error, info, instrument};
+use uuid::Uuid;
+
+/// Media persistence abstraction.
+///
+/// Stores metadata of uploaded files and counts the references to each
+/// blob of file contents; the contents live in media storage.
+#[async_trait]
+pub trait MediaRepository: Send + Sync {
+    /// Starts a unit of work on the media database.
+    async fn begin(&self) -> Result<UnitOfWork, DomainError>;
+
+    /// Persists metadata of a new upload.
+    ///
+    /// The upload's blob must already be retained with [`Self::retain_blob`].
+    async fn create(&self, media: Media) -> Result<Media, DomainError>;
+
+    /// Returns media metadata by its ID, if it exists.
+    async fn get(&self, id: Uuid) -> Result<Option<Media>, DomainError>;
+
+    /// Deletes media metadata and its variants, releasing its blob.
+    ///
+    /// Returns the deleted media, or `None` if there was none.
+    async fn delete(&self, id: Uuid) -> Result<Option<Media>, DomainError>;
+
+    /// Adds a reference to the blob with the given hash, recording the blob
+    /// if it is new.
+    ///
+    /// Waits while garbage collection holds the blob, so a blob is never
+    /// collected once this returns.
+    async fn retain_blob(&self, sha256: &str, size_bytes: i64) -> Result<(), DomainError>;
+
+    /// Drops a reference to the blob with the given hash.
+    async fn release_blob(&self, sha256: &str) -> Result<(), DomainError>;
+
+    /// Locks up to `limit` blobs nothing references, for the unit of work to
+    /// delete; returns their hashes.
+    ///
+    /// Blobs another collection holds are skipped.
+    async fn lock_unreferenced_blobs(
+        &self,
+        uow: &mut UnitOfWork,
+        limit: i64,
+    ) -> Result<Vec<String>, DomainError>;
+
+    /// Deletes the records of the given blobs as part of the unit of work.
+    async fn delete_blobs(&self, uow: &mut UnitOfWork, hashes: &[String]) -> Result<(), DomainError>;
+
+    /// Returns metadata of every file the given user uploaded, oldest first.
+    async fn list_by_owner(&self, owner_id: Uuid) -> Result<Vec<Media>, DomainError>;
+
+    /// Returns the total size in bytes of the files the given user uploaded.
+    async fn total_size(&self, owner_id: Uuid) -> Result<i64, DomainError>;
+
+    /// Stores metadata of a generated variant, replacing an earlier one of the same name.
+    async fn upsert_variant(&self, variant: &MediaVariant) -> Result<(), DomainError>;
+
+    /// Returns the metadata of one variant of a media file, if it was generated.
+    async fn get_variant(
+        &self,
+        media_id: Uuid,
+        name: VariantName,
+    ) -> Result<Option<MediaVariant>, DomainError>;
+
+    /// Returns the metadata of the generated variants of a media file.
+    async fn list_variants(&self, media_id: Uuid) -> Result<Vec<MediaVariant>, DomainError>;
+
+    /// Persists a new chunked upload.
+    async fn create_upload(&self, upload: &MediaUpload) -> Result<(), DomainError>;
+
+    /// Returns a chunked upload by its ID, if it is still in progress.
+    async fn get_upload(&self, id: Uuid) -> Result<Option<MediaUpload>, DomainError>;
+
+    /// Records `len` more bytes received after the first `offset`.
+    ///
+    /// Returns the updated upload, or `None` when no upload has received
+    /// exactly `offset` bytes, e.g. because another request got there first.
+    async fn advance_upload(
+        &self,
+        id: Uuid,
+        offset: i64,
+        len: i64,
+    ) -> Result<Option<MediaUpload>, DomainError>;
+
+    /// Removes a chunked upload; a missing one is not an error.
+    async fn delete_upload(&self, id: Uuid) -> Result<(), DomainError>;
+
+    /// Removes uploads that received nothing since `before`; returns their IDs.
+    async fn delete_stale_uploads(&self, before: DateTime<Utc>) -> Result<Vec<Uuid>, DomainError>;
+}
+
+/// PostgreSQL-backed media repository implementation.
+#[derive(Clone)]
+pub struct PostgresMediaRepository {
+    pool: PgPool,
+}
+
+impl PostgresMediaRepository {
+    /// Creates a new PostgreSQL media repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl MediaRepository for PostgresMediaRepository {
+    /// Begins a transaction on the pool.
+    #[instrument(name = "media.begin", skip(self))]
+    async fn begin(&self) -> Result<UnitOfWork, DomainError> {
+        UnitOfWork::begin(&self.pool).await
+    }
+
+    /// Inserts media metadata into the database.
+    #[instrument(name = "media.create", skip(self, media), fields(media_id = %media.id))]
+    async fn create(&self, media: Media) -> Result<Media, DomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO media (id, owner_id, filename, content_type, size_bytes, sha256, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+            .bind(media.id)
+            .bind(media.owner_id)
+            .bind(&media.filename)
+            .bind(&media.content_type)
+            .bind(media.size_bytes)
+            .bind(&media.sha256)
+            .bind(media.created_at)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to create media: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        info!(media_id = %media.id, owner_id = %media.owner_id, "media created");
+        Ok(media)
+    }
+
+    /// Returns media metadata by its ID, if present.
+    #[instrument(name = "media.get", skip(self))]
+    async fn get(&self, id: Uuid) -> Result<Option<Media>, DomainError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, owner_id, filename, content_type, size_bytes, sha256, created_at
+            FROM media
+            WHERE id = $1
+            "#,
+        )
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to find media by id {}: {}", id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        row.map(map_row).transpose()
+    }
+
+    /// Deletes media, its variants and one reference to its blob in one statement.
+    #[instrument(name = "media.delete", skip(self))]
+    async fn delete(&self, id: Uuid) -> Result<Option<Media>, DomainError> {
+        let row = sqlx::query(
+            r#"
+            WITH deleted AS (
+                DELETE FROM media
+                WHERE id = $1
+                RETURNING id, owner_id, filename, content_type, size_bytes, sha256, created_at
+            ),
+            variants AS (
+                DELETE FROM media_variants
+                WHERE media_id IN (SELECT id FROM deleted)
+            ),
+            released AS (
+                UPDATE media_blobs
+                SET ref_count = ref_count - 1
+                WHERE sha256 IN (SELECT sha256 FROM deleted)
+            )
+            SELECT id, owner_id, filename, content_type, size_bytes, sha256, created_at
+            FROM deleted
+            "#,
+        )
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to delete media {}: {}", id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        if row.is_some() {
+            info!(media_id = %id, "media deleted");
+        }
+        row.map(map_row).transpose()
+    }
+
+    /// Inserts the blob with one reference, or adds one to an existing blob.
+    #[instrument(name = "media.retain_blob", skip(self))]
+    async fn retain_blob(&self, sha256: &str, size_bytes: i64) -> Result<(), DomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO media_blobs (sha256, size_bytes, ref_count, created_at)
+            VALUES ($1, $2, 1, now())
+            ON CONFLICT (sha256) DO UPDATE SET ref_count = media_blobs.ref_count + 1
+            "#,
+        )
+            .bind(sha256)
+            .bind(size_bytes)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to retain blob {}: {}", sha256, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    /// Decrements the reference count of a blob.
+    #[instrument(name = "media.release_blob", skip(self))]
+    async fn release_blob(&self, sha256: &str) -> Result<(), DomainError> {
+        sqlx::query("UPDATE media_blobs SET ref_count = ref_count - 1 WHERE sha256 = $1")
+            .bind(sha256)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to release blob {}: {}", sha256, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    /// Selects unreferenced blobs `FOR UPDATE SKIP LOCKED` inside the unit of work.
+    #[instrument(name = "media.lock_unreferenced_blobs", skip(self, uow))]
+    async fn lock_unreferenced_blobs(
+        &self,
+        uow: &mut UnitOfWork,
+        limit: i64,
+    ) -> Result<Vec<String>, DomainError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT sha256
+            FROM media_blobs
+            WHERE ref_count <= 0
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+            .bind(limit)
+            .fetch_all(uow.conn())
+            .await
+            .map_err(|e| {
+                error!("failed to lock unreferenced blobs: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        rows.into_iter()
+            .map(|row| {
+                row.try_get("sha256")
+                    .map_err(|e| DomainError::Internal(format!("row decode error: {}", e)))
+            })
+            .collect()
+    }
+
+    /// Deletes blob records inside the unit of work.
+    #[instrument(name = "media.delete_blobs", skip(self, uow, hashes), fields(count = hashes.len()))]
+    async fn delete_blobs(&self, uow: &mut UnitOfWork, hashes: &[String]) -> Result<(), DomainError> {
+        sqlx::query("DELETE FROM media_blobs WHERE sha256 = ANY($1)")
+            .bind(hashes)
+            .execute(uow.conn())
+            .await
+            .map_err(|e| {
+                error!("failed to delete blobs: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    /// Lists the uploads of a user.
+    #[instrument(name = "media.list_by_owner", skip(self))]
+    async fn list_by_owner(&self, owner_id: Uuid) -> Result<Vec<Media>, DomainError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, owner_id, filename, content_type, size_bytes, sha256, created_at
+            FROM media
+            WHERE owner_id = $1
+            ORDER BY created_at, id
+            "#,
+        )
+            .bind(owner_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to list media of owner {}: {}", owner_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        rows.into_iter().map(map_row).collect()
+    }
+
+    /// Sums the upload sizes of a user; variants are not counted.
+    #[instrument(name = "media.total_size", skip(self))]
+    async fn total_size(&self, owner_id: Uuid) -> Result<i64, DomainError> {
+        let row = sqlx::query(
+            "SELECT COALESCE(SUM(size_bytes), 0)::BIGINT AS total FROM media WHERE owner_id = $1",
+        )
+            .bind(owner_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to sum media size of owner {}: {}", owner_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        row.try_get("total")
+            .map_err(|e| DomainError::Internal(format!("row decode error: {}", e)))
+    }
+
+    /// Inserts or replaces variant metadata.
+    #[instrument(name = "media.upsert_variant", skip(self, variant), fields(media_id = %variant.media_id, variant = %variant.name))]
+    async fn upsert_variant(&self, variant: &MediaVariant) -> Result<(), DomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO media_variants (media_id, name, content_type, width, height, size_bytes, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            ON CONFLICT (media_id, name) DO UPDATE
+            SET content_type = EXCLUDED.content_type, width = EXCLUDED.width,
+                height = EXCLUDED.height, size_bytes = EXCLUDED.size_bytes,
+                created_at = EXCLUDED.created_at
+            "#,
+        )
+            .bind(variant.media_id)
+            .bind(variant.name)
+            .bind(&variant.content_type)
+            .bind(variant.width)
+            .bind(variant.height)
+            .bind(variant.size_bytes)
+            .bind(variant.created_at)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to store variant of media {}: {}", variant.media_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        info!(media_id = %variant.media_id, variant = %variant.name, "media variant stored");
+        Ok(())
+    }
+
+    /// Returns variant metadata by media ID and name, if present.
+    #[instrument(name = "media.get_variant", skip(self))]
+    async fn get_variant(
+        &self,
+        media_id: Uuid,
+        name: VariantName,
+    ) -> Result<Option<MediaVariant>, DomainError> {
+        let row = sqlx::query(
+            r#"
+            SELECT media_id, name, content_type, width, height, size_bytes, created_at
+            FROM media_variants
+            WHERE media_id = $1 AND name = $2
+            "#,
+        )
+            .bind(media_id)
+            .bind(name)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to find {} variant of media {}: {}", name, media_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        row.map(map_variant_row).transpose()
+    }
+
+    /// Lists the variants of a media file, smallest first.
+    #[instrument(name = "media.list_variants", skip(self))]
+    async fn list_variants(&self, media_id: Uuid) -> Result<Vec<MediaVariant>, DomainError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT media_id, name, content_type, width, height, size_bytes, created_at
+            FROM media_variants
+            WHERE media_id = $1
+            ORDER BY width::BIGINT * height, name
+            "#,
+        )
+            .bind(media_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to list variants of media {}: {}", media_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        rows.into_iter().map(map_variant_row).collect()
+    }
+
+    /// Inserts a chunked upload.
+    #[instrument(name = "media.create_upload", skip(self, upload), fields(upload_id = %upload.id))]
+    async fn create_upload(&self, upload: &MediaUpload) -> Result<(), DomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO media_uploads
+                (id, owner_id, filename, size_bytes, received_bytes, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+            .bind(upload.id)
+            .bind(upload.owner_id)
+            .bind(&upload.filename)
+            .bind(upload.size_bytes)
+            .bind(upload.received_bytes)
+            .bind(upload.created_at)
+            .bind(upload.updated_at)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to create media upload: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        info!(upload_id = %upload.id, owner_id = %upload.owner_id, "media upload started");
+        Ok(())
+    }
+
+    /// Returns a chunked upload by its ID, if present.
+    #[instrument(name = "media.get_upload", skip(self))]
+    async fn get_upload(&self, id: Uuid) -> Result<Option<MediaUpload>, DomainError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, owner_id, filename, size_bytes, received_bytes, created_at, updated_at
+            FROM media_uploads
+            WHERE id = $1
+            "#,
+        )
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to find media upload {}: {}", id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        row.map(map_upload_row).transpose()
+    }
+
+    /// Moves the received count forward only from the expected offset.
+    #[instrument(name = "media.advance_upload", skip(self))]
+    async fn advance_upload(
+        &self,
+        id: Uuid,
+        offset: i64,
+        len: i64,
+    ) -> Result<Option<MediaUpload>, DomainError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE media_uploads
+            SET received_bytes = received_bytes + $3, updated_at = NOW()
+            WHERE id = $1 AND received_bytes = $2
+            RETURNING id, owner_id, filename, size_bytes, received_bytes, created_at, updated_at
+            "#,
+        )
+            .bind(id)
+            .bind(offset)
+            .bind(len)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to advance media upload {}: {}", id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        row.map(map_upload_row).transpose()
+    }
+
+    /// Deletes a chunked upload by its ID.
+    #[instrument(name = "media.delete_upload", skip(self))]
+    async fn delete_upload(&self, id: Uuid) -> Result<(), DomainError> {
+        sqlx::query("DELETE FROM media_uploads WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to delete media upload {}: {}", id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    /// Deletes uploads untouched since `before`.
+    #[instrument(name = "media.delete_stale_uploads", skip(self))]
+    async fn delete_stale_uploads(&self, before: DateTime<Utc>) -> Result<Vec<Uuid>, DomainError> {
+        let rows = sqlx::query("DELETE FROM media_uploads WHERE updated_at < $1 RETURNING id")
+            .bind(before)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to delete stale media uploads: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        rows.into_iter()
+            .map(|row| {
+                row.try_get("id")
+                    .map_err(|e| DomainError::Internal(format!("row decode error: {}", e)))
+            })
+            .collect()
+    }
+}
+
+/// Maps a database row to a media domain model.
+fn map_row(row: PgRow) -> Result<Media, DomainError> {
+    let decode_err = |e: sqlx::Error| {
+        DomainError::Internal(format!("row decode error: {}", e))
+    };
+
+    Ok(Media {
+        id: row.try_get("id").map_err(decode_err)?,
+        owner_id: row.try_get("owner_id").map_err(decode_err)?,
+        filename: row.try_get("filename").map_err(decode_err)?,
+        content_type: row.try_get("content_type").map_err(decode_err)?,
+        size_bytes: row.try_get("size_bytes").map_err(decode_err)?,
+        sha256: row.try_get("sha256").map_err(decode_err)?,
+        created_at: row.try_get("created_at").map_err(decode_err)?,
+    })
+}
+
+/// Maps a database row to media variant metadata.
+fn map_variant_row(row: PgRow) -> Result<MediaVariant, DomainError> {
+    let decode_err = |e: sqlx::Error| {
+        DomainError::Internal(format!("row decode error: {}", e))
+    };
+
+    Ok(MediaVariant {
+        media_id: row.try_get("media_id").map_err(decode_err)?,
+        name: row.try_get("name").map_err(decode_err)?,
+        content_type: row.try_get("content_type").map_err(decode_err)?,
+        width: row.try_get("width").map_err(decode_err)?,
+        height: row.try_get("height").map_err(decode_err)?,
+        size_bytes: row.try_get("size_bytes").map_err(decode_err)?,
+        created_at: row.try_get("created_at").map_err(decode_err)?,
+    })
+}
+
+/// Maps a database row to a chunked upload.
+fn map_upload_row(row: PgRow) -> Result<MediaUpload, DomainError> {
+    let decode_err = |e: sqlx::Error| {
+        DomainError::Internal(format!("row decode error: {}", e))
+    };
+
+    Ok(MediaUpload {
+        id: row.try_get("id").map_err(decode_err)?,
+        owner_id: row.try_get("owner_id").map_err(decode_err)?,
+        filename: row.try_get("filename").map_err(decode_err)?,
+        size_bytes: row.try_get("size_bytes").map_err(decode_err)?,
+        received_bytes: row.try_get("received_bytes").map_err(decode_err)?,
+        created_at: row.try_get("created_at").map_err(decode_err)?,
+        updated_at: row.try_get("updated_at").map_err(decode_err)?,
+    })
+}
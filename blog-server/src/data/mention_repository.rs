@@ -0,0 +1,260 @@
+use crate::domain::error::DomainError;
+use crate::domain::mention::Mention;
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use tracing::{error, instrument};
+use uuid::Uuid;
+
+/// Columns of a mention row.
+const MENTION_COLUMNS: &str = r#"
+    m.id, m.post_id, m.source, m.target, m.status, m.author_name, m.author_url, m.title,
+    m.excerpt, m.created_at, m.verified_at
+"#;
+
+/// Webmention persistence abstraction.
+#[async_trait]
+pub trait MentionRepository: Send + Sync {
+    /// Stores a received mention; a mention with the same source and target
+    /// is kept, with its status, and returned instead.
+    async fn save(&self, mention: &Mention) -> Result<Mention, DomainError>;
+
+    /// Stores what verification found out about a mention.
+    async fn update(&self, mention: &Mention) -> Result<(), DomainError>;
+
+    /// Returns a mention by ID, if it exists.
+    async fn get(&self, id: Uuid) -> Result<Option<Mention>, DomainError>;
+
+    /// Deletes a mention.
+    async fn delete(&self, id: Uuid) -> Result<(), DomainError>;
+
+    /// Returns a page of a post's published mentions, oldest first, with the
+    /// number of such mentions.
+    async fn list_by_post(
+        &self,
+        post_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Mention>, i64), DomainError>;
+
+    /// Returns a page of the mentions awaiting a moderator, oldest first,
+    /// with their number.
+    async fn list_held(&self, limit: i64, offset: i64) -> Result<(Vec<Mention>, i64), DomainError>;
+}
+
+/// PostgreSQL-backed webmention repository implementation.
+#[derive(Clone)]
+pub struct PostgresMentionRepository {
+    pool: PgPool,
+}
+
+impl PostgresMentionRepository {
+    /// Creates a new PostgreSQL webmention repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl MentionRepository for PostgresMentionRepository {
+    /// Inserts a mention unless its source already mentioned its target.
+    #[instrument(name = "mentions.save", skip(self, mention), fields(post_id = %mention.post_id))]
+    async fn save(&self, mention: &Mention) -> Result<Mention, DomainError> {
+        let row = sqlx::query(&format!(
+            r#"
+            INSERT INTO mentions AS m (id, post_id, source, target, status, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (source, target) DO UPDATE SET post_id = EXCLUDED.post_id
+            RETURNING {}
+            "#,
+            MENTION_COLUMNS
+        ))
+            .bind(mention.id)
+            .bind(mention.post_id)
+            .bind(&mention.source)
+            .bind(&mention.target)
+            .bind(mention.status)
+            .bind(mention.created_at)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to save mention of post {}: {}", mention.post_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        map_row(row)
+    }
+
+    /// Updates the status and source details of a mention.
+    #[instrument(name = "mentions.update", skip(self, mention), fields(mention_id = %mention.id))]
+    async fn update(&self, mention: &Mention) -> Result<(), DomainError> {
+        sqlx::query(
+            r#"
+            UPDATE mentions
+            SET status = $2, author_name = $3, author_url = $4, title = $5, excerpt = $6,
+                verified_at = $7
+            WHERE id = $1
+            "#,
+        )
+            .bind(mention.id)
+            .bind(mention.status)
+            .bind(&mention.author_name)
+            .bind(&mention.author_url)
+            .bind(&mention.title)
+            .bind(&mention.excerpt)
+            .bind(mention.verified_at)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to update mention {}: {}", mention.id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    /// Returns a mention by ID, if present.
+    #[instrument(name = "mentions.get", skip(self))]
+    async fn get(&self, id: Uuid) -> Result<Option<Mention>, DomainError> {
+        let row = sqlx::query(&format!(
+            "SELECT {} FROM mentions m WHERE m.id = $1",
+            MENTION_COLUMNS
+        ))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to find mention {}: {}", id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        row.map(map_row).transpose()
+    }
+
+    /// Deletes a mention by ID.
+    #[instrument(name = "mentions.delete", skip(self))]
+    async fn delete(&self, id: Uuid) -> Result<(), DomainError> {
+        sqlx::query("DELETE FROM mentions WHERE id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to delete mention {}: {}", id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    /// Lists the published mentions of a post.
+    #[instrument(name = "mentions.list_by_post", skip(self))]
+    async fn list_by_post(
+        &self,
+        post_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Mention>, i64), DomainError> {
+        let db_err = |e: sqlx::Error| {
+            error!("failed to list mentions of post {}: {}", post_id, e);
+            DomainError::Internal(format!("database error: {}", e))
+        };
+
+        let total: i64 = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM mentions WHERE post_id = $1 AND status = 'published'",
+        )
+            .bind(post_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(db_err)?;
+
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM mentions m
+            WHERE m.post_id = $1 AND m.status = 'published'
+            ORDER BY m.created_at, m.id
+            LIMIT $2 OFFSET $3
+            "#,
+            MENTION_COLUMNS
+        ))
+            .bind(post_id)
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(db_err)?;
+
+        let mentions = rows
+            .into_iter()
+            .map(map_row)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok((mentions, total))
+    }
+
+    /// Lists held mentions of posts that still exist.
+    #[instrument(name = "mentions.list_held", skip(self))]
+    async fn list_held(&self, limit: i64, offset: i64) -> Result<(Vec<Mention>, i64), DomainError> {
+        let db_err = |e: sqlx::Error| {
+            error!("failed to list held mentions: {}", e);
+            DomainError::Internal(format!("database error: {}", e))
+        };
+
+        let total: i64 = sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM mentions m
+            JOIN posts p ON p.id = m.post_id AND p.deleted_at IS NULL
+            WHERE m.status = 'held'
+            "#,
+        )
+            .fetch_one(&self.pool)
+            .await
+            .map_err(db_err)?;
+
+        let rows = sqlx::query(&format!(
+            r#"
+            SELECT {}
+            FROM mentions m
+            JOIN posts p ON p.id = m.post_id AND p.deleted_at IS NULL
+            WHERE m.status = 'held'
+            ORDER BY m.created_at, m.id
+            LIMIT $1 OFFSET $2
+            "#,
+            MENTION_COLUMNS
+        ))
+            .bind(limit)
+            .bind(offset)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(db_err)?;
+
+        let mentions = rows
+            .into_iter()
+            .map(map_row)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        Ok((mentions, total))
+    }
+}
+
+/// Maps a database row to a mention.
+fn map_row(row: PgRow) -> Result<Mention, DomainError> {
+    let decode_err = |e: sqlx::Error| {
+        DomainError::Internal(format!("row decode error: {}", e))
+    };
+
+    Ok(Mention {
+        id: row.try_get("id").map_err(decode_err)?,
+        post_id: row.try_get("post_id").map_err(decode_err)?,
+        source: row.try_get("source").map_err(decode_err)?,
+        target: row.try_get("target").map_err(decode_err)?,
+        status: row.try_get("status").map_err(decode_err)?,
+        author_name: row.try_get("author_name").map_err(decode_err)?,
+        author_url: row.try_get("author_url").map_err(decode_err)?,
+        title: row.try_get("title").map_err(decode_err)?,
+        excerpt: row.try_get("excerpt").map_err(decode_err)?,
+        created_at: row.try_get("created_at").map_err(decode_err)?,
+        verified_at: row.try_get("verified_at").map_err(decode_err)?,
+    })
+}
@@ -1,5 +1,50 @@
+/// Activity history repository interfaces.
+pub mod activity_repository;
+
+/// Post view and engagement statistics repository interfaces.
+pub mod analytics_repository;
+
+/// Comment repository interfaces.
+pub mod comment_repository;
+
+/// Data export repository interfaces.
+pub mod export_repository;
+
+/// ActivityPub federation repository interfaces.
+pub mod federation_repository;
+
+/// IP allow and deny rule repository interfaces.
+pub mod ip_rule_repository;
+
+/// Media repository interfaces.
+pub mod media_repository;
+
+/// Webmention repository interfaces.
+pub mod mention_repository;
+
+/// Transactional outbox repository interfaces.
+pub mod outbox_repository;
+
 /// Post repository interfaces.
 pub mod post_repository;
 
+/// Like and bookmark repository interfaces.
+pub mod reaction_repository;
+
+/// Refresh token repository interfaces.
+pub mod refresh_token_repository;
+
+/// Post revision repository interfaces.
+pub mod revision_repository;
+
+/// Full-text search index interfaces.
+pub mod search_index;
+
+/// Post translation repository interfaces.
+pub mod translation_repository;
+
+/// Transactions spanning several repositories.
+pub mod unit_of_work;
+
 /// User repository interfaces.
 pub mod user_repository;
@@ -0,0 +1,192 @@
+use crate::data::unit_of_work::UnitOfWork;
+use crate::domain::error::DomainError;
+use crate::domain::outbox::{OutboxEvent, OutboxMessage};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use tracing::{error, instrument};
+use uuid::Uuid;
+
+/// Transactional outbox persistence abstraction.
+#[async_trait]
+pub trait OutboxRepository: Send + Sync {
+    /// Starts a unit of work for relaying messages.
+    async fn begin(&self) -> Result<UnitOfWork, DomainError>;
+
+    /// Stores a message as part of the unit of work making the change it describes.
+    async fn append(
+        &self,
+        uow: &mut UnitOfWork,
+        message: &OutboxMessage,
+    ) -> Result<(), DomainError>;
+
+    /// Returns up to `limit` undelivered messages, oldest first, locking them
+    /// until the unit of work ends; messages locked by another relay are skipped.
+    async fn claim(
+        &self,
+        uow: &mut UnitOfWork,
+        limit: i64,
+    ) -> Result<Vec<OutboxMessage>, DomainError>;
+
+    /// Marks a message as delivered.
+    async fn mark_delivered(&self, uow: &mut UnitOfWork, id: Uuid) -> Result<(), DomainError>;
+
+    /// Records a failed attempt to deliver a message.
+    async fn mark_failed(
+        &self,
+        uow: &mut UnitOfWork,
+        id: Uuid,
+        error: &str,
+    ) -> Result<(), DomainError>;
+
+    /// Permanently removes messages delivered before `before`; returns how many.
+    async fn purge_delivered(&self, before: DateTime<Utc>) -> Result<u64, DomainError>;
+}
+
+/// PostgreSQL-backed outbox repository implementation.
+#[derive(Clone)]
+pub struct PostgresOutboxRepository {
+    pool: PgPool,
+}
+
+impl PostgresOutboxRepository {
+    /// Creates a new PostgreSQL outbox repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl OutboxRepository for PostgresOutboxRepository {
+    async fn begin(&self) -> Result<UnitOfWork, DomainError> {
+        UnitOfWork::begin(&self.pool).await
+    }
+
+    /// Inserts a message with its event serialized as JSON.
+    #[instrument(name = "outbox.append", skip(self, uow, message), fields(message_id = %message.id))]
+    async fn append(
+        &self,
+        uow: &mut UnitOfWork,
+        message: &OutboxMessage,
+    ) -> Result<(), DomainError> {
+        let payload = serde_json::to_string(&message.event)
+            .map_err(|e| DomainError::Internal(format!("event encoding error: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO outbox (id, event_type, payload, created_at)
+            VALUES ($1, $2, $3, $4)
+            "#,
+        )
+            .bind(message.id)
+            .bind(message.event.name())
+            .bind(payload)
+            .bind(message.created_at)
+            .execute(uow.conn())
+            .await
+            .map_err(|e| {
+                error!("failed to append outbox message {}: {}", message.id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    /// Locks the oldest undelivered messages with `FOR UPDATE SKIP LOCKED`.
+    #[instrument(name = "outbox.claim", skip(self, uow))]
+    async fn claim(
+        &self,
+        uow: &mut UnitOfWork,
+        limit: i64,
+    ) -> Result<Vec<OutboxMessage>, DomainError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, payload, created_at, attempts
+            FROM outbox
+            WHERE delivered_at IS NULL
+            ORDER BY created_at, id
+            LIMIT $1
+            FOR UPDATE SKIP LOCKED
+            "#,
+        )
+            .bind(limit)
+            .fetch_all(uow.conn())
+            .await
+            .map_err(|e| {
+                error!("failed to claim outbox messages: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        rows.into_iter().map(map_row).collect()
+    }
+
+    /// Sets the delivery time of a message.
+    #[instrument(name = "outbox.mark_delivered", skip(self, uow))]
+    async fn mark_delivered(&self, uow: &mut UnitOfWork, id: Uuid) -> Result<(), DomainError> {
+        sqlx::query("UPDATE outbox SET delivered_at = now(), last_error = NULL WHERE id = $1")
+            .bind(id)
+            .execute(uow.conn())
+            .await
+            .map_err(|e| {
+                error!("failed to mark outbox message {} delivered: {}", id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    /// Counts the attempt and keeps the error of a message.
+    #[instrument(name = "outbox.mark_failed", skip(self, uow, error))]
+    async fn mark_failed(
+        &self,
+        uow: &mut UnitOfWork,
+        id: Uuid,
+        error: &str,
+    ) -> Result<(), DomainError> {
+        sqlx::query("UPDATE outbox SET attempts = attempts + 1, last_error = $2 WHERE id = $1")
+            .bind(id)
+            .bind(error)
+            .execute(uow.conn())
+            .await
+            .map_err(|e| {
+                error!("failed to mark outbox message {} failed: {}", id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    /// Deletes messages delivered before the cutoff.
+    #[instrument(name = "outbox.purge_delivered", skip(self))]
+    async fn purge_delivered(&self, before: DateTime<Utc>) -> Result<u64, DomainError> {
+        let result = sqlx::query("DELETE FROM outbox WHERE delivered_at < $1")
+            .bind(before)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to purge delivered outbox messages: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        Ok(result.rows_affected())
+    }
+}
+
+/// Maps a database row to an outbox message.
+fn map_row(row: PgRow) -> Result<OutboxMessage, DomainError> {
+    let decode_err = |e: sqlx::Error| {
+        DomainError::Internal(format!("row decode error: {}", e))
+    };
+
+    let payload: String = row.try_get("payload").map_err(decode_err)?;
+    let event: OutboxEvent = serde_json::from_str(&payload)
+        .map_err(|e| DomainError::Internal(format!("event decoding error: {}", e)))?;
+
+    Ok(OutboxMessage {
+        id: row.try_get("id").map_err(decode_err)?,
+        event,
+        created_at: row.try_get("created_at").map_err(decode_err)?,
+        attempts: row.try_get("attempts").map_err(decode_err)?,
+    })
+}
@@ -1,9 +1,14 @@
+use crate::data::unit_of_work::UnitOfWork;
 use crate::domain::error::DomainError;
-use crate::domain::post::Post;
+use crate::domain::moderation::FlaggedPost;
+use crate::domain::post::{Post, Visibility};
+use crate::domain::search::{HIGHLIGHT_END, HIGHLIGHT_START};
+use crate::domain::tag::Tag;
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use sqlx::postgres::PgRow;
-use sqlx::{PgPool, Row};
-use tracing::{error, info};
+use sqlx::{PgConnection, PgPool, Row};
+use tracing::{error, info, instrument};
 use uuid::Uuid;
 
 /// Post persistence abstraction.
@@ -11,20 +16,108 @@ use uuid::Uuid;
 /// Defines operations for storing and retrieving posts.
 #[async_trait]
 pub trait PostRepository: Send + Sync {
-    /// Persists a new post.
-    async fn create(&self, post: Post) -> Result<Post, DomainError>;
+    /// Starts a unit of work on the post database.
+    async fn begin(&self) -> Result<UnitOfWork, DomainError>;
 
-    /// Updates an existing post.
-    async fn update(&self, post: Post) -> Result<Post, DomainError>;
+    /// Persists a new post as part of the given unit of work.
+    async fn create(&self, uow: &mut UnitOfWork, post: Post) -> Result<Post, DomainError>;
 
-    /// Returns a post by its ID.
+    /// Updates an existing post as part of the given unit of work.
+    async fn update(&self, uow: &mut UnitOfWork, post: Post) -> Result<Post, DomainError>;
+
+    /// Returns a post by its ID, with its author's username.
+    ///
+    /// Posts in the trash are not returned by this or any other lookup.
     async fn get(&self, id: Uuid) -> Result<Option<Post>, DomainError>;
 
-    /// Deletes a post by its ID.
+    /// Returns the posts with the given IDs, in no particular order; IDs of
+    /// missing or trashed posts are skipped.
+    async fn get_many(&self, ids: &[Uuid]) -> Result<Vec<Post>, DomainError>;
+
+    /// Returns a post by its ID, locking it until the unit of work ends.
+    async fn get_for_update(
+        &self,
+        uow: &mut UnitOfWork,
+        id: Uuid,
+    ) -> Result<Option<Post>, DomainError>;
+
+    /// Moves a post to the trash.
     async fn delete(&self, id: Uuid) -> Result<(), DomainError>;
 
-    /// Returns posts authored by the given user.
-    async fn list(&self, author_id: Uuid) -> Result<Vec<Post>, DomainError>;
+    /// Takes a post of the given author out of the trash.
+    async fn restore(&self, id: Uuid, author_id: Uuid) -> Result<(), DomainError>;
+
+    /// Returns a page of the given author's trashed posts, most recently
+    /// deleted first, with the number of posts in the trash.
+    async fn list_trash(
+        &self,
+        author_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Post>, i64), DomainError>;
+
+    /// Returns how many posts `list_trash` pages through.
+    async fn count_trash(&self, author_id: Uuid) -> Result<i64, DomainError>;
+
+    /// Permanently removes posts trashed before `before`; returns how many.
+    async fn purge_trash(&self, before: DateTime<Utc>) -> Result<u64, DomainError>;
+
+    /// Returns a post by its slug.
+    async fn get_by_slug(&self, slug: &str) -> Result<Option<Post>, DomainError>;
+
+    /// Returns a page of posts with their authors' usernames, newest first.
+    ///
+    /// When `author_id` is set, only that user's posts are returned, otherwise
+    /// only public ones; when `tag` is set, only posts carrying that tag. The page comes with the
+    /// number of posts matching the filters, which is zero when the page is
+    /// empty.
+    async fn list(
+        &self,
+        author_id: Option<Uuid>,
+        tag: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Post>, i64), DomainError>;
+
+    /// Returns how many posts `list` pages through for the same filters.
+    async fn count(&self, author_id: Option<Uuid>, tag: Option<&str>) -> Result<i64, DomainError>;
+
+    /// Returns a page of public posts matching a full-text query, best matches first.
+    ///
+    /// Each result carries its rank and a raw snippet with matches wrapped in
+    /// the highlight markers from [`crate::domain::search`]. The total number
+    /// of matches is returned as for `list`.
+    async fn search(
+        &self,
+        query: &str,
+        tag: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<(Post, f32, String)>, i64), DomainError>;
+
+    /// Returns how many posts `search` pages through for the same filters.
+    async fn count_search(&self, query: &str, tag: Option<&str>) -> Result<i64, DomainError>;
+
+    /// Returns the tags used on the given user's posts, by name.
+    async fn tags(&self, author_id: Uuid) -> Result<Vec<Tag>, DomainError>;
+
+    /// Sends a post to the review queue as part of the given unit of work;
+    /// flagging a queued post again replaces its reason.
+    async fn flag(&self, uow: &mut UnitOfWork, id: Uuid, reason: &str) -> Result<(), DomainError>;
+
+    /// Returns a page of the review queue, oldest flag first, with the number
+    /// of queued posts; posts in the trash are left out.
+    async fn list_flagged(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<FlaggedPost>, i64), DomainError>;
+
+    /// Returns how many posts `list_flagged` pages through.
+    async fn count_flagged(&self) -> Result<i64, DomainError>;
+
+    /// Takes a post out of the review queue.
+    async fn unflag(&self, id: Uuid) -> Result<(), DomainError>;
 }
 
 /// PostgreSQL-backed post repository implementation.
@@ -42,90 +135,180 @@ impl PostgresPostRepository {
 
 #[async_trait]
 impl PostRepository for PostgresPostRepository {
+    /// Begins a transaction on the pool.
+    #[instrument(name = "posts.begin", skip(self))]
+    async fn begin(&self) -> Result<UnitOfWork, DomainError> {
+        UnitOfWork::begin(&self.pool).await
+    }
+
     /// Inserts a new post into the database.
-    async fn create(&self, post: Post) -> Result<Post, DomainError> {
-        sqlx::query(
+    #[instrument(name = "posts.create", skip(self, uow, post), fields(post_id = %post.id))]
+    async fn create(&self, uow: &mut UnitOfWork, post: Post) -> Result<Post, DomainError> {
+        sqlx::query!(
             r#"
-            INSERT INTO posts (id, author_id, title, content, created_at, deleted_at)
-            VALUES ($1, $2, $3, $4, $5, $6)
+            INSERT INTO posts (id, author_id, title, slug, content, tags, visibility, created_at, updated_at, deleted_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)
             "#,
+            post.id,
+            post.author_id,
+            post.title,
+            post.slug,
+            post.content,
+            &post.tags,
+            post.visibility as Visibility,
+            post.created_at,
+            post.updated_at,
+            post.deleted_at,
         )
-            .bind(post.id)
-            .bind(post.author_id)
-            .bind(&post.title)
-            .bind(&post.content)
-            .bind(&post.created_at)
-            .bind(&post.deleted_at)
-            .execute(&self.pool)
+            .execute(uow.conn())
             .await
             .map_err(|e| {
                 error!("failed to create post: {}", e);
                 DomainError::Internal(format!("database error: {}", e))
             })?;
+        refresh_listing(uow.conn(), post.id).await?;
 
         info!(post_id = %post.id, title = %post.title, "post created");
         Ok(post)
     }
 
-    /// Updates an existing post in the database.
-    async fn update(&self, post: Post) -> Result<Post, DomainError> {
-        sqlx::query(
+    /// Updates an existing post inside the unit of work's transaction.
+    #[instrument(name = "posts.update", skip(self, uow, post), fields(post_id = %post.id))]
+    async fn update(&self, uow: &mut UnitOfWork, post: Post) -> Result<Post, DomainError> {
+        sqlx::query!(
             r#"
             UPDATE posts
-            SET title = $2, content = $3
+            SET title = $2, content = $3, tags = $4, visibility = $5, updated_at = $6
             WHERE id = $1
             "#,
+            post.id,
+            post.title,
+            post.content,
+            &post.tags,
+            post.visibility as Visibility,
+            post.updated_at,
         )
-            .bind(post.id)
-            .bind(&post.title)
-            .bind(&post.content)
-            .execute(&self.pool)
+            .execute(uow.conn())
             .await
             .map_err(|e| {
                 error!("failed to update post: {}", e);
                 DomainError::Internal(format!("database error: {}", e))
             })?;
+        refresh_listing(uow.conn(), post.id).await?;
 
         info!(post_id = %post.id, title = %post.title, "post updated");
         Ok(post)
     }
 
     /// Returns a post by its ID, if it exists.
+    #[instrument(name = "posts.get", skip(self))]
     async fn get(&self, id: Uuid) -> Result<Option<Post>, DomainError> {
-        let row = sqlx::query(
+        sqlx::query_as!(
+            Post,
             r#"
-            SELECT id, author_id, title, content, created_at, deleted_at
-            FROM posts
-            WHERE id = $1
+            SELECT p.id, p.author_id, u.username AS "author_username?", p.title, p.slug, p.content,
+                   p.tags, p.visibility AS "visibility: Visibility", p.created_at, p.updated_at,
+                   p.deleted_at
+            FROM posts p
+            LEFT JOIN users u ON u.id = p.author_id
+            WHERE p.id = $1 AND p.deleted_at IS NULL
             "#,
+            id
         )
-            .bind(id)
             .fetch_optional(&self.pool)
             .await
             .map_err(|e| {
                 error!("failed to find post by id {}: {}", id, e);
                 DomainError::Internal(format!("database error: {}", e))
-            })?;
+            })
+    }
 
-        Ok(row.map(|row| Post {
-            id: row.get("id"),
-            author_id: row.get("author_id"),
-            title: row.get("title"),
-            content: row.get("content"),
-            created_at: row.get("created_at"),
-            deleted_at: row.get("deleted_at"),
-        }))
+    /// Returns the posts with the given IDs that exist.
+    #[instrument(name = "posts.get_many", skip(self, ids), fields(count = ids.len()))]
+    async fn get_many(&self, ids: &[Uuid]) -> Result<Vec<Post>, DomainError> {
+        sqlx::query_as!(
+            Post,
+            r#"
+            SELECT p.id, p.author_id, u.username AS "author_username?", p.title, p.slug, p.content,
+                   p.tags, p.visibility AS "visibility: Visibility", p.created_at, p.updated_at,
+                   p.deleted_at
+            FROM posts p
+            LEFT JOIN users u ON u.id = p.author_id
+            WHERE p.id = ANY($1) AND p.deleted_at IS NULL
+            "#,
+            ids
+        )
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to find {} posts by id: {}", ids.len(), e);
+                DomainError::Internal(format!("database error: {}", e))
+            })
+    }
+
+    /// Returns a post by its ID with a row lock held by the unit of work.
+    #[instrument(name = "posts.get_for_update", skip(self, uow))]
+    async fn get_for_update(
+        &self,
+        uow: &mut UnitOfWork,
+        id: Uuid,
+    ) -> Result<Option<Post>, DomainError> {
+        sqlx::query_as!(
+            Post,
+            r#"
+            SELECT p.id, p.author_id, u.username AS "author_username?", p.title, p.slug, p.content,
+                   p.tags, p.visibility AS "visibility: Visibility", p.created_at, p.updated_at,
+                   p.deleted_at
+            FROM posts p
+            LEFT JOIN users u ON u.id = p.author_id
+            WHERE p.id = $1 AND p.deleted_at IS NULL
+            FOR UPDATE OF p
+            "#,
+            id
+        )
+            .fetch_optional(uow.conn())
+            .await
+            .map_err(|e| {
+                error!("failed to lock post {}: {}", id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })
+    }
+
+    /// Returns a post by its slug, if it exists.
+    #[instrument(name = "posts.get_by_slug", skip(self))]
+    async fn get_by_slug(&self, slug: &str) -> Result<Option<Post>, DomainError> {
+        sqlx::query_as!(
+            Post,
+            r#"
+            SELECT p.id, p.author_id, u.username AS "author_username?", p.title, p.slug, p.content,
+                   p.tags, p.visibility AS "visibility: Visibility", p.created_at, p.updated_at,
+                   p.deleted_at
+            FROM posts p
+            LEFT JOIN users u ON u.id = p.author_id
+            WHERE p.slug = $1 AND p.deleted_at IS NULL
+            "#,
+            slug
+        )
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to find post by slug {}: {}", slug, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })
     }
 
-    /// Deletes a post by its ID.
+    /// Marks a post as deleted; it stays in the table until purged.
+    #[instrument(name = "posts.delete", skip(self))]
     async fn delete(&self, id: Uuid) -> Result<(), DomainError> {
+        let mut uow = self.begin().await?;
         let result = sqlx::query!(
             r#"
-            DELETE FROM posts WHERE id = $1
+            UPDATE posts SET deleted_at = now()
+            WHERE id = $1 AND deleted_at IS NULL
             "#,
             id
         )
-            .execute(&self.pool)
+            .execute(uow.conn())
             .await
             .map_err(|e| DomainError::Internal(e.to_string()))?;
 
@@ -133,43 +316,505 @@ impl PostRepository for PostgresPostRepository {
             return Err(DomainError::PostNotFound(id.to_string()));
         }
 
+        refresh_listing(uow.conn(), id).await?;
+        uow.commit().await
+    }
+
+    /// Clears the deletion mark of a trashed post owned by `author_id`.
+    #[instrument(name = "posts.restore", skip(self))]
+    async fn restore(&self, id: Uuid, author_id: Uuid) -> Result<(), DomainError> {
+        let mut uow = self.begin().await?;
+        let result = sqlx::query!(
+            r#"
+            UPDATE posts SET deleted_at = NULL
+            WHERE id = $1 AND author_id = $2 AND deleted_at IS NOT NULL
+            "#,
+            id,
+            author_id,
+        )
+            .execute(uow.conn())
+            .await
+            .map_err(|e| {
+                error!("failed to restore post {}: {}", id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::PostNotFound(format!("trashed post id: {}", id)));
+        }
+
+        refresh_listing(uow.conn(), id).await?;
+        uow.commit().await?;
+
+        info!(post_id = %id, "post restored");
         Ok(())
     }
 
-    /// Returns all posts authored by the given user.
-    async fn list(&self, author_id: Uuid) -> Result<Vec<Post>, DomainError> {
-        let rows = sqlx::query(
+    /// Returns a page of an author's trashed posts.
+    ///
+    /// The total is counted with a window function in the same query.
+    #[instrument(name = "posts.list_trash", skip(self))]
+    async fn list_trash(
+        &self,
+        author_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Post>, i64), DomainError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT p.id, p.author_id, u.username AS "author_username?", p.title, p.slug, p.content,
+                   p.tags, p.visibility AS "visibility: Visibility", p.created_at, p.updated_at,
+                   p.deleted_at,
+                   COUNT(*) OVER () AS "total!"
+            FROM posts p
+            LEFT JOIN users u ON u.id = p.author_id
+            WHERE p.author_id = $1 AND p.deleted_at IS NOT NULL
+            ORDER BY p.deleted_at DESC, p.id
+            LIMIT $2 OFFSET $3
+            "#,
+            author_id,
+            limit,
+            offset,
+        )
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to list trash of author {}: {}", author_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        let total = rows.first().map_or(0, |row| row.total);
+        let posts = rows
+            .into_iter()
+            .map(|row| Post {
+                id: row.id,
+                author_id: row.author_id,
+                author_username: row.author_username,
+                title: row.title,
+                slug: row.slug,
+                content: row.content,
+                tags: row.tags,
+                visibility: row.visibility,
+                created_at: row.created_at,
+                updated_at: row.updated_at,
+                deleted_at: row.deleted_at,
+            })
+            .collect();
+
+        Ok((posts, total))
+    }
+
+    /// Counts the posts in an author's trash.
+    #[instrument(name = "posts.count_trash", skip(self))]
+    async fn count_trash(&self, author_id: Uuid) -> Result<i64, DomainError> {
+        sqlx::query_scalar!(
             r#"
-            SELECT id, author_id, title, content, created_at, deleted_at
+            SELECT COUNT(*) AS "count!"
             FROM posts
-            WHERE author_id = $1
-            ORDER BY created_at DESC
+            WHERE author_id = $1 AND deleted_at IS NOT NULL
+            "#,
+            author_id,
+        )
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to count trash of author {}: {}", author_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })
+    }
+
+    /// Deletes the rows of posts trashed before the cutoff.
+    #[instrument(name = "posts.purge_trash", skip(self))]
+    async fn purge_trash(&self, before: DateTime<Utc>) -> Result<u64, DomainError> {
+        let result = sqlx::query!(
+            r#"
+            DELETE FROM posts WHERE deleted_at < $1
+            "#,
+            before
+        )
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to purge trashed posts: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Returns a page of posts, optionally only those of one author.
+    ///
+    /// Reads the `post_listings` read model, which needs no joins; the total
+    /// is counted with a window function in the same query.
+    #[instrument(name = "posts.list", skip(self))]
+    async fn list(
+        &self,
+        author_id: Option<Uuid>,
+        tag: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Post>, i64), DomainError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT l.id, l.author_id, l.author_username AS "author_username?", l.title, l.slug,
+                   l.content, l.tags, l.visibility AS "visibility: Visibility", l.created_at,
+                   l.updated_at,
+                   COUNT(*) OVER () AS "total!"
+            FROM post_listings l
+            WHERE ($1::UUID IS NULL AND l.visibility = 'public' OR l.author_id = $1)
+              AND ($4::TEXT IS NULL OR $4 = ANY(l.tags))
+            ORDER BY l.created_at DESC, l.id
+            LIMIT $2 OFFSET $3
+            "#,
+            author_id,
+            limit,
+            offset,
+            tag,
+        )
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to list posts for author {:?}: {}", author_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        let total = rows.first().map_or(0, |row| row.total);
+        let posts = rows
+            .into_iter()
+            .map(|row| Post {
+                id: row.id,
+                author_id: row.author_id,
+                author_username: row.author_username,
+                title: row.title,
+                slug: row.slug,
+                content: row.content,
+                tags: row.tags,
+                visibility: row.visibility,
+                created_at: row.created_at,
+                updated_at: row.updated_at,
+                deleted_at: None,
+            })
+            .collect();
+
+        Ok((posts, total))
+    }
+
+    /// Counts the posts matched by `list` regardless of paging.
+    #[instrument(name = "posts.count", skip(self))]
+    async fn count(&self, author_id: Option<Uuid>, tag: Option<&str>) -> Result<i64, DomainError> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM post_listings
+            WHERE ($1::UUID IS NULL AND visibility = 'public' OR author_id = $1)
+              AND ($2::TEXT IS NULL OR $2 = ANY(tags))
+            "#,
+            author_id,
+            tag,
+        )
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to count posts for author {:?}: {}", author_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })
+    }
+
+    /// Searches titles and content using PostgreSQL full-text search over
+    /// the `post_listings` read model.
+    #[instrument(name = "posts.search", skip(self))]
+    async fn search(
+        &self,
+        query: &str,
+        tag: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<(Post, f32, String)>, i64), DomainError> {
+        let headline = format!(
+            r#"StartSel="{}", StopSel="{}", MinWords=15, MaxWords=35, MaxFragments=2, FragmentDelimiter=" … ""#,
+            HIGHLIGHT_START, HIGHLIGHT_END
+        );
+
+        let rows = sqlx::query!(
+            r#"
+            SELECT l.id, l.author_id, l.author_username AS "author_username?", l.title, l.slug,
+                   l.content, l.tags, l.visibility AS "visibility: Visibility", l.created_at,
+                   l.updated_at,
+                   ts_rank(l.search, query) AS "rank!",
+                   ts_headline('english', l.content, query, $5) AS "snippet!",
+                   COUNT(*) OVER () AS "total!"
+            FROM post_listings l
+            CROSS JOIN websearch_to_tsquery('english', $1) AS query
+            WHERE l.search @@ query AND l.visibility = 'public'
+              AND ($2::TEXT IS NULL OR $2 = ANY(l.tags))
+            ORDER BY "rank!" DESC, l.created_at DESC, l.id
+            LIMIT $3 OFFSET $4
+            "#,
+            query,
+            tag,
+            limit,
+            offset,
+            headline,
+        )
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to search posts for {:?}: {}", query, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        let total = rows.first().map_or(0, |row| row.total);
+        let hits = rows
+            .into_iter()
+            .map(|row| {
+                let post = Post {
+                    id: row.id,
+                    author_id: row.author_id,
+                    author_username: row.author_username,
+                    title: row.title,
+                    slug: row.slug,
+                    content: row.content,
+                    tags: row.tags,
+                    visibility: row.visibility,
+                    created_at: row.created_at,
+                    updated_at: row.updated_at,
+                    deleted_at: None,
+                };
+                (post, row.rank, row.snippet)
+            })
+            .collect();
+
+        Ok((hits, total))
+    }
+
+    /// Counts the posts matched by `search` regardless of paging.
+    #[instrument(name = "posts.count_search", skip(self))]
+    async fn count_search(&self, query: &str, tag: Option<&str>) -> Result<i64, DomainError> {
+        sqlx::query_scalar!(
+            r#"
+            SELECT COUNT(*) AS "count!"
+            FROM post_listings
+            WHERE search @@ websearch_to_tsquery('english', $1)
+              AND visibility = 'public'
+              AND ($2::TEXT IS NULL OR $2 = ANY(tags))
+            "#,
+            query,
+            tag,
+        )
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to count search results for {:?}: {}", query, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })
+    }
+
+    /// Returns the tags used on the given user's posts with their post counts.
+    #[instrument(name = "posts.tags", skip(self))]
+    async fn tags(&self, author_id: Uuid) -> Result<Vec<Tag>, DomainError> {
+        sqlx::query_as!(
+            Tag,
+            r#"
+            SELECT tag AS "name!", COUNT(*) AS "post_count!"
+            FROM posts, UNNEST(tags) AS tag
+            WHERE author_id = $1 AND deleted_at IS NULL
+            GROUP BY tag
+            ORDER BY tag
+            "#,
+            author_id
+        )
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to list tags for author {}: {}", author_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })
+    }
+
+    /// Upserts the post's review queue entry.
+    #[instrument(name = "posts.flag", skip(self, uow, reason))]
+    async fn flag(&self, uow: &mut UnitOfWork, id: Uuid, reason: &str) -> Result<(), DomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO post_flags (post_id, reason, flagged_at)
+            VALUES ($1, $2, now())
+            ON CONFLICT (post_id)
+                DO UPDATE SET reason = EXCLUDED.reason, flagged_at = EXCLUDED.flagged_at
             "#,
         )
-            .bind(author_id)
+            .bind(id)
+            .bind(reason)
+            .execute(uow.conn())
+            .await
+            .map_err(|e| {
+                error!("failed to flag post {}: {}", id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        info!(post_id = %id, "post flagged for review");
+        Ok(())
+    }
+
+    /// Returns a page of flagged posts with their authors' usernames; the
+    /// total is counted with a window function in the same query.
+    #[instrument(name = "posts.list_flagged", skip(self))]
+    async fn list_flagged(
+        &self,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<FlaggedPost>, i64), DomainError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT p.id, p.author_id, u.username AS author_username, p.title, p.slug, p.content,
+                   p.tags, p.visibility, p.created_at, p.updated_at, f.reason, f.flagged_at,
+                   COUNT(*) OVER () AS total
+            FROM post_flags f
+            JOIN posts p ON p.id = f.post_id
+            LEFT JOIN users u ON u.id = p.author_id
+            WHERE p.deleted_at IS NULL
+            ORDER BY f.flagged_at, f.post_id
+            LIMIT $1 OFFSET $2
+            "#,
+        )
+            .bind(limit)
+            .bind(offset)
             .fetch_all(&self.pool)
             .await
             .map_err(|e| {
-                error!("failed to list posts for author {}: {}", author_id, e);
+                error!("failed to list flagged posts: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        let total = match rows.first() {
+            Some(row) => row
+                .try_get("total")
+                .map_err(|e| DomainError::Internal(format!("row decode error: {}", e)))?,
+            None => 0,
+        };
+        let posts = rows.into_iter().map(map_flagged_row).collect::<Result<_, _>>()?;
+
+        Ok((posts, total))
+    }
+
+    /// Counts the posts `list_flagged` pages through.
+    #[instrument(name = "posts.count_flagged", skip(self))]
+    async fn count_flagged(&self) -> Result<i64, DomainError> {
+        sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM post_flags f
+            JOIN posts p ON p.id = f.post_id
+            WHERE p.deleted_at IS NULL
+            "#,
+        )
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to count flagged posts: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })
+    }
+
+    /// Deletes the post's review queue entry.
+    #[instrument(name = "posts.unflag", skip(self))]
+    async fn unflag(&self, id: Uuid) -> Result<(), DomainError> {
+        let result = sqlx::query("DELETE FROM post_flags WHERE post_id = $1")
+            .bind(id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to unflag post {}: {}", id, e);
                 DomainError::Internal(format!("database error: {}", e))
             })?;
 
-        rows.into_iter().map(map_row).collect()
+        if result.rows_affected() == 0 {
+            return Err(DomainError::PostNotFound(format!("flagged post id: {}", id)));
+        }
+
+        info!(post_id = %id, "post flag cleared");
+        Ok(())
     }
 }
 
-/// Maps a database row to a post domain model.
-fn map_row(row: PgRow) -> Result<Post, DomainError> {
+/// Maps a review queue row to a flagged post.
+fn map_flagged_row(row: PgRow) -> Result<FlaggedPost, DomainError> {
     let decode_err = |e: sqlx::Error| {
         DomainError::Internal(format!("row decode error: {}", e))
     };
 
-    Ok(Post {
+    let post = Post {
         id: row.try_get("id").map_err(decode_err)?,
         author_id: row.try_get("author_id").map_err(decode_err)?,
+        author_username: row.try_get("author_username").map_err(decode_err)?,
         title: row.try_get("title").map_err(decode_err)?,
+        slug: row.try_get("slug").map_err(decode_err)?,
         content: row.try_get("content").map_err(decode_err)?,
+        tags: row.try_get("tags").map_err(decode_err)?,
+        visibility: row.try_get("visibility").map_err(decode_err)?,
         created_at: row.try_get("created_at").map_err(decode_err)?,
-        deleted_at: row.try_get("deleted_at").map_err(decode_err)?,
+        updated_at: row.try_get("updated_at").map_err(decode_err)?,
+        deleted_at: None,
+    };
+
+    Ok(FlaggedPost {
+        post,
+        reason: row.try_get("reason").map_err(decode_err)?,
+        flagged_at: row.try_get("flagged_at").map_err(decode_err)?,
     })
 }
+
+/// Brings the `post_listings` row of a post in line with the post: copies
+/// a live post with its author's username, and drops a trashed one.
+///
+/// Runs on the connection of the write it follows, so the read model
+/// changes in the same transaction as the post.
+async fn refresh_listing(conn: &mut PgConnection, id: Uuid) -> Result<(), DomainError> {
+    let refresh_err = |e: sqlx::Error| {
+        error!("failed to refresh listing of post {}: {}", id, e);
+        DomainError::Internal(format!("database error: {}", e))
+    };
+
+    sqlx::query("DELETE FROM post_listings WHERE id = $1")
+        .bind(id)
+        .execute(&mut *conn)
+        .await
+        .map_err(refresh_err)?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO post_listings (id, author_id, author_username, title, slug, content, tags,
+                                   visibility, created_at, updated_at)
+        SELECT p.id, p.author_id, u.username, p.title, p.slug, p.content, p.tags, p.visibility,
+               p.created_at, p.updated_at
+        FROM posts p
+        LEFT JOIN users u ON u.id = p.author_id
+        WHERE p.id = $1 AND p.deleted_at IS NULL
+        "#,
+    )
+        .bind(id)
+        .execute(conn)
+        .await
+        .map_err(refresh_err)?;
+
+    Ok(())
+}
+
+/// Updates the author username copied into the `post_listings` rows of
+/// an author's posts.
+pub(crate) async fn rename_listing_author(
+    conn: &mut PgConnection,
+    author_id: Uuid,
+    username: &str,
+) -> Result<(), DomainError> {
+    sqlx::query("UPDATE post_listings SET author_username = $2 WHERE author_id = $1")
+        .bind(author_id)
+        .bind(username)
+        .execute(conn)
+        .await
+        .map_err(|e| {
+            error!("failed to rename author {} in post listings: {}", author_id, e);
+            DomainError::Internal(format!("database error: {}", e))
+        })?;
+
+    Ok(())
+}
@@ -0,0 +1,250 @@
+use crate::domain::error::DomainError;
+use crate::domain::post::{Post, Visibility};
+use crate::domain::reaction::Reactions;
+use async_trait::async_trait;
+use sqlx::{PgPool, Row};
+use tracing::{error, info, instrument};
+use uuid::Uuid;
+
+/// Like and bookmark persistence abstraction.
+///
+/// Adding an existing like or bookmark, or removing a missing one, is a no-op.
+#[async_trait]
+pub trait ReactionRepository: Send + Sync {
+    /// Records that a user likes a post.
+    async fn add_like(&self, post_id: Uuid, user_id: Uuid) -> Result<(), DomainError>;
+
+    /// Removes a user's like from a post.
+    async fn remove_like(&self, post_id: Uuid, user_id: Uuid) -> Result<(), DomainError>;
+
+    /// Records that a user bookmarked a post.
+    async fn add_bookmark(&self, post_id: Uuid, user_id: Uuid) -> Result<(), DomainError>;
+
+    /// Removes a user's bookmark of a post.
+    async fn remove_bookmark(&self, post_id: Uuid, user_id: Uuid) -> Result<(), DomainError>;
+
+    /// Returns the like and bookmark counts of a post and whether `user_id` reacted.
+    async fn reactions(&self, post_id: Uuid, user_id: Uuid) -> Result<Reactions, DomainError>;
+
+    /// Returns a page of posts bookmarked by a user, most recently bookmarked
+    /// first, with the number of bookmarks; the count is zero when the page is empty.
+    async fn list_bookmarks(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Post>, i64), DomainError>;
+
+    /// Counts the posts `list_bookmarks` pages through.
+    async fn count_bookmarks(&self, user_id: Uuid) -> Result<i64, DomainError>;
+}
+
+/// PostgreSQL-backed reaction repository implementation.
+#[derive(Clone)]
+pub struct PostgresReactionRepository {
+    pool: PgPool,
+}
+
+impl PostgresReactionRepository {
+    /// Creates a new PostgreSQL reaction repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    /// Runs a statement binding `post_id` as `$1` and `user_id` as `$2`.
+    async fn execute(
+        &self,
+        sql: &str,
+        post_id: Uuid,
+        user_id: Uuid,
+        action: &str,
+    ) -> Result<(), DomainError> {
+        sqlx::query(sql)
+            .bind(post_id)
+            .bind(user_id)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to {} post {}: {}", action, post_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        info!(post_id = %post_id, user_id = %user_id, action, "reaction changed");
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl ReactionRepository for PostgresReactionRepository {
+    /// Inserts a like, ignoring duplicates.
+    #[instrument(name = "reactions.add_like", skip(self))]
+    async fn add_like(&self, post_id: Uuid, user_id: Uuid) -> Result<(), DomainError> {
+        self.execute(
+            r#"
+            INSERT INTO post_likes (post_id, user_id, created_at)
+            VALUES ($1, $2, now())
+            ON CONFLICT DO NOTHING
+            "#,
+            post_id,
+            user_id,
+            "like",
+        )
+            .await
+    }
+
+    /// Deletes a like, if present.
+    #[instrument(name = "reactions.remove_like", skip(self))]
+    async fn remove_like(&self, post_id: Uuid, user_id: Uuid) -> Result<(), DomainError> {
+        self.execute(
+            r#"
+            DELETE FROM post_likes
+            WHERE post_id = $1 AND user_id = $2
+            "#,
+            post_id,
+            user_id,
+            "unlike",
+        )
+            .await
+    }
+
+    /// Inserts a bookmark, ignoring duplicates.
+    #[instrument(name = "reactions.add_bookmark", skip(self))]
+    async fn add_bookmark(&self, post_id: Uuid, user_id: Uuid) -> Result<(), DomainError> {
+        self.execute(
+            r#"
+            INSERT INTO bookmarks (post_id, user_id, created_at)
+            VALUES ($1, $2, now())
+            ON CONFLICT DO NOTHING
+            "#,
+            post_id,
+            user_id,
+            "bookmark",
+        )
+            .await
+    }
+
+    /// Deletes a bookmark, if present.
+    #[instrument(name = "reactions.remove_bookmark", skip(self))]
+    async fn remove_bookmark(&self, post_id: Uuid, user_id: Uuid) -> Result<(), DomainError> {
+        self.execute(
+            r#"
+            DELETE FROM bookmarks
+            WHERE post_id = $1 AND user_id = $2
+            "#,
+            post_id,
+            user_id,
+            "unbookmark",
+        )
+            .await
+    }
+
+    /// Counts likes and bookmarks of a post in one query.
+    #[instrument(name = "reactions.reactions", skip(self))]
+    async fn reactions(&self, post_id: Uuid, user_id: Uuid) -> Result<Reactions, DomainError> {
+        let row = sqlx::query(
+            r#"
+            SELECT (SELECT COUNT(*) FROM post_likes WHERE post_id = $1) AS likes,
+                   (SELECT COUNT(*) FROM bookmarks WHERE post_id = $1) AS bookmarks,
+                   EXISTS(SELECT 1 FROM post_likes WHERE post_id = $1 AND user_id = $2) AS liked,
+                   EXISTS(SELECT 1 FROM bookmarks WHERE post_id = $1 AND user_id = $2) AS bookmarked
+            "#,
+        )
+            .bind(post_id)
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to count reactions of post {}: {}", post_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        let decode_err = |e: sqlx::Error| {
+            DomainError::Internal(format!("row decode error: {}", e))
+        };
+
+        Ok(Reactions {
+            post_id,
+            likes: row.try_get("likes").map_err(decode_err)?,
+            bookmarks: row.try_get("bookmarks").map_err(decode_err)?,
+            liked: row.try_get("liked").map_err(decode_err)?,
+            bookmarked: row.try_get("bookmarked").map_err(decode_err)?,
+        })
+    }
+
+    /// Lists bookmarked posts that still exist and are readable by the user.
+    ///
+    /// The total is counted with a window function in the same query.
+    #[instrument(name = "reactions.list_bookmarks", skip(self))]
+    async fn list_bookmarks(
+        &self,
+        user_id: Uuid,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<Post>, i64), DomainError> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT p.id, p.author_id, u.username AS "author_username?", p.title, p.slug, p.content,
+                   p.tags, p.visibility AS "visibility: Visibility", p.created_at, p.updated_at,
+                   p.deleted_at,
+                   COUNT(*) OVER () AS "total!"
+            FROM bookmarks b
+            JOIN posts p ON p.id = b.post_id
+            LEFT JOIN users u ON u.id = p.author_id
+            WHERE b.user_id = $1 AND p.deleted_at IS NULL
+              AND (p.visibility <> 'private' OR p.author_id = $1)
+            ORDER BY b.created_at DESC, p.id
+            LIMIT $2 OFFSET $3
+            "#,
+            user_id,
+            limit,
+            offset,
+        )
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to list bookmarks of user {}: {}", user_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        let total = rows.first().map_or(0, |row| row.total);
+        let posts = rows
+            .into_iter()
+            .map(|row| Post {
+                id: row.id,
+                author_id: row.author_id,
+                author_username: row.author_username,
+                title: row.title,
+                slug: row.slug,
+                content: row.content,
+                tags: row.tags,
+                visibility: row.visibility,
+                created_at: row.created_at,
+                updated_at: row.updated_at,
+                deleted_at: row.deleted_at,
+            })
+            .collect();
+
+        Ok((posts, total))
+    }
+
+    /// Counts bookmarked posts that still exist and are readable by the user.
+    #[instrument(name = "reactions.count_bookmarks", skip(self))]
+    async fn count_bookmarks(&self, user_id: Uuid) -> Result<i64, DomainError> {
+        sqlx::query_scalar(
+            r#"
+            SELECT COUNT(*)
+            FROM bookmarks b
+            JOIN posts p ON p.id = b.post_id
+            WHERE b.user_id = $1 AND p.deleted_at IS NULL
+              AND (p.visibility <> 'private' OR p.author_id = $1)
+            "#,
+        )
+            .bind(user_id)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to count bookmarks of user {}: {}", user_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })
+    }
+}
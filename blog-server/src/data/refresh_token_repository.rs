@@ -0,0 +1,152 @@
+use crate::domain::error::DomainError;
+use crate::domain::session::RefreshToken;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::{PgPool, Row};
+use tracing::{error, info, instrument};
+use uuid::Uuid;
+
+/// Refresh token persistence abstraction.
+#[async_trait]
+pub trait RefreshTokenRepository: Send + Sync {
+    /// Stores a newly issued token.
+    async fn create(&self, token: &RefreshToken) -> Result<(), DomainError>;
+
+    /// Revokes an active, unexpired token and returns its user.
+    ///
+    /// Returns `None` for unknown, expired or already used tokens, so two
+    /// concurrent refreshes with the same token cannot both succeed.
+    async fn consume(&self, token_hash: &str) -> Result<Option<Uuid>, DomainError>;
+
+    /// Revokes a token; unknown tokens are ignored.
+    async fn revoke(&self, token_hash: &str) -> Result<(), DomainError>;
+
+    /// Deletes tokens past their expiry; returns how many.
+    async fn purge_expired(&self) -> Result<u64, DomainError>;
+
+    /// Deletes tokens revoked before `before`; returns how many.
+    async fn purge_revoked(&self, before: DateTime<Utc>) -> Result<u64, DomainError>;
+}
+
+/// PostgreSQL-backed refresh token repository implementation.
+#[derive(Clone)]
+pub struct PostgresRefreshTokenRepository {
+    pool: PgPool,
+}
+
+impl PostgresRefreshTokenRepository {
+    /// Creates a new PostgreSQL refresh token repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl RefreshTokenRepository for PostgresRefreshTokenRepository {
+    /// Inserts a refresh token.
+    #[instrument(name = "refresh_tokens.create", skip(self, token), fields(user_id = %token.user_id))]
+    async fn create(&self, token: &RefreshToken) -> Result<(), DomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO refresh_tokens (id, user_id, token_hash, expires_at, created_at)
+            VALUES ($1, $2, $3, $4, $5)
+            "#,
+        )
+            .bind(token.id)
+            .bind(token.user_id)
+            .bind(&token.token_hash)
+            .bind(token.expires_at)
+            .bind(token.created_at)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to store refresh token for user {}: {}", token.user_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        info!(user_id = %token.user_id, token_id = %token.id, "refresh token issued");
+        Ok(())
+    }
+
+    /// Marks an active token as revoked in a single statement.
+    #[instrument(name = "refresh_tokens.consume", skip_all)]
+    async fn consume(&self, token_hash: &str) -> Result<Option<Uuid>, DomainError> {
+        let row = sqlx::query(
+            r#"
+            UPDATE refresh_tokens
+            SET revoked_at = now()
+            WHERE token_hash = $1 AND revoked_at IS NULL AND expires_at > now()
+            RETURNING user_id
+            "#,
+        )
+            .bind(token_hash)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to consume refresh token: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        row.map(|row| row.try_get("user_id"))
+            .transpose()
+            .map_err(|e| DomainError::Internal(format!("row decode error: {}", e)))
+    }
+
+    /// Marks a token as revoked.
+    #[instrument(name = "refresh_tokens.revoke", skip_all)]
+    async fn revoke(&self, token_hash: &str) -> Result<(), DomainError> {
+        sqlx::query(
+            r#"
+            UPDATE refresh_tokens
+            SET revoked_at = now()
+            WHERE token_hash = $1 AND revoked_at IS NULL
+            "#,
+        )
+            .bind(token_hash)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to revoke refresh token: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        Ok(())
+    }
+
+    /// Deletes every expired token, revoked or not.
+    #[instrument(name = "refresh_tokens.purge_expired", skip_all)]
+    async fn purge_expired(&self) -> Result<u64, DomainError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM refresh_tokens WHERE expires_at < now()
+            "#,
+        )
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to purge expired refresh tokens: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        Ok(result.rows_affected())
+    }
+
+    /// Deletes tokens revoked before the cutoff.
+    #[instrument(name = "refresh_tokens.purge_revoked", skip(self))]
+    async fn purge_revoked(&self, before: DateTime<Utc>) -> Result<u64, DomainError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM refresh_tokens WHERE revoked_at < $1
+            "#,
+        )
+            .bind(before)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to purge revoked refresh tokens: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        Ok(result.rows_affected())
+    }
+}
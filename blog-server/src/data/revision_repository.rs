@@ -0,0 +1,133 @@
+use crate::data::unit_of_work::UnitOfWork;
+use crate::domain::error::DomainError;
+use crate::domain::revision::PostRevision;
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use tracing::{error, info, instrument};
+use uuid::Uuid;
+
+/// Post revision persistence abstraction.
+#[async_trait]
+pub trait RevisionRepository: Send + Sync {
+    /// Records a revision as part of the given unit of work.
+    async fn create(
+        &self,
+        uow: &mut UnitOfWork,
+        revision: &PostRevision,
+    ) -> Result<(), DomainError>;
+
+    /// Returns the revisions of a post, newest first.
+    async fn list(&self, post_id: Uuid) -> Result<Vec<PostRevision>, DomainError>;
+
+    /// Returns one revision of a post.
+    async fn get(&self, post_id: Uuid, id: Uuid) -> Result<Option<PostRevision>, DomainError>;
+}
+
+/// PostgreSQL-backed revision repository implementation.
+#[derive(Clone)]
+pub struct PostgresRevisionRepository {
+    pool: PgPool,
+}
+
+impl PostgresRevisionRepository {
+    /// Creates a new PostgreSQL revision repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl RevisionRepository for PostgresRevisionRepository {
+    /// Inserts a revision inside the unit of work's transaction.
+    #[instrument(name = "revisions.create", skip(self, uow, revision), fields(post_id = %revision.post_id))]
+    async fn create(
+        &self,
+        uow: &mut UnitOfWork,
+        revision: &PostRevision,
+    ) -> Result<(), DomainError> {
+        sqlx::query(
+            r#"
+            INSERT INTO post_revisions (id, post_id, title, content, tags, edited_at, created_at)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
+            "#,
+        )
+            .bind(revision.id)
+            .bind(revision.post_id)
+            .bind(&revision.title)
+            .bind(&revision.content)
+            .bind(&revision.tags)
+            .bind(revision.edited_at)
+            .bind(revision.created_at)
+            .execute(uow.conn())
+            .await
+            .map_err(|e| {
+                error!("failed to record revision of post {}: {}", revision.post_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        info!(post_id = %revision.post_id, revision_id = %revision.id, "post revision recorded");
+        Ok(())
+    }
+
+    /// Lists the revisions of a post.
+    #[instrument(name = "revisions.list", skip(self))]
+    async fn list(&self, post_id: Uuid) -> Result<Vec<PostRevision>, DomainError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT id, post_id, title, content, tags, edited_at, created_at
+            FROM post_revisions
+            WHERE post_id = $1
+            ORDER BY created_at DESC, id
+            "#,
+        )
+            .bind(post_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to list revisions of post {}: {}", post_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        rows.into_iter().map(map_row).collect()
+    }
+
+    /// Fetches a revision by ID, scoped to its post.
+    #[instrument(name = "revisions.get", skip(self))]
+    async fn get(&self, post_id: Uuid, id: Uuid) -> Result<Option<PostRevision>, DomainError> {
+        let row = sqlx::query(
+            r#"
+            SELECT id, post_id, title, content, tags, edited_at, created_at
+            FROM post_revisions
+            WHERE post_id = $1 AND id = $2
+            "#,
+        )
+            .bind(post_id)
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to get revision {} of post {}: {}", id, post_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        row.map(map_row).transpose()
+    }
+}
+
+/// Maps a database row to a post revision.
+fn map_row(row: PgRow) -> Result<PostRevision, DomainError> {
+    let decode_err = |e: sqlx::Error| {
+        DomainError::Internal(format!("row decode error: {}", e))
+    };
+
+    Ok(PostRevision {
+        id: row.try_get("id").map_err(decode_err)?,
+        post_id: row.try_get("post_id").map_err(decode_err)?,
+        title: row.try_get("title").map_err(decode_err)?,
+        content: row.try_get("content").map_err(decode_err)?,
+        tags: row.try_get("tags").map_err(decode_err)?,
+        edited_at: row.try_get("edited_at").map_err(decode_err)?,
+        created_at: row.try_get("created_at").map_err(decode_err)?,
+    })
+}
@@ -0,0 +1,206 @@
+use crate::data::post_repository::PostRepository;
+use crate::domain::error::DomainError;
+use crate::domain::post::{Post, Visibility};
+use crate::infrastructure::meilisearch::{MeilisearchClient, PostDocument};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tracing::instrument;
+use uuid::Uuid;
+
+/// Full-text post search abstraction.
+///
+/// Only public, non-deleted posts are ever returned.
+#[async_trait]
+pub trait SearchIndex: Send + Sync {
+    /// Returns whether the index lives outside the database and must be
+    /// told about post changes through [`Self::index`] and [`Self::remove`].
+    fn is_external(&self) -> bool;
+
+    /// Returns a page of posts matching a full-text query, best matches first.
+    ///
+    /// Each result carries its rank and a raw snippet with matches wrapped in
+    /// the highlight markers from [`crate::domain::search`]. The page comes
+    /// with the number of matches, which is zero when the page is empty.
+    async fn search(
+        &self,
+        query: &str,
+        tag: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<(Post, f32, String)>, i64), DomainError>;
+
+    /// Returns how many posts `search` pages through for the same filters.
+    async fn count(&self, query: &str, tag: Option<&str>) -> Result<i64, DomainError>;
+
+    /// Adds or replaces posts in the index; posts that are not public or are
+    /// deleted are removed instead.
+    async fn index(&self, posts: &[Post]) -> Result<(), DomainError>;
+
+    /// Removes a post from the index.
+    async fn remove(&self, post_id: Uuid) -> Result<(), DomainError>;
+
+    /// Removes every post from the index before a rebuild.
+    async fn clear(&self) -> Result<(), DomainError>;
+}
+
+/// Search index backed by the posts table's PostgreSQL full-text column.
+///
+/// The database keeps the column up to date, so index writes are no-ops.
+pub struct PostgresSearchIndex<P>
+where
+    P: PostRepository + 'static,
+{
+    posts: Arc<P>,
+}
+
+impl<P> PostgresSearchIndex<P>
+where
+    P: PostRepository + 'static,
+{
+    /// Creates a search index querying `posts`.
+    pub fn new(posts: Arc<P>) -> Self {
+        Self { posts }
+    }
+}
+
+#[async_trait]
+impl<P> SearchIndex for PostgresSearchIndex<P>
+where
+    P: PostRepository + 'static,
+{
+    fn is_external(&self) -> bool {
+        false
+    }
+
+    async fn search(
+        &self,
+        query: &str,
+        tag: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<(Post, f32, String)>, i64), DomainError> {
+        self.posts.search(query, tag, limit, offset).await
+    }
+
+    async fn count(&self, query: &str, tag: Option<&str>) -> Result<i64, DomainError> {
+        self.posts.count_search(query, tag).await
+    }
+
+    async fn index(&self, _posts: &[Post]) -> Result<(), DomainError> {
+        Ok(())
+    }
+
+    async fn remove(&self, _post_id: Uuid) -> Result<(), DomainError> {
+        Ok(())
+    }
+
+    async fn clear(&self) -> Result<(), DomainError> {
+        Ok(())
+    }
+}
+
+/// Search index kept in a Meilisearch server.
+///
+/// Matches are loaded from `posts`, so posts deleted or hidden since they
+/// were indexed are left out of results.
+pub struct MeilisearchIndex<P>
+where
+    P: PostRepository + 'static,
+{
+    client: MeilisearchClient,
+    posts: Arc<P>,
+}
+
+impl<P> MeilisearchIndex<P>
+where
+    P: PostRepository + 'static,
+{
+    /// Creates a search index kept by `client`, loading matches from `posts`.
+    pub fn new(client: MeilisearchClient, posts: Arc<P>) -> Self {
+        Self { client, posts }
+    }
+}
+
+#[async_trait]
+impl<P> SearchIndex for MeilisearchIndex<P>
+where
+    P: PostRepository + 'static,
+{
+    fn is_external(&self) -> bool {
+        true
+    }
+
+    /// Searches Meilisearch, then loads the matched posts in rank order.
+    #[instrument(name = "search.meilisearch.search", skip(self))]
+    async fn search(
+        &self,
+        query: &str,
+        tag: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<(Post, f32, String)>, i64), DomainError> {
+        let (hits, total) = self.client.search(query, tag, limit, offset).await?;
+        if hits.is_empty() {
+            return Ok((Vec::new(), 0));
+        }
+
+        let ids: Vec<Uuid> = hits.iter().map(|hit| hit.post_id).collect();
+        let mut posts: HashMap<Uuid, Post> = self
+            .posts
+            .get_many(&ids)
+            .await?
+            .into_iter()
+            .filter(is_searchable)
+            .map(|post| (post.id, post))
+            .collect();
+
+        let results = hits
+            .into_iter()
+            .filter_map(|hit| {
+                let post = posts.remove(&hit.post_id)?;
+                Some((post, hit.rank, hit.snippet))
+            })
+            .collect();
+
+        Ok((results, total))
+    }
+
+    async fn count(&self, query: &str, tag: Option<&str>) -> Result<i64, DomainError> {
+        let (_, total) = self.client.search(query, tag, 0, 0).await?;
+        Ok(total)
+    }
+
+    #[instrument(name = "search.meilisearch.index", skip(self, posts), fields(count = posts.len()))]
+    async fn index(&self, posts: &[Post]) -> Result<(), DomainError> {
+        for post in posts.iter().filter(|post| !is_searchable(post)) {
+            self.client.delete_document(post.id).await?;
+        }
+
+        let documents: Vec<PostDocument> = posts
+            .iter()
+            .filter(|post| is_searchable(post))
+            .map(PostDocument::from)
+            .collect();
+        if documents.is_empty() {
+            return Ok(());
+        }
+
+        self.client.add_documents(&documents).await
+    }
+
+    #[instrument(name = "search.meilisearch.remove", skip(self))]
+    async fn remove(&self, post_id: Uuid) -> Result<(), DomainError> {
+        self.client.delete_document(post_id).await
+    }
+
+    #[instrument(name = "search.meilisearch.clear", skip(self))]
+    async fn clear(&self) -> Result<(), DomainError> {
+        self.client.reset().await
+    }
+}
+
+/// Returns whether a post belongs in search results.
+fn is_searchable(post: &Post) -> bool {
+    post.visibility == Visibility::Public && post.deleted_at.is_none()
+}
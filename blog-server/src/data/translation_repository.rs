@@ -0,0 +1,138 @@
+use crate::domain::error::DomainError;
+use crate::domain::translation::PostTranslation;
+use async_trait::async_trait;
+use sqlx::postgres::PgRow;
+use sqlx::{PgPool, Row};
+use tracing::{error, info, instrument};
+use uuid::Uuid;
+
+/// Post translation persistence abstraction.
+#[async_trait]
+pub trait TranslationRepository: Send + Sync {
+    /// Stores a translation, replacing the post's existing one for the same locale.
+    ///
+    /// Returns the stored translation; a replaced one keeps its `created_at`.
+    async fn upsert(&self, translation: &PostTranslation) -> Result<PostTranslation, DomainError>;
+
+    /// Returns the translations of a post, by locale.
+    async fn list(&self, post_id: Uuid) -> Result<Vec<PostTranslation>, DomainError>;
+
+    /// Returns the translations of several posts at once.
+    async fn list_for_posts(&self, post_ids: &[Uuid]) -> Result<Vec<PostTranslation>, DomainError>;
+
+    /// Deletes a post's translation into `locale`.
+    async fn delete(&self, post_id: Uuid, locale: &str) -> Result<(), DomainError>;
+}
+
+/// PostgreSQL-backed translation repository implementation.
+#[derive(Clone)]
+pub struct PostgresTranslationRepository {
+    pool: PgPool,
+}
+
+impl PostgresTranslationRepository {
+    /// Creates a new PostgreSQL translation repository.
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl TranslationRepository for PostgresTranslationRepository {
+    /// Inserts a translation or updates the existing one for its locale.
+    #[instrument(name = "translations.upsert", skip(self, translation), fields(post_id = %translation.post_id, locale = %translation.locale))]
+    async fn upsert(&self, translation: &PostTranslation) -> Result<PostTranslation, DomainError> {
+        let row = sqlx::query(
+            r#"
+            INSERT INTO post_translations (post_id, locale, title, content, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (post_id, locale) DO UPDATE
+            SET title = EXCLUDED.title, content = EXCLUDED.content, updated_at = EXCLUDED.updated_at
+            RETURNING post_id, locale, title, content, created_at, updated_at
+            "#,
+        )
+            .bind(translation.post_id)
+            .bind(&translation.locale)
+            .bind(&translation.title)
+            .bind(&translation.content)
+            .bind(translation.created_at)
+            .bind(translation.updated_at)
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to store translation of post {}: {}", translation.post_id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        info!(post_id = %translation.post_id, locale = %translation.locale, "post translation stored");
+        map_row(row)
+    }
+
+    /// Lists the translations of a post.
+    #[instrument(name = "translations.list", skip(self))]
+    async fn list(&self, post_id: Uuid) -> Result<Vec<PostTranslation>, DomainError> {
+        self.list_for_posts(&[post_id]).await
+    }
+
+    /// Lists the translations of the given posts.
+    #[instrument(name = "translations.list_for_posts", skip(self))]
+    async fn list_for_posts(&self, post_ids: &[Uuid]) -> Result<Vec<PostTranslation>, DomainError> {
+        let rows = sqlx::query(
+            r#"
+            SELECT post_id, locale, title, content, created_at, updated_at
+            FROM post_translations
+            WHERE post_id = ANY($1)
+            ORDER BY post_id, locale
+            "#,
+        )
+            .bind(post_ids)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to list post translations: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        rows.into_iter().map(map_row).collect()
+    }
+
+    /// Deletes one translation of a post.
+    #[instrument(name = "translations.delete", skip(self))]
+    async fn delete(&self, post_id: Uuid, locale: &str) -> Result<(), DomainError> {
+        let result = sqlx::query(
+            r#"
+            DELETE FROM post_translations WHERE post_id = $1 AND locale = $2
+            "#,
+        )
+            .bind(post_id)
+            .bind(locale)
+            .execute(&self.pool)
+            .await
+            .map_err(|e| DomainError::Internal(e.to_string()))?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::PostNotFound(format!(
+                "post {post_id} has no `{locale}` translation"
+            )));
+        }
+
+        info!(post_id = %post_id, locale, "post translation deleted");
+        Ok(())
+    }
+}
+
+/// Maps a database row to a post translation.
+fn map_row(row: PgRow) -> Result<PostTranslation, DomainError> {
+    let decode_err = |e: sqlx::Error| {
+        DomainError::Internal(format!("row decode error: {}", e))
+    };
+
+    Ok(PostTranslation {
+        post_id: row.try_get("post_id").map_err(decode_err)?,
+        locale: row.try_get("locale").map_err(decode_err)?,
+        title: row.try_get("title").map_err(decode_err)?,
+        content: row.try_get("content").map_err(decode_err)?,
+        created_at: row.try_get("created_at").map_err(decode_err)?,
+        updated_at: row.try_get("updated_at").map_err(decode_err)?,
+    })
+}
@@ -0,0 +1,37 @@
+use crate::domain::error::DomainError;
+use sqlx::{PgConnection, PgPool, Postgres, Transaction};
+use tracing::error;
+
+/// Database transaction shared by the repository calls of one operation.
+///
+/// Repository methods taking a unit of work run inside its transaction, so
+/// their changes are applied together on [`UnitOfWork::commit`] and rolled
+/// back if it is dropped without committing.
+pub struct UnitOfWork {
+    tx: Transaction<'static, Postgres>,
+}
+
+impl UnitOfWork {
+    /// Starts a transaction on the given pool.
+    pub async fn begin(pool: &PgPool) -> Result<Self, DomainError> {
+        let tx = pool.begin().await.map_err(|e| {
+            error!("failed to begin transaction: {}", e);
+            DomainError::Internal(format!("database error: {}", e))
+        })?;
+
+        Ok(Self { tx })
+    }
+
+    /// Returns the connection the transaction runs on.
+    pub(crate) fn conn(&mut self) -> &mut PgConnection {
+        &mut self.tx
+    }
+
+    /// Applies every change made through this unit of work.
+    pub async fn commit(self) -> Result<(), DomainError> {
+        self.tx.commit().await.map_err(|e| {
+            error!("failed to commit transaction: {}", e);
+            DomainError::Internal(format!("database error: {}", e))
+        })
+    }
+}
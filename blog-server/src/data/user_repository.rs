@@ -1,24 +1,58 @@
+use crate::data::post_repository::rename_listing_author;
+use crate::data::unit_of_work::UnitOfWork;
 use crate::domain::error::DomainError;
 use async_trait::async_trait;
-use sqlx::{PgPool, Row};
-use tracing::{error, info};
+use sqlx::PgPool;
+use tracing::{error, info, instrument};
 use uuid::Uuid;
 
-use crate::domain::user::User;
+use crate::domain::user::{normalize_email, normalize_username, username_skeleton, Role, User};
 
 /// User persistence abstraction.
 ///
-/// Defines operations for storing and retrieving users.
+/// Defines operations for storing and retrieving users. Usernames and emails
+/// are normalized before they are stored or looked up, and usernames that look
+/// like an existing one are rejected.
 #[async_trait]
 pub trait UserRepository: Send + Sync {
-    /// Persists a new user.
-    async fn create(&self, user: User) -> Result<User, DomainError>;
+    /// Starts a unit of work on the user database.
+    async fn begin(&self) -> Result<UnitOfWork, DomainError>;
+
+    /// Persists a new user as part of the given unit of work.
+    async fn create(&self, uow: &mut UnitOfWork, user: User) -> Result<User, DomainError>;
 
     /// Returns a user by email, if it exists.
     async fn find_by_email(&self, email: &str) -> Result<Option<User>, DomainError>;
 
     /// Returns a user by ID, if it exists.
     async fn find_by_id(&self, id: Uuid) -> Result<Option<User>, DomainError>;
+
+    /// Returns a user by username, if it exists.
+    async fn find_by_username(&self, username: &str) -> Result<Option<User>, DomainError>;
+
+    /// Returns a page of users, oldest first.
+    async fn list(&self, limit: i64, offset: i64) -> Result<Vec<User>, DomainError>;
+
+    /// Marks a user as disabled and returns the updated user.
+    async fn disable(&self, id: Uuid) -> Result<User, DomainError>;
+
+    /// Lifts a user's disabled status and returns the updated user.
+    async fn enable(&self, id: Uuid) -> Result<User, DomainError>;
+
+    /// Changes a user's role and returns the updated user.
+    async fn set_role(&self, id: Uuid, role: Role) -> Result<User, DomainError>;
+
+    /// Saves the username, email and bio of a user and returns the updated user.
+    async fn update_profile(&self, user: &User) -> Result<User, DomainError>;
+
+    /// Replaces the password hash of a user.
+    async fn set_password(&self, id: Uuid, password_hash: &str) -> Result<(), DomainError>;
+
+    /// Sets or clears the media file used as a user's avatar.
+    async fn set_avatar(&self, id: Uuid, media_id: Option<Uuid>) -> Result<(), DomainError>;
+
+    /// Returns the media file used as a user's avatar, if one is set.
+    async fn find_avatar(&self, id: Uuid) -> Result<Option<Uuid>, DomainError>;
 }
 
 /// PostgreSQL-backed user repository implementation.
@@ -32,36 +66,73 @@ impl PostgresUserRepository {
     pub fn new(pool: PgPool) -> Self {
         Self { pool }
     }
+
+    /// Fails if another user's username has the same confusable skeleton.
+    async fn ensure_distinct_username(
+        &self,
+        skeleton: &str,
+        except: Uuid,
+    ) -> Result<(), DomainError> {
+        let taken = sqlx::query_scalar!(
+            r#"
+            SELECT EXISTS(SELECT 1 FROM users WHERE username_skeleton = $1 AND id <> $2) AS "taken!"
+            "#,
+            skeleton,
+            except,
+        )
+            .fetch_one(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to check username skeleton: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        if taken {
+            return Err(DomainError::UsernameTaken(
+                "username is too similar to an existing one".into(),
+            ));
+        }
+        Ok(())
+    }
 }
 
 #[async_trait]
 impl UserRepository for PostgresUserRepository {
+    /// Begins a transaction on the pool.
+    #[instrument(name = "users.begin", skip(self))]
+    async fn begin(&self) -> Result<UnitOfWork, DomainError> {
+        UnitOfWork::begin(&self.pool).await
+    }
+
     /// Inserts a new user into the database.
-    async fn create(&self, user: User) -> Result<User, DomainError> {
-        sqlx::query(
+    #[instrument(name = "users.create", skip(self, uow, user), fields(user_id = %user.id))]
+    async fn create(&self, uow: &mut UnitOfWork, user: User) -> Result<User, DomainError> {
+        let user = User {
+            username: normalize_username(&user.username),
+            email: normalize_email(&user.email),
+            ..user
+        };
+        let skeleton = username_skeleton(&user.username);
+        self.ensure_distinct_username(&skeleton, user.id).await?;
+
+        sqlx::query!(
             r#"
-            INSERT INTO users (id, username, email, password_hash, created_at)
-            VALUES ($1, $2, $3, $4, $5)
+            INSERT INTO users (id, username, email, password_hash, role, created_at, username_skeleton)
+            VALUES ($1, $2, $3, $4, $5, $6, $7)
             "#,
+            user.id,
+            user.username,
+            user.email,
+            user.password_hash,
+            user.role.as_str(),
+            user.created_at,
+            skeleton,
         )
-            .bind(user.id)
-            .bind(&user.username)
-            .bind(&user.email)
-            .bind(&user.password_hash)
-            .bind(&user.created_at)
-            .execute(&self.pool)
+            .execute(uow.conn())
             .await
             .map_err(|e| {
                 error!("failed to create user: {}", e);
-                if e.as_database_error()
-                    .and_then(|db| db.constraint())
-                    .map(|c| c.contains("users_email"))
-                    == Some(true)
-                {
-                    DomainError::Validation("email already registered".into())
-                } else {
-                    DomainError::Internal(format!("database error: {}", e))
-                }
+                map_write_err(e)
             })?;
 
         info!(user_id = %user.id, email = %user.email, "user created");
@@ -69,56 +140,290 @@ impl UserRepository for PostgresUserRepository {
     }
 
     /// Returns a user by email, if present.
+    #[instrument(name = "users.find_by_email", skip(self))]
     async fn find_by_email(&self, email: &str) -> Result<Option<User>, DomainError> {
-        let row = sqlx::query(
+        let email = normalize_email(email);
+        sqlx::query_as!(
+            User,
             r#"
-            SELECT id, username, email, password_hash, created_at, deleted_at
+            SELECT id, username, email, bio, password_hash, role AS "role: Role",
+                   created_at, disabled_at, deleted_at
             FROM users
             WHERE email = $1
             "#,
+            email,
         )
-            .bind(email)
             .fetch_optional(&self.pool)
             .await
             .map_err(|e| {
                 error!("failed to find user by email {}: {}", email, e);
                 DomainError::Internal(format!("database error: {}", e))
-            })?;
-
-        Ok(row.map(|row| User {
-            id: row.get("id"),
-            username: row.get("username"),
-            email: row.get("email"),
-            password_hash: row.get("password_hash"),
-            created_at: row.get("created_at"),
-            deleted_at: row.get("deleted_at"),
-        }))
+            })
     }
 
     /// Returns a user by ID, if present.
+    #[instrument(name = "users.find_by_id", skip(self))]
     async fn find_by_id(&self, id: Uuid) -> Result<Option<User>, DomainError> {
-        let row = sqlx::query(
+        sqlx::query_as!(
+            User,
             r#"
-            SELECT id, username, email, password_hash, created_at, deleted_at
+            SELECT id, username, email, bio, password_hash, role AS "role: Role",
+                   created_at, disabled_at, deleted_at
             FROM users
             WHERE id = $1
             "#,
+            id,
         )
-            .bind(id)
             .fetch_optional(&self.pool)
             .await
             .map_err(|e| {
                 error!("failed to find user by id {}: {}", id, e);
                 DomainError::Internal(format!("database error: {}", e))
+            })
+    }
+
+    /// Returns a user by username, if present.
+    #[instrument(name = "users.find_by_username", skip(self))]
+    async fn find_by_username(&self, username: &str) -> Result<Option<User>, DomainError> {
+        let username = normalize_username(username);
+        sqlx::query_as!(
+            User,
+            r#"
+            SELECT id, username, email, bio, password_hash, role AS "role: Role",
+                   created_at, disabled_at, deleted_at
+            FROM users
+            WHERE username = $1
+            "#,
+            username,
+        )
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to find user by username {}: {}", username, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })
+    }
+
+    /// Returns a page of users ordered by registration time.
+    #[instrument(name = "users.list", skip(self))]
+    async fn list(&self, limit: i64, offset: i64) -> Result<Vec<User>, DomainError> {
+        sqlx::query_as!(
+            User,
+            r#"
+            SELECT id, username, email, bio, password_hash, role AS "role: Role",
+                   created_at, disabled_at, deleted_at
+            FROM users
+            WHERE deleted_at IS NULL
+            ORDER BY created_at, id
+            LIMIT $1 OFFSET $2
+            "#,
+            limit,
+            offset,
+        )
+            .fetch_all(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to list users: {}", e);
+                DomainError::Internal(format!("database error: {}", e))
+            })
+    }
+
+    /// Sets the disabled timestamp, keeping the original one if already disabled.
+    #[instrument(name = "users.disable", skip(self))]
+    async fn disable(&self, id: Uuid) -> Result<User, DomainError> {
+        let updated = sqlx::query_as!(
+            User,
+            r#"
+            UPDATE users SET disabled_at = COALESCE(disabled_at, now())
+            WHERE id = $1 AND deleted_at IS NULL
+            RETURNING id, username, email, bio, password_hash, role AS "role: Role",
+                      created_at, disabled_at, deleted_at
+            "#,
+            id,
+        )
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to disable user {}: {}", id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?
+            .ok_or_else(|| DomainError::UserNotFound(format!("user {}", id)))?;
+
+        info!(user_id = %id, "user disabled");
+        Ok(updated)
+    }
+
+    /// Clears the disabled timestamp.
+    #[instrument(name = "users.enable", skip(self))]
+    async fn enable(&self, id: Uuid) -> Result<User, DomainError> {
+        let updated = sqlx::query_as!(
+            User,
+            r#"
+            UPDATE users SET disabled_at = NULL
+            WHERE id = $1 AND deleted_at IS NULL
+            RETURNING id, username, email, bio, password_hash, role AS "role: Role",
+                      created_at, disabled_at, deleted_at
+            "#,
+            id,
+        )
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to enable user {}: {}", id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?
+            .ok_or_else(|| DomainError::UserNotFound(format!("user {}", id)))?;
+
+        info!(user_id = %id, "user enabled");
+        Ok(updated)
+    }
+
+    /// Updates the role of a user.
+    #[instrument(name = "users.set_role", skip(self))]
+    async fn set_role(&self, id: Uuid, role: Role) -> Result<User, DomainError> {
+        let updated = sqlx::query_as!(
+            User,
+            r#"
+            UPDATE users SET role = $2
+            WHERE id = $1 AND deleted_at IS NULL
+            RETURNING id, username, email, bio, password_hash, role AS "role: Role",
+                      created_at, disabled_at, deleted_at
+            "#,
+            id,
+            role.as_str(),
+        )
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to set role of user {}: {}", id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?
+            .ok_or_else(|| DomainError::UserNotFound(format!("user {}", id)))?;
+
+        info!(user_id = %id, role = %role, "user role changed");
+        Ok(updated)
+    }
+
+    /// Updates the profile fields of a user, and the username shown on their
+    /// post listings with them.
+    #[instrument(name = "users.update_profile", skip(self, user), fields(user_id = %user.id))]
+    async fn update_profile(&self, user: &User) -> Result<User, DomainError> {
+        let username = normalize_username(&user.username);
+        let skeleton = username_skeleton(&username);
+        self.ensure_distinct_username(&skeleton, user.id).await?;
+
+        let mut uow = self.begin().await?;
+        let updated = sqlx::query_as!(
+            User,
+            r#"
+            UPDATE users SET username = $2, email = $3, bio = $4, username_skeleton = $5
+            WHERE id = $1 AND deleted_at IS NULL
+            RETURNING id, username, email, bio, password_hash, role AS "role: Role",
+                      created_at, disabled_at, deleted_at
+            "#,
+            user.id,
+            username,
+            normalize_email(&user.email),
+            user.bio,
+            skeleton,
+        )
+            .fetch_optional(uow.conn())
+            .await
+            .map_err(|e| {
+                error!("failed to update profile of user {}: {}", user.id, e);
+                map_write_err(e)
+            })?
+            .ok_or_else(|| DomainError::UserNotFound(format!("user {}", user.id)))?;
+        rename_listing_author(uow.conn(), updated.id, &updated.username).await?;
+        uow.commit().await?;
+
+        info!(user_id = %user.id, "user profile updated");
+        Ok(updated)
+    }
+
+    /// Stores a new password hash.
+    #[instrument(name = "users.set_password", skip(self, password_hash))]
+    async fn set_password(&self, id: Uuid, password_hash: &str) -> Result<(), DomainError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE users SET password_hash = $2
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+            id,
+            password_hash,
+        )
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to set password of user {}: {}", id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::UserNotFound(format!("user {}", id)));
+        }
+
+        info!(user_id = %id, "user password changed");
+        Ok(())
+    }
+
+    /// Stores the avatar media ID of a user.
+    #[instrument(name = "users.set_avatar", skip(self))]
+    async fn set_avatar(&self, id: Uuid, media_id: Option<Uuid>) -> Result<(), DomainError> {
+        let result = sqlx::query!(
+            r#"
+            UPDATE users SET avatar_id = $2
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+            id,
+            media_id,
+        )
+            .execute(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to set avatar of user {}: {}", id, e);
+                DomainError::Internal(format!("database error: {}", e))
+            })?;
+
+        if result.rows_affected() == 0 {
+            return Err(DomainError::UserNotFound(format!("user {}", id)));
+        }
+
+        info!(user_id = %id, "user avatar changed");
+        Ok(())
+    }
+
+    /// Returns the avatar media ID of a user, if set.
+    #[instrument(name = "users.find_avatar", skip(self))]
+    async fn find_avatar(&self, id: Uuid) -> Result<Option<Uuid>, DomainError> {
+        let avatar = sqlx::query_scalar!(
+            r#"
+            SELECT avatar_id
+            FROM users
+            WHERE id = $1 AND deleted_at IS NULL
+            "#,
+            id,
+        )
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(|e| {
+                error!("failed to find avatar of user {}: {}", id, e);
+                DomainError::Internal(format!("database error: {}", e))
             })?;
 
-        Ok(row.map(|row| User {
-            id: row.get("id"),
-            username: row.get("username"),
-            email: row.get("email"),
-            password_hash: row.get("password_hash"),
-            created_at: row.get("created_at"),
-            deleted_at: row.get("deleted_at"),
-        }))
+        Ok(avatar.flatten())
+    }
+}
+
+/// Maps a unique constraint violation on users to the matching "taken" error.
+fn map_write_err(e: sqlx::Error) -> DomainError {
+    match e.as_database_error().and_then(|db| db.constraint()) {
+        Some(c) if c.contains("users_email") => {
+            DomainError::EmailTaken("email already registered".into())
+        }
+        Some(c) if c.contains("users_username") => {
+            DomainError::UsernameTaken("username already taken".into())
+        }
+        _ => DomainError::Internal(format!("database error: {}", e)),
     }
 }
@@ -0,0 +1,84 @@
+use chrono::{DateTime, SubsecRound, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::event::{BlogEvent, EventKind};
+
+/// What a user did.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "snake_case")]
+#[sqlx(type_name = "varchar", rename_all = "snake_case")]
+pub enum ActivityKind {
+    /// Published a post.
+    PostPublished,
+
+    /// Commented on a post.
+    Commented,
+}
+
+/// Entry of a user's activity history.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Activity {
+    /// Unique activity identifier.
+    pub id: Uuid,
+
+    /// Identifier of the user who acted.
+    pub actor_id: Uuid,
+
+    /// What the user did.
+    pub kind: ActivityKind,
+
+    /// Identifier of the post acted on.
+    pub post_id: Uuid,
+
+    /// Title of the post, filled when listing.
+    pub post_title: Option<String>,
+
+    /// Identifier of the comment, for comments.
+    pub comment_id: Option<Uuid>,
+
+    /// Identifier of the author of the post acted on.
+    pub post_author_id: Uuid,
+
+    /// When it happened.
+    pub created_at: DateTime<Utc>,
+}
+
+impl Activity {
+    /// Returns the activity an event records, if it records one.
+    ///
+    /// Comments are recorded once published, so held ones only appear after
+    /// moderators approve them.
+    pub(crate) fn from_event(event: &BlogEvent) -> Option<Self> {
+        let (actor_id, kind, post_id, comment_id, post_author_id) = match event.kind {
+            EventKind::PostCreated {
+                post_id, author_id, ..
+            } => (author_id, ActivityKind::PostPublished, post_id, None, author_id),
+            EventKind::CommentAdded {
+                comment_id,
+                post_id,
+                post_author_id,
+                author_id,
+                ..
+            } => (
+                author_id,
+                ActivityKind::Commented,
+                post_id,
+                Some(comment_id),
+                post_author_id,
+            ),
+            _ => return None,
+        };
+
+        Some(Self {
+            id: Uuid::new_v4(),
+            actor_id,
+            kind,
+            post_id,
+            post_title: None,
+            comment_id,
+            post_author_id,
+            created_at: event.at.trunc_subsecs(6),
+        })
+    }
+}
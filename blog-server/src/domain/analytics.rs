@@ -0,0 +1,163 @@
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Longest referrer domain kept; longer ones are cut.
+pub const MAX_REFERRER_LEN: usize = 255;
+
+/// User-agent substrings that mark automated clients, lowercase.
+const BOT_MARKERS: [&str; 9] = [
+    "bot",
+    "crawl",
+    "spider",
+    "slurp",
+    "curl",
+    "wget",
+    "python",
+    "headless",
+    "http-client",
+];
+
+/// User-agent substrings that mark phones and tablets, lowercase.
+const MOBILE_MARKERS: [&str; 5] = ["mobi", "android", "iphone", "ipad", "tablet"];
+
+/// Coarse kind of client a post was read with, told from its `User-Agent`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+pub enum AgentClass {
+    /// Desktop browser.
+    Desktop,
+
+    /// Phone or tablet browser.
+    Mobile,
+
+    /// Crawler, feed reader or script.
+    Bot,
+
+    /// Anything else, including requests without a `User-Agent`.
+    Other,
+}
+
+impl AgentClass {
+    /// Classifies a `User-Agent` header value.
+    pub fn classify(user_agent: Option<&str>) -> Self {
+        let Some(agent) = user_agent.map(str::to_ascii_lowercase) else {
+            return AgentClass::Other;
+        };
+
+        if BOT_MARKERS.iter().any(|marker| agent.contains(marker)) {
+            AgentClass::Bot
+        } else if MOBILE_MARKERS.iter().any(|marker| agent.contains(marker)) {
+            AgentClass::Mobile
+        } else if agent.starts_with("mozilla/") {
+            AgentClass::Desktop
+        } else {
+            AgentClass::Other
+        }
+    }
+
+    /// Returns the class as stored in the database.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AgentClass::Desktop => "desktop",
+            AgentClass::Mobile => "mobile",
+            AgentClass::Bot => "bot",
+            AgentClass::Other => "other",
+        }
+    }
+}
+
+/// Where a read of a post came from.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ViewSource {
+    /// Domain of the referring page, without `www.`; `None` for direct visits.
+    pub referrer: Option<String>,
+
+    /// Kind of client the post was read with.
+    pub agent: AgentClass,
+}
+
+/// Number of reads of a post counted on one day from one source.
+#[derive(Debug, Clone)]
+pub struct ViewCount {
+    /// Post that was read.
+    pub post_id: Uuid,
+
+    /// Day the reads happened on, in UTC.
+    pub day: NaiveDate,
+
+    /// Where the reads came from.
+    pub source: ViewSource,
+
+    /// Number of reads.
+    pub views: i64,
+}
+
+/// Number of times a post was read on one day.
+#[derive(Debug, Clone, Copy)]
+pub struct DailyViews {
+    /// Day the views were counted on, in UTC.
+    pub day: NaiveDate,
+
+    /// Number of reads that day.
+    pub views: i64,
+}
+
+/// Reads, reactions and comments a post received over a period.
+#[derive(Debug, Clone)]
+pub struct PostStats {
+    /// Post the counts belong to.
+    pub post_id: Uuid,
+
+    /// Post title.
+    pub title: String,
+
+    /// Number of reads over the period.
+    pub views: i64,
+
+    /// Number of likes given over the period.
+    pub likes: i64,
+
+    /// Number of bookmarks added over the period.
+    pub bookmarks: i64,
+
+    /// Number of published comments written over the period.
+    pub comments: i64,
+
+    /// Reads per day, oldest first, with a zero for each day without any.
+    pub daily_views: Vec<DailyViews>,
+
+    /// Reads by referrer and kind of client; only loaded for a single post.
+    pub traffic: Option<PostTraffic>,
+}
+
+/// Number of reads a post got from one referring domain over a period.
+#[derive(Debug, Clone)]
+pub struct ReferrerViews {
+    /// Referring domain; `None` for direct visits and unknown referrers.
+    pub referrer: Option<String>,
+
+    /// Number of reads.
+    pub views: i64,
+}
+
+/// Number of reads a post got from one kind of client over a period.
+#[derive(Debug, Clone, Copy)]
+pub struct AgentViews {
+    /// Kind of client.
+    pub agent: AgentClass,
+
+    /// Number of reads.
+    pub views: i64,
+}
+
+/// Where a post's readers came from over a period.
+#[derive(Debug, Clone, Default)]
+pub struct PostTraffic {
+    /// Reads per referring domain, most first.
+    pub referrers: Vec<ReferrerViews>,
+
+    /// Reads per kind of client, most first.
+    pub agents: Vec<AgentViews>,
+}
@@ -0,0 +1,63 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::user::User;
+
+/// Version of the backup archive layout; restore refuses other versions.
+pub const BACKUP_FORMAT: u32 = 1;
+
+/// Archive path of the backup manifest, the first file of every backup.
+pub const MANIFEST_PATH: &str = "manifest.json";
+
+/// Archive path of the backed-up users.
+pub const USERS_PATH: &str = "users.json";
+
+/// Archive path of the backed-up posts.
+pub const POSTS_PATH: &str = "posts.json";
+
+/// Archive path of the backed-up comments.
+pub const COMMENTS_PATH: &str = "comments.json";
+
+/// Archive path of the backed-up media metadata.
+pub const MEDIA_PATH: &str = "media.json";
+
+/// Directory of the archive holding media contents, one file per blob
+/// named by its hash.
+pub const BLOBS_DIR: &str = "blobs/";
+
+/// Description of a backup archive and what it holds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    /// Layout version, [`BACKUP_FORMAT`] when written by this server.
+    pub format: u32,
+
+    /// Version of the server that wrote the backup.
+    pub server_version: String,
+
+    /// When the backup was taken.
+    pub created_at: DateTime<Utc>,
+
+    /// Number of users in the backup.
+    pub users: usize,
+
+    /// Number of posts in the backup.
+    pub posts: usize,
+
+    /// Number of comments in the backup.
+    pub comments: usize,
+
+    /// Number of media files in the backup.
+    pub media: usize,
+}
+
+/// User account as stored in a backup.
+#[derive(Serialize, Deserialize)]
+pub struct BackupUser {
+    /// The account, password hash included so users keep logging in.
+    #[serde(flatten)]
+    pub user: User,
+
+    /// Media file used as the user's avatar, if one is set.
+    pub avatar_id: Option<Uuid>,
+}
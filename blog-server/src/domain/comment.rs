@@ -0,0 +1,82 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Whether a comment is shown on its post.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+pub enum CommentStatus {
+    /// Shown on the post.
+    #[default]
+    Published,
+
+    /// Held in the moderation queue as suspected spam.
+    Flagged,
+}
+
+/// Post comment domain model.
+#[derive(Serialize, Deserialize)]
+pub struct Comment {
+    /// Unique comment identifier.
+    pub id: Uuid,
+
+    /// Identifier of the commented post.
+    pub post_id: Uuid,
+
+    /// Identifier of the comment author.
+    pub author_id: Uuid,
+
+    /// Identifier of the comment this one replies to, if any.
+    pub parent_id: Option<Uuid>,
+
+    /// Comment text.
+    pub content: String,
+
+    /// Whether the comment is shown or awaits moderation.
+    pub status: CommentStatus,
+
+    /// Why the comment was flagged, if it was.
+    pub flag_reason: Option<String>,
+
+    /// Comment creation timestamp.
+    pub created_at: DateTime<Utc>,
+
+    /// Comment deletion timestamp, if deleted.
+    pub deleted_at: Option<DateTime<Utc>>,
+}
+
+impl Comment {
+    /// Creates a new comment instance.
+    ///
+    /// Generates a new UUID and sets the creation timestamp.
+    pub(crate) fn new(post_id: Uuid, author_id: Uuid, parent_id: Option<Uuid>, content: String) -> Self {
+        Comment {
+            id: Uuid::new_v4(),
+            post_id,
+            author_id,
+            parent_id,
+            content,
+            status: CommentStatus::default(),
+            flag_reason: None,
+            created_at: Utc::now(),
+            deleted_at: None,
+        }
+    }
+
+    /// Holds the comment for moderation, recording why.
+    pub(crate) fn flag(&mut self, reason: String) {
+        self.status = CommentStatus::Flagged;
+        self.flag_reason = Some(reason);
+    }
+}
+
+/// Where a new comment was sent from, as far as the server can tell.
+#[derive(Debug, Clone, Default)]
+pub struct CommentOrigin {
+    /// Client IP address.
+    pub ip: Option<String>,
+
+    /// Client `User-Agent`.
+    pub user_agent: Option<String>,
+}
@@ -1,9 +1,11 @@
 use actix_web::http::StatusCode;
 use actix_web::{HttpResponse, ResponseError};
-use serde::Serialize;
+use blog_core::error::{ErrorBody, ErrorCode};
 use serde_json::json;
 use thiserror::Error;
 
+use crate::presentation::middleware::request_id;
+
 /// Domain-level application errors.
 ///
 /// Used across the domain and automatically mapped
@@ -18,6 +20,14 @@ pub enum DomainError {
     #[error("validation error: {0}")]
     Validation(String),
 
+    /// Email address is already registered to another account.
+    #[error("validation error: {0}")]
+    EmailTaken(String),
+
+    /// Username is taken by, or too similar to, another account's.
+    #[error("validation error: {0}")]
+    UsernameTaken(String),
+
     /// User was not found.
     #[error("user not found: {0}")]
     UserNotFound(String),
@@ -26,20 +36,69 @@ pub enum DomainError {
     #[error("post not found: {0}")]
     PostNotFound(String),
 
+    /// Comment was not found.
+    #[error("comment not found: {0}")]
+    CommentNotFound(String),
+
+    /// Media file was not found.
+    #[error("media not found: {0}")]
+    MediaNotFound(String),
+
+    /// Data export was not found or has expired.
+    #[error("export not found: {0}")]
+    ExportNotFound(String),
+
+    /// Webmention was not found.
+    #[error("mention not found: {0}")]
+    MentionNotFound(String),
+
+    /// Request body exceeds the configured size limit.
+    #[error("payload too large: {0}")]
+    PayloadTooLarge(String),
+
+    /// Client sent too many requests of a kind in a short time.
+    #[error("rate limited: {0}")]
+    RateLimited(String),
+
+    /// User would go over one of their content limits.
+    #[error("quota exceeded: {0}")]
+    QuotaExceeded(String),
+
+    /// Resource changed since the client last read it.
+    #[error("conflict: {0}")]
+    Conflict(String),
+
     /// Authentication or authorization failure.
     #[error("forbidden: {0}")]
     InvalidCredentials(String),
-}
 
-/// HTTP error response body.
-#[derive(Serialize)]
-struct ErrorBody<'a> {
-    /// Human-readable error message.
-    error: &'a str,
+    /// Authenticated user is not allowed to perform the action.
+    #[error("access denied: {0}")]
+    Forbidden(String),
+}
 
-    /// Optional structured error details.
-    #[serde(skip_serializing_if = "Option::is_none")]
-    details: Option<serde_json::Value>,
+impl DomainError {
+    /// Returns the stable, machine-readable code clients can branch on.
+    pub fn code(&self) -> ErrorCode {
+        match self {
+            DomainError::Internal(_) => ErrorCode::Internal,
+            DomainError::Validation(_) => ErrorCode::ValidationFailed,
+            DomainError::EmailTaken(_) => ErrorCode::EmailTaken,
+            DomainError::UsernameTaken(_) => ErrorCode::UsernameTaken,
+            DomainError::UserNotFound(_) => ErrorCode::UserNotFound,
+            DomainError::PostNotFound(_) => ErrorCode::PostNotFound,
+            DomainError::CommentNotFound(_) => ErrorCode::CommentNotFound,
+            DomainError::MediaNotFound(_) => ErrorCode::MediaNotFound,
+            DomainError::ExportNotFound(_) => ErrorCode::ExportNotFound,
+            DomainError::MentionNotFound(_) => ErrorCode::MentionNotFound,
+            DomainError::PayloadTooLarge(_) => ErrorCode::PayloadTooLarge,
+            DomainError::RateLimited(_) => ErrorCode::RateLimited,
+            DomainError::QuotaExceeded(_) => ErrorCode::QuotaExceeded,
+            DomainError::Conflict(_) => ErrorCode::Conflict,
+            DomainError::InvalidCredentials(_) => ErrorCode::InvalidCredentials,
+            DomainError::Forbidden(_) => ErrorCode::Forbidden,
+        }
+    }
 }
 
 impl ResponseError for DomainError {
@@ -47,28 +106,51 @@ impl ResponseError for DomainError {
     fn status_code(&self) -> StatusCode {
         match self {
             DomainError::Validation(_) => StatusCode::BAD_REQUEST,
+            DomainError::EmailTaken(_) => StatusCode::BAD_REQUEST,
+            DomainError::UsernameTaken(_) => StatusCode::BAD_REQUEST,
             DomainError::UserNotFound(_) => StatusCode::NOT_FOUND,
             DomainError::PostNotFound(_) => StatusCode::NOT_FOUND,
+            DomainError::CommentNotFound(_) => StatusCode::NOT_FOUND,
+            DomainError::MediaNotFound(_) => StatusCode::NOT_FOUND,
+            DomainError::ExportNotFound(_) => StatusCode::NOT_FOUND,
+            DomainError::MentionNotFound(_) => StatusCode::NOT_FOUND,
+            DomainError::PayloadTooLarge(_) => StatusCode::PAYLOAD_TOO_LARGE,
+            DomainError::RateLimited(_) => StatusCode::TOO_MANY_REQUESTS,
+            DomainError::QuotaExceeded(_) => StatusCode::FORBIDDEN,
+            DomainError::Conflict(_) => StatusCode::CONFLICT,
             DomainError::InvalidCredentials(_) => StatusCode::UNAUTHORIZED,
+            DomainError::Forbidden(_) => StatusCode::FORBIDDEN,
             DomainError::Internal(_) => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
 
     /// Builds an HTTP JSON error response.
     fn error_response(&self) -> HttpResponse {
-        let message = self.to_string();
         let details = match self {
             DomainError::Validation(msg)
+            | DomainError::EmailTaken(msg)
+            | DomainError::UsernameTaken(msg)
             | DomainError::UserNotFound(msg)
             | DomainError::PostNotFound(msg)
-            | DomainError::InvalidCredentials(msg) => {
+            | DomainError::CommentNotFound(msg)
+            | DomainError::MediaNotFound(msg)
+            | DomainError::ExportNotFound(msg)
+            | DomainError::MentionNotFound(msg)
+            | DomainError::PayloadTooLarge(msg)
+            | DomainError::RateLimited(msg)
+            | DomainError::QuotaExceeded(msg)
+            | DomainError::Conflict(msg)
+            | DomainError::InvalidCredentials(msg)
+            | DomainError::Forbidden(msg) => {
                 Some(json!({ "message": msg }))
             }
             DomainError::Internal(_) => None,
         };
 
         let body = ErrorBody {
-            error: &message,
+            error: self.to_string(),
+            code: self.code(),
+            request_id: request_id::current(),
             details,
         };
 
@@ -0,0 +1,90 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use uuid::Uuid;
+
+use crate::domain::export::ExportStatus;
+
+/// Blog activity event delivered to subscribers.
+#[derive(Debug, Clone, Serialize)]
+pub struct BlogEvent {
+    /// Monotonic event sequence number, unique per server run.
+    pub id: u64,
+
+    /// Event timestamp.
+    pub at: DateTime<Utc>,
+
+    /// What happened.
+    #[serde(flatten)]
+    pub kind: EventKind,
+}
+
+/// Kind of blog activity, serialized with a `type` tag.
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum EventKind {
+    /// A post was created.
+    PostCreated {
+        post_id: Uuid,
+        author_id: Uuid,
+        title: String,
+    },
+
+    /// A post was updated.
+    PostUpdated {
+        post_id: Uuid,
+        author_id: Uuid,
+        title: String,
+    },
+
+    /// A post was deleted.
+    PostDeleted { post_id: Uuid, author_id: Uuid },
+
+    /// A comment was added to a post.
+    CommentAdded {
+        comment_id: Uuid,
+        post_id: Uuid,
+        post_author_id: Uuid,
+        author_id: Uuid,
+        parent_id: Option<Uuid>,
+    },
+
+    /// A requested data export finished, successfully or not.
+    ExportCompleted {
+        export_id: Uuid,
+        user_id: Uuid,
+        status: ExportStatus,
+    },
+}
+
+impl EventKind {
+    /// Returns the event type name used on the wire.
+    pub fn name(&self) -> &'static str {
+        match self {
+            EventKind::PostCreated { .. } => "post_created",
+            EventKind::PostUpdated { .. } => "post_updated",
+            EventKind::PostDeleted { .. } => "post_deleted",
+            EventKind::CommentAdded { .. } => "comment_added",
+            EventKind::ExportCompleted { .. } => "export_completed",
+        }
+    }
+
+    /// Returns whether the user should receive this event.
+    ///
+    /// Users see activity on their own posts and their own comments, and
+    /// their own data exports finishing.
+    pub fn is_visible_to(&self, user_id: Uuid) -> bool {
+        match self {
+            EventKind::PostCreated { author_id, .. }
+            | EventKind::PostUpdated { author_id, .. }
+            | EventKind::PostDeleted { author_id, .. } => *author_id == user_id,
+            EventKind::CommentAdded {
+                post_author_id,
+                author_id,
+                ..
+            } => *post_author_id == user_id || *author_id == user_id,
+            EventKind::ExportCompleted {
+                user_id: owner_id, ..
+            } => *owner_id == user_id,
+        }
+    }
+}
@@ -0,0 +1,114 @@
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::post::{timestamp, Post};
+
+/// How long an export may take before it is given up on and can be requested again.
+const PENDING_EXPORT_TIMEOUT_MINUTES: i64 = 60;
+
+/// Progress of a data export.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+pub enum ExportStatus {
+    /// The archive is being built.
+    #[default]
+    Pending,
+
+    /// The archive is stored and can be downloaded.
+    Ready,
+
+    /// Building the archive failed.
+    Failed,
+}
+
+/// Archive of everything a user stored on the blog, built on request.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DataExport {
+    /// Unique export identifier; also names the stored archive.
+    pub id: Uuid,
+
+    /// Identifier of the exported user.
+    pub user_id: Uuid,
+
+    /// Whether the archive is ready.
+    pub status: ExportStatus,
+
+    /// Archive size, once built.
+    pub size_bytes: Option<i64>,
+
+    /// Why the export failed, if it did.
+    pub error: Option<String>,
+
+    /// When the export was requested.
+    pub created_at: DateTime<Utc>,
+
+    /// When the archive was built or the export failed.
+    pub completed_at: Option<DateTime<Utc>>,
+
+    /// When the export is purged; download links stop working then.
+    pub expires_at: DateTime<Utc>,
+}
+
+impl DataExport {
+    /// Creates a pending export of a user's data.
+    pub(crate) fn new(user_id: Uuid) -> Self {
+        let now = timestamp();
+
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            status: ExportStatus::Pending,
+            size_bytes: None,
+            error: None,
+            created_at: now,
+            completed_at: None,
+            expires_at: now + Duration::minutes(PENDING_EXPORT_TIMEOUT_MINUTES),
+        }
+    }
+
+    /// Marks the archive as built; it is kept for `ttl` from now.
+    pub(crate) fn complete(&mut self, size_bytes: i64, ttl: Duration) {
+        let now = timestamp();
+
+        self.status = ExportStatus::Ready;
+        self.size_bytes = Some(size_bytes);
+        self.completed_at = Some(now);
+        self.expires_at = now + ttl;
+    }
+
+    /// Marks the export as failed, recording why; it is kept for `ttl` from now.
+    pub(crate) fn fail(&mut self, error: String, ttl: Duration) {
+        let now = timestamp();
+
+        self.status = ExportStatus::Failed;
+        self.error = Some(error);
+        self.completed_at = Some(now);
+        self.expires_at = now + ttl;
+    }
+
+    /// Returns whether the archive can be downloaded at `now`.
+    pub fn is_downloadable(&self, now: DateTime<Utc>) -> bool {
+        self.status == ExportStatus::Ready && self.expires_at > now
+    }
+}
+
+/// Renders a post as a Markdown file with its metadata in YAML front matter.
+///
+/// Strings are written as JSON literals, which YAML reads back unchanged.
+pub(crate) fn post_markdown(post: &Post) -> String {
+    let quote = |value: &str| serde_json::to_string(value).unwrap_or_default();
+    let tags: Vec<String> = post.tags.iter().map(|tag| quote(tag)).collect();
+
+    format!(
+        "---\ntitle: {}\nslug: {}\ntags: [{}]\nvisibility: {}\ncreated_at: {}\nupdated_at: {}\n---\n\n{}\n",
+        quote(&post.title),
+        quote(&post.slug),
+        tags.join(", "),
+        serde_json::to_string(&post.visibility).unwrap_or_default(),
+        post.created_at.to_rfc3339(),
+        post.updated_at.to_rfc3339(),
+        post.content.trim_end(),
+    )
+}
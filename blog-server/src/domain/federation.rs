@@ -0,0 +1,31 @@
+use chrono::{DateTime, Utc};
+use uuid::Uuid;
+
+/// Key pair an author's ActivityPub actor signs deliveries with.
+#[derive(Debug, Clone)]
+pub struct ActorKeys {
+    /// Identifier of the author.
+    pub user_id: Uuid,
+
+    /// Public key, as SubjectPublicKeyInfo PEM; published on the actor.
+    pub public_key_pem: String,
+
+    /// Private key, as PKCS#8 PEM.
+    pub private_key_pem: String,
+}
+
+/// Remote ActivityPub actor following an author.
+#[derive(Debug, Clone)]
+pub struct Follower {
+    /// Identifier of the followed author.
+    pub user_id: Uuid,
+
+    /// ID (URL) of the remote actor.
+    pub actor_url: String,
+
+    /// Inbox new posts are delivered to; the server's shared inbox if it has one.
+    pub inbox_url: String,
+
+    /// When the follow was accepted.
+    pub created_at: DateTime<Utc>,
+}
@@ -0,0 +1,161 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::post::Visibility;
+
+/// Blog export format posts can be imported from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ImportFormat {
+    /// WordPress eXtended RSS (WXR) export.
+    Wordpress,
+
+    /// Medium export: the zip of HTML files, or a single post's HTML file.
+    Medium,
+}
+
+impl ImportFormat {
+    /// Returns the format name as accepted by the import endpoint.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ImportFormat::Wordpress => "wordpress",
+            ImportFormat::Medium => "medium",
+        }
+    }
+}
+
+impl fmt::Display for ImportFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Author as named in an export.
+#[derive(Debug, Clone)]
+pub struct SourceAuthor {
+    /// Name posts refer to the author by, e.g. the WordPress login.
+    pub login: String,
+
+    /// Email address, used to find the matching local account.
+    pub email: Option<String>,
+
+    /// Display name.
+    pub display_name: Option<String>,
+}
+
+/// Post as found in an export.
+#[derive(Debug, Clone)]
+pub struct SourcePost {
+    /// Identifier of the post in the export, e.g. its WordPress ID or file name.
+    pub source_id: String,
+
+    /// Post title.
+    pub title: String,
+
+    /// Post body as HTML.
+    pub content: String,
+
+    /// Login of the author, matched against [`SourceAuthor::login`].
+    pub author: Option<String>,
+
+    /// Tags and categories.
+    pub tags: Vec<String>,
+
+    /// Who may read the imported post; drafts stay private.
+    pub visibility: Visibility,
+
+    /// When the post was published, if it was.
+    pub published_at: Option<DateTime<Utc>>,
+}
+
+/// Contents of a parsed export.
+#[derive(Debug, Default)]
+pub struct SourceDocument {
+    /// Authors declared by the export.
+    pub authors: Vec<SourceAuthor>,
+
+    /// Posts to import.
+    pub posts: Vec<SourcePost>,
+
+    /// Entries that are not imported, such as pages and attachments.
+    pub skipped: Vec<SkippedItem>,
+}
+
+/// Outcome of an import.
+#[derive(Debug, Default, Serialize)]
+pub struct ImportSummary {
+    /// How the export's authors were mapped to local accounts.
+    pub authors: Vec<AuthorMapping>,
+
+    /// Posts that were created.
+    pub imported: Vec<ImportedPost>,
+
+    /// Entries that were not imported, with the reason.
+    pub skipped: Vec<SkippedItem>,
+
+    /// Referenced images downloaded into the media store.
+    pub images_imported: usize,
+
+    /// Referenced images that could not be downloaded; posts keep their
+    /// original URLs.
+    pub image_failures: Vec<ImageFailure>,
+
+    /// Anything else worth a look, such as dropped tags.
+    pub warnings: Vec<String>,
+}
+
+/// Local account an export author's posts were assigned to.
+#[derive(Debug, Serialize)]
+pub struct AuthorMapping {
+    /// Author name in the export.
+    pub source: String,
+
+    /// Account the posts were assigned to.
+    pub user_id: Uuid,
+
+    /// Whether an account with the author's email exists; if not, the posts
+    /// went to the default author.
+    pub matched: bool,
+}
+
+/// Post created by an import.
+#[derive(Debug, Serialize)]
+pub struct ImportedPost {
+    /// Identifier of the post in the export.
+    pub source_id: String,
+
+    /// Identifier of the created post.
+    pub post_id: Uuid,
+
+    /// Post title.
+    pub title: String,
+
+    /// Images of the post that were downloaded into the media store.
+    pub images: usize,
+}
+
+/// Export entry that was not imported.
+#[derive(Debug, Serialize)]
+pub struct SkippedItem {
+    /// Identifier of the entry in the export.
+    pub source_id: String,
+
+    /// Entry title, if it has one.
+    pub title: String,
+
+    /// Why it was skipped.
+    pub reason: String,
+}
+
+/// Referenced image that could not be imported.
+#[derive(Debug, Serialize)]
+pub struct ImageFailure {
+    /// Image URL as referenced by the post.
+    pub url: String,
+
+    /// Why it failed.
+    pub reason: String,
+}
@@ -0,0 +1,165 @@
+use std::fmt;
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::post::timestamp;
+
+/// Block of IP addresses in CIDR notation, e.g. `203.0.113.0/24`; a bare
+/// address stands for itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct IpNet {
+    addr: IpAddr,
+    prefix: u8,
+}
+
+impl IpNet {
+    /// Returns whether `ip` is in the block. IPv4-mapped IPv6 addresses
+    /// match IPv4 blocks.
+    pub fn contains(&self, ip: IpAddr) -> bool {
+        let ip = match ip {
+            IpAddr::V6(v6) => v6.to_ipv4_mapped().map(IpAddr::V4).unwrap_or(ip),
+            ip => ip,
+        };
+
+        match (self.addr, ip) {
+            (IpAddr::V4(net), IpAddr::V4(ip)) => {
+                let mask = u32::MAX
+                    .checked_shl(32 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u32::from(net) & mask == u32::from(ip) & mask
+            }
+            (IpAddr::V6(net), IpAddr::V6(ip)) => {
+                let mask = u128::MAX
+                    .checked_shl(128 - u32::from(self.prefix))
+                    .unwrap_or(0);
+                u128::from(net) & mask == u128::from(ip) & mask
+            }
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for IpNet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}", self.addr, self.prefix)
+    }
+}
+
+impl FromStr for IpNet {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || format!("invalid IP address or CIDR block `{s}`");
+        let (addr, prefix) = match s.trim().split_once('/') {
+            Some((addr, prefix)) => (addr, Some(prefix)),
+            None => (s.trim(), None),
+        };
+
+        let addr: IpAddr = addr.parse().map_err(|_| invalid())?;
+        let max = if addr.is_ipv4() { 32 } else { 128 };
+        let prefix = match prefix {
+            Some(prefix) => prefix
+                .parse()
+                .ok()
+                .filter(|p| *p <= max)
+                .ok_or_else(invalid)?,
+            None => max,
+        };
+
+        Ok(Self { addr, prefix })
+    }
+}
+
+impl Serialize for IpNet {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for IpNet {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(serde::de::Error::custom)
+    }
+}
+
+/// What an IP rule does with the addresses it matches.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+pub enum IpRuleAction {
+    /// Let requests through, even from a denied block.
+    Allow,
+
+    /// Refuse requests.
+    Deny,
+}
+
+/// Allow or deny rule for a block of client addresses, added through the
+/// admin API.
+#[derive(Debug, Clone)]
+pub struct IpRule {
+    /// Unique rule identifier.
+    pub id: Uuid,
+
+    /// Addresses the rule matches.
+    pub network: IpNet,
+
+    /// Whether matching requests are let through or refused.
+    pub action: IpRuleAction,
+
+    /// Why the rule was added, for other administrators.
+    pub reason: Option<String>,
+
+    /// Rule creation timestamp.
+    pub created_at: DateTime<Utc>,
+}
+
+impl IpRule {
+    /// Creates a new rule instance.
+    pub(crate) fn new(network: IpNet, action: IpRuleAction, reason: Option<String>) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            network,
+            action,
+            reason,
+            created_at: timestamp(),
+        }
+    }
+}
+
+/// Decides which client addresses may use the server.
+///
+/// An address matching an allow block is let through; otherwise one matching
+/// a deny block is refused, and any other is let through.
+#[derive(Debug, Clone, Default)]
+pub struct IpFilter {
+    allow: Vec<IpNet>,
+    deny: Vec<IpNet>,
+}
+
+impl IpFilter {
+    /// Creates a filter from allow and deny rules.
+    pub fn new(rules: impl IntoIterator<Item = (IpNet, IpRuleAction)>) -> Self {
+        let mut filter = Self::default();
+        for (network, action) in rules {
+            match action {
+                IpRuleAction::Allow => filter.allow.push(network),
+                IpRuleAction::Deny => filter.deny.push(network),
+            }
+        }
+
+        filter
+    }
+
+    /// Returns whether requests from `ip` are let through.
+    pub fn allows(&self, ip: IpAddr) -> bool {
+        self.allow.iter().any(|network| network.contains(ip))
+            || !self.deny.iter().any(|network| network.contains(ip))
+    }
+}
@@ -0,0 +1,248 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::error::DomainError;
+
+/// Most characters kept from an uploaded file name.
+const MAX_FILENAME_LEN: usize = 120;
+
+/// Uploaded media file metadata.
+#[derive(Serialize, Deserialize)]
+pub struct Media {
+    /// Unique media identifier; also names the resized variant files.
+    pub id: Uuid,
+
+    /// Identifier of the uploading user.
+    pub owner_id: Uuid,
+
+    /// Sanitized original file name.
+    pub filename: String,
+
+    /// MIME type detected from the file contents.
+    pub content_type: String,
+
+    /// File size in bytes.
+    pub size_bytes: i64,
+
+    /// Hex SHA-256 of the contents, naming the blob they are stored in.
+    ///
+    /// Identical files share one blob. `None` for files uploaded before
+    /// deduplication, which are stored under the media ID.
+    pub sha256: Option<String>,
+
+    /// Upload timestamp.
+    pub created_at: DateTime<Utc>,
+}
+
+impl Media {
+    /// Creates a new media instance stored in the blob `sha256`.
+    ///
+    /// Generates a new UUID and sets the creation timestamp.
+    pub(crate) fn new(
+        owner_id: Uuid,
+        filename: String,
+        content_type: String,
+        size_bytes: i64,
+        sha256: String,
+    ) -> Self {
+        Media {
+            id: Uuid::new_v4(),
+            owner_id,
+            filename,
+            content_type,
+            size_bytes,
+            sha256: Some(sha256),
+            created_at: Utc::now(),
+        }
+    }
+}
+
+/// Size variant an uploaded image is served in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+pub enum VariantName {
+    /// Small preview for lists, at most 320 pixels on its longer side.
+    Thumbnail,
+
+    /// Inline size for post bodies, at most 1024 pixels on its longer side.
+    Medium,
+
+    /// The file as uploaded.
+    Original,
+}
+
+impl VariantName {
+    /// Every variant, smallest first.
+    pub const ALL: [VariantName; 3] = [
+        VariantName::Thumbnail,
+        VariantName::Medium,
+        VariantName::Original,
+    ];
+
+    /// Returns the variant name as used in URLs and the database.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            VariantName::Thumbnail => "thumbnail",
+            VariantName::Medium => "medium",
+            VariantName::Original => "original",
+        }
+    }
+
+    /// Longest side of the variant in pixels; `None` for the original.
+    pub fn max_dimension(self) -> Option<u32> {
+        match self {
+            VariantName::Thumbnail => Some(320),
+            VariantName::Medium => Some(1024),
+            VariantName::Original => None,
+        }
+    }
+}
+
+impl fmt::Display for VariantName {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for VariantName {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "thumbnail" => Ok(VariantName::Thumbnail),
+            "medium" => Ok(VariantName::Medium),
+            "original" => Ok(VariantName::Original),
+            other => Err(DomainError::MediaNotFound(format!("no `{other}` variant"))),
+        }
+    }
+}
+
+/// Metadata of one size variant of an uploaded image.
+#[derive(Serialize, Deserialize)]
+pub struct MediaVariant {
+    /// Identifier of the media the variant belongs to.
+    pub media_id: Uuid,
+
+    /// Which variant this is.
+    pub name: VariantName,
+
+    /// MIME type of the variant file.
+    pub content_type: String,
+
+    /// Width in pixels.
+    pub width: i32,
+
+    /// Height in pixels.
+    pub height: i32,
+
+    /// File size in bytes.
+    pub size_bytes: i64,
+
+    /// When the variant was generated.
+    pub created_at: DateTime<Utc>,
+}
+
+/// Contents of one variant of a media file, as served.
+#[derive(Debug, Clone)]
+pub struct MediaFile {
+    /// Variant the contents are; the original while the requested one is
+    /// still being generated.
+    pub variant: VariantName,
+
+    /// MIME type of the file.
+    pub content_type: String,
+
+    /// When the file was stored; it never changes afterwards.
+    pub modified: DateTime<Utc>,
+
+    /// File contents.
+    pub bytes: Vec<u8>,
+}
+
+/// Media file being uploaded in chunks, so an interrupted upload can resume.
+#[derive(Debug, Clone)]
+pub struct MediaUpload {
+    /// Unique upload identifier; also names the partial file.
+    pub id: Uuid,
+
+    /// Identifier of the uploading user.
+    pub owner_id: Uuid,
+
+    /// Sanitized original file name.
+    pub filename: String,
+
+    /// Size of the whole file in bytes, announced when the upload started.
+    pub size_bytes: i64,
+
+    /// Number of leading bytes received so far.
+    pub received_bytes: i64,
+
+    /// When the upload started.
+    pub created_at: DateTime<Utc>,
+
+    /// When the last chunk arrived.
+    pub updated_at: DateTime<Utc>,
+}
+
+impl MediaUpload {
+    /// Creates a new upload of `size_bytes` bytes with nothing received yet.
+    pub(crate) fn new(owner_id: Uuid, filename: String, size_bytes: i64) -> Self {
+        let now = Utc::now();
+        MediaUpload {
+            id: Uuid::new_v4(),
+            owner_id,
+            filename,
+            size_bytes,
+            received_bytes: 0,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+
+    /// Returns whether every byte of the file has arrived.
+    pub fn is_complete(&self) -> bool {
+        self.received_bytes >= self.size_bytes
+    }
+}
+
+/// Detects the image type from the leading bytes of a file.
+///
+/// Only raster formats browsers render inline are recognised; SVG is
+/// deliberately absent because it can carry scripts.
+pub(crate) fn sniff_content_type(bytes: &[u8]) -> Option<&'static str> {
+    match bytes {
+        [0x89, b'P', b'N', b'G', 0x0D, 0x0A, 0x1A, 0x0A, ..] => Some("image/png"),
+        [0xFF, 0xD8, 0xFF, ..] => Some("image/jpeg"),
+        [b'G', b'I', b'F', b'8', b'7' | b'9', b'a', ..] => Some("image/gif"),
+        [b'R', b'I', b'F', b'F', _, _, _, _, b'W', b'E', b'B', b'P', ..] => Some("image/webp"),
+        _ => None,
+    }
+}
+
+/// Reduces an uploaded file name to a safe display name.
+///
+/// Directory components are dropped and anything outside ASCII letters,
+/// digits, `.`, `-` and `_` becomes `_`.
+pub(crate) fn sanitize_filename(name: &str) -> String {
+    let base = name.rsplit(['/', '\\']).next().unwrap_or_default();
+    let cleaned: String = base
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | 'A'..='Z' | '0'..='9' | '.' | '-' | '_' => c,
+            _ => '_',
+        })
+        .take(MAX_FILENAME_LEN)
+        .collect();
+    let cleaned = cleaned.trim_start_matches('.');
+
+    if cleaned.is_empty() {
+        "upload".into()
+    } else {
+        cleaned.to_string()
+    }
+}
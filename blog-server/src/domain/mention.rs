@@ -0,0 +1,75 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Whether a received webmention is shown on its post.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+pub enum MentionStatus {
+    /// Received; the source has not been checked yet.
+    #[default]
+    Pending,
+
+    /// The source links to the post; awaits a moderator.
+    Held,
+
+    /// Shown on the post.
+    Published,
+}
+
+/// Webmention received for a post: another site linking to it.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Mention {
+    /// Unique mention identifier.
+    pub id: Uuid,
+
+    /// Identifier of the mentioned post.
+    pub post_id: Uuid,
+
+    /// URL of the page linking to the post.
+    pub source: String,
+
+    /// URL of the post the source links to, as sent.
+    pub target: String,
+
+    /// Whether the mention is verified and shown.
+    pub status: MentionStatus,
+
+    /// Name of the source's author, if the page names one.
+    pub author_name: Option<String>,
+
+    /// URL of the source's author, if the page names one.
+    pub author_url: Option<String>,
+
+    /// Title of the source page, if it has one.
+    pub title: Option<String>,
+
+    /// Start of the source page's text.
+    pub excerpt: Option<String>,
+
+    /// When the mention was first received.
+    pub created_at: DateTime<Utc>,
+
+    /// When the source was last found linking to the post.
+    pub verified_at: Option<DateTime<Utc>>,
+}
+
+impl Mention {
+    /// Creates a new, unverified mention.
+    pub(crate) fn new(post_id: Uuid, source: String, target: String) -> Self {
+        Mention {
+            id: Uuid::new_v4(),
+            post_id,
+            source,
+            target,
+            status: MentionStatus::default(),
+            author_name: None,
+            author_url: None,
+            title: None,
+            excerpt: None,
+            created_at: Utc::now(),
+            verified_at: None,
+        }
+    }
+}
@@ -1,4 +1,26 @@
 //! Domain models and business logic.
+pub mod activity;
+pub mod analytics;
+pub mod backup;
+pub mod comment;
 pub mod error;
+pub mod event;
+pub mod export;
+pub mod federation;
+pub mod import;
+pub mod ip_rule;
+pub mod media;
+pub mod mention;
+pub mod moderation;
+pub mod outbox;
+pub mod page;
 pub mod post;
+pub mod quota;
+pub mod reaction;
+pub mod revision;
+pub mod search;
+pub mod session;
+pub mod tag;
+pub mod translation;
 pub mod user;
+pub mod validation;
@@ -0,0 +1,53 @@
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+use crate::domain::post::Post;
+
+/// Kind of user content a moderator is asked about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ContentKind {
+    /// A post, created or updated.
+    Post,
+
+    /// A new comment.
+    Comment,
+}
+
+/// What a moderator decided about a piece of content.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Verdict {
+    /// The content may be stored as is.
+    Accept,
+
+    /// The content is refused, for the given reason.
+    Reject(String),
+
+    /// The content is stored but goes to the review queue, for the given reason.
+    Flag(String),
+}
+
+impl Verdict {
+    /// Combines two verdicts, keeping the stricter one: a rejection over a
+    /// flag, a flag over an acceptance, and the earlier of two equally strict.
+    pub(crate) fn and(self, other: Verdict) -> Verdict {
+        match (self, other) {
+            (Verdict::Reject(reason), _) => Verdict::Reject(reason),
+            (_, Verdict::Reject(reason)) => Verdict::Reject(reason),
+            (Verdict::Flag(reason), _) => Verdict::Flag(reason),
+            (Verdict::Accept, other) => other,
+        }
+    }
+}
+
+/// Review queue entry: a post a moderator flagged.
+pub struct FlaggedPost {
+    /// Flagged post.
+    pub post: Post,
+
+    /// Why the post was flagged.
+    pub reason: String,
+
+    /// When the post was last flagged.
+    pub flagged_at: DateTime<Utc>,
+}
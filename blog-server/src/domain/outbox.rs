@@ -0,0 +1,78 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Domain event published to the message broker for other services.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum OutboxEvent {
+    /// A user account was registered.
+    UserRegistered { user_id: Uuid, username: String },
+
+    /// A public post was published.
+    PostPublished {
+        post_id: Uuid,
+        author_id: Uuid,
+        title: String,
+        slug: String,
+        tags: Vec<String>,
+    },
+
+    /// A comment was published on a post.
+    CommentCreated {
+        comment_id: Uuid,
+        post_id: Uuid,
+        author_id: Uuid,
+        parent_id: Option<Uuid>,
+        post_author_id: Uuid,
+    },
+}
+
+impl OutboxEvent {
+    /// Returns the event type name used on the wire.
+    pub fn name(&self) -> &'static str {
+        match self {
+            OutboxEvent::UserRegistered { .. } => "user_registered",
+            OutboxEvent::PostPublished { .. } => "post_published",
+            OutboxEvent::CommentCreated { .. } => "comment_created",
+        }
+    }
+
+    /// Returns the ID events are keyed by: the registered user's, or that of
+    /// the post the event concerns.
+    pub fn key(&self) -> Uuid {
+        match self {
+            OutboxEvent::UserRegistered { user_id, .. } => *user_id,
+            OutboxEvent::PostPublished { post_id, .. }
+            | OutboxEvent::CommentCreated { post_id, .. } => *post_id,
+        }
+    }
+}
+
+/// Domain event stored in the outbox until it is published.
+#[derive(Debug, Clone)]
+pub struct OutboxMessage {
+    /// Unique message identifier, also the published event's ID.
+    pub id: Uuid,
+
+    /// The event.
+    pub event: OutboxEvent,
+
+    /// When the change the event describes was made.
+    pub created_at: DateTime<Utc>,
+
+    /// Failed attempts to publish the event so far.
+    pub attempts: i32,
+}
+
+impl OutboxMessage {
+    /// Creates a message for an event that happens now.
+    pub(crate) fn new(event: OutboxEvent) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            event,
+            created_at: Utc::now(),
+            attempts: 0,
+        }
+    }
+}
@@ -0,0 +1,43 @@
+/// One page of a paged listing, with the size of the whole listing.
+#[derive(Debug)]
+pub struct Page<T> {
+    /// Items on this page.
+    pub items: Vec<T>,
+
+    /// Number of items across all pages.
+    pub total: i64,
+
+    /// Page size the items were fetched with.
+    pub limit: u32,
+
+    /// Number of items skipped before this page.
+    pub offset: u32,
+}
+
+impl<T> Page<T> {
+    /// Converts the items, keeping the paging information.
+    pub fn map<U>(self, f: impl FnMut(T) -> U) -> Page<U> {
+        Page {
+            items: self.items.into_iter().map(f).collect(),
+            total: self.total,
+            limit: self.limit,
+            offset: self.offset,
+        }
+    }
+
+    /// Returns the offset of the next page, if there is one.
+    pub fn next_offset(&self) -> Option<u32> {
+        let next = self.offset.saturating_add(self.limit);
+        (i64::from(next) < self.total).then_some(next)
+    }
+
+    /// Returns the offset of the previous page, if there is one.
+    ///
+    /// Past the end of the listing this is the start of the last page.
+    pub fn prev_offset(&self) -> Option<u32> {
+        let limit = i64::from(self.limit.max(1));
+        let last = (self.total - 1).max(0) / limit * limit;
+        let prev = i64::from(self.offset.saturating_sub(self.limit)).min(last);
+        (self.offset > 0).then_some(prev as u32)
+    }
+}
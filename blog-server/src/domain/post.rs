@@ -1,7 +1,34 @@
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, SubsecRound, Utc};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::domain::validation::Violations;
+
+/// Most title characters carried into a slug.
+const MAX_SLUG_TITLE_LEN: usize = 60;
+
+/// Longest accepted post title, in characters.
+const MAX_TITLE_LEN: usize = 200;
+
+/// Longest accepted post content, in characters.
+const MAX_CONTENT_LEN: usize = 100_000;
+
+/// Who may read a post.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+pub enum Visibility {
+    /// Listed in the feed and search, readable by anyone.
+    #[default]
+    Public,
+
+    /// Left out of the feed and search; readable through a share link.
+    Unlisted,
+
+    /// Readable by its author only.
+    Private,
+}
+
 /// Blog post domain model.
 #[derive(Serialize, Deserialize)]
 pub struct Post {
@@ -11,15 +38,30 @@ pub struct Post {
     /// Identifier of the post author.
     pub author_id: Uuid,
 
+    /// Username of the post author, when loaded together with the post.
+    pub author_username: Option<String>,
+
     /// Post title.
     pub title: String,
 
+    /// URL-friendly permalink; fixed at creation so links survive title edits.
+    pub slug: String,
+
     /// Post content.
     pub content: String,
 
+    /// Normalized post tags.
+    pub tags: Vec<String>,
+
+    /// Who may read the post.
+    pub visibility: Visibility,
+
     /// Post creation timestamp.
     pub created_at: DateTime<Utc>,
 
+    /// Last modification timestamp; clients compare it to detect conflicting edits.
+    pub updated_at: DateTime<Utc>,
+
     /// Post deletion timestamp, if deleted.
     pub deleted_at: Option<DateTime<Utc>>,
 }
@@ -28,14 +70,71 @@ impl Post {
     /// Creates a new post instance.
     ///
     /// Generates a new UUID and sets the creation timestamp.
-    pub(crate) fn new(title: String, content: String, author_id: Uuid) -> Self {
+    pub(crate) fn new(title: String, content: String, tags: Vec<String>, author_id: Uuid) -> Self {
+        let id = Uuid::new_v4();
+        let now = timestamp();
+
         Post {
-            id: Uuid::new_v4(),
+            id,
             author_id,
+            author_username: None,
+            slug: slugify(&title, id),
             title,
             content,
-            created_at: Utc::now(),
+            tags,
+            visibility: Visibility::default(),
+            created_at: now,
+            updated_at: now,
             deleted_at: None,
         }
     }
+
+    /// Returns whether `viewer`, or an anonymous reader when `None`, may read
+    /// the post without a share link.
+    pub(crate) fn is_readable_by(&self, viewer: Option<Uuid>) -> bool {
+        self.visibility == Visibility::Public || viewer == Some(self.author_id)
+    }
+}
+
+/// Checks a post's title and content: both must be non-blank and within
+/// their length limits.
+pub(crate) fn validate_post(title: &str, content: &str, violations: &mut Violations) {
+    violations.check(!title.trim().is_empty(), "title must not be empty");
+    violations.check(
+        title.chars().count() <= MAX_TITLE_LEN,
+        format!("title must be at most {MAX_TITLE_LEN} characters long"),
+    );
+    violations.check(!content.trim().is_empty(), "content must not be empty");
+    violations.check(
+        content.chars().count() <= MAX_CONTENT_LEN,
+        format!("content must be at most {MAX_CONTENT_LEN} characters long"),
+    );
+}
+
+/// Returns the current time at the microsecond precision PostgreSQL stores,
+/// so timestamps handed to clients compare equal to what is read back later.
+pub(crate) fn timestamp() -> DateTime<Utc> {
+    Utc::now().trunc_subsecs(6)
+}
+
+/// Builds a post slug from its title and ID.
+///
+/// The title is lowercased and every run of non-alphanumeric characters
+/// becomes a single `-`; the first eight hex digits of the ID keep slugs unique.
+pub(crate) fn slugify(title: &str, id: Uuid) -> String {
+    let mut slug = String::new();
+
+    for c in title.chars().take(MAX_SLUG_TITLE_LEN).flat_map(char::to_lowercase) {
+        if c.is_alphanumeric() {
+            slug.push(c);
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+
+    if !slug.is_empty() && !slug.ends_with('-') {
+        slug.push('-');
+    }
+    slug.push_str(&id.simple().to_string()[..8]);
+    slug
 }
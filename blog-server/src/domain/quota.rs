@@ -0,0 +1,65 @@
+use crate::domain::error::DomainError;
+
+/// Per-user content limits; a zero limit means unlimited.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Quota {
+    /// Most posts a user may have outside the trash.
+    pub max_posts: u64,
+
+    /// Most bytes of uploaded media a user may store.
+    pub max_media_bytes: u64,
+
+    /// Largest post content, in bytes.
+    pub max_post_bytes: u64,
+}
+
+impl Quota {
+    /// Fails if a user who already has `posts` posts may not add another.
+    pub(crate) fn check_posts(&self, posts: i64) -> Result<(), DomainError> {
+        if self.max_posts > 0 && posts as u64 >= self.max_posts {
+            return Err(DomainError::QuotaExceeded(format!(
+                "post limit of {} reached",
+                self.max_posts
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Fails if post content of this size is over the limit.
+    pub(crate) fn check_post_size(&self, content: &str) -> Result<(), DomainError> {
+        if self.max_post_bytes > 0 && content.len() as u64 > self.max_post_bytes {
+            return Err(DomainError::QuotaExceeded(format!(
+                "post content exceeds {} bytes",
+                self.max_post_bytes
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Fails if a user storing `used` bytes of media may not add `adding` more.
+    pub(crate) fn check_media(&self, used: i64, adding: usize) -> Result<(), DomainError> {
+        if self.max_media_bytes > 0 && used as u64 + adding as u64 > self.max_media_bytes {
+            return Err(DomainError::QuotaExceeded(format!(
+                "media storage limit of {} bytes reached",
+                self.max_media_bytes
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+/// A user's usage measured against their quota.
+#[derive(Debug)]
+pub struct QuotaUsage {
+    /// Posts outside the trash.
+    pub posts: i64,
+
+    /// Bytes of uploaded media stored.
+    pub media_bytes: i64,
+
+    /// The limits the usage counts against.
+    pub quota: Quota,
+}
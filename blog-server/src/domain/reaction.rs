@@ -0,0 +1,20 @@
+use uuid::Uuid;
+
+/// Like and bookmark state of a post, as seen by one user.
+#[derive(Debug, Clone)]
+pub struct Reactions {
+    /// Post the counts belong to.
+    pub post_id: Uuid,
+
+    /// Number of users who liked the post.
+    pub likes: i64,
+
+    /// Number of users who bookmarked the post.
+    pub bookmarks: i64,
+
+    /// Whether the requesting user liked the post.
+    pub liked: bool,
+
+    /// Whether the requesting user bookmarked the post.
+    pub bookmarked: bool,
+}
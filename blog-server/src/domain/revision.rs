@@ -0,0 +1,45 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::post::{timestamp, Post};
+
+/// Earlier version of a post, recorded whenever the post is edited.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostRevision {
+    /// Unique revision identifier.
+    pub id: Uuid,
+
+    /// Identifier of the revised post.
+    pub post_id: Uuid,
+
+    /// Title the post had.
+    pub title: String,
+
+    /// Content the post had.
+    pub content: String,
+
+    /// Tags the post had.
+    pub tags: Vec<String>,
+
+    /// When the post was last changed before this revision was replaced.
+    pub edited_at: DateTime<Utc>,
+
+    /// When the revision was replaced by a newer version.
+    pub created_at: DateTime<Utc>,
+}
+
+impl PostRevision {
+    /// Captures the current state of a post before it is edited.
+    pub(crate) fn of(post: &Post) -> Self {
+        Self {
+            id: Uuid::new_v4(),
+            post_id: post.id,
+            title: post.title.clone(),
+            content: post.content.clone(),
+            tags: post.tags.clone(),
+            edited_at: post.updated_at,
+            created_at: timestamp(),
+        }
+    }
+}
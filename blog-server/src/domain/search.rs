@@ -0,0 +1,42 @@
+use crate::domain::post::Post;
+
+/// Marks the start of a matched term in raw search snippets.
+pub(crate) const HIGHLIGHT_START: char = '\u{2}';
+
+/// Marks the end of a matched term in raw search snippets.
+pub(crate) const HIGHLIGHT_END: char = '\u{3}';
+
+/// Post matching a search query.
+pub struct SearchHit {
+    /// Matching post.
+    pub post: Post,
+
+    /// Relevance score; higher is better.
+    pub rank: f32,
+
+    /// Content excerpt around the matches, as HTML with matches in `<mark>`.
+    pub snippet: String,
+}
+
+/// Turns a raw snippet into safe HTML.
+///
+/// The text is HTML-escaped, then the highlight markers become `<mark>` tags,
+/// so user content can never inject markup.
+pub(crate) fn highlight(raw: &str) -> String {
+    let mut html = String::with_capacity(raw.len() + 16);
+
+    for c in raw.chars() {
+        match c {
+            HIGHLIGHT_START => html.push_str("<mark>"),
+            HIGHLIGHT_END => html.push_str("</mark>"),
+            '&' => html.push_str("&amp;"),
+            '<' => html.push_str("&lt;"),
+            '>' => html.push_str("&gt;"),
+            '"' => html.push_str("&quot;"),
+            '\'' => html.push_str("&#39;"),
+            c => html.push(c),
+        }
+    }
+
+    html
+}
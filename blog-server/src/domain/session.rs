@@ -0,0 +1,40 @@
+use chrono::{DateTime, Duration, Utc};
+use uuid::Uuid;
+
+use crate::domain::post::timestamp;
+
+/// Long-lived token a client exchanges for a new access token.
+///
+/// Only a hash of the token is stored; each token can be used once.
+#[derive(Debug, Clone)]
+pub struct RefreshToken {
+    /// Unique token identifier.
+    pub id: Uuid,
+
+    /// User the token was issued to.
+    pub user_id: Uuid,
+
+    /// SHA-256 hash of the token, hex encoded.
+    pub token_hash: String,
+
+    /// When the token stops being accepted.
+    pub expires_at: DateTime<Utc>,
+
+    /// When the token was issued.
+    pub created_at: DateTime<Utc>,
+}
+
+impl RefreshToken {
+    /// Creates a token record for `user_id` valid for `ttl`.
+    pub fn new(user_id: Uuid, token_hash: String, ttl: Duration) -> Self {
+        let created_at = timestamp();
+
+        Self {
+            id: Uuid::new_v4(),
+            user_id,
+            token_hash,
+            expires_at: created_at + ttl,
+            created_at,
+        }
+    }
+}
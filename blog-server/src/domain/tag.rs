@@ -0,0 +1,88 @@
+use crate::domain::error::DomainError;
+
+/// Longest accepted tag name, in characters.
+const MAX_TAG_LEN: usize = 32;
+
+/// Most tags a single post may carry.
+const MAX_TAGS_PER_POST: usize = 16;
+
+/// Tag with the number of posts using it.
+#[derive(Debug)]
+pub struct Tag {
+    /// Normalized tag name.
+    pub name: String,
+
+    /// Number of posts carrying the tag.
+    pub post_count: i64,
+}
+
+/// Normalizes a tag name: trims surrounding whitespace and lowercases it.
+pub(crate) fn normalize_tag(tag: &str) -> String {
+    tag.trim().to_lowercase()
+}
+
+/// Normalizes and validates a post's tag list.
+///
+/// Empty entries and duplicates are dropped; order of first use is kept.
+pub(crate) fn normalize_tags(tags: Vec<String>) -> Result<Vec<String>, DomainError> {
+    let mut normalized: Vec<String> = Vec::with_capacity(tags.len());
+
+    for tag in tags.iter().map(|tag| normalize_tag(tag)) {
+        if tag.is_empty() || normalized.contains(&tag) {
+            continue;
+        }
+
+        if tag.chars().count() > MAX_TAG_LEN {
+            return Err(DomainError::Validation(format!(
+                "tag `{tag}` is longer than {MAX_TAG_LEN} characters"
+            )));
+        }
+
+        if tag.contains(',') {
+            return Err(DomainError::Validation(format!(
+                "tag `{tag}` must not contain commas"
+            )));
+        }
+
+        normalized.push(tag);
+    }
+
+    if normalized.len() > MAX_TAGS_PER_POST {
+        return Err(DomainError::Validation(format!(
+            "a post can have at most {MAX_TAGS_PER_POST} tags"
+        )));
+    }
+
+    Ok(normalized)
+}
+
+/// Normalizes an imported post's tag list, dropping tags instead of failing.
+///
+/// Returns the kept tags and the dropped ones with the reason.
+pub(crate) fn normalize_tags_lenient(tags: Vec<String>) -> (Vec<String>, Vec<String>) {
+    let mut kept: Vec<String> = Vec::with_capacity(tags.len());
+    let mut dropped = Vec::new();
+
+    for tag in tags {
+        match normalize_tags(vec![tag]) {
+            Ok(normalized) => {
+                for tag in normalized {
+                    if kept.contains(&tag) {
+                        continue;
+                    }
+                    if kept.len() == MAX_TAGS_PER_POST {
+                        dropped.push(format!(
+                            "tag `{tag}` is over the limit of {MAX_TAGS_PER_POST} tags"
+                        ));
+                    } else {
+                        kept.push(tag);
+                    }
+                }
+            }
+            Err(DomainError::Validation(reason)) => dropped.push(reason),
+            Err(e) => dropped.push(e.to_string()),
+        }
+    }
+
+    (kept, dropped)
+}
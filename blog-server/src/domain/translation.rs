@@ -0,0 +1,116 @@
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+use crate::domain::error::DomainError;
+use crate::domain::post::{timestamp, Post};
+
+/// Longest accepted locale tag, in characters.
+const MAX_LOCALE_LEN: usize = 35;
+
+/// Title and content of a post in another language.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PostTranslation {
+    /// Identifier of the translated post.
+    pub post_id: Uuid,
+
+    /// Normalized locale tag, e.g. `de` or `pt-br`.
+    pub locale: String,
+
+    /// Translated title.
+    pub title: String,
+
+    /// Translated content.
+    pub content: String,
+
+    /// When the translation was first added.
+    pub created_at: DateTime<Utc>,
+
+    /// When the translation was last changed.
+    pub updated_at: DateTime<Utc>,
+}
+
+impl PostTranslation {
+    /// Creates a translation of a post into `locale`.
+    pub(crate) fn new(post_id: Uuid, locale: String, title: String, content: String) -> Self {
+        let now = timestamp();
+
+        Self {
+            post_id,
+            locale,
+            title,
+            content,
+            created_at: now,
+            updated_at: now,
+        }
+    }
+}
+
+/// Post as served to a reader, in the language picked for them.
+pub struct LocalizedPost {
+    /// The post, with title and content replaced by the picked translation.
+    pub post: Post,
+
+    /// Locale of the title and content.
+    pub locale: String,
+}
+
+/// Normalizes a locale tag: trims it, lowercases it and turns `_` into `-`.
+///
+/// Tags must start with a two- or three-letter language code, optionally
+/// followed by alphanumeric subtags such as a region (`pt-BR`).
+pub(crate) fn normalize_locale(locale: &str) -> Result<String, DomainError> {
+    let locale = locale.trim().to_lowercase().replace('_', "-");
+    let mut subtags = locale.split('-');
+
+    let language_ok = subtags.next().is_some_and(|lang| {
+        (2..=3).contains(&lang.len()) && lang.chars().all(|c| c.is_ascii_lowercase())
+    });
+    let subtags_ok = subtags
+        .all(|tag| (1..=8).contains(&tag.len()) && tag.chars().all(|c| c.is_ascii_alphanumeric()));
+
+    if !language_ok || !subtags_ok || locale.len() > MAX_LOCALE_LEN {
+        return Err(DomainError::Validation(format!(
+            "`{locale}` is not a valid locale"
+        )));
+    }
+
+    Ok(locale)
+}
+
+/// Returns the primary language of a normalized locale, e.g. `pt` for `pt-br`.
+fn language(locale: &str) -> &str {
+    locale.split('-').next().unwrap_or(locale)
+}
+
+/// Picks the translation a reader preferring `preferred` locales, best first,
+/// should get.
+///
+/// Each preference is tried in turn against the post's own `default_locale`
+/// and its translations, first by exact tag, then by primary language. `None`
+/// means the untranslated post, which is also the fallback when nothing matches.
+pub(crate) fn best_translation<'a>(
+    translations: &'a [PostTranslation],
+    preferred: &[String],
+    default_locale: &str,
+) -> Option<&'a PostTranslation> {
+    for wanted in preferred {
+        if *wanted == default_locale {
+            return None;
+        }
+        if let Some(exact) = translations.iter().find(|t| t.locale == *wanted) {
+            return Some(exact);
+        }
+        if language(wanted) == language(default_locale) {
+            return None;
+        }
+        if let Some(related) = translations
+            .iter()
+            .find(|t| language(&t.locale) == language(wanted))
+        {
+            return Some(related);
+        }
+    }
+
+    None
+}
@@ -1,7 +1,65 @@
+use std::fmt;
+use std::str::FromStr;
+
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use unicode_normalization::UnicodeNormalization;
+use unicode_security::skeleton;
 use uuid::Uuid;
 
+use crate::domain::error::DomainError;
+use crate::domain::validation::Violations;
+
+/// Accepted username length, in characters.
+const USERNAME_LEN: std::ops::RangeInclusive<usize> = 3..=32;
+
+/// Longest accepted email address, per RFC 5321.
+const MAX_EMAIL_LEN: usize = 254;
+
+/// Accepted password length, in characters.
+const PASSWORD_LEN: std::ops::RangeInclusive<usize> = 8..=128;
+
+/// User role controlling access to administrative operations.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[serde(rename_all = "lowercase")]
+#[sqlx(type_name = "varchar", rename_all = "lowercase")]
+pub enum Role {
+    /// Regular blog author.
+    #[default]
+    User,
+
+    /// Administrator allowed to manage other users.
+    Admin,
+}
+
+impl Role {
+    /// Returns the role name as stored in the database.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Role::User => "user",
+            Role::Admin => "admin",
+        }
+    }
+}
+
+impl fmt::Display for Role {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for Role {
+    type Err = DomainError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "user" => Ok(Role::User),
+            "admin" => Ok(Role::Admin),
+            other => Err(DomainError::Validation(format!("unknown role `{other}`"))),
+        }
+    }
+}
+
 /// User domain model.
 #[derive(Serialize, Deserialize)]
 pub struct User {
@@ -14,12 +72,21 @@ pub struct User {
     /// User email address.
     pub email: String,
 
+    /// Short self-description shown on the profile, if set.
+    pub bio: Option<String>,
+
     /// Hashed user password.
     pub password_hash: String,
 
+    /// User role.
+    pub role: Role,
+
     /// User creation timestamp.
     pub created_at: DateTime<Utc>,
 
+    /// Time an administrator disabled the account, if disabled.
+    pub disabled_at: Option<DateTime<Utc>>,
+
     /// User deletion timestamp, if deleted.
     pub deleted_at: Option<DateTime<Utc>>,
 }
@@ -33,9 +100,97 @@ impl User {
             id: Uuid::new_v4(),
             username,
             email,
+            bio: None,
             password_hash,
+            role: Role::User,
             created_at: Utc::now(),
+            disabled_at: None,
             deleted_at: None,
         }
     }
+
+    /// Returns whether the account has been disabled.
+    pub fn is_disabled(&self) -> bool {
+        self.disabled_at.is_some()
+    }
+}
+
+/// Returns the canonical form of a username: trimmed, lowercased and NFC-normalized.
+pub(crate) fn normalize_username(username: &str) -> String {
+    username.trim().to_lowercase().nfc().collect()
+}
+
+/// Returns the canonical form of an email address: trimmed, lowercased and
+/// NFC-normalized.
+pub(crate) fn normalize_email(email: &str) -> String {
+    email.trim().to_lowercase().nfc().collect()
+}
+
+/// Returns the confusable skeleton of a normalized username (Unicode TR39).
+///
+/// Usernames that look alike, such as `admin` and `adrnin` or `paul` and
+/// `pau1`, share a skeleton.
+pub(crate) fn username_skeleton(username: &str) -> String {
+    skeleton(username).collect::<String>().to_lowercase()
+}
+
+/// Checks a normalized (trimmed, lowercased) username.
+///
+/// Usernames are 3–32 characters of `a-z`, `0-9`, `_`, `.` and `-`, and
+/// start with a letter or digit.
+pub(crate) fn validate_username(username: &str, violations: &mut Violations) {
+    let len = username.chars().count();
+    violations.check(
+        USERNAME_LEN.contains(&len),
+        format!(
+            "username must be {} to {} characters long",
+            USERNAME_LEN.start(),
+            USERNAME_LEN.end()
+        ),
+    );
+    violations.check(
+        username
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '_' | '.' | '-')),
+        "username may only contain letters, digits, `_`, `.` and `-`",
+    );
+    violations.check(
+        username
+            .chars()
+            .next()
+            .is_none_or(|c| c.is_ascii_alphanumeric()),
+        "username must start with a letter or digit",
+    );
+}
+
+/// Checks the shape of a normalized email address: `local@domain.tld`
+/// without whitespace.
+pub(crate) fn validate_email(email: &str, violations: &mut Violations) {
+    let valid = email.len() <= MAX_EMAIL_LEN
+        && !email.chars().any(char::is_whitespace)
+        && email.split_once('@').is_some_and(|(local, domain)| {
+            !local.is_empty()
+                && !domain.contains('@')
+                && domain.contains('.')
+                && domain.split('.').all(|label| !label.is_empty())
+        });
+    violations.check(valid, "email must be a valid address");
+}
+
+/// Checks a password against the strength policy: 8–128 characters with at
+/// least one letter and one digit.
+pub(crate) fn validate_password(password: &str, violations: &mut Violations) {
+    let len = password.chars().count();
+    violations.check(
+        PASSWORD_LEN.contains(&len),
+        format!(
+            "password must be {} to {} characters long",
+            PASSWORD_LEN.start(),
+            PASSWORD_LEN.end()
+        ),
+    );
+    violations.check(
+        password.chars().any(char::is_alphabetic) && password.chars().any(|c| c.is_ascii_digit()),
+        "password must contain a letter and a digit",
+    );
 }
@@ -0,0 +1,23 @@
+use crate::domain::error::DomainError;
+
+/// Input rules an operation found violated, reported together.
+#[derive(Debug, Default)]
+pub(crate) struct Violations(Vec<String>);
+
+impl Violations {
+    /// Records `rule` as violated unless `ok` holds.
+    pub(crate) fn check(&mut self, ok: bool, rule: impl Into<String>) {
+        if !ok {
+            self.0.push(rule.into());
+        }
+    }
+
+    /// Fails with a validation error listing every violated rule, if any.
+    pub(crate) fn into_result(self) -> Result<(), DomainError> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(DomainError::Validation(self.0.join("; ")))
+        }
+    }
+}
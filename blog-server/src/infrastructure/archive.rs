@@ -0,0 +1,152 @@
+use std::fmt::Display;
+use std::fs::File;
+use std::io::{BufReader, BufWriter, Cursor, Read, Write};
+use std::path::{Path, PathBuf};
+
+use tokio::sync::mpsc;
+use tokio::task::JoinHandle;
+use zip::write::SimpleFileOptions;
+use zip::{CompressionMethod, ZipWriter};
+
+use crate::domain::error::DomainError;
+
+/// File to put into an archive.
+pub struct ArchiveEntry {
+    /// Path of the file inside the archive.
+    pub path: String,
+
+    /// File contents.
+    pub bytes: Vec<u8>,
+
+    /// Whether the contents are already compressed, like images, and are
+    /// stored as they are.
+    pub compressed: bool,
+}
+
+impl ArchiveEntry {
+    /// Serializes `value` as a pretty-printed JSON file of the archive.
+    pub fn json(path: &str, value: &impl serde::Serialize) -> Result<Self, DomainError> {
+        let bytes = serde_json::to_vec_pretty(value)
+            .map_err(|e| DomainError::Internal(format!("archive encoding error: {}", e)))?;
+
+        Ok(Self {
+            path: path.to_string(),
+            bytes,
+            compressed: false,
+        })
+    }
+}
+
+/// Writes `entries` into an in-memory zip archive.
+///
+/// CPU-bound; run it on a blocking thread.
+pub fn write_zip(entries: Vec<ArchiveEntry>) -> Result<Vec<u8>, DomainError> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for entry in entries {
+        let method = if entry.compressed {
+            CompressionMethod::Stored
+        } else {
+            CompressionMethod::Deflated
+        };
+        let options = SimpleFileOptions::default().compression_method(method);
+
+        zip.start_file(entry.path, options).map_err(archive_error)?;
+        zip.write_all(&entry.bytes).map_err(archive_error)?;
+    }
+
+    let cursor = zip.finish().map_err(archive_error)?;
+    Ok(cursor.into_inner())
+}
+
+/// Entries handed to a tar.zst writer or reader before it waits for the
+/// other side; bounds the memory a large archive is streamed through.
+const TAR_CHANNEL_CAPACITY: usize = 8;
+
+/// Streams entries sent to the returned channel into a zstd-compressed tar
+/// archive at `path`, in the order they are sent.
+///
+/// The archive is finished once the sender is dropped; the returned task
+/// then resolves with its size. If writing fails the channel closes early
+/// and the task resolves with the failure.
+pub fn write_tar_zst(path: PathBuf) -> (mpsc::Sender<ArchiveEntry>, JoinHandle<Result<u64, DomainError>>) {
+    let (tx, mut rx) = mpsc::channel::<ArchiveEntry>(TAR_CHANNEL_CAPACITY);
+
+    let task = tokio::task::spawn_blocking(move || {
+        let file = File::create(&path).map_err(archive_error)?;
+        let encoder = zstd::Encoder::new(BufWriter::new(file), 0).map_err(archive_error)?;
+        let mut tar = tar::Builder::new(encoder);
+
+        while let Some(entry) = rx.blocking_recv() {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(entry.bytes.len() as u64);
+            header.set_mode(0o644);
+            tar.append_data(&mut header, &entry.path, entry.bytes.as_slice())
+                .map_err(archive_error)?;
+        }
+
+        let mut out = tar
+            .into_inner()
+            .and_then(|encoder| encoder.finish())
+            .map_err(archive_error)?;
+        out.flush().map_err(archive_error)?;
+
+        let size = std::fs::metadata(&path).map_err(archive_error)?.len();
+        Ok(size)
+    });
+
+    (tx, task)
+}
+
+/// Streams the files of the zstd-compressed tar archive at `path` through
+/// the returned channel, in archive order.
+///
+/// A failure is sent as the last item; directories and links are skipped.
+pub fn read_tar_zst(path: PathBuf) -> mpsc::Receiver<Result<ArchiveEntry, DomainError>> {
+    let (tx, rx) = mpsc::channel(TAR_CHANNEL_CAPACITY);
+
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) = read_entries(&path, &tx) {
+            let _ = tx.blocking_send(Err(e));
+        }
+    });
+
+    rx
+}
+
+/// Sends each file of a tar.zst archive to `tx` until the archive ends or
+/// the receiver goes away.
+fn read_entries(
+    path: &Path,
+    tx: &mpsc::Sender<Result<ArchiveEntry, DomainError>>,
+) -> Result<(), DomainError> {
+    let file = File::open(path).map_err(archive_error)?;
+    let decoder = zstd::Decoder::new(BufReader::new(file)).map_err(archive_error)?;
+    let mut tar = tar::Archive::new(decoder);
+
+    for entry in tar.entries().map_err(archive_error)? {
+        let mut entry = entry.map_err(archive_error)?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+
+        let path = entry.path().map_err(archive_error)?.to_string_lossy().into_owned();
+        let mut bytes = Vec::with_capacity(entry.size() as usize);
+        entry.read_to_end(&mut bytes).map_err(archive_error)?;
+
+        let entry = ArchiveEntry {
+            path,
+            bytes,
+            compressed: false,
+        };
+        if tx.blocking_send(Ok(entry)).is_err() {
+            break;
+        }
+    }
+
+    Ok(())
+}
+
+/// Wraps an archive or I/O failure.
+fn archive_error(e: impl Display) -> DomainError {
+    DomainError::Internal(format!("archive error: {}", e))
+}
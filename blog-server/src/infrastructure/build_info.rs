@@ -0,0 +1,26 @@
+use chrono::{DateTime, Utc};
+
+/// Server crate version.
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Abbreviated git commit the server was built from, or `unknown`.
+pub const GIT_SHA: &str = env!("BLOG_GIT_SHA");
+
+/// Build time in seconds since the Unix epoch, set by the build script.
+const BUILD_UNIX_SECS: &str = env!("BLOG_BUILD_UNIX_SECS");
+
+/// Comma-separated Cargo features the server was built with.
+const FEATURES: &str = env!("BLOG_BUILD_FEATURES");
+
+/// Returns when the server was built.
+pub fn build_time() -> Option<DateTime<Utc>> {
+    BUILD_UNIX_SECS
+        .parse()
+        .ok()
+        .and_then(|secs| DateTime::from_timestamp(secs, 0))
+}
+
+/// Returns the Cargo features the server was built with, sorted.
+pub fn features() -> Vec<&'static str> {
+    FEATURES.split(',').filter(|f| !f.is_empty()).collect()
+}
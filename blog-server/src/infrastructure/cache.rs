@@ -0,0 +1,356 @@
+use std::fmt;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use async_trait::async_trait;
+use moka::Expiry;
+use redis::aio::ConnectionManager;
+use redis::AsyncCommands;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use tracing::warn;
+
+use crate::domain::error::DomainError;
+
+/// Where cached values are kept.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum CacheBackend {
+    /// In this process only; each server instance caches separately.
+    #[default]
+    Memory,
+
+    /// In Redis, shared by every server instance.
+    Redis,
+
+    /// In memory in front of Redis: reads are served locally for a short
+    /// while, everything else goes to Redis.
+    Tiered,
+}
+
+impl fmt::Display for CacheBackend {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CacheBackend::Memory => "memory",
+            CacheBackend::Redis => "redis",
+            CacheBackend::Tiered => "tiered",
+        })
+    }
+}
+
+impl FromStr for CacheBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "memory" => Ok(CacheBackend::Memory),
+            "redis" => Ok(CacheBackend::Redis),
+            "tiered" => Ok(CacheBackend::Tiered),
+            other => Err(format!("unknown cache backend `{other}`")),
+        }
+    }
+}
+
+/// Key-value cache with per-entry expiry.
+///
+/// Callers namespace their keys, e.g. `post:{id}`, and treat errors as
+/// misses: a cache that cannot be reached must not fail the request.
+#[async_trait]
+pub trait Cache: Send + Sync {
+    /// Returns the value stored under `key`, if it has not expired.
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, DomainError>;
+
+    /// Stores `value` under `key` for `ttl`.
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Duration) -> Result<(), DomainError>;
+
+    /// Removes the value stored under `key`.
+    async fn delete(&self, key: &str) -> Result<(), DomainError>;
+
+    /// Adds one to the counter under `key` and returns its new value.
+    ///
+    /// A new counter starts at zero and expires `ttl` after it is created;
+    /// incrementing does not extend it.
+    async fn increment(&self, key: &str, ttl: Duration) -> Result<u64, DomainError>;
+}
+
+impl dyn Cache {
+    /// Returns the JSON value stored under `key`; errors and values that no
+    /// longer decode are logged and reported as misses.
+    pub async fn get_json<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        let bytes = match self.get(key).await {
+            Ok(bytes) => bytes?,
+            Err(e) => {
+                warn!(key, error = %e, "cache read failed");
+                return None;
+            }
+        };
+
+        match serde_json::from_slice(&bytes) {
+            Ok(value) => Some(value),
+            Err(e) => {
+                warn!(key, error = %e, "cached value could not be decoded");
+                None
+            }
+        }
+    }
+
+    /// Stores `value` as JSON under `key` for `ttl`; errors are logged.
+    pub async fn set_json<T: Serialize>(&self, key: &str, value: &T, ttl: Duration) {
+        let bytes = match serde_json::to_vec(value) {
+            Ok(bytes) => bytes,
+            Err(e) => {
+                warn!(key, error = %e, "value could not be encoded for the cache");
+                return;
+            }
+        };
+
+        if let Err(e) = self.set(key, bytes, ttl).await {
+            warn!(key, error = %e, "cache write failed");
+        }
+    }
+
+    /// Removes the value stored under `key`; errors are logged.
+    pub async fn forget(&self, key: &str) {
+        if let Err(e) = self.delete(key).await {
+            warn!(key, error = %e, "cache delete failed");
+        }
+    }
+}
+
+/// A cached value with the time it may be kept.
+#[derive(Clone)]
+struct MemoryEntry {
+    value: Arc<Vec<u8>>,
+    ttl: Duration,
+    counter: bool,
+}
+
+/// Expires memory entries after their own time to live.
+struct EntryExpiry;
+
+impl Expiry<String, MemoryEntry> for EntryExpiry {
+    fn expire_after_create(
+        &self,
+        _key: &String,
+        entry: &MemoryEntry,
+        _created_at: Instant,
+    ) -> Option<Duration> {
+        Some(entry.ttl)
+    }
+
+    fn expire_after_update(
+        &self,
+        _key: &String,
+        entry: &MemoryEntry,
+        _updated_at: Instant,
+        duration_until_expiry: Option<Duration>,
+    ) -> Option<Duration> {
+        // Counters keep the expiry they were created with; replaced values get their own.
+        if entry.counter {
+            duration_until_expiry
+        } else {
+            Some(entry.ttl)
+        }
+    }
+}
+
+/// In-process cache holding up to a fixed number of entries, least
+/// recently used ones evicted first.
+#[derive(Clone)]
+pub struct MemoryCache {
+    entries: moka::future::Cache<String, MemoryEntry>,
+}
+
+impl MemoryCache {
+    /// Creates a cache holding at most `capacity` entries.
+    pub fn new(capacity: u64) -> Self {
+        let entries = moka::future::Cache::builder()
+            .max_capacity(capacity)
+            .expire_after(EntryExpiry)
+            .build();
+
+        Self { entries }
+    }
+}
+
+#[async_trait]
+impl Cache for MemoryCache {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, DomainError> {
+        Ok(self
+            .entries
+            .get(key)
+            .await
+            .map(|entry| entry.value.as_ref().clone()))
+    }
+
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Duration) -> Result<(), DomainError> {
+        let entry = MemoryEntry {
+            value: Arc::new(value),
+            ttl,
+            counter: false,
+        };
+        self.entries.insert(key.to_string(), entry).await;
+
+        Ok(())
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), DomainError> {
+        self.entries.invalidate(key).await;
+
+        Ok(())
+    }
+
+    async fn increment(&self, key: &str, ttl: Duration) -> Result<u64, DomainError> {
+        let entry = self
+            .entries
+            .entry(key.to_string())
+            .and_upsert_with(|existing| {
+                let count = existing
+                    .and_then(|entry| counter_value(&entry.into_value().value))
+                    .unwrap_or(0)
+                    + 1;
+
+                std::future::ready(MemoryEntry {
+                    value: Arc::new(count.to_string().into_bytes()),
+                    ttl,
+                    counter: true,
+                })
+            })
+            .await;
+
+        Ok(counter_value(&entry.into_value().value).unwrap_or(1))
+    }
+}
+
+/// Parses a counter stored as a decimal string, the way Redis stores them.
+fn counter_value(bytes: &[u8]) -> Option<u64> {
+    std::str::from_utf8(bytes).ok()?.parse().ok()
+}
+
+/// Cache stored in Redis, with every key under a common prefix.
+#[derive(Clone)]
+pub struct RedisCache {
+    connection: ConnectionManager,
+    prefix: String,
+}
+
+impl RedisCache {
+    /// Connects to the Redis server at `url`; keys are stored as
+    /// `{prefix}{key}`.
+    ///
+    /// Dropped connections are re-established on the next command.
+    pub async fn connect(url: &str, prefix: String) -> Result<Self, DomainError> {
+        let connect_err = |e: redis::RedisError| {
+            DomainError::Internal(format!("Redis connection error: {}", e))
+        };
+        let client = redis::Client::open(url).map_err(connect_err)?;
+        let connection = ConnectionManager::new(client).await.map_err(connect_err)?;
+
+        Ok(Self { connection, prefix })
+    }
+
+    /// Returns the Redis key of a cache key.
+    fn key(&self, key: &str) -> String {
+        format!("{}{}", self.prefix, key)
+    }
+}
+
+#[async_trait]
+impl Cache for RedisCache {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, DomainError> {
+        let mut connection = self.connection.clone();
+        connection.get(self.key(key)).await.map_err(redis_err)
+    }
+
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Duration) -> Result<(), DomainError> {
+        let mut connection = self.connection.clone();
+        connection
+            .pset_ex(self.key(key), value, millis(ttl))
+            .await
+            .map_err(redis_err)
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), DomainError> {
+        let mut connection = self.connection.clone();
+        connection.del(self.key(key)).await.map_err(redis_err)
+    }
+
+    async fn increment(&self, key: &str, ttl: Duration) -> Result<u64, DomainError> {
+        let key = self.key(key);
+        let mut connection = self.connection.clone();
+
+        let count: u64 = connection.incr(&key, 1).await.map_err(redis_err)?;
+        if count == 1 {
+            let _: () = connection
+                .pexpire(&key, millis(ttl) as i64)
+                .await
+                .map_err(redis_err)?;
+        }
+
+        Ok(count)
+    }
+}
+
+/// Returns a duration in whole milliseconds, at least one, as Redis expects.
+fn millis(duration: Duration) -> u64 {
+    (duration.as_millis() as u64).max(1)
+}
+
+/// Reports a Redis command that failed.
+fn redis_err(e: redis::RedisError) -> DomainError {
+    DomainError::Internal(format!("Redis error: {}", e))
+}
+
+/// Two-level cache: a fast `near` cache, usually in memory, in front of a
+/// shared `far` one, usually Redis.
+///
+/// Values read from or written to the far cache are kept in the near one for
+/// at most `near_ttl`, so a change made by another server instance shows up
+/// here within that time. Counters live only in the far cache, so every
+/// instance counts together.
+pub struct TieredCache {
+    near: Arc<dyn Cache>,
+    far: Arc<dyn Cache>,
+    near_ttl: Duration,
+}
+
+impl TieredCache {
+    /// Creates a cache keeping values of `far` in `near` for up to `near_ttl`.
+    pub fn new(near: Arc<dyn Cache>, far: Arc<dyn Cache>, near_ttl: Duration) -> Self {
+        Self {
+            near,
+            far,
+            near_ttl,
+        }
+    }
+}
+
+#[async_trait]
+impl Cache for TieredCache {
+    async fn get(&self, key: &str) -> Result<Option<Vec<u8>>, DomainError> {
+        if let Some(value) = self.near.get(key).await? {
+            return Ok(Some(value));
+        }
+
+        let value = self.far.get(key).await?;
+        if let Some(value) = &value {
+            self.near.set(key, value.clone(), self.near_ttl).await?;
+        }
+
+        Ok(value)
+    }
+
+    async fn set(&self, key: &str, value: Vec<u8>, ttl: Duration) -> Result<(), DomainError> {
+        self.far.set(key, value.clone(), ttl).await?;
+        self.near.set(key, value, ttl.min(self.near_ttl)).await
+    }
+
+    async fn delete(&self, key: &str) -> Result<(), DomainError> {
+        self.far.delete(key).await?;
+        self.near.delete(key).await
+    }
+
+    async fn increment(&self, key: &str, ttl: Duration) -> Result<u64, DomainError> {
+        self.far.increment(key, ttl).await
+    }
+}
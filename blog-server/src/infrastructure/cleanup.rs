@@ -0,0 +1,39 @@
+use std::future::Future;
+use std::time::Duration;
+
+use tracing::{info, warn};
+
+use crate::domain::error::DomainError;
+use crate::infrastructure::metrics::Metrics;
+
+/// Starts a background task running `job` every `interval`.
+///
+/// `job` returns how many rows it removed, which is logged and added to the
+/// `cleanup_purged_rows_total` counter under `task`. Failures are logged and
+/// retried on the next tick. A zero `interval` disables the task.
+pub fn spawn_cleanup<F, Fut>(task: &'static str, interval: Duration, metrics: Metrics, job: F)
+where
+    F: Fn() -> Fut + Send + 'static,
+    Fut: Future<Output = Result<u64, DomainError>> + Send,
+{
+    if interval.is_zero() {
+        info!(task, "cleanup task disabled");
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(interval);
+
+        loop {
+            ticker.tick().await;
+            match job().await {
+                Ok(0) => {}
+                Ok(purged) => {
+                    metrics.observe_purge(task, purged);
+                    info!(task, purged, "cleanup task purged rows");
+                }
+                Err(e) => warn!(task, error = %e, "cleanup task failed"),
+            }
+        }
+    });
+}
@@ -1,4 +1,13 @@
 use serde::Deserialize;
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::domain::ip_rule::IpNet;
+use crate::domain::translation::normalize_locale;
+use crate::infrastructure::cache::CacheBackend;
+use crate::infrastructure::cors::CorsOrigins;
+use crate::infrastructure::event_publisher::EventBroker;
+use crate::infrastructure::moderation::ProfanityAction;
 
 /// Application configuration.
 ///
@@ -14,6 +23,25 @@ pub struct AppConfig {
     /// gRPC server port.
     pub grpc_port: u16,
 
+    /// HTTP worker threads; zero starts one per CPU.
+    pub http_workers: usize,
+
+    /// How long idle keep-alive connections stay open, in seconds; zero disables keep-alive.
+    pub http_keep_alive_secs: u64,
+
+    /// How long a client may take to send the request head, in milliseconds; zero disables the limit.
+    pub http_client_request_timeout_ms: u64,
+
+    /// How long a client may take to close the connection after the response,
+    /// in milliseconds; zero disables the limit.
+    pub http_client_disconnect_timeout_ms: u64,
+
+    /// Most concurrent connections each HTTP worker accepts.
+    pub http_max_connections: usize,
+
+    /// Most connections waiting to be accepted by the HTTP listener.
+    pub http_backlog: u32,
+
     /// Database connection URL.
     pub database_url: String,
 
@@ -22,12 +50,247 @@ pub struct AppConfig {
 
     /// Allowed CORS origins.
     ///
-    /// Defaults to `*` if not specified.
-    #[serde(default)]
-    pub cors_origins: Vec<String>,
+    /// Defaults to any origin, without credentials, if not specified.
+    #[serde(skip)]
+    pub cors_origins: CorsOrigins,
 
     /// Logging output format.
     pub log_format: String,
+
+    /// Directory uploaded media files are stored in.
+    pub media_dir: String,
+
+    /// Largest accepted media upload, in bytes.
+    pub max_upload_bytes: usize,
+
+    /// HTML tags allowed in posts and comments.
+    ///
+    /// Defaults to ammonia's allowlist if not specified.
+    #[serde(default)]
+    pub html_allowed_tags: Option<Vec<String>>,
+
+    /// Statements running longer than this many milliseconds are logged as slow.
+    pub slow_query_ms: u64,
+
+    /// How long to keep retrying the database connection at startup, in seconds.
+    pub db_connect_max_wait_secs: u64,
+
+    /// Start without a database connection and connect on first use.
+    pub db_lazy_connect: bool,
+
+    /// Directory the `blog-wasm` frontend is served from.
+    ///
+    /// Defaults to the embedded bundle when built with `embed-frontend`.
+    #[serde(default)]
+    pub static_dir: Option<String>,
+
+    /// Path the API is mounted under; versioned routes live below it, e.g. `/api/v1`.
+    pub api_base_path: String,
+
+    /// Also serve the deprecated unversioned routes directly under `api_base_path`.
+    pub legacy_api_routes: bool,
+
+    /// How long refresh tokens stay valid, in days.
+    pub refresh_token_ttl_days: i64,
+
+    /// Mark session cookies `Secure`, so browsers only send them over HTTPS.
+    pub cookie_secure: bool,
+
+    /// Language posts are written in; translations add the others.
+    pub default_post_locale: String,
+
+    /// How long deleted posts stay in the trash before they are purged, in days.
+    pub post_trash_retention_days: i64,
+
+    /// How long deleted comments are kept before they are purged, in days.
+    pub deleted_comment_retention_days: i64,
+
+    /// How long refresh tokens of ended sessions are kept, in days.
+    pub ended_session_retention_days: i64,
+
+    /// Seconds between purges of the post trash; zero disables them.
+    pub cleanup_trash_interval_secs: u64,
+
+    /// Seconds between purges of deleted comments; zero disables them.
+    pub cleanup_comments_interval_secs: u64,
+
+    /// Seconds between purges of expired refresh tokens; zero disables them.
+    pub cleanup_refresh_tokens_interval_secs: u64,
+
+    /// Seconds between purges of ended sessions; zero disables them.
+    pub cleanup_sessions_interval_secs: u64,
+
+    /// Seconds between purges of expired data exports; zero disables them.
+    pub cleanup_exports_interval_secs: u64,
+
+    /// How long data export archives and their download links last, in hours.
+    pub export_ttl_hours: i64,
+
+    /// Seconds between purges of stale chunked uploads; zero disables them.
+    pub cleanup_uploads_interval_secs: u64,
+
+    /// How long a chunked upload may go without a chunk before it is purged, in hours.
+    pub upload_ttl_hours: i64,
+
+    /// Seconds between removals of media blobs no upload references; zero disables them.
+    pub cleanup_media_blobs_interval_secs: u64,
+
+    /// Comments one user may post per rate limit window; zero disables the limit.
+    pub comment_rate_limit_per_user: usize,
+
+    /// Comments one client IP may post per rate limit window; zero disables the limit.
+    pub comment_rate_limit_per_ip: usize,
+
+    /// Length of the comment rate limit window, in seconds.
+    pub comment_rate_window_secs: u64,
+
+    /// Akismet-compatible `comment-check` URL new comments are checked against.
+    ///
+    /// Comments are not checked for spam if not specified.
+    #[serde(default)]
+    pub spam_classifier_url: Option<String>,
+
+    /// API key sent to the spam classifier.
+    pub spam_classifier_key: String,
+
+    /// Site URL sent to the spam classifier as the `blog` the comments belong to.
+    pub spam_classifier_site: String,
+
+    /// Gravatar base URL avatars fall back to.
+    pub gravatar_url: String,
+
+    /// Image Gravatar serves for addresses without one, e.g. `identicon`.
+    pub gravatar_default: String,
+
+    /// How long fetched Gravatars are cached, in seconds.
+    pub gravatar_cache_secs: u64,
+
+    /// Largest accepted WordPress or Medium export, in bytes.
+    pub import_max_bytes: usize,
+
+    /// How long to wait for each image referenced by an imported post, in seconds.
+    pub import_fetch_timeout_secs: u64,
+
+    /// Expose authors as ActivityPub actors that fediverse users can follow.
+    pub federation_enabled: bool,
+
+    /// Public URL of the server, e.g. `https://blog.example.com`; actor IDs and
+    /// WebFinger handles are built from it. Required when federation is enabled.
+    #[serde(default)]
+    pub federation_base_url: Option<String>,
+
+    /// How long to wait for remote ActivityPub servers, in seconds.
+    pub federation_timeout_secs: u64,
+
+    /// Receive webmentions for posts and send them for links in new posts.
+    pub webmention_enabled: bool,
+
+    /// Public URL of the site, e.g. `https://blog.example.com`; webmention
+    /// targets must point below it. Required when webmentions are enabled.
+    #[serde(default)]
+    pub webmention_base_url: Option<String>,
+
+    /// How long to wait for sites when verifying or sending webmentions, in seconds.
+    pub webmention_timeout_secs: u64,
+
+    /// Meilisearch server URL post searches are answered from.
+    ///
+    /// PostgreSQL full-text search is used if not specified.
+    #[serde(default)]
+    pub meilisearch_url: Option<String>,
+
+    /// API key sent to Meilisearch, if it requires one.
+    #[serde(default)]
+    pub meilisearch_api_key: Option<String>,
+
+    /// Name of the Meilisearch index holding the posts.
+    pub meilisearch_index: String,
+
+    /// Message broker domain events are published to.
+    ///
+    /// Events are not published if not specified.
+    #[serde(skip)]
+    pub event_broker: Option<EventBroker>,
+
+    /// NATS server URL, or comma-separated Kafka bootstrap brokers.
+    pub event_broker_url: String,
+
+    /// NATS subject prefix, or Kafka topic, events are published to.
+    pub event_broker_topic: String,
+
+    /// Milliseconds between checks for outbox events to publish.
+    pub outbox_relay_interval_ms: u64,
+
+    /// Seconds between purges of published outbox events; zero disables them.
+    pub cleanup_outbox_interval_secs: u64,
+
+    /// How long published outbox events are kept, in hours.
+    pub outbox_retention_hours: i64,
+
+    /// Where cached users, posts and rate limit counters are kept.
+    #[serde(skip)]
+    pub cache_backend: CacheBackend,
+
+    /// Redis server URL; required by the `redis` and `tiered` cache backends.
+    #[serde(default)]
+    pub redis_url: Option<String>,
+
+    /// Prefix of every Redis cache key.
+    pub cache_key_prefix: String,
+
+    /// Most entries kept by the in-memory cache.
+    pub cache_memory_capacity: u64,
+
+    /// Seconds the tiered cache serves a value from memory before asking Redis again.
+    pub cache_near_ttl_secs: u64,
+
+    /// How long authenticated users are cached, in seconds; zero disables the cache.
+    pub user_cache_ttl_secs: u64,
+
+    /// How long posts read by ID are cached, in seconds; zero disables the cache.
+    pub post_cache_ttl_secs: u64,
+
+    /// Most posts a user may have outside the trash; zero means unlimited.
+    pub quota_max_posts: u64,
+
+    /// Most bytes of uploaded media a user may store; zero means unlimited.
+    pub quota_max_media_bytes: u64,
+
+    /// Largest post content in bytes; zero means only the built-in length limit applies.
+    pub quota_max_post_bytes: u64,
+
+    /// Words the profanity filter looks for in posts and comments; empty
+    /// disables the filter.
+    pub profanity_words: Vec<String>,
+
+    /// Whether content using a profane word is flagged for review or rejected.
+    #[serde(skip)]
+    pub profanity_action: ProfanityAction,
+
+    /// URL of an external moderation service posts and comments are sent to.
+    ///
+    /// No external moderation is done if not specified.
+    #[serde(default)]
+    pub moderation_url: Option<String>,
+
+    /// Bearer token sent to the moderation service.
+    #[serde(default)]
+    pub moderation_token: Option<String>,
+
+    /// Client addresses let through even if a deny rule matches them.
+    #[serde(skip)]
+    pub ip_allow_list: Vec<IpNet>,
+
+    /// Client addresses whose requests are refused.
+    #[serde(skip)]
+    pub ip_deny_list: Vec<IpNet>,
+
+    /// How often IP rules added through the admin API are reloaded, in
+    /// seconds; zero only loads them at startup and on changes made here.
+    pub ip_rules_refresh_secs: u64,
+
+    /// Seconds between writes of counted post views to the database.
+    pub view_flush_interval_secs: u64,
 }
 
 impl AppConfig {
@@ -46,6 +309,17 @@ impl AppConfig {
             .unwrap_or_else(|_| "5000".into())
             .parse()
             .map_err(|e| anyhow::anyhow!("invalid GRPC PORT: {}", e))?;
+
+        let http_workers = env_or("HTTP_WORKERS", "0")?;
+        let http_keep_alive_secs = env_or("HTTP_KEEP_ALIVE_SECS", "5")?;
+        let http_client_request_timeout_ms = env_or("HTTP_CLIENT_REQUEST_TIMEOUT_MS", "5000")?;
+        let http_client_disconnect_timeout_ms =
+            env_or("HTTP_CLIENT_DISCONNECT_TIMEOUT_MS", "1000")?;
+        let http_max_connections = env_or("HTTP_MAX_CONNECTIONS", "25000")?;
+        if http_max_connections == 0 {
+            anyhow::bail!("HTTP_MAX_CONNECTIONS must be greater than zero");
+        }
+        let http_backlog = env_or("HTTP_BACKLOG", "1024")?;
         let database_url = std::env::var("DATABASE_URL")
             .map_err(|_| anyhow::anyhow!("DATABASE_URL must be set"))?;
         let jwt_secret =
@@ -55,18 +329,293 @@ impl AppConfig {
             .split(',')
             .map(|s| s.trim().to_string())
             .filter(|s| !s.is_empty())
-            .collect();
+            .collect::<Vec<_>>();
+        let cors_origins = CorsOrigins::parse(&cors_origins)
+            .map_err(|e| anyhow::anyhow!("invalid CORS_ORIGINS: {}", e))?;
 
         let log_format = std::env::var("LOG_FORMAT").unwrap_or_else(|_| "text".into());
+        let media_dir = std::env::var("MEDIA_DIR").unwrap_or_else(|_| "media".into());
+        let max_upload_bytes = std::env::var("MAX_UPLOAD_BYTES")
+            .unwrap_or_else(|_| (10 * 1024 * 1024).to_string())
+            .parse()
+            .map_err(|e| anyhow::anyhow!("invalid MAX_UPLOAD_BYTES: {}", e))?;
+        let html_allowed_tags = std::env::var("HTML_ALLOWED_TAGS").ok().map(|tags| {
+            tags.split(',')
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty())
+                .collect()
+        });
+
+        let slow_query_ms = std::env::var("SLOW_QUERY_MS")
+            .unwrap_or_else(|_| "200".into())
+            .parse()
+            .map_err(|e| anyhow::anyhow!("invalid SLOW_QUERY_MS: {}", e))?;
+
+        let db_connect_max_wait_secs = std::env::var("DB_CONNECT_MAX_WAIT_SECS")
+            .unwrap_or_else(|_| "30".into())
+            .parse()
+            .map_err(|e| anyhow::anyhow!("invalid DB_CONNECT_MAX_WAIT_SECS: {}", e))?;
+        let db_lazy_connect = std::env::var("DB_LAZY_CONNECT")
+            .unwrap_or_else(|_| "false".into())
+            .parse()
+            .map_err(|e| anyhow::anyhow!("invalid DB_LAZY_CONNECT: {}", e))?;
+
+        let static_dir = std::env::var("STATIC_DIR").ok().filter(|dir| !dir.is_empty());
+
+        let api_base_path = std::env::var("API_BASE_PATH").unwrap_or_else(|_| "/api".into());
+        let api_base_path = match api_base_path.trim_matches('/') {
+            "" => anyhow::bail!("API_BASE_PATH must not be empty"),
+            path => format!("/{}", path),
+        };
+        let legacy_api_routes = std::env::var("LEGACY_API_ROUTES")
+            .unwrap_or_else(|_| "true".into())
+            .parse()
+            .map_err(|e| anyhow::anyhow!("invalid LEGACY_API_ROUTES: {}", e))?;
+
+        let refresh_token_ttl_days = std::env::var("REFRESH_TOKEN_TTL_DAYS")
+            .unwrap_or_else(|_| "30".into())
+            .parse()
+            .map_err(|e| anyhow::anyhow!("invalid REFRESH_TOKEN_TTL_DAYS: {}", e))?;
+        let cookie_secure = std::env::var("COOKIE_SECURE")
+            .unwrap_or_else(|_| "true".into())
+            .parse()
+            .map_err(|e| anyhow::anyhow!("invalid COOKIE_SECURE: {}", e))?;
+
+        let default_post_locale =
+            std::env::var("DEFAULT_POST_LOCALE").unwrap_or_else(|_| "en".into());
+        let default_post_locale = normalize_locale(&default_post_locale)
+            .map_err(|e| anyhow::anyhow!("invalid DEFAULT_POST_LOCALE: {}", e))?;
+
+        let post_trash_retention_days = std::env::var("POST_TRASH_RETENTION_DAYS")
+            .unwrap_or_else(|_| "30".into())
+            .parse()
+            .map_err(|e| anyhow::anyhow!("invalid POST_TRASH_RETENTION_DAYS: {}", e))?;
+        let deleted_comment_retention_days = env_or("DELETED_COMMENT_RETENTION_DAYS", "30")?;
+        let ended_session_retention_days = env_or("ENDED_SESSION_RETENTION_DAYS", "7")?;
+
+        let cleanup_trash_interval_secs = env_or("CLEANUP_TRASH_INTERVAL_SECS", "3600")?;
+        let cleanup_comments_interval_secs = env_or("CLEANUP_COMMENTS_INTERVAL_SECS", "3600")?;
+        let cleanup_refresh_tokens_interval_secs =
+            env_or("CLEANUP_REFRESH_TOKENS_INTERVAL_SECS", "3600")?;
+        let cleanup_sessions_interval_secs = env_or("CLEANUP_SESSIONS_INTERVAL_SECS", "86400")?;
+        let cleanup_exports_interval_secs = env_or("CLEANUP_EXPORTS_INTERVAL_SECS", "3600")?;
+
+        let export_ttl_hours = env_or("EXPORT_TTL_HOURS", "48")?;
+
+        let cleanup_uploads_interval_secs = env_or("CLEANUP_UPLOADS_INTERVAL_SECS", "3600")?;
+        let upload_ttl_hours = env_or("UPLOAD_TTL_HOURS", "24")?;
+        let cleanup_media_blobs_interval_secs =
+            env_or("CLEANUP_MEDIA_BLOBS_INTERVAL_SECS", "3600")?;
+
+        let comment_rate_limit_per_user = env_or("COMMENT_RATE_LIMIT_PER_USER", "5")?;
+        let comment_rate_limit_per_ip = env_or("COMMENT_RATE_LIMIT_PER_IP", "20")?;
+        let comment_rate_window_secs = env_or("COMMENT_RATE_WINDOW_SECS", "60")?;
+        let spam_classifier_url = std::env::var("SPAM_CLASSIFIER_URL")
+            .ok()
+            .filter(|url| !url.is_empty());
+        let spam_classifier_key = std::env::var("SPAM_CLASSIFIER_KEY").unwrap_or_default();
+        let spam_classifier_site = std::env::var("SPAM_CLASSIFIER_SITE").unwrap_or_default();
+
+        let gravatar_url =
+            std::env::var("GRAVATAR_URL").unwrap_or_else(|_| "https://www.gravatar.com".into());
+        let gravatar_default =
+            std::env::var("GRAVATAR_DEFAULT").unwrap_or_else(|_| "identicon".into());
+        let gravatar_cache_secs = env_or("GRAVATAR_CACHE_SECS", "86400")?;
+
+        let import_max_bytes = env_or("IMPORT_MAX_BYTES", "52428800")?;
+        let import_fetch_timeout_secs = env_or("IMPORT_FETCH_TIMEOUT_SECS", "30")?;
+
+        let federation_enabled = env_or("FEDERATION_ENABLED", "false")?;
+        let federation_base_url = std::env::var("FEDERATION_BASE_URL")
+            .ok()
+            .map(|url| url.trim_end_matches('/').to_string())
+            .filter(|url| !url.is_empty());
+        if federation_enabled && federation_base_url.is_none() {
+            anyhow::bail!("FEDERATION_BASE_URL must be set when FEDERATION_ENABLED is true");
+        }
+        let federation_timeout_secs = env_or("FEDERATION_TIMEOUT_SECS", "10")?;
+
+        let webmention_enabled = env_or("WEBMENTION_ENABLED", "false")?;
+        let webmention_base_url = std::env::var("WEBMENTION_BASE_URL")
+            .ok()
+            .map(|url| url.trim_end_matches('/').to_string())
+            .filter(|url| !url.is_empty());
+        if webmention_enabled && webmention_base_url.is_none() {
+            anyhow::bail!("WEBMENTION_BASE_URL must be set when WEBMENTION_ENABLED is true");
+        }
+        let webmention_timeout_secs = env_or("WEBMENTION_TIMEOUT_SECS", "10")?;
+
+        let meilisearch_url = std::env::var("MEILISEARCH_URL")
+            .ok()
+            .filter(|url| !url.is_empty());
+        let meilisearch_api_key = std::env::var("MEILISEARCH_API_KEY")
+            .ok()
+            .filter(|key| !key.is_empty());
+        let meilisearch_index =
+            std::env::var("MEILISEARCH_INDEX").unwrap_or_else(|_| "posts".into());
+
+        let event_broker = std::env::var("EVENT_BROKER")
+            .ok()
+            .filter(|broker| !broker.is_empty() && broker != "none")
+            .map(|broker| broker.parse::<EventBroker>())
+            .transpose()
+            .map_err(|e| anyhow::anyhow!("invalid EVENT_BROKER: {}", e))?;
+        let event_broker_url = std::env::var("EVENT_BROKER_URL").unwrap_or_default();
+        if event_broker.is_some() && event_broker_url.is_empty() {
+            anyhow::bail!("EVENT_BROKER_URL must be set when EVENT_BROKER is set");
+        }
+        let event_broker_topic =
+            std::env::var("EVENT_BROKER_TOPIC").unwrap_or_else(|_| "blog.events".into());
+        let outbox_relay_interval_ms = env_or("OUTBOX_RELAY_INTERVAL_MS", "1000")?;
+        let cleanup_outbox_interval_secs = env_or("CLEANUP_OUTBOX_INTERVAL_SECS", "3600")?;
+        let outbox_retention_hours = env_or("OUTBOX_RETENTION_HOURS", "24")?;
+
+        let cache_backend = env_or("CACHE_BACKEND", "memory")?;
+        let redis_url = std::env::var("REDIS_URL")
+            .ok()
+            .filter(|url| !url.is_empty());
+        if cache_backend != CacheBackend::Memory && redis_url.is_none() {
+            anyhow::bail!("REDIS_URL must be set for the {} cache backend", cache_backend);
+        }
+        let cache_key_prefix =
+            std::env::var("CACHE_KEY_PREFIX").unwrap_or_else(|_| "blog:".into());
+        let cache_memory_capacity = env_or("CACHE_MEMORY_CAPACITY", "10000")?;
+        let cache_near_ttl_secs = env_or("CACHE_NEAR_TTL_SECS", "5")?;
+        let user_cache_ttl_secs = env_or("USER_CACHE_TTL_SECS", "60")?;
+        let post_cache_ttl_secs = env_or("POST_CACHE_TTL_SECS", "60")?;
+
+        let quota_max_posts = env_or("QUOTA_MAX_POSTS", "0")?;
+        let quota_max_media_bytes = env_or("QUOTA_MAX_MEDIA_BYTES", "0")?;
+        let quota_max_post_bytes = env_or("QUOTA_MAX_POST_BYTES", "0")?;
+
+        let profanity_words = std::env::var("PROFANITY_WORDS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|s| s.trim().to_string())
+            .filter(|s| !s.is_empty())
+            .collect();
+        let profanity_action = env_or("PROFANITY_ACTION", "flag")?;
+        let moderation_url = std::env::var("MODERATION_URL")
+            .ok()
+            .filter(|url| !url.is_empty());
+        let moderation_token = std::env::var("MODERATION_TOKEN")
+            .ok()
+            .filter(|token| !token.is_empty());
+
+        let ip_allow_list = ip_list("IP_ALLOW_LIST")?;
+        let ip_deny_list = ip_list("IP_DENY_LIST")?;
+        let ip_rules_refresh_secs = env_or("IP_RULES_REFRESH_SECS", "30")?;
+        let view_flush_interval_secs = env_or("VIEW_FLUSH_INTERVAL_SECS", "10")?;
 
         Ok(Self {
             host,
             http_port,
             grpc_port,
+            http_workers,
+            http_keep_alive_secs,
+            http_client_request_timeout_ms,
+            http_client_disconnect_timeout_ms,
+            http_max_connections,
+            http_backlog,
             database_url,
             jwt_secret,
             cors_origins,
             log_format,
+            media_dir,
+            max_upload_bytes,
+            html_allowed_tags,
+            slow_query_ms,
+            db_connect_max_wait_secs,
+            db_lazy_connect,
+            static_dir,
+            api_base_path,
+            legacy_api_routes,
+            refresh_token_ttl_days,
+            cookie_secure,
+            default_post_locale,
+            post_trash_retention_days,
+            deleted_comment_retention_days,
+            ended_session_retention_days,
+            cleanup_trash_interval_secs,
+            cleanup_comments_interval_secs,
+            cleanup_refresh_tokens_interval_secs,
+            cleanup_sessions_interval_secs,
+            cleanup_exports_interval_secs,
+            export_ttl_hours,
+            cleanup_uploads_interval_secs,
+            upload_ttl_hours,
+            cleanup_media_blobs_interval_secs,
+            comment_rate_limit_per_user,
+            comment_rate_limit_per_ip,
+            comment_rate_window_secs,
+            spam_classifier_url,
+            spam_classifier_key,
+            spam_classifier_site,
+            gravatar_url,
+            gravatar_default,
+            gravatar_cache_secs,
+            import_max_bytes,
+            import_fetch_timeout_secs,
+            federation_enabled,
+            federation_base_url,
+            federation_timeout_secs,
+            webmention_enabled,
+            webmention_base_url,
+            webmention_timeout_secs,
+            meilisearch_url,
+            meilisearch_api_key,
+            meilisearch_index,
+            event_broker,
+            event_broker_url,
+            event_broker_topic,
+            outbox_relay_interval_ms,
+            cleanup_outbox_interval_secs,
+            outbox_retention_hours,
+            cache_backend,
+            redis_url,
+            cache_key_prefix,
+            cache_memory_capacity,
+            cache_near_ttl_secs,
+            user_cache_ttl_secs,
+            post_cache_ttl_secs,
+            quota_max_posts,
+            quota_max_media_bytes,
+            quota_max_post_bytes,
+            profanity_words,
+            profanity_action,
+            moderation_url,
+            moderation_token,
+            ip_allow_list,
+            ip_deny_list,
+            ip_rules_refresh_secs,
+            view_flush_interval_secs,
         })
     }
 }
+
+/// Parses an environment variable, falling back to `default` when unset.
+fn env_or<T>(name: &str, default: &str) -> anyhow::Result<T>
+where
+    T: FromStr,
+    T::Err: Display,
+{
+    std::env::var(name)
+        .unwrap_or_else(|_| default.into())
+        .parse()
+        .map_err(|e| anyhow::anyhow!("invalid {}: {}", name, e))
+}
+
+/// Reads a comma-separated list of IP addresses and CIDR blocks; unset
+/// means empty.
+fn ip_list(name: &str) -> anyhow::Result<Vec<IpNet>> {
+    std::env::var(name)
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            s.parse::<IpNet>()
+                .map_err(|e| anyhow::anyhow!("invalid {}: {}", name, e))
+        })
+        .collect()
+}
@@ -0,0 +1,113 @@
+use regex::Regex;
+
+/// Origins allowed to make cross-origin requests, parsed from `CORS_ORIGINS`.
+#[derive(Debug, Clone, Default)]
+pub enum CorsOrigins {
+    /// Any origin (`*`); credentials are then never allowed.
+    #[default]
+    Any,
+
+    /// Origins matching one of the rules, with credentials allowed.
+    List(Vec<OriginRule>),
+}
+
+/// One allowed origin or origin pattern.
+#[derive(Debug, Clone)]
+pub enum OriginRule {
+    /// A single origin, e.g. `https://blog.example.com`.
+    Exact(String),
+
+    /// Any subdomain of a domain, e.g. `https://*.example.com`.
+    Subdomain {
+        /// Scheme including `://`.
+        scheme: String,
+        /// Domain and optional port, starting with a dot.
+        suffix: String,
+    },
+
+    /// A regular expression matched against the whole origin, e.g. `~https://pr-\d+\.example\.com`.
+    Pattern(Regex),
+}
+
+impl CorsOrigins {
+    /// Parses the configured entries, rejecting anything that is not a valid
+    /// origin, subdomain wildcard or regular expression.
+    ///
+    /// `*` must be the only entry.
+    pub fn parse(entries: &[String]) -> anyhow::Result<Self> {
+        if entries.iter().any(|entry| entry == "*") {
+            if entries.len() > 1 {
+                anyhow::bail!("`*` cannot be combined with other origins");
+            }
+            return Ok(Self::Any);
+        }
+        if entries.is_empty() {
+            anyhow::bail!("no origins given");
+        }
+
+        entries
+            .iter()
+            .map(|entry| OriginRule::parse(entry))
+            .collect::<anyhow::Result<_>>()
+            .map(Self::List)
+    }
+}
+
+impl OriginRule {
+    /// Parses one configured entry.
+    fn parse(entry: &str) -> anyhow::Result<Self> {
+        if let Some(pattern) = entry.strip_prefix('~') {
+            let regex = Regex::new(&format!("^(?:{pattern})$"))
+                .map_err(|e| anyhow::anyhow!("invalid origin pattern `{entry}`: {e}"))?;
+            return Ok(Self::Pattern(regex));
+        }
+
+        let entry = entry.to_ascii_lowercase();
+        let (scheme, host) = split_origin(&entry)
+            .ok_or_else(|| anyhow::anyhow!("invalid origin `{entry}`: expected scheme://host[:port]"))?;
+
+        if !host.contains('*') {
+            return Ok(Self::Exact(entry));
+        }
+        match host.strip_prefix('*') {
+            Some(suffix) if suffix.starts_with('.') && suffix.len() > 1 && !suffix.contains('*') => {
+                Ok(Self::Subdomain {
+                    scheme: format!("{scheme}://"),
+                    suffix: suffix.to_string(),
+                })
+            }
+            _ => anyhow::bail!("invalid origin `{entry}`: `*` is only allowed as `scheme://*.domain`"),
+        }
+    }
+
+    /// Returns whether `origin` is allowed by this rule.
+    pub fn matches(&self, origin: &str) -> bool {
+        match self {
+            Self::Exact(allowed) => origin.eq_ignore_ascii_case(allowed),
+            Self::Subdomain { scheme, suffix } => {
+                let origin = origin.to_ascii_lowercase();
+                origin
+                    .strip_prefix(scheme.as_str())
+                    .and_then(|host| host.strip_suffix(suffix.as_str()))
+                    .is_some_and(|sub| {
+                        !sub.is_empty()
+                            && sub
+                                .chars()
+                                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '.')
+                    })
+            }
+            Self::Pattern(regex) => regex.is_match(origin),
+        }
+    }
+}
+
+/// Splits an origin into its scheme and `host[:port]`, if it is one.
+///
+/// Origins have no path, so trailing slashes are rejected too.
+fn split_origin(origin: &str) -> Option<(&str, &str)> {
+    let (scheme, host) = origin.split_once("://")?;
+    let valid = matches!(scheme, "http" | "https")
+        && !host.is_empty()
+        && !host.contains(['/', '?', '#', '@']);
+    valid.then_some((scheme, host))
+}
@@ -1,23 +1,117 @@
-use sqlx::{postgres::PgPoolOptions, PgPool};
-use tracing::info;
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::{ConnectOptions, PgPool};
+use std::str::FromStr;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+use tracing_log::log::LevelFilter;
+
+/// Delay before the first connection retry; doubled after every failure.
+const INITIAL_RETRY_DELAY: Duration = Duration::from_millis(250);
+
+/// Longest delay between two connection retries.
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(5);
 
 /// Creates a PostgreSQL connection pool.
-pub async fn create_pool(database_url: &str) -> Result<PgPool, sqlx::Error> {
-    let pool = PgPoolOptions::new()
+///
+/// Statements slower than `slow_query` are logged at warn level with their
+/// SQL, inside the span of the repository call that ran them. Failed
+/// connection attempts are retried with exponential backoff until
+/// `max_wait` has passed, so the server can start before the database.
+pub async fn create_pool(
+    database_url: &str,
+    slow_query: Duration,
+    max_wait: Duration,
+) -> Result<PgPool, sqlx::Error> {
+    let options = connect_options(database_url, slow_query)?;
+    let started = Instant::now();
+    let mut delay = INITIAL_RETRY_DELAY;
+
+    loop {
+        match pool_options().connect_with(options.clone()).await {
+            Ok(pool) => {
+                info!("connected to PostgreSQL");
+                return Ok(pool);
+            }
+            Err(e) if retryable(&e) && started.elapsed() + delay <= max_wait => {
+                warn!(error = %e, retry_in_ms = delay.as_millis() as u64, "database unavailable, retrying");
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(MAX_RETRY_DELAY);
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
+
+/// Creates a PostgreSQL connection pool without connecting.
+///
+/// Connections are opened on first use, so the server starts even while the
+/// database is down; readiness reports the database until it is reachable.
+pub fn create_lazy_pool(database_url: &str, slow_query: Duration) -> Result<PgPool, sqlx::Error> {
+    let options = connect_options(database_url, slow_query)?;
+    Ok(pool_options().connect_lazy_with(options))
+}
+
+/// Parses the database URL and applies the slow statement threshold.
+fn connect_options(database_url: &str, slow_query: Duration) -> Result<PgConnectOptions, sqlx::Error> {
+    Ok(PgConnectOptions::from_str(database_url)?.log_slow_statements(LevelFilter::Warn, slow_query))
+}
+
+/// Returns the pool settings shared by eager and lazy pools.
+fn pool_options() -> PgPoolOptions {
+    PgPoolOptions::new()
         .max_connections(20)
         .min_connections(5)
-        .acquire_timeout(std::time::Duration::from_secs(5))
-        .connect(database_url)
-        .await?;
+        .acquire_timeout(Duration::from_secs(5))
+}
 
-    info!("connected to PostgreSQL");
-    Ok(pool)
+/// Returns whether a connection error may go away by waiting.
+fn retryable(e: &sqlx::Error) -> bool {
+    !matches!(e, sqlx::Error::Configuration(_))
+}
+
+/// Whether the database schema is up to date, for readiness checks.
+#[derive(Clone, Default)]
+pub struct MigrationStatus(Arc<AtomicBool>);
+
+impl MigrationStatus {
+    /// Returns whether migrations have been applied.
+    pub fn is_done(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+
+    /// Records that migrations have been applied.
+    fn mark_done(&self) {
+        self.0.store(true, Ordering::Release);
+    }
 }
 
 /// Runs database migrations.
-pub async fn run_migrations(pool: &PgPool) -> Result<(), sqlx::Error> {
+pub async fn run_migrations(pool: &PgPool, status: &MigrationStatus) -> Result<(), sqlx::Error> {
     info!("running database migrations");
     sqlx::migrate!().run(pool).await?;
+    status.mark_done();
     info!("migrations completed");
     Ok(())
 }
+
+/// Runs database migrations once the database is reachable.
+///
+/// Used with lazy pools: failures are retried with the same backoff as
+/// [`create_pool`], without a time limit.
+pub async fn run_migrations_when_ready(pool: PgPool, status: MigrationStatus) {
+    let mut delay = INITIAL_RETRY_DELAY;
+
+    while let Err(e) = run_migrations(&pool, &status).await {
+        warn!(error = %e, retry_in_ms = delay.as_millis() as u64, "migrations failed, retrying");
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(MAX_RETRY_DELAY);
+    }
+}
+
+/// Checks that the database answers a trivial query.
+pub async fn ping(pool: &PgPool) -> Result<(), sqlx::Error> {
+    sqlx::query("SELECT 1").execute(pool).await?;
+    Ok(())
+}
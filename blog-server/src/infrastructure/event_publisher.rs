@@ -0,0 +1,155 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+use async_trait::async_trait;
+use chrono::Utc;
+use rskafka::client::partition::{Compression, PartitionClient, UnknownTopicHandling};
+use rskafka::client::ClientBuilder;
+use rskafka::record::Record;
+
+use crate::domain::error::DomainError;
+
+/// Header carrying the event type of Kafka records.
+const EVENT_TYPE_HEADER: &str = "event-type";
+
+/// Message broker domain events are published to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EventBroker {
+    /// NATS; each event type gets its own subject below the configured prefix.
+    Nats,
+
+    /// Kafka; every event goes to the configured topic, keyed by user or post ID.
+    Kafka,
+}
+
+impl fmt::Display for EventBroker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            EventBroker::Nats => "nats",
+            EventBroker::Kafka => "kafka",
+        })
+    }
+}
+
+impl FromStr for EventBroker {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "nats" => Ok(EventBroker::Nats),
+            "kafka" => Ok(EventBroker::Kafka),
+            other => Err(format!("unknown event broker `{other}`")),
+        }
+    }
+}
+
+/// Publishes encoded domain events to a message broker.
+#[async_trait]
+pub trait EventPublisher: Send + Sync {
+    /// Publishes an event of type `event_type`, e.g. `post_published`, about
+    /// the entity identified by `key`.
+    async fn publish(
+        &self,
+        event_type: &str,
+        key: &str,
+        payload: Vec<u8>,
+    ) -> Result<(), DomainError>;
+}
+
+/// Publisher sending events to NATS subjects named `{prefix}.{event_type}`.
+pub struct NatsPublisher {
+    client: async_nats::Client,
+    prefix: String,
+}
+
+impl NatsPublisher {
+    /// Creates a publisher for the NATS server at `url`.
+    ///
+    /// The connection is made, and re-made after failures, in the background.
+    pub async fn connect(url: &str, prefix: String) -> Result<Self, DomainError> {
+        let client = async_nats::ConnectOptions::new()
+            .retry_on_initial_connect()
+            .connect(url)
+            .await
+            .map_err(|e| DomainError::Internal(format!("NATS connection error: {}", e)))?;
+
+        Ok(Self { client, prefix })
+    }
+}
+
+#[async_trait]
+impl EventPublisher for NatsPublisher {
+    async fn publish(
+        &self,
+        event_type: &str,
+        _key: &str,
+        payload: Vec<u8>,
+    ) -> Result<(), DomainError> {
+        self.client
+            .publish(format!("{}.{}", self.prefix, event_type), payload.into())
+            .await
+            .map_err(|e| DomainError::Internal(format!("NATS publish error: {}", e)))
+    }
+}
+
+/// Publisher appending events to the first partition of a Kafka topic,
+/// which keeps them in order.
+///
+/// Each record carries its event type in the `event-type` header.
+pub struct KafkaPublisher {
+    partition: PartitionClient,
+}
+
+impl KafkaPublisher {
+    /// Creates a publisher for `topic` on the cluster reachable through the
+    /// comma-separated `brokers`.
+    pub async fn connect(brokers: &str, topic: String) -> Result<Self, DomainError> {
+        let connect_err = |e: rskafka::client::error::Error| {
+            DomainError::Internal(format!("Kafka connection error: {}", e))
+        };
+        let brokers = brokers
+            .split(',')
+            .map(|broker| broker.trim().to_string())
+            .filter(|broker| !broker.is_empty())
+            .collect();
+
+        let client = ClientBuilder::new(brokers)
+            .build()
+            .await
+            .map_err(connect_err)?;
+        let partition = client
+            .partition_client(topic, 0, UnknownTopicHandling::Retry)
+            .await
+            .map_err(connect_err)?;
+
+        Ok(Self { partition })
+    }
+}
+
+#[async_trait]
+impl EventPublisher for KafkaPublisher {
+    async fn publish(
+        &self,
+        event_type: &str,
+        key: &str,
+        payload: Vec<u8>,
+    ) -> Result<(), DomainError> {
+        let record = Record {
+            key: Some(key.as_bytes().to_vec()),
+            value: Some(payload),
+            headers: BTreeMap::from([(
+                EVENT_TYPE_HEADER.to_string(),
+                event_type.as_bytes().to_vec(),
+            )]),
+            timestamp: Utc::now(),
+        };
+
+        self.partition
+            .produce(vec![record], Compression::NoCompression)
+            .await
+            .map_err(|e| DomainError::Internal(format!("Kafka publish error: {}", e)))?;
+
+        Ok(())
+    }
+}
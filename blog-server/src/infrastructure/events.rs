@@ -0,0 +1,98 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use chrono::Utc;
+use tokio::sync::broadcast;
+use tracing::debug;
+
+use crate::domain::event::{BlogEvent, EventKind};
+
+/// Events buffered per subscriber before slow subscribers start missing them.
+const CHANNEL_CAPACITY: usize = 1024;
+
+/// Recent events kept for subscribers resuming after a disconnect.
+const HISTORY_CAPACITY: usize = 1024;
+
+/// In-process publish/subscribe bus for blog events.
+///
+/// Cloning is cheap; all clones share the same channel and sequence.
+#[derive(Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<Arc<BlogEvent>>,
+    next_id: Arc<AtomicU64>,
+    history: Arc<Mutex<VecDeque<Arc<BlogEvent>>>>,
+}
+
+impl EventBus {
+    /// Creates a new event bus.
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(CHANNEL_CAPACITY);
+
+        Self {
+            sender,
+            next_id: Arc::new(AtomicU64::new(1)),
+            history: Arc::new(Mutex::new(VecDeque::with_capacity(HISTORY_CAPACITY))),
+        }
+    }
+
+    /// Publishes an event to all current subscribers.
+    ///
+    /// Events published while nobody listens are dropped, but stay in the
+    /// history for subscribers resuming with [`Self::subscribe_since`].
+    pub fn publish(&self, kind: EventKind) {
+        // Numbering and sending under the lock keeps the history in order
+        // and in step with the channel.
+        let mut history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        let event = Arc::new(BlogEvent {
+            id: self.next_id.fetch_add(1, Ordering::Relaxed),
+            at: Utc::now(),
+            kind,
+        });
+
+        debug!(event_id = event.id, kind = event.kind.name(), "event published");
+        if history.len() == HISTORY_CAPACITY {
+            history.pop_front();
+        }
+        history.push_back(event.clone());
+        let _ = self.sender.send(event);
+    }
+
+    /// Subscribes to events published from now on.
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<BlogEvent>> {
+        self.sender.subscribe()
+    }
+
+    /// Subscribes to events published from now on, also returning the
+    /// events after `last_event_id` still in the history.
+    ///
+    /// An ID this server run has not reached yet was issued before a restart,
+    /// so the whole history is returned. Events older than the history are lost.
+    pub fn subscribe_since(
+        &self,
+        last_event_id: Option<u64>,
+    ) -> (Vec<Arc<BlogEvent>>, broadcast::Receiver<Arc<BlogEvent>>) {
+        let history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        let receiver = self.sender.subscribe();
+
+        let backlog = match last_event_id {
+            None => Vec::new(),
+            Some(id) if id >= self.next_id.load(Ordering::Relaxed) => {
+                history.iter().cloned().collect()
+            }
+            Some(id) => history
+                .iter()
+                .filter(|event| event.id > id)
+                .cloned()
+                .collect(),
+        };
+
+        (backlog, receiver)
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,123 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use sha2::{Digest, Sha256};
+use tracing::error;
+
+use crate::domain::error::DomainError;
+use crate::infrastructure::security::to_hex;
+
+/// How long to wait for Gravatar.
+const GRAVATAR_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Number of cached images above which expired ones are dropped.
+const PRUNE_THRESHOLD: usize = 1_000;
+
+/// Image fetched from Gravatar.
+#[derive(Clone)]
+pub struct GravatarImage {
+    /// MIME type Gravatar answered with.
+    pub content_type: String,
+
+    /// Image contents.
+    pub bytes: Vec<u8>,
+}
+
+/// Caching proxy for Gravatar images.
+///
+/// Looks avatars up by the SHA-256 hash of the email address, so addresses
+/// never leave the server, and keeps fetched images in memory for `ttl`.
+#[derive(Clone)]
+pub struct GravatarProxy {
+    client: reqwest::Client,
+    base_url: String,
+    default_image: String,
+    ttl: Duration,
+    cache: Arc<Mutex<HashMap<(String, u32), (Instant, GravatarImage)>>>,
+}
+
+impl GravatarProxy {
+    /// Creates a proxy fetching from `base_url`, asking for `default_image`
+    /// (e.g. `identicon`) when an address has no Gravatar.
+    pub fn new(
+        base_url: String,
+        default_image: String,
+        ttl: Duration,
+    ) -> Result<Self, DomainError> {
+        let client = reqwest::Client::builder()
+            .timeout(GRAVATAR_TIMEOUT)
+            .build()
+            .map_err(|e| DomainError::Internal(format!("gravatar client error: {}", e)))?;
+
+        Ok(Self {
+            client,
+            base_url: base_url.trim_end_matches('/').to_string(),
+            default_image,
+            ttl,
+            cache: Arc::new(Mutex::new(HashMap::new())),
+        })
+    }
+
+    /// Returns the Gravatar of `email` at `size` pixels square.
+    pub async fn fetch(&self, email: &str, size: u32) -> Result<GravatarImage, DomainError> {
+        let hash = to_hex(&Sha256::digest(email.trim().to_lowercase().as_bytes()));
+        let key = (hash, size);
+
+        if let Some(image) = self.cached(&key) {
+            return Ok(image);
+        }
+
+        let response = self
+            .client
+            .get(format!("{}/avatar/{}", self.base_url, key.0))
+            .query(&[("s", size.to_string()), ("d", self.default_image.clone())])
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| {
+                error!("failed to fetch gravatar: {}", e);
+                DomainError::Internal(format!("gravatar error: {}", e))
+            })?;
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or("image/png")
+            .to_string();
+        let bytes = response
+            .bytes()
+            .await
+            .map_err(|e| DomainError::Internal(format!("gravatar error: {}", e)))?
+            .to_vec();
+
+        let image = GravatarImage {
+            content_type,
+            bytes,
+        };
+        self.store(key, image.clone());
+
+        Ok(image)
+    }
+
+    /// Returns a cached image if it has not expired.
+    fn cached(&self, key: &(String, u32)) -> Option<GravatarImage> {
+        let cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+
+        cache
+            .get(key)
+            .filter(|(fetched, _)| fetched.elapsed() < self.ttl)
+            .map(|(_, image)| image.clone())
+    }
+
+    /// Caches an image, dropping expired ones when the cache grows large.
+    fn store(&self, key: (String, u32), image: GravatarImage) {
+        let mut cache = self.cache.lock().unwrap_or_else(|e| e.into_inner());
+
+        if cache.len() > PRUNE_THRESHOLD {
+            cache.retain(|_, (fetched, _)| fetched.elapsed() < self.ttl);
+        }
+        cache.insert(key, (Instant::now(), image));
+    }
+}
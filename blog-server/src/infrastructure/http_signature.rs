@@ -0,0 +1,273 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chrono::{DateTime, Duration, Utc};
+use rand_core::OsRng;
+use rsa::pkcs1::DecodeRsaPublicKey;
+use rsa::pkcs1v15::{Signature, SigningKey, VerifyingKey};
+use rsa::pkcs8::{DecodePrivateKey, DecodePublicKey, EncodePrivateKey, EncodePublicKey, LineEnding};
+use rsa::signature::{SignatureEncoding, Signer, Verifier};
+use rsa::{RsaPrivateKey, RsaPublicKey};
+use sha2::{Digest, Sha256};
+
+use crate::domain::error::DomainError;
+
+/// Size of generated RSA keys, in bits.
+const KEY_BITS: usize = 2048;
+
+/// How far a signed request's `Date` may be from the server clock.
+const MAX_CLOCK_SKEW_MINUTES: i64 = 60;
+
+/// Headers a signature must cover; `digest` as well when there is a body.
+const REQUIRED_HEADERS: [&str; 3] = ["(request-target)", "host", "date"];
+
+/// PEM-encoded RSA key pair.
+pub struct KeyPair {
+    /// Public key, as SubjectPublicKeyInfo PEM.
+    pub public_key_pem: String,
+
+    /// Private key, as PKCS#8 PEM.
+    pub private_key_pem: String,
+}
+
+/// Headers to add to a signed outgoing request.
+pub struct SignatureHeaders {
+    /// `Date` header value.
+    pub date: String,
+
+    /// `Digest` header value, for requests with a body.
+    pub digest: Option<String>,
+
+    /// `Signature` header value.
+    pub signature: String,
+}
+
+/// Incoming request as seen by signature verification.
+pub struct IncomingRequest<'a> {
+    /// HTTP method.
+    pub method: &'a str,
+
+    /// Path and query.
+    pub target: &'a str,
+
+    /// Headers by lowercase name.
+    pub headers: HashMap<String, String>,
+
+    /// Request body.
+    pub body: &'a [u8],
+}
+
+/// Parameters of a `Signature` header.
+pub struct SignatureParams {
+    /// URL of the key that signed the request.
+    pub key_id: String,
+
+    headers: Vec<String>,
+    signature: Vec<u8>,
+}
+
+/// Generates an RSA key pair for signing requests.
+///
+/// CPU-bound and slow; run it on a blocking thread.
+pub fn generate_key_pair() -> Result<KeyPair, DomainError> {
+    let private_key = RsaPrivateKey::new(&mut OsRng, KEY_BITS).map_err(key_error)?;
+    let private_key_pem = private_key
+        .to_pkcs8_pem(LineEnding::LF)
+        .map_err(key_error)?
+        .to_string();
+    let public_key_pem = private_key
+        .to_public_key()
+        .to_public_key_pem(LineEnding::LF)
+        .map_err(key_error)?;
+
+    Ok(KeyPair {
+        public_key_pem,
+        private_key_pem,
+    })
+}
+
+/// Signs an outgoing request with `rsa-sha256`, covering the request
+/// target, `Host`, `Date` and, with a body, its `Digest`.
+pub fn sign_request(
+    private_key_pem: &str,
+    key_id: &str,
+    method: &str,
+    url: &reqwest::Url,
+    body: Option<&[u8]>,
+) -> Result<SignatureHeaders, DomainError> {
+    let private_key = RsaPrivateKey::from_pkcs8_pem(private_key_pem).map_err(key_error)?;
+
+    let host = match (url.host_str(), url.port()) {
+        (Some(host), Some(port)) => format!("{}:{}", host, port),
+        (Some(host), None) => host.to_string(),
+        (None, _) => return Err(DomainError::Internal(format!("no host in {}", url))),
+    };
+    let target = match url.query() {
+        Some(query) => format!("{}?{}", url.path(), query),
+        None => url.path().to_string(),
+    };
+    let date = Utc::now().format("%a, %d %b %Y %H:%M:%S GMT").to_string();
+    let digest = body.map(body_digest);
+
+    let mut headers = vec![
+        ("(request-target)", format!("{} {}", method.to_lowercase(), target)),
+        ("host", host),
+        ("date", date.clone()),
+    ];
+    if let Some(digest) = &digest {
+        headers.push(("digest", digest.clone()));
+    }
+
+    let signing_string = headers
+        .iter()
+        .map(|(name, value)| format!("{}: {}", name, value))
+        .collect::<Vec<_>>()
+        .join("\n");
+    let signature = SigningKey::<Sha256>::new(private_key).sign(signing_string.as_bytes());
+    let names: Vec<&str> = headers.iter().map(|(name, _)| *name).collect();
+
+    Ok(SignatureHeaders {
+        date,
+        digest,
+        signature: format!(
+            r#"keyId="{}",algorithm="rsa-sha256",headers="{}",signature="{}""#,
+            key_id,
+            names.join(" "),
+            BASE64.encode(signature.to_bytes())
+        ),
+    })
+}
+
+/// Parses the `Signature` header of an incoming request and checks what can
+/// be checked without the signer's key: that the signature covers the
+/// required headers, that `Date` is recent and that `Digest` matches the body.
+pub fn parse_signature(request: &IncomingRequest<'_>) -> Result<SignatureParams, DomainError> {
+    let invalid = |reason: &str| DomainError::InvalidCredentials(format!("signature {}", reason));
+
+    let header = request
+        .headers
+        .get("signature")
+        .ok_or_else(|| invalid("missing"))?;
+    let params = parse_params(header);
+
+    let key_id = params
+        .get("keyId")
+        .cloned()
+        .ok_or_else(|| invalid("has no keyId"))?;
+    if params
+        .get("algorithm")
+        .is_some_and(|algorithm| algorithm != "rsa-sha256" && algorithm != "hs2019")
+    {
+        return Err(invalid("uses an unsupported algorithm"));
+    }
+    let signature = params
+        .get("signature")
+        .and_then(|signature| BASE64.decode(signature).ok())
+        .ok_or_else(|| invalid("is not base64"))?;
+    let headers: Vec<String> = params
+        .get("headers")
+        .map_or("date", String::as_str)
+        .split_whitespace()
+        .map(str::to_lowercase)
+        .collect();
+
+    let covers = |name: &str| headers.iter().any(|header| header == name);
+    if !REQUIRED_HEADERS.iter().all(|name| covers(name))
+        || (!request.body.is_empty() && !covers("digest"))
+    {
+        return Err(invalid("does not cover the required headers"));
+    }
+
+    let date = request
+        .headers
+        .get("date")
+        .and_then(|date| DateTime::parse_from_rfc2822(date).ok())
+        .ok_or_else(|| invalid("date is missing or malformed"))?;
+    if (Utc::now() - date.to_utc()).abs() > Duration::minutes(MAX_CLOCK_SKEW_MINUTES) {
+        return Err(invalid("date is too far from the server clock"));
+    }
+
+    if covers("digest") {
+        let expected = BASE64.encode(Sha256::digest(request.body));
+        let matches = request.headers.get("digest").is_some_and(|digest| {
+            digest.split(',').any(|value| {
+                value.trim().split_once('=').is_some_and(|(algorithm, hash)| {
+                    algorithm.eq_ignore_ascii_case("SHA-256") && hash == expected
+                })
+            })
+        });
+        if !matches {
+            return Err(invalid("digest does not match the body"));
+        }
+    }
+
+    Ok(SignatureParams {
+        key_id,
+        headers,
+        signature,
+    })
+}
+
+/// Verifies a parsed signature with the signer's PEM public key.
+pub fn verify_signature(
+    request: &IncomingRequest<'_>,
+    params: &SignatureParams,
+    public_key_pem: &str,
+) -> Result<(), DomainError> {
+    let invalid = || DomainError::InvalidCredentials("signature does not verify".into());
+
+    let public_key = RsaPublicKey::from_public_key_pem(public_key_pem)
+        .or_else(|_| RsaPublicKey::from_pkcs1_pem(public_key_pem))
+        .map_err(|_| invalid())?;
+
+    let mut lines = Vec::with_capacity(params.headers.len());
+    for name in &params.headers {
+        let value = if name == "(request-target)" {
+            format!("{} {}", request.method.to_lowercase(), request.target)
+        } else {
+            request.headers.get(name).cloned().ok_or_else(invalid)?
+        };
+        lines.push(format!("{}: {}", name, value));
+    }
+
+    let signature = Signature::try_from(params.signature.as_slice()).map_err(|_| invalid())?;
+    VerifyingKey::<Sha256>::new(public_key)
+        .verify(lines.join("\n").as_bytes(), &signature)
+        .map_err(|_| invalid())
+}
+
+/// Reports a key that cannot be generated, encoded or decoded.
+fn key_error(e: impl Display) -> DomainError {
+    DomainError::Internal(format!("key error: {}", e))
+}
+
+/// Returns the `Digest` header value of a body.
+fn body_digest(body: &[u8]) -> String {
+    format!("SHA-256={}", BASE64.encode(Sha256::digest(body)))
+}
+
+/// Splits `name="value",name="value"` parameters.
+fn parse_params(header: &str) -> HashMap<String, String> {
+    let mut params = HashMap::new();
+    let mut rest = header.trim();
+
+    while let Some((name, after)) = rest.split_once('=') {
+        let name = name.trim().trim_start_matches(',').trim().to_string();
+        let after = after.trim_start();
+
+        let (value, remainder) = match after.strip_prefix('"') {
+            Some(quoted) => match quoted.split_once('"') {
+                Some((value, remainder)) => (value, remainder),
+                None => (quoted, ""),
+            },
+            None => after.split_once(',').unwrap_or((after, "")),
+        };
+
+        params.insert(name, value.to_string());
+        rest = remainder.trim_start().trim_start_matches(',');
+    }
+
+    params
+}
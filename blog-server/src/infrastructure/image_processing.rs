@@ -0,0 +1,95 @@
+use std::io::Cursor;
+
+use image::imageops::FilterType;
+use image::{DynamicImage, GenericImageView, ImageFormat};
+
+use crate::domain::error::DomainError;
+use crate::domain::media::VariantName;
+
+/// One size variant of an uploaded image, ready to store.
+pub struct RenderedVariant {
+    /// Which variant this is.
+    pub name: VariantName,
+
+    /// MIME type of the encoded image.
+    pub content_type: String,
+
+    /// Width in pixels.
+    pub width: u32,
+
+    /// Height in pixels.
+    pub height: u32,
+
+    /// Encoded image; `None` for the original, which is already stored.
+    pub bytes: Option<Vec<u8>>,
+}
+
+/// Decodes an uploaded image and renders every [`VariantName`] of it.
+///
+/// Variants are scaled down to fit their size, keeping the aspect ratio, and
+/// images that already fit are kept as they are. JPEGs stay JPEGs; other
+/// formats become PNGs to keep transparency, and animated GIFs keep only
+/// their first frame. This is CPU-bound and should run off the async runtime.
+pub fn render_variants(
+    bytes: &[u8],
+    content_type: &str,
+) -> Result<Vec<RenderedVariant>, DomainError> {
+    let format = ImageFormat::from_mime_type(content_type).ok_or_else(|| {
+        DomainError::Internal(format!("unsupported image type: {}", content_type))
+    })?;
+    let image = image::load_from_memory_with_format(bytes, format)
+        .map_err(|e| DomainError::Internal(format!("image decode error: {}", e)))?;
+    let (width, height) = image.dimensions();
+
+    VariantName::ALL
+        .into_iter()
+        .map(|name| match name.max_dimension() {
+            Some(max) if width > max || height > max => resize(&image, name, max, format),
+            Some(_) => Ok(RenderedVariant {
+                name,
+                content_type: content_type.to_string(),
+                width,
+                height,
+                bytes: Some(bytes.to_vec()),
+            }),
+            None => Ok(RenderedVariant {
+                name,
+                content_type: content_type.to_string(),
+                width,
+                height,
+                bytes: None,
+            }),
+        })
+        .collect()
+}
+
+/// Scales `image` down to fit in `max` x `max` pixels and encodes it.
+fn resize(
+    image: &DynamicImage,
+    name: VariantName,
+    max: u32,
+    format: ImageFormat,
+) -> Result<RenderedVariant, DomainError> {
+    let resized = image.resize(max, max, FilterType::Lanczos3);
+    let (resized, format, content_type) = match format {
+        ImageFormat::Jpeg => (
+            DynamicImage::ImageRgb8(resized.to_rgb8()),
+            ImageFormat::Jpeg,
+            "image/jpeg",
+        ),
+        _ => (resized, ImageFormat::Png, "image/png"),
+    };
+
+    let mut bytes = Vec::new();
+    resized
+        .write_to(&mut Cursor::new(&mut bytes), format)
+        .map_err(|e| DomainError::Internal(format!("image encode error: {}", e)))?;
+
+    Ok(RenderedVariant {
+        name,
+        content_type: content_type.to_string(),
+        width: resized.width(),
+        height: resized.height(),
+        bytes: Some(bytes),
+    })
+}
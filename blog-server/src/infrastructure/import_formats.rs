@@ -0,0 +1,348 @@
+use std::fmt::Display;
+use std::io::{Cursor, Read};
+
+use chrono::{DateTime, NaiveDateTime, Utc};
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use scraper::{Html, Selector};
+use zip::ZipArchive;
+
+use crate::domain::error::DomainError;
+use crate::domain::import::{ImportFormat, SkippedItem, SourceAuthor, SourceDocument, SourcePost};
+use crate::domain::post::Visibility;
+
+/// Largest post file read from a Medium export, in bytes.
+const MAX_MEDIUM_POST_BYTES: u64 = 5 * 1024 * 1024;
+
+/// Default WordPress category, which says nothing about a post.
+const WORDPRESS_DEFAULT_CATEGORY: &str = "uncategorized";
+
+/// Parses an export in the given format.
+///
+/// CPU-bound; run it on a blocking thread.
+pub fn parse_export(format: ImportFormat, bytes: &[u8]) -> Result<SourceDocument, DomainError> {
+    match format {
+        ImportFormat::Wordpress => parse_wxr(bytes),
+        ImportFormat::Medium => parse_medium(bytes),
+    }
+}
+
+/// Returns the distinct `http(s)` URLs of the images an HTML fragment shows.
+pub fn image_sources(html: &str) -> Vec<String> {
+    let fragment = Html::parse_fragment(html);
+    let mut sources: Vec<String> = Vec::new();
+
+    for img in fragment.select(&selector("img[src]")) {
+        let Some(src) = img.value().attr("src") else {
+            continue;
+        };
+        let is_remote = src.starts_with("http://") || src.starts_with("https://");
+        if is_remote && !sources.iter().any(|known| known == src) {
+            sources.push(src.to_string());
+        }
+    }
+
+    sources
+}
+
+/// Fields of a WXR `<item>` collected while reading it.
+#[derive(Default)]
+struct WxrItem {
+    id: String,
+    title: String,
+    creator: String,
+    content: String,
+    post_type: String,
+    status: String,
+    date_gmt: String,
+    date: String,
+    pub_date: String,
+    tags: Vec<String>,
+}
+
+/// Parses a WordPress eXtended RSS export.
+///
+/// Posts keep their tags and categories as tags; pages, attachments and
+/// trashed entries are skipped.
+fn parse_wxr(bytes: &[u8]) -> Result<SourceDocument, DomainError> {
+    let mut reader = Reader::from_reader(bytes);
+    let mut buf = Vec::new();
+    let mut path: Vec<String> = Vec::new();
+    let mut text = String::new();
+    let mut doc = SourceDocument::default();
+    let mut item: Option<WxrItem> = None;
+    let mut author: Option<SourceAuthor> = None;
+    let mut category_domain: Option<String> = None;
+
+    loop {
+        match reader.read_event_into(&mut buf).map_err(invalid_wxr)? {
+            Event::Start(e) => {
+                let name = String::from_utf8_lossy(e.name().as_ref()).into_owned();
+                if path.is_empty() && name != "rss" {
+                    return Err(invalid_wxr("the document is not an RSS feed"));
+                }
+
+                match name.as_str() {
+                    "item" => item = Some(WxrItem::default()),
+                    "wp:author" => {
+                        author = Some(SourceAuthor {
+                            login: String::new(),
+                            email: None,
+                            display_name: None,
+                        })
+                    }
+                    "category" => {
+                        category_domain = e
+                            .try_get_attribute("domain")
+                            .ok()
+                            .flatten()
+                            .and_then(|attr| attr.unescape_value().ok())
+                            .map(|value| value.into_owned());
+                    }
+                    _ => {}
+                }
+
+                path.push(name);
+                text.clear();
+            }
+            // Entities XML does not define, such as `&nbsp;`, are kept as written.
+            Event::Text(e) => match e.unescape() {
+                Ok(unescaped) => text.push_str(&unescaped),
+                Err(_) => text.push_str(&String::from_utf8_lossy(&e)),
+            },
+            Event::CData(e) => text.push_str(&String::from_utf8_lossy(&e.into_inner())),
+            Event::End(_) => {
+                let name = path.pop().unwrap_or_default();
+                let parent = path.last().map(String::as_str).unwrap_or_default();
+                let value = std::mem::take(&mut text).trim().to_string();
+
+                if let (Some(item), "item") = (item.as_mut(), parent) {
+                    match name.as_str() {
+                        "title" => item.title = value,
+                        "dc:creator" => item.creator = value,
+                        "content:encoded" => item.content = value,
+                        "wp:post_id" => item.id = value,
+                        "wp:post_type" => item.post_type = value,
+                        "wp:status" => item.status = value,
+                        "wp:post_date_gmt" => item.date_gmt = value,
+                        "wp:post_date" => item.date = value,
+                        "pubDate" => item.pub_date = value,
+                        "category" => {
+                            let is_tag = matches!(
+                                category_domain.take().as_deref(),
+                                Some("post_tag") | Some("category")
+                            );
+                            if is_tag && !value.eq_ignore_ascii_case(WORDPRESS_DEFAULT_CATEGORY) {
+                                item.tags.push(value);
+                            }
+                        }
+                        _ => {}
+                    }
+                } else if let (Some(author), "wp:author") = (author.as_mut(), parent) {
+                    match name.as_str() {
+                        "wp:author_login" => author.login = value,
+                        "wp:author_email" => author.email = Some(value).filter(|v| !v.is_empty()),
+                        "wp:author_display_name" => {
+                            author.display_name = Some(value).filter(|v| !v.is_empty())
+                        }
+                        _ => {}
+                    }
+                }
+
+                match name.as_str() {
+                    "item" => {
+                        if let Some(item) = item.take() {
+                            add_wxr_item(item, &mut doc);
+                        }
+                    }
+                    "wp:author" => {
+                        if let Some(author) = author.take().filter(|a| !a.login.is_empty()) {
+                            doc.authors.push(author);
+                        }
+                    }
+                    _ => {}
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+        buf.clear();
+    }
+
+    Ok(doc)
+}
+
+/// Reports a malformed WordPress export.
+fn invalid_wxr(e: impl Display) -> DomainError {
+    DomainError::Validation(format!("invalid WordPress export: {}", e))
+}
+
+/// Adds a WXR item to the document as a post, or records why it is skipped.
+fn add_wxr_item(item: WxrItem, doc: &mut SourceDocument) {
+    let source_id = if item.id.is_empty() {
+        item.title.clone()
+    } else {
+        item.id.clone()
+    };
+    let skip = |reason: String| SkippedItem {
+        source_id: source_id.clone(),
+        title: item.title.clone(),
+        reason,
+    };
+
+    if !item.post_type.is_empty() && item.post_type != "post" {
+        doc.skipped
+            .push(skip(format!("`{}` entries are not imported", item.post_type)));
+        return;
+    }
+
+    let visibility = match item.status.as_str() {
+        "publish" | "" => Visibility::Public,
+        "trash" | "auto-draft" | "inherit" => {
+            doc.skipped
+                .push(skip(format!("`{}` posts are not imported", item.status)));
+            return;
+        }
+        // Drafts, scheduled and private posts.
+        _ => Visibility::Private,
+    };
+
+    let published_at = parse_wordpress_date(&item.date_gmt)
+        .or_else(|| DateTime::parse_from_rfc2822(&item.pub_date).ok().map(|d| d.to_utc()))
+        .or_else(|| parse_wordpress_date(&item.date));
+
+    doc.posts.push(SourcePost {
+        source_id,
+        title: item.title,
+        content: item.content,
+        author: Some(item.creator).filter(|creator| !creator.is_empty()),
+        tags: item.tags,
+        visibility,
+        published_at,
+    });
+}
+
+/// Parses a WordPress `YYYY-MM-DD HH:MM:SS` date, read as UTC.
+///
+/// Unpublished posts carry `0000-00-00 00:00:00`, which yields `None`.
+fn parse_wordpress_date(value: &str) -> Option<DateTime<Utc>> {
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S")
+        .ok()
+        .map(|date| date.and_utc())
+}
+
+/// Parses a Medium export: the zip Medium sends, whose `posts/` directory
+/// holds one HTML file per post, or a single post's HTML file.
+///
+/// Files named `draft_*` are drafts and are imported as private posts.
+fn parse_medium(bytes: &[u8]) -> Result<SourceDocument, DomainError> {
+    let mut doc = SourceDocument::default();
+
+    if !bytes.starts_with(b"PK\x03\x04") {
+        let html = std::str::from_utf8(bytes).map_err(|_| {
+            DomainError::Validation("Medium export is neither a zip nor UTF-8 HTML".into())
+        })?;
+        add_medium_post("post.html", html, &mut doc);
+        return Ok(doc);
+    }
+
+    let invalid = |e: zip::result::ZipError| {
+        DomainError::Validation(format!("invalid Medium export: {}", e))
+    };
+    let mut archive = ZipArchive::new(Cursor::new(bytes)).map_err(invalid)?;
+
+    for index in 0..archive.len() {
+        let mut file = archive.by_index(index).map_err(invalid)?;
+        let Some(file_name) = file
+            .name()
+            .strip_prefix("posts/")
+            .filter(|name| name.ends_with(".html") && !name.contains('/'))
+            .map(str::to_string)
+        else {
+            continue;
+        };
+
+        let skip = |reason: &str| SkippedItem {
+            source_id: file_name.clone(),
+            title: String::new(),
+            reason: reason.to_string(),
+        };
+        if file.size() > MAX_MEDIUM_POST_BYTES {
+            doc.skipped.push(skip("the file is too large"));
+            continue;
+        }
+
+        let mut html = String::new();
+        if file.read_to_string(&mut html).is_err() {
+            doc.skipped.push(skip("the file is not readable UTF-8 HTML"));
+            continue;
+        }
+        add_medium_post(&file_name, &html, &mut doc);
+    }
+
+    Ok(doc)
+}
+
+/// Adds a Medium post file to the document, or records why it is skipped.
+fn add_medium_post(file_name: &str, html: &str, doc: &mut SourceDocument) {
+    let page = Html::parse_document(html);
+    let title = first_text(&page, "h1.p-name")
+        .or_else(|| first_text(&page, "title"))
+        .unwrap_or_default();
+
+    let Some(body) = page.select(&selector("section[data-field=\"body\"]")).next() else {
+        doc.skipped.push(SkippedItem {
+            source_id: file_name.to_string(),
+            title,
+            reason: "no post body found".into(),
+        });
+        return;
+    };
+
+    let published_at = page
+        .select(&selector("time.dt-published"))
+        .next()
+        .and_then(|time| time.value().attr("datetime"))
+        .and_then(|value| DateTime::parse_from_rfc3339(value).ok())
+        .map(|date| date.to_utc());
+    let author = first_text(&page, "a.p-author");
+
+    if let Some(name) = &author
+        && !doc.authors.iter().any(|known| known.login == *name)
+    {
+        doc.authors.push(SourceAuthor {
+            login: name.clone(),
+            email: None,
+            display_name: Some(name.clone()),
+        });
+    }
+
+    let visibility = if file_name.starts_with("draft_") {
+        Visibility::Private
+    } else {
+        Visibility::Public
+    };
+
+    doc.posts.push(SourcePost {
+        source_id: file_name.to_string(),
+        title,
+        content: body.inner_html().trim().to_string(),
+        author,
+        tags: Vec::new(),
+        visibility,
+        published_at,
+    });
+}
+
+/// Returns the trimmed text of the first element matching `css`, if not empty.
+fn first_text(page: &Html, css: &'static str) -> Option<String> {
+    page.select(&selector(css))
+        .next()
+        .map(|element| element.text().collect::<String>().trim().to_string())
+        .filter(|text| !text.is_empty())
+}
+
+/// Parses a selector written in this module.
+fn selector(css: &'static str) -> Selector {
+    Selector::parse(css).expect("static selectors are valid")
+}
@@ -0,0 +1,230 @@
+use std::io::{ErrorKind, SeekFrom};
+use std::path::PathBuf;
+
+use sha2::{Digest, Sha256};
+use tokio::io::{AsyncSeekExt, AsyncWriteExt};
+use tracing::error;
+use uuid::Uuid;
+
+use crate::domain::error::DomainError;
+use crate::domain::media::{Media, VariantName};
+use crate::infrastructure::security::to_hex;
+
+/// Directory under the root that blobs are kept in.
+const BLOB_DIR: &str = "blobs";
+
+/// Returns the hex SHA-256 of file contents, the name of their blob.
+pub fn content_hash(bytes: &[u8]) -> String {
+    to_hex(&Sha256::digest(bytes))
+}
+
+/// Local-disk store for uploaded media files.
+///
+/// Uploaded files are saved as blobs named by the SHA-256 of their contents,
+/// so identical uploads share one file; older uploads stay under their media
+/// ID. Resized variants are saved under the media ID and variant name, so
+/// client-supplied names never reach the filesystem. Data export archives
+/// are kept alongside, under their export ID, as are the partial files of
+/// chunked uploads, under their upload ID.
+#[derive(Clone)]
+pub struct MediaStorage {
+    root: PathBuf,
+}
+
+impl MediaStorage {
+    /// Creates a store rooted at `root`; the directory is created on first write.
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    /// Writes a blob unless it is already stored.
+    ///
+    /// The contents go to a temporary file first, so readers and concurrent
+    /// uploads of the same file never see a partial blob.
+    pub async fn write_blob(&self, hash: &str, bytes: &[u8]) -> Result<(), DomainError> {
+        let path = self.blob_path(hash);
+        let storage_err = |e: std::io::Error| {
+            error!("failed to write blob {}: {}", hash, e);
+            DomainError::Internal(format!("storage error: {}", e))
+        };
+
+        if tokio::fs::try_exists(&path).await.map_err(storage_err)? {
+            return Ok(());
+        }
+        if let Some(dir) = path.parent() {
+            tokio::fs::create_dir_all(dir).await.map_err(storage_err)?;
+        }
+
+        let tmp = path.with_extension(format!("{}.tmp", Uuid::new_v4()));
+        tokio::fs::write(&tmp, bytes).await.map_err(storage_err)?;
+        if let Err(e) = tokio::fs::rename(&tmp, &path).await {
+            let _ = tokio::fs::remove_file(&tmp).await;
+            return Err(storage_err(e));
+        }
+
+        Ok(())
+    }
+
+    /// Writes the contents of a resized variant of a media file.
+    pub async fn write_variant(
+        &self,
+        id: Uuid,
+        name: VariantName,
+        bytes: &[u8],
+    ) -> Result<(), DomainError> {
+        self.write_file(id, self.variant_path(id, name), bytes).await
+    }
+
+    /// Reads the contents of a media file, from its blob or, for files
+    /// uploaded before deduplication, from under its ID.
+    pub async fn read(&self, media: &Media) -> Result<Vec<u8>, DomainError> {
+        let path = match &media.sha256 {
+            Some(hash) => self.blob_path(hash),
+            None => self.path(media.id),
+        };
+        self.read_file(media.id, path).await
+    }
+
+    /// Reads the contents of a resized variant of a media file.
+    pub async fn read_variant(&self, id: Uuid, name: VariantName) -> Result<Vec<u8>, DomainError> {
+        self.read_file(id, self.variant_path(id, name)).await
+    }
+
+    /// Writes a data export archive.
+    pub async fn write_export(&self, id: Uuid, bytes: &[u8]) -> Result<(), DomainError> {
+        self.write_file(id, self.export_path(id), bytes).await
+    }
+
+    /// Reads a data export archive.
+    ///
+    /// A missing archive is reported as missing media.
+    pub async fn read_export(&self, id: Uuid) -> Result<Vec<u8>, DomainError> {
+        self.read_file(id, self.export_path(id)).await
+    }
+
+    /// Writes a chunk of a partial upload starting at byte `offset`,
+    /// dropping anything stored past it.
+    pub async fn write_upload_chunk(
+        &self,
+        id: Uuid,
+        offset: u64,
+        bytes: &[u8],
+    ) -> Result<(), DomainError> {
+        self.create_root().await?;
+
+        let storage_err = |e: std::io::Error| {
+            error!("failed to write upload {}: {}", id, e);
+            DomainError::Internal(format!("storage error: {}", e))
+        };
+        let mut file = tokio::fs::OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(false)
+            .open(self.upload_path(id))
+            .await
+            .map_err(storage_err)?;
+        file.seek(SeekFrom::Start(offset)).await.map_err(storage_err)?;
+        file.write_all(bytes).await.map_err(storage_err)?;
+        file.set_len(offset + bytes.len() as u64).await.map_err(storage_err)?;
+        file.flush().await.map_err(storage_err)
+    }
+
+    /// Reads the received contents of a partial upload.
+    pub async fn read_upload(&self, id: Uuid) -> Result<Vec<u8>, DomainError> {
+        self.read_file(id, self.upload_path(id)).await
+    }
+
+    /// Removes a partial upload; a missing file is not an error.
+    pub async fn remove_upload(&self, id: Uuid) -> Result<(), DomainError> {
+        self.remove_file(id, self.upload_path(id)).await
+    }
+
+    async fn create_root(&self) -> Result<(), DomainError> {
+        tokio::fs::create_dir_all(&self.root).await.map_err(|e| {
+            error!("failed to create media directory {}: {}", self.root.display(), e);
+            DomainError::Internal(format!("storage error: {}", e))
+        })
+    }
+
+    async fn write_file(&self, id: Uuid, path: PathBuf, bytes: &[u8]) -> Result<(), DomainError> {
+        self.create_root().await?;
+
+        tokio::fs::write(path, bytes).await.map_err(|e| {
+            error!("failed to write media {}: {}", id, e);
+            DomainError::Internal(format!("storage error: {}", e))
+        })
+    }
+
+    async fn read_file(&self, id: Uuid, path: PathBuf) -> Result<Vec<u8>, DomainError> {
+        tokio::fs::read(path).await.map_err(|e| match e.kind() {
+            ErrorKind::NotFound => DomainError::MediaNotFound(id.to_string()),
+            _ => {
+                error!("failed to read media {}: {}", id, e);
+                DomainError::Internal(format!("storage error: {}", e))
+            }
+        })
+    }
+
+    /// Removes a media file stored under its ID and its resized variants,
+    /// leaving any blob it shares with others; missing files are not an error.
+    pub async fn remove(&self, media: &Media) -> Result<(), DomainError> {
+        if media.sha256.is_none() {
+            self.remove_file(media.id, self.path(media.id)).await?;
+        }
+        for name in VariantName::ALL {
+            self.remove_file(media.id, self.variant_path(media.id, name)).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Removes a blob; a missing blob is not an error.
+    pub async fn remove_blob(&self, hash: &str) -> Result<(), DomainError> {
+        match tokio::fs::remove_file(self.blob_path(hash)).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => {
+                error!("failed to remove blob {}: {}", hash, e);
+                Err(DomainError::Internal(format!("storage error: {}", e)))
+            }
+        }
+    }
+
+    /// Removes a data export archive; a missing archive is not an error.
+    pub async fn remove_export(&self, id: Uuid) -> Result<(), DomainError> {
+        self.remove_file(id, self.export_path(id)).await
+    }
+
+    async fn remove_file(&self, id: Uuid, path: PathBuf) -> Result<(), DomainError> {
+        match tokio::fs::remove_file(path).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == ErrorKind::NotFound => Ok(()),
+            Err(e) => {
+                error!("failed to remove media {}: {}", id, e);
+                Err(DomainError::Internal(format!("storage error: {}", e)))
+            }
+        }
+    }
+
+    fn path(&self, id: Uuid) -> PathBuf {
+        self.root.join(id.to_string())
+    }
+
+    /// Returns the path of a blob, fanned out by the first byte of its hash
+    /// so no directory grows too large.
+    fn blob_path(&self, hash: &str) -> PathBuf {
+        self.root.join(BLOB_DIR).join(&hash[..2]).join(hash)
+    }
+
+    fn variant_path(&self, id: Uuid, name: VariantName) -> PathBuf {
+        self.root.join(format!("{}-{}", id, name))
+    }
+
+    fn export_path(&self, id: Uuid) -> PathBuf {
+        self.root.join(format!("{}.zip", id))
+    }
+
+    fn upload_path(&self, id: Uuid) -> PathBuf {
+        self.root.join(format!("{}.part", id))
+    }
+}
@@ -0,0 +1,205 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::domain::error::DomainError;
+use crate::domain::post::Post;
+use crate::domain::search::{HIGHLIGHT_END, HIGHLIGHT_START};
+
+/// How long to wait for the Meilisearch server.
+const MEILISEARCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Words kept around the matches in a search snippet.
+const SNIPPET_WORDS: usize = 35;
+
+/// A post as stored in the Meilisearch index.
+#[derive(Debug, Serialize)]
+pub struct PostDocument<'a> {
+    pub id: Uuid,
+    pub title: &'a str,
+    pub content: &'a str,
+    pub tags: &'a [String],
+    pub created_at: i64,
+}
+
+impl<'a> From<&'a Post> for PostDocument<'a> {
+    fn from(post: &'a Post) -> Self {
+        Self {
+            id: post.id,
+            title: &post.title,
+            content: &post.content,
+            tags: &post.tags,
+            created_at: post.created_at.timestamp(),
+        }
+    }
+}
+
+/// A search match: the post ID, its ranking score and a snippet with
+/// matches wrapped in the highlight markers from [`crate::domain::search`].
+#[derive(Debug)]
+pub struct DocumentHit {
+    pub post_id: Uuid,
+    pub rank: f32,
+    pub snippet: String,
+}
+
+#[derive(Deserialize)]
+struct SearchResponse {
+    hits: Vec<RawHit>,
+    #[serde(rename = "estimatedTotalHits", default)]
+    estimated_total_hits: i64,
+}
+
+#[derive(Deserialize)]
+struct RawHit {
+    id: Uuid,
+    #[serde(rename = "_rankingScore", default)]
+    ranking_score: f32,
+    #[serde(rename = "_formatted")]
+    formatted: Option<Formatted>,
+}
+
+#[derive(Deserialize)]
+struct Formatted {
+    #[serde(default)]
+    content: String,
+}
+
+/// Client for one index of a Meilisearch server.
+///
+/// Writes are queued by Meilisearch and applied shortly after they are
+/// accepted.
+#[derive(Clone)]
+pub struct MeilisearchClient {
+    client: reqwest::Client,
+    index_url: String,
+    api_key: Option<String>,
+}
+
+impl MeilisearchClient {
+    /// Creates a client for the index named `index` on the server at `url`.
+    pub fn new(url: &str, api_key: Option<String>, index: &str) -> Result<Self, DomainError> {
+        let client = reqwest::Client::builder()
+            .timeout(MEILISEARCH_TIMEOUT)
+            .build()
+            .map_err(|e| DomainError::Internal(format!("meilisearch client error: {}", e)))?;
+
+        Ok(Self {
+            client,
+            index_url: format!("{}/indexes/{}", url.trim_end_matches('/'), index),
+            api_key,
+        })
+    }
+
+    /// Returns a page of the documents matching `query`, best matches first,
+    /// with the estimated number of matches.
+    pub async fn search(
+        &self,
+        query: &str,
+        tag: Option<&str>,
+        limit: i64,
+        offset: i64,
+    ) -> Result<(Vec<DocumentHit>, i64), DomainError> {
+        let filter = tag.map(|tag| format!("tags = \"{}\"", tag.replace('"', "\\\"")));
+        let body = json!({
+            "q": query,
+            "filter": filter,
+            "limit": limit,
+            "offset": offset,
+            "attributesToCrop": ["content"],
+            "cropLength": SNIPPET_WORDS,
+            "cropMarker": " … ",
+            "attributesToHighlight": ["content"],
+            "highlightPreTag": HIGHLIGHT_START.to_string(),
+            "highlightPostTag": HIGHLIGHT_END.to_string(),
+            "showRankingScore": true,
+        });
+
+        let response: SearchResponse = self
+            .request(self.client.post(format!("{}/search", self.index_url)))
+            .json(&body)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(request_err)?
+            .json()
+            .await
+            .map_err(request_err)?;
+
+        let hits = response
+            .hits
+            .into_iter()
+            .map(|hit| DocumentHit {
+                post_id: hit.id,
+                rank: hit.ranking_score,
+                snippet: hit.formatted.map(|f| f.content).unwrap_or_default(),
+            })
+            .collect();
+
+        Ok((hits, response.estimated_total_hits))
+    }
+
+    /// Adds documents, replacing those with the same IDs.
+    pub async fn add_documents(&self, documents: &[PostDocument<'_>]) -> Result<(), DomainError> {
+        self.send(
+            self.client
+                .post(format!("{}/documents?primaryKey=id", self.index_url))
+                .json(documents),
+        )
+        .await
+    }
+
+    /// Deletes the document of a post.
+    pub async fn delete_document(&self, id: Uuid) -> Result<(), DomainError> {
+        self.send(
+            self.client
+                .delete(format!("{}/documents/{}", self.index_url, id)),
+        )
+        .await
+    }
+
+    /// Deletes every document and applies the index settings searches rely
+    /// on: searchable fields and tag filtering.
+    pub async fn reset(&self) -> Result<(), DomainError> {
+        self.send(self.client.delete(format!("{}/documents", self.index_url)))
+            .await?;
+
+        let settings = json!({
+            "searchableAttributes": ["title", "content", "tags"],
+            "filterableAttributes": ["tags"],
+            "sortableAttributes": ["created_at"],
+        });
+        self.send(
+            self.client
+                .patch(format!("{}/settings", self.index_url))
+                .json(&settings),
+        )
+        .await
+    }
+
+    /// Sends a write request, which Meilisearch enqueues.
+    async fn send(&self, request: reqwest::RequestBuilder) -> Result<(), DomainError> {
+        self.request(request)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(request_err)?;
+
+        Ok(())
+    }
+
+    /// Adds the API key to a request, if one is configured.
+    fn request(&self, request: reqwest::RequestBuilder) -> reqwest::RequestBuilder {
+        match &self.api_key {
+            Some(key) => request.bearer_auth(key),
+            None => request,
+        }
+    }
+}
+
+/// Reports a Meilisearch server that cannot be reached or answers with an error.
+fn request_err(e: reqwest::Error) -> DomainError {
+    DomainError::Internal(format!("meilisearch request failed: {}", e))
+}
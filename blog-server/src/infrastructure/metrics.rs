@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, Opts, Registry, TextEncoder,
+};
+
+/// Prometheus metrics of the server.
+#[derive(Clone)]
+pub struct Metrics {
+    registry: Registry,
+    http_latency: HistogramVec,
+    purged_rows: IntCounterVec,
+}
+
+impl Metrics {
+    /// Creates the metrics and registers them with a fresh registry.
+    pub fn new() -> Self {
+        let registry = Registry::new();
+        let http_latency = HistogramVec::new(
+            HistogramOpts::new(
+                "http_request_duration_seconds",
+                "HTTP request latency by route, method and status.",
+            ),
+            &["method", "route", "status"],
+        )
+        .expect("valid histogram options");
+        let purged_rows = IntCounterVec::new(
+            Opts::new(
+                "cleanup_purged_rows_total",
+                "Rows removed by background cleanup tasks, by task.",
+            ),
+            &["task"],
+        )
+        .expect("valid counter options");
+
+        registry
+            .register(Box::new(http_latency.clone()))
+            .expect("histogram registered once");
+        registry
+            .register(Box::new(purged_rows.clone()))
+            .expect("counter registered once");
+
+        Self {
+            registry,
+            http_latency,
+            purged_rows,
+        }
+    }
+
+    /// Records how long a request took.
+    ///
+    /// `route` is the matched route pattern, so paths with IDs share a series.
+    pub fn observe_request(&self, method: &str, route: &str, status: u16, elapsed: Duration) {
+        self.http_latency
+            .with_label_values(&[method, route, &status.to_string()])
+            .observe(elapsed.as_secs_f64());
+    }
+
+    /// Records how many rows a cleanup task removed in one run.
+    pub fn observe_purge(&self, task: &str, rows: u64) {
+        self.purged_rows.with_label_values(&[task]).inc_by(rows);
+    }
+
+    /// Renders every metric in the Prometheus text format.
+    pub fn render(&self) -> String {
+        let mut buffer = Vec::new();
+        // Encoding into a Vec only fails on malformed metric families.
+        let _ = TextEncoder::new().encode(&self.registry.gather(), &mut buffer);
+        String::from_utf8(buffer).unwrap_or_default()
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
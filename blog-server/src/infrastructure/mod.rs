@@ -1,11 +1,74 @@
+/// Zip and tar.zst archive reading and writing.
+pub mod archive;
+
+/// Version, commit and features embedded at build time.
+pub mod build_info;
+
+/// Key-value caching in memory and Redis.
+pub mod cache;
+
+/// Periodic cleanup of expired data.
+pub mod cleanup;
+
 /// Configuration utilities.
 pub mod config;
 
+/// CORS origin rules.
+pub mod cors;
+
 /// Database utilities.
 pub mod database;
 
+/// Message broker event publishing.
+pub mod event_publisher;
+
+/// In-process event bus.
+pub mod events;
+
+/// Caching Gravatar proxy.
+pub mod gravatar;
+
+/// Parsing of WordPress and Medium exports.
+pub mod import_formats;
+
+/// HTTP message signatures for ActivityPub.
+pub mod http_signature;
+
+/// Resizing of uploaded images.
+pub mod image_processing;
+
+/// Uploaded media file storage.
+pub mod media_storage;
+
 /// Logging utilities.
 pub mod logging;
 
+/// Meilisearch search engine client.
+pub mod meilisearch;
+
+/// Prometheus metrics.
+pub mod metrics;
+
+/// Content moderation hooks.
+pub mod moderation;
+
+/// Request rate limiting.
+pub mod rate_limit;
+
+/// Downloading of remote images.
+pub mod remote_media;
+
 /// Security utilities.
 pub mod security;
+
+/// User-generated HTML sanitization.
+pub mod sanitizer;
+
+/// Comment spam classification.
+pub mod spam;
+
+/// Static frontend files.
+pub mod static_files;
+
+/// Webmention discovery, sending and source verification.
+pub mod webmention;
@@ -0,0 +1,210 @@
+use std::collections::HashSet;
+use std::str::FromStr;
+use std::sync::Arc;
+use std::time::Duration;
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::domain::error::DomainError;
+use crate::domain::moderation::{ContentKind, Verdict};
+
+/// How long to wait for the moderation service.
+const MODERATION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Content submitted for moderation.
+#[derive(Debug, Serialize)]
+pub struct ModeratedContent<'a> {
+    /// Whether this is a post or a comment.
+    pub kind: ContentKind,
+
+    /// Identifier of the user who wrote it.
+    pub author_id: Uuid,
+
+    /// Post title; comments have none.
+    pub title: Option<&'a str>,
+
+    /// Sanitized post or comment text.
+    pub content: &'a str,
+}
+
+/// Decides whether posts and comments may be stored.
+///
+/// Called when a post is created or updated and when a comment is added,
+/// before anything is written.
+#[async_trait]
+pub trait ContentModerator: Send + Sync {
+    /// Returns whether the content is accepted, rejected or flagged for review.
+    async fn moderate(&self, content: &ModeratedContent<'_>) -> Result<Verdict, DomainError>;
+}
+
+/// What the profanity filter does with content using a listed word.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProfanityAction {
+    /// Store it and send it to the review queue.
+    #[default]
+    Flag,
+
+    /// Refuse it.
+    Reject,
+}
+
+impl FromStr for ProfanityAction {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "flag" => Ok(ProfanityAction::Flag),
+            "reject" => Ok(ProfanityAction::Reject),
+            other => Err(format!("unknown profanity action `{other}`")),
+        }
+    }
+}
+
+/// Moderator matching content against a list of words.
+///
+/// Words match whole and case-insensitively, so a listed `ass` does not
+/// catch `class`.
+pub struct ProfanityFilter {
+    words: HashSet<String>,
+    action: ProfanityAction,
+}
+
+impl ProfanityFilter {
+    /// Creates a filter applying `action` to content using any of `words`.
+    pub fn new(words: &[String], action: ProfanityAction) -> Self {
+        let words = words
+            .iter()
+            .map(|word| word.trim().to_lowercase())
+            .filter(|word| !word.is_empty())
+            .collect();
+
+        Self { words, action }
+    }
+
+    /// Returns the first listed word used in `text`.
+    fn find<'a>(&self, text: &'a str) -> Option<&'a str> {
+        text.split(|c: char| !c.is_alphanumeric())
+            .find(|token| !token.is_empty() && self.words.contains(&token.to_lowercase()))
+    }
+}
+
+#[async_trait]
+impl ContentModerator for ProfanityFilter {
+    async fn moderate(&self, content: &ModeratedContent<'_>) -> Result<Verdict, DomainError> {
+        let found = content
+            .title
+            .and_then(|title| self.find(title))
+            .or_else(|| self.find(content.content));
+
+        Ok(match (found, self.action) {
+            (None, _) => Verdict::Accept,
+            (Some(word), ProfanityAction::Flag) => Verdict::Flag(format!("profanity: {}", word)),
+            (Some(_), ProfanityAction::Reject) => {
+                Verdict::Reject("content contains disallowed language".into())
+            }
+        })
+    }
+}
+
+/// Verdict returned by the moderation service.
+#[derive(Deserialize)]
+struct HttpVerdict {
+    verdict: String,
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+/// Moderator backed by an external HTTP service.
+///
+/// Posts the content as JSON (`kind`, `author_id`, `title`, `content`) and
+/// expects `{"verdict": "accept" | "reject" | "flag", "reason": "..."}` back.
+pub struct HttpModerator {
+    client: reqwest::Client,
+    url: String,
+    token: Option<String>,
+}
+
+impl HttpModerator {
+    /// Creates a moderator asking the service at `url`, authenticating with
+    /// `token` as a bearer token when set.
+    pub fn new(url: String, token: Option<String>) -> Result<Self, DomainError> {
+        let client = reqwest::Client::builder()
+            .timeout(MODERATION_TIMEOUT)
+            .build()
+            .map_err(|e| DomainError::Internal(format!("moderation client error: {}", e)))?;
+
+        Ok(Self { client, url, token })
+    }
+}
+
+#[async_trait]
+impl ContentModerator for HttpModerator {
+    async fn moderate(&self, content: &ModeratedContent<'_>) -> Result<Verdict, DomainError> {
+        let mut request = self.client.post(&self.url).json(content);
+        if let Some(token) = &self.token {
+            request = request.bearer_auth(token);
+        }
+
+        let answer: HttpVerdict = request
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(|e| DomainError::Internal(format!("moderation service error: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| DomainError::Internal(format!("moderation service error: {}", e)))?;
+
+        let reason = || {
+            answer
+                .reason
+                .clone()
+                .unwrap_or_else(|| "moderation service".into())
+        };
+        match answer.verdict.as_str() {
+            "accept" => Ok(Verdict::Accept),
+            "reject" => Ok(Verdict::Reject(reason())),
+            "flag" => Ok(Verdict::Flag(reason())),
+            other => Err(DomainError::Internal(format!(
+                "moderation service answered `{}`",
+                other
+            ))),
+        }
+    }
+}
+
+/// Moderator asking several others in turn and keeping the strictest verdict.
+///
+/// Stops at the first rejection. A moderator that fails is logged and
+/// skipped, so the others still apply.
+pub struct ModeratorChain {
+    moderators: Vec<Arc<dyn ContentModerator>>,
+}
+
+impl ModeratorChain {
+    /// Creates a chain asking `moderators` in order.
+    pub fn new(moderators: Vec<Arc<dyn ContentModerator>>) -> Self {
+        Self { moderators }
+    }
+}
+
+#[async_trait]
+impl ContentModerator for ModeratorChain {
+    async fn moderate(&self, content: &ModeratedContent<'_>) -> Result<Verdict, DomainError> {
+        let mut verdict = Verdict::Accept;
+
+        for moderator in &self.moderators {
+            match moderator.moderate(content).await {
+                Ok(next) => verdict = verdict.and(next),
+                Err(e) => warn!(kind = ?content.kind, error = %e, "content moderator failed"),
+            }
+            if matches!(verdict, Verdict::Reject(_)) {
+                break;
+            }
+        }
+
+        Ok(verdict)
+    }
+}
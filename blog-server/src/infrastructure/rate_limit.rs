@@ -0,0 +1,88 @@
+use std::sync::Arc;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use tracing::warn;
+
+use crate::infrastructure::cache::Cache;
+
+/// Sliding window rate limiter counting hits in a [`Cache`].
+///
+/// Allows about `limit` hits per key within any `window`. Hits are counted
+/// per fixed window, and the count of the previous window is weighted by how
+/// much of it the sliding window still covers. With a Redis-backed cache every
+/// server instance counts together; with an in-memory one each counts
+/// separately.
+#[derive(Clone)]
+pub struct RateLimiter {
+    cache: Arc<dyn Cache>,
+    name: &'static str,
+    limit: usize,
+    window: Duration,
+}
+
+impl RateLimiter {
+    /// Creates a limiter allowing `limit` hits per `window`, keeping its
+    /// counters in `cache` under `name`; a zero `limit` allows everything.
+    pub fn new(cache: Arc<dyn Cache>, name: &'static str, limit: usize, window: Duration) -> Self {
+        Self {
+            cache,
+            name,
+            limit,
+            window,
+        }
+    }
+
+    /// Records a hit for `key` and returns whether it is within the limit.
+    ///
+    /// Rejected hits are not recorded, so a client that waits gets through
+    /// once its earlier hits leave the window. Hits are allowed when the
+    /// cache cannot be reached.
+    pub async fn check(&self, key: &str) -> bool {
+        if self.limit == 0 || self.window.is_zero() {
+            return true;
+        }
+
+        let window = self.window.as_millis();
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_millis();
+        let current = now / window;
+        let covered = 1.0 - (now % window) as f64 / window as f64;
+
+        let previous = self.count(key, current.saturating_sub(1)).await;
+        let counted = self.count(key, current).await;
+        if previous as f64 * covered + counted as f64 >= self.limit as f64 {
+            return false;
+        }
+
+        // Kept until the next window no longer looks back at it.
+        if let Err(e) = self
+            .cache
+            .increment(&self.key(key, current), self.window * 2)
+            .await
+        {
+            warn!(limiter = self.name, error = %e, "failed to record rate limited hit");
+        }
+        true
+    }
+
+    /// Returns the hits recorded for `key` in the given fixed window.
+    async fn count(&self, key: &str, window: u128) -> u64 {
+        match self.cache.get(&self.key(key, window)).await {
+            Ok(value) => value
+                .and_then(|value| String::from_utf8(value).ok())
+                .and_then(|value| value.parse().ok())
+                .unwrap_or(0),
+            Err(e) => {
+                warn!(limiter = self.name, error = %e, "failed to read rate limit counter");
+                0
+            }
+        }
+    }
+
+    /// Returns the cache key counting hits of `key` in a fixed window.
+    fn key(&self, key: &str, window: u128) -> String {
+        format!("rate:{}:{}:{}", self.name, key, window)
+    }
+}
@@ -0,0 +1,56 @@
+use std::time::Duration;
+
+use crate::domain::error::DomainError;
+
+/// Downloads images referenced by imported posts.
+#[derive(Clone)]
+pub struct RemoteMediaFetcher {
+    client: reqwest::Client,
+}
+
+impl RemoteMediaFetcher {
+    /// Creates a fetcher giving up on a download after `timeout`.
+    pub fn new(timeout: Duration) -> Result<Self, DomainError> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| DomainError::Internal(format!("media fetch client error: {}", e)))?;
+
+        Ok(Self { client })
+    }
+
+    /// Downloads an `http(s)` URL, refusing bodies over `max_bytes`.
+    pub async fn fetch(&self, url: &str, max_bytes: usize) -> Result<Vec<u8>, DomainError> {
+        let fetch_err = |e: reqwest::Error| DomainError::Internal(format!("download failed: {}", e));
+
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            return Err(DomainError::Validation("only http(s) URLs are downloaded".into()));
+        }
+
+        let mut response = self
+            .client
+            .get(url)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(fetch_err)?;
+
+        let too_large = || DomainError::PayloadTooLarge(format!("image exceeds {} bytes", max_bytes));
+        if response
+            .content_length()
+            .is_some_and(|length| length > max_bytes as u64)
+        {
+            return Err(too_large());
+        }
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(fetch_err)? {
+            if bytes.len() + chunk.len() > max_bytes {
+                return Err(too_large());
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        Ok(bytes)
+    }
+}
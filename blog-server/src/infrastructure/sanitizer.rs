@@ -0,0 +1,45 @@
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use ammonia::Builder;
+
+/// Tags whose content is always removed; they can never be allowed.
+const BLOCKED_TAGS: &[&str] = &["script", "style"];
+
+/// HTML sanitizer for user-generated content.
+///
+/// Keeps an allowlist of tags and strips everything else, including
+/// scripts, event handler attributes and `javascript:` URLs.
+#[derive(Clone)]
+pub struct HtmlSanitizer {
+    builder: Arc<Builder<'static>>,
+}
+
+impl HtmlSanitizer {
+    /// Creates a sanitizer allowing `allowed_tags`, or ammonia's default
+    /// allowlist when `None`.
+    ///
+    /// The tag names are leaked, as the sanitizer lives for the whole process.
+    pub fn new(allowed_tags: Option<&[String]>) -> Self {
+        let mut builder = Builder::default();
+        builder.link_rel(Some("noopener noreferrer nofollow"));
+
+        if let Some(tags) = allowed_tags {
+            let tags: HashSet<&'static str> = tags
+                .iter()
+                .filter(|tag| !BLOCKED_TAGS.iter().any(|b| b.eq_ignore_ascii_case(tag)))
+                .map(|tag| &*Box::leak(tag.to_ascii_lowercase().into_boxed_str()))
+                .collect();
+            builder.tags(tags);
+        }
+
+        Self {
+            builder: Arc::new(builder),
+        }
+    }
+
+    /// Returns `html` with every disallowed tag, attribute and URL removed.
+    pub fn clean(&self, html: &str) -> String {
+        self.builder.clean(html).to_string()
+    }
+}
@@ -3,10 +3,22 @@ use argon2::{
     password_hash::{PasswordHash, PasswordHasher, PasswordVerifier, SaltString},
 };
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
-use rand_core::OsRng;
+use rand_core::{OsRng, RngCore};
+use sha2::{Digest, Sha256};
 use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
+use crate::domain::user::Role;
+
+/// Lifetime of access tokens, in seconds.
+pub const ACCESS_TOKEN_TTL_SECS: i64 = 3600;
+
+/// Audience of post share tokens, keeping them apart from access tokens.
+const SHARE_TOKEN_AUDIENCE: &str = "post-share";
+
+/// Audience of data export download tokens.
+const EXPORT_TOKEN_AUDIENCE: &str = "data-export";
+
 /// JWT signing and verification keys.
 #[derive(Clone)]
 pub struct JwtKeys {
@@ -19,15 +31,17 @@ impl JwtKeys {
         Self { secret }
     }
 
-    /// Generates a signed JWT for the given user ID.
+    /// Generates a signed JWT for the given user ID and role.
     pub fn generate_token(
         &self,
         user_id: Uuid,
+        role: Role,
     ) -> Result<String, jsonwebtoken::errors::Error> {
         let claims = Claims {
             sub: user_id.to_string(),
+            role,
             exp: chrono::Utc::now()
-                .checked_add_signed(chrono::Duration::hours(1))
+                .checked_add_signed(chrono::Duration::seconds(ACCESS_TOKEN_TTL_SECS))
                 .unwrap()
                 .timestamp() as usize,
             iat: chrono::Utc::now().timestamp() as usize,
@@ -53,6 +67,83 @@ impl JwtKeys {
 
         Ok(data.claims)
     }
+
+    /// Generates a signed, non-expiring token granting read access to a post.
+    pub fn generate_share_token(
+        &self,
+        post_id: Uuid,
+    ) -> Result<String, jsonwebtoken::errors::Error> {
+        let claims = ShareClaims {
+            sub: post_id,
+            aud: SHARE_TOKEN_AUDIENCE.to_string(),
+            iat: chrono::Utc::now().timestamp() as usize,
+        };
+
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(self.secret.as_bytes()),
+        )
+    }
+
+    /// Verifies a post share token and returns the post ID it grants access to.
+    ///
+    /// Access tokens are rejected: they carry no share audience.
+    pub fn verify_share_token(
+        &self,
+        token: &str,
+    ) -> Result<Uuid, jsonwebtoken::errors::Error> {
+        let mut validation = Validation::default();
+        validation.set_audience(&[SHARE_TOKEN_AUDIENCE]);
+        validation.set_required_spec_claims(&["aud", "sub"]);
+        validation.validate_exp = false;
+
+        let data = decode::<ShareClaims>(
+            token,
+            &DecodingKey::from_secret(self.secret.as_bytes()),
+            &validation,
+        )?;
+
+        Ok(data.claims.sub)
+    }
+
+    /// Generates a signed token granting download of a data export until `expires_at`.
+    pub fn generate_export_token(
+        &self,
+        export_id: Uuid,
+        expires_at: chrono::DateTime<chrono::Utc>,
+    ) -> Result<String, jsonwebtoken::errors::Error> {
+        let claims = ExportClaims {
+            sub: export_id,
+            aud: EXPORT_TOKEN_AUDIENCE.to_string(),
+            exp: expires_at.timestamp() as usize,
+            iat: chrono::Utc::now().timestamp() as usize,
+        };
+
+        encode(
+            &Header::default(),
+            &claims,
+            &EncodingKey::from_secret(self.secret.as_bytes()),
+        )
+    }
+
+    /// Verifies a data export download token and returns the export ID.
+    pub fn verify_export_token(
+        &self,
+        token: &str,
+    ) -> Result<Uuid, jsonwebtoken::errors::Error> {
+        let mut validation = Validation::default();
+        validation.set_audience(&[EXPORT_TOKEN_AUDIENCE]);
+        validation.set_required_spec_claims(&["aud", "exp", "sub"]);
+
+        let data = decode::<ExportClaims>(
+            token,
+            &DecodingKey::from_secret(self.secret.as_bytes()),
+            &validation,
+        )?;
+
+        Ok(data.claims.sub)
+    }
 }
 
 /// JWT claims payload.
@@ -61,6 +152,11 @@ pub struct Claims {
     /// Subject (user ID).
     pub sub: String,
 
+    /// User role at the time the token was issued; informational only,
+    /// authorization always checks the stored role.
+    #[serde(default)]
+    pub role: Role,
+
     /// Expiration timestamp (seconds since epoch).
     pub exp: usize,
 
@@ -68,6 +164,35 @@ pub struct Claims {
     pub iat: usize,
 }
 
+/// Post share token claims.
+#[derive(Debug, Serialize, Deserialize)]
+struct ShareClaims {
+    /// Subject (post ID).
+    sub: Uuid,
+
+    /// Audience; always [`SHARE_TOKEN_AUDIENCE`].
+    aud: String,
+
+    /// Issued-at timestamp (seconds since epoch).
+    iat: usize,
+}
+
+/// Data export download token claims.
+#[derive(Debug, Serialize, Deserialize)]
+struct ExportClaims {
+    /// Subject (export ID).
+    sub: Uuid,
+
+    /// Audience; always [`EXPORT_TOKEN_AUDIENCE`].
+    aud: String,
+
+    /// Expiration timestamp (seconds since epoch).
+    exp: usize,
+
+    /// Issued-at timestamp (seconds since epoch).
+    iat: usize,
+}
+
 /// Hashes a plaintext password using Argon2.
 pub fn hash_password(
     password: &str,
@@ -94,3 +219,22 @@ pub fn verify_password(
         .verify_password(password.as_bytes(), &parsed)
         .is_ok())
 }
+
+/// Generates a random opaque token, such as a refresh or CSRF token.
+pub fn generate_secret() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    to_hex(&bytes)
+}
+
+/// Hashes a refresh token for storage.
+///
+/// Refresh tokens are random, so a fast unsalted hash is enough.
+pub fn hash_refresh_token(token: &str) -> String {
+    to_hex(&Sha256::digest(token.as_bytes()))
+}
+
+/// Encodes bytes as lowercase hex.
+pub(crate) fn to_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{b:02x}")).collect()
+}
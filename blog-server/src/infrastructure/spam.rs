@@ -0,0 +1,100 @@
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::domain::comment::{Comment, CommentOrigin};
+use crate::domain::error::DomainError;
+
+/// How long to wait for the spam classification service.
+const CLASSIFIER_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Decides whether new comments look like spam.
+#[async_trait]
+pub trait SpamClassifier: Send + Sync {
+    /// Returns why the comment looks like spam, or `None` if it looks legitimate.
+    async fn classify(
+        &self,
+        comment: &Comment,
+        origin: &CommentOrigin,
+    ) -> Result<Option<String>, DomainError>;
+}
+
+/// Classifier backed by an Akismet-compatible `comment-check` endpoint.
+///
+/// Posts the comment as a form and reads a `true` (spam) or `false` body.
+pub struct AkismetClassifier {
+    client: reqwest::Client,
+    endpoint: String,
+    api_key: String,
+    site: String,
+}
+
+impl AkismetClassifier {
+    /// Creates a classifier checking comments on `site` against `endpoint`.
+    pub fn new(endpoint: String, api_key: String, site: String) -> Result<Self, DomainError> {
+        let client = reqwest::Client::builder()
+            .timeout(CLASSIFIER_TIMEOUT)
+            .build()
+            .map_err(|e| DomainError::Internal(format!("spam classifier client error: {}", e)))?;
+
+        Ok(Self {
+            client,
+            endpoint,
+            api_key,
+            site,
+        })
+    }
+}
+
+#[async_trait]
+impl SpamClassifier for AkismetClassifier {
+    async fn classify(
+        &self,
+        comment: &Comment,
+        origin: &CommentOrigin,
+    ) -> Result<Option<String>, DomainError> {
+        let author_id = comment.author_id.to_string();
+        let form = [
+            ("api_key", self.api_key.as_str()),
+            ("blog", self.site.as_str()),
+            ("user_ip", origin.ip.as_deref().unwrap_or_default()),
+            ("user_agent", origin.user_agent.as_deref().unwrap_or_default()),
+            ("comment_type", if comment.parent_id.is_some() { "reply" } else { "comment" }),
+            ("comment_author", author_id.as_str()),
+            ("comment_content", comment.content.as_str()),
+        ];
+
+        let response = self
+            .client
+            .post(&self.endpoint)
+            .form(&form)
+            .send()
+            .await
+            .map_err(|e| DomainError::Internal(format!("spam classifier error: {}", e)))?;
+
+        let discard = response
+            .headers()
+            .get("X-akismet-pro-tip")
+            .is_some_and(|tip| tip == "discard");
+        let debug_help = response
+            .headers()
+            .get("X-akismet-debug-help")
+            .and_then(|help| help.to_str().ok())
+            .map(str::to_string);
+        let body = response
+            .text()
+            .await
+            .map_err(|e| DomainError::Internal(format!("spam classifier error: {}", e)))?;
+
+        match body.trim() {
+            "false" => Ok(None),
+            "true" if discard => Ok(Some("classifier: blatant spam".into())),
+            "true" => Ok(Some("classifier: spam".into())),
+            other => Err(DomainError::Internal(format!(
+                "spam classifier answered `{}`: {}",
+                other,
+                debug_help.unwrap_or_default()
+            ))),
+        }
+    }
+}
@@ -0,0 +1,115 @@
+use std::borrow::Cow;
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use tracing::error;
+
+use crate::domain::error::DomainError;
+
+/// Frontend bundle compiled into the binary with the `embed-frontend` feature.
+///
+/// Build `blog-wasm` with `wasm-pack build --target web` first, so `pkg/` exists.
+#[cfg(feature = "embed-frontend")]
+#[derive(rust_embed::RustEmbed)]
+#[folder = "../blog-wasm/"]
+#[include = "index.html"]
+#[include = "main.js"]
+#[include = "pkg/*"]
+#[exclude = "pkg/.gitignore"]
+struct EmbeddedFrontend;
+
+/// A frontend file ready to be served.
+pub struct StaticAsset {
+    /// File contents.
+    pub body: Cow<'static, [u8]>,
+
+    /// MIME type guessed from the file extension.
+    pub content_type: String,
+
+    /// Validator for conditional requests.
+    pub etag: String,
+}
+
+/// Source of the `blog-wasm` frontend bundle.
+#[derive(Clone)]
+pub enum StaticFiles {
+    /// Files read from a directory on every request.
+    Dir(PathBuf),
+
+    /// Files compiled into the binary.
+    #[cfg(feature = "embed-frontend")]
+    Embedded,
+}
+
+impl StaticFiles {
+    /// Picks the configured source: `static_dir` when set, the embedded
+    /// bundle when compiled in, and no frontend otherwise.
+    pub fn from_config(static_dir: Option<&str>) -> Option<Self> {
+        match static_dir {
+            Some(dir) => Some(Self::Dir(dir.into())),
+            #[cfg(feature = "embed-frontend")]
+            None => Some(Self::Embedded),
+            #[cfg(not(feature = "embed-frontend"))]
+            None => None,
+        }
+    }
+
+    /// Returns the file at the relative `path`, or `None` when there is none.
+    ///
+    /// Paths leaving the bundle, such as `../x` or `/etc/x`, are never found.
+    pub async fn get(&self, path: &str) -> Result<Option<StaticAsset>, DomainError> {
+        let path = Path::new(path);
+        if !path.components().all(|c| matches!(c, Component::Normal(_))) {
+            return Ok(None);
+        }
+        let content_type = mime_guess::from_path(path)
+            .first_or_octet_stream()
+            .to_string();
+
+        match self {
+            Self::Dir(root) => read_file(&root.join(path), content_type).await,
+            #[cfg(feature = "embed-frontend")]
+            Self::Embedded => Ok(path.to_str().and_then(EmbeddedFrontend::get).map(|file| {
+                let etag = file
+                    .metadata
+                    .sha256_hash()
+                    .iter()
+                    .map(|b| format!("{b:02x}"))
+                    .collect();
+                StaticAsset {
+                    body: file.data,
+                    content_type,
+                    etag,
+                }
+            })),
+        }
+    }
+}
+
+/// Reads a file from disk, tagging it with its size and modification time.
+async fn read_file(path: &Path, content_type: String) -> Result<Option<StaticAsset>, DomainError> {
+    let storage_err = |e: std::io::Error| {
+        error!("failed to read static file {}: {}", path.display(), e);
+        DomainError::Internal(format!("storage error: {}", e))
+    };
+
+    let metadata = match tokio::fs::metadata(path).await {
+        Ok(metadata) if metadata.is_file() => metadata,
+        Ok(_) => return Ok(None),
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(storage_err(e)),
+    };
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .unwrap_or_default();
+    let body = tokio::fs::read(path).await.map_err(storage_err)?;
+
+    Ok(Some(StaticAsset {
+        body: Cow::Owned(body),
+        content_type,
+        etag: format!("{:x}-{:x}", metadata.len(), modified.as_nanos()),
+    }))
+}
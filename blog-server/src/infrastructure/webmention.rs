@@ -0,0 +1,286 @@
+use std::collections::HashSet;
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use regex::Regex;
+use reqwest::header::{CONTENT_TYPE, LINK};
+use reqwest::{StatusCode, Url};
+use scraper::{Html, Selector};
+
+use crate::domain::error::DomainError;
+
+/// Largest page read when verifying a source or discovering an endpoint.
+const MAX_PAGE_BYTES: usize = 1024 * 1024;
+
+/// Longest excerpt kept from a source page, in characters.
+const MAX_EXCERPT_CHARS: usize = 280;
+
+/// Absolute `http(s)` URLs in post content, Markdown or HTML.
+static LINK_PATTERN: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"https?://[^\s"'<>()\[\]`]+"#).expect("static regex is valid"));
+
+/// What a source page says about the page linking to a post.
+#[derive(Debug, Default)]
+pub struct SourcePage {
+    /// Author name, from the page's h-card.
+    pub author_name: Option<String>,
+
+    /// Author URL, from the page's h-card.
+    pub author_url: Option<String>,
+
+    /// Entry name or page title.
+    pub title: Option<String>,
+
+    /// Start of the entry's text.
+    pub excerpt: Option<String>,
+}
+
+/// Outcome of checking a webmention source.
+#[derive(Debug)]
+pub enum SourceCheck {
+    /// The source links to the target.
+    Links(SourcePage),
+
+    /// The source does not link to the target (any more).
+    NoLink,
+
+    /// The source was deleted (`410 Gone`).
+    Gone,
+}
+
+/// Webmention client: verifies received mentions and notifies other sites.
+#[derive(Clone)]
+pub struct WebmentionClient {
+    client: reqwest::Client,
+}
+
+impl WebmentionClient {
+    /// Creates a client giving up on remote sites after `timeout`.
+    pub fn new(timeout: Duration) -> Result<Self, DomainError> {
+        let client = reqwest::Client::builder()
+            .timeout(timeout)
+            .build()
+            .map_err(|e| DomainError::Internal(format!("webmention client error: {}", e)))?;
+
+        Ok(Self { client })
+    }
+
+    /// Fetches a source page and checks whether it links to `target`.
+    pub async fn check_source(
+        &self,
+        source: &str,
+        target: &str,
+    ) -> Result<SourceCheck, DomainError> {
+        let response = self.client.get(source).send().await.map_err(fetch_err)?;
+        if response.status() == StatusCode::GONE {
+            return Ok(SourceCheck::Gone);
+        }
+        let response = response.error_for_status().map_err(fetch_err)?;
+
+        let is_html = is_html(&response);
+        let body = read_limited(response).await?;
+        let body = String::from_utf8_lossy(&body);
+
+        let escaped_target = target.replace('&', "&amp;");
+        if !body.contains(target) && !body.contains(&escaped_target) {
+            return Ok(SourceCheck::NoLink);
+        }
+
+        let page = if is_html {
+            source_page(&body)
+        } else {
+            SourcePage::default()
+        };
+        Ok(SourceCheck::Links(page))
+    }
+
+    /// Finds the webmention endpoint `target` advertises, in its `Link`
+    /// headers or its HTML.
+    pub async fn discover_endpoint(&self, target: &str) -> Result<Option<Url>, DomainError> {
+        let response = self
+            .client
+            .get(target)
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(fetch_err)?;
+        let base = response.url().clone();
+
+        let from_header = response
+            .headers()
+            .get_all(LINK)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .find_map(link_header_endpoint);
+        if let Some(href) = from_header {
+            return Ok(base.join(&href).ok());
+        }
+
+        if !is_html(&response) {
+            return Ok(None);
+        }
+        let body = read_limited(response).await?;
+        let href = html_endpoint(&String::from_utf8_lossy(&body));
+
+        Ok(href.and_then(|href| base.join(&href).ok()))
+    }
+
+    /// Notifies `endpoint` that `source` links to `target`.
+    pub async fn send(&self, endpoint: Url, source: &str, target: &str) -> Result<(), DomainError> {
+        self.client
+            .post(endpoint)
+            .form(&[("source", source), ("target", target)])
+            .send()
+            .await
+            .and_then(|response| response.error_for_status())
+            .map_err(fetch_err)?;
+
+        Ok(())
+    }
+}
+
+/// Returns the distinct absolute links in post content, in order.
+pub fn extract_links(content: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+
+    LINK_PATTERN
+        .find_iter(content)
+        .map(|link| {
+            link.as_str()
+                .trim_end_matches(['.', ',', ';', ':', '!', '?'])
+                .to_string()
+        })
+        .filter(|link| seen.insert(link.clone()))
+        .collect()
+}
+
+/// Reports a remote site that cannot be reached or answers with an error.
+fn fetch_err(e: reqwest::Error) -> DomainError {
+    DomainError::Internal(format!("webmention request failed: {}", e))
+}
+
+/// Returns whether a response is an HTML page.
+fn is_html(response: &reqwest::Response) -> bool {
+    response
+        .headers()
+        .get(CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("text/html"))
+}
+
+/// Reads a response body, refusing pages over [`MAX_PAGE_BYTES`].
+async fn read_limited(mut response: reqwest::Response) -> Result<Vec<u8>, DomainError> {
+    let too_large =
+        || DomainError::PayloadTooLarge(format!("page exceeds {} bytes", MAX_PAGE_BYTES));
+    if response
+        .content_length()
+        .is_some_and(|length| length > MAX_PAGE_BYTES as u64)
+    {
+        return Err(too_large());
+    }
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = response.chunk().await.map_err(fetch_err)? {
+        if bytes.len() + chunk.len() > MAX_PAGE_BYTES {
+            return Err(too_large());
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    Ok(bytes)
+}
+
+/// Returns the target of a `rel="webmention"` link in a `Link` header.
+fn link_header_endpoint(header: &str) -> Option<String> {
+    header.split(',').find_map(|link| {
+        let (target, params) = link.trim().strip_prefix('<')?.split_once('>')?;
+        let is_webmention = params.split(';').any(|param| {
+            param.trim().strip_prefix("rel=").is_some_and(|rel| {
+                rel.trim_matches('"')
+                    .split_whitespace()
+                    .any(|rel| rel == "webmention")
+            })
+        });
+
+        is_webmention.then(|| target.to_string())
+    })
+}
+
+/// Returns the `href` of the first `rel="webmention"` link in an HTML page.
+fn html_endpoint(html: &str) -> Option<String> {
+    let page = Html::parse_document(html);
+    page.select(&selector(
+        r#"link[rel~="webmention"][href], a[rel~="webmention"][href]"#,
+    ))
+    .next()
+    .and_then(|element| element.value().attr("href"))
+    .map(str::to_string)
+}
+
+/// Reads the author, title and excerpt of a source page, preferring its
+/// microformats (`h-entry`, `h-card`).
+fn source_page(html: &str) -> SourcePage {
+    let page = Html::parse_document(html);
+
+    let author = page.select(&selector(".h-entry .p-author, .h-card")).next();
+    let author_name = author.and_then(|author| {
+        author
+            .select(&selector(".p-name"))
+            .next()
+            .map(|name| collapse_text(name.text()))
+            .or_else(|| Some(collapse_text(author.text())))
+            .filter(|name| !name.is_empty())
+    });
+    let author_url = author.and_then(|author| {
+        author
+            .value()
+            .attr("href")
+            .or_else(|| {
+                author
+                    .select(&selector(".u-url[href]"))
+                    .next()
+                    .and_then(|url| url.value().attr("href"))
+            })
+            .map(str::to_string)
+    });
+
+    let title = page
+        .select(&selector(".h-entry .p-name, title"))
+        .next()
+        .map(|title| collapse_text(title.text()))
+        .filter(|title| !title.is_empty());
+
+    let excerpt = page
+        .select(&selector(".h-entry .p-summary, .h-entry .e-content"))
+        .next()
+        .map(|content| collapse_text(content.text()))
+        .filter(|text| !text.is_empty())
+        .map(|text| truncate(&text, MAX_EXCERPT_CHARS));
+
+    SourcePage {
+        author_name,
+        author_url,
+        title,
+        excerpt,
+    }
+}
+
+/// Joins text nodes, collapsing runs of whitespace.
+fn collapse_text<'a>(text: impl Iterator<Item = &'a str>) -> String {
+    text.flat_map(str::split_whitespace)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Cuts text to at most `max` characters, marking the cut with an ellipsis.
+fn truncate(text: &str, max: usize) -> String {
+    match text.char_indices().nth(max) {
+        Some((end, _)) => format!("{}…", text[..end].trim_end()),
+        None => text.to_string(),
+    }
+}
+
+/// Parses a selector written in this module.
+fn selector(css: &'static str) -> Selector {
+    Selector::parse(css).expect("static selectors are valid")
+}
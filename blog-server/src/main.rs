@@ -4,125 +4,859 @@ mod domain;
 mod infrastructure;
 mod presentation;
 
+use crate::application::activity_service::ActivityService;
+use crate::application::admin_service::AdminService;
+use crate::application::analytics_service::AnalyticsService;
 use crate::application::auth_service::AuthService;
+use crate::application::avatar_service::AvatarService;
+use crate::application::backup_service::BackupService;
+use crate::application::comment_service::CommentService;
+use crate::application::export_service::ExportService;
+use crate::application::federation_service::FederationService;
+use crate::application::import_service::ImportService;
+use crate::application::ip_rule_service::IpRuleService;
+use crate::application::media_service::MediaService;
+use crate::application::mention_service::MentionService;
+use crate::application::outbox_service::OutboxService;
 use crate::application::post_service::PostService;
+use crate::application::reaction_service::ReactionService;
+use crate::application::session_service::SessionService;
+use crate::application::translation_service::TranslationService;
+use crate::data::activity_repository::PostgresActivityRepository;
+use crate::data::analytics_repository::PostgresAnalyticsRepository;
+use crate::data::comment_repository::PostgresCommentRepository;
+use crate::data::export_repository::PostgresExportRepository;
+use crate::data::federation_repository::PostgresFederationRepository;
+use crate::data::ip_rule_repository::PostgresIpRuleRepository;
+use crate::data::media_repository::PostgresMediaRepository;
+use crate::data::mention_repository::PostgresMentionRepository;
+use crate::data::outbox_repository::PostgresOutboxRepository;
 use crate::data::post_repository::PostgresPostRepository;
+use crate::data::reaction_repository::PostgresReactionRepository;
+use crate::data::refresh_token_repository::PostgresRefreshTokenRepository;
+use crate::data::revision_repository::PostgresRevisionRepository;
+use crate::data::search_index::{MeilisearchIndex, PostgresSearchIndex, SearchIndex};
+use crate::data::translation_repository::PostgresTranslationRepository;
 use crate::data::user_repository::PostgresUserRepository;
+use crate::domain::quota::Quota;
+use crate::infrastructure::build_info;
+use crate::infrastructure::cache::{Cache, CacheBackend, MemoryCache, RedisCache, TieredCache};
+use crate::infrastructure::cleanup::spawn_cleanup;
 use crate::infrastructure::config::AppConfig;
-use crate::infrastructure::database::{create_pool, run_migrations};
+use crate::infrastructure::cors::CorsOrigins;
+use crate::infrastructure::database::{
+    self, create_lazy_pool, create_pool, run_migrations, run_migrations_when_ready, MigrationStatus,
+};
+use crate::infrastructure::event_publisher::{
+    EventBroker, EventPublisher, KafkaPublisher, NatsPublisher,
+};
+use crate::infrastructure::events::EventBus;
+use crate::infrastructure::gravatar::GravatarProxy;
 use crate::infrastructure::logging::init_logging;
+use crate::infrastructure::meilisearch::MeilisearchClient;
+use crate::infrastructure::media_storage::MediaStorage;
+use crate::infrastructure::metrics::Metrics;
+use crate::infrastructure::moderation::{
+    ContentModerator, HttpModerator, ModeratorChain, ProfanityFilter,
+};
+use crate::infrastructure::rate_limit::RateLimiter;
+use crate::infrastructure::remote_media::RemoteMediaFetcher;
+use crate::infrastructure::sanitizer::HtmlSanitizer;
 use crate::infrastructure::security::JwtKeys;
+use crate::infrastructure::spam::{AkismetClassifier, SpamClassifier};
+use crate::infrastructure::static_files::StaticFiles;
+use crate::infrastructure::webmention::WebmentionClient;
+use crate::presentation::broker;
+use crate::presentation::cli::Command;
 use crate::presentation::handler;
-use crate::presentation::middleware::{JwtAuthMiddleware, RequestIdMiddleware};
+use crate::presentation::handler::media::ApiPath;
+use crate::presentation::handler::protected::{LINK_HEADER, TOTAL_COUNT_HEADER};
+use crate::presentation::middleware::deprecation::DEPRECATION_HEADER;
+use crate::presentation::session_cookies::{SessionCookies, CSRF_HEADER};
+use crate::presentation::middleware::{
+    DeprecatedRouteMiddleware, IpFilterMiddleware, JwtAuthMiddleware, RequestIdMiddleware,
+    RequestSpanMiddleware,
+};
 use actix_cors::Cors;
-use actix_web::middleware::{DefaultHeaders, Logger};
-use actix_web::{App, HttpServer, web};
+use actix_web::http::KeepAlive;
+use actix_web::middleware::DefaultHeaders;
+use actix_web::{App, HttpResponse, HttpServer, Scope, web};
 use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
 use crate::presentation::grpc_service::GrpcService;
+use sqlx::PgPool;
 use tonic::transport::Server;
-use tracing::info;
+use tonic_health::server::HealthReporter;
+use tracing::{info, warn};
 use crate::presentation::blog::blog_service_server::BlogServiceServer;
 
+/// Current API version, mounted below the API base path.
+const API_VERSION: &str = "v1";
+
+/// Seconds the HTTP server waits for in-flight requests when shutting down.
+const SHUTDOWN_TIMEOUT_SECS: u64 = 30;
+
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
+    let command = match Command::parse(std::env::args().skip(1)) {
+        Ok(command) => command,
+        Err(usage) => {
+            eprintln!("{}", usage);
+            std::process::exit(2);
+        }
+    };
     let config = AppConfig::from_env().expect("invalid configuration");
 
     init_logging(config.log_format.clone());
+    info!(
+        version = build_info::VERSION,
+        git_sha = build_info::GIT_SHA,
+        built_at = ?build_info::build_time(),
+        features = ?build_info::features(),
+        "blog-server starting"
+    );
 
-    let pool = create_pool(&config.database_url)
-        .await
-        .expect("failed to connect to database");
+    let slow_query = Duration::from_millis(config.slow_query_ms);
+    let migrations = MigrationStatus::default();
+    // Backups and restores need the schema in place before they start.
+    let pool = if config.db_lazy_connect && command == Command::Serve {
+        let pool = create_lazy_pool(&config.database_url, slow_query)
+            .expect("invalid database configuration");
+        tokio::spawn(run_migrations_when_ready(pool.clone(), migrations.clone()));
+        pool
+    } else {
+        let max_wait = Duration::from_secs(config.db_connect_max_wait_secs);
+        let pool = create_pool(&config.database_url, slow_query, max_wait)
+            .await
+            .expect("failed to connect to database");
+        run_migrations(&pool, &migrations)
+            .await
+            .expect("failed to run migrations");
+        pool
+    };
 
-    run_migrations(&pool)
-        .await
-        .expect("failed to run migrations");
+    match &command {
+        Command::Serve => {}
+        Command::Backup { out } => {
+            let backups = backup_service(&pool, &config);
+            return backups.backup(out).await.map(drop).map_err(std::io::Error::other);
+        }
+        Command::Restore { input } => {
+            let backups = backup_service(&pool, &config);
+            return backups.restore(input).await.map(drop).map_err(std::io::Error::other);
+        }
+    }
 
     let config_data = config.clone();
+    let http_pool = pool.clone();
 
     let user_repo = Arc::new(PostgresUserRepository::new(pool.clone()));
     let post_repo = Arc::new(PostgresPostRepository::new(pool.clone()));
+    let comment_repo = Arc::new(PostgresCommentRepository::new(pool.clone()));
+    let events = EventBus::new();
+    let cache = cache(&config).await;
+    info!(backend = %config.cache_backend, "cache configured");
+    // Events are only stored for publishing when there is a broker to publish them to.
+    let outbox_service = if config.event_broker.is_some() {
+        OutboxService::new(Arc::new(PostgresOutboxRepository::new(pool.clone())))
+    } else {
+        OutboxService::disabled()
+    };
     let auth_service = AuthService::new(
         Arc::clone(&user_repo),
         JwtKeys::new(config.jwt_secret.clone()),
+        outbox_service.clone(),
+        Arc::clone(&cache),
+        Duration::from_secs(config.user_cache_ttl_secs),
+    );
+    let admin_service = AdminService::new(Arc::clone(&user_repo), Arc::clone(&cache));
+    let ip_rule_service = IpRuleService::new(
+        Arc::new(PostgresIpRuleRepository::new(pool.clone())),
+        &config.ip_allow_list,
+        &config.ip_deny_list,
+    );
+    if let Err(e) = ip_rule_service.reload().await {
+        warn!(error = %e, "failed to load IP rules; only configured ones apply");
+    }
+    if config.ip_rules_refresh_secs > 0 {
+        ip_rule_service.spawn_refresh(Duration::from_secs(config.ip_rules_refresh_secs));
+    }
+    let session_service = SessionService::new(
+        auth_service.clone(),
+        Arc::new(PostgresRefreshTokenRepository::new(pool.clone())),
+        chrono::Duration::days(config.refresh_token_ttl_days),
+    );
+    let session_cookies = SessionCookies::new(
+        config.cookie_secure,
+        config.api_base_path.clone(),
+        session_service.refresh_ttl(),
+    );
+    let metrics = Metrics::new();
+    let static_files = StaticFiles::from_config(config.static_dir.as_deref());
+    let api_base_path = config.api_base_path.clone();
+    let api_v1_path = format!("{}/{}", config.api_base_path, API_VERSION);
+    let legacy_api_routes = config.legacy_api_routes;
+    let sanitizer = HtmlSanitizer::new(config.html_allowed_tags.as_deref());
+    let moderator = content_moderator(&config);
+    let quota = Quota {
+        max_posts: config.quota_max_posts,
+        max_media_bytes: config.quota_max_media_bytes,
+        max_post_bytes: config.quota_max_post_bytes,
+    };
+    let post_service = PostService::new(
+        Arc::clone(&post_repo),
+        Arc::new(PostgresRevisionRepository::new(pool.clone())),
+        events.clone(),
+        sanitizer.clone(),
+        auth_service.keys().clone(),
+        search_index(&config, &post_repo),
+        outbox_service.clone(),
+        Arc::clone(&cache),
+        Duration::from_secs(config.post_cache_ttl_secs),
+        quota,
+        moderator.clone(),
+    );
+    if config.meilisearch_url.is_some() {
+        post_service.spawn_search_indexer(&events);
+        info!(index = %config.meilisearch_index, "Meilisearch search enabled");
+    }
+    let translation_service = TranslationService::new(
+        Arc::new(PostgresTranslationRepository::new(pool.clone())),
+        Arc::clone(&post_repo),
+        sanitizer.clone(),
+        config.default_post_locale.clone(),
+    );
+    let comment_rate_window = Duration::from_secs(config.comment_rate_window_secs);
+    let comment_service = CommentService::new(
+        Arc::clone(&comment_repo),
+        Arc::clone(&post_repo),
+        events.clone(),
+        sanitizer,
+        spam_classifier(&config),
+        RateLimiter::new(
+            Arc::clone(&cache),
+            "comment_user",
+            config.comment_rate_limit_per_user,
+            comment_rate_window,
+        ),
+        RateLimiter::new(
+            Arc::clone(&cache),
+            "comment_ip",
+            config.comment_rate_limit_per_ip,
+            comment_rate_window,
+        ),
+        outbox_service.clone(),
+        moderator,
+    );
+    let reaction_service = ReactionService::new(
+        Arc::new(PostgresReactionRepository::new(pool.clone())),
+        Arc::clone(&post_repo),
+    );
+    let analytics_service =
+        AnalyticsService::new(Arc::new(PostgresAnalyticsRepository::new(pool.clone())));
+    analytics_service.spawn_flush(Duration::from_secs(config.view_flush_interval_secs.max(1)));
+    let media_repo = Arc::new(PostgresMediaRepository::new(pool.clone()));
+    let media_storage = MediaStorage::new(config.media_dir.clone());
+    let media_service = MediaService::new(
+        Arc::clone(&media_repo),
+        media_storage.clone(),
+        config.max_upload_bytes,
+        quota,
+    );
+    let gravatar = GravatarProxy::new(
+        config.gravatar_url.clone(),
+        config.gravatar_default.clone(),
+        Duration::from_secs(config.gravatar_cache_secs),
+    )
+    .expect("invalid gravatar configuration");
+    let avatar_service =
+        AvatarService::new(Arc::clone(&user_repo), media_service.clone(), gravatar);
+    let import_service = ImportService::new(
+        post_service.clone(),
+        Arc::clone(&user_repo),
+        media_service.clone(),
+        RemoteMediaFetcher::new(Duration::from_secs(config.import_fetch_timeout_secs))
+            .expect("invalid import configuration"),
+        config.import_max_bytes,
+    );
+    let export_service = ExportService::new(
+        Arc::new(PostgresExportRepository::new(pool.clone())),
+        Arc::clone(&user_repo),
+        Arc::clone(&post_repo),
+        comment_repo,
+        media_repo,
+        media_storage,
+        auth_service.keys().clone(),
+        events.clone(),
+        chrono::Duration::hours(config.export_ttl_hours),
+    );
+
+    let activity_service = ActivityService::new(
+        Arc::new(PostgresActivityRepository::new(pool.clone())),
+        Arc::clone(&user_repo),
+    );
+    activity_service.spawn_recorder(&events);
+
+    let federation_service = config
+        .federation_base_url
+        .as_deref()
+        .filter(|_| config.federation_enabled)
+        .map(|base_url| {
+            FederationService::new(
+                Arc::new(PostgresFederationRepository::new(pool.clone())),
+                Arc::clone(&user_repo),
+                Arc::clone(&post_repo),
+                base_url,
+                Duration::from_secs(config.federation_timeout_secs),
+            )
+            .expect("invalid federation configuration")
+        });
+    if let Some(federation_service) = &federation_service {
+        federation_service.spawn_publisher(&events);
+        info!(base_url = ?config.federation_base_url, "ActivityPub federation enabled");
+    }
+
+    let webmention_base_url = config
+        .webmention_base_url
+        .clone()
+        .filter(|_| config.webmention_enabled);
+    let mention_service = MentionService::new(
+        Arc::new(PostgresMentionRepository::new(pool.clone())),
+        Arc::clone(&post_repo),
+        WebmentionClient::new(Duration::from_secs(config.webmention_timeout_secs))
+            .expect("invalid webmention configuration"),
+        webmention_base_url.as_deref(),
+        api_v1_path.clone(),
+        RateLimiter::new(
+            Arc::clone(&cache),
+            "webmention_ip",
+            config.comment_rate_limit_per_ip,
+            comment_rate_window,
+        ),
+    )
+    .expect("invalid webmention configuration");
+    if let Some(base_url) = &webmention_base_url {
+        mention_service.spawn_sender(&events);
+        info!(base_url = %base_url, "webmentions enabled");
+    }
+    // Advertised on every response so any page of the site can be mentioned.
+    let webmention_link = webmention_base_url
+        .as_ref()
+        .map(|base_url| format!("<{}/webmention>; rel=\"webmention\"", base_url));
+
+    if let Some(event_broker) = config.event_broker {
+        broker::spawn_relay(
+            outbox_service.clone(),
+            event_publisher(&config, event_broker).await,
+            Duration::from_millis(config.outbox_relay_interval_ms),
+        );
+        info!(
+            broker = %event_broker,
+            topic = %config.event_broker_topic,
+            "domain event publishing enabled"
+        );
+    }
+
+    spawn_cleanup_tasks(
+        &config,
+        &metrics,
+        &post_service,
+        &comment_service,
+        &session_service,
+        &export_service,
+        &outbox_service,
+        &media_service,
     );
-    let post_service = PostService::new(Arc::clone(&post_repo));
 
     let http_auth_service = auth_service.clone();
     let http_post_service = post_service.clone();
+    let http_comment_service = comment_service.clone();
+    let http_admin_service = admin_service.clone();
+    let http_events = events.clone();
+    let http_analytics_service = analytics_service.clone();
+    let http_session_service = session_service.clone();
 
     // ---------- HTTP server ----------
     let http_server = HttpServer::new(move || {
         let cors = build_cors(&config_data);
+        let mut default_headers = DefaultHeaders::new()
+            .add(("X-Content-Type-Options", "nosniff"))
+            .add(("Referrer-Policy", "no-referrer"))
+            .add(("Permissions-Policy", "geolocation=()"))
+            .add(("Cross-Origin-Opener-Policy", "same-origin"));
+        if let Some(link) = &webmention_link {
+            default_headers = default_headers.add(("Link", link.as_str()));
+        }
         App::new()
-            .wrap(Logger::default())
+            .wrap(IpFilterMiddleware::new(ip_rule_service.clone()))
+            .wrap(RequestSpanMiddleware)
             .wrap(RequestIdMiddleware)
-            .wrap(
-                DefaultHeaders::new()
-                    .add(("X-Content-Type-Options", "nosniff"))
-                    .add(("Referrer-Policy", "no-referrer"))
-                    .add(("Permissions-Policy", "geolocation=()"))
-                    .add(("Cross-Origin-Opener-Policy", "same-origin")),
-            )
+            .wrap(default_headers)
             .wrap(cors)
             .app_data(web::Data::new(http_auth_service.clone()))
+            .app_data(web::Data::new(http_session_service.clone()))
+            .app_data(web::Data::new(session_cookies.clone()))
             .app_data(web::Data::new(http_post_service.clone()))
-            .service(
-                web::scope("/api")
-                    .service(web::scope("/public").service(handler::public::scope()))
-                    .service(
-                        web::scope("/protected")
-                            .wrap(JwtAuthMiddleware::new(http_auth_service.keys().clone()))
-                            .service(handler::protected::scope()),
-                    ),
-            )
+            .app_data(web::Data::new(http_comment_service.clone()))
+            .app_data(web::Data::new(translation_service.clone()))
+            .app_data(web::Data::new(http_admin_service.clone()))
+            .app_data(web::Data::new(ip_rule_service.clone()))
+            .app_data(web::Data::new(media_service.clone()))
+            .app_data(web::Data::new(avatar_service.clone()))
+            .app_data(web::Data::new(export_service.clone()))
+            .app_data(web::Data::new(import_service.clone()))
+            .app_data(web::Data::new(activity_service.clone()))
+            .app_data(web::Data::new(mention_service.clone()))
+            .app_data(web::Data::new(ApiPath(api_v1_path.clone())))
+            .app_data(web::Data::new(reaction_service.clone()))
+            .app_data(web::Data::new(http_analytics_service.clone()))
+            .app_data(web::Data::new(http_events.clone()))
+            .app_data(web::Data::new(metrics.clone()))
+            .app_data(web::Data::new(http_pool.clone()))
+            .app_data(web::Data::new(migrations.clone()))
+            // Versioned routes first: the legacy scope's prefix also matches them.
+            .service(api_scope(&api_v1_path, http_auth_service.keys()))
+            .configure(|cfg| {
+                if legacy_api_routes {
+                    cfg.service(
+                        api_scope(&api_base_path, http_auth_service.keys())
+                            .wrap(DeprecatedRouteMiddleware::new(&api_base_path, &api_v1_path)),
+                    );
+                }
+            })
+            // Other API paths get a 404 instead of the frontend, with or without legacy routes.
+            .service(web::scope(&api_base_path).default_service(web::to(HttpResponse::NotFound)))
+            .route("/metrics", web::get().to(handler::metrics::metrics))
+            .configure(|cfg| {
+                if let Some(federation) = &federation_service {
+                    cfg.app_data(web::Data::new(federation.clone()))
+                        .route(
+                            "/.well-known/webfinger",
+                            web::get().to(handler::federation::webfinger),
+                        )
+                        .service(handler::federation::scope());
+                }
+                if webmention_link.is_some() {
+                    cfg.route("/webmention", web::post().to(handler::webmention::receive));
+                }
+            })
+            .service(handler::health::scope())
+            .configure(|cfg| {
+                if let Some(files) = &static_files {
+                    cfg.app_data(web::Data::new(files.clone()))
+                        .default_service(web::to(handler::frontend::frontend));
+                }
+            })
+    })
+    .keep_alive(match config.http_keep_alive_secs {
+        0 => KeepAlive::Disabled,
+        secs => KeepAlive::Timeout(Duration::from_secs(secs)),
     })
-    .bind((config.host.as_str(), config.http_port))?
-    .run();
+    .client_request_timeout(Duration::from_millis(config.http_client_request_timeout_ms))
+    .client_disconnect_timeout(Duration::from_millis(config.http_client_disconnect_timeout_ms))
+    .max_connections(config.http_max_connections)
+    .backlog(config.http_backlog);
+    let http_server = match config.http_workers {
+        0 => http_server,
+        workers => http_server.workers(workers),
+    };
+    let http_server = http_server
+        .bind((config.host.as_str(), config.http_port))?
+        .disable_signals()
+        .shutdown_timeout(SHUTDOWN_TIMEOUT_SECS)
+        .run();
+    let http_handle = http_server.handle();
+
+    // Either server exiting, or a signal, asks both to drain and stop.
+    let (shutdown_tx, shutdown_rx) = watch::channel(false);
 
     // ---------- gRPC server ----------
     let grpc_addr = format!("{}:{}", config.host, config.grpc_port)
         .parse()
         .expect("invalid grpc addr");
 
-    let grpc_service = GrpcService::new(post_service.clone(), auth_service.clone());
+    let grpc_service = GrpcService::new(
+        post_service.clone(),
+        auth_service.clone(),
+        session_service.clone(),
+        comment_service.clone(),
+        events.clone(),
+    );
+
+    let (health_reporter, health_service) = tonic_health::server::health_reporter();
+    tokio::spawn(report_grpc_health(health_reporter, pool.clone()));
 
     let grpc_server = Server::builder()
+        .add_service(health_service)
         .add_service(BlogServiceServer::new(grpc_service))
-        .serve(grpc_addr);
+        .serve_with_shutdown(grpc_addr, shutdown_requested(shutdown_rx.clone()));
 
     info!(host=config.host ,port=config.grpc_port, "staring gRPC server");
 
-    tokio::select!{
-        http_res = http_server => {
-            if let Err(e) = http_res {
-                eprintln!("HTTP server error: {e}");
-                return Err(e);
-            }
+    tokio::spawn({
+        let shutdown_tx = shutdown_tx.clone();
+        async move {
+            shutdown_signal().await;
+            info!("shutdown signal received, draining in-flight requests");
+            shutdown_tx.send_replace(true);
         }
-        grpc_res = grpc_server => {
-            if let Err(e) = grpc_res {
-                eprintln!("gRPC server error: {e}");
-                return Err(std::io::Error::new(std::io::ErrorKind::Other, e));
+    });
+    tokio::spawn(async move {
+        shutdown_requested(shutdown_rx).await;
+        http_handle.stop(true).await;
+    });
+
+    let http = async {
+        let res = http_server.await;
+        shutdown_tx.send_replace(true);
+        res
+    };
+    let grpc = async {
+        let res = grpc_server.await;
+        shutdown_tx.send_replace(true);
+        res
+    };
+    let (http_res, grpc_res) = tokio::join!(http, grpc);
+
+    if let Err(e) = analytics_service.flush().await {
+        warn!(error = %e, "failed to record post views on shutdown");
+    }
+
+    if let Err(e) = &grpc_res {
+        eprintln!("gRPC server error: {e}");
+    }
+    if let Err(e) = http_res {
+        eprintln!("HTTP server error: {e}");
+        return Err(e);
+    }
+    grpc_res.map_err(std::io::Error::other)?;
+
+    info!("servers stopped");
+    Ok(())
+}
+
+/// Resolves when the process receives Ctrl+C or, on Unix, SIGTERM.
+async fn shutdown_signal() {
+    let ctrl_c = async {
+        let _ = tokio::signal::ctrl_c().await;
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
             }
+            Err(_) => std::future::pending().await,
         }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => {}
+        _ = terminate => {}
     }
+}
 
-    Ok(())
+/// Resolves once a shutdown has been requested on the channel.
+async fn shutdown_requested(mut shutdown: watch::Receiver<bool>) {
+    let _ = shutdown.wait_for(|stop| *stop).await;
+}
+
+/// Keeps the gRPC health status of the blog service in line with database reachability.
+async fn report_grpc_health(mut reporter: HealthReporter, pool: PgPool) {
+    let mut interval = tokio::time::interval(Duration::from_secs(5));
+
+    loop {
+        interval.tick().await;
+        match database::ping(&pool).await {
+            Ok(()) => reporter.set_serving::<BlogServiceServer<GrpcService>>().await,
+            Err(_) => reporter.set_not_serving::<BlogServiceServer<GrpcService>>().await,
+        }
+    }
 }
 
+/// Starts the background tasks purging expired data, each on its own schedule.
+fn spawn_cleanup_tasks(
+    config: &AppConfig,
+    metrics: &Metrics,
+    posts: &handler::protected::Posts,
+    comments: &handler::protected::Comments,
+    sessions: &handler::public::Sessions,
+    exports: &handler::export::Exports,
+    outbox: &OutboxService,
+    media: &handler::media::Media,
+) {
+    let retention = chrono::Duration::days(config.post_trash_retention_days);
+    let posts = posts.clone();
+    spawn_cleanup(
+        "post_trash",
+        Duration::from_secs(config.cleanup_trash_interval_secs),
+        metrics.clone(),
+        move || {
+            let posts = posts.clone();
+            async move { posts.purge_trash(retention).await }
+        },
+    );
+
+    let retention = chrono::Duration::days(config.deleted_comment_retention_days);
+    let comments = comments.clone();
+    spawn_cleanup(
+        "deleted_comments",
+        Duration::from_secs(config.cleanup_comments_interval_secs),
+        metrics.clone(),
+        move || {
+            let comments = comments.clone();
+            async move { comments.purge_deleted(retention).await }
+        },
+    );
+
+    let expired = sessions.clone();
+    spawn_cleanup(
+        "expired_refresh_tokens",
+        Duration::from_secs(config.cleanup_refresh_tokens_interval_secs),
+        metrics.clone(),
+        move || {
+            let sessions = expired.clone();
+            async move { sessions.purge_expired_tokens().await }
+        },
+    );
+
+    let retention = chrono::Duration::days(config.ended_session_retention_days);
+    let sessions = sessions.clone();
+    spawn_cleanup(
+        "ended_sessions",
+        Duration::from_secs(config.cleanup_sessions_interval_secs),
+        metrics.clone(),
+        move || {
+            let sessions = sessions.clone();
+            async move { sessions.purge_ended_sessions(retention).await }
+        },
+    );
+
+    let exports = exports.clone();
+    spawn_cleanup(
+        "expired_exports",
+        Duration::from_secs(config.cleanup_exports_interval_secs),
+        metrics.clone(),
+        move || {
+            let exports = exports.clone();
+            async move { exports.purge_expired().await }
+        },
+    );
+
+    let retention = chrono::Duration::hours(config.outbox_retention_hours);
+    let outbox = outbox.clone();
+    spawn_cleanup(
+        "delivered_outbox",
+        Duration::from_secs(config.cleanup_outbox_interval_secs),
+        metrics.clone(),
+        move || {
+            let outbox = outbox.clone();
+            async move { outbox.purge_delivered(retention).await }
+        },
+    );
+
+    let ttl = chrono::Duration::hours(config.upload_ttl_hours);
+    let uploads = media.clone();
+    spawn_cleanup(
+        "stale_uploads",
+        Duration::from_secs(config.cleanup_uploads_interval_secs),
+        metrics.clone(),
+        move || {
+            let media = uploads.clone();
+            async move { media.purge_stale_uploads(ttl).await }
+        },
+    );
+
+    let media = media.clone();
+    spawn_cleanup(
+        "media_blobs",
+        Duration::from_secs(config.cleanup_media_blobs_interval_secs),
+        metrics.clone(),
+        move || {
+            let media = media.clone();
+            async move { media.collect_garbage().await }
+        },
+    );
+}
+
+/// Returns the spam classifier new comments are checked with, if one is configured.
+fn spam_classifier(config: &AppConfig) -> Option<Arc<dyn SpamClassifier>> {
+    let url = config.spam_classifier_url.clone()?;
+    let classifier = AkismetClassifier::new(
+        url,
+        config.spam_classifier_key.clone(),
+        config.spam_classifier_site.clone(),
+    )
+    .expect("invalid spam classifier configuration");
+
+    Some(Arc::new(classifier))
+}
+
+/// Returns the moderator new and updated posts and new comments are checked
+/// with: the profanity filter and the external moderation service, whichever
+/// are configured.
+fn content_moderator(config: &AppConfig) -> Option<Arc<dyn ContentModerator>> {
+    let mut moderators: Vec<Arc<dyn ContentModerator>> = Vec::new();
+    if !config.profanity_words.is_empty() {
+        moderators.push(Arc::new(ProfanityFilter::new(
+            &config.profanity_words,
+            config.profanity_action,
+        )));
+    }
+    if let Some(url) = config.moderation_url.clone() {
+        let moderator = HttpModerator::new(url, config.moderation_token.clone())
+            .expect("invalid moderation service configuration");
+        moderators.push(Arc::new(moderator));
+    }
+
+    if moderators.is_empty() {
+        return None;
+    }
+    info!(moderators = moderators.len(), "content moderation enabled");
+    Some(Arc::new(ModeratorChain::new(moderators)))
+}
+
+/// Returns the cache users, posts and rate limit counters are kept in, as
+/// selected by `CACHE_BACKEND`.
+async fn cache(config: &AppConfig) -> Arc<dyn Cache> {
+    let memory = Arc::new(MemoryCache::new(config.cache_memory_capacity));
+    let Some(url) = config
+        .redis_url
+        .as_deref()
+        .filter(|_| config.cache_backend != CacheBackend::Memory)
+    else {
+        return memory;
+    };
+    let redis = Arc::new(
+        RedisCache::connect(url, config.cache_key_prefix.clone())
+            .await
+            .expect("failed to connect to Redis"),
+    );
+
+    match config.cache_backend {
+        CacheBackend::Tiered => Arc::new(TieredCache::new(
+            memory,
+            redis,
+            Duration::from_secs(config.cache_near_ttl_secs),
+        )),
+        _ => redis,
+    }
+}
+
+/// Connects to the message broker domain events are published to.
+async fn event_publisher(config: &AppConfig, broker: EventBroker) -> Arc<dyn EventPublisher> {
+    let url = &config.event_broker_url;
+    let topic = config.event_broker_topic.clone();
+
+    match broker {
+        EventBroker::Nats => Arc::new(
+            NatsPublisher::connect(url, topic)
+                .await
+                .expect("failed to connect to NATS"),
+        ),
+        EventBroker::Kafka => Arc::new(
+            KafkaPublisher::connect(url, topic)
+                .await
+                .expect("failed to connect to Kafka"),
+        ),
+    }
+}
+
+/// Returns the index post searches are answered from: Meilisearch if it is
+/// configured, PostgreSQL full-text search otherwise.
+fn search_index(
+    config: &AppConfig,
+    posts: &Arc<PostgresPostRepository>,
+) -> Arc<dyn SearchIndex> {
+    let Some(url) = &config.meilisearch_url else {
+        return Arc::new(PostgresSearchIndex::new(Arc::clone(posts)));
+    };
+    let client = MeilisearchClient::new(
+        url,
+        config.meilisearch_api_key.clone(),
+        &config.meilisearch_index,
+    )
+    .expect("invalid Meilisearch configuration");
+
+    Arc::new(MeilisearchIndex::new(client, Arc::clone(posts)))
+}
+
+/// Returns the service behind the `backup` and `restore` subcommands.
+fn backup_service(
+    pool: &PgPool,
+    config: &AppConfig,
+) -> BackupService<
+    PostgresUserRepository,
+    PostgresPostRepository,
+    PostgresCommentRepository,
+    PostgresMediaRepository,
+> {
+    BackupService::new(
+        Arc::new(PostgresUserRepository::new(pool.clone())),
+        Arc::new(PostgresPostRepository::new(pool.clone())),
+        Arc::new(PostgresCommentRepository::new(pool.clone())),
+        Arc::new(PostgresMediaRepository::new(pool.clone())),
+        MediaStorage::new(config.media_dir.clone()),
+    )
+}
+
+/// Returns the HTTP API mounted at `path`.
+///
+/// Paths under `path` that match no route get a 404, never the frontend.
+fn api_scope(path: &str, keys: &JwtKeys) -> Scope {
+    web::scope(path)
+        .service(
+            web::scope("/public")
+                .service(handler::media::public_scope())
+                .service(handler::account::public_scope())
+                .service(handler::export::public_scope())
+                .service(handler::public::scope()),
+        )
+        .service(
+            web::scope("/protected")
+                .wrap(JwtAuthMiddleware::new(keys.clone()))
+                .service(handler::admin::scope())
+                .service(handler::account::scope())
+                .service(handler::activity::scope())
+                .service(handler::analytics::scope())
+                .service(handler::export::scope())
+                .service(handler::media::scope())
+                .service(handler::translation::scope())
+                .service(handler::protected::scope()),
+        )
+        .default_service(web::to(HttpResponse::NotFound))
+}
+
+/// Builds the CORS policy.
+///
+/// Credentials are only allowed for listed origins; browsers reject them
+/// alongside a wildcard `Access-Control-Allow-Origin`.
 fn build_cors(config: &AppConfig) -> Cors {
-    let mut cors = Cors::default()
+    let cors = Cors::default()
         .allowed_methods(vec!["GET", "POST", "PUT", "DELETE"])
         .allowed_headers(vec![
             actix_web::http::header::CONTENT_TYPE,
             actix_web::http::header::AUTHORIZATION,
         ])
-        .supports_credentials()
+        .allowed_header(CSRF_HEADER)
+        .expose_headers(vec![TOTAL_COUNT_HEADER, LINK_HEADER, DEPRECATION_HEADER])
         .max_age(3600);
 
-    for origin in &config.cors_origins {
-        cors = cors.allowed_origin(origin);
+    match &config.cors_origins {
+        CorsOrigins::Any => cors.allow_any_origin().send_wildcard(),
+        CorsOrigins::List(rules) => {
+            let rules = rules.clone();
+            cors.allowed_origin_fn(move |origin, _| {
+                origin
+                    .to_str()
+                    .is_ok_and(|origin| rules.iter().any(|rule| rule.matches(origin)))
+            })
+            .supports_credentials()
+        }
     }
-
-    cors
 }
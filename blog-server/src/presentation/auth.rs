@@ -48,10 +48,14 @@ pub async fn extract_user_from_token(
         Uuid::parse_str(&claims.sub).map_err(|_| ErrorUnauthorized("invalid token"))?;
 
     let user = auth_service
-        .get_user(user_id)
+        .token_user(user_id)
         .await
         .map_err(|_| ErrorUnauthorized("user not found"))?;
 
+    if user.disabled {
+        return Err(ErrorUnauthorized("account is disabled"));
+    }
+
     Ok(AuthenticatedUser {
         id: user.id,
         email: user.email,
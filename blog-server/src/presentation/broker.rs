@@ -0,0 +1,100 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use prost::Message;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::{debug, warn};
+
+use crate::application::outbox_service::OutboxService;
+use crate::domain::outbox::{OutboxEvent, OutboxMessage};
+use crate::infrastructure::event_publisher::EventPublisher;
+use crate::presentation::blog::domain_event::Event;
+use crate::presentation::blog::{CommentCreated, DomainEvent, PostPublished, UserRegistered};
+
+/// Outbox events published per batch.
+const RELAY_BATCH: i64 = 100;
+
+/// Publishes the events stored in `outbox` to the message broker in a
+/// background task, checking for new ones every `poll_interval`.
+///
+/// Events are encoded as protobuf `DomainEvent` messages whose `id` is the
+/// outbox message ID, so consumers can drop the duplicates at-least-once
+/// delivery may produce. An event the broker refuses is retried on the next
+/// check, ahead of any later ones.
+pub fn spawn_relay(
+    outbox: OutboxService,
+    publisher: Arc<dyn EventPublisher>,
+    poll_interval: Duration,
+) {
+    tokio::spawn(async move {
+        let mut ticker = interval(poll_interval);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+        loop {
+            ticker.tick().await;
+
+            // A full batch means more may be waiting; drain them first.
+            loop {
+                match outbox.relay(publisher.as_ref(), encode, RELAY_BATCH).await {
+                    Ok(published) => {
+                        if published > 0 {
+                            debug!(published, "outbox events published");
+                        }
+                        if (published as i64) < RELAY_BATCH {
+                            break;
+                        }
+                    }
+                    Err(e) => {
+                        warn!(error = %e, "outbox relay failed");
+                        break;
+                    }
+                }
+            }
+        }
+    });
+}
+
+/// Encodes an outbox message as a protobuf `DomainEvent`.
+fn encode(message: &OutboxMessage) -> Vec<u8> {
+    let event = match &message.event {
+        OutboxEvent::UserRegistered { user_id, username } => {
+            Event::UserRegistered(UserRegistered {
+                user_id: user_id.to_string(),
+                username: username.clone(),
+            })
+        }
+        OutboxEvent::PostPublished {
+            post_id,
+            author_id,
+            title,
+            slug,
+            tags,
+        } => Event::PostPublished(PostPublished {
+            post_id: post_id.to_string(),
+            author_id: author_id.to_string(),
+            title: title.clone(),
+            slug: slug.clone(),
+            tags: tags.clone(),
+        }),
+        OutboxEvent::CommentCreated {
+            comment_id,
+            post_id,
+            author_id,
+            parent_id,
+            post_author_id,
+        } => Event::CommentCreated(CommentCreated {
+            comment_id: comment_id.to_string(),
+            post_id: post_id.to_string(),
+            author_id: author_id.to_string(),
+            parent_id: parent_id.map(|id| id.to_string()).unwrap_or_default(),
+            post_author_id: post_author_id.to_string(),
+        }),
+    };
+
+    DomainEvent {
+        id: message.id.to_string(),
+        occurred_at: message.created_at.to_rfc3339(),
+        event: Some(event),
+    }
+    .encode_to_vec()
+}
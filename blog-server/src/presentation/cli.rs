@@ -0,0 +1,42 @@
+use std::path::PathBuf;
+
+/// Usage printed when the command line cannot be parsed.
+pub const USAGE: &str =
+    "usage: blog-server [serve | backup --out <file.tar.zst> | restore --in <file.tar.zst>]";
+
+/// What the server binary was asked to do.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Serves the HTTP and gRPC APIs; the default.
+    Serve,
+
+    /// Writes a backup of the instance to a file and exits.
+    Backup {
+        /// Archive to write.
+        out: PathBuf,
+    },
+
+    /// Restores a backup into an instance without users and exits.
+    Restore {
+        /// Archive to read.
+        input: PathBuf,
+    },
+}
+
+impl Command {
+    /// Parses the arguments following the program name; returns the usage
+    /// on anything unexpected.
+    pub fn parse(args: impl IntoIterator<Item = String>) -> Result<Self, &'static str> {
+        let args: Vec<String> = args.into_iter().collect();
+        let args: Vec<&str> = args.iter().map(String::as_str).collect();
+
+        match args.as_slice() {
+            [] | ["serve"] => Ok(Command::Serve),
+            ["backup", "--out", out] => Ok(Command::Backup { out: out.into() }),
+            ["restore", "--in", input] => Ok(Command::Restore {
+                input: input.into(),
+            }),
+            _ => Err(USAGE),
+        }
+    }
+}
@@ -1,29 +1,107 @@
-use crate::domain::post::Post;
-use chrono::{DateTime, Utc};
+use crate::domain::activity::{Activity, ActivityKind};
+use crate::domain::analytics::{AgentClass, AgentViews, DailyViews, PostStats, ReferrerViews};
+use crate::domain::comment::{Comment, CommentStatus};
+use crate::domain::error::DomainError;
+use crate::domain::export::{DataExport, ExportStatus};
+use crate::domain::import::ImportFormat;
+use crate::domain::ip_rule::{IpNet, IpRule, IpRuleAction};
+use crate::domain::media::{Media, MediaUpload, MediaVariant, VariantName};
+use crate::domain::mention::{Mention, MentionStatus};
+use crate::domain::moderation::FlaggedPost;
+use crate::domain::post::{Post, Visibility};
+use crate::domain::quota::QuotaUsage;
+use crate::domain::reaction::Reactions;
+use crate::domain::revision::PostRevision;
+use crate::domain::search::SearchHit;
+use crate::domain::tag::Tag;
+use crate::domain::translation::{LocalizedPost, PostTranslation};
+use crate::domain::user::{Role, User};
+use blog_core::error::ErrorCode;
+use chrono::{DateTime, NaiveDate, Utc};
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use uuid::Uuid;
 
-/// User registration request payload.
-#[derive(Debug, Deserialize)]
-pub struct RegisterRequest {
-    /// Desired username.
-    pub username: String,
+// Payloads shared with the clients through blog-core, under the names the
+// handlers use for them.
+pub use blog_core::auth::{
+    AuthTokens as TokenResponse, CookieSession as CookieSessionResponse, LoginRequest,
+    PasswordChange as ChangePasswordRequest, Profile as ProfileResponse,
+    ProfileUpdate as UpdateProfileRequest, RegisterRequest,
+};
+pub use blog_core::comment::{Comment as CommentResponse, CommentRequest as CreateCommentRequest};
+pub use blog_core::media::{
+    Media as MediaResponse, MediaUpload as MediaUploadResponse, MediaVariants as MediaVariantUrls,
+};
+pub use blog_core::post::{
+    Author as AuthorResponse, Post as PostResponse, PostRequest as CreatePostRequest,
+    SearchResult as SearchResultResponse, Tag as TagResponse,
+};
 
-    /// User email address.
-    pub email: String,
+impl From<Visibility> for blog_core::post::Visibility {
+    fn from(visibility: Visibility) -> Self {
+        match visibility {
+            Visibility::Public => Self::Public,
+            Visibility::Unlisted => Self::Unlisted,
+            Visibility::Private => Self::Private,
+        }
+    }
+}
+
+impl From<blog_core::post::Visibility> for Visibility {
+    fn from(visibility: blog_core::post::Visibility) -> Self {
+        match visibility {
+            blog_core::post::Visibility::Public => Self::Public,
+            blog_core::post::Visibility::Unlisted => Self::Unlisted,
+            blog_core::post::Visibility::Private => Self::Private,
+        }
+    }
+}
+
+impl From<CommentStatus> for blog_core::comment::CommentStatus {
+    fn from(status: CommentStatus) -> Self {
+        match status {
+            CommentStatus::Published => Self::Published,
+            CommentStatus::Flagged => Self::Flagged,
+        }
+    }
+}
+
+impl From<Role> for blog_core::auth::Role {
+    fn from(role: Role) -> Self {
+        match role {
+            Role::User => Self::User,
+            Role::Admin => Self::Admin,
+        }
+    }
+}
 
-    /// User plaintext password.
-    pub password: String,
+impl From<User> for ProfileResponse {
+    /// Converts a domain user into a profile response DTO.
+    fn from(user: User) -> Self {
+        Self {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            bio: user.bio,
+            role: user.role.into(),
+            created_at: user.created_at,
+        }
+    }
 }
 
-/// User login request payload.
+/// Avatar selection request payload.
 #[derive(Debug, Deserialize)]
-pub struct LoginRequest {
-    /// User email address.
-    pub email: String,
+pub struct SetAvatarRequest {
+    /// Uploaded image to use as avatar.
+    pub media_id: Uuid,
+}
 
-    /// User plaintext password.
-    pub password: String,
+/// Avatar query parameters.
+#[derive(Debug, Deserialize)]
+pub struct AvatarQuery {
+    /// Size to display the avatar at, in pixels.
+    pub size: Option<u32>,
 }
 
 /// Health check response.
@@ -32,55 +110,944 @@ pub struct HealthResponse {
     /// Service status.
     pub status: &'static str,
 
+    /// Server version.
+    pub version: &'static str,
+
     /// Current server timestamp.
     pub timestamp: DateTime<Utc>,
 }
 
-/// JWT token response.
+/// Build information of the running server.
 #[derive(Debug, Serialize)]
-pub struct TokenResponse {
-    /// Access token.
-    pub access_token: String,
+pub struct VersionResponse {
+    /// Server version.
+    pub version: &'static str,
+
+    /// Abbreviated git commit the server was built from, or `unknown`.
+    pub git_sha: &'static str,
+
+    /// When the server was built.
+    pub build_timestamp: Option<DateTime<Utc>>,
+
+    /// Cargo features the server was built with.
+    pub features: Vec<&'static str>,
 }
 
-/// Post response payload.
+/// Readiness check response.
 #[derive(Debug, Serialize)]
-pub struct PostResponse {
-    /// Post identifier.
-    pub id: Uuid,
+pub struct ReadinessResponse {
+    /// `ok` when every dependency is healthy, `unavailable` otherwise.
+    pub status: &'static str,
 
-    /// Post author identifier.
-    pub author_id: Uuid,
+    /// Server version.
+    pub version: &'static str,
 
-    /// Post title.
-    pub title: String,
+    /// Health of each dependency, keyed by name.
+    pub checks: BTreeMap<&'static str, DependencyHealth>,
+}
 
-    /// Post content.
-    pub content: String,
+/// Health of a single server dependency.
+#[derive(Debug, Serialize)]
+pub struct DependencyHealth {
+    /// `ok` or `error`.
+    pub status: &'static str,
 
-    /// Post creation timestamp.
-    pub created_at: DateTime<Utc>,
+    /// Time the check took, in milliseconds.
+    pub latency_ms: u64,
+
+    /// Failure reason, if the check failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
 }
 
-/// Post creation request payload.
+/// How a login or refresh hands the session to the client.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionMode {
+    /// Tokens in the response body, sent back as a `Bearer` header.
+    #[default]
+    Token,
+
+    /// Tokens in `HttpOnly` cookies, with a CSRF token for unsafe requests.
+    Cookie,
+}
+
+/// Query parameters of the login endpoint.
 #[derive(Debug, Deserialize)]
-pub struct CreatePostRequest {
-    /// Post title.
+pub struct SessionQuery {
+    /// Session delivery; `token` when omitted.
+    #[serde(default)]
+    pub mode: SessionMode,
+}
+
+/// Refresh and logout request payload.
+///
+/// Cookie sessions send no body; the refresh token comes from its cookie.
+#[derive(Debug, Default, Deserialize)]
+pub struct RefreshRequest {
+    /// Refresh token of a token-mode session.
+    pub refresh_token: Option<String>,
+}
+
+/// Pagination query parameters for list endpoints.
+#[derive(Debug, Deserialize)]
+pub struct PageQuery {
+    /// Maximum number of items to return.
+    pub limit: Option<u32>,
+
+    /// Number of items to skip.
+    pub offset: Option<u32>,
+}
+
+/// Post list query parameters.
+#[derive(Debug, Deserialize)]
+pub struct PostListQuery {
+    /// Maximum number of posts to return.
+    pub limit: Option<u32>,
+
+    /// Number of posts to skip.
+    pub offset: Option<u32>,
+
+    /// Only return posts carrying this tag.
+    pub tag: Option<String>,
+}
+
+/// Post search query parameters.
+#[derive(Debug, Deserialize)]
+pub struct SearchQuery {
+    /// Search terms, in web-search syntax.
+    pub q: String,
+
+    /// Maximum number of results to return.
+    pub limit: Option<u32>,
+
+    /// Number of results to skip.
+    pub offset: Option<u32>,
+
+    /// Only return posts carrying this tag.
+    pub tag: Option<String>,
+}
+
+/// Search index rebuild response payload.
+#[derive(Debug, Serialize)]
+pub struct ReindexResponse {
+    /// Number of public posts sent to the search index.
+    pub indexed: u64,
+}
+
+/// Share link response payload for an unlisted post.
+#[derive(Debug, Serialize)]
+pub struct ShareLinkResponse {
+    /// Identifier of the shared post.
+    pub post_id: Uuid,
+
+    /// Token to read the post with at `GET /posts/shared/{token}`.
+    pub token: String,
+}
+
+/// Post translation request payload.
+#[derive(Debug, Deserialize)]
+pub struct TranslationRequest {
+    /// Translated title.
     pub title: String,
 
-    /// Post content.
+    /// Translated content.
     pub content: String,
 }
 
+/// Message a live comment socket client sends, tagged by `type`.
+#[derive(Debug, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LiveCommentRequest {
+    /// Authenticates the socket with an access token.
+    Auth {
+        /// JWT access token.
+        token: String,
+    },
+
+    /// Adds a comment to the post, as the authenticated user.
+    Comment {
+        /// Comment text.
+        content: String,
+
+        /// Comment being replied to, if any.
+        #[serde(default)]
+        parent_id: Option<Uuid>,
+    },
+}
+
+/// Message the server sends on a live comment socket, tagged by `type`.
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum LiveCommentMessage {
+    /// The socket is authenticated and may submit comments.
+    Authenticated {
+        /// Authenticated user ID.
+        user_id: Uuid,
+    },
+
+    /// A comment was published on the post, or the sender's own comment
+    /// was held for moderation.
+    Comment(CommentResponse),
+
+    /// A request sent over the socket failed.
+    Error {
+        /// Stable error code, as in HTTP error responses.
+        code: ErrorCode,
+
+        /// Human-readable error message.
+        error: String,
+    },
+}
+
+impl From<DomainError> for LiveCommentMessage {
+    /// Converts a domain error into a socket error message.
+    fn from(err: DomainError) -> Self {
+        Self::Error {
+            code: err.code(),
+            error: err.to_string(),
+        }
+    }
+}
+
+/// Moderation queue entry: the comment plus why it was flagged.
+#[derive(Debug, Serialize)]
+pub struct FlaggedCommentResponse {
+    /// Flagged comment.
+    #[serde(flatten)]
+    pub comment: CommentResponse,
+
+    /// Why the comment was flagged.
+    pub flag_reason: Option<String>,
+}
+
+/// Review queue entry: the post plus why and when it was flagged.
+#[derive(Debug, Serialize)]
+pub struct FlaggedPostResponse {
+    /// Flagged post.
+    #[serde(flatten)]
+    pub post: PostResponse,
+
+    /// Why the post was flagged.
+    pub flag_reason: String,
+
+    /// When the post was flagged.
+    pub flagged_at: DateTime<Utc>,
+}
+
 impl From<Post> for PostResponse {
     /// Converts a domain post into an HTTP response DTO.
     fn from(post: Post) -> Self {
         Self {
             id: post.id,
             author_id: post.author_id,
+            author: post.author_username.map(|username| AuthorResponse {
+                id: post.author_id,
+                username,
+            }),
             title: post.title,
+            slug: post.slug,
             content: post.content,
+            tags: post.tags,
+            visibility: post.visibility.into(),
             created_at: post.created_at,
+            updated_at: post.updated_at,
+            deleted_at: post.deleted_at,
+            locale: None,
+        }
+    }
+}
+
+impl From<LocalizedPost> for PostResponse {
+    /// Converts a post served in a reader's language into an HTTP response DTO.
+    fn from(localized: LocalizedPost) -> Self {
+        Self {
+            locale: Some(localized.locale),
+            ..Self::from(localized.post)
+        }
+    }
+}
+
+impl From<PostTranslation> for TranslationResponse {
+    /// Converts a domain post translation into an HTTP response DTO.
+    fn from(translation: PostTranslation) -> Self {
+        Self {
+            post_id: translation.post_id,
+            locale: translation.locale,
+            title: translation.title,
+            content: translation.content,
+            created_at: translation.created_at,
+            updated_at: translation.updated_at,
+        }
+    }
+}
+
+impl From<SearchHit> for SearchResultResponse {
+    /// Converts a search hit into an HTTP response DTO.
+    fn from(hit: SearchHit) -> Self {
+        Self {
+            post: PostResponse::from(hit.post),
+            rank: hit.rank,
+            snippet: hit.snippet,
+        }
+    }
+}
+
+impl From<Comment> for CommentResponse {
+    /// Converts a domain comment into an HTTP response DTO.
+    fn from(comment: Comment) -> Self {
+        Self {
+            id: comment.id,
+            post_id: comment.post_id,
+            author_id: comment.author_id,
+            parent_id: comment.parent_id,
+            content: comment.content,
+            status: comment.status.into(),
+            created_at: comment.created_at,
+        }
+    }
+}
+
+impl From<Comment> for FlaggedCommentResponse {
+    /// Converts a flagged domain comment into a moderation queue DTO.
+    fn from(mut comment: Comment) -> Self {
+        let flag_reason = comment.flag_reason.take();
+
+        Self {
+            comment: CommentResponse::from(comment),
+            flag_reason,
+        }
+    }
+}
+
+impl From<FlaggedPost> for FlaggedPostResponse {
+    /// Converts a flagged post into a review queue DTO.
+    fn from(flagged: FlaggedPost) -> Self {
+        Self {
+            post: PostResponse::from(flagged.post),
+            flag_reason: flagged.reason,
+            flagged_at: flagged.flagged_at,
+        }
+    }
+}
+
+impl From<Tag> for TagResponse {
+    /// Converts a domain tag into an HTTP response DTO.
+    fn from(tag: Tag) -> Self {
+        Self {
+            name: tag.name,
+            post_count: tag.post_count.max(0) as u64,
+        }
+    }
+}
+
+/// Like and bookmark counts of a post.
+#[derive(Debug, Serialize)]
+pub struct ReactionResponse {
+    /// Post identifier.
+    pub post_id: Uuid,
+
+    /// Number of likes.
+    pub likes: i64,
+
+    /// Number of bookmarks.
+    pub bookmarks: i64,
+
+    /// Whether the authenticated user liked the post.
+    pub liked: bool,
+
+    /// Whether the authenticated user bookmarked the post.
+    pub bookmarked: bool,
+}
+
+impl From<Reactions> for ReactionResponse {
+    /// Converts reaction counts into an HTTP response DTO.
+    fn from(reactions: Reactions) -> Self {
+        Self {
+            post_id: reactions.post_id,
+            likes: reactions.likes,
+            bookmarks: reactions.bookmarks,
+            liked: reactions.liked,
+            bookmarked: reactions.bookmarked,
+        }
+    }
+}
+
+/// Post stats query parameters.
+#[derive(Debug, Deserialize)]
+pub struct StatsQuery {
+    /// Number of days to cover, today included; 30 when omitted, at most 365.
+    pub days: Option<u32>,
+}
+
+/// Reads of a post on one day.
+#[derive(Debug, Serialize)]
+pub struct DailyViewsResponse {
+    /// Day, in UTC.
+    pub day: NaiveDate,
+
+    /// Number of reads.
+    pub views: i64,
+}
+
+impl From<DailyViews> for DailyViewsResponse {
+    /// Converts daily views into an HTTP response DTO.
+    fn from(daily: DailyViews) -> Self {
+        Self {
+            day: daily.day,
+            views: daily.views,
+        }
+    }
+}
+
+/// Post stats response payload.
+#[derive(Debug, Serialize)]
+pub struct PostStatsResponse {
+    /// Post identifier.
+    pub post_id: Uuid,
+
+    /// Post title.
+    pub title: String,
+
+    /// Reads over the period.
+    pub views: i64,
+
+    /// Likes given over the period.
+    pub likes: i64,
+
+    /// Bookmarks added over the period.
+    pub bookmarks: i64,
+
+    /// Published comments written over the period.
+    pub comments: i64,
+
+    /// Reads per day, oldest first.
+    pub daily_views: Vec<DailyViewsResponse>,
+
+    /// Reads per referring domain, most first; only for a single post.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub referrers: Option<Vec<ReferrerViewsResponse>>,
+
+    /// Reads per kind of client, most first; only for a single post.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user_agents: Option<Vec<AgentViewsResponse>>,
+}
+
+impl From<PostStats> for PostStatsResponse {
+    /// Converts post stats into an HTTP response DTO.
+    fn from(stats: PostStats) -> Self {
+        let (referrers, user_agents) = match stats.traffic {
+            Some(traffic) => (
+                Some(traffic.referrers.into_iter().map(Into::into).collect()),
+                Some(traffic.agents.into_iter().map(Into::into).collect()),
+            ),
+            None => (None, None),
+        };
+
+        Self {
+            post_id: stats.post_id,
+            title: stats.title,
+            views: stats.views,
+            likes: stats.likes,
+            bookmarks: stats.bookmarks,
+            comments: stats.comments,
+            daily_views: stats.daily_views.into_iter().map(Into::into).collect(),
+            referrers,
+            user_agents,
+        }
+    }
+}
+
+/// Reads from one referring domain.
+#[derive(Debug, Serialize)]
+pub struct ReferrerViewsResponse {
+    /// Referring domain; `null` for direct visits.
+    pub referrer: Option<String>,
+
+    /// Number of reads.
+    pub views: i64,
+}
+
+impl From<ReferrerViews> for ReferrerViewsResponse {
+    /// Converts referrer views into an HTTP response DTO.
+    fn from(referrer: ReferrerViews) -> Self {
+        Self {
+            referrer: referrer.referrer,
+            views: referrer.views,
+        }
+    }
+}
+
+/// Reads from one kind of client.
+#[derive(Debug, Serialize)]
+pub struct AgentViewsResponse {
+    /// Kind of client: `desktop`, `mobile`, `bot` or `other`.
+    pub agent: AgentClass,
+
+    /// Number of reads.
+    pub views: i64,
+}
+
+impl From<AgentViews> for AgentViewsResponse {
+    /// Converts client kind views into an HTTP response DTO.
+    fn from(agent: AgentViews) -> Self {
+        Self {
+            agent: agent.agent,
+            views: agent.views,
+        }
+    }
+}
+
+/// Usage of one limited resource.
+#[derive(Debug, Serialize)]
+pub struct QuotaItemResponse {
+    /// Amount in use.
+    pub used: i64,
+
+    /// Limit on the amount; `null` when unlimited.
+    pub limit: Option<u64>,
+}
+
+/// Quota usage response payload.
+#[derive(Debug, Serialize)]
+pub struct QuotaResponse {
+    /// Posts outside the trash.
+    pub posts: QuotaItemResponse,
+
+    /// Bytes of uploaded media stored.
+    pub media_bytes: QuotaItemResponse,
+
+    /// Largest accepted post content in bytes; `null` when only the built-in length limit applies.
+    pub max_post_bytes: Option<u64>,
+}
+
+impl From<QuotaUsage> for QuotaResponse {
+    /// Converts quota usage into an HTTP response DTO.
+    fn from(usage: QuotaUsage) -> Self {
+        let limit = |limit: u64| (limit > 0).then_some(limit);
+
+        Self {
+            posts: QuotaItemResponse {
+                used: usage.posts,
+                limit: limit(usage.quota.max_posts),
+            },
+            media_bytes: QuotaItemResponse {
+                used: usage.media_bytes,
+                limit: limit(usage.quota.max_media_bytes),
+            },
+            max_post_bytes: limit(usage.quota.max_post_bytes),
+        }
+    }
+}
+
+/// Post revision response payload.
+#[derive(Debug, Serialize)]
+pub struct RevisionResponse {
+    /// Revision identifier.
+    pub id: Uuid,
+
+    /// Identifier of the revised post.
+    pub post_id: Uuid,
+
+    /// Title the post had.
+    pub title: String,
+
+    /// Content the post had.
+    pub content: String,
+
+    /// Tags the post had.
+    pub tags: Vec<String>,
+
+    /// When this version was written.
+    pub edited_at: DateTime<Utc>,
+
+    /// When this version was replaced.
+    pub created_at: DateTime<Utc>,
+}
+
+/// Post translation response payload.
+#[derive(Debug, Serialize)]
+pub struct TranslationResponse {
+    /// Identifier of the translated post.
+    pub post_id: Uuid,
+
+    /// Locale of the translation.
+    pub locale: String,
+
+    /// Translated title.
+    pub title: String,
+
+    /// Translated content.
+    pub content: String,
+
+    /// When the translation was added.
+    pub created_at: DateTime<Utc>,
+
+    /// When the translation was last changed.
+    pub updated_at: DateTime<Utc>,
+}
+
+impl From<PostRevision> for RevisionResponse {
+    /// Converts a post revision into an HTTP response DTO.
+    fn from(revision: PostRevision) -> Self {
+        Self {
+            id: revision.id,
+            post_id: revision.post_id,
+            title: revision.title,
+            content: revision.content,
+            tags: revision.tags,
+            edited_at: revision.edited_at,
+            created_at: revision.created_at,
+        }
+    }
+}
+
+/// Converts domain media into an HTTP response DTO served under `api_path`.
+pub fn media_response(media: Media, api_path: &str) -> MediaResponse {
+    MediaResponse {
+        url: media_url(api_path, media.id),
+        variants: MediaVariantUrls {
+            thumbnail: variant_url(api_path, media.id, VariantName::Thumbnail),
+            medium: variant_url(api_path, media.id, VariantName::Medium),
+            original: variant_url(api_path, media.id, VariantName::Original),
+        },
+        id: media.id,
+        filename: media.filename,
+        content_type: media.content_type,
+        size: media.size_bytes as u64,
+        created_at: media.created_at,
+    }
+}
+
+/// Chunked upload start request payload.
+#[derive(Debug, Deserialize)]
+pub struct StartUploadRequest {
+    /// Original file name.
+    pub filename: String,
+
+    /// Size of the whole file in bytes.
+    pub size: i64,
+}
+
+/// Chunk upload query parameters.
+#[derive(Debug, Deserialize)]
+pub struct UploadChunkQuery {
+    /// Byte of the file the chunk starts at.
+    pub offset: i64,
+}
+
+/// Converts a chunked upload into an HTTP response DTO.
+pub fn upload_response(upload: MediaUpload, max_chunk_bytes: usize) -> MediaUploadResponse {
+    MediaUploadResponse {
+        id: upload.id,
+        filename: upload.filename,
+        size: upload.size_bytes as u64,
+        offset: upload.received_bytes as u64,
+        max_chunk_bytes: max_chunk_bytes as u64,
+        created_at: upload.created_at,
+    }
+}
+
+/// Generated media variant response payload.
+#[derive(Debug, Serialize)]
+pub struct MediaVariantResponse {
+    /// Variant name.
+    pub name: VariantName,
+
+    /// Public path the variant is served from.
+    pub url: String,
+
+    /// MIME type of the variant.
+    pub content_type: String,
+
+    /// Width in pixels.
+    pub width: i32,
+
+    /// Height in pixels.
+    pub height: i32,
+
+    /// File size in bytes.
+    pub size: i64,
+}
+
+impl MediaVariantResponse {
+    /// Converts variant metadata into an HTTP response DTO served under `api_path`.
+    pub fn new(variant: MediaVariant, api_path: &str) -> Self {
+        Self {
+            url: variant_url(api_path, variant.media_id, variant.name),
+            name: variant.name,
+            content_type: variant.content_type,
+            width: variant.width,
+            height: variant.height,
+            size: variant.size_bytes,
+        }
+    }
+}
+
+/// Returns the public path of an uploaded file.
+fn media_url(api_path: &str, id: Uuid) -> String {
+    format!("{}/public/media/{}", api_path, id)
+}
+
+/// Returns the public path of a size variant of an uploaded file.
+fn variant_url(api_path: &str, id: Uuid, name: VariantName) -> String {
+    format!("{}/{}", media_url(api_path, id), name)
+}
+
+/// Data export response payload.
+#[derive(Debug, Serialize)]
+pub struct DataExportResponse {
+    /// Export identifier.
+    pub id: Uuid,
+
+    /// Whether the archive is being built, ready or failed.
+    pub status: ExportStatus,
+
+    /// Archive size in bytes, once built.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size: Option<i64>,
+
+    /// Why the export failed, if it did.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+
+    /// Signed public path the archive downloads from until `expires_at`, once ready.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub download_url: Option<String>,
+
+    /// When the export was requested.
+    pub created_at: DateTime<Utc>,
+
+    /// When the archive was built or the export failed.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<DateTime<Utc>>,
+
+    /// When the export and its download link expire.
+    pub expires_at: DateTime<Utc>,
+}
+
+impl DataExportResponse {
+    /// Converts a data export into an HTTP response DTO, with a download link
+    /// under `api_path` when a `token` is given.
+    pub fn new(export: DataExport, token: Option<String>, api_path: &str) -> Self {
+        Self {
+            id: export.id,
+            status: export.status,
+            size: export.size_bytes,
+            error: export.error,
+            download_url: token.map(|token| format!("{}/public/exports/{}", api_path, token)),
+            created_at: export.created_at,
+            completed_at: export.completed_at,
+            expires_at: export.expires_at,
+        }
+    }
+}
+
+/// User account as seen by administrators.
+#[derive(Debug, Serialize)]
+pub struct UserResponse {
+    /// User ID.
+    pub id: Uuid,
+
+    /// User display name.
+    pub username: String,
+
+    /// User email address.
+    pub email: String,
+
+    /// User role.
+    pub role: Role,
+
+    /// Registration timestamp.
+    pub created_at: DateTime<Utc>,
+
+    /// Time the account was disabled, if disabled.
+    pub disabled_at: Option<DateTime<Utc>>,
+}
+
+impl From<User> for UserResponse {
+    /// Converts a domain user into an HTTP response DTO.
+    fn from(user: User) -> Self {
+        Self {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            role: user.role,
+            created_at: user.created_at,
+            disabled_at: user.disabled_at,
+        }
+    }
+}
+
+/// Role change request payload.
+#[derive(Debug, Deserialize)]
+pub struct SetRoleRequest {
+    /// New role: `user` or `admin`.
+    pub role: Role,
+}
+
+/// IP rule creation request payload.
+#[derive(Debug, Deserialize)]
+pub struct CreateIpRuleRequest {
+    /// Address or CIDR block the rule matches, e.g. `203.0.113.0/24`.
+    pub network: String,
+
+    /// `allow` or `deny`.
+    pub action: IpRuleAction,
+
+    /// Why the rule is added.
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// IP rule response payload.
+#[derive(Debug, Serialize)]
+pub struct IpRuleResponse {
+    /// Rule identifier.
+    pub id: Uuid,
+
+    /// CIDR block the rule matches.
+    pub network: IpNet,
+
+    /// `allow` or `deny`.
+    pub action: IpRuleAction,
+
+    /// Why the rule was added.
+    pub reason: Option<String>,
+
+    /// Rule creation timestamp.
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<IpRule> for IpRuleResponse {
+    /// Converts a domain IP rule into an HTTP response DTO.
+    fn from(rule: IpRule) -> Self {
+        Self {
+            id: rule.id,
+            network: rule.network,
+            action: rule.action,
+            reason: rule.reason,
+            created_at: rule.created_at,
+        }
+    }
+}
+
+/// Blog import query parameters.
+#[derive(Debug, Deserialize)]
+pub struct ImportQuery {
+    /// Export format: `wordpress` or `medium`.
+    pub format: ImportFormat,
+
+    /// Account receiving posts whose author has no local account; the
+    /// importing admin when omitted.
+    pub author_id: Option<Uuid>,
+}
+
+/// Activity response payload.
+#[derive(Debug, Serialize)]
+pub struct ActivityResponse {
+    /// Unique activity identifier.
+    pub id: Uuid,
+
+    /// What the user did: `post_published` or `commented`.
+    pub kind: ActivityKind,
+
+    /// Identifier of the user who acted.
+    pub actor_id: Uuid,
+
+    /// Identifier of the post acted on.
+    pub post_id: Uuid,
+
+    /// Title of the post acted on.
+    pub post_title: Option<String>,
+
+    /// Identifier of the comment, for comments.
+    pub comment_id: Option<Uuid>,
+
+    /// When it happened.
+    pub created_at: DateTime<Utc>,
+}
+
+impl From<Activity> for ActivityResponse {
+    /// Converts a domain activity into an HTTP response DTO.
+    fn from(activity: Activity) -> Self {
+        Self {
+            id: activity.id,
+            kind: activity.kind,
+            actor_id: activity.actor_id,
+            post_id: activity.post_id,
+            post_title: activity.post_title,
+            comment_id: activity.comment_id,
+            created_at: activity.created_at,
+        }
+    }
+}
+
+/// WebFinger query parameters.
+#[derive(Debug, Deserialize)]
+pub struct WebFingerQuery {
+    /// Resource to resolve, as `acct:username@domain`.
+    pub resource: String,
+}
+
+/// Webmention notification, sent form-encoded.
+#[derive(Debug, Deserialize)]
+pub struct WebmentionForm {
+    /// URL of the page linking to the post.
+    pub source: String,
+
+    /// URL of the post it links to.
+    pub target: String,
+}
+
+/// Webmention response payload.
+#[derive(Debug, Serialize)]
+pub struct MentionResponse {
+    /// Mention identifier.
+    pub id: Uuid,
+
+    /// Mentioned post identifier.
+    pub post_id: Uuid,
+
+    /// URL of the page linking to the post.
+    pub source: String,
+
+    /// Whether the mention is verified and shown.
+    pub status: MentionStatus,
+
+    /// Name of the source's author, if the page names one.
+    pub author_name: Option<String>,
+
+    /// URL of the source's author, if the page names one.
+    pub author_url: Option<String>,
+
+    /// Title of the source page.
+    pub title: Option<String>,
+
+    /// Start of the source page's text.
+    pub excerpt: Option<String>,
+
+    /// When the mention was first received.
+    pub created_at: DateTime<Utc>,
+
+    /// When the source was last found linking to the post.
+    pub verified_at: Option<DateTime<Utc>>,
+}
+
+impl From<Mention> for MentionResponse {
+    /// Converts a domain mention into an HTTP response DTO.
+    fn from(mention: Mention) -> Self {
+        Self {
+            id: mention.id,
+            post_id: mention.post_id,
+            source: mention.source,
+            status: mention.status,
+            author_name: mention.author_name,
+            author_url: mention.author_url,
+            title: mention.title,
+            excerpt: mention.excerpt,
+            created_at: mention.created_at,
+            verified_at: mention.verified_at,
         }
     }
 }
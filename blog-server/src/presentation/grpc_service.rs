@@ -1,26 +1,76 @@
+// `tonic::Status` is large; boxing it in every error path is not worth the noise.
+#![allow(clippy::result_large_err)]
+
+use std::pin::Pin;
+use std::sync::Arc;
+use futures_util::{Stream, StreamExt};
+use prost::Message;
+use tokio::sync::broadcast::error::RecvError;
 use tonic::{Request, Response, Status};
-use tracing_log::log::info;
+use tracing_log::log::{info, warn};
 use uuid::Uuid;
 use crate::application::auth_service::AuthService;
+use crate::application::comment_service::CommentService;
+use crate::data::comment_repository::PostgresCommentRepository;
 use crate::data::post_repository::{PostgresPostRepository};
+use crate::data::revision_repository::PostgresRevisionRepository;
 use crate::application::post_service::PostService;
 use crate::data::user_repository::PostgresUserRepository;
+use crate::domain::comment::{CommentOrigin, CommentStatus};
 use crate::domain::error::DomainError;
-use crate::presentation::blog::{EmptyResponse, GetPostRequest, ListPostRequest, ListPostsResponse, LoginRequest, LoginResponse, Post, PostResponse, RegisterRequest, RegisterResponse, UpdatePostRequest};
+use crate::domain::event::BlogEvent;
+use crate::domain::post::Visibility;
+use crate::infrastructure::events::EventBus;
+use crate::presentation::handler::public::Sessions;
+use crate::presentation::blog::{AddCommentRequest, CommentResponse, DeleteCommentRequest, ErrorDetails, ListCommentsRequest, ListCommentsResponse, ListTagsRequest, ListTagsResponse, EmptyResponse, GetPostRequest, ListPostRequest, ListPostsResponse, LoginRequest, LoginResponse, RefreshTokenRequest, Post, PostResponse, RegisterRequest, RegisterResponse, UpdatePostRequest, GetUserRequest, UserResponse, GetProfileRequest, ProfileResponse, UpdateProfileRequest, SearchPostsRequest, SearchPostsResponse, SearchResult, StreamEventsRequest, Event, ListRevisionsRequest, ListRevisionsResponse, RevisionRequest, RevisionResponse};
 
 pub struct GrpcService {
-    post: PostService<PostgresPostRepository>,
+    post: PostService<PostgresPostRepository, PostgresRevisionRepository>,
     auth: AuthService<PostgresUserRepository>,
+    sessions: Sessions,
+    comment: CommentService<PostgresCommentRepository, PostgresPostRepository>,
+    events: EventBus,
 }
 
+/// Stream of events sent by `StreamEvents`.
+type EventStream = Pin<Box<dyn Stream<Item = Result<Event, Status>> + Send>>;
+
 impl GrpcService {
-    pub fn new(post: PostService<PostgresPostRepository>,auth: AuthService<PostgresUserRepository>) -> Self {
-        Self { post,auth }
+    pub fn new(
+        post: PostService<PostgresPostRepository, PostgresRevisionRepository>,
+        auth: AuthService<PostgresUserRepository>,
+        sessions: Sessions,
+        comment: CommentService<PostgresCommentRepository, PostgresPostRepository>,
+        events: EventBus,
+    ) -> Self {
+        Self { post, auth, sessions, comment, events }
+    }
+
+    /// Verifies the request token, rejects disabled accounts, and returns the user ID.
+    async fn user_id<T>(&self, request: &Request<T>) -> Result<Uuid, Status> {
+        let token = extract_token(request)?;
+        let claims = self.auth.keys()
+            .verify_token(&token)
+            .map_err(|_| Status::unauthenticated("invalid token claims"))?;
+
+        let user_id =
+            Uuid::parse_str(&claims.sub).map_err(|_| Status::unauthenticated("invalid token"))?;
+
+        let user = self.auth.token_user(user_id)
+            .await
+            .map_err(|_| Status::unauthenticated("user not found"))?;
+        if user.disabled {
+            return Err(Status::unauthenticated("account is disabled"));
+        }
+
+        Ok(user_id)
     }
 }
 
 #[tonic::async_trait]
 impl BlogService for GrpcService {
+    type StreamEventsStream = EventStream;
+
     async fn register(&self, request: Request<RegisterRequest>) -> Result<Response<RegisterResponse>, Status> {
         let req = request.into_inner();
         let user = self.auth.register(req.username,req.email,req.password).await.map_err(to_status)?;
@@ -32,23 +82,38 @@ impl BlogService for GrpcService {
 
     async fn login(&self, request: Request<LoginRequest>) -> Result<Response<LoginResponse>, Status> {
         let req = request.into_inner();
-        let token = self.auth.login(req.email.as_ref(),req.password.as_ref())
+        let tokens = self.sessions.login(req.email.as_ref(),req.password.as_ref())
             .await.map_err(to_status)?;
 
         Ok(Response::new(LoginResponse{
-            token: token.into(),
+            token: tokens.access_token,
+            refresh_token: tokens.refresh_token,
+        }))
+    }
+
+    async fn refresh_token(&self, request: Request<RefreshTokenRequest>) -> Result<Response<LoginResponse>, Status> {
+        let req = request.into_inner();
+        if req.refresh_token.is_empty() {
+            return Err(Status::invalid_argument("missing refresh token"));
+        }
+        let tokens = self.sessions.refresh(&req.refresh_token).await.map_err(to_status)?;
+
+        Ok(Response::new(LoginResponse{
+            token: tokens.access_token,
+            refresh_token: tokens.refresh_token,
         }))
     }
 
     async fn get_post(&self, request: Request<GetPostRequest>) -> Result<Response<PostResponse>, Status> {
         let token = extract_token(&request)?;
-        self.auth.keys()
+        let claims = self.auth.keys()
             .verify_token(&token)
             .map_err(|_| Status::unauthenticated("invalid token"))?;
+        let user_id = Uuid::parse_str(&claims.sub).map_err(|_| Status::unauthenticated("invalid token"))?;
 
         let req = request.into_inner();
         let id =Uuid::parse_str(&req.id).map_err(|_| Status::invalid_argument("invalid id"))?;
-        let post = self.post.get_post(id).await.map_err(to_status)?;
+        let post = self.post.read_post(id, Some(user_id)).await.map_err(to_status)?;
 
         Ok(Response::new(PostResponse{
             post: Some(post.into()),
@@ -61,16 +126,19 @@ impl BlogService for GrpcService {
             .verify_token(&token)
             .map_err(|_| Status::unauthenticated("invalid token claims"))?;
         let user_id = Uuid::parse_str(&claims.sub).map_err(|_| Status::unauthenticated("invalid token"))?;
-        let posts = self.post.list_posts(user_id).await.map_err(to_status)?;
-        let response: Vec<Post> = posts
+        let req = request.into_inner();
+        let tag = Some(req.tag.as_str()).filter(|tag| !tag.is_empty());
+        let page = self.post.list_posts(user_id, tag, req.limit, req.offset).await.map_err(to_status)?;
+        let response: Vec<Post> = page.items
             .into_iter()
             .map(Into::into)
             .collect();
 
-        info!("grpc got {} posts", response.len());
+        info!("grpc got {} of {} posts", response.len(), page.total);
 
         Ok(Response::new(ListPostsResponse{
             posts: response,
+            total: page.total as u64,
         }))
     }
 
@@ -82,7 +150,15 @@ impl BlogService for GrpcService {
 
         let req = request.into_inner();
         let id =Uuid::parse_str(&req.id).map_err(|_| Status::invalid_argument("invalid id"))?;
-        let post= self.post.update_post(id,req.title, req.content).await.map_err(to_status)?;
+        let tags = req.tags.map(|tags| tags.names);
+        let expected_updated_at = if req.expected_updated_at.is_empty() {
+            None
+        } else {
+            let at = chrono::DateTime::parse_from_rfc3339(&req.expected_updated_at)
+                .map_err(|_| Status::invalid_argument("invalid expected_updated_at"))?;
+            Some(at.with_timezone(&chrono::Utc))
+        };
+        let post= self.post.update_post(id,req.title, req.content, tags, None, expected_updated_at, false).await.map_err(to_status)?;
 
         Ok(Response::new(PostResponse{
             post: Some(post.into()),
@@ -110,19 +186,192 @@ impl BlogService for GrpcService {
             .map_err(|_| Status::unauthenticated("invalid token"))?;
 
         let req = request.into_inner();
-        let post = self.post.create_post(req.title,req.content,Uuid::parse_str(&req.author_id)
-            .map_err(|_| Status::invalid_argument("invalid author id"))?)
+        let post = self.post.create_post(req.title,req.content,req.tags,Visibility::default(),Uuid::parse_str(&req.author_id)
+            .map_err(|_| Status::invalid_argument("invalid author id"))?, false)
             .await.map_err(to_status)?;
 
         Ok(Response::new(PostResponse{
             post: Some(post.into()),
         }))
     }
+
+    async fn list_tags(&self, request: Request<ListTagsRequest>) -> Result<Response<ListTagsResponse>, Status> {
+        let user_id = self.user_id(&request).await?;
+        let tags = self.post.list_tags(user_id).await.map_err(to_status)?;
+
+        Ok(Response::new(ListTagsResponse{
+            tags: tags.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn search_posts(&self, request: Request<SearchPostsRequest>) -> Result<Response<SearchPostsResponse>, Status> {
+        let req = request.into_inner();
+        let tag = Some(req.tag.as_str()).filter(|tag| !tag.is_empty());
+        let page = self.post.search_posts(&req.query, tag, req.limit, req.offset).await.map_err(to_status)?;
+
+        info!("grpc found {} of {} posts for {:?}", page.items.len(), page.total, req.query);
+
+        Ok(Response::new(SearchPostsResponse{
+            results: page.items.into_iter().map(Into::into).collect(),
+            total: page.total as u64,
+        }))
+    }
+
+    async fn list_revisions(&self, request: Request<ListRevisionsRequest>) -> Result<Response<ListRevisionsResponse>, Status> {
+        self.user_id(&request).await?;
+
+        let req = request.into_inner();
+        let post_id = Uuid::parse_str(&req.post_id).map_err(|_| Status::invalid_argument("invalid post id"))?;
+        let revisions = self.post.list_revisions(post_id).await.map_err(to_status)?;
+
+        Ok(Response::new(ListRevisionsResponse{
+            revisions: revisions.into_iter().map(Into::into).collect(),
+        }))
+    }
+
+    async fn get_revision(&self, request: Request<RevisionRequest>) -> Result<Response<RevisionResponse>, Status> {
+        self.user_id(&request).await?;
+
+        let req = request.into_inner();
+        let (post_id, revision_id) = parse_revision_request(&req)?;
+        let revision = self.post.get_revision(post_id, revision_id).await.map_err(to_status)?;
+
+        Ok(Response::new(RevisionResponse{
+            revision: Some(revision.into()),
+        }))
+    }
+
+    async fn restore_revision(&self, request: Request<RevisionRequest>) -> Result<Response<PostResponse>, Status> {
+        let user_id = self.user_id(&request).await?;
+
+        let req = request.into_inner();
+        let (post_id, revision_id) = parse_revision_request(&req)?;
+        let post = self.post.restore_revision(post_id, revision_id, user_id).await.map_err(to_status)?;
+        info!("post {} restored to revision {}", post_id, revision_id);
+
+        Ok(Response::new(PostResponse{
+            post: Some(post.into()),
+        }))
+    }
+
+    async fn list_comments(&self, request: Request<ListCommentsRequest>) -> Result<Response<ListCommentsResponse>, Status> {
+        self.user_id(&request).await?;
+
+        let req = request.into_inner();
+        let post_id = Uuid::parse_str(&req.post_id).map_err(|_| Status::invalid_argument("invalid post id"))?;
+        let page = self.comment.list_comments(post_id, req.limit, req.offset).await.map_err(to_status)?;
+
+        Ok(Response::new(ListCommentsResponse{
+            comments: page.items.into_iter().map(Into::into).collect(),
+            total: page.total as u64,
+        }))
+    }
+
+    async fn add_comment(&self, request: Request<AddCommentRequest>) -> Result<Response<CommentResponse>, Status> {
+        let user_id = self.user_id(&request).await?;
+        let origin = CommentOrigin {
+            ip: request.remote_addr().map(|addr| addr.ip().to_string()),
+            user_agent: request.metadata().get("user-agent")
+                .and_then(|agent| agent.to_str().ok())
+                .map(str::to_string),
+        };
+
+        let req = request.into_inner();
+        let post_id = Uuid::parse_str(&req.post_id).map_err(|_| Status::invalid_argument("invalid post id"))?;
+        let parent_id = match req.parent_id.as_str() {
+            "" => None,
+            id => Some(Uuid::parse_str(id).map_err(|_| Status::invalid_argument("invalid parent id"))?),
+        };
+        let comment = self.comment.add_comment(post_id, user_id, req.content, parent_id, &origin).await.map_err(to_status)?;
+
+        Ok(Response::new(CommentResponse{
+            comment: Some(comment.into()),
+        }))
+    }
+
+    async fn delete_comment(&self, request: Request<DeleteCommentRequest>) -> Result<Response<EmptyResponse>, Status> {
+        let user_id = self.user_id(&request).await?;
+
+        let req = request.into_inner();
+        let id = Uuid::parse_str(&req.id).map_err(|_| Status::invalid_argument("invalid id"))?;
+
+        self.comment.delete_comment(id, user_id).await.map_err(to_status)?;
+
+        Ok(Response::new(EmptyResponse{}))
+    }
+
+    async fn get_user(&self, request: Request<GetUserRequest>) -> Result<Response<UserResponse>, Status> {
+        self.user_id(&request).await?;
+
+        let req = request.into_inner();
+        let id = Uuid::parse_str(&req.id).map_err(|_| Status::invalid_argument("invalid id"))?;
+        let user = self.auth.get_user(id).await.map_err(to_status)?;
+
+        Ok(Response::new(UserResponse{
+            user: Some(user.into()),
+        }))
+    }
+
+    async fn get_profile(&self, request: Request<GetProfileRequest>) -> Result<Response<ProfileResponse>, Status> {
+        let user_id = self.user_id(&request).await?;
+        let user = self.auth.get_user(user_id).await.map_err(to_status)?;
+
+        Ok(Response::new(ProfileResponse{
+            profile: Some(user.into()),
+        }))
+    }
+
+    async fn update_profile(&self, request: Request<UpdateProfileRequest>) -> Result<Response<ProfileResponse>, Status> {
+        let user_id = self.user_id(&request).await?;
+
+        let req = request.into_inner();
+        let user = self.auth.update_profile(user_id, req.username, req.email, req.bio).await.map_err(to_status)?;
+        info!("profile updated: {}", user_id);
+
+        Ok(Response::new(ProfileResponse{
+            profile: Some(user.into()),
+        }))
+    }
+
+    async fn stream_events(&self, request: Request<StreamEventsRequest>) -> Result<Response<Self::StreamEventsStream>, Status> {
+        let user_id = self.user_id(&request).await?;
+
+        let last_event_id = Some(request.into_inner().last_event_id).filter(|id| *id > 0);
+        let (backlog, receiver) = self.events.subscribe_since(last_event_id);
+        info!("grpc event stream opened: {}, {} replayed", user_id, backlog.len());
+
+        let backlog = backlog.into_iter().filter(move |event| event.kind.is_visible_to(user_id));
+        let live = futures_util::stream::unfold(receiver, move |mut receiver| async move {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) if event.kind.is_visible_to(user_id) => return Some((event, receiver)),
+                    Ok(_) => continue,
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!("grpc event subscriber {} lagged behind by {}", user_id, skipped);
+                        continue;
+                    }
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        });
+        let stream = futures_util::stream::iter(backlog)
+            .chain(live)
+            .map(|event| Ok(Event::from(event)));
+
+        Ok(Response::new(Box::pin(stream)))
+    }
 }
 
+/// Maps a domain error to a gRPC status carrying its code in `ErrorDetails`.
 fn to_status(err: DomainError) -> Status {
-    match err {
-        DomainError::Validation(msg) =>
+    let details = ErrorDetails {
+        code: err.code().to_string(),
+    };
+
+    let status = match err {
+        DomainError::Validation(msg)
+        | DomainError::EmailTaken(msg)
+        | DomainError::UsernameTaken(msg) =>
             Status::invalid_argument(msg),
 
         DomainError::UserNotFound(id) =>
@@ -131,15 +380,41 @@ fn to_status(err: DomainError) -> Status {
         DomainError::PostNotFound(id) =>
             Status::not_found(format!("post not found: {id}")),
 
+        DomainError::CommentNotFound(id) =>
+            Status::not_found(format!("comment not found: {id}")),
+
+        DomainError::MediaNotFound(id) =>
+            Status::not_found(format!("media not found: {id}")),
+
+        DomainError::ExportNotFound(id) =>
+            Status::not_found(format!("export not found: {id}")),
+
+        DomainError::MentionNotFound(id) =>
+            Status::not_found(format!("mention not found: {id}")),
+
+        DomainError::PayloadTooLarge(msg)
+        | DomainError::RateLimited(msg)
+        | DomainError::QuotaExceeded(msg) =>
+            Status::resource_exhausted(msg),
+
+        DomainError::Conflict(msg) =>
+            Status::aborted(msg),
+
         DomainError::InvalidCredentials(msg) =>
             Status::unauthenticated(msg),
 
+        DomainError::Forbidden(msg) =>
+            Status::permission_denied(msg),
+
         DomainError::Internal(msg) =>
             Status::internal(msg),
-    }
+    };
+
+    Status::with_details(status.code(), status.message(), details.encode_to_vec().into())
 }
 
 use crate::presentation::blog::Post as ProtoPost;
+use crate::presentation::blog::Author as ProtoAuthor;
 use crate::domain::post::Post as DomainPost;
 
 impl From<DomainPost> for ProtoPost {
@@ -148,7 +423,87 @@ impl From<DomainPost> for ProtoPost {
             id: p.id.to_string(),
             title: p.title,
             content: p.content,
+            author: p.author_username.map(|username| ProtoAuthor {
+                id: p.author_id.to_string(),
+                username,
+            }),
             author_id: p.author_id.to_string(),
+            tags: p.tags,
+            slug: p.slug,
+            visibility: blog_core::post::Visibility::from(p.visibility).to_string(),
+            created_at: p.created_at.to_rfc3339(),
+            updated_at: p.updated_at.to_rfc3339(),
+        }
+    }
+}
+
+use crate::domain::search::SearchHit;
+
+impl From<SearchHit> for SearchResult {
+    fn from(h: SearchHit) -> Self {
+        Self {
+            post: Some(h.post.into()),
+            rank: h.rank,
+            snippet: h.snippet,
+        }
+    }
+}
+
+impl From<Arc<BlogEvent>> for Event {
+    fn from(e: Arc<BlogEvent>) -> Self {
+        Self {
+            id: e.id,
+            kind: e.kind.name().to_string(),
+            data: serde_json::to_string(e.as_ref()).unwrap_or_else(|_| "{}".into()),
+        }
+    }
+}
+
+use crate::presentation::blog::Tag as ProtoTag;
+use crate::domain::tag::Tag as DomainTag;
+
+impl From<DomainTag> for ProtoTag {
+    fn from(t: DomainTag) -> Self {
+        Self {
+            name: t.name,
+            post_count: t.post_count.max(0) as u64,
+        }
+    }
+}
+
+use crate::presentation::blog::Revision as ProtoRevision;
+use crate::domain::revision::PostRevision;
+
+impl From<PostRevision> for ProtoRevision {
+    fn from(r: PostRevision) -> Self {
+        Self {
+            id: r.id.to_string(),
+            post_id: r.post_id.to_string(),
+            title: r.title,
+            content: r.content,
+            tags: r.tags,
+            edited_at: r.edited_at.to_rfc3339(),
+            created_at: r.created_at.to_rfc3339(),
+        }
+    }
+}
+
+use crate::presentation::blog::Comment as ProtoComment;
+use crate::domain::comment::Comment as DomainComment;
+
+impl From<DomainComment> for ProtoComment {
+    fn from(c: DomainComment) -> Self {
+        Self {
+            id: c.id.to_string(),
+            post_id: c.post_id.to_string(),
+            author_id: c.author_id.to_string(),
+            parent_id: c.parent_id.map(|id| id.to_string()).unwrap_or_default(),
+            content: c.content,
+            created_at: c.created_at.to_rfc3339(),
+            status: match c.status {
+                CommentStatus::Published => "published".to_string(),
+                CommentStatus::Flagged => "flagged".to_string(),
+            },
         }
     }
 }
@@ -167,6 +522,33 @@ impl From<DomainUser> for ProtoUser {
     }
 }
 
+use crate::presentation::blog::PublicUser as ProtoPublicUser;
+use crate::presentation::blog::Profile as ProtoProfile;
+
+impl From<DomainUser> for ProtoPublicUser {
+    fn from(u: DomainUser) -> Self {
+        Self {
+            id: u.id.to_string(),
+            username: u.username,
+            bio: u.bio.unwrap_or_default(),
+            created_at: u.created_at.to_rfc3339(),
+        }
+    }
+}
+
+impl From<DomainUser> for ProtoProfile {
+    fn from(u: DomainUser) -> Self {
+        Self {
+            id: u.id.to_string(),
+            username: u.username,
+            email: u.email,
+            bio: u.bio.unwrap_or_default(),
+            role: u.role.as_str().to_string(),
+            created_at: u.created_at.to_rfc3339(),
+        }
+    }
+}
+
 fn extract_token<T>(request: &Request<T>) -> Result<String, Status> {
     let value = request.metadata()
         .get("authorization")
@@ -183,3 +565,10 @@ fn extract_token<T>(request: &Request<T>) -> Result<String, Status> {
     let token = auth_str.trim_start_matches("Bearer ").to_string();
     Ok(token)
 }
+
+/// Parses the post and revision IDs of a revision request.
+fn parse_revision_request(req: &RevisionRequest) -> Result<(Uuid, Uuid), Status> {
+    let post_id = Uuid::parse_str(&req.post_id).map_err(|_| Status::invalid_argument("invalid post id"))?;
+    let revision_id = Uuid::parse_str(&req.revision_id).map_err(|_| Status::invalid_argument("invalid revision id"))?;
+    Ok((post_id, revision_id))
+}
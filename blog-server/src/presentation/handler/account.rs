@@ -0,0 +1,146 @@
+use crate::application::auth_service::AuthService;
+use crate::application::avatar_service::AvatarService;
+use crate::data::media_repository::PostgresMediaRepository;
+use crate::data::user_repository::PostgresUserRepository;
+use crate::domain::error::DomainError;
+use crate::presentation::auth::AuthenticatedUser;
+use crate::presentation::dto::{
+    AvatarQuery, ChangePasswordRequest, ProfileResponse, SetAvatarRequest, UpdateProfileRequest,
+};
+use crate::presentation::handler::activity;
+use actix_web::http::header::{CacheControl, CacheDirective, ContentType};
+use actix_web::{delete, get, put, web, HttpResponse, Scope};
+use tracing::info;
+use uuid::Uuid;
+
+/// Auth service type used by the HTTP handlers.
+type Auth = AuthService<PostgresUserRepository>;
+
+/// Avatar service type used by the HTTP handlers.
+pub(crate) type Avatars = AvatarService<PostgresUserRepository, PostgresMediaRepository>;
+
+/// Seconds clients may cache an avatar; short, as users can change it.
+const AVATAR_MAX_AGE_SECS: u32 = 300;
+
+/// Returns the account settings scope for the authenticated user.
+pub fn scope() -> Scope {
+    web::scope("/profile")
+        .service(get_profile)
+        .service(update_profile)
+        .service(change_password)
+        .service(set_avatar)
+        .service(clear_avatar)
+}
+
+/// Returns the public user scope.
+pub fn public_scope() -> Scope {
+    web::scope("/users")
+        .service(get_avatar)
+        .service(activity::list_user_activity)
+}
+
+/// Returns the profile of the authenticated user.
+#[get("")]
+async fn get_profile(
+    user: AuthenticatedUser,
+    auth: web::Data<Auth>,
+) -> Result<HttpResponse, DomainError> {
+    let profile = auth.get_user(user.id).await?;
+
+    Ok(HttpResponse::Ok().json(ProfileResponse::from(profile)))
+}
+
+/// Updates the username, email or bio of the authenticated user.
+#[put("")]
+async fn update_profile(
+    user: AuthenticatedUser,
+    auth: web::Data<Auth>,
+    payload: web::Json<UpdateProfileRequest>,
+) -> Result<HttpResponse, DomainError> {
+    let payload = payload.into_inner();
+    let profile = auth
+        .update_profile(user.id, payload.username, payload.email, payload.bio)
+        .await?;
+
+    info!(
+        user_id = %user.id,
+        "profile updated"
+    );
+
+    Ok(HttpResponse::Ok().json(ProfileResponse::from(profile)))
+}
+
+/// Changes the password of the authenticated user.
+#[put("/password")]
+async fn change_password(
+    user: AuthenticatedUser,
+    auth: web::Data<Auth>,
+    payload: web::Json<ChangePasswordRequest>,
+) -> Result<HttpResponse, DomainError> {
+    auth.change_password(user.id, &payload.current_password, &payload.new_password)
+        .await?;
+
+    info!(
+        user_id = %user.id,
+        "password changed"
+    );
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Makes one of the authenticated user's uploaded images their avatar.
+#[put("/avatar")]
+async fn set_avatar(
+    user: AuthenticatedUser,
+    avatars: web::Data<Avatars>,
+    payload: web::Json<SetAvatarRequest>,
+) -> Result<HttpResponse, DomainError> {
+    avatars.set_avatar(user.id, payload.media_id).await?;
+
+    info!(
+        user_id = %user.id,
+        media_id = %payload.media_id,
+        "avatar set"
+    );
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Removes the authenticated user's avatar.
+#[delete("/avatar")]
+async fn clear_avatar(
+    user: AuthenticatedUser,
+    avatars: web::Data<Avatars>,
+) -> Result<HttpResponse, DomainError> {
+    avatars.clear_avatar(user.id).await?;
+
+    info!(
+        user_id = %user.id,
+        "avatar cleared"
+    );
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Serves a user's avatar, or their Gravatar if they have none.
+#[get("/{id}/avatar")]
+async fn get_avatar(
+    avatars: web::Data<Avatars>,
+    path: web::Path<Uuid>,
+    query: web::Query<AvatarQuery>,
+) -> Result<HttpResponse, DomainError> {
+    let avatar = avatars.avatar(path.into_inner(), query.size).await?;
+    let content_type = avatar
+        .content_type
+        .parse()
+        .map(ContentType)
+        .unwrap_or_else(|_| ContentType::octet_stream());
+
+    Ok(HttpResponse::Ok()
+        .insert_header(content_type)
+        .insert_header(CacheControl(vec![
+            CacheDirective::Public,
+            CacheDirective::MaxAge(AVATAR_MAX_AGE_SECS),
+        ]))
+        .body(avatar.bytes))
+}
@@ -0,0 +1,68 @@
+use crate::application::activity_service::ActivityService;
+use crate::data::activity_repository::PostgresActivityRepository;
+use crate::data::user_repository::PostgresUserRepository;
+use crate::domain::error::DomainError;
+use crate::presentation::auth::AuthenticatedUser;
+use crate::presentation::dto::{ActivityResponse, PageQuery};
+use crate::presentation::handler::protected::page_response;
+use actix_web::{get, web, HttpRequest, HttpResponse, Scope};
+use tracing::info;
+use uuid::Uuid;
+
+/// Activity service type used by the HTTP handlers.
+pub(crate) type Activities = ActivityService<PostgresActivityRepository, PostgresUserRepository>;
+
+/// Returns the authenticated user's activity feed scope.
+pub fn scope() -> Scope {
+    web::scope("/activity").service(feed)
+}
+
+/// Lists what a user did on public posts, newest first.
+///
+/// The total number of activities is returned in `X-Total-Count`.
+#[get("/{id}/activity")]
+pub(super) async fn list_user_activity(
+    req: HttpRequest,
+    activities: web::Data<Activities>,
+    path: web::Path<Uuid>,
+    query: web::Query<PageQuery>,
+) -> Result<HttpResponse, DomainError> {
+    let user_id = path.into_inner();
+    let page = activities
+        .list_user_activity(user_id, query.limit.unwrap_or(0), query.offset.unwrap_or(0))
+        .await?
+        .map(ActivityResponse::from);
+
+    info!(
+        user_id = %user_id,
+        total = page.total,
+        "user activity listed"
+    );
+
+    Ok(page_response(&req, page))
+}
+
+/// Lists the authenticated user's feed, newest first: what they did and what
+/// others did on their posts.
+///
+/// The total number of activities is returned in `X-Total-Count`.
+#[get("")]
+async fn feed(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    activities: web::Data<Activities>,
+    query: web::Query<PageQuery>,
+) -> Result<HttpResponse, DomainError> {
+    let page = activities
+        .feed(user.id, query.limit.unwrap_or(0), query.offset.unwrap_or(0))
+        .await?
+        .map(ActivityResponse::from);
+
+    info!(
+        user_id = %user.id,
+        total = page.total,
+        "activity feed listed"
+    );
+
+    Ok(page_response(&req, page))
+}
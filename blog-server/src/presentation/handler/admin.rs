@@ -0,0 +1,481 @@
+use crate::application::admin_service::AdminService;
+use crate::application::import_service::ImportService;
+use crate::application::ip_rule_service::IpRuleService;
+use crate::data::media_repository::PostgresMediaRepository;
+use crate::data::post_repository::PostgresPostRepository;
+use crate::data::revision_repository::PostgresRevisionRepository;
+use crate::data::user_repository::PostgresUserRepository;
+use crate::domain::error::DomainError;
+use crate::presentation::auth::AuthenticatedUser;
+use crate::presentation::dto::{
+    CommentResponse, CreateIpRuleRequest, FlaggedCommentResponse, FlaggedPostResponse,
+    ImportQuery, IpRuleResponse, MentionResponse, PageQuery, ReindexResponse, SetRoleRequest,
+    UserResponse,
+};
+use crate::presentation::handler::protected::{page_response, Comments, Posts};
+use crate::presentation::handler::media::ApiPath;
+use crate::presentation::handler::webmention::Mentions;
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Scope};
+use futures_util::StreamExt;
+use tracing::info;
+use uuid::Uuid;
+
+/// Admin service type used by the HTTP handlers.
+type Admin = AdminService<PostgresUserRepository>;
+
+/// Import service type used by the HTTP handlers.
+pub(crate) type Imports = ImportService<
+    PostgresPostRepository,
+    PostgresRevisionRepository,
+    PostgresUserRepository,
+    PostgresMediaRepository,
+>;
+
+/// Returns the admin API scope; every route requires the admin role.
+pub fn scope() -> Scope {
+    web::scope("/admin")
+        .service(list_users)
+        .service(disable_user)
+        .service(enable_user)
+        .service(set_role)
+        .service(list_flagged_comments)
+        .service(approve_comment)
+        .service(reject_comment)
+        .service(list_flagged_posts)
+        .service(approve_post)
+        .service(reject_post)
+        .service(list_held_mentions)
+        .service(approve_mention)
+        .service(reject_mention)
+        .service(import_posts)
+        .service(reindex_search)
+        .service(list_ip_rules)
+        .service(create_ip_rule)
+        .service(delete_ip_rule)
+}
+
+/// Lists registered users, oldest first.
+#[get("/users")]
+async fn list_users(
+    user: AuthenticatedUser,
+    admin: web::Data<Admin>,
+    query: web::Query<PageQuery>,
+) -> Result<HttpResponse, DomainError> {
+    let users = admin
+        .list_users(user.id, query.limit.unwrap_or(0), query.offset.unwrap_or(0))
+        .await?;
+    let response: Vec<_> = users.into_iter().map(UserResponse::from).collect();
+
+    info!(
+        admin_id = %user.id,
+        count = response.len(),
+        "users listed"
+    );
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Disables a user account.
+#[post("/users/{id}/disable")]
+async fn disable_user(
+    user: AuthenticatedUser,
+    admin: web::Data<Admin>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    let disabled = admin.disable_user(user.id, path.into_inner()).await?;
+
+    info!(
+        admin_id = %user.id,
+        user_id = %disabled.id,
+        "user disabled"
+    );
+
+    Ok(HttpResponse::Ok().json(UserResponse::from(disabled)))
+}
+
+/// Re-enables a disabled user account.
+#[post("/users/{id}/enable")]
+async fn enable_user(
+    user: AuthenticatedUser,
+    admin: web::Data<Admin>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    let enabled = admin.enable_user(user.id, path.into_inner()).await?;
+
+    info!(
+        admin_id = %user.id,
+        user_id = %enabled.id,
+        "user enabled"
+    );
+
+    Ok(HttpResponse::Ok().json(UserResponse::from(enabled)))
+}
+
+/// Changes the role of a user.
+#[put("/users/{id}/role")]
+async fn set_role(
+    user: AuthenticatedUser,
+    admin: web::Data<Admin>,
+    path: web::Path<Uuid>,
+    payload: web::Json<SetRoleRequest>,
+) -> Result<HttpResponse, DomainError> {
+    let updated = admin
+        .set_role(user.id, path.into_inner(), payload.role)
+        .await?;
+
+    info!(
+        admin_id = %user.id,
+        user_id = %updated.id,
+        role = %updated.role,
+        "user role changed"
+    );
+
+    Ok(HttpResponse::Ok().json(UserResponse::from(updated)))
+}
+
+/// Lists the moderation queue of flagged comments, oldest first.
+///
+/// The total number of flagged comments is returned in `X-Total-Count`.
+#[get("/comments/flagged")]
+async fn list_flagged_comments(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    admin: web::Data<Admin>,
+    comments: web::Data<Comments>,
+    query: web::Query<PageQuery>,
+) -> Result<HttpResponse, DomainError> {
+    admin.require_admin(user.id).await?;
+
+    let page = comments
+        .list_flagged(query.limit.unwrap_or(0), query.offset.unwrap_or(0))
+        .await?
+        .map(FlaggedCommentResponse::from);
+
+    info!(
+        admin_id = %user.id,
+        total = page.total,
+        "flagged comments listed"
+    );
+
+    Ok(page_response(&req, page))
+}
+
+/// Publishes a flagged comment on its post.
+#[post("/comments/{id}/approve")]
+async fn approve_comment(
+    user: AuthenticatedUser,
+    admin: web::Data<Admin>,
+    comments: web::Data<Comments>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    admin.require_admin(user.id).await?;
+
+    let approved = comments.approve_comment(path.into_inner()).await?;
+
+    info!(
+        admin_id = %user.id,
+        comment_id = %approved.id,
+        "comment approved"
+    );
+
+    Ok(HttpResponse::Ok().json(CommentResponse::from(approved)))
+}
+
+/// Deletes a comment, typically one rejected from the moderation queue.
+#[delete("/comments/{id}")]
+async fn reject_comment(
+    user: AuthenticatedUser,
+    admin: web::Data<Admin>,
+    comments: web::Data<Comments>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    admin.require_admin(user.id).await?;
+
+    let id = path.into_inner();
+    comments.reject_comment(id).await?;
+
+    info!(
+        admin_id = %user.id,
+        comment_id = %id,
+        "comment rejected"
+    );
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Lists the review queue of flagged posts, oldest flag first.
+///
+/// The total number of flagged posts is returned in `X-Total-Count`.
+#[get("/posts/flagged")]
+async fn list_flagged_posts(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    admin: web::Data<Admin>,
+    posts: web::Data<Posts>,
+    query: web::Query<PageQuery>,
+) -> Result<HttpResponse, DomainError> {
+    admin.require_admin(user.id).await?;
+
+    let page = posts
+        .list_flagged(query.limit.unwrap_or(0), query.offset.unwrap_or(0))
+        .await?
+        .map(FlaggedPostResponse::from);
+
+    info!(
+        admin_id = %user.id,
+        total = page.total,
+        "flagged posts listed"
+    );
+
+    Ok(page_response(&req, page))
+}
+
+/// Takes a post out of the review queue, leaving it published.
+#[post("/posts/{id}/approve")]
+async fn approve_post(
+    user: AuthenticatedUser,
+    admin: web::Data<Admin>,
+    posts: web::Data<Posts>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    admin.require_admin(user.id).await?;
+
+    let id = path.into_inner();
+    posts.approve_post(id).await?;
+
+    info!(
+        admin_id = %user.id,
+        post_id = %id,
+        "post approved"
+    );
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Moves a post to the trash, typically one rejected from the review queue.
+#[delete("/posts/{id}")]
+async fn reject_post(
+    user: AuthenticatedUser,
+    admin: web::Data<Admin>,
+    posts: web::Data<Posts>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    admin.require_admin(user.id).await?;
+
+    let id = path.into_inner();
+    posts.delete_post(id).await?;
+
+    info!(
+        admin_id = %user.id,
+        post_id = %id,
+        "post rejected"
+    );
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Lists the verified webmentions awaiting moderation, oldest first.
+///
+/// The total number of held mentions is returned in `X-Total-Count`.
+#[get("/mentions/held")]
+async fn list_held_mentions(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    admin: web::Data<Admin>,
+    mentions: web::Data<Mentions>,
+    query: web::Query<PageQuery>,
+) -> Result<HttpResponse, DomainError> {
+    admin.require_admin(user.id).await?;
+
+    let page = mentions
+        .list_held(query.limit.unwrap_or(0), query.offset.unwrap_or(0))
+        .await?
+        .map(MentionResponse::from);
+
+    info!(
+        admin_id = %user.id,
+        total = page.total,
+        "held mentions listed"
+    );
+
+    Ok(page_response(&req, page))
+}
+
+/// Publishes a held webmention on its post.
+#[post("/mentions/{id}/approve")]
+async fn approve_mention(
+    user: AuthenticatedUser,
+    admin: web::Data<Admin>,
+    mentions: web::Data<Mentions>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    admin.require_admin(user.id).await?;
+
+    let approved = mentions.approve_mention(path.into_inner()).await?;
+
+    info!(
+        admin_id = %user.id,
+        mention_id = %approved.id,
+        "mention approved"
+    );
+
+    Ok(HttpResponse::Ok().json(MentionResponse::from(approved)))
+}
+
+/// Deletes a webmention, typically one rejected from the moderation queue.
+#[delete("/mentions/{id}")]
+async fn reject_mention(
+    user: AuthenticatedUser,
+    admin: web::Data<Admin>,
+    mentions: web::Data<Mentions>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    admin.require_admin(user.id).await?;
+
+    let id = path.into_inner();
+    mentions.reject_mention(id).await?;
+
+    info!(
+        admin_id = %user.id,
+        mention_id = %id,
+        "mention rejected"
+    );
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Imports posts from a WordPress WXR or Medium export sent as the request
+/// body, and returns a summary of what was imported and skipped.
+#[post("/import")]
+async fn import_posts(
+    user: AuthenticatedUser,
+    admin: web::Data<Admin>,
+    imports: web::Data<Imports>,
+    api_path: web::Data<ApiPath>,
+    query: web::Query<ImportQuery>,
+    mut payload: web::Payload,
+) -> Result<HttpResponse, DomainError> {
+    admin.require_admin(user.id).await?;
+
+    let mut bytes = Vec::new();
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk.map_err(|e| DomainError::Validation(e.to_string()))?;
+        if bytes.len() + chunk.len() > imports.max_bytes() {
+            return Err(DomainError::PayloadTooLarge(format!(
+                "export exceeds {} bytes",
+                imports.max_bytes()
+            )));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+    if bytes.is_empty() {
+        return Err(DomainError::Validation("export must not be empty".into()));
+    }
+
+    let summary = imports
+        .import(
+            query.format,
+            bytes,
+            query.author_id.unwrap_or(user.id),
+            &api_path.0,
+        )
+        .await?;
+
+    info!(
+        admin_id = %user.id,
+        format = %query.format,
+        imported = summary.imported.len(),
+        skipped = summary.skipped.len(),
+        "posts imported"
+    );
+
+    Ok(HttpResponse::Ok().json(summary))
+}
+
+/// Rebuilds the search index from every public post.
+///
+/// Only needed with an external search engine, after enabling it or when it
+/// fell out of step with the database.
+#[post("/search/reindex")]
+async fn reindex_search(
+    user: AuthenticatedUser,
+    admin: web::Data<Admin>,
+    posts: web::Data<Posts>,
+) -> Result<HttpResponse, DomainError> {
+    admin.require_admin(user.id).await?;
+
+    let indexed = posts.reindex_search().await?;
+
+    info!(
+        admin_id = %user.id,
+        indexed,
+        "search index rebuilt"
+    );
+
+    Ok(HttpResponse::Ok().json(ReindexResponse { indexed }))
+}
+
+/// Lists the IP allow and deny rules added through the API, oldest first.
+///
+/// Rules from the configuration are not included.
+#[get("/ip-rules")]
+async fn list_ip_rules(
+    user: AuthenticatedUser,
+    admin: web::Data<Admin>,
+    ip_rules: web::Data<IpRuleService>,
+) -> Result<HttpResponse, DomainError> {
+    admin.require_admin(user.id).await?;
+
+    let rules = ip_rules.list_rules().await?;
+    let response: Vec<_> = rules.into_iter().map(IpRuleResponse::from).collect();
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Adds an IP allow or deny rule.
+#[post("/ip-rules")]
+async fn create_ip_rule(
+    user: AuthenticatedUser,
+    admin: web::Data<Admin>,
+    ip_rules: web::Data<IpRuleService>,
+    payload: web::Json<CreateIpRuleRequest>,
+) -> Result<HttpResponse, DomainError> {
+    admin.require_admin(user.id).await?;
+
+    let payload = payload.into_inner();
+    let rule = ip_rules
+        .add_rule(&payload.network, payload.action, payload.reason)
+        .await?;
+
+    info!(
+        admin_id = %user.id,
+        rule_id = %rule.id,
+        network = %rule.network,
+        action = ?rule.action,
+        "IP rule added"
+    );
+
+    Ok(HttpResponse::Created().json(IpRuleResponse::from(rule)))
+}
+
+/// Removes an IP allow or deny rule.
+#[delete("/ip-rules/{id}")]
+async fn delete_ip_rule(
+    user: AuthenticatedUser,
+    admin: web::Data<Admin>,
+    ip_rules: web::Data<IpRuleService>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    admin.require_admin(user.id).await?;
+
+    let id = path.into_inner();
+    ip_rules.remove_rule(id).await?;
+
+    info!(
+        admin_id = %user.id,
+        rule_id = %id,
+        "IP rule removed"
+    );
+
+    Ok(HttpResponse::NoContent().finish())
+}
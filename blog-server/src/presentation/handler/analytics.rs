@@ -0,0 +1,63 @@
+use crate::application::analytics_service::AnalyticsService;
+use crate::data::analytics_repository::PostgresAnalyticsRepository;
+use crate::domain::error::DomainError;
+use crate::presentation::auth::AuthenticatedUser;
+use crate::presentation::dto::{PostStatsResponse, StatsQuery};
+use actix_web::{get, web, HttpResponse, Scope};
+use tracing::info;
+use uuid::Uuid;
+
+/// Analytics service type used by the HTTP handlers.
+pub(crate) type Analytics = AnalyticsService<PostgresAnalyticsRepository>;
+
+/// Returns the authenticated user's post analytics scope.
+pub fn scope() -> Scope {
+    web::scope("/analytics")
+        .service(list_post_stats)
+        .service(get_post_stats)
+}
+
+/// Lists views, reactions and comments of the authenticated user's posts
+/// over the last `days` days, most viewed first.
+#[get("/posts")]
+async fn list_post_stats(
+    user: AuthenticatedUser,
+    analytics: web::Data<Analytics>,
+    query: web::Query<StatsQuery>,
+) -> Result<HttpResponse, DomainError> {
+    let stats = analytics
+        .list_post_stats(user.id, query.days.unwrap_or(0))
+        .await?;
+
+    info!(
+        user_id = %user.id,
+        count = stats.len(),
+        "post stats listed"
+    );
+
+    let response: Vec<PostStatsResponse> = stats.into_iter().map(Into::into).collect();
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Returns views, reactions and comments of one of the authenticated user's
+/// posts over the last `days` days.
+#[get("/posts/{id}")]
+async fn get_post_stats(
+    user: AuthenticatedUser,
+    analytics: web::Data<Analytics>,
+    path: web::Path<Uuid>,
+    query: web::Query<StatsQuery>,
+) -> Result<HttpResponse, DomainError> {
+    let stats = analytics
+        .get_post_stats(user.id, path.into_inner(), query.days.unwrap_or(0))
+        .await?;
+
+    info!(
+        user_id = %user.id,
+        post_id = %stats.post_id,
+        views = stats.views,
+        "post stats retrieved"
+    );
+
+    Ok(HttpResponse::Ok().json(PostStatsResponse::from(stats)))
+}
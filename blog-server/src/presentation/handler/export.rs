@@ -0,0 +1,101 @@
+use crate::application::export_service::ExportService;
+use crate::data::comment_repository::PostgresCommentRepository;
+use crate::data::export_repository::PostgresExportRepository;
+use crate::data::media_repository::PostgresMediaRepository;
+use crate::data::post_repository::PostgresPostRepository;
+use crate::data::user_repository::PostgresUserRepository;
+use crate::domain::error::DomainError;
+use crate::presentation::auth::AuthenticatedUser;
+use crate::presentation::dto::DataExportResponse;
+use crate::presentation::handler::media::ApiPath;
+use actix_web::http::header::{
+    self, CacheControl, CacheDirective, ContentDisposition, DispositionParam, DispositionType,
+};
+use actix_web::{get, post, web, HttpResponse, Scope};
+use tracing::info;
+use uuid::Uuid;
+
+/// Export service type used by the HTTP handlers.
+pub(crate) type Exports = ExportService<
+    PostgresExportRepository,
+    PostgresUserRepository,
+    PostgresPostRepository,
+    PostgresCommentRepository,
+    PostgresMediaRepository,
+>;
+
+/// Returns the authenticated data export scope.
+pub fn scope() -> Scope {
+    web::scope("/exports")
+        .service(request_export)
+        .service(get_export)
+}
+
+/// Returns the public export download scope.
+pub fn public_scope() -> Scope {
+    web::scope("/exports").service(download_export)
+}
+
+/// Starts exporting everything the authenticated user stored on the blog.
+///
+/// The archive is built in the background; poll the export, or wait for its
+/// `export_completed` event, for the download link.
+#[post("")]
+async fn request_export(
+    user: AuthenticatedUser,
+    exports: web::Data<Exports>,
+    api_path: web::Data<ApiPath>,
+) -> Result<HttpResponse, DomainError> {
+    let export = exports.request_export(user.id).await?;
+
+    info!(
+        user_id = %user.id,
+        export_id = %export.id,
+        "data export requested"
+    );
+
+    Ok(HttpResponse::Accepted().json(DataExportResponse::new(export, None, &api_path.0)))
+}
+
+/// Returns one of the authenticated user's exports, with a download link once ready.
+#[get("/{id}")]
+async fn get_export(
+    user: AuthenticatedUser,
+    exports: web::Data<Exports>,
+    api_path: web::Data<ApiPath>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    let export = exports.get_export(path.into_inner(), user.id).await?;
+    let token = exports.download_token(&export)?;
+
+    Ok(HttpResponse::Ok().json(DataExportResponse::new(export, token, &api_path.0)))
+}
+
+/// Downloads an export archive through its signed, expiring link.
+#[get("/{token}")]
+async fn download_export(
+    exports: web::Data<Exports>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, DomainError> {
+    let (export, bytes) = exports.download(&path.into_inner()).await?;
+
+    info!(
+        export_id = %export.id,
+        "data export downloaded"
+    );
+
+    Ok(HttpResponse::Ok()
+        .insert_header((header::CONTENT_TYPE, "application/zip"))
+        .insert_header(ContentDisposition {
+            disposition: DispositionType::Attachment,
+            parameters: vec![DispositionParam::Filename(format!(
+                "blog-export-{}.zip",
+                export.created_at.format("%Y%m%d")
+            ))],
+        })
+        .insert_header(CacheControl(vec![
+            CacheDirective::Private,
+            CacheDirective::NoStore,
+        ]))
+        .body(bytes))
+}
@@ -0,0 +1,121 @@
+use crate::application::federation_service::{FederationService, ACTIVITY_JSON};
+use crate::data::federation_repository::PostgresFederationRepository;
+use crate::data::post_repository::PostgresPostRepository;
+use crate::data::user_repository::PostgresUserRepository;
+use crate::domain::error::DomainError;
+use crate::infrastructure::http_signature::IncomingRequest;
+use crate::presentation::dto::WebFingerQuery;
+use actix_web::{get, post, web, HttpRequest, HttpResponse, Scope};
+use serde_json::Value;
+use tracing::info;
+use uuid::Uuid;
+
+/// Media type of WebFinger responses.
+const JRD_JSON: &str = "application/jrd+json";
+
+/// Federation service type used by the HTTP handlers.
+pub(crate) type Federation =
+    FederationService<PostgresFederationRepository, PostgresUserRepository, PostgresPostRepository>;
+
+/// Returns the ActivityPub actor and object scope.
+pub fn scope() -> Scope {
+    web::scope("/ap")
+        .service(actor)
+        .service(inbox)
+        .service(outbox)
+        .service(followers)
+        .service(article)
+}
+
+/// Resolves `acct:username@domain` to the author's ActivityPub actor.
+pub async fn webfinger(
+    federation: web::Data<Federation>,
+    query: web::Query<WebFingerQuery>,
+) -> Result<HttpResponse, DomainError> {
+    let document = federation.webfinger(&query.resource).await?;
+    Ok(HttpResponse::Ok().content_type(JRD_JSON).json(document))
+}
+
+/// Returns an author's actor document.
+#[get("/users/{id}")]
+async fn actor(
+    federation: web::Data<Federation>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    Ok(activity_json(federation.actor(path.into_inner()).await?))
+}
+
+/// Accepts a signed activity for an author, such as a follow.
+#[post("/users/{id}/inbox")]
+async fn inbox(
+    req: HttpRequest,
+    federation: web::Data<Federation>,
+    path: web::Path<Uuid>,
+    body: web::Bytes,
+) -> Result<HttpResponse, DomainError> {
+    let user_id = path.into_inner();
+    let headers = req
+        .headers()
+        .iter()
+        .filter_map(|(name, value)| {
+            let value = value.to_str().ok()?;
+            Some((name.as_str().to_lowercase(), value.to_string()))
+        })
+        .collect();
+    let target = req
+        .uri()
+        .path_and_query()
+        .map_or_else(|| req.path(), |target| target.as_str());
+
+    federation
+        .handle_inbox(
+            user_id,
+            IncomingRequest {
+                method: req.method().as_str(),
+                target,
+                headers,
+                body: &body,
+            },
+        )
+        .await?;
+
+    info!(user_id = %user_id, "inbox activity accepted");
+
+    Ok(HttpResponse::Accepted().finish())
+}
+
+/// Returns an author's latest public posts.
+#[get("/users/{id}/outbox")]
+async fn outbox(
+    federation: web::Data<Federation>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    Ok(activity_json(federation.outbox(path.into_inner()).await?))
+}
+
+/// Returns how many remote actors follow an author.
+#[get("/users/{id}/followers")]
+async fn followers(
+    federation: web::Data<Federation>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    Ok(activity_json(
+        federation.followers(path.into_inner()).await?,
+    ))
+}
+
+/// Returns a public post as an ActivityPub `Article`.
+#[get("/posts/{id}")]
+async fn article(
+    federation: web::Data<Federation>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    Ok(activity_json(federation.article(path.into_inner()).await?))
+}
+
+/// Responds with an ActivityPub document.
+fn activity_json(document: Value) -> HttpResponse {
+    HttpResponse::Ok()
+        .content_type(ACTIVITY_JSON)
+        .json(document)
+}
@@ -0,0 +1,78 @@
+use crate::domain::error::DomainError;
+use crate::infrastructure::static_files::{StaticAsset, StaticFiles};
+use actix_web::http::header::{self, CacheControl, CacheDirective, ContentType};
+use actix_web::http::Method;
+use actix_web::{web, HttpRequest, HttpResponse};
+
+/// Page every client-side route is answered with.
+const INDEX: &str = "index.html";
+
+/// How long browsers may reuse a bundle file before revalidating, in seconds.
+///
+/// `wasm-pack` output names are not content-hashed, so this stays short.
+const ASSET_MAX_AGE: u32 = 3600;
+
+/// Serves the `blog-wasm` frontend for every path no other route matched.
+///
+/// Paths without a file extension that match no file get `index.html`, so
+/// client-side routes survive a reload. API paths never reach this handler.
+pub async fn frontend(
+    req: HttpRequest,
+    files: web::Data<StaticFiles>,
+) -> Result<HttpResponse, DomainError> {
+    if !matches!(*req.method(), Method::GET | Method::HEAD) {
+        return Ok(HttpResponse::NotFound().finish());
+    }
+
+    let path = match req.path().trim_start_matches('/') {
+        "" => INDEX,
+        path => path,
+    };
+    let asset = match files.get(path).await? {
+        Some(asset) => Some((path, asset)),
+        None if !path.rsplit('/').next().unwrap_or_default().contains('.') => {
+            files.get(INDEX).await?.map(|asset| (INDEX, asset))
+        }
+        None => None,
+    };
+    let Some((path, asset)) = asset else {
+        return Ok(HttpResponse::NotFound().finish());
+    };
+
+    Ok(respond(&req, path, asset))
+}
+
+/// Builds the response for a file, honoring `If-None-Match`.
+fn respond(req: &HttpRequest, path: &str, asset: StaticAsset) -> HttpResponse {
+    let etag = format!("\"{}\"", asset.etag);
+    // The page must be revalidated so a new deployment is picked up at once.
+    let cache = if path == INDEX {
+        CacheControl(vec![CacheDirective::NoCache])
+    } else {
+        CacheControl(vec![CacheDirective::Public, CacheDirective::MaxAge(ASSET_MAX_AGE)])
+    };
+
+    let not_modified = req
+        .headers()
+        .get(header::IF_NONE_MATCH)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.split(',').any(|tag| tag.trim() == etag || tag.trim() == "*"));
+    if not_modified {
+        return HttpResponse::NotModified()
+            .insert_header((header::ETAG, etag))
+            .insert_header(cache)
+            .finish();
+    }
+
+    let content_type = asset
+        .content_type
+        .parse()
+        .map(ContentType)
+        .unwrap_or_else(|_| ContentType::octet_stream());
+
+    HttpResponse::Ok()
+        .insert_header(content_type)
+        .insert_header((header::ETAG, etag))
+        .insert_header(cache)
+        .body(asset.body.into_owned())
+}
@@ -0,0 +1,99 @@
+use crate::infrastructure::build_info;
+use crate::infrastructure::database::{self, MigrationStatus};
+use crate::presentation::dto::{DependencyHealth, HealthResponse, ReadinessResponse, VersionResponse};
+use actix_web::{web, HttpResponse, Responder, Scope};
+use chrono::Utc;
+use sqlx::PgPool;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Server version reported by the health endpoints.
+pub use crate::infrastructure::build_info::VERSION;
+
+/// Upper bound on a single dependency check.
+const CHECK_TIMEOUT: Duration = Duration::from_secs(2);
+
+/// Returns the liveness and readiness probe scope.
+pub fn scope() -> Scope {
+    web::scope("")
+        .route("/healthz", web::get().to(healthz))
+        .route("/readyz", web::get().to(readyz))
+}
+
+/// Liveness probe: the process is up and serving requests.
+pub async fn healthz() -> impl Responder {
+    HttpResponse::Ok().json(HealthResponse {
+        status: "ok",
+        version: VERSION,
+        timestamp: Utc::now(),
+    })
+}
+
+/// Reports the version, commit, build time and features of the running server.
+pub async fn version() -> impl Responder {
+    HttpResponse::Ok().json(VersionResponse {
+        version: VERSION,
+        git_sha: build_info::GIT_SHA,
+        build_timestamp: build_info::build_time(),
+        features: build_info::features(),
+    })
+}
+
+/// Readiness probe: every dependency the server needs is reachable.
+///
+/// Responds with 503 when any check fails, including while migrations are
+/// still pending on a lazily connected database.
+async fn readyz(pool: web::Data<PgPool>, migrations: web::Data<MigrationStatus>) -> impl Responder {
+    let mut checks = BTreeMap::new();
+    checks.insert("database", check_database(&pool).await);
+    checks.insert("migrations", check_migrations(&migrations));
+
+    let ready = checks.values().all(|check| check.error.is_none());
+    let body = ReadinessResponse {
+        status: if ready { "ok" } else { "unavailable" },
+        version: VERSION,
+        checks,
+    };
+
+    if ready {
+        HttpResponse::Ok().json(body)
+    } else {
+        HttpResponse::ServiceUnavailable().json(body)
+    }
+}
+
+/// Reports whether the database schema has been migrated.
+fn check_migrations(migrations: &MigrationStatus) -> DependencyHealth {
+    DependencyHealth {
+        status: if migrations.is_done() { "ok" } else { "error" },
+        latency_ms: 0,
+        error: (!migrations.is_done()).then(|| "migrations pending".to_string()),
+    }
+}
+
+/// Pings the database and records how long it took.
+async fn check_database(pool: &PgPool) -> DependencyHealth {
+    let started = Instant::now();
+    let result = match tokio::time::timeout(CHECK_TIMEOUT, database::ping(pool)).await {
+        Ok(result) => result.map_err(|e| e.to_string()),
+        Err(_) => Err("timed out".to_string()),
+    };
+    let latency_ms = started.elapsed().as_millis() as u64;
+
+    match result {
+        Ok(()) => DependencyHealth {
+            status: "ok",
+            latency_ms,
+            error: None,
+        },
+        Err(e) => {
+            warn!(error = %e, "database readiness check failed");
+            DependencyHealth {
+                status: "error",
+                latency_ms,
+                error: Some(e),
+            }
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use actix_web::{get, rt, web, HttpRequest, HttpResponse};
+use actix_ws::{AggregatedMessage, AggregatedMessageStream, Session};
+use futures_util::StreamExt;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Receiver;
+use tokio::time::{interval, MissedTickBehavior};
+use tracing::{info, warn};
+use uuid::Uuid;
+
+use crate::application::auth_service::AuthService;
+use crate::data::user_repository::PostgresUserRepository;
+use crate::domain::comment::{CommentOrigin, CommentStatus};
+use crate::domain::error::DomainError;
+use crate::domain::event::{BlogEvent, EventKind};
+use crate::infrastructure::events::EventBus;
+use crate::presentation::auth::extract_user_from_token;
+use crate::presentation::dto::{CommentResponse, LiveCommentMessage, LiveCommentRequest};
+use crate::presentation::handler::protected::{comment_origin, Comments, Posts};
+
+/// Auth service type used by the HTTP handlers.
+type Auth = AuthService<PostgresUserRepository>;
+
+/// Interval between pings sent to the client.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// How long a client may stay silent, pongs included, before it is dropped.
+const CLIENT_TIMEOUT: Duration = Duration::from_secs(45);
+
+/// Largest message accepted from a client.
+const MAX_MESSAGE_SIZE: usize = 64 * 1024;
+
+/// Opens a WebSocket streaming the comments published on a public post.
+///
+/// Anyone may listen. Clients send `{"type": "auth", "token": "..."}` to
+/// authenticate and then `{"type": "comment", "content": "..."}` to comment.
+#[get("/posts/{id}/comments/ws")]
+async fn comment_socket(
+    req: HttpRequest,
+    body: web::Payload,
+    posts: web::Data<Posts>,
+    comments: web::Data<Comments>,
+    auth: web::Data<Auth>,
+    bus: web::Data<EventBus>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, actix_web::Error> {
+    let post_id = path.into_inner();
+    posts.read_post(post_id, None).await?;
+
+    let (response, session, stream) = actix_ws::handle(&req, body)?;
+    let stream = stream
+        .max_frame_size(MAX_MESSAGE_SIZE)
+        .aggregate_continuations()
+        .max_continuation_size(MAX_MESSAGE_SIZE);
+
+    info!(post_id = %post_id, "comment socket opened");
+
+    let socket = CommentSocket {
+        session,
+        post_id,
+        origin: comment_origin(&req),
+        user_id: None,
+        comments: comments.into_inner(),
+        auth: auth.into_inner(),
+    };
+    rt::spawn(socket.run(stream, bus.subscribe()));
+
+    Ok(response)
+}
+
+/// One client connected to a post's comment socket.
+struct CommentSocket {
+    session: Session,
+    post_id: Uuid,
+    origin: CommentOrigin,
+    user_id: Option<Uuid>,
+    comments: Arc<Comments>,
+    auth: Arc<Auth>,
+}
+
+impl CommentSocket {
+    /// Relays published comments to the client and handles its messages
+    /// until either side closes the socket.
+    async fn run(
+        mut self,
+        mut stream: AggregatedMessageStream,
+        mut events: Receiver<Arc<BlogEvent>>,
+    ) {
+        let mut heartbeat = interval(HEARTBEAT_INTERVAL);
+        heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+        let mut last_seen = Instant::now();
+
+        let reason = loop {
+            let sent = tokio::select! {
+                _ = heartbeat.tick() => {
+                    if last_seen.elapsed() > CLIENT_TIMEOUT {
+                        info!(post_id = %self.post_id, "comment socket timed out");
+                        break None;
+                    }
+                    self.session.ping(b"").await.is_ok()
+                }
+                received = events.recv() => match received {
+                    Ok(event) => self.relay(&event).await,
+                    Err(RecvError::Lagged(skipped)) => {
+                        warn!(post_id = %self.post_id, skipped, "comment socket lagged behind");
+                        true
+                    }
+                    Err(RecvError::Closed) => break None,
+                },
+                message = stream.next() => {
+                    last_seen = Instant::now();
+                    match message {
+                        Some(Ok(AggregatedMessage::Text(text))) => self.receive(&text).await,
+                        Some(Ok(AggregatedMessage::Ping(bytes))) => {
+                            self.session.pong(&bytes).await.is_ok()
+                        }
+                        Some(Ok(AggregatedMessage::Close(reason))) => break reason,
+                        Some(Ok(_)) => true,
+                        Some(Err(e)) => {
+                            warn!(
+                                post_id = %self.post_id,
+                                error = %e,
+                                "comment socket protocol error"
+                            );
+                            break None;
+                        }
+                        None => break None,
+                    }
+                }
+            };
+
+            if !sent {
+                // The client is gone; there is nobody to close the socket with.
+                return;
+            }
+        };
+
+        info!(post_id = %self.post_id, "comment socket closed");
+        let _ = self.session.close(reason).await;
+    }
+
+    /// Sends the client a comment published on the post; returns whether
+    /// the socket is still open.
+    async fn relay(&mut self, event: &BlogEvent) -> bool {
+        let EventKind::CommentAdded {
+            comment_id,
+            post_id,
+            ..
+        } = &event.kind
+        else {
+            return true;
+        };
+        if *post_id != self.post_id {
+            return true;
+        }
+
+        match self.comments.get_comment(*comment_id).await {
+            Ok(comment) => {
+                self.send(LiveCommentMessage::Comment(CommentResponse::from(comment)))
+                    .await
+            }
+            Err(e) => {
+                warn!(comment_id = %comment_id, error = %e, "failed to load comment for socket");
+                true
+            }
+        }
+    }
+
+    /// Handles a message from the client; returns whether the socket is
+    /// still open.
+    async fn receive(&mut self, text: &str) -> bool {
+        let request = match serde_json::from_str(text) {
+            Ok(request) => request,
+            Err(e) => {
+                let err = DomainError::Validation(format!("invalid message: {}", e));
+                return self.send(err.into()).await;
+            }
+        };
+
+        match request {
+            LiveCommentRequest::Auth { token } => {
+                match extract_user_from_token(&token, self.auth.keys(), &self.auth).await {
+                    Ok(user) => {
+                        self.user_id = Some(user.id);
+                        self.send(LiveCommentMessage::Authenticated { user_id: user.id })
+                            .await
+                    }
+                    Err(_) => {
+                        let err = DomainError::InvalidCredentials("invalid token".into());
+                        self.send(err.into()).await
+                    }
+                }
+            }
+            LiveCommentRequest::Comment { content, parent_id } => {
+                let Some(user_id) = self.user_id else {
+                    let err = DomainError::InvalidCredentials("authenticate first".into());
+                    return self.send(err.into()).await;
+                };
+
+                let added = self
+                    .comments
+                    .add_comment(self.post_id, user_id, content, parent_id, &self.origin)
+                    .await;
+                match added {
+                    Ok(comment) => {
+                        info!(
+                            author_id = %user_id,
+                            post_id = %self.post_id,
+                            comment_id = %comment.id,
+                            "comment added over socket"
+                        );
+                        // Published comments reach everyone, the author included,
+                        // through the event bus; held ones only the author.
+                        if comment.status == CommentStatus::Flagged {
+                            let comment = CommentResponse::from(comment);
+                            return self.send(LiveCommentMessage::Comment(comment)).await;
+                        }
+                        true
+                    }
+                    Err(err) => self.send(err.into()).await,
+                }
+            }
+        }
+    }
+
+    /// Sends a message as JSON; returns whether the socket is still open.
+    async fn send(&mut self, message: LiveCommentMessage) -> bool {
+        let text = serde_json::to_string(&message).unwrap_or_else(|_| "{}".into());
+        self.session.text(text).await.is_ok()
+    }
+}
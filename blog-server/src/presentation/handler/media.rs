@@ -0,0 +1,408 @@
+use crate::application::media_service::{MediaService, MAX_UPLOAD_CHUNK_BYTES};
+use crate::data::media_repository::PostgresMediaRepository;
+use crate::domain::error::DomainError;
+use crate::domain::media::{MediaFile, VariantName};
+use crate::presentation::auth::AuthenticatedUser;
+use crate::presentation::dto::{
+    media_response, upload_response, MediaVariantResponse, StartUploadRequest, UploadChunkQuery,
+};
+use actix_multipart::Multipart;
+use actix_web::http::header::{
+    self, CacheControl, CacheDirective, ContentRange, ContentRangeSpec, ContentType, ETag,
+    EntityTag, Header, IfModifiedSince, IfNoneMatch, IfRange, LastModified, Range,
+};
+use actix_web::http::StatusCode;
+use actix_web::{delete, get, post, put, web, HttpRequest, HttpResponse, Scope};
+use futures_util::StreamExt;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::info;
+use uuid::Uuid;
+
+/// Media service type used by the HTTP handlers.
+pub(crate) type Media = MediaService<PostgresMediaRepository>;
+
+/// Path of the current API version, e.g. `/api/v1`, that media URLs point under.
+#[derive(Clone)]
+pub struct ApiPath(pub String);
+
+/// Multipart field carrying the uploaded file.
+const FILE_FIELD: &str = "file";
+
+/// Returns the authenticated media scope.
+pub fn scope() -> Scope {
+    web::scope("/media")
+        .service(list_media)
+        .service(upload_media)
+        .service(start_upload)
+        .service(get_upload)
+        .service(upload_chunk)
+        .service(complete_upload)
+        .service(cancel_upload)
+        .service(delete_media)
+}
+
+/// Returns the public media scope.
+pub fn public_scope() -> Scope {
+    web::scope("/media")
+        .service(get_media)
+        // Before `get_variant`, whose `{variant}` would otherwise claim `variants`.
+        .service(list_variants)
+        .service(get_variant)
+}
+
+/// Uploads an image from the `file` field of a multipart form.
+#[post("")]
+async fn upload_media(
+    user: AuthenticatedUser,
+    media: web::Data<Media>,
+    api_path: web::Data<ApiPath>,
+    mut payload: Multipart,
+) -> Result<HttpResponse, DomainError> {
+    while let Some(field) = payload.next().await {
+        let mut field = field.map_err(|e| DomainError::Validation(e.to_string()))?;
+        if field.name() != Some(FILE_FIELD) {
+            continue;
+        }
+
+        let filename = field
+            .content_disposition()
+            .and_then(|cd| cd.get_filename())
+            .unwrap_or_default()
+            .to_string();
+
+        let mut bytes = Vec::new();
+        while let Some(chunk) = field.next().await {
+            let chunk = chunk.map_err(|e| DomainError::Validation(e.to_string()))?;
+            if bytes.len() + chunk.len() > media.max_bytes() {
+                return Err(DomainError::PayloadTooLarge(format!(
+                    "file exceeds {} bytes",
+                    media.max_bytes()
+                )));
+            }
+            bytes.extend_from_slice(&chunk);
+        }
+
+        let uploaded = media.upload(user.id, &filename, bytes).await?;
+        let response = media_response(uploaded, &api_path.0);
+
+        info!(
+            owner_id = %user.id,
+            media_id = %response.id,
+            size = response.size,
+            "media uploaded"
+        );
+
+        return Ok(HttpResponse::Created().json(response));
+    }
+
+    Err(DomainError::Validation(format!("missing `{FILE_FIELD}` field")))
+}
+
+/// Starts a chunked upload for a large file, to be sent over several
+/// requests that can resume after a dropped connection.
+#[post("/uploads")]
+async fn start_upload(
+    user: AuthenticatedUser,
+    media: web::Data<Media>,
+    payload: web::Json<StartUploadRequest>,
+) -> Result<HttpResponse, DomainError> {
+    let upload = media
+        .start_upload(user.id, &payload.filename, payload.size)
+        .await?;
+
+    info!(
+        owner_id = %user.id,
+        upload_id = %upload.id,
+        size = upload.size_bytes,
+        "chunked media upload started"
+    );
+
+    Ok(HttpResponse::Created().json(upload_response(upload, MAX_UPLOAD_CHUNK_BYTES)))
+}
+
+/// Returns the progress of a chunked upload, i.e. where to resume it.
+#[get("/uploads/{id}")]
+async fn get_upload(
+    user: AuthenticatedUser,
+    media: web::Data<Media>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    let upload = media.get_upload(user.id, path.into_inner()).await?;
+
+    Ok(HttpResponse::Ok().json(upload_response(upload, MAX_UPLOAD_CHUNK_BYTES)))
+}
+
+/// Stores the next chunk of a chunked upload from the raw request body.
+///
+/// `offset` must equal the bytes received so far; otherwise the chunk is
+/// refused with `409 Conflict`.
+#[put("/uploads/{id}")]
+async fn upload_chunk(
+    user: AuthenticatedUser,
+    media: web::Data<Media>,
+    path: web::Path<Uuid>,
+    query: web::Query<UploadChunkQuery>,
+    mut payload: web::Payload,
+) -> Result<HttpResponse, DomainError> {
+    let mut bytes = Vec::new();
+    while let Some(chunk) = payload.next().await {
+        let chunk = chunk.map_err(|e| DomainError::Validation(e.to_string()))?;
+        if bytes.len() + chunk.len() > MAX_UPLOAD_CHUNK_BYTES {
+            return Err(DomainError::PayloadTooLarge(format!(
+                "chunk exceeds {} bytes",
+                MAX_UPLOAD_CHUNK_BYTES
+            )));
+        }
+        bytes.extend_from_slice(&chunk);
+    }
+
+    let upload = media
+        .upload_chunk(user.id, path.into_inner(), query.offset, bytes)
+        .await?;
+
+    info!(
+        owner_id = %user.id,
+        upload_id = %upload.id,
+        received = upload.received_bytes,
+        size = upload.size_bytes,
+        "media upload chunk stored"
+    );
+
+    Ok(HttpResponse::Ok().json(upload_response(upload, MAX_UPLOAD_CHUNK_BYTES)))
+}
+
+/// Turns a fully received chunked upload into stored media.
+#[post("/uploads/{id}/complete")]
+async fn complete_upload(
+    user: AuthenticatedUser,
+    media: web::Data<Media>,
+    api_path: web::Data<ApiPath>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    let uploaded = media.complete_upload(user.id, path.into_inner()).await?;
+    let response = media_response(uploaded, &api_path.0);
+
+    info!(
+        owner_id = %user.id,
+        media_id = %response.id,
+        size = response.size,
+        "chunked media upload completed"
+    );
+
+    Ok(HttpResponse::Created().json(response))
+}
+
+/// Abandons a chunked upload, discarding the bytes received so far.
+#[delete("/uploads/{id}")]
+async fn cancel_upload(
+    user: AuthenticatedUser,
+    media: web::Data<Media>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    let id = path.into_inner();
+    media.cancel_upload(user.id, id).await?;
+
+    info!(owner_id = %user.id, upload_id = %id, "media upload cancelled");
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Lists the authenticated user's uploads, oldest first.
+#[get("")]
+async fn list_media(
+    user: AuthenticatedUser,
+    media: web::Data<Media>,
+    api_path: web::Data<ApiPath>,
+) -> Result<HttpResponse, DomainError> {
+    let response: Vec<_> = media
+        .list(user.id)
+        .await?
+        .into_iter()
+        .map(|media| media_response(media, &api_path.0))
+        .collect();
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Deletes one of the user's uploads.
+///
+/// URLs of the file and its variants stop working, including where posts
+/// embed them.
+#[delete("/{id}")]
+async fn delete_media(
+    user: AuthenticatedUser,
+    media: web::Data<Media>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    let id = path.into_inner();
+    media.delete(user.id, id).await?;
+
+    info!(owner_id = %user.id, media_id = %id, "media deleted");
+
+    Ok(HttpResponse::NoContent().finish())
+}
+
+/// Serves an uploaded file.
+///
+/// Media is immutable once stored, so responses may be cached indefinitely.
+/// Honors conditional and `Range` requests, see [`file_response`].
+#[get("/{id}")]
+async fn get_media(
+    req: HttpRequest,
+    media: web::Data<Media>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    let id = path.into_inner();
+    let file = media.read_variant(id, VariantName::Original).await?;
+
+    Ok(file_response(&req, id, file, true))
+}
+
+/// Lists the size variants generated for an uploaded image, smallest first.
+#[get("/{id}/variants")]
+async fn list_variants(
+    media: web::Data<Media>,
+    api_path: web::Data<ApiPath>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    let response: Vec<_> = media
+        .list_variants(path.into_inner())
+        .await?
+        .into_iter()
+        .map(|variant| MediaVariantResponse::new(variant, &api_path.0))
+        .collect();
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Serves a size variant of an uploaded image.
+///
+/// While the variant is still being generated the original is served instead,
+/// without long-term caching.
+#[get("/{id}/{variant}")]
+async fn get_variant(
+    req: HttpRequest,
+    media: web::Data<Media>,
+    path: web::Path<(Uuid, String)>,
+) -> Result<HttpResponse, DomainError> {
+    let (id, variant) = path.into_inner();
+    let variant: VariantName = variant.parse()?;
+    let file = media.read_variant(id, variant).await?;
+    let immutable = file.variant == variant;
+
+    Ok(file_response(&req, id, file, immutable))
+}
+
+/// Part of a file a request asks for.
+enum Requested {
+    /// The whole file.
+    Whole,
+    /// The bytes from `.0` to `.1`, inclusive.
+    Range(u64, u64),
+    /// A range starting past the end of the file.
+    Unsatisfiable,
+}
+
+/// Builds the response serving a stored file, cacheable forever when `immutable`.
+///
+/// Every variant of a file gets its own strong `ETag`, so a client holding
+/// the original served in place of a pending variant fetches the variant once
+/// it is ready. `If-None-Match`, or without it `If-Modified-Since`, yields
+/// `304`; a single `Range` yields `206`, or `416` past the end of the file.
+fn file_response(req: &HttpRequest, id: Uuid, file: MediaFile, immutable: bool) -> HttpResponse {
+    let etag = EntityTag::new_strong(format!("{}-{}", id, file.variant));
+    // HTTP dates have whole seconds; compare validators at that precision.
+    let modified = UNIX_EPOCH + Duration::from_secs(file.modified.timestamp().max(0) as u64);
+    let cache_control = if immutable {
+        CacheControl(vec![
+            CacheDirective::Public,
+            CacheDirective::MaxAge(31_536_000),
+            CacheDirective::Extension("immutable".into(), None),
+        ])
+    } else {
+        CacheControl(vec![CacheDirective::NoCache])
+    };
+
+    let response = |status: StatusCode| {
+        let mut builder = HttpResponse::build(status);
+        builder
+            .insert_header(ETag(etag.clone()))
+            .insert_header(LastModified(modified.into()))
+            .insert_header(cache_control.clone())
+            .insert_header((header::ACCEPT_RANGES, "bytes"));
+        builder
+    };
+
+    if not_modified(req, &etag, modified) {
+        return response(StatusCode::NOT_MODIFIED).finish();
+    }
+
+    let content_type = file
+        .content_type
+        .parse()
+        .map(ContentType)
+        .unwrap_or_else(|_| ContentType::octet_stream());
+    let len = file.bytes.len() as u64;
+
+    match requested(req, &etag, modified, len) {
+        Requested::Whole => response(StatusCode::OK)
+            .insert_header(content_type)
+            .body(file.bytes),
+        Requested::Range(first, last) => response(StatusCode::PARTIAL_CONTENT)
+            .insert_header(content_type)
+            .insert_header(ContentRange(ContentRangeSpec::Bytes {
+                range: Some((first, last)),
+                instance_length: Some(len),
+            }))
+            .body(file.bytes[first as usize..=last as usize].to_vec()),
+        Requested::Unsatisfiable => response(StatusCode::RANGE_NOT_SATISFIABLE)
+            .insert_header(ContentRange(ContentRangeSpec::Bytes {
+                range: None,
+                instance_length: Some(len),
+            }))
+            .finish(),
+    }
+}
+
+/// Returns whether the client's copy is current, going by `If-None-Match`
+/// or, when that is absent, `If-Modified-Since`.
+fn not_modified(req: &HttpRequest, etag: &EntityTag, modified: SystemTime) -> bool {
+    if req.headers().contains_key(header::IF_NONE_MATCH) {
+        return match IfNoneMatch::parse(req) {
+            Ok(IfNoneMatch::Any) => true,
+            Ok(IfNoneMatch::Items(tags)) => tags.iter().any(|tag| tag.weak_eq(etag)),
+            Err(_) => false,
+        };
+    }
+
+    IfModifiedSince::parse(req).is_ok_and(|since| modified <= SystemTime::from(since.0))
+}
+
+/// Returns the part of a file of `len` bytes the `Range` header asks for.
+///
+/// The range is ignored when `If-Range` names another version of the file,
+/// so a stale partial copy is replaced rather than patched. Several ranges
+/// are answered with the whole file rather than a multipart body.
+fn requested(req: &HttpRequest, etag: &EntityTag, modified: SystemTime, len: u64) -> Requested {
+    let Ok(Range::Bytes(ranges)) = Range::parse(req) else {
+        return Requested::Whole;
+    };
+
+    let [range] = ranges.as_slice() else {
+        return Requested::Whole;
+    };
+
+    let current = match IfRange::parse(req) {
+        Ok(IfRange::EntityTag(tag)) => tag.strong_eq(etag),
+        Ok(IfRange::Date(date)) => SystemTime::from(date) == modified,
+        Err(_) => !req.headers().contains_key(header::IF_RANGE),
+    };
+    if !current {
+        return Requested::Whole;
+    }
+
+    match range.to_satisfiable_range(len) {
+        Some((first, last)) => Requested::Range(first, last),
+        None => Requested::Unsatisfiable,
+    }
+}
@@ -0,0 +1,9 @@
+use crate::infrastructure::metrics::Metrics;
+use actix_web::{web, HttpResponse, Responder};
+
+/// Exposes the server metrics in the Prometheus text format.
+pub async fn metrics(metrics: web::Data<Metrics>) -> impl Responder {
+    HttpResponse::Ok()
+        .content_type("text/plain; version=0.0.4")
+        .body(metrics.render())
+}
@@ -1,5 +1,44 @@
+/// Account settings handlers.
+pub mod account;
+
+/// Activity history handlers.
+pub mod activity;
+
+/// Post analytics handlers.
+pub mod analytics;
+
+/// Administrative HTTP handlers.
+pub mod admin;
+
+/// Personal data export handlers.
+pub mod export;
+
+/// ActivityPub and WebFinger handlers.
+pub mod federation;
+
+/// Static `blog-wasm` frontend.
+pub mod frontend;
+
+/// Liveness and readiness probes.
+pub mod health;
+
+/// Live comment WebSocket handlers.
+pub mod live;
+
+/// Media upload and download handlers.
+pub mod media;
+
+/// Prometheus metrics endpoint.
+pub mod metrics;
+
+/// Webmention handlers.
+pub mod webmention;
+
 /// Authenticated HTTP handlers.
 pub mod protected;
 
 /// Public HTTP handlers.
 pub mod public;
+
+/// Post translation handlers.
+pub mod translation;
@@ -1,129 +1,289 @@
+use crate::application::admin_service::AdminService;
+use crate::application::comment_service::CommentService;
 use crate::application::post_service::PostService;
+use crate::application::reaction_service::ReactionService;
+use crate::data::comment_repository::PostgresCommentRepository;
 use crate::data::post_repository::PostgresPostRepository;
+use crate::data::reaction_repository::PostgresReactionRepository;
+use crate::data::revision_repository::PostgresRevisionRepository;
+use crate::data::user_repository::PostgresUserRepository;
+use crate::domain::comment::CommentOrigin;
 use crate::domain::error::DomainError;
+use crate::domain::page::Page;
+use crate::domain::post::Visibility;
+use crate::domain::quota::QuotaUsage;
+use crate::infrastructure::events::EventBus;
 use crate::presentation::auth::AuthenticatedUser;
-use crate::presentation::dto::{CreatePostRequest, PostResponse};
+use crate::presentation::handler::media::Media;
+use crate::presentation::negotiate::NegotiatedBody;
+use crate::presentation::sse;
+use crate::presentation::dto::{
+    CommentResponse, CreateCommentRequest, CreatePostRequest, PageQuery, PostListQuery,
+    PostResponse, QuotaResponse, ReactionResponse, RevisionResponse, ShareLinkResponse,
+    TagResponse,
+};
+use actix_web::http::header;
 use actix_web::{
-    delete, get, post, put, web, HttpMessage, HttpRequest, HttpResponse, Scope,
+    delete, get, post, put, web, HttpRequest, HttpResponse, Scope,
 };
-use tracing::info;
+use serde::Serialize;
+use tracing::{info, warn};
 use uuid::Uuid;
 
+/// Post service type used by the HTTP handlers.
+pub(crate) type Posts = PostService<PostgresPostRepository, PostgresRevisionRepository>;
+
+/// Admin service type used to authorize raw post content.
+type Admin = AdminService<PostgresUserRepository>;
+
+/// Comment service type used by the HTTP handlers.
+pub(crate) type Comments = CommentService<PostgresCommentRepository, PostgresPostRepository>;
+
+/// Reaction service type used by the HTTP handlers.
+type Reactions = ReactionService<PostgresReactionRepository, PostgresPostRepository>;
+
 /// Returns the protected posts API scope.
 pub fn scope() -> Scope {
     web::scope("")
         .service(list_posts)
+        // Before `get_post`, whose `{id}` would otherwise claim `/posts/trash`.
+        .service(list_trash)
         .service(get_post)
         .service(create_posts)
         .service(update_post)
+        .service(list_revisions)
+        .service(get_revision)
+        .service(restore_revision)
         .service(delete_post)
+        .service(restore_post)
+        .service(share_post)
+        .service(list_tags)
+        .service(get_quota)
+        .service(list_comments)
+        .service(add_comment)
+        .service(delete_comment)
+        .service(get_reactions)
+        .service(like_post)
+        .service(unlike_post)
+        .service(bookmark_post)
+        .service(unbookmark_post)
+        .service(list_bookmarks)
+        .service(events)
 }
 
-/// Lists posts of the authenticated user.
+/// Header carrying the total number of items a paged list spans.
+pub const TOTAL_COUNT_HEADER: &str = "X-Total-Count";
+
+/// Header carrying links to the neighbouring pages of a paged list.
+pub const LINK_HEADER: &str = "Link";
+
+/// Header carrying the ID of the last event a reconnecting SSE client got.
+const LAST_EVENT_ID_HEADER: &str = "Last-Event-ID";
+
+/// Lists posts of the authenticated user, optionally filtered by tag.
+///
+/// The total number of matching posts is returned in `X-Total-Count` and
+/// links to the neighbouring pages in `Link`.
 #[get("/posts")]
 async fn list_posts(
     req: HttpRequest,
     user: AuthenticatedUser,
-    post: web::Data<PostService<PostgresPostRepository>>,
+    post: web::Data<Posts>,
+    query: web::Query<PostListQuery>,
 ) -> Result<HttpResponse, DomainError> {
-    let posts = post.list_posts(user.id).await?;
-    let response: Vec<_> = posts.into_iter().map(PostResponse::from).collect();
+    let page = post
+        .list_posts(
+            user.id,
+            query.tag.as_deref(),
+            query.limit.unwrap_or(0),
+            query.offset.unwrap_or(0),
+        )
+        .await?
+        .map(PostResponse::from);
 
     info!(
-        request_id = %request_id(&req),
         author_id = %user.id,
-        count = response.len(),
+        count = page.items.len(),
+        total = page.total,
         "posts listed"
     );
 
-    Ok(HttpResponse::Ok().json(response))
+    Ok(page_response(&req, page))
 }
 
 /// Creates a new post.
+///
+/// Raw content is only accepted from administrators.
 #[post("/posts")]
 async fn create_posts(
     req: HttpRequest,
     user: AuthenticatedUser,
-    post: web::Data<PostService<PostgresPostRepository>>,
+    post: web::Data<Posts>,
+    admin: web::Data<Admin>,
     payload: web::Json<CreatePostRequest>,
 ) -> Result<HttpResponse, DomainError> {
+    let payload = payload.into_inner();
+    if payload.raw {
+        admin.require_admin(user.id).await?;
+    }
+
     let post = post
         .create_post(
-            payload.title.clone(),
-            payload.content.clone(),
+            payload.title,
+            payload.content,
+            payload.tags.unwrap_or_default(),
+            payload.visibility.map(Visibility::from).unwrap_or_default(),
             user.id,
+            payload.raw,
         )
         .await?;
 
     let response = PostResponse::from(post);
 
     info!(
-        request_id = %request_id(&req),
         author_id = %user.id,
         title = %response.title,
         "post created"
     );
 
-    Ok(HttpResponse::Ok().json(response))
+    Ok(HttpResponse::Ok().negotiated(&req, &response))
 }
 
 /// Returns a post by its ID.
+///
+/// Private posts are only visible to their author.
 #[get("/posts/{id}")]
 async fn get_post(
     req: HttpRequest,
-    post: web::Data<PostService<PostgresPostRepository>>,
+    user: AuthenticatedUser,
+    post: web::Data<Posts>,
     path: web::Path<Uuid>,
 ) -> Result<HttpResponse, DomainError> {
-    let post = post.get_post(path.into_inner()).await?;
+    let post = post.read_post(path.into_inner(), Some(user.id)).await?;
     let response = PostResponse::from(post);
 
     info!(
-        request_id = %request_id(&req),
         post_id = %response.id,
         "post retrieved"
     );
 
-    Ok(HttpResponse::Ok().json(response))
+    Ok(HttpResponse::Ok().negotiated(&req, &response))
 }
 
 /// Updates an existing post.
+///
+/// Raw content is only accepted from administrators.
 #[put("/posts/{id}")]
 async fn update_post(
     req: HttpRequest,
-    post: web::Data<PostService<PostgresPostRepository>>,
+    user: AuthenticatedUser,
+    post: web::Data<Posts>,
+    admin: web::Data<Admin>,
     path: web::Path<Uuid>,
     payload: web::Json<CreatePostRequest>,
 ) -> Result<HttpResponse, DomainError> {
     let id = path.into_inner();
     let payload = payload.into_inner();
+    if payload.raw {
+        admin.require_admin(user.id).await?;
+    }
 
     let updated = post
-        .update_post(id, payload.title, payload.content)
+        .update_post(
+            id,
+            payload.title,
+            payload.content,
+            payload.tags,
+            payload.visibility.map(Visibility::from),
+            payload.expected_updated_at,
+            payload.raw,
+        )
         .await?;
 
     let response = PostResponse::from(updated);
 
     info!(
-        request_id = %request_id(&req),
         post_id = %response.id,
         "post updated"
     );
 
-    Ok(HttpResponse::Ok().json(response))
+    Ok(HttpResponse::Ok().negotiated(&req, &response))
+}
+
+/// Lists the earlier versions of a post, newest first.
+#[get("/posts/{id}/revisions")]
+async fn list_revisions(
+    req: HttpRequest,
+    post: web::Data<Posts>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    let id = path.into_inner();
+    let revisions = post.list_revisions(id).await?;
+    let response: Vec<RevisionResponse> =
+        revisions.into_iter().map(RevisionResponse::from).collect();
+
+    info!(
+        post_id = %id,
+        count = response.len(),
+        "post revisions listed"
+    );
+
+    Ok(HttpResponse::Ok().negotiated(&req, &response))
+}
+
+/// Returns one earlier version of a post.
+#[get("/posts/{id}/revisions/{revision_id}")]
+async fn get_revision(
+    req: HttpRequest,
+    post: web::Data<Posts>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, DomainError> {
+    let (id, revision_id) = path.into_inner();
+    let revision = post.get_revision(id, revision_id).await?;
+    let response = RevisionResponse::from(revision);
+
+    info!(
+        post_id = %id,
+        revision_id = %revision_id,
+        "post revision retrieved"
+    );
+
+    Ok(HttpResponse::Ok().negotiated(&req, &response))
+}
+
+/// Makes an earlier version of one of the authenticated user's posts
+/// current again; the replaced version becomes a revision in turn.
+#[post("/posts/{id}/revisions/{revision_id}/restore")]
+async fn restore_revision(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    post: web::Data<Posts>,
+    path: web::Path<(Uuid, Uuid)>,
+) -> Result<HttpResponse, DomainError> {
+    let (id, revision_id) = path.into_inner();
+    let restored = post.restore_revision(id, revision_id, user.id).await?;
+    let response = PostResponse::from(restored);
+
+    info!(
+        author_id = %user.id,
+        post_id = %response.id,
+        revision_id = %revision_id,
+        "post revision restored"
+    );
+
+    Ok(HttpResponse::Ok().negotiated(&req, &response))
 }
 
-/// Deletes a post by its ID.
+/// Moves a post to the trash.
 #[delete("/posts/{id}")]
 async fn delete_post(
-    req: HttpRequest,
-    post: web::Data<PostService<PostgresPostRepository>>,
+    post: web::Data<Posts>,
     path: web::Path<Uuid>,
 ) -> Result<HttpResponse, DomainError> {
     let id = path.into_inner();
     post.delete_post(id).await?;
 
     info!(
-        request_id = %request_id(&req),
         post_id = %id,
         "post deleted"
     );
@@ -131,10 +291,386 @@ async fn delete_post(
     Ok(HttpResponse::Ok().json("{}"))
 }
 
-/// Returns the request identifier if present.
-fn request_id(req: &HttpRequest) -> String {
-    req.extensions()
-        .get::<crate::presentation::middleware::RequestId>()
-        .map(|rid| rid.0.clone())
-        .unwrap_or_else(|| "unknown".into())
+/// Lists the authenticated user's trashed posts, most recently deleted first.
+///
+/// The total number of trashed posts is returned in `X-Total-Count` and
+/// links to the neighbouring pages in `Link`.
+#[get("/posts/trash")]
+async fn list_trash(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    post: web::Data<Posts>,
+    query: web::Query<PageQuery>,
+) -> Result<HttpResponse, DomainError> {
+    let page = post
+        .list_trash(user.id, query.limit.unwrap_or(0), query.offset.unwrap_or(0))
+        .await?
+        .map(PostResponse::from);
+
+    info!(
+        author_id = %user.id,
+        count = page.items.len(),
+        total = page.total,
+        "trash listed"
+    );
+
+    Ok(page_response(&req, page))
+}
+
+/// Restores one of the authenticated user's trashed posts.
+#[post("/posts/{id}/restore")]
+async fn restore_post(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    post: web::Data<Posts>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    let restored = post.restore_post(path.into_inner(), user.id).await?;
+    let response = PostResponse::from(restored);
+
+    info!(
+        author_id = %user.id,
+        post_id = %response.id,
+        "post restored"
+    );
+
+    Ok(HttpResponse::Ok().negotiated(&req, &response))
+}
+
+/// Creates a share token for one of the authenticated user's unlisted posts.
+///
+/// The token reads the post through `GET /posts/shared/{token}` without auth.
+#[post("/posts/{id}/share")]
+async fn share_post(
+    user: AuthenticatedUser,
+    post: web::Data<Posts>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    let post_id = path.into_inner();
+    let token = post.share_post(post_id, user.id).await?;
+
+    info!(
+        author_id = %user.id,
+        post_id = %post_id,
+        "post share link created"
+    );
+
+    Ok(HttpResponse::Ok().json(ShareLinkResponse { post_id, token }))
+}
+
+/// Lists the tags used on the authenticated user's posts.
+#[get("/tags")]
+async fn list_tags(
+    user: AuthenticatedUser,
+    post: web::Data<Posts>,
+) -> Result<HttpResponse, DomainError> {
+    let tags = post.list_tags(user.id).await?;
+    let response: Vec<_> = tags.into_iter().map(TagResponse::from).collect();
+
+    info!(
+        author_id = %user.id,
+        count = response.len(),
+        "tags listed"
+    );
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Returns the authenticated user's content usage and limits.
+#[get("/quota")]
+async fn get_quota(
+    user: AuthenticatedUser,
+    post: web::Data<Posts>,
+    media: web::Data<Media>,
+) -> Result<HttpResponse, DomainError> {
+    let usage = QuotaUsage {
+        posts: post.count_posts(user.id).await?,
+        media_bytes: media.storage_used(user.id).await?,
+        quota: post.quota(),
+    };
+
+    Ok(HttpResponse::Ok().json(QuotaResponse::from(usage)))
+}
+
+/// Lists comments on a post, oldest first.
+///
+/// The total number of comments is returned in `X-Total-Count` and links to
+/// the neighbouring pages in `Link`.
+#[get("/posts/{id}/comments")]
+async fn list_comments(
+    req: HttpRequest,
+    comments: web::Data<Comments>,
+    path: web::Path<Uuid>,
+    query: web::Query<PageQuery>,
+) -> Result<HttpResponse, DomainError> {
+    let post_id = path.into_inner();
+    let page = comments
+        .list_comments(post_id, query.limit.unwrap_or(0), query.offset.unwrap_or(0))
+        .await?
+        .map(CommentResponse::from);
+
+    info!(
+        post_id = %post_id,
+        count = page.items.len(),
+        total = page.total,
+        "comments listed"
+    );
+
+    Ok(page_response(&req, page))
+}
+
+/// Adds a comment to a post.
+///
+/// Requests that fill in the `website` honeypot field are rejected.
+#[post("/posts/{id}/comments")]
+async fn add_comment(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    comments: web::Data<Comments>,
+    path: web::Path<Uuid>,
+    payload: web::Json<CreateCommentRequest>,
+) -> Result<HttpResponse, DomainError> {
+    let post_id = path.into_inner();
+    let payload = payload.into_inner();
+    if payload.website.as_deref().is_some_and(|w| !w.trim().is_empty()) {
+        warn!(
+            author_id = %user.id,
+            post_id = %post_id,
+            "comment honeypot filled in"
+        );
+        return Err(DomainError::Validation("comment rejected".into()));
+    }
+
+    let comment = comments
+        .add_comment(
+            post_id,
+            user.id,
+            payload.content,
+            payload.parent_id,
+            &comment_origin(&req),
+        )
+        .await?;
+
+    let response = CommentResponse::from(comment);
+
+    info!(
+        author_id = %user.id,
+        post_id = %response.post_id,
+        comment_id = %response.id,
+        "comment added"
+    );
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Returns where a comment request came from, for rate limits and spam checks.
+///
+/// The IP honors `Forwarded` and `X-Forwarded-For`, so the server should sit
+/// behind a proxy that sets them.
+pub(super) fn comment_origin(req: &HttpRequest) -> CommentOrigin {
+    CommentOrigin {
+        ip: req.connection_info().realip_remote_addr().map(str::to_string),
+        user_agent: req
+            .headers()
+            .get(header::USER_AGENT)
+            .and_then(|agent| agent.to_str().ok())
+            .map(str::to_string),
+    }
+}
+
+/// Deletes a comment by its ID.
+#[delete("/comments/{id}")]
+async fn delete_comment(
+    user: AuthenticatedUser,
+    comments: web::Data<Comments>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    let id = path.into_inner();
+    comments.delete_comment(id, user.id).await?;
+
+    info!(
+        user_id = %user.id,
+        comment_id = %id,
+        "comment deleted"
+    );
+
+    Ok(HttpResponse::Ok().json("{}"))
+}
+
+/// Returns the like and bookmark counts of a post.
+#[get("/posts/{id}/reactions")]
+async fn get_reactions(
+    user: AuthenticatedUser,
+    reactions: web::Data<Reactions>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    let counts = reactions.get_reactions(path.into_inner(), user.id).await?;
+
+    Ok(HttpResponse::Ok().json(ReactionResponse::from(counts)))
+}
+
+/// Likes a post; liking it again is a no-op.
+#[put("/posts/{id}/like")]
+async fn like_post(
+    user: AuthenticatedUser,
+    reactions: web::Data<Reactions>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    let counts = reactions.like_post(path.into_inner(), user.id).await?;
+
+    info!(
+        post_id = %counts.post_id,
+        user_id = %user.id,
+        "post liked"
+    );
+
+    Ok(HttpResponse::Ok().json(ReactionResponse::from(counts)))
+}
+
+/// Removes the authenticated user's like from a post.
+#[delete("/posts/{id}/like")]
+async fn unlike_post(
+    user: AuthenticatedUser,
+    reactions: web::Data<Reactions>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    let counts = reactions.unlike_post(path.into_inner(), user.id).await?;
+
+    info!(
+        post_id = %counts.post_id,
+        user_id = %user.id,
+        "post unliked"
+    );
+
+    Ok(HttpResponse::Ok().json(ReactionResponse::from(counts)))
+}
+
+/// Bookmarks a post; bookmarking it again is a no-op.
+#[put("/posts/{id}/bookmark")]
+async fn bookmark_post(
+    user: AuthenticatedUser,
+    reactions: web::Data<Reactions>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    let counts = reactions.bookmark_post(path.into_inner(), user.id).await?;
+
+    info!(
+        post_id = %counts.post_id,
+        user_id = %user.id,
+        "post bookmarked"
+    );
+
+    Ok(HttpResponse::Ok().json(ReactionResponse::from(counts)))
+}
+
+/// Removes a post from the authenticated user's bookmarks.
+#[delete("/posts/{id}/bookmark")]
+async fn unbookmark_post(
+    user: AuthenticatedUser,
+    reactions: web::Data<Reactions>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    let counts = reactions.unbookmark_post(path.into_inner(), user.id).await?;
+
+    info!(
+        post_id = %counts.post_id,
+        user_id = %user.id,
+        "bookmark removed"
+    );
+
+    Ok(HttpResponse::Ok().json(ReactionResponse::from(counts)))
+}
+
+/// Lists the authenticated user's bookmarked posts, most recently bookmarked first.
+///
+/// The total number of bookmarks is returned in `X-Total-Count` and links to
+/// the neighbouring pages in `Link`.
+#[get("/bookmarks")]
+async fn list_bookmarks(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    reactions: web::Data<Reactions>,
+    query: web::Query<PageQuery>,
+) -> Result<HttpResponse, DomainError> {
+    let page = reactions
+        .list_bookmarks(user.id, query.limit.unwrap_or(0), query.offset.unwrap_or(0))
+        .await?
+        .map(PostResponse::from);
+
+    info!(
+        user_id = %user.id,
+        count = page.items.len(),
+        total = page.total,
+        "bookmarks listed"
+    );
+
+    Ok(page_response(&req, page))
+}
+
+/// Streams blog events for the authenticated user as Server-Sent Events.
+///
+/// A reconnecting client sending `Last-Event-ID` first gets the events it
+/// missed, as far as the bus still holds them.
+#[get("/events")]
+async fn events(
+    req: HttpRequest,
+    user: AuthenticatedUser,
+    bus: web::Data<EventBus>,
+) -> HttpResponse {
+    let last_event_id = req
+        .headers()
+        .get(LAST_EVENT_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.trim().parse().ok());
+    let (backlog, receiver) = bus.subscribe_since(last_event_id);
+
+    info!(
+        user_id = %user.id,
+        last_event_id = ?last_event_id,
+        replayed = backlog.len(),
+        "event stream opened"
+    );
+
+    HttpResponse::Ok()
+        .content_type("text/event-stream")
+        .insert_header(("Cache-Control", "no-cache"))
+        .streaming(sse::event_stream(backlog, receiver, user.id))
+}
+
+/// Builds the response for a page of a list.
+///
+/// The items form the body, encoded as the client accepts; the total goes
+/// into `X-Total-Count` and the `next`/`prev` page URLs into `Link`.
+pub(super) fn page_response<T: Serialize>(req: &HttpRequest, page: Page<T>) -> HttpResponse {
+    let mut response = HttpResponse::Ok();
+    response.insert_header((TOTAL_COUNT_HEADER, page.total));
+
+    let links: Vec<String> = [("next", page.next_offset()), ("prev", page.prev_offset())]
+        .into_iter()
+        .filter_map(|(rel, offset)| {
+            offset.map(|offset| format!("<{}>; rel=\"{}\"", page_url(req, page.limit, offset), rel))
+        })
+        .collect();
+    if !links.is_empty() {
+        response.insert_header((LINK_HEADER, links.join(", ")));
+    }
+
+    response.negotiated(req, &page.items)
+}
+
+/// Returns the request's path and query with the paging parameters replaced.
+fn page_url(req: &HttpRequest, limit: u32, offset: u32) -> String {
+    let mut params: Vec<&str> = req
+        .query_string()
+        .split('&')
+        .filter(|param| {
+            let name = param.split('=').next().unwrap_or_default();
+            !param.is_empty() && name != "limit" && name != "offset"
+        })
+        .collect();
+
+    let paging = format!("limit={}&offset={}", limit, offset);
+    params.push(&paging);
+
+    format!("{}?{}", req.path(), params.join("&"))
 }
@@ -1,27 +1,46 @@
 use crate::application::auth_service::AuthService;
+use crate::application::session_service::{SessionService, SessionTokens};
+use crate::data::refresh_token_repository::PostgresRefreshTokenRepository;
 use crate::data::user_repository::PostgresUserRepository;
 use crate::domain::error::DomainError;
+use crate::infrastructure::security::{generate_secret, ACCESS_TOKEN_TTL_SECS};
 use crate::presentation::dto::{
-    HealthResponse, LoginRequest, RegisterRequest, TokenResponse,
+    CookieSessionResponse, LoginRequest, PostListQuery, PostResponse, RefreshRequest,
+    RegisterRequest, SearchQuery, SearchResultResponse, SessionMode, SessionQuery, TokenResponse,
 };
-use actix_web::{post, web, HttpResponse, Responder, Scope};
-use chrono::Utc;
+use crate::presentation::negotiate::{preferred_locales, NegotiatedBody};
+use crate::presentation::session_cookies::{csrf_ok, SessionCookies, REFRESH_COOKIE};
+use crate::presentation::handler::analytics::Analytics;
+use crate::presentation::handler::health::{healthz, version};
+use crate::presentation::handler::live;
+use crate::presentation::handler::protected::{page_response, Posts};
+use crate::presentation::handler::translation::Translations;
+use crate::presentation::handler::webmention;
+use actix_web::http::header::{self, HeaderValue};
+use actix_web::{get, post, web, HttpRequest, HttpResponse, Responder, Scope};
 use tracing::info;
+use uuid::Uuid;
+
+/// Session service type used by the HTTP handlers.
+pub type Sessions = SessionService<PostgresUserRepository, PostgresRefreshTokenRepository>;
 
 /// Returns the public API scope.
 pub fn scope() -> Scope {
     web::scope("")
-        .route("/health", web::get().to(health))
+        .route("/health", web::get().to(healthz))
+        .route("/version", web::get().to(version))
         .service(register)
         .service(login)
-}
-
-/// Health check endpoint.
-async fn health() -> impl Responder {
-    HttpResponse::Ok().json(HealthResponse {
-        status: "ok",
-        timestamp: Utc::now(),
-    })
+        .service(refresh)
+        .service(logout)
+        .service(list_feed)
+        .service(search_posts)
+        .service(get_post_by_slug)
+        .service(webmention::list_mentions)
+        .service(live::comment_socket)
+        // Registered before `get_post` so `shared` is not parsed as a post ID.
+        .service(get_shared_post)
+        .service(get_post)
 }
 
 /// Registers a new user.
@@ -47,17 +66,262 @@ async fn register(
     })))
 }
 
-/// Authenticates a user and returns a JWT.
+/// Authenticates a user and starts a session.
+///
+/// `?mode=cookie` puts the tokens in `HttpOnly` cookies instead of the body.
 #[post("/auth/login")]
 async fn login(
-    service: web::Data<AuthService<PostgresUserRepository>>,
+    sessions: web::Data<Sessions>,
+    cookies: web::Data<SessionCookies>,
+    query: web::Query<SessionQuery>,
     payload: web::Json<LoginRequest>,
-) -> Result<impl Responder, DomainError> {
-    let jwt = service.login(&payload.email, &payload.password).await?;
+) -> Result<HttpResponse, DomainError> {
+    let tokens = sessions.login(&payload.email, &payload.password).await?;
+
+    info!(email = %payload.email, mode = ?query.mode, "user logged in");
+
+    Ok(session_response(query.mode, tokens, &cookies))
+}
+
+/// Exchanges a refresh token for a new token pair.
+///
+/// Token-mode sessions send the refresh token in the body; cookie-mode
+/// sessions send none and get new cookies.
+#[post("/auth/refresh")]
+async fn refresh(
+    req: HttpRequest,
+    sessions: web::Data<Sessions>,
+    cookies: web::Data<SessionCookies>,
+    payload: Option<web::Json<RefreshRequest>>,
+) -> Result<HttpResponse, DomainError> {
+    let (mode, token) = refresh_token(&req, payload)?
+        .ok_or_else(|| DomainError::Validation("missing refresh token".into()))?;
+    let tokens = sessions.refresh(&token).await?;
+
+    Ok(session_response(mode, tokens, &cookies))
+}
+
+/// Ends a session by revoking its refresh token.
+///
+/// Cookie-mode sessions also get their cookies deleted.
+#[post("/auth/logout")]
+async fn logout(
+    req: HttpRequest,
+    sessions: web::Data<Sessions>,
+    cookies: web::Data<SessionCookies>,
+    payload: Option<web::Json<RefreshRequest>>,
+) -> Result<HttpResponse, DomainError> {
+    let token = refresh_token(&req, payload)?;
+    if let Some((_, token)) = &token {
+        sessions.logout(token).await?;
+    }
+
+    let mut response = HttpResponse::NoContent();
+    if matches!(token, None | Some((SessionMode::Cookie, _))) {
+        for cookie in cookies.clear() {
+            response.cookie(cookie);
+        }
+    }
+    Ok(response.finish())
+}
+
+/// Returns the refresh token of a request and the session mode it came with.
+///
+/// The body wins over the refresh cookie; a cookie must pass the CSRF check.
+fn refresh_token(
+    req: &HttpRequest,
+    payload: Option<web::Json<RefreshRequest>>,
+) -> Result<Option<(SessionMode, String)>, DomainError> {
+    if let Some(token) = payload.and_then(|payload| payload.into_inner().refresh_token) {
+        return Ok(Some((SessionMode::Token, token)));
+    }
+
+    let Some(cookie) = req.cookie(REFRESH_COOKIE) else {
+        return Ok(None);
+    };
+    if !csrf_ok(req) {
+        return Err(DomainError::Forbidden("missing or invalid CSRF token".into()));
+    }
+    Ok(Some((SessionMode::Cookie, cookie.value().to_string())))
+}
+
+/// Hands a session to the client in the body or in cookies.
+fn session_response(
+    mode: SessionMode,
+    tokens: SessionTokens,
+    cookies: &SessionCookies,
+) -> HttpResponse {
+    match mode {
+        SessionMode::Token => HttpResponse::Ok().json(TokenResponse {
+            access_token: tokens.access_token,
+            refresh_token: Some(tokens.refresh_token),
+        }),
+        SessionMode::Cookie => {
+            let csrf_token = generate_secret();
+            let mut response = HttpResponse::Ok();
+            for cookie in cookies.issue(&tokens, &csrf_token) {
+                response.cookie(cookie);
+            }
+            response.json(CookieSessionResponse {
+                csrf_token,
+                expires_in: ACCESS_TOKEN_TTL_SECS,
+            })
+        }
+    }
+}
+
+/// Lists posts of all authors, newest first, optionally filtered by tag.
+///
+/// Each post comes in the best language `Accept-Language` asks for. The total
+/// number of matching posts is returned in `X-Total-Count` and links to the
+/// neighbouring pages in `Link`.
+#[get("/posts")]
+async fn list_feed(
+    req: HttpRequest,
+    post: web::Data<Posts>,
+    translations: web::Data<Translations>,
+    query: web::Query<PostListQuery>,
+) -> Result<HttpResponse, DomainError> {
+    let page = post
+        .list_feed(
+            query.tag.as_deref(),
+            query.limit.unwrap_or(0),
+            query.offset.unwrap_or(0),
+        )
+        .await?;
+    let page = translations
+        .localize_page(page, &preferred_locales(&req))
+        .await?
+        .map(PostResponse::from);
 
-    info!(email = %payload.email, "user logged in");
+    info!(
+        count = page.items.len(),
+        total = page.total,
+        "public posts listed"
+    );
+
+    let mut response = page_response(&req, page);
+    response
+        .headers_mut()
+        .append(header::VARY, HeaderValue::from_static("Accept-Language"));
+    Ok(response)
+}
+
+/// Searches posts of all authors, best matches first, optionally filtered by tag.
+///
+/// The total number of matches is returned in `X-Total-Count` and links to
+/// the neighbouring pages in `Link`.
+#[get("/posts/search")]
+async fn search_posts(
+    req: HttpRequest,
+    post: web::Data<Posts>,
+    query: web::Query<SearchQuery>,
+) -> Result<HttpResponse, DomainError> {
+    let page = post
+        .search_posts(
+            &query.q,
+            query.tag.as_deref(),
+            query.limit.unwrap_or(0),
+            query.offset.unwrap_or(0),
+        )
+        .await?
+        .map(SearchResultResponse::from);
+
+    info!(
+        query = %query.q,
+        count = page.items.len(),
+        total = page.total,
+        "posts searched"
+    );
+
+    Ok(page_response(&req, page))
+}
+
+/// Returns a post by its slug, in the best language `Accept-Language` asks for.
+#[get("/posts/slug/{slug}")]
+async fn get_post_by_slug(
+    req: HttpRequest,
+    post: web::Data<Posts>,
+    analytics: web::Data<Analytics>,
+    translations: web::Data<Translations>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, DomainError> {
+    let post = post.read_post_by_slug(&path.into_inner(), None).await?;
+    let post = translations.localize(post, &preferred_locales(&req)).await?;
+    let locale = post.locale.clone();
+    let response = PostResponse::from(post);
+    record_view(&req, &analytics, response.id);
+
+    info!(
+        post_id = %response.id,
+        slug = %response.slug,
+        locale = %locale,
+        "public post retrieved"
+    );
+
+    Ok(localized_response(&req, &locale, &response))
+}
+
+/// Returns a post by its ID, in the best language `Accept-Language` asks for.
+#[get("/posts/{id}")]
+async fn get_post(
+    req: HttpRequest,
+    post: web::Data<Posts>,
+    analytics: web::Data<Analytics>,
+    translations: web::Data<Translations>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    let post = post.read_post(path.into_inner(), None).await?;
+    let post = translations.localize(post, &preferred_locales(&req)).await?;
+    let locale = post.locale.clone();
+    let response = PostResponse::from(post);
+    record_view(&req, &analytics, response.id);
+
+    info!(
+        post_id = %response.id,
+        locale = %locale,
+        "public post retrieved"
+    );
+
+    Ok(localized_response(&req, &locale, &response))
+}
+
+/// Returns the unlisted post a share token grants access to.
+#[get("/posts/shared/{token}")]
+async fn get_shared_post(
+    req: HttpRequest,
+    post: web::Data<Posts>,
+    analytics: web::Data<Analytics>,
+    translations: web::Data<Translations>,
+    path: web::Path<String>,
+) -> Result<HttpResponse, DomainError> {
+    let post = post.get_shared_post(&path.into_inner()).await?;
+    let post = translations.localize(post, &preferred_locales(&req)).await?;
+    let locale = post.locale.clone();
+    let response = PostResponse::from(post);
+    record_view(&req, &analytics, response.id);
+
+    info!(
+        post_id = %response.id,
+        locale = %locale,
+        "shared post retrieved"
+    );
+
+    Ok(localized_response(&req, &locale, &response))
+}
+
+/// Builds the response for a post served in `locale`, naming it in `Content-Language`.
+fn localized_response(req: &HttpRequest, locale: &str, post: &PostResponse) -> HttpResponse {
+    HttpResponse::Ok()
+        .insert_header((header::CONTENT_LANGUAGE, locale))
+        .append_header((header::VARY, "Accept-Language"))
+        .negotiated(req, post)
+}
 
-    Ok(HttpResponse::Ok().json(TokenResponse {
-        access_token: jwt,
-    }))
+/// Counts a read of a post with the referrer and client of the request.
+fn record_view(req: &HttpRequest, analytics: &Analytics, post_id: Uuid) {
+    let value = |name: header::HeaderName| {
+        req.headers().get(name).and_then(|value| value.to_str().ok())
+    };
+    analytics.record_view(post_id, value(header::REFERER), value(header::USER_AGENT));
 }
@@ -0,0 +1,94 @@
+use crate::application::translation_service::TranslationService;
+use crate::data::post_repository::PostgresPostRepository;
+use crate::data::translation_repository::PostgresTranslationRepository;
+use crate::domain::error::DomainError;
+use crate::presentation::auth::AuthenticatedUser;
+use crate::presentation::dto::{TranslationRequest, TranslationResponse};
+use actix_web::{delete, get, put, web, HttpResponse, Scope};
+use tracing::info;
+use uuid::Uuid;
+
+/// Translation service type used by the HTTP handlers.
+pub(crate) type Translations =
+    TranslationService<PostgresTranslationRepository, PostgresPostRepository>;
+
+/// Returns the post translation management scope.
+pub fn scope() -> Scope {
+    web::scope("/posts/{id}/translations")
+        .service(list_translations)
+        .service(set_translation)
+        .service(delete_translation)
+}
+
+/// Lists the translations of a post, by locale.
+#[get("")]
+async fn list_translations(
+    translations: web::Data<Translations>,
+    path: web::Path<Uuid>,
+) -> Result<HttpResponse, DomainError> {
+    let post_id = path.into_inner();
+    let response: Vec<_> = translations
+        .list_translations(post_id)
+        .await?
+        .into_iter()
+        .map(TranslationResponse::from)
+        .collect();
+
+    info!(
+        post_id = %post_id,
+        count = response.len(),
+        "post translations listed"
+    );
+
+    Ok(HttpResponse::Ok().json(response))
+}
+
+/// Adds or replaces the translation of a post into a locale.
+///
+/// Only the post's author may translate it.
+#[put("/{locale}")]
+async fn set_translation(
+    user: AuthenticatedUser,
+    translations: web::Data<Translations>,
+    path: web::Path<(Uuid, String)>,
+    payload: web::Json<TranslationRequest>,
+) -> Result<HttpResponse, DomainError> {
+    let (post_id, locale) = path.into_inner();
+    let payload = payload.into_inner();
+    let translation = translations
+        .set_translation(post_id, user.id, &locale, payload.title, payload.content)
+        .await?;
+
+    info!(
+        author_id = %user.id,
+        post_id = %post_id,
+        locale = %translation.locale,
+        "post translation stored"
+    );
+
+    Ok(HttpResponse::Ok().json(TranslationResponse::from(translation)))
+}
+
+/// Removes the translation of a post into a locale.
+///
+/// Only the post's author may remove it.
+#[delete("/{locale}")]
+async fn delete_translation(
+    user: AuthenticatedUser,
+    translations: web::Data<Translations>,
+    path: web::Path<(Uuid, String)>,
+) -> Result<HttpResponse, DomainError> {
+    let (post_id, locale) = path.into_inner();
+    translations
+        .delete_translation(post_id, user.id, &locale)
+        .await?;
+
+    info!(
+        author_id = %user.id,
+        post_id = %post_id,
+        locale = %locale,
+        "post translation deleted"
+    );
+
+    Ok(HttpResponse::NoContent().finish())
+}
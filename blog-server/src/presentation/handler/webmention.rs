@@ -0,0 +1,64 @@
+use crate::application::mention_service::MentionService;
+use crate::data::mention_repository::PostgresMentionRepository;
+use crate::data::post_repository::PostgresPostRepository;
+use crate::domain::error::DomainError;
+use crate::presentation::dto::{MentionResponse, PageQuery, WebmentionForm};
+use crate::presentation::handler::protected::page_response;
+use actix_web::{get, web, HttpRequest, HttpResponse};
+use tracing::info;
+use uuid::Uuid;
+
+/// Webmention service type used by the HTTP handlers.
+pub(crate) type Mentions = MentionService<PostgresMentionRepository, PostgresPostRepository>;
+
+/// Receives a webmention: `source` says it links to `target`, a public post.
+///
+/// Answers `202 Accepted`; the source is verified in the background. The
+/// client IP is rate limited like comment authors' IPs.
+pub async fn receive(
+    req: HttpRequest,
+    mentions: web::Data<Mentions>,
+    form: web::Form<WebmentionForm>,
+) -> Result<HttpResponse, DomainError> {
+    let form = form.into_inner();
+    let ip = req
+        .connection_info()
+        .realip_remote_addr()
+        .map(str::to_string);
+    let mention = mentions
+        .receive(form.source, form.target, ip.as_deref())
+        .await?;
+
+    info!(
+        mention_id = %mention.id,
+        post_id = %mention.post_id,
+        "webmention accepted"
+    );
+
+    Ok(HttpResponse::Accepted().json(MentionResponse::from(mention)))
+}
+
+/// Lists the published webmentions of a public post, oldest first.
+///
+/// The total number of mentions is returned in `X-Total-Count`.
+#[get("/posts/{id}/mentions")]
+pub(super) async fn list_mentions(
+    req: HttpRequest,
+    mentions: web::Data<Mentions>,
+    path: web::Path<Uuid>,
+    query: web::Query<PageQuery>,
+) -> Result<HttpResponse, DomainError> {
+    let post_id = path.into_inner();
+    let page = mentions
+        .list_mentions(post_id, query.limit.unwrap_or(0), query.offset.unwrap_or(0))
+        .await?
+        .map(MentionResponse::from);
+
+    info!(
+        post_id = %post_id,
+        total = page.total,
+        "mentions listed"
+    );
+
+    Ok(page_response(&req, page))
+}
@@ -0,0 +1,103 @@
+use actix_service::{Service, Transform};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::http::header::{HeaderName, HeaderValue, LINK};
+use actix_web::Error;
+use futures_util::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use std::rc::Rc;
+use std::task::{Context, Poll};
+use tracing::debug;
+
+/// Header marking a response as coming from a deprecated route (RFC 9745).
+pub const DEPRECATION_HEADER: &str = "Deprecation";
+
+/// Deprecated route middleware.
+///
+/// Marks responses of routes mounted under `legacy_prefix` with a
+/// `Deprecation` header and a `Link` to the same route under
+/// `successor_prefix`, so clients can find their replacement.
+pub struct DeprecatedRouteMiddleware {
+    legacy_prefix: Rc<str>,
+    successor_prefix: Rc<str>,
+}
+
+impl DeprecatedRouteMiddleware {
+    /// Creates a middleware pointing `legacy_prefix` routes at `successor_prefix`.
+    pub fn new(legacy_prefix: &str, successor_prefix: &str) -> Self {
+        Self {
+            legacy_prefix: legacy_prefix.into(),
+            successor_prefix: successor_prefix.into(),
+        }
+    }
+}
+
+/// Deprecated route middleware service.
+pub struct DeprecatedRouteService<S> {
+    service: S,
+    legacy_prefix: Rc<str>,
+    successor_prefix: Rc<str>,
+}
+
+impl<S, B> Transform<S, ServiceRequest> for DeprecatedRouteMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = DeprecatedRouteService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    /// Creates a new deprecated route service.
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(DeprecatedRouteService {
+            service,
+            legacy_prefix: Rc::clone(&self.legacy_prefix),
+            successor_prefix: Rc::clone(&self.successor_prefix),
+        }))
+    }
+}
+
+impl<S, B> Service<ServiceRequest> for DeprecatedRouteService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    /// Checks whether the underlying service is ready.
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    /// Processes an incoming request and marks its response as deprecated.
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let route = req
+            .path()
+            .strip_prefix(&*self.legacy_prefix)
+            .unwrap_or_default();
+        let successor = match req.query_string() {
+            "" => format!("{}{}", self.successor_prefix, route),
+            query => format!("{}{}?{}", self.successor_prefix, route, query),
+        };
+        debug!(path = req.path(), %successor, "deprecated route called");
+
+        let fut = self.service.call(req);
+
+        Box::pin(async move {
+            let mut res = fut.await?;
+            let headers = res.response_mut().headers_mut();
+            headers.insert(
+                HeaderName::from_static("deprecation"),
+                HeaderValue::from_static("true"),
+            );
+            if let Ok(link) = HeaderValue::from_str(&format!("<{successor}>; rel=\"successor-version\"")) {
+                headers.append(LINK, link);
+            }
+            Ok(res)
+        })
+    }
+}
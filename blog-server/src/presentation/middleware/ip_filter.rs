@@ -0,0 +1,94 @@
+use crate::application::ip_rule_service::IpRuleService;
+use crate::domain::error::DomainError;
+use actix_service::{Service, Transform};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::Error;
+use futures_util::future::{ready, LocalBoxFuture, Ready};
+use std::net::{IpAddr, SocketAddr};
+use std::task::{Context, Poll};
+use tracing::info;
+
+/// Client address filtering middleware.
+///
+/// Refuses requests from addresses the allow and deny lists block with
+/// `403 Forbidden`. The address honors `Forwarded` and `X-Forwarded-For`, as
+/// for rate limiting, so the server should sit behind a proxy that sets them.
+pub struct IpFilterMiddleware {
+    rules: IpRuleService,
+}
+
+impl IpFilterMiddleware {
+    /// Creates a middleware checking client addresses against `rules`.
+    pub fn new(rules: IpRuleService) -> Self {
+        Self { rules }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for IpFilterMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type InitError = ();
+    type Transform = IpFilterService<S>;
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    /// Creates a new middleware service.
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(IpFilterService {
+            service,
+            rules: self.rules.clone(),
+        }))
+    }
+}
+
+/// Client address filtering service.
+pub struct IpFilterService<S> {
+    service: S,
+    rules: IpRuleService,
+}
+
+impl<S, B> Service<ServiceRequest> for IpFilterService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    /// Checks whether the underlying service is ready.
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    /// Refuses the request if its client address is blocked, otherwise
+    /// passes it on.
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let ip = req
+            .connection_info()
+            .realip_remote_addr()
+            .and_then(parse_client_ip);
+
+        if let Some(ip) = ip
+            && !self.rules.allows(ip)
+        {
+            info!(%ip, path = req.path(), "request from blocked address refused");
+            return Box::pin(ready(Err(DomainError::Forbidden(
+                "requests from this address are not allowed".into(),
+            )
+            .into())));
+        }
+
+        Box::pin(self.service.call(req))
+    }
+}
+
+/// Parses a client address, which proxies may send with a port.
+fn parse_client_ip(addr: &str) -> Option<IpAddr> {
+    addr.parse()
+        .ok()
+        .or_else(|| addr.parse::<SocketAddr>().ok().map(|addr| addr.ip()))
+}
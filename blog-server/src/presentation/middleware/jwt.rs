@@ -2,9 +2,11 @@ use crate::application::auth_service::AuthService;
 use crate::data::user_repository::PostgresUserRepository;
 use crate::infrastructure::security::JwtKeys;
 use crate::presentation::auth::extract_user_from_token;
+use crate::presentation::middleware::request_span::record_user;
+use crate::presentation::session_cookies::{csrf_ok, ACCESS_COOKIE};
 use actix_service::{Service, Transform};
 use actix_web::dev::{ServiceRequest, ServiceResponse};
-use actix_web::{web, Error, HttpMessage};
+use actix_web::{web, Error, HttpMessage, HttpRequest};
 use futures_util::future::{ready, LocalBoxFuture, Ready};
 use std::cell::RefCell;
 use std::rc::Rc;
@@ -12,8 +14,9 @@ use std::task::{Context, Poll};
 
 /// JWT authentication middleware.
 ///
-/// Validates the `Authorization` header, extracts the user from the JWT,
-/// and attaches it to the request extensions.
+/// Validates the JWT from the `Authorization` header or, for cookie-mode
+/// sessions, the access cookie, and attaches its user to the request
+/// extensions.
 pub struct JwtAuthMiddleware {
     keys: JwtKeys,
 }
@@ -79,27 +82,17 @@ where
             .app_data::<web::Data<AuthService<PostgresUserRepository>>>()
             .cloned();
 
-        let auth_header = req
-            .headers()
-            .get(actix_web::http::header::AUTHORIZATION)
-            .and_then(|value| value.to_str().ok())
-            .map(|value| value.to_string());
+        let token = request_token(req.request());
 
         Box::pin(async move {
             let auth_service = auth_service
                 .ok_or_else(|| actix_web::error::ErrorInternalServerError("AuthService missing"))?;
 
-            let header = auth_header.ok_or_else(|| {
-                actix_web::error::ErrorUnauthorized("missing authorization header")
-            })?;
-
-            let token = header.strip_prefix("Bearer ").ok_or_else(|| {
-                actix_web::error::ErrorUnauthorized("invalid authorization header")
-            })?;
-
+            let token = token?;
             let user =
-                extract_user_from_token(token, &keys, auth_service.get_ref()).await?;
+                extract_user_from_token(&token, &keys, auth_service.get_ref()).await?;
 
+            record_user(user.id);
             req.extensions_mut().insert(user);
 
             let fut = {
@@ -112,3 +105,27 @@ where
         })
     }
 }
+
+/// Returns the access token of a request.
+///
+/// A `Bearer` header wins over the access cookie; requests authenticated by
+/// cookie must also pass the CSRF check.
+fn request_token(req: &HttpRequest) -> Result<String, Error> {
+    if let Some(header) = req.headers().get(actix_web::http::header::AUTHORIZATION) {
+        return header
+            .to_str()
+            .ok()
+            .and_then(|value| value.strip_prefix("Bearer "))
+            .map(str::to_string)
+            .ok_or_else(|| actix_web::error::ErrorUnauthorized("invalid authorization header"));
+    }
+
+    let cookie = req
+        .cookie(ACCESS_COOKIE)
+        .ok_or_else(|| actix_web::error::ErrorUnauthorized("missing authorization header"))?;
+    if !csrf_ok(req) {
+        return Err(actix_web::error::ErrorForbidden("missing or invalid CSRF token"));
+    }
+
+    Ok(cookie.value().to_string())
+}
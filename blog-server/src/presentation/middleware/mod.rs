@@ -1,9 +1,24 @@
+/// Deprecated route marking middleware.
+pub mod deprecation;
+
+/// Client address filtering middleware.
+pub mod ip_filter;
+
 /// JWT-based authentication middleware.
 pub mod jwt;
 
 /// Request ID propagation middleware.
 pub mod request_id;
 
+/// Per-request tracing span middleware.
+pub mod request_span;
+
+/// Middleware for marking deprecated routes.
+pub use deprecation::DeprecatedRouteMiddleware;
+
+/// Middleware for refusing blocked client addresses.
+pub use ip_filter::IpFilterMiddleware;
+
 /// Middleware for validating JWT tokens.
 pub use jwt::JwtAuthMiddleware;
 
@@ -12,3 +27,6 @@ pub use request_id::RequestId;
 
 /// Middleware for attaching request IDs.
 pub use request_id::RequestIdMiddleware;
+
+/// Middleware for opening a tracing span per request.
+pub use request_span::RequestSpanMiddleware;
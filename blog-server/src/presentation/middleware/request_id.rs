@@ -14,6 +14,16 @@ static REQUEST_ID_HEADER: HeaderName = HeaderName::from_static("x-request-id");
 #[derive(Clone)]
 pub struct RequestId(pub String);
 
+tokio::task_local! {
+    /// ID of the request being handled, for code without access to the request.
+    static CURRENT: String;
+}
+
+/// Returns the ID of the request being handled, if called while handling one.
+pub fn current() -> Option<String> {
+    CURRENT.try_with(String::clone).ok()
+}
+
 /// Request ID middleware.
 ///
 /// Attaches a request ID to each incoming request and
@@ -59,7 +69,8 @@ where
     /// Processes an incoming request.
     ///
     /// Generates a request ID if missing and adds it to both
-    /// request extensions and response headers.
+    /// request extensions and response headers. The ID is also available
+    /// through [`current`] while the request is handled.
     fn call(&self, req: ServiceRequest) -> Self::Future {
         let request_id = req
             .headers()
@@ -70,7 +81,7 @@ where
 
         req.extensions_mut().insert(RequestId(request_id.clone()));
 
-        let fut = self.service.call(req);
+        let fut = CURRENT.scope(request_id.clone(), self.service.call(req));
 
         Box::pin(async move {
             let mut res = fut.await?;
@@ -0,0 +1,110 @@
+use crate::infrastructure::metrics::Metrics;
+use crate::presentation::middleware::RequestId;
+use actix_service::{Service, Transform};
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::{web, Error, HttpMessage};
+use futures_util::future::LocalBoxFuture;
+use std::future::{ready, Ready};
+use std::task::{Context, Poll};
+use std::time::Instant;
+use tracing::field::Empty;
+use tracing::{info, info_span, Instrument, Span};
+
+/// Request span middleware.
+///
+/// Opens a `request` span per HTTP request carrying the method, route
+/// pattern and request ID, so every event logged while handling the request
+/// inherits them. The JWT middleware adds the user ID once it authenticates
+/// the caller. Logs one line with the status and latency when the request
+/// completes and records the latency in [`Metrics`], when registered.
+pub struct RequestSpanMiddleware;
+
+/// Request span middleware service.
+pub struct RequestSpanService<S> {
+    service: S,
+}
+
+impl<S, B> Transform<S, ServiceRequest> for RequestSpanMiddleware
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = RequestSpanService<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    /// Creates a new request span service.
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(RequestSpanService { service }))
+    }
+}
+
+impl<S, B> Service<ServiceRequest> for RequestSpanService<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    /// Checks whether the underlying service is ready.
+    fn poll_ready(&self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.service.poll_ready(cx)
+    }
+
+    /// Handles the request inside its span and logs its outcome.
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let request_id = req
+            .extensions()
+            .get::<RequestId>()
+            .map(|rid| rid.0.clone())
+            .unwrap_or_else(|| "unknown".into());
+        let pattern = req.match_pattern();
+        let route = pattern.clone().unwrap_or_else(|| req.path().to_owned());
+        let method = req.method().clone();
+        let metrics = req.app_data::<web::Data<Metrics>>().cloned();
+
+        let span = info_span!(
+            "request",
+            method = %method,
+            route = %route,
+            request_id = %request_id,
+            user_id = Empty,
+        );
+        let fut = span.in_scope(|| self.service.call(req));
+        let started = Instant::now();
+
+        Box::pin(
+            async move {
+                let res = fut.await;
+                let status = match &res {
+                    Ok(res) => res.status(),
+                    Err(err) => err.as_response_error().status_code(),
+                };
+
+                let elapsed = started.elapsed();
+                info!(
+                    status = status.as_u16(),
+                    elapsed_ms = elapsed.as_millis() as u64,
+                    "request completed"
+                );
+
+                if let Some(metrics) = metrics {
+                    // Unmatched paths share one series to bound the label set.
+                    let route = pattern.as_deref().unwrap_or("unmatched");
+                    metrics.observe_request(method.as_str(), route, status.as_u16(), elapsed);
+                }
+                res
+            }
+            .instrument(span),
+        )
+    }
+}
+
+/// Records the authenticated user on the current request span.
+pub fn record_user(user_id: impl std::fmt::Display) {
+    Span::current().record("user_id", tracing::field::display(user_id));
+}
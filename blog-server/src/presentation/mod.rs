@@ -1,6 +1,12 @@
 /// Authentication utilities.
 pub mod auth;
 
+/// Domain event publishing to the message broker.
+pub mod broker;
+
+/// Command line subcommands.
+pub mod cli;
+
 /// Data transfer objects.
 pub mod dto;
 
@@ -10,6 +16,15 @@ pub mod handler;
 /// Middleware.
 pub mod middleware;
 
+/// Response body content negotiation.
+pub mod negotiate;
+
+/// Cookie-mode session cookies and CSRF checks.
+pub mod session_cookies;
+
+/// Server-Sent Events encoding.
+pub mod sse;
+
 /// gRPC services.
 pub mod grpc_service;
 
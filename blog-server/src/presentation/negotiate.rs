@@ -0,0 +1,104 @@
+use actix_web::http::header::{self, Accept, AcceptLanguage, Header, Preference};
+use actix_web::{HttpRequest, HttpResponse, HttpResponseBuilder};
+use serde::Serialize;
+use tracing::error;
+
+/// Media type of MessagePack bodies.
+pub const MSGPACK_MIME: &str = "application/msgpack";
+
+/// Media type of CBOR bodies.
+pub const CBOR_MIME: &str = "application/cbor";
+
+/// Encoding of a response body, picked from the request's `Accept` header.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum BodyFormat {
+    #[default]
+    Json,
+    MsgPack,
+    Cbor,
+}
+
+impl BodyFormat {
+    /// Returns the most preferred format the request accepts.
+    ///
+    /// Requests without a usable `Accept` header, or accepting none of the
+    /// supported types, get JSON.
+    pub fn from_request(req: &HttpRequest) -> Self {
+        let Ok(accept) = Accept::parse(req) else {
+            return Self::Json;
+        };
+
+        accept
+            .ranked()
+            .iter()
+            .find_map(|mime| match mime.essence_str() {
+                "application/json" | "application/*" | "*/*" => Some(Self::Json),
+                MSGPACK_MIME | "application/x-msgpack" => Some(Self::MsgPack),
+                CBOR_MIME => Some(Self::Cbor),
+                _ => None,
+            })
+            .unwrap_or_default()
+    }
+
+    /// Returns the `Content-Type` of bodies in this format.
+    pub fn content_type(self) -> &'static str {
+        match self {
+            Self::Json => "application/json",
+            Self::MsgPack => MSGPACK_MIME,
+            Self::Cbor => CBOR_MIME,
+        }
+    }
+
+    /// Serializes `value` in this format.
+    pub fn encode<T: Serialize>(self, value: &T) -> Result<Vec<u8>, String> {
+        match self {
+            Self::Json => serde_json::to_vec(value).map_err(|e| e.to_string()),
+            Self::MsgPack => rmp_serde::to_vec_named(value).map_err(|e| e.to_string()),
+            Self::Cbor => {
+                let mut body = Vec::new();
+                ciborium::into_writer(value, &mut body).map_err(|e| e.to_string())?;
+                Ok(body)
+            }
+        }
+    }
+}
+
+/// Returns the languages the request's `Accept-Language` header asks for,
+/// most preferred first.
+///
+/// The `*` wildcard is skipped; a missing or malformed header yields none.
+pub fn preferred_locales(req: &HttpRequest) -> Vec<String> {
+    let Ok(accept) = AcceptLanguage::parse(req) else {
+        return Vec::new();
+    };
+
+    accept
+        .ranked()
+        .into_iter()
+        .filter_map(|language| match language {
+            Preference::Specific(tag) => Some(tag.to_string()),
+            Preference::Any => None,
+        })
+        .collect()
+}
+
+/// Finishes a response with a body encoded in the format the client asked for.
+pub trait NegotiatedBody {
+    /// Sets the body to `value` in the request's preferred [`BodyFormat`].
+    fn negotiated<T: Serialize>(&mut self, req: &HttpRequest, value: &T) -> HttpResponse;
+}
+
+impl NegotiatedBody for HttpResponseBuilder {
+    fn negotiated<T: Serialize>(&mut self, req: &HttpRequest, value: &T) -> HttpResponse {
+        let format = BodyFormat::from_request(req);
+        self.append_header((header::VARY, "Accept"));
+
+        match format.encode(value) {
+            Ok(body) => self.content_type(format.content_type()).body(body),
+            Err(err) => {
+                error!(error = %err, format = ?format, "failed to encode response body");
+                HttpResponse::InternalServerError().finish()
+            }
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use actix_web::cookie::time::Duration as CookieDuration;
+use actix_web::cookie::{Cookie, SameSite};
+use actix_web::http::Method;
+use actix_web::HttpRequest;
+
+use crate::application::session_service::SessionTokens;
+use crate::infrastructure::security::ACCESS_TOKEN_TTL_SECS;
+
+/// Cookie holding the access token of a cookie-mode session.
+pub const ACCESS_COOKIE: &str = "blog_access";
+
+/// Cookie holding the refresh token of a cookie-mode session.
+pub const REFRESH_COOKIE: &str = "blog_refresh";
+
+/// Cookie holding the CSRF token; readable by scripts, unlike the others.
+pub const CSRF_COOKIE: &str = "blog_csrf";
+
+/// Header unsafe cookie-authenticated requests must echo the CSRF token in.
+pub const CSRF_HEADER: &str = "X-CSRF-Token";
+
+/// Settings of the cookies cookie-mode sessions live in.
+///
+/// Session cookies are `HttpOnly` and `SameSite=Strict`, scoped to the API
+/// path; the CSRF cookie is readable from every page of the site.
+#[derive(Clone)]
+pub struct SessionCookies {
+    secure: bool,
+    api_path: String,
+    refresh_ttl: chrono::Duration,
+}
+
+impl SessionCookies {
+    /// Creates cookie settings for sessions served under `api_path`.
+    pub fn new(secure: bool, api_path: String, refresh_ttl: chrono::Duration) -> Self {
+        Self {
+            secure,
+            api_path,
+            refresh_ttl,
+        }
+    }
+
+    /// Returns the cookies carrying a new session.
+    pub fn issue(&self, tokens: &SessionTokens, csrf_token: &str) -> [Cookie<'static>; 3] {
+        let refresh_secs = self.refresh_ttl.num_seconds();
+        [
+            self.cookie(ACCESS_COOKIE, tokens.access_token.clone(), ACCESS_TOKEN_TTL_SECS),
+            self.cookie(REFRESH_COOKIE, tokens.refresh_token.clone(), refresh_secs),
+            self.cookie(CSRF_COOKIE, csrf_token.to_string(), refresh_secs),
+        ]
+    }
+
+    /// Returns cookies deleting the session ones.
+    pub fn clear(&self) -> [Cookie<'static>; 3] {
+        [ACCESS_COOKIE, REFRESH_COOKIE, CSRF_COOKIE].map(|name| {
+            let mut cookie = self.cookie(name, String::new(), 0);
+            cookie.make_removal();
+            cookie
+        })
+    }
+
+    /// Builds one session cookie.
+    fn cookie(&self, name: &'static str, value: String, max_age_secs: i64) -> Cookie<'static> {
+        let readable = name == CSRF_COOKIE;
+
+        Cookie::build(name, value)
+            .path(if readable { "/".to_string() } else { self.api_path.clone() })
+            .http_only(!readable)
+            .secure(self.secure)
+            .same_site(SameSite::Strict)
+            .max_age(CookieDuration::seconds(max_age_secs))
+            .finish()
+    }
+}
+
+/// Returns whether a cookie-authenticated request passes the CSRF check.
+///
+/// Safe methods always pass; others must send the CSRF cookie's value in
+/// `X-CSRF-Token` (double-submit), which other sites cannot read.
+pub fn csrf_ok(req: &HttpRequest) -> bool {
+    if matches!(*req.method(), Method::GET | Method::HEAD | Method::OPTIONS) {
+        return true;
+    }
+
+    let header = req.headers().get(CSRF_HEADER).and_then(|v| v.to_str().ok());
+    match (req.cookie(CSRF_COOKIE), header) {
+        (Some(cookie), Some(header)) => !header.is_empty() && constant_time_eq(cookie.value(), header),
+        _ => false,
+    }
+}
+
+/// Compares two strings without leaking where they differ through timing.
+fn constant_time_eq(a: &str, b: &str) -> bool {
+    a.len() == b.len() && a.bytes().zip(b.bytes()).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
@@ -0,0 +1,76 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use actix_web::web::Bytes;
+use futures_util::{Stream, StreamExt};
+use tokio::sync::broadcast::Receiver;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::time::{Interval, MissedTickBehavior, interval};
+use tracing::warn;
+use uuid::Uuid;
+
+use crate::domain::event::BlogEvent;
+
+/// Interval between keep-alive comments on idle streams.
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Turns a bus subscription into an SSE byte stream for one user.
+///
+/// The `backlog` of missed events goes first. Only events visible to
+/// `user_id` are forwarded; a comment line is sent periodically so proxies
+/// do not close idle connections.
+pub fn event_stream(
+    backlog: Vec<Arc<BlogEvent>>,
+    events: Receiver<Arc<BlogEvent>>,
+    user_id: Uuid,
+) -> impl Stream<Item = Result<Bytes, actix_web::Error>> {
+    let mut heartbeat = interval(HEARTBEAT_INTERVAL);
+    heartbeat.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    let backlog = backlog
+        .into_iter()
+        .filter(move |event| event.kind.is_visible_to(user_id))
+        .map(|event| Ok(encode(&event)));
+    let live = futures_util::stream::unfold(
+        (events, heartbeat),
+        move |(mut events, mut heartbeat)| async move {
+            let frame = next_frame(&mut events, &mut heartbeat, user_id).await?;
+            Some((Ok(frame), (events, heartbeat)))
+        },
+    );
+
+    futures_util::stream::iter(backlog).chain(live)
+}
+
+/// Waits for the next frame to send, or `None` once the bus is closed.
+async fn next_frame(
+    events: &mut Receiver<Arc<BlogEvent>>,
+    heartbeat: &mut Interval,
+    user_id: Uuid,
+) -> Option<Bytes> {
+    loop {
+        tokio::select! {
+            _ = heartbeat.tick() => return Some(Bytes::from_static(b": ping\n\n")),
+            received = events.recv() => match received {
+                Ok(event) if event.kind.is_visible_to(user_id) => return Some(encode(&event)),
+                Ok(_) => continue,
+                Err(RecvError::Lagged(skipped)) => {
+                    warn!(%user_id, skipped, "event subscriber lagged behind");
+                    continue;
+                }
+                Err(RecvError::Closed) => return None,
+            },
+        }
+    }
+}
+
+/// Encodes an event as an SSE frame with `id`, `event`, and JSON `data` fields.
+fn encode(event: &BlogEvent) -> Bytes {
+    let data = serde_json::to_string(event).unwrap_or_else(|_| "{}".into());
+    Bytes::from(format!(
+        "id: {}\nevent: {}\ndata: {}\n\n",
+        event.id,
+        event.kind.name(),
+        data
+    ))
+}
@@ -1,7 +1,26 @@
-import init, { BlogApp } from "./pkg/blog_wasm.js";
+import init, { BlogApp, renderMarkdown } from "./pkg/blog_wasm.js";
 
 let app;
 
+// Server the app talks to: the one that served this page, unless it is the
+// standalone dev server on port 3000.
+const API_ADDR = window.location.port === "3000" ? "http://localhost:8080" : window.location.origin;
+
+const PAGE_SIZE = 10;
+let pageOffset = 0;
+let feedPage = 1;
+// What the feed shows: everything, one tag (`{ tag }`) or search results (`{ query }`).
+let feedFilter = {};
+// Cursor of the next page of a tag archive, which scrolls endlessly instead of paging.
+let feedCursor = null;
+
+// Hash route prefix for article permalinks, e.g. `#/p/my-first-post-1a2b3c4d`.
+const ARTICLE_ROUTE = "#/p/";
+
+// Local ID the new-post form autosaves its draft under.
+const DRAFT_ID = "new-post";
+let draftTimer;
+
 function setPostsMessage(message) {
     const postsEl = document.getElementById("posts");
     postsEl.textContent = message;
@@ -10,7 +29,315 @@ function setPostsMessage(message) {
 async function refreshAuthStatus() {
     const statusEl = document.getElementById("auth-status");
     const isAuth = await app.isAuthenticated();
-    statusEl.textContent = isAuth ? "Logged in" : "Not logged in";
+    const expiresAt = app.tokenExpiresAt();
+    statusEl.textContent = !isAuth
+        ? "Not logged in"
+        : expiresAt != null
+            ? `Logged in (token until ${new Date(expiresAt).toLocaleTimeString()})`
+            : "Logged in";
+}
+
+function renderPager(pagerId, page) {
+    const pagerEl = document.getElementById(pagerId);
+    const current = Math.floor(page.offset / page.limit) + 1;
+    const pages = page.total != null ? Math.max(1, Math.ceil(page.total / page.limit)) : null;
+
+    pagerEl.querySelector(".page-info").textContent =
+        pages != null ? `Page ${current} of ${pages}` : `Page ${current}`;
+    pagerEl.querySelector(".prev").disabled = page.offset === 0;
+    pagerEl.querySelector(".next").disabled = !page.has_more;
+    pagerEl.hidden = page.offset === 0 && !page.has_more;
+}
+
+function renderPost(p, onDelete) {
+    const postEl = document.createElement("div");
+    postEl.className = "post";
+
+    const header = document.createElement("div");
+    header.className = "post-header";
+
+    const title = document.createElement("div");
+    title.className = "post-title";
+    if (p.slug) {
+        const link = document.createElement("a");
+        link.href = ARTICLE_ROUTE + encodeURIComponent(p.slug);
+        link.textContent = p.title || "(no title)";
+        title.appendChild(link);
+    } else {
+        title.textContent = p.title || "(no title)";
+    }
+
+    const meta = document.createElement("div");
+    meta.className = "post-meta";
+    meta.textContent = [
+        p.author ? `@${p.author.username}` : "",
+        p.created_at ? new Date(p.created_at).toLocaleString() : "",
+    ].filter(Boolean).join(" · ");
+
+    header.appendChild(title);
+    header.appendChild(meta);
+    postEl.appendChild(header);
+
+    const content = document.createElement("div");
+    content.className = "post-content";
+    if (p.snippet != null) {
+        // Search snippets are escaped by the server; only <mark> is markup.
+        content.innerHTML = p.snippet;
+    } else {
+        content.textContent = p.content || "";
+    }
+    postEl.appendChild(content);
+
+    if (p.tags && p.tags.length > 0) {
+        const tags = document.createElement("div");
+        tags.className = "post-tags";
+        for (const tag of p.tags) {
+            const chip = document.createElement("button");
+            chip.className = "tag-chip";
+            chip.type = "button";
+            chip.textContent = `#${tag}`;
+            chip.onclick = () => filterFeed({ tag });
+            tags.appendChild(chip);
+        }
+        postEl.appendChild(tags);
+    }
+
+    if (onDelete && p.id != null) {
+        const actions = document.createElement("div");
+        actions.className = "post-actions";
+
+        const commentsBtn = document.createElement("button");
+        commentsBtn.className = "btn-ghost comments-btn";
+        commentsBtn.dataset.postId = p.id;
+        commentsBtn.textContent = commentsLabel(app.commentCount(String(p.id)));
+        commentsBtn.onclick = () => toggleComments(postEl, String(p.id));
+
+        // Like and bookmark toggles show the counts the server returns
+        const likeBtn = document.createElement("button");
+        likeBtn.className = "btn-ghost";
+        const bookmarkBtn = document.createElement("button");
+        bookmarkBtn.className = "btn-ghost";
+        let reactions;
+        const showReactions = (r) => {
+            reactions = r;
+            likeBtn.textContent = `${r.liked ? "♥" : "♡"} ${r.likes}`;
+            bookmarkBtn.textContent = r.bookmarked ? "Bookmarked" : "Bookmark";
+        };
+        const id = String(p.id);
+        app.getReactions(id).then(showReactions).catch(console.error);
+        likeBtn.onclick = () => (reactions?.liked ? app.unlikePost(id) : app.likePost(id))
+            .then(showReactions).catch(console.error);
+        bookmarkBtn.onclick = () => (reactions?.bookmarked ? app.unbookmarkPost(id) : app.bookmarkPost(id))
+            .then(showReactions).catch(console.error);
+
+        const delBtn = document.createElement("button");
+        delBtn.className = "btn-danger";
+        delBtn.textContent = "Delete";
+        delBtn.onclick = onDelete;
+
+        actions.appendChild(commentsBtn);
+        actions.appendChild(likeBtn);
+        actions.appendChild(bookmarkBtn);
+        actions.appendChild(delBtn);
+        postEl.appendChild(actions);
+    }
+
+    return postEl;
+}
+
+function commentsLabel(count) {
+    return count == null ? "Comments" : `Comments (${count})`;
+}
+
+function renderComment(c) {
+    const el = document.createElement("div");
+    el.className = "comment";
+
+    const text = document.createElement("span");
+    text.textContent = c.content;
+    el.appendChild(text);
+
+    const del = document.createElement("button");
+    del.className = "btn-ghost";
+    del.textContent = "×";
+    del.onclick = async () => {
+        el.hidden = true;
+        try {
+            await app.deleteComment(c.id);
+            el.remove();
+        } catch (e) {
+            console.error(e);
+            el.hidden = false;
+            alert("Failed to delete comment");
+        }
+    };
+    el.appendChild(del);
+
+    return el;
+}
+
+async function toggleComments(postEl, postId) {
+    let panel = postEl.querySelector(".comments");
+    if (panel) {
+        panel.remove();
+        return;
+    }
+
+    panel = document.createElement("div");
+    panel.className = "comments";
+    panel.textContent = "Loading...";
+    postEl.appendChild(panel);
+
+    try {
+        const page = await app.loadComments(postId, 100);
+        panel.textContent = "";
+
+        const list = document.createElement("div");
+        for (const c of page.items) {
+            list.appendChild(renderComment(c));
+        }
+        panel.appendChild(list);
+
+        const form = document.createElement("form");
+        form.className = "comment-form";
+        const input = document.createElement("input");
+        input.placeholder = "Write a comment...";
+        form.appendChild(input);
+        form.onsubmit = async (e) => {
+            e.preventDefault();
+            const text = input.value.trim();
+            if (!text) {
+                return;
+            }
+
+            input.value = "";
+            try {
+                const comment = await app.addComment(postId, text);
+                list.appendChild(renderComment(comment));
+            } catch (err) {
+                console.error(err);
+                input.value = text;
+                alert("Failed to add comment");
+            }
+        };
+        panel.appendChild(form);
+    } catch (e) {
+        console.error(e);
+        panel.textContent = "Failed to load comments";
+    }
+}
+
+async function filterFeed(filter) {
+    feedFilter = filter;
+    feedPage = 1;
+
+    document.getElementById("search-query").value = filter.query ?? "";
+    document.getElementById("feed-filter").textContent =
+        filter.tag ? `#${filter.tag}` : filter.query ? `“${filter.query}”` : "";
+    document.getElementById("clear-filter").hidden = !filter.tag && !filter.query;
+
+    await loadFeed();
+}
+
+// Controller of the feed request in flight, aborted when a newer one starts.
+let feedRequest = null;
+
+async function loadFeed() {
+    const feedEl = document.getElementById("feed");
+    feedEl.textContent = "Loading...";
+    setFeedCursor(null);
+
+    feedRequest?.abort();
+    const request = new AbortController();
+    feedRequest = request;
+
+    try {
+        const page = feedFilter.query
+            ? await app.searchPosts(feedFilter.query, feedPage, request.signal)
+            : feedFilter.tag
+                ? await app.loadPostsByTag(feedFilter.tag)
+                : await app.loadPublicPosts(feedPage);
+        if (feedFilter.tag) {
+            document.getElementById("feed-pager").hidden = true;
+        } else {
+            renderPager("feed-pager", page);
+        }
+        setFeedCursor(feedFilter.tag ? page.next_cursor : null);
+
+        feedEl.innerHTML = "";
+        for (const p of page.items) {
+            feedEl.appendChild(renderPost(p));
+        }
+
+        if (page.items.length === 0) {
+            feedEl.textContent = feedFilter.query || feedFilter.tag
+                ? "No matching posts"
+                : "Nothing published yet";
+        }
+    } catch (e) {
+        if (request.signal.aborted) {
+            return;
+        }
+        console.error(e);
+        feedEl.textContent = "Failed to load the feed";
+    }
+}
+
+function setFeedCursor(cursor) {
+    feedCursor = cursor ?? null;
+    document.getElementById("feed-more").hidden = feedCursor == null;
+}
+
+// Appends the next page of the tag archive; at most one load runs at a time.
+let loadingMore = false;
+
+async function loadMoreByTag() {
+    if (loadingMore || feedCursor == null || !feedFilter.tag) {
+        return;
+    }
+
+    loadingMore = true;
+    const tag = feedFilter.tag;
+    try {
+        const page = await app.loadPostsByTag(tag, feedCursor);
+        if (feedFilter.tag !== tag) {
+            return; // the filter changed while loading
+        }
+
+        const feedEl = document.getElementById("feed");
+        for (const p of page.items) {
+            feedEl.appendChild(renderPost(p));
+        }
+        setFeedCursor(page.next_cursor);
+    } catch (e) {
+        console.error(e);
+    } finally {
+        loadingMore = false;
+    }
+}
+
+async function showArticle() {
+    const cardEl = document.getElementById("article-card");
+    const articleEl = document.getElementById("article");
+
+    if (!location.hash.startsWith(ARTICLE_ROUTE)) {
+        cardEl.hidden = true;
+        return;
+    }
+
+    cardEl.hidden = false;
+    articleEl.textContent = "Loading...";
+
+    try {
+        const slug = decodeURIComponent(location.hash.slice(ARTICLE_ROUTE.length));
+        const post = await app.getPostBySlug(slug);
+        articleEl.innerHTML = "";
+        articleEl.appendChild(renderPost(post));
+        cardEl.scrollIntoView({ behavior: "smooth" });
+    } catch (e) {
+        console.error(e);
+        articleEl.textContent = "Post not found";
+    }
 }
 
 async function loadPosts() {
@@ -18,74 +345,104 @@ async function loadPosts() {
     postsEl.textContent = "Loading...";
 
     try {
-        const res = await app.loadPosts();
-        const posts = Array.isArray(res) ? res : Object.values(res);
+        const page = await app.loadPosts(PAGE_SIZE, pageOffset);
+
+        // Step back if the current page emptied, e.g. after deleting its last post.
+        if (page.items.length === 0 && pageOffset > 0) {
+            pageOffset = Math.max(0, pageOffset - PAGE_SIZE);
+            return loadPosts();
+        }
+
+        const posts = page.items;
+        renderPager("pager", page);
 
         postsEl.innerHTML = "";
 
         for (const p of posts) {
-            console.log(p);
-            const postEl = document.createElement("div");
-            postEl.className = "post";
-
-            const header = document.createElement("div");
-            header.className = "post-header";
-
-            const title = document.createElement("div");
-            title.className = "post-title";
-            title.textContent = p.title || "(no title)";
-
-            const meta = document.createElement("div");
-            meta.className = "post-meta";
-            meta.textContent = p.created_at
-                ? new Date(p.created_at).toLocaleString()
-                : "";
-
-            header.appendChild(title);
-            header.appendChild(meta);
-            postEl.appendChild(header);
-
-            const content = document.createElement("div");
-            content.className = "post-content";
-            content.textContent = p.content || "";
-            postEl.appendChild(content);
-
-            if (p.id != null) {
-                const actions = document.createElement("div");
-                actions.className = "post-actions";
-
-                const delBtn = document.createElement("button");
-                delBtn.className = "btn-danger";
-                delBtn.textContent = "Delete";
-                delBtn.onclick = async () => {
-                    try {
-                        await app.deletePost(String(p.id));
-                        await loadPosts();
-                    } catch (e) {
-                        console.error(e);
-                        alert("Delete error");
-                    }
-                };
-
-                actions.appendChild(delBtn);
-                postEl.appendChild(actions);
-            }
-
-            postsEl.appendChild(postEl);
+            postsEl.appendChild(renderPost(p, async () => {
+                try {
+                    await app.deletePost(String(p.id));
+                    await loadPosts();
+                    await loadFeed();
+                } catch (e) {
+                    console.error(e);
+                    alert("Delete error");
+                }
+            }));
         }
 
         if (posts.length === 0) {
             setPostsMessage("No posts yet");
         }
     } catch (e) {
+        if (e.code === "UNAUTHORIZED") {
+            setPostsMessage("Log in to see posts.");
+            return;
+        }
         console.error(e);
         postsEl.textContent = "Failed to load posts";
     }
 }
 
+function renderPreview() {
+    const source = document.getElementById("post-content").value;
+    document.getElementById("post-preview").innerHTML = renderMarkdown(source);
+}
+
+async function refreshPendingStatus() {
+    const changes = await app.pendingChanges().catch(() => []);
+    document.getElementById("pending-status").textContent = changes.length
+        ? `${changes.length} change(s) waiting to sync`
+        : "";
+}
+
+async function loadProfile() {
+    const card = document.getElementById("account-card");
+    try {
+        const profile = await app.getProfile();
+        document.getElementById("profile-username").value = profile.username;
+        document.getElementById("profile-email").value = profile.email;
+        document.getElementById("profile-bio").value = profile.bio ?? "";
+        card.hidden = false;
+    } catch (err) {
+        console.error(err);
+        card.hidden = true;
+    }
+}
+
 async function main() {
     await init();
-    app = new BlogApp("http://localhost:8080");
+    app = new BlogApp(API_ADDR);
+
+    // Login, logout, refresh and expiry, from this tab or another one
+    app.onAuthChange(async (event) => {
+        await refreshAuthStatus();
+
+        if (event.kind === "refresh") {
+            return;
+        }
+
+        pageOffset = 0;
+        if (event.authenticated) {
+            await loadPosts();
+            await loadProfile();
+        } else {
+            document.getElementById("account-card").hidden = true;
+            document.getElementById("pager").hidden = true;
+            setPostsMessage(event.kind === "expired"
+                ? "Your session has expired. Log in again to see posts."
+                : "You have logged out. Log in to see posts.");
+        }
+    });
+
+    // Counts change optimistically as comments are added or deleted
+    app.onCommentCountChange((postId, count) => {
+        for (const btn of document.querySelectorAll(".comments-btn")) {
+            if (btn.dataset.postId === postId) {
+                btn.textContent = commentsLabel(count);
+            }
+        }
+    });
 
     // Registration
     document.getElementById("register-form").addEventListener("submit", async (e) => {
@@ -102,12 +459,10 @@ async function main() {
 
         try {
             await app.register(username, email, password);
-            await refreshAuthStatus();
-            await loadPosts(); // load posts immediately after registration
             alert("Registration successful");
         } catch (err) {
             console.error(err);
-            alert("Registration failed");
+            alert(`Registration failed: ${err.message ?? err}`);
         }
     });
 
@@ -125,26 +480,151 @@ async function main() {
 
         try {
             await app.login(email, password);
-            await refreshAuthStatus();
-            await loadPosts(); // load posts immediately after login
             alert("Login successful");
         } catch (err) {
             console.error(err);
-            alert("Login failed");
+            alert(`Login failed: ${err.message ?? err}`);
         }
     });
 
     // Logout
-    document.getElementById("logout-btn").addEventListener("click", async () => {
+    document.getElementById("logout-btn").addEventListener("click", () => {
         try {
             app.logout();
-            await refreshAuthStatus();
-            setPostsMessage("You have logged out. Log in to see posts.");
         } catch (e) {
             console.error(e);
         }
     });
 
+    // Account settings
+    document.getElementById("profile-form").addEventListener("submit", async (e) => {
+        e.preventDefault();
+        try {
+            await app.updateProfile({
+                username: document.getElementById("profile-username").value.trim(),
+                email: document.getElementById("profile-email").value.trim(),
+                bio: document.getElementById("profile-bio").value,
+            });
+            await loadProfile();
+            await refreshAuthStatus();
+        } catch (err) {
+            console.error(err);
+            alert(`Profile update failed: ${err.message ?? err}`);
+        }
+    });
+
+    document.getElementById("password-form").addEventListener("submit", async (e) => {
+        e.preventDefault();
+        const current = document.getElementById("current-password");
+        const next = document.getElementById("new-password");
+        try {
+            await app.changePassword(current.value, next.value);
+            current.value = "";
+            next.value = "";
+            alert("Password changed");
+        } catch (err) {
+            console.error(err);
+            alert(`Password change failed: ${err.message ?? err}`);
+        }
+    });
+
+    // Paging
+    document.getElementById("prev-page").addEventListener("click", async () => {
+        pageOffset = Math.max(0, pageOffset - PAGE_SIZE);
+        await loadPosts();
+    });
+
+    document.getElementById("next-page").addEventListener("click", async () => {
+        pageOffset += PAGE_SIZE;
+        await loadPosts();
+    });
+
+    document.getElementById("feed-prev").addEventListener("click", async () => {
+        feedPage = Math.max(1, feedPage - 1);
+        await loadFeed();
+    });
+
+    document.getElementById("feed-next").addEventListener("click", async () => {
+        feedPage += 1;
+        await loadFeed();
+    });
+
+    // Tag archives load their next page as the end of the list scrolls into view.
+    const feedMore = document.getElementById("feed-more");
+    feedMore.addEventListener("click", loadMoreByTag);
+    new IntersectionObserver((entries) => {
+        if (entries.some((entry) => entry.isIntersecting)) {
+            loadMoreByTag();
+        }
+    }).observe(feedMore);
+
+    document.getElementById("search-form").addEventListener("submit", async (e) => {
+        e.preventDefault();
+        const query = document.getElementById("search-query").value.trim();
+        await filterFeed(query ? { query } : {});
+    });
+
+    document.getElementById("clear-filter").addEventListener("click", () => filterFeed({}));
+
+    // Articles open from `#/p/<slug>` links
+    window.addEventListener("hashchange", showArticle);
+
+    // Live Markdown preview; the output is already sanitized
+    document.getElementById("post-content").addEventListener("input", renderPreview);
+
+    // Autosave the new post as a draft shortly after typing stops
+    for (const id of ["post-title", "post-content"]) {
+        document.getElementById(id).addEventListener("input", () => {
+            clearTimeout(draftTimer);
+            draftTimer = setTimeout(() => app.saveDraft(
+                DRAFT_ID,
+                document.getElementById("post-title").value,
+                document.getElementById("post-content").value,
+            ).catch(console.error), 500);
+        });
+    }
+    const draft = (await app.listDrafts().catch(() => []))
+        .find((d) => d.local_id === DRAFT_ID);
+    if (draft) {
+        document.getElementById("post-title").value = draft.title;
+        document.getElementById("post-content").value = draft.content;
+        renderPreview();
+    }
+
+    // Upload an image and embed its URL in the post content
+    document.getElementById("post-image").addEventListener("change", async (e) => {
+        const file = e.target.files[0];
+        if (!file) return;
+
+        const status = document.getElementById("upload-status");
+        try {
+            const media = await app.uploadMedia(file, (loaded, total) => {
+                status.textContent = `Uploading… ${Math.round((loaded / total) * 100)}%`;
+            });
+            const content = document.getElementById("post-content");
+            content.value += `${content.value ? "\n" : ""}![${media.filename}](${media.url})`;
+            renderPreview();
+            status.textContent = "Uploaded";
+        } catch (err) {
+            console.error(err);
+            status.textContent = err.message || "Upload failed";
+        } finally {
+            e.target.value = "";
+        }
+    });
+
+    // Changes queued offline are sent when the browser reconnects
+    app.onSync(async (report) => {
+        await refreshPendingStatus();
+        if (report.conflicts.length) {
+            alert(`${report.conflicts.length} offline edit(s) conflict with newer server changes`);
+        }
+        if (report.synced.length) {
+            await loadPosts();
+            await loadFeed();
+        }
+    });
+
     // Create post
     document.getElementById("create-post-form").addEventListener("submit", async (e) => {
         e.preventDefault();
@@ -158,10 +638,20 @@ async function main() {
         }
 
         try {
-            await app.createPost(title, content);
+            clearTimeout(draftTimer);
+            await app.saveDraft(DRAFT_ID, title, content);
+            const result = await app.promoteDraft(DRAFT_ID);
             document.getElementById("post-title").value = "";
             document.getElementById("post-content").value = "";
+            renderPreview();
+            if ("queued_at" in result) {
+                await refreshPendingStatus(); // offline, queued for later
+                return;
+            }
+            pageOffset = 0; // new posts appear first
+            feedPage = 1;
             await loadPosts();
+            await loadFeed();
         } catch (err) {
             console.error(err);
             alert("Failed to create post");
@@ -170,9 +660,14 @@ async function main() {
 
     // Initialize UI on page load
     await refreshAuthStatus();
+    await loadFeed();
+    await showArticle();
     const isAuth = await app.isAuthenticated();
     if (isAuth) {
+        await app.sync().catch(console.error);
+        await refreshPendingStatus();
         await loadPosts();
+        await loadProfile();
     } else {
         setPostsMessage("Log in to see posts.");
     }
@@ -0,0 +1,261 @@
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+
+use serde::Serialize;
+use serde_wasm_bindgen as swb;
+use tsify::Tsify;
+use gloo_net::http::RequestBuilder;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::prelude::*;
+use web_sys::{RequestCredentials, StorageEvent, XmlHttpRequest, window};
+
+use crate::token_store::{TokenStorage, TokenStore};
+use crate::{REFRESH_TOKEN_KEY, SESSION_EXPIRY_KEY, TOKEN_KEY, jwt};
+
+/// Longest delay `setTimeout` accepts, in milliseconds.
+const MAX_TIMEOUT_MS: i64 = i32::MAX as i64;
+
+/// Header carrying the CSRF token of a cookie session.
+pub(crate) const CSRF_HEADER: &str = "X-CSRF-Token";
+
+/// Credentials attached to authenticated requests.
+#[derive(Debug, Clone)]
+pub(crate) enum Credentials {
+    /// Access token sent in the `Authorization` header.
+    Bearer(String),
+    /// Session cookies sent by the browser, plus the CSRF token they require.
+    Cookie(String),
+}
+
+impl Credentials {
+    /// Adds the credentials to a `fetch` request.
+    pub(crate) fn apply(&self, request: RequestBuilder) -> RequestBuilder {
+        match self {
+            Self::Bearer(token) => request.header("Authorization", &format!("Bearer {}", token)),
+            Self::Cookie(csrf) => request
+                .header(CSRF_HEADER, csrf)
+                .credentials(RequestCredentials::Include),
+        }
+    }
+
+    /// Adds the credentials to an opened `XMLHttpRequest`.
+    pub(crate) fn apply_xhr(&self, xhr: &XmlHttpRequest) -> Result<(), JsValue> {
+        match self {
+            Self::Bearer(token) => {
+                xhr.set_request_header("Authorization", &format!("Bearer {}", token))
+            }
+            Self::Cookie(csrf) => {
+                xhr.set_with_credentials(true);
+                xhr.set_request_header(CSRF_HEADER, csrf)
+            }
+        }
+    }
+
+    /// Returns the token or CSRF token, which identifies the session.
+    pub(crate) fn secret(&self) -> &str {
+        match self {
+            Self::Bearer(secret) | Self::Cookie(secret) => secret,
+        }
+    }
+
+    /// Returns the ID of the user, if the credentials reveal it.
+    ///
+    /// Cookie sessions keep the access token out of reach of scripts.
+    pub(crate) fn subject(&self) -> Option<String> {
+        match self {
+            Self::Bearer(token) => jwt::subject(token),
+            Self::Cookie(_) => None,
+        }
+    }
+}
+
+/// Why the authentication state changed.
+#[derive(Debug, Clone, Copy, Serialize, Tsify)]
+#[serde(rename_all = "lowercase")]
+pub(crate) enum AuthChange {
+    Login,
+    Logout,
+    Refresh,
+    Expired,
+}
+
+/// Payload passed to `onAuthChange` callbacks.
+#[derive(Debug, Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct AuthEvent {
+    kind: AuthChange,
+    authenticated: bool,
+    /// Token expiry in milliseconds since the Unix epoch, if known.
+    #[tsify(optional)]
+    expires_at: Option<f64>,
+    /// Whether the change was made in another tab.
+    external: bool,
+}
+
+/// Authentication state shared between `BlogApp` and browser event handlers.
+#[derive(Default)]
+pub(crate) struct AuthState {
+    /// Where tokens are persisted.
+    pub(crate) store: TokenStore,
+    /// Whether the session lives in `HttpOnly` cookies rather than tokens.
+    pub(crate) cookie_session: bool,
+    /// Access token held in memory; the CSRF token for cookie sessions.
+    pub(crate) token: RefCell<Option<String>>,
+    /// Callback registered through `onAuthChange`.
+    listener: RefCell<Option<js_sys::Function>>,
+    /// Handle of the pending expiry timer, if any.
+    expiry_timer: Cell<Option<i32>>,
+}
+
+impl AuthState {
+    /// Creates an empty state persisting tokens in `store`.
+    pub(crate) fn new(store: TokenStore, cookie_session: bool) -> Self {
+        Self {
+            store,
+            cookie_session,
+            ..Self::default()
+        }
+    }
+
+    /// Returns the credentials of the current session, if any.
+    pub(crate) fn credentials(&self) -> Option<Credentials> {
+        let token = self.token.borrow().clone()?;
+        Some(if self.cookie_session {
+            Credentials::Cookie(token)
+        } else {
+            Credentials::Bearer(token)
+        })
+    }
+
+    /// Returns the access token's expiry in seconds since the Unix epoch, if known.
+    ///
+    /// Cookie sessions cannot read their token, so the expiry reported at
+    /// login is stored next to the CSRF token.
+    pub(crate) fn expires_at(&self) -> Option<i64> {
+        if self.cookie_session {
+            self.token.borrow().as_ref()?;
+            return self.store.get(SESSION_EXPIRY_KEY).ok()??.parse().ok();
+        }
+        self.token.borrow().as_deref().and_then(jwt::expires_at)
+    }
+
+    /// Returns whether the access token expires within `leeway_secs` from now.
+    ///
+    /// Tokens whose expiry cannot be read are left for the server to judge.
+    pub(crate) fn expires_within(&self, leeway_secs: i64) -> bool {
+        self.expires_at()
+            .is_some_and(|exp| exp - leeway_secs <= jwt::now())
+    }
+
+    /// Returns whether a refresh can renew the session once the access token lapses.
+    pub(crate) fn renewable(&self) -> bool {
+        self.cookie_session || self.store.get(REFRESH_TOKEN_KEY).unwrap_or(None).is_some()
+    }
+
+    /// Replaces the `onAuthChange` callback; `None` removes it.
+    pub(crate) fn set_listener(&self, callback: Option<js_sys::Function>) {
+        *self.listener.borrow_mut() = callback;
+    }
+
+    /// Re-arms the expiry timer and notifies the listener about a change.
+    pub(crate) fn emit(self: &Rc<Self>, kind: AuthChange, external: bool) {
+        self.schedule_expiry();
+
+        let event = AuthEvent {
+            kind,
+            authenticated: self.token.borrow().is_some(),
+            expires_at: self.expires_at().map(|exp| exp as f64 * 1000.0),
+            external,
+        };
+
+        // Cloned so the callback may replace itself without a borrow conflict.
+        let listener = self.listener.borrow().clone();
+        if let (Some(listener), Ok(event)) = (listener, swb::to_value(&event)) {
+            let _ = listener.call1(&JsValue::NULL, &event);
+        }
+    }
+
+    /// Schedules an `expired` notification for when the current token lapses,
+    /// replacing any earlier schedule.
+    pub(crate) fn schedule_expiry(self: &Rc<Self>) {
+        let Some(win) = window() else {
+            return;
+        };
+
+        if let Some(handle) = self.expiry_timer.take() {
+            win.clear_timeout_with_handle(handle);
+        }
+
+        let Some(exp) = self.expires_at() else {
+            return;
+        };
+        let delay = ((exp - jwt::now()) * 1000).clamp(0, MAX_TIMEOUT_MS) as i32;
+
+        let state = Rc::downgrade(self);
+        let callback = Closure::once_into_js(move || on_expiry(state));
+        if let Ok(handle) = win.set_timeout_with_callback_and_timeout_and_arguments_0(
+            callback.unchecked_ref(),
+            delay,
+        ) {
+            self.expiry_timer.set(Some(handle));
+        }
+    }
+}
+
+/// Clears a lapsed token and reports it, unless a refresh token can still
+/// renew the session on the next request.
+fn on_expiry(state: Weak<AuthState>) {
+    let Some(state) = state.upgrade() else {
+        return;
+    };
+    state.expiry_timer.set(None);
+
+    if state.expires_within(0) && !state.renewable() {
+        *state.token.borrow_mut() = None;
+        let _ = state.store.remove(TOKEN_KEY);
+        state.emit(AuthChange::Expired, false);
+    }
+}
+
+/// Listens for token changes made by other tabs and mirrors them into `state`.
+///
+/// Only `localStorage` is shared between tabs, so other strategies get no listener.
+///
+/// The returned closure must be kept alive and passed to [`unwatch_storage`]
+/// once the app goes away.
+pub(crate) fn watch_storage(state: &Rc<AuthState>) -> Option<Closure<dyn FnMut(StorageEvent)>> {
+    if state.store.kind() != TokenStorage::Local {
+        return None;
+    }
+    let win = window()?;
+    let state = Rc::downgrade(state);
+
+    let handler = Closure::<dyn FnMut(StorageEvent)>::new(move |event: StorageEvent| {
+        // A missing key means the whole storage was cleared.
+        if event.key().is_some_and(|key| key != TOKEN_KEY) {
+            return;
+        }
+        let Some(state) = state.upgrade() else {
+            return;
+        };
+
+        let old = state.token.replace(event.new_value());
+        let kind = match (old, event.new_value()) {
+            (_, None) => AuthChange::Logout,
+            (None, Some(_)) => AuthChange::Login,
+            (Some(_), Some(_)) => AuthChange::Refresh,
+        };
+        state.emit(kind, true);
+    });
+
+    win.add_event_listener_with_callback("storage", handler.as_ref().unchecked_ref())
+        .ok()?;
+    Some(handler)
+}
+
+/// Removes a listener installed by [`watch_storage`].
+pub(crate) fn unwatch_storage(handler: &Closure<dyn FnMut(StorageEvent)>) {
+    if let Some(win) = window() {
+        let _ = win.remove_event_listener_with_callback("storage", handler.as_ref().unchecked_ref());
+    }
+}
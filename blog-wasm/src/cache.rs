@@ -0,0 +1,153 @@
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::future::Future;
+use std::rc::Rc;
+
+use js_sys::{Date, Promise};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::{JsFuture, future_to_promise, spawn_local};
+
+/// Cached or in-flight response.
+struct Entry {
+    /// Distinguishes this entry from later ones stored under the same key.
+    id: u64,
+    /// Resolves to the response; shared by every caller asking for the key.
+    promise: Promise,
+    /// When the response arrived, in ms since the epoch; `None` while in flight.
+    fetched_at: Option<f64>,
+    /// Whether a background refresh of a stale entry is running.
+    revalidating: bool,
+}
+
+/// Stale-while-revalidate cache for GET requests.
+///
+/// Identical requests made while one is in flight share its response.
+/// Responses younger than the TTL are served from memory; older ones are
+/// served once more while a fresh copy is fetched in the background.
+/// Failed requests are never cached.
+pub(crate) struct RequestCache {
+    entries: Rc<RefCell<HashMap<String, Entry>>>,
+    next_id: Cell<u64>,
+    ttl_ms: f64,
+}
+
+impl RequestCache {
+    /// Creates a cache keeping responses fresh for `ttl_ms`; with `0` only
+    /// in-flight requests are shared.
+    pub(crate) fn new(ttl_ms: f64) -> Self {
+        Self {
+            entries: Rc::new(RefCell::new(HashMap::new())),
+            next_id: Cell::new(0),
+            ttl_ms,
+        }
+    }
+
+    /// Returns the response for `key`, calling `fetch` only when it is
+    /// neither cached nor in flight, or to refresh a stale copy.
+    pub(crate) async fn get<F, Fut>(&self, key: String, fetch: F) -> Result<JsValue, JsValue>
+    where
+        F: FnOnce() -> Fut,
+        Fut: Future<Output = Result<JsValue, JsValue>> + 'static,
+    {
+        let now = Date::now();
+        let cached = self.entries.borrow_mut().get_mut(&key).map(|entry| {
+            let stale = entry
+                .fetched_at
+                .is_some_and(|fetched_at| now - fetched_at >= self.ttl_ms);
+            let revalidate = stale && !entry.revalidating;
+            entry.revalidating |= revalidate;
+            (entry.promise.clone(), revalidate.then_some(entry.id))
+        });
+
+        let promise = match cached {
+            Some((promise, Some(id))) => {
+                self.revalidate(key, id, fetch());
+                promise
+            }
+            Some((promise, None)) => promise,
+            None => self.start(key, fetch()),
+        };
+
+        JsFuture::from(promise).await
+    }
+
+    /// Drops every entry whose key starts with `prefix`.
+    ///
+    /// Requests in flight still resolve for their callers but are not cached.
+    pub(crate) fn invalidate(&self, prefix: &str) {
+        self.entries
+            .borrow_mut()
+            .retain(|key, _| !key.starts_with(prefix));
+    }
+
+    /// Drops every entry.
+    pub(crate) fn clear(&self) {
+        self.entries.borrow_mut().clear();
+    }
+
+    /// Returns an ID for a new entry.
+    fn next_id(&self) -> u64 {
+        let id = self.next_id.get();
+        self.next_id.set(id + 1);
+        id
+    }
+
+    /// Sends a request and stores it as in flight under `key`.
+    fn start<Fut>(&self, key: String, fetch: Fut) -> Promise
+    where
+        Fut: Future<Output = Result<JsValue, JsValue>> + 'static,
+    {
+        let id = self.next_id();
+        let entries = Rc::clone(&self.entries);
+        let keep = self.ttl_ms > 0.0;
+        let entry_key = key.clone();
+
+        let promise = future_to_promise(async move {
+            let result = fetch.await;
+
+            let mut entries = entries.borrow_mut();
+            if let Some(entry) = entries.get_mut(&entry_key).filter(|entry| entry.id == id) {
+                if keep && result.is_ok() {
+                    entry.fetched_at = Some(Date::now());
+                } else {
+                    entries.remove(&entry_key);
+                }
+            }
+
+            result
+        });
+
+        self.entries.borrow_mut().insert(
+            key,
+            Entry {
+                id,
+                promise: promise.clone(),
+                fetched_at: None,
+                revalidating: false,
+            },
+        );
+        promise
+    }
+
+    /// Refreshes the stale entry `id` in the background, replacing it on success.
+    fn revalidate<Fut>(&self, key: String, id: u64, fetch: Fut)
+    where
+        Fut: Future<Output = Result<JsValue, JsValue>> + 'static,
+    {
+        let entries = Rc::clone(&self.entries);
+
+        spawn_local(async move {
+            let result = fetch.await;
+
+            let mut entries = entries.borrow_mut();
+            let Some(entry) = entries.get_mut(&key).filter(|entry| entry.id == id) else {
+                return;
+            };
+            if let Ok(value) = result {
+                entry.promise = Promise::resolve(&value);
+                entry.fetched_at = Some(Date::now());
+            }
+            entry.revalidating = false;
+        });
+    }
+}
@@ -0,0 +1,37 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+
+use crate::error::BlogError;
+
+/// Where the next page of a list starts, handed to JS as an opaque token.
+///
+/// Frontends only pass tokens back, so infinite scroll needs no offset
+/// arithmetic and the encoding can change without breaking callers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct PageCursor {
+    /// Page size the list was loaded with.
+    pub limit: u32,
+    /// Number of items before the page.
+    pub offset: u32,
+}
+
+impl PageCursor {
+    /// Encodes the cursor as a URL-safe token.
+    pub(crate) fn encode(self) -> String {
+        URL_SAFE_NO_PAD.encode(format!("{}:{}", self.limit, self.offset))
+    }
+
+    /// Decodes a token returned as `next_cursor`.
+    pub(crate) fn decode(token: &str) -> Result<Self, BlogError> {
+        let invalid = || BlogError::new(format!("invalid page cursor `{}`", token));
+
+        let bytes = URL_SAFE_NO_PAD.decode(token.trim()).map_err(|_| invalid())?;
+        let text = String::from_utf8(bytes).map_err(|_| invalid())?;
+        let (limit, offset) = text.split_once(':').ok_or_else(invalid)?;
+
+        Ok(Self {
+            limit: limit.parse().ok().filter(|limit| *limit > 0).ok_or_else(invalid)?,
+            offset: offset.parse().map_err(|_| invalid())?,
+        })
+    }
+}
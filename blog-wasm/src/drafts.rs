@@ -0,0 +1,58 @@
+use serde_wasm_bindgen as swb;
+use wasm_bindgen::prelude::*;
+use web_sys::IdbTransactionMode;
+
+use crate::error::BlogError;
+use crate::models::Draft;
+use crate::{idb, to_js};
+
+/// IndexedDB database holding unsent drafts.
+const DB_NAME: &str = "blog_drafts";
+
+/// Object store of drafts, keyed by the caller-chosen `local_id`.
+const STORE: &str = "drafts";
+
+/// Key path of [`STORE`].
+const KEY_PATH: &str = "local_id";
+
+/// Stores a draft, replacing any previous version with the same local ID.
+pub(crate) async fn save(draft: &Draft) -> Result<(), JsValue> {
+    let db = idb::open(DB_NAME, STORE, KEY_PATH).await?;
+    let store = idb::store(&db, STORE, IdbTransactionMode::Readwrite)?;
+
+    idb::complete(&store.put(&to_js(draft)?)?).await?;
+    Ok(())
+}
+
+/// Returns stored drafts, most recently saved first.
+pub(crate) async fn list() -> Result<Vec<Draft>, JsValue> {
+    let db = idb::open(DB_NAME, STORE, KEY_PATH).await?;
+    let store = idb::store(&db, STORE, IdbTransactionMode::Readonly)?;
+
+    let all = idb::complete(&store.get_all()?).await?;
+    let mut drafts: Vec<Draft> =
+        swb::from_value(all).map_err(|e| JsValue::from(BlogError::new(e.to_string())))?;
+    drafts.sort_by(|a, b| b.saved_at.total_cmp(&a.saved_at));
+    Ok(drafts)
+}
+
+/// Returns the draft stored under `local_id`, if any.
+pub(crate) async fn get(local_id: &str) -> Result<Option<Draft>, JsValue> {
+    let db = idb::open(DB_NAME, STORE, KEY_PATH).await?;
+    let store = idb::store(&db, STORE, IdbTransactionMode::Readonly)?;
+
+    let value = idb::complete(&store.get(&JsValue::from_str(local_id))?).await?;
+    if value.is_undefined() {
+        return Ok(None);
+    }
+    swb::from_value(value).map(Some).map_err(|e| BlogError::new(e.to_string()).into())
+}
+
+/// Removes the draft stored under `local_id`; missing drafts are ignored.
+pub(crate) async fn remove(local_id: &str) -> Result<(), JsValue> {
+    let db = idb::open(DB_NAME, STORE, KEY_PATH).await?;
+    let store = idb::store(&db, STORE, IdbTransactionMode::Readwrite)?;
+
+    idb::complete(&store.delete(&JsValue::from_str(local_id))?).await?;
+    Ok(())
+}
@@ -0,0 +1,214 @@
+use std::fmt::Display;
+
+use blog_core::error::ErrorBody;
+use gloo_net::http::Response;
+use js_sys::Reflect;
+use serde::{Deserialize, Serialize};
+use serde_wasm_bindgen as swb;
+use tsify::Tsify;
+use wasm_bindgen::JsValue;
+
+/// Name of the JS `Error` subclass every rejection carries.
+const ERROR_NAME: &str = "BlogError";
+
+/// Machine-readable error category, for branching in frontends.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum ErrorCode {
+    /// The server rejected the input (`400`).
+    BadRequest,
+    /// Not logged in, or the session expired (`401`).
+    Unauthorized,
+    /// Logged in but not allowed (`403`).
+    Forbidden,
+    /// The resource does not exist (`404`).
+    NotFound,
+    /// The resource changed since it was loaded (`409`).
+    Conflict,
+    /// The upload exceeds the server's size limit (`413`).
+    PayloadTooLarge,
+    /// Too many requests (`429`).
+    RateLimited,
+    /// The server failed (`5xx`).
+    ServerError,
+    /// Any other non-2xx status.
+    HttpError,
+    /// The server could not be reached.
+    NetworkError,
+    /// The request was cancelled through its `AbortSignal`.
+    Aborted,
+    /// The client failed before or after talking to the server.
+    ClientError,
+}
+
+impl ErrorCode {
+    /// Maps an HTTP status to its error code.
+    fn from_status(status: u16) -> Self {
+        match status {
+            400 => Self::BadRequest,
+            401 => Self::Unauthorized,
+            403 => Self::Forbidden,
+            404 => Self::NotFound,
+            409 => Self::Conflict,
+            413 => Self::PayloadTooLarge,
+            429 => Self::RateLimited,
+            500..=599 => Self::ServerError,
+            _ => Self::HttpError,
+        }
+    }
+
+    /// Returns the code as it appears in JS.
+    fn as_str(self) -> &'static str {
+        match self {
+            Self::BadRequest => "BAD_REQUEST",
+            Self::Unauthorized => "UNAUTHORIZED",
+            Self::Forbidden => "FORBIDDEN",
+            Self::NotFound => "NOT_FOUND",
+            Self::Conflict => "CONFLICT",
+            Self::PayloadTooLarge => "PAYLOAD_TOO_LARGE",
+            Self::RateLimited => "RATE_LIMITED",
+            Self::ServerError => "SERVER_ERROR",
+            Self::HttpError => "HTTP_ERROR",
+            Self::NetworkError => "NETWORK_ERROR",
+            Self::Aborted => "ABORTED",
+            Self::ClientError => "CLIENT_ERROR",
+        }
+    }
+}
+
+/// Error every `BlogApp` method rejects with.
+///
+/// Rejections are JS `Error` instances named `BlogError` carrying these
+/// fields, so `err.code === "UNAUTHORIZED"` works alongside `err.stack`.
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+pub struct BlogError {
+    /// Error category.
+    pub code: ErrorCode,
+
+    /// HTTP status code, when the server answered.
+    #[tsify(optional)]
+    pub status: Option<u16>,
+
+    /// Human-readable description.
+    pub message: String,
+
+    /// Stable server error code such as `POST_NOT_FOUND`, when the server sent one.
+    #[serde(rename = "serverCode")]
+    #[tsify(optional)]
+    pub server_code: Option<String>,
+
+    /// Server request ID from `X-Request-Id`, for matching server logs.
+    #[serde(rename = "requestId")]
+    #[tsify(optional)]
+    pub request_id: Option<String>,
+}
+
+impl BlogError {
+    /// Creates a client-side error that did not come from an HTTP response.
+    pub(crate) fn new(message: impl Into<String>) -> Self {
+        Self {
+            code: ErrorCode::ClientError,
+            status: None,
+            message: message.into(),
+            server_code: None,
+            request_id: None,
+        }
+    }
+
+    /// Creates an error from a failed HTTP status and its response body.
+    ///
+    /// The message uses the server's error details when the body is the
+    /// server's JSON error format, and the raw body otherwise.
+    pub(crate) fn http(status: u16, context: &str, body: &str) -> Self {
+        let error = serde_json::from_str::<ErrorBody>(body).ok();
+
+        Self {
+            code: ErrorCode::from_status(status),
+            status: Some(status),
+            message: format!("{} ({}): {}", context, status, server_message(error.as_ref(), body)),
+            server_code: error.as_ref().map(|error| error.code.to_string()),
+            request_id: error.and_then(|error| error.request_id),
+        }
+    }
+
+    /// Creates an error from a failed response, keeping its request ID.
+    ///
+    /// The ID comes from `X-Request-Id` when readable, and from the body otherwise.
+    pub(crate) fn from_response(resp: &Response, context: &str, body: &str) -> Self {
+        Self::http(resp.status(), context, body).with_request_id(resp.headers().get("x-request-id"))
+    }
+
+    /// Replaces the error code.
+    pub(crate) fn with_code(mut self, code: ErrorCode) -> Self {
+        self.code = code;
+        self
+    }
+
+    /// Attaches the server request ID, keeping the one from the body when `None`.
+    pub(crate) fn with_request_id(mut self, request_id: Option<String>) -> Self {
+        self.request_id = request_id.or(self.request_id);
+        self
+    }
+
+    /// Recovers the error from a value a method rejected with.
+    pub(crate) fn from_js(value: &JsValue) -> Self {
+        let field = |key: &str| {
+            Reflect::get(value, &JsValue::from_str(key))
+                .ok()
+                .filter(|v| !v.is_undefined() && !v.is_null())
+        };
+
+        Self {
+            code: field("code")
+                .and_then(|code| swb::from_value(code).ok())
+                .unwrap_or(ErrorCode::ClientError),
+            status: field("status").and_then(|status| status.as_f64()).map(|status| status as u16),
+            message: field("message")
+                .and_then(|message| message.as_string())
+                .or_else(|| value.as_string())
+                .unwrap_or_else(|| "unknown error".into()),
+            server_code: field("serverCode").and_then(|code| code.as_string()),
+            request_id: field("requestId").and_then(|id| id.as_string()),
+        }
+    }
+}
+
+/// Extracts the message from a server error body, given its parsed form.
+fn server_message(error: Option<&ErrorBody>, body: &str) -> String {
+    let Some(error) = error else {
+        return body.trim().to_string();
+    };
+
+    error
+        .details
+        .as_ref()
+        .and_then(|details| details.get("message"))
+        .and_then(|msg| msg.as_str())
+        .unwrap_or(&error.error)
+        .to_string()
+}
+
+impl From<BlogError> for JsValue {
+    /// Converts the error into a JS `Error` named `BlogError`.
+    fn from(err: BlogError) -> Self {
+        let js = js_sys::Error::new(&err.message);
+        js.set_name(ERROR_NAME);
+
+        let fields = [
+            ("code", JsValue::from_str(err.code.as_str())),
+            ("status", err.status.map_or(JsValue::UNDEFINED, JsValue::from)),
+            ("serverCode", err.server_code.map_or(JsValue::UNDEFINED, JsValue::from)),
+            ("requestId", err.request_id.map_or(JsValue::UNDEFINED, JsValue::from)),
+        ];
+        for (key, value) in fields {
+            let _ = Reflect::set(&js, &JsValue::from_str(key), &value);
+        }
+
+        js.into()
+    }
+}
+
+/// Converts any displayable error into a [`BlogError`] JS value.
+pub(crate) fn to_js_error<E: Display>(e: E) -> JsValue {
+    BlogError::new(e.to_string()).into()
+}
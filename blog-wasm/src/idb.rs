@@ -0,0 +1,75 @@
+use js_sys::Promise;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    IdbDatabase, IdbObjectStore, IdbObjectStoreParameters, IdbRequest, IdbTransactionMode, window,
+};
+
+use crate::error::BlogError;
+
+/// Opens an IndexedDB database holding a single auto-keyed object store.
+///
+/// Records get their key injected under `key_path` when first stored.
+pub(crate) async fn open(name: &str, store: &str, key_path: &str) -> Result<IdbDatabase, JsValue> {
+    let factory = window()
+        .ok_or_else(|| BlogError::new("no window"))?
+        .indexed_db()?
+        .ok_or_else(|| BlogError::new("IndexedDB not available"))?;
+    let request = factory.open_with_u32(name, 1)?;
+
+    let store = store.to_string();
+    let key_path = JsValue::from_str(key_path);
+    let upgrade_request = request.clone();
+    let upgrade = Closure::once(move || {
+        let Ok(db) = upgrade_request.result() else {
+            return;
+        };
+        let db: IdbDatabase = db.unchecked_into();
+        if !db.object_store_names().contains(&store) {
+            let params = IdbObjectStoreParameters::new();
+            params.set_key_path(&key_path);
+            params.set_auto_increment(true);
+            let _ = db.create_object_store_with_optional_parameters(&store, &params);
+        }
+    });
+    request.set_onupgradeneeded(Some(upgrade.as_ref().unchecked_ref()));
+
+    let db = complete(&request).await;
+    request.set_onupgradeneeded(None);
+    drop(upgrade);
+
+    Ok(db?.unchecked_into())
+}
+
+/// Returns `store` from a new transaction on `db`.
+pub(crate) fn store(
+    db: &IdbDatabase,
+    name: &str,
+    mode: IdbTransactionMode,
+) -> Result<IdbObjectStore, JsValue> {
+    db.transaction_with_str_and_mode(name, mode)?.object_store(name)
+}
+
+/// Waits for an IndexedDB request to finish and returns its result.
+pub(crate) async fn complete(request: &IdbRequest) -> Result<JsValue, JsValue> {
+    let done = Promise::new(&mut |resolve, reject| {
+        request.set_onsuccess(Some(&resolve));
+        request.set_onerror(Some(&reject));
+    });
+
+    let finished = JsFuture::from(done).await;
+    request.set_onsuccess(None);
+    request.set_onerror(None);
+
+    finished.map_err(|_| {
+        let reason = request
+            .error()
+            .ok()
+            .flatten()
+            .map(|e| e.message())
+            .unwrap_or_else(|| "unknown error".into());
+        JsValue::from(BlogError::new(format!("IndexedDB error: {}", reason)))
+    })?;
+
+    request.result()
+}
@@ -0,0 +1,40 @@
+use base64::Engine;
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use serde::Deserialize;
+
+/// Claims the browser client reads from an access token.
+///
+/// The signature is not verified: the server remains the authority, these
+/// are only used to decide when to refresh.
+#[derive(Debug, Deserialize)]
+struct TokenClaims {
+    /// Expiration time, in seconds since the Unix epoch.
+    #[serde(default)]
+    exp: Option<i64>,
+
+    /// ID of the user the token was issued to.
+    #[serde(default)]
+    sub: Option<String>,
+}
+
+/// Decodes the token's claims, if it is a readable JWT.
+fn claims(token: &str) -> Option<TokenClaims> {
+    let payload = token.split('.').nth(1)?;
+    let bytes = URL_SAFE_NO_PAD.decode(payload).ok()?;
+    serde_json::from_slice(&bytes).ok()
+}
+
+/// Returns the token's expiry in seconds since the Unix epoch, if it can be read.
+pub(crate) fn expires_at(token: &str) -> Option<i64> {
+    claims(token)?.exp
+}
+
+/// Returns the ID of the user the token belongs to, if it can be read.
+pub(crate) fn subject(token: &str) -> Option<String> {
+    claims(token)?.sub
+}
+
+/// Returns the current time in seconds since the Unix epoch.
+pub(crate) fn now() -> i64 {
+    (js_sys::Date::now() / 1000.0) as i64
+}
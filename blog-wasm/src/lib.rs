@@ -1,159 +1,618 @@
-use gloo_net::http::Request;
-use serde::{Deserialize, Serialize};
-use serde_json::Value;
+mod auth;
+mod cache;
+mod cursor;
+mod drafts;
+mod error;
+mod idb;
+mod jwt;
+mod markdown;
+mod models;
+mod offline;
+mod optimistic;
+mod token_store;
+mod upload;
+
+use std::cell::{Cell, RefCell};
+use std::collections::HashMap;
+use std::rc::Rc;
+
+use auth::{AuthChange, AuthState, Credentials};
+use cache::RequestCache;
+use cursor::PageCursor;
+use offline::SyncState;
+use optimistic::{OptimisticEvent, OptimisticOutcome, OptimisticState};
+use token_store::TokenStore;
+use error::{to_js_error, BlogError, ErrorCode};
+use models::{
+    AuthTokens, BlogAppOptions, Comment, CookieSession, Draft, Media, Page, PendingChange, Post,
+    Profile, ProfileUpdate, ProvisionalPost, Reactions, RegisteredUser, SearchResult,
+};
+
+use blog_core::auth::{LoginRequest, PasswordChange, RegisterRequest};
+use blog_core::comment::CommentRequest;
+use blog_core::post::PostRequest;
+use chrono::{DateTime, Utc};
+use gloo_net::http::{Request, RequestBuilder, Response};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
 use serde_wasm_bindgen as swb;
 use wasm_bindgen::prelude::*;
-use web_sys::{window, Storage};
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{AbortSignal, File, FormData, RequestCredentials, StorageEvent};
+use uuid::Uuid;
 
 /// Key used to store the JWT token in browser storage.
 const TOKEN_KEY: &str = "blog_token";
 
+/// Key used to store the refresh token in browser storage.
+const REFRESH_TOKEN_KEY: &str = "blog_refresh_token";
+
+/// Key used to store when a cookie session's access token expires.
+const SESSION_EXPIRY_KEY: &str = "blog_session_expires";
+
+/// Access tokens are refreshed once they are this close to expiry.
+const REFRESH_LEEWAY_SECS: i64 = 60;
+
+/// Page size used when `loadPosts` is called without a limit; matches the server default.
+const DEFAULT_PAGE_SIZE: u32 = 20;
+
+/// Response header carrying the total number of posts across all pages.
+const TOTAL_COUNT_HEADER: &str = "X-Total-Count";
+
 /// WASM client for interacting with the Blog backend.
 ///
 /// Exposed to JavaScript via `wasm-bindgen`.
 #[wasm_bindgen]
 pub struct BlogApp {
     server_addr: String,
-    token: Option<String>,
+    /// Server address plus the API prefix; every route is relative to it.
+    api_base: String,
+    /// Token and `onAuthChange` state, shared with browser event handlers.
+    auth: Rc<AuthState>,
+    /// Cross-tab `storage` listener, removed when the app is dropped.
+    storage_listener: Option<Closure<dyn FnMut(StorageEvent)>>,
+    /// Offline queue state, shared with the `online` handler.
+    sync: Rc<SyncState>,
+    /// Optimistic change listener, shared with the requests still running.
+    optimistic: Rc<OptimisticState>,
+    /// `online` listener that syncs the queue, removed when the app is dropped.
+    online_listener: Option<Closure<dyn FnMut()>>,
+    /// Whether a token refresh is in flight, so concurrent calls start only one.
+    refreshing: Cell<bool>,
+    /// Known comment count per post ID, updated optimistically.
+    comment_counts: RefCell<HashMap<String, u64>>,
+    /// Post ID of each comment seen, so deletes know which count to adjust.
+    comment_posts: RefCell<HashMap<String, String>>,
+    /// Callback invoked as `(postId, count)` whenever a known count changes.
+    comment_count_listener: RefCell<Option<js_sys::Function>>,
+    /// Shared and cached list responses, dropped whenever data changes.
+    cache: Rc<RequestCache>,
+}
+
+/// Converts a 1-based page number of public listings into an offset.
+fn page_offset(page: Option<u32>) -> u32 {
+    page.unwrap_or(1).saturating_sub(1) * DEFAULT_PAGE_SIZE
 }
 
-/// User registration request payload.
-#[derive(Serialize)]
-struct RegisterRequest {
-    username: String,
-    email: String,
-    password: String,
+/// Joins a base URL and a relative path.
+fn join_url(base: &str, path: &str) -> String {
+    format!(
+        "{}/{}",
+        base.trim_end_matches('/'),
+        path.trim_start_matches('/')
+    )
 }
 
-/// User login request payload.
-#[derive(Serialize)]
-struct LoginRequest {
-    email: String,
-    password: String,
+/// Sends a post create or update request.
+///
+/// An `Err` means the server could not be reached at all.
+async fn send_post(
+    request: RequestBuilder,
+    token: &Credentials,
+    body: &PostRequest,
+) -> Result<Response, gloo_net::Error> {
+    token.apply(request)
+        .header("Content-Type", "application/json")
+        .json(body)?
+        .send()
+        .await
+}
+
+/// Outcome of [`submit_post`].
+enum Saved {
+    /// The server stored the post.
+    Post(Post),
+    /// The browser was offline and the change was queued.
+    Queued(PendingChange),
+}
+
+/// Sends a post create or update, queueing it instead when offline.
+///
+/// `post_id` names the edited post for updates.
+async fn submit_post(
+    request: RequestBuilder,
+    token: &Credentials,
+    post_id: Option<Uuid>,
+    body: PostRequest,
+    context: &str,
+) -> Result<Saved, JsValue> {
+    if !offline::is_offline()
+        && let Ok(resp) = send_post(request, token, &body).await
+    {
+        return parse_response(resp, context).await.map(Saved::Post);
+    }
+
+    offline::enqueue(PendingChange::new(post_id, body))
+        .await
+        .map(Saved::Queued)
 }
 
-/// Post creation and update payload.
-#[derive(Serialize)]
-struct PostPayload {
-    title: String,
-    content: String,
+/// Parses an ID passed in from JavaScript.
+fn parse_id(id: &str) -> Result<Uuid, JsValue> {
+    Uuid::parse_str(id).map_err(|e| BlogError::new(format!("Invalid ID {:?}: {}", id, e)).into())
 }
 
-/// Post model used for deserializing API responses.
-#[derive(Debug, Serialize, Deserialize)]
-struct Post {
-    id: String,
-    author_id: String,
-    title: String,
-    content: String,
-    created_at: String,
+/// Parses an RFC 3339 timestamp passed in from JavaScript.
+fn parse_timestamp(value: &str) -> Result<DateTime<Utc>, JsValue> {
+    DateTime::parse_from_rfc3339(value)
+        .map(|ts| ts.with_timezone(&Utc))
+        .map_err(|e| BlogError::new(format!("Invalid timestamp {:?}: {}", value, e)).into())
 }
 
-/// Converts an error into a JavaScript value.
-fn to_js_error<E: std::fmt::Display>(e: E) -> JsValue {
-    JsValue::from_str(&e.to_string())
+/// Converts a failed request into a JS error, reporting cancellations as such.
+fn request_error(err: gloo_net::Error, signal: Option<&AbortSignal>) -> JsValue {
+    if signal.is_some_and(AbortSignal::aborted) {
+        BlogError::new("Request aborted").with_code(ErrorCode::Aborted).into()
+    } else {
+        network_error(err)
+    }
 }
 
-/// Returns browser local storage.
-fn storage() -> Result<Storage, JsValue> {
-    let win = window().ok_or_else(|| JsValue::from_str("no window"))?;
-    let storage = win
-        .local_storage()
-        .map_err(|e| JsValue::from_str(&format!("localStorage error: {:?}", e)))?
-        .ok_or_else(|| JsValue::from_str("localStorage not available"))?;
-    Ok(storage)
+/// Converts a request that never got a response into a JS error.
+fn network_error(err: gloo_net::Error) -> JsValue {
+    BlogError::new(err.to_string()).with_code(ErrorCode::NetworkError).into()
 }
 
-/// Saves a JWT token to browser storage.
-fn save_token_to_storage(token: &str) -> Result<(), JsValue> {
-    let storage = storage()?;
-    storage
-        .set_item(TOKEN_KEY, token)
-        .map_err(|e| JsValue::from_str(&format!("Failed to save token: {:?}", e)))
+/// Serializes a response model into a plain JS object.
+fn to_js<T: Serialize>(value: &T) -> Result<JsValue, JsValue> {
+    swb::to_value(value).map_err(to_js_error)
 }
 
-/// Loads a JWT token from browser storage.
-fn get_token_from_storage() -> Result<Option<String>, JsValue> {
-    let storage = storage()?;
-    let res = storage
-        .get_item(TOKEN_KEY)
-        .map_err(|e| JsValue::from_str(&format!("Failed to read token: {:?}", e)))?;
-    Ok(res)
+/// Reads a JSON response body, failing with `context` on non-2xx statuses.
+async fn parse_response<T: DeserializeOwned>(resp: Response, context: &str) -> Result<T, JsValue> {
+    let status = resp.status();
+    let text = resp.text().await.map_err(to_js_error)?;
+    if !(200..300).contains(&status) {
+        return Err(BlogError::from_response(&resp, context, &text).into());
+    }
+
+    serde_json::from_str(&text).map_err(to_js_error)
 }
 
-/// Removes the JWT token from browser storage.
-fn remove_token_from_storage() -> Result<(), JsValue> {
-    let storage = storage()?;
-    let _ = storage.remove_item(TOKEN_KEY);
+/// Fails with `context` on non-2xx statuses, ignoring the body otherwise.
+async fn check_response(resp: Response, context: &str) -> Result<(), JsValue> {
+    if !resp.ok() {
+        let text = resp.text().await.unwrap_or_default();
+        return Err(BlogError::from_response(&resp, context, &text).into());
+    }
     Ok(())
 }
 
+/// Fetches one page of `T` from a list endpoint.
+///
+/// `total` comes from `X-Total-Count`; without it, a full page is taken
+/// to mean more items may follow, and then `next_cursor` points past this
+/// page. Aborting `signal` cancels the request.
+async fn get_page<T: DeserializeOwned>(
+    url: String,
+    query: Vec<(String, String)>,
+    token: Option<Credentials>,
+    limit: u32,
+    offset: u32,
+    signal: Option<&AbortSignal>,
+) -> Result<Page<T>, JsValue> {
+    let mut req = Request::get(&url)
+        .query(query.iter().map(|(key, value)| (key.as_str(), value)))
+        .abort_signal(signal);
+    if let Some(token) = token {
+        req = token.apply(req);
+    }
+    let resp = req.send().await.map_err(|e| request_error(e, signal))?;
+
+    let total = resp
+        .headers()
+        .get(TOTAL_COUNT_HEADER)
+        .and_then(|value| value.parse::<u64>().ok());
+
+    let items: Vec<T> = parse_response(resp, "Load failed").await?;
+    let has_more = match total {
+        Some(total) => u64::from(offset) + (items.len() as u64) < total,
+        None => items.len() as u32 == limit,
+    };
+    let next_cursor = has_more.then(|| {
+        PageCursor {
+            limit,
+            offset: offset.saturating_add(limit),
+        }
+        .encode()
+    });
+
+    Ok(Page {
+        items,
+        total,
+        limit,
+        offset,
+        has_more,
+        next_cursor,
+    })
+}
+
 impl BlogApp {
     /// Builds a full API URL from a relative path.
     fn url(&self, path: &str) -> String {
-        format!(
-            "{}/{}",
-            self.server_addr.trim_end_matches('/'),
-            path.trim_start_matches('/')
-        )
+        join_url(&self.api_base, path)
+    }
+
+    /// Sends a post create or update, queueing it instead when offline.
+    async fn save_post(
+        &self,
+        request: RequestBuilder,
+        post_id: Option<Uuid>,
+        body: PostRequest,
+        context: &str,
+    ) -> Result<JsValue, JsValue> {
+        let token = self.auth_token().await?;
+
+        let saved = submit_post(request, &token, post_id, body, context).await;
+        self.cache.clear();
+
+        match saved? {
+            Saved::Post(post) => to_js(&post),
+            Saved::Queued(change) => to_js(&change),
+        }
     }
 
-    /// Converts an HTTP response into a `JsValue`.
-    async fn response_to_jsvalue(
-        resp: gloo_net::http::Response,
+    /// Sends a post create or update in the background and returns
+    /// `provisional` right away; `onOptimistic` learns how it ended.
+    ///
+    /// `previous` is the post being updated, if any.
+    fn save_post_optimistic(
+        &self,
+        request: RequestBuilder,
+        token: Credentials,
+        body: PostRequest,
+        provisional: ProvisionalPost,
+        previous: Option<Post>,
+        context: &'static str,
     ) -> Result<JsValue, JsValue> {
-        let status = resp.status();
-        if !resp.ok() {
-            let text = resp.text().await.unwrap_or_default();
-            return Err(JsValue::from_str(&format!("HTTP {}: {}", status, text)));
+        let cache = Rc::clone(&self.cache);
+        let state = Rc::clone(&self.optimistic);
+        let provisional_id = provisional.post.id;
+        let post_id = previous.as_ref().map(|post| post.id);
+
+        spawn_local(async move {
+            let saved = submit_post(request, &token, post_id, body, context).await;
+            cache.clear();
+
+            let mut event = OptimisticEvent {
+                kind: OptimisticOutcome::RolledBack,
+                provisional_id,
+                post: None,
+                change: None,
+                previous,
+                error: None,
+            };
+            match saved {
+                Ok(Saved::Post(post)) => {
+                    event.kind = OptimisticOutcome::Confirmed;
+                    event.post = Some(post);
+                }
+                Ok(Saved::Queued(change)) => {
+                    event.kind = OptimisticOutcome::Queued;
+                    event.change = Some(change);
+                }
+                Err(e) => event.error = Some(BlogError::from_js(&e)),
+            }
+            state.emit(&event);
+        });
+
+        to_js(&provisional)
+    }
+
+    /// Sends a like or bookmark change and returns the post's updated counts.
+    async fn react(&self, request: RequestBuilder, context: &str) -> Result<JsValue, JsValue> {
+        let token = self.auth_token().await?;
+
+        let resp = token.apply(request)
+            .send()
+            .await
+            .map_err(network_error)?;
+        self.cache.clear();
+
+        let reactions: Reactions = parse_response(resp, context).await?;
+        to_js(&reactions)
+    }
+
+    /// Fetches one page from a list endpoint, adding `params` to the query.
+    ///
+    /// Requests without a signal go through the response cache, so identical
+    /// ones in flight share a response and recent ones are served from memory.
+    /// Aborting `signal` cancels the request.
+    async fn fetch_page<T: DeserializeOwned + Serialize + 'static>(
+        &self,
+        path: &str,
+        token: Option<&Credentials>,
+        params: &[(&str, &str)],
+        limit: u32,
+        offset: u32,
+        signal: Option<&AbortSignal>,
+    ) -> Result<Page<T>, JsValue> {
+        let url = self.url(path);
+        let mut query: Vec<(String, String)> = params
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        query.push(("limit".into(), limit.to_string()));
+        query.push(("offset".into(), offset.to_string()));
+        let token = token.cloned();
+
+        if signal.is_some() {
+            return get_page(url, query, token, limit, offset, signal).await;
         }
 
-        let text = resp.text().await.map_err(to_js_error)?;
-        if text.trim().is_empty() {
-            return Ok(JsValue::UNDEFINED);
+        let secret = token.as_ref().map(Credentials::secret).unwrap_or_default();
+        let key = format!("{}?{:?} {}", url, query, secret);
+        let page = self
+            .cache
+            .get(key, move || async move {
+                to_js(&get_page::<T>(url, query, token, limit, offset, None).await?)
+            })
+            .await?;
+        swb::from_value(page).map_err(to_js_error)
+    }
+
+    /// Fetches a single post from the public API.
+    async fn fetch_post(&self, path: &str) -> Result<JsValue, JsValue> {
+        let url = self.url(path);
+
+        let resp = Request::get(&url).send().await.map_err(network_error)?;
+
+        let post: Post = parse_response(resp, "Load post failed").await?;
+        to_js(&post)
+    }
+
+    /// Records a post's comment count and notifies the listener.
+    fn set_comment_count(&self, post_id: &str, count: u64) {
+        self.comment_counts
+            .borrow_mut()
+            .insert(post_id.to_string(), count);
+        self.notify_comment_count(post_id, count);
+    }
+
+    /// Shifts a known comment count by `delta`; unknown counts stay unknown.
+    fn adjust_comment_count(&self, post_id: &str, delta: i64) {
+        let count = self
+            .comment_counts
+            .borrow()
+            .get(post_id)
+            .map(|count| count.saturating_add_signed(delta));
+
+        if let Some(count) = count {
+            self.set_comment_count(post_id, count);
         }
+    }
 
-        let json: Value = serde_json::from_str(&text).map_err(to_js_error)?;
-        swb::to_value(&json).map_err(to_js_error)
+    /// Invokes the comment count listener, if one is registered.
+    fn notify_comment_count(&self, post_id: &str, count: u64) {
+        // Cloned so the callback may replace itself without a borrow conflict.
+        let listener = self.comment_count_listener.borrow().clone();
+        if let Some(listener) = listener {
+            let _ = listener.call2(
+                &JsValue::NULL,
+                &JsValue::from_str(post_id),
+                &JsValue::from_f64(count as f64),
+            );
+        }
     }
 
     /// Stores the JWT token in memory and browser storage.
-    fn set_token(&mut self, token: &str) -> Result<(), JsValue> {
-        self.token = Some(token.to_string());
-        save_token_to_storage(token)
+    fn set_token(&self, token: &str) -> Result<(), JsValue> {
+        *self.auth.token.borrow_mut() = Some(token.to_string());
+        self.auth.store.save(TOKEN_KEY, token)
     }
 
-    /// Extracts and stores a JWT token from a JSON response.
-    fn extract_and_store_token(&mut self, json: &Value) -> Result<(), JsValue> {
-        if let Some(token) = json.get("access_token").and_then(|t| t.as_str()) {
-            self.set_token(token)?;
-        }
+    /// Stores the CSRF token and access token expiry of a cookie session.
+    ///
+    /// Cookie sessions keep their tokens in `HttpOnly` cookies, out of reach
+    /// of scripts, so no refresh token is stored either.
+    fn store_cookie_session(&self, session: &CookieSession) -> Result<(), JsValue> {
+        self.auth.store.remove(REFRESH_TOKEN_KEY)?;
+        self.auth
+            .store
+            .save(SESSION_EXPIRY_KEY, &(jwt::now() + session.expires_in).to_string())?;
+        self.set_token(&session.csrf_token)?;
+        self.cache.clear();
         Ok(())
     }
 
-    /// Returns the currently active JWT token, if any.
-    fn get_current_token(&self) -> Result<Option<String>, JsValue> {
-        if let Some(t) = &self.token {
-            return Ok(Some(t.clone()));
+    /// Stores the JWT and refresh tokens from a login or refresh response.
+    ///
+    /// A response without a refresh token clears the stored one, so it never
+    /// outlives the session it belonged to.
+    fn store_tokens(&self, tokens: &AuthTokens) -> Result<(), JsValue> {
+        self.set_token(&tokens.access_token)?;
+        self.cache.clear();
+
+        match &tokens.refresh_token {
+            Some(refresh) => self.auth.store.save(REFRESH_TOKEN_KEY, refresh),
+            None => self.auth.store.remove(REFRESH_TOKEN_KEY),
         }
-        get_token_from_storage()
+    }
+
+    /// Forgets both tokens.
+    fn clear_tokens(&self) -> Result<(), JsValue> {
+        *self.auth.token.borrow_mut() = None;
+        self.cache.clear();
+        self.auth.store.remove(REFRESH_TOKEN_KEY)?;
+        self.auth.store.remove(SESSION_EXPIRY_KEY)?;
+        self.auth.store.remove(TOKEN_KEY)
+    }
+
+    /// Returns credentials for an authenticated request, refreshing the
+    /// session first when its access token is about to expire and can be renewed.
+    ///
+    /// A failed refresh keeps the current token while it is still valid;
+    /// once it has expired the session is cleared.
+    async fn auth_token(&self) -> Result<Credentials, JsValue> {
+        let token = self
+            .auth
+            .credentials()
+            .ok_or_else(|| BlogError::new("Not authenticated").with_code(ErrorCode::Unauthorized))?;
+
+        if !self.auth.expires_within(REFRESH_LEEWAY_SECS)
+            || !self.auth.renewable()
+            || self.refreshing.get()
+        {
+            return Ok(token);
+        }
+
+        self.refreshing.set(true);
+        let result = self.refresh(&token).await;
+        self.refreshing.set(false);
+
+        match result {
+            Ok(token) => {
+                self.auth.emit(AuthChange::Refresh, false);
+                Ok(token)
+            }
+            Err(_) if !self.auth.expires_within(0) => Ok(token),
+            Err(_) => {
+                self.clear_tokens()?;
+                self.auth.emit(AuthChange::Expired, false);
+                Err(BlogError::new("Session expired, please log in again")
+                    .with_code(ErrorCode::Unauthorized)
+                    .into())
+            }
+        }
+    }
+
+    /// Renews the session and stores the new tokens.
+    ///
+    /// Token sessions exchange the stored refresh token for a new pair;
+    /// cookie sessions send their refresh cookie and get new cookies.
+    async fn refresh(&self, current: &Credentials) -> Result<Credentials, JsValue> {
+        let url = self.url("/public/auth/refresh");
+
+        if let Credentials::Cookie(_) = current {
+            let resp = current
+                .apply(Request::post(&url))
+                .send()
+                .await
+                .map_err(network_error)?;
+
+            let session: CookieSession = parse_response(resp, "Token refresh failed").await?;
+            self.store_cookie_session(&session)?;
+            return Ok(Credentials::Cookie(session.csrf_token));
+        }
+
+        let refresh_token = self
+            .auth
+            .store
+            .get(REFRESH_TOKEN_KEY)?
+            .ok_or_else(|| BlogError::new("No refresh token").with_code(ErrorCode::Unauthorized))?;
+        let body = serde_json::json!({ "refresh_token": refresh_token });
+
+        let resp = Request::post(&url)
+            .header("Content-Type", "application/json")
+            .json(&body)
+            .map_err(to_js_error)?
+            .send()
+            .await
+            .map_err(network_error)?;
+
+        let tokens: AuthTokens = parse_response(resp, "Token refresh failed").await?;
+        self.store_tokens(&tokens)?;
+        Ok(Credentials::Bearer(tokens.access_token))
+    }
+
+    /// Ends the session on the server in the background.
+    ///
+    /// Token sessions revoke their refresh token; cookie sessions also get
+    /// their cookies deleted. Failures are ignored, the local session is
+    /// gone either way.
+    fn revoke_session(&self) {
+        let url = self.url("/public/auth/logout");
+        let request = match self.auth.credentials() {
+            Some(credentials @ Credentials::Cookie(_)) => {
+                credentials.apply(Request::post(&url)).build()
+            }
+            _ => {
+                let Some(refresh_token) = self.auth.store.get(REFRESH_TOKEN_KEY).unwrap_or(None)
+                else {
+                    return;
+                };
+                let body = serde_json::json!({ "refresh_token": refresh_token });
+                Request::post(&url).json(&body)
+            }
+        };
+        let Ok(request) = request else {
+            return;
+        };
+        spawn_local(async move {
+            let _ = request.send().await;
+        });
     }
 }
 
 #[wasm_bindgen]
 impl BlogApp {
     /// Creates a new Blog WASM client.
+    ///
+    /// `options.storage` picks where tokens are kept: `"local"` (default),
+    /// `"session"`, `"memory"` or `"cookie"`. `options.apiPrefix` is the
+    /// path the API is mounted under, `"/api/v1"` by default. `options.cacheTtlMs`
+    /// is how long list responses stay fresh; `0` only shares in-flight requests.
+    /// `options.cookieSession` keeps the session in `HttpOnly` cookies set by
+    /// the server instead of tokens readable by scripts.
     #[wasm_bindgen(constructor)]
-    pub fn new(addr: String) -> BlogApp {
-        let token = get_token_from_storage().unwrap_or(None);
-        BlogApp {
+    pub fn new(
+        addr: String,
+        #[wasm_bindgen(unchecked_param_type = "BlogAppOptions | undefined")] options: JsValue,
+    ) -> Result<BlogApp, JsValue> {
+        let options: BlogAppOptions = if options.is_undefined() || options.is_null() {
+            BlogAppOptions::default()
+        } else {
+            swb::from_value(options).map_err(to_js_error)?
+        };
+
+        let auth = Rc::new(AuthState::new(
+            TokenStore::new(options.storage),
+            options.cookie_session,
+        ));
+        *auth.token.borrow_mut() = auth.store.get(TOKEN_KEY).unwrap_or(None);
+        auth.schedule_expiry();
+
+        let sync = Rc::new(SyncState::default());
+        let api_base = join_url(&addr, &options.api_prefix);
+
+        Ok(BlogApp {
+            storage_listener: auth::watch_storage(&auth),
+            online_listener: offline::watch_online(api_base.clone(), &auth, &sync),
             server_addr: addr,
-            token,
-        }
+            api_base,
+            sync,
+            optimistic: Rc::default(),
+            auth,
+            refreshing: Cell::new(false),
+            comment_counts: RefCell::new(HashMap::new()),
+            comment_posts: RefCell::new(HashMap::new()),
+            comment_count_listener: RefCell::new(None),
+            cache: Rc::new(RequestCache::new(options.cache_ttl_ms)),
+        })
     }
 
-    /// Registers a new user.
-    #[wasm_bindgen]
+    /// Registers a new user; log in afterwards to get a token.
+    #[wasm_bindgen(unchecked_return_type = "RegisteredUser")]
     pub async fn register(
         &mut self,
         username: String,
@@ -166,7 +625,7 @@ impl BlogApp {
             password,
         };
 
-        let url = self.url("/register");
+        let url = self.url("/public/auth/register");
 
         let resp = Request::post(&url)
             .header("Content-Type", "application/json")
@@ -174,165 +633,780 @@ impl BlogApp {
             .map_err(to_js_error)?
             .send()
             .await
-            .map_err(to_js_error)?;
-
-        let status = resp.status();
-        let text = resp.text().await.map_err(to_js_error)?;
-        if status < 200 || status >= 300 {
-            return Err(JsValue::from_str(&format!(
-                "Register failed ({}): {}",
-                status, text
-            )));
-        }
+            .map_err(network_error)?;
 
-        let json: Value = serde_json::from_str(&text).map_err(to_js_error)?;
-        self.extract_and_store_token(&json)?;
-        swb::to_value(&json).map_err(to_js_error)
+        let user: RegisteredUser = parse_response(resp, "Register failed").await?;
+        to_js(&user)
     }
 
     /// Authenticates a user and stores the JWT token.
-    #[wasm_bindgen]
+    ///
+    /// With `options.cookieSession` the server keeps the tokens in cookies
+    /// and this resolves to the session's CSRF token instead.
+    #[wasm_bindgen(unchecked_return_type = "AuthTokens | CookieSession")]
     pub async fn login(
         &mut self,
         email: String,
         password: String,
     ) -> Result<JsValue, JsValue> {
         let body = LoginRequest { email, password };
-        let url = self.url("/api/public/auth/login");
+        let url = self.url("/public/auth/login");
+        let mut request = Request::post(&url).header("Content-Type", "application/json");
+        if self.auth.cookie_session {
+            request = request
+                .query([("mode", "cookie")])
+                .credentials(RequestCredentials::Include);
+        }
 
-        let resp = Request::post(&url)
-            .header("Content-Type", "application/json")
+        let resp = request
             .json(&body)
             .map_err(to_js_error)?
             .send()
             .await
-            .map_err(to_js_error)?;
-
-        let status = resp.status();
-        let text = resp.text().await.map_err(to_js_error)?;
-        if status < 200 || status >= 300 {
-            return Err(JsValue::from_str(&format!(
-                "Login failed ({}): {}",
-                status, text
-            )));
-        }
+            .map_err(network_error)?;
 
-        let json: Value = serde_json::from_str(&text).map_err(to_js_error)?;
-        self.extract_and_store_token(&json)?;
-        swb::to_value(&json).map_err(to_js_error)
+        let result = if self.auth.cookie_session {
+            let session: CookieSession = parse_response(resp, "Login failed").await?;
+            self.store_cookie_session(&session)?;
+            to_js(&session)
+        } else {
+            let tokens: AuthTokens = parse_response(resp, "Login failed").await?;
+            self.store_tokens(&tokens)?;
+            to_js(&tokens)
+        };
+        self.auth.emit(AuthChange::Login, false);
+        result
     }
 
     /// Logs out the current user.
+    ///
+    /// The session is also ended on the server, without waiting for it.
     #[wasm_bindgen]
     pub fn logout(&mut self) -> Result<(), JsValue> {
-        self.token = None;
-        remove_token_from_storage()
+        self.revoke_session();
+        self.clear_tokens()?;
+        self.auth.emit(AuthChange::Logout, false);
+        Ok(())
     }
 
-    /// Loads posts of the authenticated user.
-    #[wasm_bindgen(js_name = "loadPosts")]
-    pub async fn load_posts(&self) -> Result<JsValue, JsValue> {
-        let token = self
-            .get_current_token()?
-            .ok_or_else(|| JsValue::from_str("Not authenticated"))?;
-
-        let url = self.url("/api/protected/posts");
+    /// Loads the account settings of the logged-in user.
+    #[wasm_bindgen(js_name = "getProfile", unchecked_return_type = "Profile")]
+    pub async fn get_profile(&self) -> Result<JsValue, JsValue> {
+        let token = self.auth_token().await?;
 
-        let resp = Request::get(&url)
-            .header("Authorization", &format!("Bearer {}", token))
+        let resp = token.apply(Request::get(&self.url("/protected/profile")))
             .send()
             .await
-            .map_err(to_js_error)?;
-
-        let status = resp.status();
-        let text = resp.text().await.map_err(to_js_error)?;
-        if status < 200 || status >= 300 {
-            return Err(JsValue::from_str(&format!(
-                "Load posts failed ({}): {}",
-                status, text
-            )));
-        }
+            .map_err(network_error)?;
 
-        let posts: Vec<Post> = serde_json::from_str(&text).map_err(to_js_error)?;
-        swb::to_value(&posts).map_err(to_js_error)
+        let profile: Profile = parse_response(resp, "Load profile failed").await?;
+        to_js(&profile)
     }
 
-    /// Creates a new post.
-    #[wasm_bindgen(js_name = "createPost")]
-    pub async fn create_post(
+    /// Changes the username, email or bio of the logged-in user.
+    ///
+    /// Only the fields present in `update` change. Resolves to the updated profile.
+    #[wasm_bindgen(js_name = "updateProfile", unchecked_return_type = "Profile")]
+    pub async fn update_profile(
         &self,
-        title: String,
-        content: String,
+        #[wasm_bindgen(unchecked_param_type = "ProfileUpdate")] update: JsValue,
     ) -> Result<JsValue, JsValue> {
-        let token = self
-            .get_current_token()?
-            .ok_or_else(|| JsValue::from_str("Not authenticated"))?;
+        let update: ProfileUpdate = swb::from_value(update).map_err(to_js_error)?;
+        let token = self.auth_token().await?;
+
+        let resp = token.apply(Request::put(&self.url("/protected/profile")))
+            .header("Content-Type", "application/json")
+            .json(&update)
+            .map_err(to_js_error)?
+            .send()
+            .await
+            .map_err(network_error)?;
 
-        let body = PostPayload { title, content };
-        let url = self.url("/api/protected/posts");
+        let profile: Profile = parse_response(resp, "Update profile failed").await?;
+        to_js(&profile)
+    }
 
-        let resp = Request::post(&url)
+    /// Changes the password of the logged-in user; the current one must match.
+    ///
+    /// The session stays valid afterwards.
+    #[wasm_bindgen(js_name = "changePassword")]
+    pub async fn change_password(
+        &self,
+        #[wasm_bindgen(js_name = "currentPassword")] current_password: String,
+        #[wasm_bindgen(js_name = "newPassword")] new_password: String,
+    ) -> Result<(), JsValue> {
+        let token = self.auth_token().await?;
+        let body = PasswordChange {
+            current_password,
+            new_password,
+        };
+
+        let resp = token.apply(Request::put(&self.url("/protected/profile/password")))
             .header("Content-Type", "application/json")
-            .header("Authorization", &format!("Bearer {}", token))
             .json(&body)
             .map_err(to_js_error)?
             .send()
             .await
-            .map_err(to_js_error)?;
+            .map_err(network_error)?;
+
+        check_response(resp, "Change password failed").await
+    }
+
+    /// Loads a page of the authenticated user's posts, newest first.
+    ///
+    /// Resolves to `{ items, total, limit, offset, has_more, next_cursor }`; `limit`
+    /// defaults to 20 (the server caps it at 100) and `offset` to 0.
+    #[wasm_bindgen(js_name = "loadPosts", unchecked_return_type = "Page<Post>")]
+    pub async fn load_posts(
+        &self,
+        limit: Option<u32>,
+        offset: Option<u32>,
+        signal: Option<AbortSignal>,
+    ) -> Result<JsValue, JsValue> {
+        let token = self.auth_token().await?;
+
+        let page: Page<Post> = self
+            .fetch_page(
+                "/protected/posts",
+                Some(&token),
+                &[],
+                limit.unwrap_or(DEFAULT_PAGE_SIZE),
+                offset.unwrap_or(0),
+                signal.as_ref(),
+            )
+            .await?;
+        to_js(&page)
+    }
+
+    /// Loads a page of the public feed: posts of all authors, newest first.
+    ///
+    /// `page` is 1-based and defaults to 1; pages hold 20 posts. Resolves to
+    /// the same shape as `loadPosts` and needs no login.
+    #[wasm_bindgen(js_name = "loadPublicPosts", unchecked_return_type = "Page<Post>")]
+    pub async fn load_public_posts(&self, page: Option<u32>) -> Result<JsValue, JsValue> {
+        let page: Page<Post> = self
+            .fetch_page(
+                "/public/posts",
+                None,
+                &[],
+                DEFAULT_PAGE_SIZE,
+                page_offset(page),
+                None,
+            )
+            .await?;
+        to_js(&page)
+    }
+
+    /// Loads a page of the public feed limited to posts carrying `tag`, for
+    /// tag archives that scroll endlessly.
+    ///
+    /// Without `cursor` the first page loads; passing the `next_cursor` of a
+    /// page loads the one after it, until a page comes without one. Needs no
+    /// login.
+    #[wasm_bindgen(js_name = "loadPostsByTag", unchecked_return_type = "Page<Post>")]
+    pub async fn load_posts_by_tag(
+        &self,
+        tag: String,
+        cursor: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let cursor = match cursor {
+            Some(cursor) => PageCursor::decode(&cursor)?,
+            None => PageCursor {
+                limit: DEFAULT_PAGE_SIZE,
+                offset: 0,
+            },
+        };
+
+        let page: Page<Post> = self
+            .fetch_page(
+                "/public/posts",
+                None,
+                &[("tag", &tag)],
+                cursor.limit,
+                cursor.offset,
+                None,
+            )
+            .await?;
+        to_js(&page)
+    }
+
+    /// Searches posts of all authors by title and content, best matches first.
+    ///
+    /// `query` accepts web-search syntax (`"exact phrase"`, `or`, `-word`);
+    /// `page` is 1-based. Each result's `snippet` is escaped HTML with the
+    /// matches wrapped in `<mark>`. Needs no login.
+    #[wasm_bindgen(js_name = "searchPosts", unchecked_return_type = "Page<SearchResult>")]
+    pub async fn search_posts(
+        &self,
+        query: String,
+        page: Option<u32>,
+        signal: Option<AbortSignal>,
+    ) -> Result<JsValue, JsValue> {
+        let page: Page<SearchResult> = self
+            .fetch_page(
+                "/public/posts/search",
+                None,
+                &[("q", &query)],
+                DEFAULT_PAGE_SIZE,
+                page_offset(page),
+                signal.as_ref(),
+            )
+            .await?;
+        to_js(&page)
+    }
+
+    /// Loads a single post by its ID; needs no login.
+    #[wasm_bindgen(js_name = "getPost", unchecked_return_type = "Post")]
+    pub async fn get_post(&self, id: String) -> Result<JsValue, JsValue> {
+        self.fetch_post(&format!("/public/posts/{}", id)).await
+    }
+
+    /// Loads a single post by its slug, e.g. for `/p/<slug>` permalinks; needs no login.
+    #[wasm_bindgen(js_name = "getPostBySlug", unchecked_return_type = "Post")]
+    pub async fn get_post_by_slug(&self, slug: String) -> Result<JsValue, JsValue> {
+        let slug = String::from(js_sys::encode_uri_component(&slug));
+        self.fetch_post(&format!("/public/posts/slug/{}", slug)).await
+    }
+
+    /// Creates a new post.
+    ///
+    /// When the network is down the post is queued in IndexedDB instead and
+    /// the queued change is returned; `sync` sends it later.
+    #[wasm_bindgen(js_name = "createPost", unchecked_return_type = "Post | PendingChange")]
+    pub async fn create_post(
+        &self,
+        title: String,
+        content: String,
+    ) -> Result<JsValue, JsValue> {
+        let body = PostRequest {
+            title,
+            content,
+            ..Default::default()
+        };
+        let url = self.url("/protected/posts");
 
-        BlogApp::response_to_jsvalue(resp).await
+        self.save_post(Request::post(&url), None, body, "Create post failed")
+            .await
     }
 
     /// Updates an existing post.
-    #[wasm_bindgen(js_name = "updatePost")]
+    ///
+    /// Pass the post's `updated_at` as `expectedUpdatedAt` to reject the edit
+    /// with a `409` if someone changed the post meanwhile. Offline edits are
+    /// queued like in `createPost`.
+    #[wasm_bindgen(js_name = "updatePost", unchecked_return_type = "Post | PendingChange")]
     pub async fn update_post(
         &self,
         id: String,
         title: String,
         content: String,
+        #[wasm_bindgen(js_name = "expectedUpdatedAt")] expected_updated_at: Option<String>,
     ) -> Result<JsValue, JsValue> {
-        let token = self
-            .get_current_token()?
-            .ok_or_else(|| JsValue::from_str("Not authenticated"))?;
-
-        let body = PostPayload { title, content };
-        let url = self.url(&format!("/api/protected/posts/{}", id));
+        let post_id = parse_id(&id)?;
+        let body = PostRequest {
+            title,
+            content,
+            expected_updated_at: expected_updated_at.as_deref().map(parse_timestamp).transpose()?,
+            ..Default::default()
+        };
+        let url = self.url(&format!("/protected/posts/{}", id));
 
-        let resp = Request::put(&url)
-            .header("Content-Type", "application/json")
-            .header("Authorization", &format!("Bearer {}", token))
-            .json(&body)
-            .map_err(to_js_error)?
-            .send()
+        self.save_post(Request::put(&url), Some(post_id), body, "Update post failed")
             .await
-            .map_err(to_js_error)?;
+    }
+
+    /// Lists post changes queued while offline, oldest first.
+    #[wasm_bindgen(js_name = "pendingChanges", unchecked_return_type = "PendingChange[]")]
+    pub async fn pending_changes(&self) -> Result<JsValue, JsValue> {
+        to_js(&offline::pending().await?)
+    }
+
+    /// Sends queued changes to the server.
+    ///
+    /// Runs automatically when the browser comes back online; call it
+    /// directly to retry, e.g. on startup.
+    #[wasm_bindgen(unchecked_return_type = "SyncReport")]
+    pub async fn sync(&self) -> Result<JsValue, JsValue> {
+        let token = self.auth_token().await?;
+        let report = self.sync.run(&self.api_base, &token).await?;
+        self.cache.clear();
+        to_js(&report)
+    }
+
+    /// Drops a queued change, e.g. after resolving a sync conflict by hand.
+    #[wasm_bindgen(js_name = "discardChange")]
+    pub async fn discard_change(&self, id: u32) -> Result<(), JsValue> {
+        offline::remove(id).await
+    }
+
+    /// Drops cached list responses so the next load hits the server.
+    ///
+    /// With a `path` such as `"/public/posts"` only lists under it are
+    /// dropped; without one the whole cache is. Changes made through this
+    /// client invalidate the cache by themselves.
+    pub fn invalidate(&self, path: Option<String>) {
+        match path {
+            Some(path) => self.cache.invalidate(&self.url(&path)),
+            None => self.cache.clear(),
+        }
+    }
+
+    /// Saves an unsent draft in IndexedDB under a caller-chosen `localId`,
+    /// replacing the previous version; call it as the writer types.
+    #[wasm_bindgen(js_name = "saveDraft", unchecked_return_type = "Draft")]
+    pub async fn save_draft(
+        &self,
+        local_id: String,
+        title: String,
+        content: String,
+    ) -> Result<JsValue, JsValue> {
+        let draft = Draft {
+            local_id,
+            title,
+            content,
+            saved_at: js_sys::Date::now(),
+        };
+        drafts::save(&draft).await?;
+        to_js(&draft)
+    }
+
+    /// Lists stored drafts, most recently saved first.
+    #[wasm_bindgen(js_name = "listDrafts", unchecked_return_type = "Draft[]")]
+    pub async fn list_drafts(&self) -> Result<JsValue, JsValue> {
+        to_js(&drafts::list().await?)
+    }
+
+    /// Publishes a draft through `createPost` and deletes it once the post
+    /// is created or queued; the draft is kept if that fails.
+    #[wasm_bindgen(js_name = "promoteDraft", unchecked_return_type = "Post | PendingChange")]
+    pub async fn promote_draft(&self, local_id: String) -> Result<JsValue, JsValue> {
+        let draft = drafts::get(&local_id).await?.ok_or_else(|| {
+            BlogError::new(format!("Draft not found: {}", local_id)).with_code(ErrorCode::NotFound)
+        })?;
+
+        let post = self.create_post(draft.title, draft.content).await?;
+        drafts::remove(&local_id).await?;
+        Ok(post)
+    }
+
+    /// Deletes a draft without publishing it.
+    #[wasm_bindgen(js_name = "discardDraft")]
+    pub async fn discard_draft(&self, local_id: String) -> Result<(), JsValue> {
+        drafts::remove(&local_id).await
+    }
+
+    /// Registers a callback receiving the report of every sync, including
+    /// automatic ones; pass `undefined` to remove it.
+    #[wasm_bindgen(js_name = "onSync")]
+    pub fn on_sync(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "((report: SyncReport) => void) | undefined")]
+        callback: Option<js_sys::Function>,
+    ) {
+        self.sync.set_listener(callback);
+    }
+
+    /// Creates a post without waiting for the server.
+    ///
+    /// Resolves at once to a provisional post with a temporary random ID;
+    /// `onOptimistic` later reports it confirmed (with the real post),
+    /// queued while offline, or rolled back.
+    #[wasm_bindgen(js_name = "createPostOptimistic", unchecked_return_type = "ProvisionalPost")]
+    pub async fn create_post_optimistic(
+        &self,
+        title: String,
+        content: String,
+    ) -> Result<JsValue, JsValue> {
+        let token = self.auth_token().await?;
 
-        BlogApp::response_to_jsvalue(resp).await
+        let now = Utc::now();
+        let provisional = ProvisionalPost {
+            post: Post {
+                id: Uuid::new_v4(),
+                author_id: token
+                    .subject()
+                    .and_then(|sub| Uuid::parse_str(&sub).ok())
+                    .unwrap_or_default(),
+                author: None,
+                title: title.clone(),
+                slug: String::new(),
+                content: content.clone(),
+                tags: Vec::new(),
+                visibility: Default::default(),
+                created_at: now,
+                updated_at: now,
+                deleted_at: None,
+                locale: None,
+            },
+            provisional: true,
+        };
+        let body = PostRequest {
+            title,
+            content,
+            ..Default::default()
+        };
+        let url = self.url("/protected/posts");
+
+        self.save_post_optimistic(
+            Request::post(&url),
+            token,
+            body,
+            provisional,
+            None,
+            "Create post failed",
+        )
+    }
+
+    /// Updates a post without waiting for the server.
+    ///
+    /// Takes the post as last loaded and resolves at once to its edited,
+    /// provisional version. The edit is rejected if the post changed on the
+    /// server meanwhile; the rollback event then carries `previous`.
+    #[wasm_bindgen(js_name = "updatePostOptimistic", unchecked_return_type = "ProvisionalPost")]
+    pub async fn update_post_optimistic(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "Post")] post: JsValue,
+        title: String,
+        content: String,
+    ) -> Result<JsValue, JsValue> {
+        let previous: Post = swb::from_value(post).map_err(to_js_error)?;
+        let token = self.auth_token().await?;
+
+        let provisional = ProvisionalPost {
+            post: Post {
+                title: title.clone(),
+                content: content.clone(),
+                updated_at: Utc::now(),
+                ..previous.clone()
+            },
+            provisional: true,
+        };
+        let body = PostRequest {
+            title,
+            content,
+            expected_updated_at: Some(previous.updated_at),
+            ..Default::default()
+        };
+        let url = self.url(&format!("/protected/posts/{}", previous.id));
+
+        self.save_post_optimistic(
+            Request::put(&url),
+            token,
+            body,
+            provisional,
+            Some(previous),
+            "Update post failed",
+        )
+    }
+
+    /// Registers a callback told how every optimistic change ended; pass
+    /// `undefined` to remove it.
+    #[wasm_bindgen(js_name = "onOptimistic")]
+    pub fn on_optimistic(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "((event: OptimisticEvent) => void) | undefined")]
+        callback: Option<js_sys::Function>,
+    ) {
+        self.optimistic.set_listener(callback);
     }
 
     /// Deletes a post by its ID.
     #[wasm_bindgen(js_name = "deletePost")]
-    pub async fn delete_post(&self, id: String) -> Result<JsValue, JsValue> {
-        let token = self
-            .get_current_token()?
-            .ok_or_else(|| JsValue::from_str("Not authenticated"))?;
+    pub async fn delete_post(&self, id: String) -> Result<(), JsValue> {
+        let token = self.auth_token().await?;
 
-        let url = self.url(&format!("/api/protected/posts/{}", id));
+        let url = self.url(&format!("/protected/posts/{}", id));
 
-        let resp = Request::delete(&url)
-            .header("Authorization", &format!("Bearer {}", token))
+        let resp = token.apply(Request::delete(&url))
             .send()
             .await
-            .map_err(to_js_error)?;
+            .map_err(network_error)?;
+        self.cache.clear();
+
+        check_response(resp, "Delete post failed").await
+    }
+
+    /// Likes a post and resolves to its updated counts; liking twice is harmless.
+    #[wasm_bindgen(js_name = "likePost", unchecked_return_type = "Reactions")]
+    pub async fn like_post(&self, id: String) -> Result<JsValue, JsValue> {
+        let url = self.url(&format!("/protected/posts/{}/like", id));
+        self.react(Request::put(&url), "Like failed").await
+    }
+
+    /// Removes the user's like and resolves to the post's updated counts.
+    #[wasm_bindgen(js_name = "unlikePost", unchecked_return_type = "Reactions")]
+    pub async fn unlike_post(&self, id: String) -> Result<JsValue, JsValue> {
+        let url = self.url(&format!("/protected/posts/{}/like", id));
+        self.react(Request::delete(&url), "Unlike failed").await
+    }
+
+    /// Bookmarks a post and resolves to its updated counts.
+    #[wasm_bindgen(js_name = "bookmarkPost", unchecked_return_type = "Reactions")]
+    pub async fn bookmark_post(&self, id: String) -> Result<JsValue, JsValue> {
+        let url = self.url(&format!("/protected/posts/{}/bookmark", id));
+        self.react(Request::put(&url), "Bookmark failed").await
+    }
+
+    /// Removes a bookmark and resolves to the post's updated counts.
+    #[wasm_bindgen(js_name = "unbookmarkPost", unchecked_return_type = "Reactions")]
+    pub async fn unbookmark_post(&self, id: String) -> Result<JsValue, JsValue> {
+        let url = self.url(&format!("/protected/posts/{}/bookmark", id));
+        self.react(Request::delete(&url), "Removing bookmark failed").await
+    }
+
+    /// Loads the like and bookmark counts of a post.
+    #[wasm_bindgen(js_name = "getReactions", unchecked_return_type = "Reactions")]
+    pub async fn get_reactions(&self, id: String) -> Result<JsValue, JsValue> {
+        let url = self.url(&format!("/protected/posts/{}/reactions", id));
+        self.react(Request::get(&url), "Loading reactions failed").await
+    }
+
+    /// Loads a 1-based page of the user's bookmarks, most recently bookmarked first.
+    #[wasm_bindgen(js_name = "loadBookmarks", unchecked_return_type = "Page<Post>")]
+    pub async fn load_bookmarks(&self, page: Option<u32>) -> Result<JsValue, JsValue> {
+        let token = self.auth_token().await?;
+
+        let page: Page<Post> = self
+            .fetch_page(
+                "/protected/bookmarks",
+                Some(&token),
+                &[],
+                DEFAULT_PAGE_SIZE,
+                page_offset(page),
+                None,
+            )
+            .await?;
+        to_js(&page)
+    }
 
-        BlogApp::response_to_jsvalue(resp).await
+    /// Uploads an image for embedding in post content.
+    ///
+    /// `onProgress` is called with `(loaded, total)` bytes as the file is
+    /// sent. Resolves to the stored media; its `url` and `variants` are absolute.
+    #[wasm_bindgen(js_name = "uploadMedia", unchecked_return_type = "Media")]
+    pub async fn upload_media(
+        &self,
+        file: File,
+        #[wasm_bindgen(unchecked_param_type = "((loaded: number, total: number) => void) | undefined")]
+        on_progress: Option<js_sys::Function>,
+        signal: Option<AbortSignal>,
+    ) -> Result<JsValue, JsValue> {
+        let token = self.auth_token().await?;
+
+        let form = FormData::new()?;
+        form.append_with_blob_and_filename("file", &file, &file.name())?;
+
+        let url = self.url("/protected/media");
+        let resp = upload::post_form(&url, &token, &form, on_progress, signal.as_ref()).await?;
+        if !(200..300).contains(&resp.status) {
+            return Err(BlogError::http(resp.status, "Upload failed", &resp.body)
+                .with_request_id(resp.request_id)
+                .into());
+        }
+
+        let mut media: Media = serde_json::from_str(&resp.body).map_err(to_js_error)?;
+        media.url = join_url(&self.server_addr, &media.url);
+        for url in [
+            &mut media.variants.thumbnail,
+            &mut media.variants.medium,
+            &mut media.variants.original,
+        ] {
+            *url = join_url(&self.server_addr, url);
+        }
+        to_js(&media)
+    }
+
+    /// Loads a page of comments on a post, oldest first.
+    ///
+    /// Resolves to `{ items, total, limit, offset, has_more, next_cursor }` like
+    /// `loadPosts`; `total` also becomes the post's `commentCount`.
+    #[wasm_bindgen(js_name = "loadComments", unchecked_return_type = "Page<Comment>")]
+    pub async fn load_comments(
+        &self,
+        post_id: String,
+        limit: Option<u32>,
+        offset: Option<u32>,
+    ) -> Result<JsValue, JsValue> {
+        let token = self.auth_token().await?;
+
+        let page: Page<Comment> = self
+            .fetch_page(
+                &format!("/protected/posts/{}/comments", post_id),
+                Some(&token),
+                &[],
+                limit.unwrap_or(DEFAULT_PAGE_SIZE),
+                offset.unwrap_or(0),
+                None,
+            )
+            .await?;
+
+        {
+            let mut comment_posts = self.comment_posts.borrow_mut();
+            for comment in &page.items {
+                comment_posts.insert(comment.id.to_string(), comment.post_id.to_string());
+            }
+        }
+        if let Some(total) = page.total {
+            self.set_comment_count(&post_id, total);
+        }
+
+        to_js(&page)
+    }
+
+    /// Adds a comment to a post, optionally as a reply to `parentId`.
+    ///
+    /// The post's known comment count is bumped before the request is sent
+    /// and restored if it fails. Resolves to the created comment.
+    #[wasm_bindgen(js_name = "addComment", unchecked_return_type = "Comment")]
+    pub async fn add_comment(
+        &self,
+        post_id: String,
+        text: String,
+        parent_id: Option<String>,
+    ) -> Result<JsValue, JsValue> {
+        let token = self.auth_token().await?;
+
+        let body = CommentRequest {
+            content: text,
+            parent_id: parent_id.as_deref().map(parse_id).transpose()?,
+            ..Default::default()
+        };
+        let url = self.url(&format!("/protected/posts/{}/comments", post_id));
+
+        self.adjust_comment_count(&post_id, 1);
+
+        let result = async {
+            let resp = token.apply(Request::post(&url))
+                .header("Content-Type", "application/json")
+                .json(&body)
+                .map_err(to_js_error)?
+                .send()
+                .await
+                .map_err(network_error)?;
+
+            parse_response::<Comment>(resp, "Add comment failed").await
+        }
+        .await;
+
+        match result {
+            Ok(comment) => {
+                self.cache.clear();
+                self.comment_posts
+                    .borrow_mut()
+                    .insert(comment.id.to_string(), comment.post_id.to_string());
+                to_js(&comment)
+            }
+            Err(e) => {
+                self.adjust_comment_count(&post_id, -1);
+                Err(e)
+            }
+        }
+    }
+
+    /// Deletes a comment by its ID.
+    ///
+    /// If the comment was seen through `loadComments` or `addComment`, its
+    /// post's count drops before the request is sent and is restored on failure.
+    #[wasm_bindgen(js_name = "deleteComment")]
+    pub async fn delete_comment(&self, id: String) -> Result<(), JsValue> {
+        let token = self.auth_token().await?;
+
+        let url = self.url(&format!("/protected/comments/{}", id));
+        let post_id = self.comment_posts.borrow().get(&id).cloned();
+
+        if let Some(post_id) = &post_id {
+            self.adjust_comment_count(post_id, -1);
+        }
+
+        let result = async {
+            let resp = token.apply(Request::delete(&url))
+                .send()
+                .await
+                .map_err(network_error)?;
+
+            check_response(resp, "Delete comment failed").await
+        }
+        .await;
+
+        match (result, post_id) {
+            (Ok(()), _) => {
+                self.cache.clear();
+                self.comment_posts.borrow_mut().remove(&id);
+                Ok(())
+            }
+            (Err(e), Some(post_id)) => {
+                self.adjust_comment_count(&post_id, 1);
+                Err(e)
+            }
+            (Err(e), None) => Err(e),
+        }
+    }
+
+    /// Returns the known comment count of a post, or `undefined` before
+    /// its comments have been loaded.
+    #[wasm_bindgen(js_name = "commentCount")]
+    pub fn comment_count(&self, post_id: String) -> Option<f64> {
+        self.comment_counts
+            .borrow()
+            .get(&post_id)
+            .map(|count| *count as f64)
+    }
+
+    /// Registers a callback invoked as `(postId, count)` whenever a known
+    /// comment count changes, including optimistic updates and rollbacks.
+    ///
+    /// Passing `undefined` removes the callback.
+    #[wasm_bindgen(js_name = "onCommentCountChange")]
+    pub fn on_comment_count_change(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "((postId: string, count: number) => void) | undefined")]
+        callback: Option<js_sys::Function>,
+    ) {
+        *self.comment_count_listener.borrow_mut() = callback;
+    }
+
+    /// Registers a callback invoked on login, logout, token refresh and
+    /// token expiry, including changes made in other tabs.
+    ///
+    /// The callback receives `{ kind, authenticated, expiresAt, external }`,
+    /// where `kind` is `"login"`, `"logout"`, `"refresh"` or `"expired"` and
+    /// `external` marks changes from another tab. Passing `undefined`
+    /// removes the callback.
+    #[wasm_bindgen(js_name = "onAuthChange")]
+    pub fn on_auth_change(
+        &self,
+        #[wasm_bindgen(unchecked_param_type = "((event: AuthEvent) => void) | undefined")]
+        callback: Option<js_sys::Function>,
+    ) {
+        self.auth.set_listener(callback);
     }
 
     /// Returns whether the user is authenticated.
+    ///
+    /// An expired token only counts while a refresh token can renew it.
     #[wasm_bindgen(js_name = "isAuthenticated")]
-    pub fn is_authenticated(&self) -> Result<JsValue, JsValue> {
-        let has =
-            self.token.is_some() || get_token_from_storage().unwrap_or(None).is_some();
-        Ok(JsValue::from_bool(has))
+    pub fn is_authenticated(&self) -> Result<bool, JsValue> {
+        let has = self.auth.token.borrow().is_some();
+        Ok(has && (!self.auth.expires_within(0) || self.auth.renewable()))
+    }
+
+    /// Returns when the current access token expires, in milliseconds since
+    /// the Unix epoch (ready for `new Date(...)`), or `undefined` if there
+    /// is no token or its expiry cannot be read.
+    #[wasm_bindgen(js_name = "tokenExpiresAt")]
+    pub fn token_expires_at(&self) -> Option<f64> {
+        self.auth.expires_at().map(|exp| exp as f64 * 1000.0)
+    }
+}
+
+impl Drop for BlogApp {
+    /// Detaches the cross-tab listener when JS frees the app.
+    fn drop(&mut self) {
+        if let Some(listener) = &self.storage_listener {
+            auth::unwatch_storage(listener);
+        }
+        if let Some(listener) = &self.online_listener {
+            offline::unwatch_online(listener);
+        }
     }
 }
@@ -0,0 +1,126 @@
+use std::collections::HashSet;
+
+use ammonia::Builder;
+use pulldown_cmark::{CowStr, Event, Options, Parser, Tag, html};
+use serde_wasm_bindgen as swb;
+use wasm_bindgen::prelude::*;
+
+use crate::error::to_js_error;
+use crate::models::RenderOptions;
+
+/// URL schemes links may use; anything else is dropped.
+const LINK_SCHEMES: &[&str] = &["http", "https", "mailto"];
+
+/// URL schemes images may use.
+const IMAGE_SCHEMES: &[&str] = &["http", "https"];
+
+/// Renders post Markdown to sanitized HTML.
+///
+/// CommonMark plus tables, strikethrough and task lists. Raw HTML in the
+/// source is kept only for tags on the allowlist, scripts and event handlers
+/// are stripped, and links or images with schemes such as `javascript:` lose
+/// their target, so the output is safe for `innerHTML`. `options.allowedTags`
+/// replaces the allowlist; `options.trusted` skips sanitization entirely.
+#[wasm_bindgen(js_name = "renderMarkdown")]
+pub fn render_markdown(
+    source: &str,
+    #[wasm_bindgen(unchecked_param_type = "RenderOptions | undefined")] options: JsValue,
+) -> Result<String, JsValue> {
+    let options: RenderOptions = if options.is_undefined() || options.is_null() {
+        RenderOptions::default()
+    } else {
+        swb::from_value(options).map_err(to_js_error)?
+    };
+
+    let parser_options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS;
+    let parser = Parser::new_ext(source, parser_options);
+
+    let mut out = String::with_capacity(source.len() * 3 / 2);
+    if options.trusted {
+        html::push_html(&mut out, parser);
+        return Ok(out);
+    }
+
+    html::push_html(&mut out, parser.map(sanitize));
+    Ok(cleaner(options.allowed_tags.as_deref()).clean(&out).to_string())
+}
+
+/// Builds the HTML cleaner, keeping `allowed_tags` or the default allowlist
+/// plus task list checkboxes.
+fn cleaner(allowed_tags: Option<&[String]>) -> Builder<'_> {
+    let mut builder = Builder::default();
+    builder
+        .url_schemes(LINK_SCHEMES.iter().copied().collect())
+        .link_rel(Some("noopener noreferrer nofollow"));
+
+    match allowed_tags {
+        Some(tags) => {
+            let tags: HashSet<&str> = tags
+                .iter()
+                .map(String::as_str)
+                .filter(|tag| !["script", "style"].contains(tag))
+                .collect();
+            builder.tags(tags);
+        }
+        None => {
+            builder
+                .add_tags(["input"])
+                .add_tag_attributes("input", ["type", "checked", "disabled"]);
+        }
+    }
+
+    builder
+}
+
+/// Neutralizes links and images with unsafe URL schemes.
+fn sanitize(event: Event<'_>) -> Event<'_> {
+    match event {
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Link {
+            link_type,
+            dest_url: safe_url(dest_url, LINK_SCHEMES),
+            title,
+            id,
+        }),
+        Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Image {
+            link_type,
+            dest_url: safe_url(dest_url, IMAGE_SCHEMES),
+            title,
+            id,
+        }),
+        event => event,
+    }
+}
+
+/// Returns `url` if it is relative or uses one of `schemes`, otherwise an empty URL.
+fn safe_url<'a>(url: CowStr<'a>, schemes: &[&str]) -> CowStr<'a> {
+    // Browsers ignore whitespace and control characters inside a scheme,
+    // so `java\tscript:` must be caught too.
+    let normalized: String = url
+        .chars()
+        .filter(|c| !c.is_ascii_whitespace() && !c.is_control())
+        .collect();
+
+    let scheme = normalized
+        .split_once(':')
+        .map(|(scheme, _)| scheme)
+        .filter(|scheme| !scheme.contains(['/', '?', '#']));
+
+    match scheme {
+        Some(scheme) if !schemes.iter().any(|s| s.eq_ignore_ascii_case(scheme)) => {
+            CowStr::Borrowed("")
+        }
+        _ => url,
+    }
+}
@@ -0,0 +1,206 @@
+use blog_core::post::PostRequest;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tsify::Tsify;
+use uuid::Uuid;
+
+use crate::error::BlogError;
+use crate::token_store::TokenStorage;
+
+pub use blog_core::auth::{AuthTokens, CookieSession, Profile, ProfileUpdate};
+pub use blog_core::comment::Comment;
+pub use blog_core::media::Media;
+pub use blog_core::post::{Post, SearchResult};
+
+/// Options accepted by the `BlogApp` constructor.
+#[derive(Debug, Deserialize, Tsify)]
+pub struct BlogAppOptions {
+    /// Token storage strategy; `local` when omitted.
+    #[serde(default)]
+    #[tsify(optional)]
+    pub storage: TokenStorage,
+
+    /// Path the API is mounted under, e.g. `/blog/api/v1`; `/api/v1` when omitted.
+    #[serde(default = "default_api_prefix", rename = "apiPrefix")]
+    #[tsify(optional)]
+    pub api_prefix: String,
+
+    /// How long cached list responses stay fresh, in milliseconds; 30 s when omitted.
+    #[serde(default = "default_cache_ttl_ms", rename = "cacheTtlMs")]
+    #[tsify(optional)]
+    pub cache_ttl_ms: f64,
+
+    /// Whether the server keeps the session in `HttpOnly` cookies; `false` when omitted.
+    #[serde(default, rename = "cookieSession")]
+    #[tsify(optional)]
+    pub cookie_session: bool,
+}
+
+impl Default for BlogAppOptions {
+    fn default() -> Self {
+        Self {
+            storage: TokenStorage::default(),
+            api_prefix: default_api_prefix(),
+            cache_ttl_ms: default_cache_ttl_ms(),
+            cookie_session: false,
+        }
+    }
+}
+
+/// API prefix the server mounts its routes under by default.
+fn default_api_prefix() -> String {
+    "/api/v1".into()
+}
+
+/// Default freshness window of cached list responses.
+fn default_cache_ttl_ms() -> f64 {
+    30_000.0
+}
+
+/// Options accepted by `renderMarkdown`.
+#[derive(Debug, Default, Deserialize, Tsify)]
+pub struct RenderOptions {
+    /// HTML tags kept in the output; a built-in allowlist when omitted.
+    #[serde(default, rename = "allowedTags")]
+    #[tsify(optional)]
+    pub allowed_tags: Option<Vec<String>>,
+
+    /// Skips sanitization; only for content written by trusted administrators.
+    #[serde(default)]
+    #[tsify(optional)]
+    pub trusted: bool,
+}
+
+/// Account created by `register`.
+#[derive(Debug, Serialize, Deserialize, Tsify)]
+pub struct RegisteredUser {
+    pub user_id: String,
+    pub username: String,
+    pub email: String,
+}
+
+/// Post returned by the optimistic methods before the server confirms it.
+#[derive(Debug, Clone, Serialize, Deserialize, Tsify)]
+pub struct ProvisionalPost {
+    #[serde(flatten)]
+    pub post: Post,
+    /// Always `true`; the server has not stored this version yet.
+    pub provisional: bool,
+}
+
+/// Kind of change waiting in the offline queue.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, Tsify)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeKind {
+    Create,
+    Update,
+}
+
+/// Post change stored while offline, replayed by `sync`.
+#[derive(Debug, Serialize, Deserialize, Tsify)]
+pub struct PendingChange {
+    /// Queue ID, assigned when the change is stored.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[tsify(optional)]
+    pub id: Option<u32>,
+    pub kind: ChangeKind,
+    /// Edited post, for updates.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[tsify(optional, type = "string")]
+    pub post_id: Option<Uuid>,
+    pub title: String,
+    pub content: String,
+    /// `updated_at` of the post the edit was based on, if known.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[tsify(optional, type = "string")]
+    pub expected_updated_at: Option<DateTime<Utc>>,
+    /// Milliseconds since the Unix epoch.
+    pub queued_at: f64,
+}
+
+impl PendingChange {
+    /// Creates a queue entry for `payload`, which edits `post_id` if set.
+    pub(crate) fn new(post_id: Option<Uuid>, payload: PostRequest) -> Self {
+        Self {
+            id: None,
+            kind: match post_id {
+                Some(_) => ChangeKind::Update,
+                None => ChangeKind::Create,
+            },
+            post_id,
+            title: payload.title,
+            content: payload.content,
+            expected_updated_at: payload.expected_updated_at,
+            queued_at: js_sys::Date::now(),
+        }
+    }
+
+    /// Returns the request body that replays this change.
+    pub(crate) fn payload(&self) -> PostRequest {
+        PostRequest {
+            title: self.title.clone(),
+            content: self.content.clone(),
+            expected_updated_at: self.expected_updated_at,
+            ..Default::default()
+        }
+    }
+}
+
+/// Like and bookmark counts of a post, returned by every reaction change.
+#[derive(Debug, Serialize, Deserialize, Tsify)]
+pub struct Reactions {
+    pub post_id: String,
+    pub likes: u64,
+    pub bookmarks: u64,
+    /// Whether the current user liked the post.
+    pub liked: bool,
+    /// Whether the current user bookmarked the post.
+    pub bookmarked: bool,
+}
+
+/// Post draft kept in IndexedDB until it is promoted or discarded.
+#[derive(Debug, Serialize, Deserialize, Tsify)]
+pub struct Draft {
+    /// Caller-chosen ID, e.g. one per editor session.
+    pub local_id: String,
+    pub title: String,
+    pub content: String,
+    /// Milliseconds since the Unix epoch.
+    pub saved_at: f64,
+}
+
+/// Queued change the server rejected.
+#[derive(Debug, Serialize, Tsify)]
+pub struct SyncFailure {
+    pub change: PendingChange,
+    pub error: BlogError,
+}
+
+/// Outcome of replaying the offline queue.
+#[derive(Debug, Default, Serialize, Tsify)]
+pub struct SyncReport {
+    /// Posts as saved by the server.
+    pub synced: Vec<Post>,
+    /// Updates whose post changed on the server meanwhile; they stay queued
+    /// until discarded.
+    pub conflicts: Vec<PendingChange>,
+    /// Changes the server rejected; they are removed from the queue.
+    pub failed: Vec<SyncFailure>,
+    /// Changes still queued, including conflicts.
+    pub pending: u32,
+}
+
+/// One page of a list with the metadata needed to render paging controls.
+#[derive(Debug, Serialize, Deserialize, Tsify)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Total number of items, if the server reported it.
+    #[tsify(optional)]
+    pub total: Option<u64>,
+    pub limit: u32,
+    pub offset: u32,
+    pub has_more: bool,
+    /// Token loading the following page, when `has_more` is set.
+    #[tsify(optional)]
+    pub next_cursor: Option<String>,
+}
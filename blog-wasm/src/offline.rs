@@ -0,0 +1,187 @@
+use std::cell::{Cell, RefCell};
+use std::rc::{Rc, Weak};
+
+use gloo_net::http::{Request, Response};
+use js_sys::Function;
+use serde_wasm_bindgen as swb;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::spawn_local;
+use web_sys::{IdbTransactionMode, window};
+
+use crate::auth::{AuthState, Credentials};
+use crate::error::BlogError;
+use crate::models::{ChangeKind, PendingChange, Post, SyncFailure, SyncReport};
+use crate::{idb, join_url, parse_response, send_post, to_js};
+
+/// IndexedDB database holding the offline queue.
+const DB_NAME: &str = "blog_offline";
+
+/// Object store of queued changes, keyed by an auto-incremented `id`.
+const STORE: &str = "pending_changes";
+
+/// Returns whether the browser reports being offline.
+pub(crate) fn is_offline() -> bool {
+    window().is_some_and(|win| !win.navigator().on_line())
+}
+
+/// Stores a change for later and returns it with its queue ID.
+pub(crate) async fn enqueue(mut change: PendingChange) -> Result<PendingChange, JsValue> {
+    let db = idb::open(DB_NAME, STORE, "id").await?;
+    let store = idb::store(&db, STORE, IdbTransactionMode::Readwrite)?;
+
+    let key = idb::complete(&store.put(&to_js(&change)?)?).await?;
+    change.id = key.as_f64().map(|id| id as u32);
+    Ok(change)
+}
+
+/// Returns queued changes, oldest first.
+pub(crate) async fn pending() -> Result<Vec<PendingChange>, JsValue> {
+    let db = idb::open(DB_NAME, STORE, "id").await?;
+    let store = idb::store(&db, STORE, IdbTransactionMode::Readonly)?;
+
+    let all = idb::complete(&store.get_all()?).await?;
+    swb::from_value(all).map_err(|e| BlogError::new(e.to_string()).into())
+}
+
+/// Removes a change from the queue.
+pub(crate) async fn remove(id: u32) -> Result<(), JsValue> {
+    let db = idb::open(DB_NAME, STORE, "id").await?;
+    let store = idb::store(&db, STORE, IdbTransactionMode::Readwrite)?;
+
+    idb::complete(&store.delete(&JsValue::from(id))?).await?;
+    Ok(())
+}
+
+/// Whether a failed replay may succeed later and should stay queued.
+fn is_retryable(status: u16) -> bool {
+    matches!(status, 401 | 408 | 429) || status >= 500
+}
+
+/// Replays one queued change against the server.
+async fn replay(
+    base: &str,
+    token: &Credentials,
+    change: &PendingChange,
+) -> Result<Response, gloo_net::Error> {
+    match (&change.kind, &change.post_id) {
+        (ChangeKind::Update, Some(post_id)) => {
+            let url = join_url(base, &format!("/protected/posts/{}", post_id));
+            send_post(Request::put(&url), token, &change.payload()).await
+        }
+        _ => {
+            let url = join_url(base, "/protected/posts");
+            send_post(Request::post(&url), token, &change.payload()).await
+        }
+    }
+}
+
+/// Sends queued changes in order until the server becomes unreachable.
+///
+/// Conflicting updates stay queued for the application to resolve; changes
+/// the server rejects outright are dropped and reported as failed.
+async fn sync_queue(base: &str, token: &Credentials) -> Result<SyncReport, JsValue> {
+    let mut report = SyncReport::default();
+
+    for change in pending().await? {
+        let id = change.id.unwrap_or_default();
+        let Ok(resp) = replay(base, token, &change).await else {
+            break;
+        };
+
+        match resp.status() {
+            200..=299 => {
+                let post: Post = parse_response(resp, "Sync failed").await?;
+                remove(id).await?;
+                report.synced.push(post);
+            }
+            409 => report.conflicts.push(change),
+            status if is_retryable(status) => break,
+            _ => {
+                let text = resp.text().await.unwrap_or_default();
+                remove(id).await?;
+                report.failed.push(SyncFailure {
+                    change,
+                    error: BlogError::from_response(&resp, "Sync failed", &text),
+                });
+            }
+        }
+    }
+
+    report.pending = pending().await?.len() as u32;
+    Ok(report)
+}
+
+/// Offline queue state shared between `BlogApp` and the `online` handler.
+#[derive(Default)]
+pub(crate) struct SyncState {
+    /// Whether a sync is in flight, so only one runs at a time.
+    running: Cell<bool>,
+    /// Callback registered through `onSync`.
+    listener: RefCell<Option<Function>>,
+}
+
+impl SyncState {
+    /// Replaces the `onSync` callback; `None` removes it.
+    pub(crate) fn set_listener(&self, callback: Option<Function>) {
+        *self.listener.borrow_mut() = callback;
+    }
+
+    /// Syncs the queue and reports the outcome to the listener.
+    pub(crate) async fn run(&self, base: &str, token: &Credentials) -> Result<SyncReport, JsValue> {
+        if self.running.replace(true) {
+            return Err(BlogError::new("Sync already in progress").into());
+        }
+        let result = sync_queue(base, token).await;
+        self.running.set(false);
+
+        let report = result?;
+        let listener = self.listener.borrow().clone();
+        if let (Some(listener), Ok(value)) = (listener, to_js(&report)) {
+            let _ = listener.call1(&JsValue::NULL, &value);
+        }
+        Ok(report)
+    }
+}
+
+/// Syncs the queue whenever the browser comes back online.
+///
+/// Only a still-valid token is used; with an expired one the queue waits for
+/// an explicit `sync()`, which can refresh it. The returned closure must be
+/// kept alive and passed to [`unwatch_online`] once the app goes away.
+pub(crate) fn watch_online(
+    base: String,
+    auth: &Rc<AuthState>,
+    state: &Rc<SyncState>,
+) -> Option<Closure<dyn FnMut()>> {
+    let win = window()?;
+    let auth: Weak<AuthState> = Rc::downgrade(auth);
+    let state = Rc::downgrade(state);
+
+    let handler = Closure::<dyn FnMut()>::new(move || {
+        let (Some(auth), Some(state)) = (auth.upgrade(), state.upgrade()) else {
+            return;
+        };
+        let Some(token) = auth.credentials() else {
+            return;
+        };
+        if auth.expires_within(0) {
+            return;
+        }
+
+        let base = base.clone();
+        spawn_local(async move {
+            let _ = state.run(&base, &token).await;
+        });
+    });
+
+    win.add_event_listener_with_callback("online", handler.as_ref().unchecked_ref())
+        .ok()?;
+    Some(handler)
+}
+
+/// Removes a listener installed by [`watch_online`].
+pub(crate) fn unwatch_online(handler: &Closure<dyn FnMut()>) {
+    if let Some(win) = window() {
+        let _ = win.remove_event_listener_with_callback("online", handler.as_ref().unchecked_ref());
+    }
+}
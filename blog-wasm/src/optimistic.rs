@@ -0,0 +1,68 @@
+use std::cell::RefCell;
+
+use js_sys::Function;
+use serde::Serialize;
+use tsify::Tsify;
+use uuid::Uuid;
+use wasm_bindgen::prelude::*;
+
+use crate::error::BlogError;
+use crate::models::{PendingChange, Post};
+use crate::to_js;
+
+/// How an optimistic change ended.
+#[derive(Debug, Clone, Copy, Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub(crate) enum OptimisticOutcome {
+    /// The server accepted the change; `post` is the stored version.
+    Confirmed,
+    /// The browser was offline; `change` was queued for `sync`.
+    Queued,
+    /// The server rejected the change; `error` says why.
+    RolledBack,
+}
+
+/// Payload passed to `onOptimistic` callbacks.
+#[derive(Debug, Serialize, Tsify)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct OptimisticEvent {
+    pub kind: OptimisticOutcome,
+    /// ID of the provisional post; replace it with `post.id` on confirmation.
+    #[tsify(type = "string")]
+    pub provisional_id: Uuid,
+    /// Server version of the post, when confirmed.
+    #[tsify(optional)]
+    pub post: Option<Post>,
+    /// Queued change, when the browser was offline.
+    #[tsify(optional)]
+    pub change: Option<PendingChange>,
+    /// Post as it was before an optimistic update, to restore on rollback.
+    #[tsify(optional)]
+    pub previous: Option<Post>,
+    /// Why the server rejected the change.
+    #[tsify(optional)]
+    pub error: Option<BlogError>,
+}
+
+/// Optimistic change state shared with the requests it spawns.
+#[derive(Default)]
+pub(crate) struct OptimisticState {
+    /// Callback registered through `onOptimistic`.
+    listener: RefCell<Option<Function>>,
+}
+
+impl OptimisticState {
+    /// Replaces the `onOptimistic` callback; `None` removes it.
+    pub(crate) fn set_listener(&self, listener: Option<Function>) {
+        *self.listener.borrow_mut() = listener;
+    }
+
+    /// Notifies the listener about how a change ended.
+    pub(crate) fn emit(&self, event: &OptimisticEvent) {
+        // Cloned so the callback may replace itself without a borrow conflict.
+        let listener = self.listener.borrow().clone();
+        if let (Some(listener), Ok(event)) = (listener, to_js(event)) {
+            let _ = listener.call1(&JsValue::NULL, &event);
+        }
+    }
+}
@@ -0,0 +1,150 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use tsify::Tsify;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlDocument, Storage, window};
+
+use crate::error::BlogError;
+
+/// Lifetime of token cookies; the tokens themselves expire much sooner.
+const COOKIE_MAX_AGE_SECS: u32 = 30 * 24 * 60 * 60;
+
+/// Where `BlogApp` keeps its tokens.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize, Tsify)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenStorage {
+    /// `localStorage`: survives restarts and is shared between tabs.
+    #[default]
+    Local,
+    /// `sessionStorage`: private to the tab and cleared when it closes.
+    Session,
+    /// Process memory: gone on reload, never touches disk.
+    Memory,
+    /// First-party cookies, for setups where web storage is unavailable.
+    Cookie,
+}
+
+/// Token persistence behind one of the [`TokenStorage`] strategies.
+///
+/// All strategies share the same semantics: `get` returns what `save` last
+/// stored under a key, and `remove` of a missing key succeeds.
+pub(crate) struct TokenStore {
+    kind: TokenStorage,
+    /// Values held by the in-memory strategy.
+    memory: RefCell<HashMap<String, String>>,
+}
+
+impl Default for TokenStore {
+    fn default() -> Self {
+        Self::new(TokenStorage::default())
+    }
+}
+
+impl TokenStore {
+    /// Creates a store using the given strategy.
+    pub(crate) fn new(kind: TokenStorage) -> Self {
+        Self {
+            kind,
+            memory: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Returns the strategy in use.
+    pub(crate) fn kind(&self) -> TokenStorage {
+        self.kind
+    }
+
+    /// Saves `token` under `key`.
+    pub(crate) fn save(&self, key: &str, token: &str) -> Result<(), JsValue> {
+        match self.kind {
+            TokenStorage::Local | TokenStorage::Session => web_storage(self.kind)?
+                .set_item(key, token)
+                .map_err(|e| BlogError::new(format!("Failed to save token: {:?}", e)).into()),
+            TokenStorage::Memory => {
+                self.memory.borrow_mut().insert(key.into(), token.into());
+                Ok(())
+            }
+            TokenStorage::Cookie => {
+                let value = String::from(js_sys::encode_uri_component(token));
+                set_cookie(key, &value, COOKIE_MAX_AGE_SECS)
+            }
+        }
+    }
+
+    /// Returns the token stored under `key`, if any.
+    pub(crate) fn get(&self, key: &str) -> Result<Option<String>, JsValue> {
+        match self.kind {
+            TokenStorage::Local | TokenStorage::Session => Ok(web_storage(self.kind)?
+                .get_item(key)
+                .map_err(|e| BlogError::new(format!("Failed to read token: {:?}", e)))?),
+            TokenStorage::Memory => Ok(self.memory.borrow().get(key).cloned()),
+            TokenStorage::Cookie => {
+                let cookies = html_document()?
+                    .cookie()
+                    .map_err(|e| BlogError::new(format!("Failed to read cookies: {:?}", e)))?;
+                let value = cookies
+                    .split(';')
+                    .filter_map(|pair| pair.trim().split_once('='))
+                    .find(|(name, _)| *name == key)
+                    .and_then(|(_, value)| js_sys::decode_uri_component(value).ok())
+                    .map(String::from);
+                Ok(value)
+            }
+        }
+    }
+
+    /// Removes the token stored under `key`.
+    pub(crate) fn remove(&self, key: &str) -> Result<(), JsValue> {
+        match self.kind {
+            TokenStorage::Local | TokenStorage::Session => {
+                let _ = web_storage(self.kind)?.remove_item(key);
+                Ok(())
+            }
+            TokenStorage::Memory => {
+                self.memory.borrow_mut().remove(key);
+                Ok(())
+            }
+            TokenStorage::Cookie => set_cookie(key, "", 0),
+        }
+    }
+}
+
+/// Returns `localStorage` or `sessionStorage`.
+fn web_storage(kind: TokenStorage) -> Result<Storage, JsValue> {
+    let win = window().ok_or_else(|| BlogError::new("no window"))?;
+    let storage = match kind {
+        TokenStorage::Session => win.session_storage(),
+        _ => win.local_storage(),
+    };
+    let storage = storage
+        .map_err(|e| BlogError::new(format!("web storage error: {:?}", e)))?
+        .ok_or_else(|| BlogError::new("web storage not available"))?;
+    Ok(storage)
+}
+
+/// Returns the current document with its cookie API.
+fn html_document() -> Result<HtmlDocument, JsValue> {
+    window()
+        .and_then(|win| win.document())
+        .and_then(|doc| doc.dyn_into::<HtmlDocument>().ok())
+        .ok_or_else(|| BlogError::new("no document").into())
+}
+
+/// Writes a cookie readable only by this site; a `max_age` of 0 deletes it.
+fn set_cookie(name: &str, value: &str, max_age: u32) -> Result<(), JsValue> {
+    let secure = window()
+        .and_then(|win| win.location().protocol().ok())
+        .is_some_and(|protocol| protocol == "https:");
+
+    let mut cookie = format!("{name}={value}; Path=/; Max-Age={max_age}; SameSite=Strict");
+    if secure {
+        cookie.push_str("; Secure");
+    }
+
+    html_document()?
+        .set_cookie(&cookie)
+        .map_err(|e| BlogError::new(format!("Failed to save cookie: {:?}", e)).into())
+}
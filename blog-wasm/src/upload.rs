@@ -0,0 +1,87 @@
+use js_sys::{Function, Promise};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{AbortSignal, FormData, ProgressEvent, XmlHttpRequest};
+
+use crate::auth::Credentials;
+use crate::error::{BlogError, ErrorCode};
+
+/// Status code, body and request ID of a finished upload request.
+pub(crate) struct UploadResponse {
+    pub status: u16,
+    pub body: String,
+    pub request_id: Option<String>,
+}
+
+/// Posts `form` to `url`, reporting upload progress as `(loaded, total)`.
+///
+/// `fetch` exposes no upload progress, so this goes through
+/// `XMLHttpRequest`, whose `upload.onprogress` does. Aborting `signal`
+/// aborts the request.
+pub(crate) async fn post_form(
+    url: &str,
+    token: &Credentials,
+    form: &FormData,
+    on_progress: Option<Function>,
+    signal: Option<&AbortSignal>,
+) -> Result<UploadResponse, JsValue> {
+    if signal.is_some_and(AbortSignal::aborted) {
+        return Err(BlogError::new("Upload aborted").with_code(ErrorCode::Aborted).into());
+    }
+
+    let xhr = XmlHttpRequest::new()?;
+    xhr.open_with_async("POST", url, true)?;
+    token.apply_xhr(&xhr)?;
+
+    let progress = on_progress.map(|callback| {
+        Closure::<dyn FnMut(ProgressEvent)>::new(move |event: ProgressEvent| {
+            if event.length_computable() {
+                let _ = callback.call2(
+                    &JsValue::NULL,
+                    &JsValue::from_f64(event.loaded()),
+                    &JsValue::from_f64(event.total()),
+                );
+            }
+        })
+    });
+    if let Some(progress) = &progress {
+        xhr.upload()?
+            .set_onprogress(Some(progress.as_ref().unchecked_ref()));
+    }
+
+    let done = Promise::new(&mut |resolve, reject| {
+        xhr.set_onload(Some(&resolve));
+        xhr.set_onerror(Some(&reject));
+        xhr.set_onabort(Some(&reject));
+    });
+
+    let abort = signal.map(|signal| {
+        let xhr = xhr.clone();
+        let listener = Closure::<dyn FnMut()>::new(move || {
+            let _ = xhr.abort();
+        });
+        let _ = signal.add_event_listener_with_callback("abort", listener.as_ref().unchecked_ref());
+        (signal, listener)
+    });
+
+    xhr.send_with_opt_form_data(Some(form))?;
+    let finished = JsFuture::from(done).await;
+
+    // The closures must outlive the request; they are dropped only now.
+    drop(progress);
+    if let Some((signal, listener)) = abort {
+        let _ = signal.remove_event_listener_with_callback("abort", listener.as_ref().unchecked_ref());
+    }
+    if finished.is_err() && signal.is_some_and(AbortSignal::aborted) {
+        return Err(BlogError::new("Upload aborted").with_code(ErrorCode::Aborted).into());
+    }
+    finished.map_err(|_| {
+        BlogError::new("Upload failed: network error").with_code(ErrorCode::NetworkError)
+    })?;
+
+    Ok(UploadResponse {
+        status: xhr.status()?,
+        body: xhr.response_text()?.unwrap_or_default(),
+        request_id: xhr.get_response_header("x-request-id")?,
+    })
+}